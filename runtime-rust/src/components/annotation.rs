@@ -0,0 +1,15 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::ReleaseNode;
+use whitenoise_validator::utilities::get_argument;
+use crate::components::Evaluable;
+use whitenoise_validator::proto;
+
+
+impl Evaluable for proto::Annotation {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        // annotations carry no computation; the annotated data passes through unchanged
+        Ok(ReleaseNode::new(get_argument(&arguments, "data")?.clone()))
+    }
+}
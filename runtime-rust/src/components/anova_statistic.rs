@@ -0,0 +1,32 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Array, ReleaseNode};
+use whitenoise_validator::utilities::get_argument;
+use whitenoise_validator::components::anova_statistic::anova_f_test;
+use whitenoise_validator::components::dp_welch_t_test::GroupStatistics;
+use crate::components::Evaluable;
+use whitenoise_validator::proto;
+use ndarray::{Axis, Ix2, arr1};
+
+
+impl Evaluable for proto::AnovaStatistic {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let moments = match get_argument(&arguments, "data")?.array()? {
+            Array::F64(data) => data.clone(),
+            Array::I64(data) => data.mapv(|v| v as f64),
+            _ => return Err("data must be numeric".into())
+        };
+        let moments = moments.into_dimensionality::<Ix2>()?;
+        if moments.len_of(Axis(1)) != 3 {
+            return Err("data must contain the count, sum and sum of squares of each group".into())
+        }
+
+        let groups = moments.outer_iter()
+            .map(|group| GroupStatistics { count: group[0], sum: group[1], sum_squares: group[2] })
+            .collect::<Vec<GroupStatistics>>();
+        let (statistic, degrees_of_freedom_between, degrees_of_freedom_within) = anova_f_test(&groups)?;
+
+        Ok(ReleaseNode::new(arr1(&[statistic, degrees_of_freedom_between, degrees_of_freedom_within]).into_dyn().into()))
+    }
+}
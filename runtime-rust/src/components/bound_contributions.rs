@@ -0,0 +1,63 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Array, ReleaseNode};
+use whitenoise_validator::utilities::get_argument;
+use crate::components::Evaluable;
+use ndarray::{ArrayD, Axis};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use whitenoise_validator::proto;
+
+use whitenoise_validator::utilities::array::slow_select;
+
+
+impl Evaluable for proto::BoundContributions {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let max_contributions = self.max_contributions as usize;
+
+        let indices = match get_argument(&arguments, "identifier")?.array()? {
+            Array::Str(identifier) => bound_contributions(identifier, max_contributions),
+            Array::I64(identifier) => bound_contributions(identifier, max_contributions),
+            Array::Bool(identifier) => bound_contributions(identifier, max_contributions),
+            Array::F64(_) => return Err("BoundContributions: identifier may not be floats".into())
+        };
+
+        Ok(ReleaseNode::new(match get_argument(&arguments, "data")?.array()? {
+            Array::Str(data) => slow_select(data, Axis(0), &indices).into(),
+            Array::F64(data) => slow_select(data, Axis(0), &indices).into(),
+            Array::I64(data) => slow_select(data, Axis(0), &indices).into(),
+            Array::Bool(data) => slow_select(data, Axis(0), &indices).into(),
+        }))
+    }
+}
+
+/// Indices of the rows to retain, such that no identifier appears more than `max_contributions` times.
+///
+/// # Arguments
+/// * `identifier` - Column identifying the individual each row belongs to.
+/// * `max_contributions` - Maximum number of rows to retain for each identifier.
+///
+/// # Return
+/// Indices of the first `max_contributions` rows of each identifier.
+///
+/// # Example
+/// ```
+/// use ndarray::arr1;
+/// use whitenoise_runtime::components::bound_contributions::bound_contributions;
+///
+/// let identifier = arr1(&[1, 2, 1, 1, 2]).into_dyn();
+/// assert_eq!(bound_contributions(&identifier, 2), vec![0, 1, 2, 4]);
+/// ```
+pub fn bound_contributions<T: Eq + Hash>(identifier: &ArrayD<T>, max_contributions: usize) -> Vec<usize> {
+    let mut counts = HashMap::<&T, usize>::new();
+    identifier.iter().enumerate()
+        .filter(|(_, id)| {
+            let count = counts.entry(*id).or_insert(0);
+            *count += 1;
+            *count <= max_contributions
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
@@ -0,0 +1,25 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Array, ReleaseNode};
+use whitenoise_validator::utilities::get_argument;
+use whitenoise_validator::components::chi_square_statistic::{chi_square_test, squared_noise_ratio};
+use crate::components::Evaluable;
+use whitenoise_validator::proto;
+use ndarray::arr1;
+
+
+impl Evaluable for proto::ChiSquareStatistic {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let table = match get_argument(&arguments, "data")?.array()? {
+            Array::F64(data) => data.iter().cloned().collect::<Vec<f64>>(),
+            Array::I64(data) => data.iter().map(|v| *v as f64).collect::<Vec<f64>>(),
+            _ => return Err("data must be numeric".into())
+        };
+
+        let (statistic, scale, degrees_of_freedom) = chi_square_test(
+            &table, self.num_rows as usize, self.noise_variance, squared_noise_ratio(&self.mechanism)?)?;
+
+        Ok(ReleaseNode::new(arr1(&[statistic, scale, degrees_of_freedom]).into_dyn().into()))
+    }
+}
@@ -0,0 +1,61 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Array, Jagged, ReleaseNode};
+use crate::components::Evaluable;
+use crate::components::contingency_table::{contingency_table, unwrap_categories};
+use ndarray::{ArrayD, Array2};
+use whitenoise_validator::proto;
+use whitenoise_validator::utilities::get_argument;
+use std::hash::Hash;
+
+
+impl Evaluable for proto::ConfusionMatrix {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let actual = get_argument(arguments, "actual")?.array()?;
+        let predicted = get_argument(arguments, "predicted")?.array()?;
+
+        Ok(ReleaseNode::new(match (actual, predicted, get_argument(arguments, "categories")?.jagged()?) {
+            (Array::Bool(actual), Array::Bool(predicted), Jagged::Bool(categories)) =>
+                confusion_matrix(actual, predicted, &unwrap_categories(categories)?)?.into(),
+            (Array::I64(actual), Array::I64(predicted), Jagged::I64(categories)) =>
+                confusion_matrix(actual, predicted, &unwrap_categories(categories)?)?.into(),
+            (Array::Str(actual), Array::Str(predicted), Jagged::Str(categories)) =>
+                confusion_matrix(actual, predicted, &unwrap_categories(categories)?)?.into(),
+            (Array::F64(_), _, _) | (_, Array::F64(_), _) => return Err("float data may not be categorical".into()),
+            _ => return Err("actual, predicted and categories must be homogeneously typed".into())
+        }))
+    }
+}
+
+/// Count the records of every combination of an actual and a predicted label.
+///
+/// Cells are ordered as in a row-major array, where rows are the actual labels and the predicted labels vary fastest.
+/// Records with a label outside of the categories are not counted.
+///
+/// # Example
+/// ```
+/// use ndarray::arr1;
+/// use whitenoise_runtime::components::confusion_matrix::confusion_matrix;
+///
+/// let actual = arr1(&[true, true, false, false]).into_dyn();
+/// let predicted = arr1(&[true, false, false, false]).into_dyn();
+/// let matrix = confusion_matrix(&actual, &predicted, &vec![vec![false, true], vec![false, true]]).unwrap();
+/// assert_eq!(matrix.into_dimensionality::<ndarray::Ix1>().unwrap().to_vec(), vec![2, 0, 1, 1]);
+/// ```
+pub fn confusion_matrix<T: Clone + Eq + Hash>(
+    actual: &ArrayD<T>, predicted: &ArrayD<T>, categories: &[Vec<T>],
+) -> Result<ArrayD<i64>> {
+    if actual.len() != predicted.len() {
+        return Err("actual and predicted must have the same number of records".into())
+    }
+    if categories.len() != 2 {
+        return Err("categories must be defined for the actual and predicted labels".into())
+    }
+
+    let pairs = actual.iter().zip(predicted.iter())
+        .flat_map(|(actual, predicted)| vec![actual.clone(), predicted.clone()])
+        .collect::<Vec<T>>();
+
+    contingency_table(&Array2::from_shape_vec((actual.len(), 2), pairs)?.into_dyn(), categories)
+}
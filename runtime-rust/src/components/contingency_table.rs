@@ -0,0 +1,74 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Array, Jagged, ReleaseNode};
+use crate::components::Evaluable;
+use ndarray::{ArrayD, arr1};
+use whitenoise_validator::proto;
+use whitenoise_validator::utilities::get_argument;
+use std::collections::HashMap;
+use std::hash::Hash;
+use crate::utilities::to_nd;
+
+
+impl Evaluable for proto::ContingencyTable {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        Ok(ReleaseNode::new(match (get_argument(arguments, "data")?.array()?, get_argument(arguments, "categories")?.jagged()?) {
+            (Array::Bool(data), Jagged::Bool(categories)) =>
+                contingency_table(data, &unwrap_categories(categories)?)?.into(),
+            (Array::I64(data), Jagged::I64(categories)) =>
+                contingency_table(data, &unwrap_categories(categories)?)?.into(),
+            (Array::Str(data), Jagged::Str(categories)) =>
+                contingency_table(data, &unwrap_categories(categories)?)?.into(),
+            (Array::F64(_), _) => return Err("data: float data may not be categorical".into()),
+            _ => return Err("data and categories must be homogeneously typed".into())
+        }))
+    }
+}
+
+/// Categories of every column, which must all be defined.
+pub fn unwrap_categories<T: Clone>(categories: &[Option<Vec<T>>]) -> Result<Vec<Vec<T>>> {
+    categories.iter()
+        .map(|column| column.clone().ok_or_else(|| Error::from("categories must be defined for every column")))
+        .collect()
+}
+
+/// Count the records in each cell of the cross-tabulation of the columns.
+///
+/// Cells are ordered as in a row-major array over the columns, so the categories of the last column vary fastest.
+/// Records with a value outside of the categories of its column are not counted.
+///
+/// # Example
+/// ```
+/// use ndarray::arr2;
+/// use whitenoise_runtime::components::contingency_table::contingency_table;
+///
+/// let data = arr2(&[[0, 1], [1, 2], [0, 2], [1, 2]]).into_dyn();
+/// let table = contingency_table(&data, &vec![vec![0, 1], vec![1, 2]]).unwrap();
+/// assert_eq!(table.into_dimensionality::<ndarray::Ix1>().unwrap().to_vec(), vec![1, 1, 0, 2]);
+/// ```
+pub fn contingency_table<T: Clone + Eq + Hash>(data: &ArrayD<T>, categories: &[Vec<T>]) -> Result<ArrayD<i64>> {
+    let data = to_nd(data.clone(), &2)?;
+    if data.shape()[1] != categories.len() {
+        return Err("categories must be defined for every column".into())
+    }
+
+    let indices = categories.iter()
+        .map(|column| column.iter().enumerate()
+            .map(|(index, category)| (category, index))
+            .collect::<HashMap<&T, usize>>())
+        .collect::<Vec<HashMap<&T, usize>>>();
+    let num_cells = categories.iter().map(Vec::len).product::<usize>();
+
+    let mut counts = vec![0i64; num_cells];
+    data.genrows().into_iter().for_each(|row| {
+        let cell = row.iter().zip(categories.iter().zip(indices.iter()))
+            .try_fold(0, |cell, (value, (column, indices))| indices.get(value)
+                .map(|index| cell * column.len() + index));
+        if let Some(cell) = cell {
+            counts[cell] += 1;
+        }
+    });
+
+    Ok(arr1(&counts).into_dyn())
+}
@@ -0,0 +1,45 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Array, ReleaseNode};
+use crate::components::Evaluable;
+use ndarray::ArrayD;
+use ndarray;
+use whitenoise_validator::proto;
+use whitenoise_validator::utilities::get_argument;
+use noisy_float::types::n64;
+use std::collections::HashSet;
+use std::hash::Hash;
+
+
+impl Evaluable for proto::CountDistinct {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        Ok(ReleaseNode::new(match get_argument(arguments, "data")?.array()? {
+            Array::Bool(data) => count_distinct(data)?.into(),
+            Array::F64(data) => count_distinct(&data.mapv(n64))?.into(),
+            Array::I64(data) => count_distinct(data)?.into(),
+            Array::Str(data) => count_distinct(data)?.into()
+        }))
+    }
+}
+
+/// Gets the number of distinct values in a single column of data.
+///
+/// # Arguments
+/// * `data` - Single column of data.
+///
+/// # Return
+/// Number of distinct values in data.
+///
+/// # Example
+/// ```
+/// use ndarray::arr1;
+/// use whitenoise_runtime::components::count_distinct::count_distinct;
+/// let data = arr1(&["a", "b", "a", "c"]).into_dyn();
+/// let n = count_distinct(&data).unwrap();
+/// assert!(n.first().unwrap() == &3);
+/// ```
+pub fn count_distinct<T: Eq + Hash>(data: &ArrayD<T>) -> Result<ArrayD<i64>> {
+    let distinct = data.iter().collect::<HashSet<&T>>();
+    Ok(ndarray::Array::from_shape_vec(vec![], vec![distinct.len() as i64])?)
+}
@@ -0,0 +1,37 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::ReleaseNode;
+use whitenoise_validator::utilities::get_argument;
+use crate::components::Evaluable;
+use crate::components::sum::sum;
+use whitenoise_validator::proto;
+use ndarray::ArrayD;
+
+
+impl Evaluable for proto::CountTrue {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let data = get_argument(arguments, "data")?.array()?.bool()?;
+        Ok(ReleaseNode::new(count_true(data)?.into()))
+    }
+}
+
+/// Counts the true values in each column of the data.
+///
+/// # Arguments
+/// * `data` - Boolean data for which you would like the number of true values in each column.
+///
+/// # Return
+/// Number of true values in each column of the data.
+///
+/// # Example
+/// ```
+/// use ndarray::prelude::*;
+/// use whitenoise_runtime::components::count_true::count_true;
+/// let data = arr2(&[ [true, false], [true, true], [false, false] ]).into_dyn();
+/// let counts = count_true(&data).unwrap();
+/// assert!(counts == arr2(&[[2, 1]]).into_dyn());
+/// ```
+pub fn count_true(data: &ArrayD<bool>) -> Result<ArrayD<i64>> {
+    sum(&data.mapv(|v| v as i64))
+}
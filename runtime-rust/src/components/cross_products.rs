@@ -0,0 +1,67 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::ReleaseNode;
+use whitenoise_validator::utilities::get_argument;
+use crate::components::Evaluable;
+use whitenoise_validator::proto;
+use ndarray::{ArrayD, Axis, arr1};
+
+
+impl Evaluable for proto::CrossProducts {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let data = get_argument(&arguments, "data")?.array()?.f64()?;
+        let target = get_argument(&arguments, "target")?.array()?.f64()?;
+
+        let statistics = cross_products(data, target, self.intercept)?;
+
+        // flatten into a row vector, every column is a release
+        Ok(ReleaseNode::new(arr1(&statistics).insert_axis(Axis(0)).into_dyn().into()))
+    }
+}
+
+/// Sufficient statistics of a least-squares regression of the target on the features.
+///
+/// # Arguments
+/// * `data` - Features, one row per record.
+/// * `target` - Responses, one per record.
+/// * `intercept` - Whether to prepend a column of ones to the features.
+///
+/// # Return
+/// The flattened upper triangle of `Z^T Z`, followed by `Z^T y`.
+///
+/// # Example
+/// ```
+/// use ndarray::{arr1, arr2};
+/// use whitenoise_runtime::components::cross_products::cross_products;
+///
+/// let data = arr2(&[ [0.], [1.], [2.] ]).into_dyn();
+/// let target = arr1(&[1., 3., 5.]).into_dyn();
+/// let statistics = cross_products(&data, &target, true).unwrap();
+/// assert_eq!(statistics, vec![3., 3., 5., 9., 13.]);
+/// ```
+pub fn cross_products(data: &ArrayD<f64>, target: &ArrayD<f64>, intercept: bool) -> Result<Vec<f64>> {
+    let num_records = data.len_of(Axis(0));
+    let target = target.iter().cloned().collect::<Vec<f64>>();
+    if target.len() != num_records {
+        return Err("data and target must have the same number of records".into())
+    }
+
+    // columns of Z
+    let mut columns = if intercept { vec![vec![1.; num_records]] } else { Vec::new() };
+    if data.ndim() == 1 {
+        columns.push(data.iter().cloned().collect());
+    } else {
+        columns.extend(data.gencolumns().into_iter()
+            .map(|column| column.iter().cloned().collect::<Vec<f64>>()));
+    }
+
+    let dot = |left: &[f64], right: &[f64]| left.iter().zip(right.iter())
+        .map(|(l, r)| l * r).sum::<f64>();
+
+    Ok((0..columns.len())
+        .flat_map(|i| (i..columns.len()).map(move |j| (i, j)))
+        .map(|(i, j)| dot(&columns[i], &columns[j]))
+        .chain(columns.iter().map(|column| dot(column, &target)))
+        .collect())
+}
@@ -0,0 +1,31 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Value, Array, ReleaseNode};
+use whitenoise_validator::utilities::get_argument;
+use whitenoise_validator::components::derived_metric::Operation;
+use crate::components::Evaluable;
+use crate::utilities::broadcast_map;
+use whitenoise_validator::proto;
+use ndarray::ArrayD;
+
+
+impl Evaluable for proto::DerivedMetric {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let operation = Operation::parse(&self.operation)?;
+
+        let left = to_f64(get_argument(&arguments, "left")?)?;
+        let right = to_f64(get_argument(&arguments, "right")?)?;
+
+        Ok(ReleaseNode::new(broadcast_map(
+            &left, &right, &|l: &f64, r: &f64| operation.evaluate(*l, *r))?.into()))
+    }
+}
+
+fn to_f64(value: &Value) -> Result<ArrayD<f64>> {
+    match value.array()? {
+        Array::F64(array) => Ok(array.clone()),
+        Array::I64(array) => Ok(array.mapv(|v| v as f64)),
+        _ => Err("DerivedMetric: The atomic type must be numeric".into())
+    }
+}
@@ -0,0 +1,56 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Array, ReleaseNode};
+use whitenoise_validator::utilities::get_argument;
+use whitenoise_validator::components::dp_auc::roc_curve;
+use whitenoise_validator::components::dp_welch_t_test::get_test_epsilon;
+use crate::components::Evaluable;
+use crate::utilities::mechanisms::laplace_mechanism;
+use whitenoise_validator::proto;
+use ndarray::Array2;
+
+
+impl Evaluable for proto::DpAuc {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let lower = get_argument(&arguments, "lower")?.first_f64()?;
+        let upper = get_argument(&arguments, "upper")?.first_f64()?;
+        let sensitivity = get_argument(&arguments, "sensitivity")?.first_f64()?;
+        let epsilon = get_test_epsilon(&self.privacy_usage)?;
+        let num_bins = self.num_bins as usize;
+        if num_bins == 0 {
+            return Err("num_bins must be greater than zero".into())
+        }
+
+        let scores = match get_argument(&arguments, "data")?.array()? {
+            Array::F64(data) => data.iter().cloned().collect::<Vec<f64>>(),
+            Array::I64(data) => data.iter().map(|v| *v as f64).collect::<Vec<f64>>(),
+            _ => return Err("data must be numeric".into())
+        };
+        let labels = get_argument(&arguments, "labels")?.array()?.bool()?;
+        if labels.len() != scores.len() {
+            return Err("data and labels must have the same number of records".into())
+        }
+
+        // each record is counted in one bin of one class, so both histograms spend the whole usage
+        let (mut positives, mut negatives) = (vec![0.; num_bins], vec![0.; num_bins]);
+        scores.iter().zip(labels.iter()).for_each(|(score, label)| {
+            let bin = (((score.max(lower).min(upper) - lower) / (upper - lower) * num_bins as f64) as usize).min(num_bins - 1);
+            if *label { positives[bin] += 1. } else { negatives[bin] += 1. }
+        });
+        for count in positives.iter_mut().chain(negatives.iter_mut()) {
+            *count += laplace_mechanism(&epsilon, &sensitivity)?;
+        }
+
+        let curve = roc_curve(&positives, &negatives, lower, upper)?;
+
+        Ok(ReleaseNode {
+            value: Array2::from_shape_vec((curve.len(), 3), curve.into_iter().flat_map(|row| row.to_vec()).collect())?
+                .into_dyn().into(),
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+            public_shape: true,
+            public_metadata: true
+        })
+    }
+}
@@ -0,0 +1,164 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Array, Jagged, ReleaseNode, Value, Hashmap};
+use whitenoise_validator::utilities::get_argument;
+use whitenoise_validator::components::dp_decision_tree::get_level_epsilons;
+use crate::components::Evaluable;
+use crate::utilities::to_nd;
+use crate::utilities::mechanisms::{exponential_mechanism, laplace_mechanism};
+use whitenoise_validator::proto;
+use ndarray::{ArrayD, Array2, arr1};
+
+
+impl Evaluable for proto::DpDecisionTree {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let sensitivity = get_argument(&arguments, "sensitivity")?.first_f64()?;
+        let epsilons = get_level_epsilons(&self.privacy_usage, self.depth)?;
+
+        let (features, num_categories) = match (get_argument(&arguments, "data")?.array()?, get_argument(&arguments, "categories")?.jagged()?) {
+            (Array::Bool(data), Jagged::Bool(categories)) => get_feature_indices(data, categories)?,
+            (Array::I64(data), Jagged::I64(categories)) => get_feature_indices(data, categories)?,
+            (Array::Str(data), Jagged::Str(categories)) => get_feature_indices(data, categories)?,
+            (Array::F64(_), _) => return Err("data: float data may not be categorical".into()),
+            _ => return Err("data and categories must be homogeneously typed".into())
+        };
+
+        let (classes, num_classes) = match (get_argument(&arguments, "target")?.array()?, get_argument(&arguments, "target_categories")?.array()?) {
+            (Array::Bool(target), Array::Bool(categories)) => (get_indices(target, categories), categories.len()),
+            (Array::I64(target), Array::I64(categories)) => (get_indices(target, categories), categories.len()),
+            (Array::Str(target), Array::Str(categories)) => (get_indices(target, categories), categories.len()),
+            (Array::F64(_), _) => return Err("target: float data may not be categorical".into()),
+            _ => return Err("target and target_categories must be homogeneously typed".into())
+        };
+
+        let (splits, counts) = decision_tree(
+            &features, &classes, &num_categories, num_classes,
+            &epsilons, sensitivity)?;
+
+        Ok(ReleaseNode {
+            value: Value::Hashmap(Hashmap::Str(vec![
+                ("splits".to_string(), splits.into()),
+                ("counts".to_string(), counts.into())
+            ].into_iter().collect())),
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+            public_shape: true,
+            public_metadata: true
+        })
+    }
+}
+
+/// Index of each value within the categories, or None if the value does not match any category.
+fn get_indices<T: PartialEq>(data: &ArrayD<T>, categories: &ArrayD<T>) -> Vec<Option<usize>> {
+    data.iter()
+        .map(|value| categories.iter().position(|category| category == value))
+        .collect()
+}
+
+/// Category index of each feature of each record, and the number of categories of each feature.
+pub fn get_feature_indices<T: PartialEq + Clone>(
+    data: &ArrayD<T>, categories: &[Option<Vec<T>>],
+) -> Result<(Vec<Vec<Option<usize>>>, Vec<usize>)> {
+    let data = to_nd(data.clone(), &2)?;
+    if data.shape()[1] != categories.len() {
+        return Err("categories must be defined for every column".into())
+    }
+    let categories = categories.iter()
+        .map(|column| column.as_ref().ok_or_else(|| Error::from("categories must be defined for every column")))
+        .collect::<Result<Vec<&Vec<T>>>>()?;
+
+    let features = data.genrows().into_iter()
+        .map(|record| record.iter().zip(categories.iter())
+            .map(|(value, column)| column.iter().position(|category| category == value))
+            .collect())
+        .collect();
+
+    Ok((features, categories.iter().map(|column| column.len()).collect()))
+}
+
+/// Fit a complete binary decision tree over categorical features.
+///
+/// The first `epsilons.len() - 1` epsilons are spent on selecting the splits of each level,
+/// and the last epsilon is spent on the leaf counts.
+/// Records with a feature outside of its categories fall to the right child, where the split does not hold,
+/// and records with an unknown class are not counted.
+///
+/// # Returns
+/// The feature and category index of the split of each internal node in breadth-first order,
+/// and the noisy count of each class at each leaf.
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::components::dp_decision_tree::decision_tree;
+///
+/// let features = (0..100).map(|i| vec![Some(i % 2), Some(i % 3)]).collect::<Vec<_>>();
+/// let classes = (0..100).map(|i| Some(i % 2)).collect::<Vec<_>>();
+/// let (splits, counts) = decision_tree(&features, &classes, &[2, 3], 2, &[1., 1., 1.], 1.).unwrap();
+/// assert_eq!(splits.shape(), &[3, 2]);
+/// assert_eq!(counts.shape(), &[4, 2]);
+/// ```
+pub fn decision_tree(
+    features: &[Vec<Option<usize>>], classes: &[Option<usize>],
+    num_categories: &[usize], num_classes: usize,
+    epsilons: &[f64], sensitivity: f64,
+) -> Result<(ArrayD<i64>, ArrayD<f64>)> {
+    if features.len() != classes.len() {
+        return Err("data and target must have the same number of records".into())
+    }
+    if epsilons.len() < 2 {
+        return Err("epsilons must be defined for at least one level of splits and the leaf counts".into())
+    }
+    let candidates = num_categories.iter().enumerate()
+        .flat_map(|(feature, num_categories)| (0..*num_categories).map(move |category| (feature, category)))
+        .collect::<Vec<(usize, usize)>>();
+    if candidates.is_empty() {
+        return Err("data: at least one category must be known".into())
+    }
+
+    let class_counts = |records: &[usize]| -> Vec<f64> {
+        let mut counts = vec![0.; num_classes];
+        records.iter()
+            .filter_map(|record| classes[*record])
+            .for_each(|class| counts[class] += 1.);
+        counts
+    };
+    let majority = |records: &[usize]| class_counts(records).into_iter().fold(0., f64::max);
+
+    // the nodes on each level partition the records
+    let mut level: Vec<Vec<usize>> = vec![(0..features.len()).collect()];
+    let mut splits = Vec::new();
+
+    for epsilon in &epsilons[..epsilons.len() - 1] {
+        let mut next_level = Vec::with_capacity(level.len() * 2);
+        for records in &level {
+            let partition = |(feature, category): &(usize, usize)| -> (Vec<usize>, Vec<usize>) {
+                records.iter().copied().partition(|record| features[*record][*feature] == Some(*category))
+            };
+            let utility = |candidate: &(usize, usize)| {
+                let (left, right) = partition(candidate);
+                majority(&left) + majority(&right)
+            };
+            let (feature, category) = exponential_mechanism(
+                epsilon, &sensitivity, arr1(&candidates).into_dyn(), &utility)?;
+
+            let (left, right) = partition(&(feature, category));
+            next_level.push(left);
+            next_level.push(right);
+            splits.push(feature as i64);
+            splits.push(category as i64);
+        }
+        level = next_level;
+    }
+
+    let leaf_epsilon = epsilons[epsilons.len() - 1];
+    let counts = level.iter()
+        .flat_map(|records| class_counts(records))
+        .map(|count| Ok(count + laplace_mechanism(&leaf_epsilon, &sensitivity)?))
+        .collect::<Result<Vec<f64>>>()?;
+
+    Ok((
+        Array2::from_shape_vec((splits.len() / 2, 2), splits)?.into_dyn(),
+        Array2::from_shape_vec((level.len(), num_classes), counts)?.into_dyn()
+    ))
+}
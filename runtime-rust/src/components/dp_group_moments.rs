@@ -0,0 +1,75 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Array, Jagged, ReleaseNode};
+use whitenoise_validator::utilities::get_argument;
+use whitenoise_validator::components::dp_welch_t_test::get_test_epsilon;
+use crate::components::Evaluable;
+use crate::utilities::mechanisms::laplace_mechanism;
+use whitenoise_validator::proto;
+use ndarray::{ArrayD, Array2};
+
+
+impl Evaluable for proto::DpGroupMoments {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let lower = get_argument(&arguments, "lower")?.first_f64()?;
+        let upper = get_argument(&arguments, "upper")?.first_f64()?;
+        let sensitivity = get_argument(&arguments, "sensitivity")?.first_f64()?;
+        let epsilon = get_test_epsilon(&self.privacy_usage)? / 3.;
+
+        let data = match get_argument(&arguments, "data")?.array()? {
+            Array::F64(data) => data.iter().cloned().collect::<Vec<f64>>(),
+            Array::I64(data) => data.iter().map(|v| *v as f64).collect::<Vec<f64>>(),
+            _ => return Err("data must be numeric".into())
+        };
+
+        let (groups, num_groups) = match (get_argument(&arguments, "by")?.array()?, get_argument(&arguments, "categories")?.jagged()?) {
+            (Array::Bool(by), Jagged::Bool(categories)) => get_groups(by, categories)?,
+            (Array::I64(by), Jagged::I64(categories)) => get_groups(by, categories)?,
+            (Array::Str(by), Jagged::Str(categories)) => get_groups(by, categories)?,
+            (Array::F64(_), _) => return Err("by: float data may not be categorical".into()),
+            _ => return Err("by and categories must be homogeneously typed".into())
+        };
+        if groups.len() != data.len() {
+            return Err("data and by must have the same number of records".into())
+        }
+
+        // one row of [count, sum, sum of squares] for each group, of records centered at the midpoint of the bounds
+        let (midpoint, radius) = ((lower + upper) / 2., (upper - lower) / 2.);
+        let mut moments = Array2::<f64>::zeros((num_groups, 3));
+        data.iter().zip(groups.iter())
+            .filter_map(|(value, group)| Some((value, (*group)?)))
+            .for_each(|(value, group)| {
+                let centered = value.max(lower).min(upper) - midpoint;
+                moments[[group, 0]] += 1.;
+                moments[[group, 1]] += centered;
+                moments[[group, 2]] += centered.powi(2);
+            });
+
+        // each statistic of each group is released with a third of the usage
+        for mut group in moments.outer_iter_mut() {
+            group[0] += laplace_mechanism(&epsilon, &sensitivity)?;
+            group[1] += laplace_mechanism(&epsilon, &(sensitivity * radius))?;
+            group[2] += laplace_mechanism(&epsilon, &(sensitivity * radius.powi(2)))?;
+        }
+
+        Ok(ReleaseNode {
+            value: moments.into_dyn().into(),
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+            public_shape: true,
+            public_metadata: true
+        })
+    }
+}
+
+/// Index of the group of each record, or None if the record falls into no group, and the number of groups.
+fn get_groups<T: PartialEq>(by: &ArrayD<T>, categories: &[Option<Vec<T>>]) -> Result<(Vec<Option<usize>>, usize)> {
+    let categories = match categories {
+        [Some(categories)] if categories.len() >= 2 => categories,
+        _ => return Err("categories must contain at least two groups".into())
+    };
+    Ok((by.iter()
+        .map(|value| categories.iter().position(|category| category == value))
+        .collect(), categories.len()))
+}
@@ -0,0 +1,168 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Array, ReleaseNode};
+use whitenoise_validator::utilities::get_argument;
+use whitenoise_validator::components::dp_quantiles::{get_quantiles_epsilon, num_levels};
+use crate::components::Evaluable;
+use crate::utilities::{to_nd, get_num_columns, noise};
+use whitenoise_validator::proto;
+use ndarray::{ArrayD, Array2};
+
+
+impl Evaluable for proto::DpQuantiles {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let lower = get_argument(&arguments, "lower")?.array()?.f64()?.iter().cloned().collect::<Vec<f64>>();
+        let upper = get_argument(&arguments, "upper")?.array()?.f64()?.iter().cloned().collect::<Vec<f64>>();
+        let sensitivity = get_argument(&arguments, "sensitivity")?.first_f64()?;
+
+        // every record informs exactly one estimate on each level of recursion
+        let epsilon = get_quantiles_epsilon(&self.privacy_usage)? / num_levels(self.alphas.len()) as f64;
+
+        let data = match get_argument(&arguments, "data")?.array()? {
+            Array::F64(data) => data.clone(),
+            Array::I64(data) => data.mapv(|v| v as f64),
+            _ => return Err("data: atomic type must be numeric".into())
+        };
+        let ndim = data.ndim();
+        let data = to_nd(data, &2)?;
+        let num_columns = get_num_columns(&data)? as usize;
+
+        if lower.len() != num_columns || upper.len() != num_columns {
+            return Err("bounds must be defined for each column".into())
+        }
+
+        let mut quantiles = Array2::<f64>::zeros((self.alphas.len(), num_columns));
+        for (column_number, column) in data.gencolumns().into_iter().enumerate() {
+            let estimates = joint_quantiles(
+                &column.to_vec(), &self.alphas,
+                lower[column_number], upper[column_number],
+                epsilon, sensitivity)?;
+            quantiles.column_mut(column_number).iter_mut()
+                .zip(estimates.into_iter())
+                .for_each(|(quantile, estimate)| *quantile = estimate);
+        }
+
+        let quantiles: ArrayD<f64> = quantiles.into_dyn();
+        let quantiles = if ndim == 1 { to_nd(quantiles, &1)? } else { quantiles };
+
+        Ok(ReleaseNode {
+            value: quantiles.into(),
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+            public_shape: true,
+            public_metadata: true
+        })
+    }
+}
+
+/// Estimate several quantiles of one column, spending `epsilon` on each level of recursion.
+///
+/// The middle alpha is estimated first. Records below the estimate are used to estimate the smaller alphas,
+/// and records above it are used to estimate the larger alphas.
+/// The estimates are sorted before being returned, so that they are non-decreasing in alpha.
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::components::dp_quantiles::joint_quantiles;
+///
+/// let data = (0..100).map(|v| v as f64).collect::<Vec<f64>>();
+/// let quantiles = joint_quantiles(&data, &[0.25, 0.5, 0.75], 0., 100., 1., 1.).unwrap();
+/// assert_eq!(quantiles.len(), 3);
+/// assert!(quantiles.windows(2).all(|w| w[0] <= w[1]));
+/// assert!(quantiles.iter().all(|q| 0. <= *q && *q <= 100.));
+/// ```
+pub fn joint_quantiles(
+    data: &[f64], alphas: &[f64],
+    lower: f64, upper: f64,
+    epsilon: f64, sensitivity: f64,
+) -> Result<Vec<f64>> {
+    if lower > upper {
+        return Err("lower may not be greater than upper".into())
+    }
+
+    let mut data = data.iter()
+        .map(|v| v.max(lower).min(upper))
+        .collect::<Vec<f64>>();
+    data.sort_by(|l, r| l.partial_cmp(r).unwrap());
+
+    let mut estimates = Vec::with_capacity(alphas.len());
+    partition_quantiles(&data, alphas, 0., 1., lower, upper, epsilon, sensitivity, &mut estimates)?;
+
+    // postprocessing: enforce monotonicity in alpha
+    estimates.sort_by(|l, r| l.partial_cmp(r).unwrap());
+    Ok(estimates)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn partition_quantiles(
+    data: &[f64], alphas: &[f64],
+    alpha_lower: f64, alpha_upper: f64,
+    lower: f64, upper: f64,
+    epsilon: f64, sensitivity: f64,
+    estimates: &mut Vec<f64>,
+) -> Result<()> {
+    if alphas.is_empty() {
+        return Ok(())
+    }
+
+    let middle = alphas.len() / 2;
+    // the alpha relative to the records within [lower, upper]
+    let alpha = (alphas[middle] - alpha_lower) / (alpha_upper - alpha_lower);
+    let estimate = exponential_quantile(data, alpha, lower, upper, epsilon, sensitivity)?;
+
+    let split = data.iter().take_while(|v| **v < estimate).count();
+
+    partition_quantiles(
+        &data[..split], &alphas[..middle],
+        alpha_lower, alphas[middle], lower, estimate,
+        epsilon, sensitivity, estimates)?;
+    estimates.push(estimate);
+    partition_quantiles(
+        &data[split..], &alphas[middle + 1..],
+        alphas[middle], alpha_upper, estimate, upper,
+        epsilon, sensitivity, estimates)
+}
+
+/// Sample a single quantile of sorted data within [lower, upper] via the exponential mechanism.
+///
+/// The candidates are the intervals between consecutive records.
+/// The utility of the interval above the i-th record is the negated distance between i and the target rank.
+fn exponential_quantile(
+    data: &[f64], alpha: f64,
+    lower: f64, upper: f64,
+    epsilon: f64, sensitivity: f64,
+) -> Result<f64> {
+    let points = std::iter::once(lower)
+        .chain(data.iter().cloned())
+        .chain(std::iter::once(upper))
+        .collect::<Vec<f64>>();
+    let target = alpha * data.len() as f64;
+
+    // weights are computed in log space, scaled by the intervals' widths
+    let log_weights = points.windows(2).enumerate()
+        .map(|(rank, interval)| (interval[1] - interval[0]).ln()
+            - epsilon * (rank as f64 - target).abs() / (2. * sensitivity))
+        .collect::<Vec<f64>>();
+
+    let max_log_weight = log_weights.iter().cloned().fold(std::f64::NEG_INFINITY, f64::max);
+    // all intervals are empty when the bounds coincide
+    if max_log_weight == std::f64::NEG_INFINITY {
+        return Ok(lower)
+    }
+
+    let weights = log_weights.iter()
+        .map(|log_weight| (log_weight - max_log_weight).exp())
+        .collect::<Vec<f64>>();
+    let total: f64 = weights.iter().sum();
+
+    let mut remaining = noise::sample_uniform(&0., &total)?;
+    let index = weights.iter()
+        .position(|weight| {
+            remaining -= weight;
+            remaining <= 0.
+        })
+        .unwrap_or(weights.len() - 1);
+
+    noise::sample_uniform(&points[index], &points[index + 1])
+}
@@ -0,0 +1,52 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Value, Array, ReleaseNode, Hashmap};
+use whitenoise_validator::utilities::{get_argument, get_epsilon};
+use crate::components::Evaluable;
+use crate::utilities;
+use whitenoise_validator::proto;
+use ndarray::{ArrayD, arr0};
+use std::collections::BTreeMap;
+
+
+impl Evaluable for proto::DpStabilityHistogram {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let sensitivity = get_argument(&arguments, "sensitivity")?.first_f64()?;
+        let threshold = get_argument(&arguments, "threshold")?.first_f64()?;
+        let epsilon = get_epsilon(self.privacy_usage.first()
+            .ok_or_else(|| Error::from("privacy_usage: must be defined"))?)?;
+
+        let value = Value::Hashmap(match get_argument(&arguments, "data")?.array()? {
+            Array::Str(data) => Hashmap::Str(stability_histogram(data, epsilon, sensitivity, threshold)?),
+            Array::I64(data) => Hashmap::I64(stability_histogram(data, epsilon, sensitivity, threshold)?),
+            Array::Bool(data) => Hashmap::Bool(stability_histogram(data, epsilon, sensitivity, threshold)?),
+            Array::F64(_) => return Err("data: categories may not be floats".into())
+        });
+
+        Ok(ReleaseNode {
+            value,
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+            public_shape: true,
+            public_metadata: true
+        })
+    }
+}
+
+/// Count each category present in the data, add noise, and retain the categories whose noisy count exceeds the threshold.
+fn stability_histogram<T: Ord + Clone>(
+    data: &ArrayD<T>, epsilon: f64, sensitivity: f64, threshold: f64
+) -> Result<BTreeMap<T, Value>> {
+    let mut counts = BTreeMap::<T, i64>::new();
+    data.iter().for_each(|category| *counts.entry(category.clone()).or_insert(0) += 1);
+
+    let mut histogram = BTreeMap::new();
+    for (category, count) in counts {
+        let noised = count as f64 + utilities::mechanisms::laplace_mechanism(&epsilon, &sensitivity)?;
+        if noised > threshold {
+            histogram.insert(category, arr0(noised).into_dyn().into());
+        }
+    }
+    Ok(histogram)
+}
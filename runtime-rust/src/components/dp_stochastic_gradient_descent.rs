@@ -0,0 +1,100 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Array, ReleaseNode};
+use whitenoise_validator::utilities::get_argument;
+use whitenoise_validator::components::dp_stochastic_gradient_descent::sgd_privacy_usage;
+use crate::components::Evaluable;
+use crate::utilities::{to_nd, noise};
+use whitenoise_validator::proto;
+
+
+impl Evaluable for proto::DpStochasticGradientDescent {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let data = match get_argument(&arguments, "data")?.array()? {
+            Array::F64(data) => data.clone(),
+            Array::I64(data) => data.mapv(|v| v as f64),
+            _ => return Err("data must be numeric".into())
+        };
+        let labels = match get_argument(&arguments, "target")?.array()? {
+            Array::Bool(target) => target.iter().cloned().collect::<Vec<bool>>(),
+            _ => return Err("target must be boolean".into())
+        };
+
+        // the intercept is a leading constant column
+        let features = to_nd(data, &2)?.genrows().into_iter()
+            .map(|row| if self.intercept { Some(1.) } else { None }.into_iter()
+                .chain(row.iter().cloned())
+                .collect::<Vec<f64>>())
+            .collect::<Vec<Vec<f64>>>();
+
+        let coefficients = noisy_gradient_descent(
+            &features, &labels,
+            self.noise_multiplier, self.sampling_rate, self.steps,
+            self.clipping_norm, self.learning_rate)?;
+
+        Ok(ReleaseNode {
+            value: ndarray::arr1(&coefficients).into_dyn().into(),
+            privacy_usages: Some(vec![sgd_privacy_usage(self)?]),
+            public: true,
+            public_shape: true,
+            public_metadata: true
+        })
+    }
+}
+
+/// Fit the coefficients of a logistic regression by noisy stochastic gradient descent.
+///
+/// On each step, each record is sampled with probability `sampling_rate`.
+/// The gradient of the log loss of each sampled record is clipped to an L2 norm of `clipping_norm`,
+/// and Gaussian noise with standard deviation `noise_multiplier * clipping_norm` is added to each coordinate of the sum.
+/// The noisy sum is divided by the expected batch size, so that the step does not reveal the size of the batch.
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::components::dp_stochastic_gradient_descent::noisy_gradient_descent;
+///
+/// let features = (0..100).map(|i| vec![1., (i % 10) as f64 / 10.]).collect::<Vec<Vec<f64>>>();
+/// let labels = (0..100).map(|i| i % 10 >= 5).collect::<Vec<bool>>();
+/// let coefficients = noisy_gradient_descent(&features, &labels, 1., 0.1, 10, 1., 0.1).unwrap();
+/// assert_eq!(coefficients.len(), 2);
+/// ```
+pub fn noisy_gradient_descent(
+    features: &[Vec<f64>], labels: &[bool],
+    noise_multiplier: f64, sampling_rate: f64, steps: u32,
+    clipping_norm: f64, learning_rate: f64,
+) -> Result<Vec<f64>> {
+    if features.len() != labels.len() {
+        return Err("data and target must have the same number of records".into())
+    }
+    let num_coefficients = features.first().map(Vec::len)
+        .ok_or_else(|| Error::from("data must contain at least one record"))?;
+
+    let expected_batch_size = (sampling_rate * features.len() as f64).max(1.);
+    let mut coefficients = vec![0.; num_coefficients];
+
+    for _ in 0..steps {
+        let mut gradient = vec![0.; num_coefficients];
+
+        for (record, label) in features.iter().zip(labels.iter()) {
+            if noise::sample_bit(&sampling_rate)? == 0 {
+                continue
+            }
+            let score = record.iter().zip(coefficients.iter()).map(|(x, c)| x * c).sum::<f64>();
+            let residual = 1. / (1. + (-score).exp()) - if *label { 1. } else { 0. };
+
+            let norm = record.iter().map(|x| (residual * x).powi(2)).sum::<f64>().sqrt();
+            let scale = if norm > clipping_norm { clipping_norm / norm } else { 1. };
+            gradient.iter_mut().zip(record.iter())
+                .for_each(|(total, x)| *total += residual * x * scale);
+        }
+
+        coefficients.iter_mut().zip(gradient.iter())
+            .for_each(|(coefficient, total)| {
+                let noisy_total = total + noise::sample_gaussian(&0., &(noise_multiplier * clipping_norm));
+                *coefficient -= learning_rate * noisy_total / expected_batch_size;
+            });
+    }
+
+    Ok(coefficients)
+}
@@ -0,0 +1,203 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Array, Jagged, ReleaseNode};
+use whitenoise_validator::utilities::get_argument;
+use whitenoise_validator::components::marginals::marginal_columns;
+use whitenoise_validator::components::dp_synthetic_data::get_iteration_epsilons;
+use crate::components::Evaluable;
+use crate::components::dp_decision_tree::get_feature_indices;
+use crate::utilities::mechanisms::{exponential_mechanism, laplace_mechanism};
+use whitenoise_validator::proto;
+use ndarray::{ArrayD, Array2, arr1};
+use std::cmp::Ordering;
+
+
+impl Evaluable for proto::DpSyntheticData {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let sensitivity = get_argument(&arguments, "sensitivity")?.first_f64()?;
+        let (select_epsilon, measure_epsilon) = get_iteration_epsilons(&self.privacy_usage, self.iterations)?;
+
+        let synthesize = |records: &[Vec<Option<usize>>], num_categories: &[usize]| mwem(
+            records, num_categories, &self.marginals, self.iterations,
+            select_epsilon, measure_epsilon, sensitivity);
+
+        Ok(ReleaseNode {
+            value: match (get_argument(&arguments, "data")?.array()?, get_argument(&arguments, "categories")?.jagged()?) {
+                (Array::Bool(data), Jagged::Bool(categories)) => {
+                    let (records, num_categories) = get_feature_indices(data, categories)?;
+                    to_records(&synthesize(&records, &num_categories)?, categories, records.len())?.into()
+                },
+                (Array::I64(data), Jagged::I64(categories)) => {
+                    let (records, num_categories) = get_feature_indices(data, categories)?;
+                    to_records(&synthesize(&records, &num_categories)?, categories, records.len())?.into()
+                },
+                (Array::Str(data), Jagged::Str(categories)) => {
+                    let (records, num_categories) = get_feature_indices(data, categories)?;
+                    to_records(&synthesize(&records, &num_categories)?, categories, records.len())?.into()
+                },
+                (Array::F64(_), _) => return Err("data: float data may not be categorical".into()),
+                _ => return Err("data and categories must be homogeneously typed".into())
+            },
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+            public_shape: true,
+            public_metadata: true
+        })
+    }
+}
+
+/// Synthetic distribution over the cross product of the categories, via the multiplicative weights exponential mechanism.
+///
+/// Cells of the cross product are ordered with the categories of the last column varying fastest.
+/// The distribution sums to the number of records, and starts uniform.
+/// On each iteration, the cell of the workload whose count is estimated worst by the synthetic distribution is selected via the exponential mechanism,
+/// its count is measured via the Laplace mechanism, and the multiplicative weights update is replayed over every measurement so far.
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::components::dp_synthetic_data::mwem;
+///
+/// let records = (0..100).map(|i| vec![Some(i % 2), Some(i % 3)]).collect::<Vec<Vec<Option<usize>>>>();
+/// let synthetic = mwem(&records, &[2, 3], &[0b01, 0b10], 5, 0.5, 0.5, 1.).unwrap();
+/// assert_eq!(synthetic.len(), 6);
+/// assert!((synthetic.iter().sum::<f64>() - 100.).abs() < 1e-6);
+/// ```
+pub fn mwem(
+    records: &[Vec<Option<usize>>], num_categories: &[usize], marginals: &[u64],
+    iterations: u32, select_epsilon: f64, measure_epsilon: f64, sensitivity: f64,
+) -> Result<Vec<f64>> {
+    if records.is_empty() {
+        return Err("data must contain at least one record".into())
+    }
+    let num_records = records.len() as f64;
+
+    let strides = (0..num_categories.len())
+        .map(|column| num_categories[column + 1..].iter().product())
+        .collect::<Vec<usize>>();
+    let domain_size = num_categories.iter().product::<usize>();
+
+    // cell of each marginal that each cell of the cross product falls into
+    let projections = marginals.iter()
+        .map(|marginal| {
+            let columns = marginal_columns(*marginal, num_categories.len())?;
+            let projection = (0..domain_size)
+                .map(|cell| columns.iter().fold(0, |marginal_cell, column|
+                    marginal_cell * num_categories[*column] + (cell / strides[*column]) % num_categories[*column]))
+                .collect::<Vec<usize>>();
+            let num_cells = columns.iter().map(|column| num_categories[*column]).product::<usize>();
+            Ok((projection, num_cells))
+        })
+        .collect::<Result<Vec<(Vec<usize>, usize)>>>()?;
+
+    let mut histogram = vec![0.; domain_size];
+    records.iter()
+        .filter_map(|record| record.iter().zip(strides.iter())
+            .try_fold(0, |cell, (index, stride)| Some(cell + (*index)? * stride)))
+        .for_each(|cell| histogram[cell] += 1.);
+
+    let true_tables = projections.iter()
+        .map(|(projection, num_cells)| project(&histogram, projection, *num_cells))
+        .collect::<Vec<Vec<f64>>>();
+
+    // candidate queries are the cells of every marginal in the workload
+    let candidates = true_tables.iter().enumerate()
+        .flat_map(|(marginal, table)| (0..table.len()).map(move |cell| (marginal, cell)))
+        .collect::<Vec<(usize, usize)>>();
+
+    let mut synthetic = vec![num_records / domain_size as f64; domain_size];
+    let mut measurements = Vec::new();
+
+    for _ in 0..iterations {
+        let synthetic_tables = projections.iter()
+            .map(|(projection, num_cells)| project(&synthetic, projection, *num_cells))
+            .collect::<Vec<Vec<f64>>>();
+
+        let utility = |candidate: &usize| {
+            let (marginal, cell) = candidates[*candidate];
+            (synthetic_tables[marginal][cell] - true_tables[marginal][cell]).abs()
+        };
+        let selected = exponential_mechanism(
+            &select_epsilon, &sensitivity,
+            arr1(&(0..candidates.len()).collect::<Vec<usize>>()).into_dyn(), &utility)?;
+
+        let (marginal, cell) = candidates[selected];
+        measurements.push((marginal, cell, true_tables[marginal][cell] + laplace_mechanism(&measure_epsilon, &sensitivity)?));
+
+        for (marginal, cell, measurement) in &measurements {
+            let projection = &projections[*marginal].0;
+            let estimate = synthetic.iter().zip(projection.iter())
+                .filter(|(_, marginal_cell)| *marginal_cell == cell)
+                .map(|(weight, _)| weight)
+                .sum::<f64>();
+
+            let factor = ((measurement - estimate) / (2. * num_records)).exp();
+            synthetic.iter_mut().zip(projection.iter())
+                .filter(|(_, marginal_cell)| *marginal_cell == cell)
+                .for_each(|(weight, _)| *weight *= factor);
+
+            let scale = num_records / synthetic.iter().sum::<f64>();
+            synthetic.iter_mut().for_each(|weight| *weight *= scale);
+        }
+    }
+
+    Ok(synthetic)
+}
+
+/// Sum a distribution over the cross product into the cells of a marginal.
+fn project(distribution: &[f64], projection: &[usize], num_cells: usize) -> Vec<f64> {
+    let mut table = vec![0.; num_cells];
+    projection.iter().zip(distribution.iter())
+        .for_each(|(cell, weight)| table[*cell] += weight);
+    table
+}
+
+/// Round a synthetic distribution into whole records, preserving the number of records.
+///
+/// Each cell receives the floor of its weight, and the remaining records go to the cells with the largest remainders.
+fn round_distribution(distribution: &[f64], num_records: usize) -> Vec<usize> {
+    let mut counts = distribution.iter()
+        .map(|weight| weight.max(0.).floor() as usize)
+        .collect::<Vec<usize>>();
+    let remaining = num_records.saturating_sub(counts.iter().sum());
+
+    let remainder = |cell: &usize| distribution[*cell] - distribution[*cell].floor();
+    let mut cells = (0..distribution.len()).collect::<Vec<usize>>();
+    cells.sort_by(|l, r| remainder(r).partial_cmp(&remainder(l)).unwrap_or(Ordering::Equal));
+    cells.into_iter().take(remaining).for_each(|cell| counts[cell] += 1);
+    counts
+}
+
+/// Synthetic records, with the categories of each cell of the cross product repeated as often as the rounded distribution.
+fn to_records<T: Clone>(
+    distribution: &[f64], categories: &[Option<Vec<T>>], num_records: usize,
+) -> Result<ArrayD<T>> {
+    let categories = categories.iter()
+        .map(|column| column.as_ref().ok_or_else(|| Error::from("categories must be defined for every column")))
+        .collect::<Result<Vec<&Vec<T>>>>()?;
+    let strides = (0..categories.len())
+        .map(|column| categories[column + 1..].iter().map(|column| column.len()).product())
+        .collect::<Vec<usize>>();
+
+    let values = round_distribution(distribution, num_records).into_iter().enumerate()
+        .flat_map(|(cell, count)| std::iter::repeat(cell).take(count))
+        .flat_map(|cell| categories.iter().zip(strides.iter())
+            .map(|(column, stride)| column[(cell / stride) % column.len()].clone())
+            .collect::<Vec<T>>())
+        .collect::<Vec<T>>();
+
+    Ok(Array2::from_shape_vec((num_records, categories.len()), values)?.into_dyn())
+}
+
+
+#[cfg(test)]
+mod test_dp_synthetic_data {
+    use crate::components::dp_synthetic_data::round_distribution;
+
+    #[test]
+    fn test_rounding() {
+        assert_eq!(round_distribution(&[1.5, 2.25, 0.25], 4), vec![2, 2, 0]);
+        assert_eq!(round_distribution(&[0.4, 0.3, 0.3], 1), vec![1, 0, 0]);
+        assert_eq!(round_distribution(&[2., 3.], 5), vec![2, 3]);
+    }
+}
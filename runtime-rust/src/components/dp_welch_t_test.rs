@@ -0,0 +1,75 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Array, Jagged, ReleaseNode};
+use whitenoise_validator::utilities::get_argument;
+use whitenoise_validator::components::dp_welch_t_test::{GroupStatistics, get_test_epsilon, welch_t_test};
+use crate::components::Evaluable;
+use crate::utilities::mechanisms::laplace_mechanism;
+use whitenoise_validator::proto;
+use ndarray::{ArrayD, arr1};
+
+
+impl Evaluable for proto::DpWelchTTest {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let lower = get_argument(&arguments, "lower")?.first_f64()?;
+        let upper = get_argument(&arguments, "upper")?.first_f64()?;
+        let sensitivity = get_argument(&arguments, "sensitivity")?.first_f64()?;
+        let epsilon = get_test_epsilon(&self.privacy_usage)? / 3.;
+
+        let data = match get_argument(&arguments, "data")?.array()? {
+            Array::F64(data) => data.iter().cloned().collect::<Vec<f64>>(),
+            Array::I64(data) => data.iter().map(|v| *v as f64).collect::<Vec<f64>>(),
+            _ => return Err("data must be numeric".into())
+        };
+
+        let groups = match (get_argument(&arguments, "by")?.array()?, get_argument(&arguments, "categories")?.jagged()?) {
+            (Array::Bool(by), Jagged::Bool(categories)) => get_groups(by, categories)?,
+            (Array::I64(by), Jagged::I64(categories)) => get_groups(by, categories)?,
+            (Array::Str(by), Jagged::Str(categories)) => get_groups(by, categories)?,
+            (Array::F64(_), _) => return Err("by: float data may not be categorical".into()),
+            _ => return Err("by and categories must be homogeneously typed".into())
+        };
+        if groups.len() != data.len() {
+            return Err("data and by must have the same number of records".into())
+        }
+
+        // each statistic of each group is released with a third of the usage
+        let (midpoint, radius) = ((lower + upper) / 2., (upper - lower) / 2.);
+        let mut statistics = [GroupStatistics { count: 0., sum: 0., sum_squares: 0. }; 2];
+        data.iter().zip(groups.iter())
+            .filter_map(|(value, group)| Some((value, (*group)?)))
+            .for_each(|(value, group)| {
+                let centered = value.max(lower).min(upper) - midpoint;
+                statistics[group].count += 1.;
+                statistics[group].sum += centered;
+                statistics[group].sum_squares += centered.powi(2);
+            });
+        for group in statistics.iter_mut() {
+            group.count += laplace_mechanism(&epsilon, &sensitivity)?;
+            group.sum += laplace_mechanism(&epsilon, &(sensitivity * radius))?;
+            group.sum_squares += laplace_mechanism(&epsilon, &(sensitivity * radius.powi(2)))?;
+        }
+
+        let (statistic, degrees_of_freedom, noise_deviation) = welch_t_test(&statistics, sensitivity * radius / epsilon)?;
+
+        Ok(ReleaseNode {
+            value: arr1(&[statistic, degrees_of_freedom, noise_deviation]).into_dyn().into(),
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+            public_shape: true,
+            public_metadata: true
+        })
+    }
+}
+
+/// Index of the group of each record, or None if the record falls into neither group.
+fn get_groups<T: PartialEq>(by: &ArrayD<T>, categories: &[Option<Vec<T>>]) -> Result<Vec<Option<usize>>> {
+    let categories = match categories {
+        [Some(categories)] if categories.len() == 2 => categories,
+        _ => return Err("categories must contain exactly two groups".into())
+    };
+    Ok(by.iter()
+        .map(|value| categories.iter().position(|category| category == value))
+        .collect())
+}
@@ -0,0 +1,28 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Array, ReleaseNode};
+use whitenoise_validator::utilities::get_argument;
+use whitenoise_validator::components::empirical_cdf::empirical_cdf;
+use crate::components::Evaluable;
+use whitenoise_validator::proto;
+use ndarray::ArrayD;
+
+
+impl Evaluable for proto::EmpiricalCdf {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let counts = match get_argument(&arguments, "counts")?.array()? {
+            Array::F64(counts) => counts.iter().cloned().collect::<Vec<f64>>(),
+            Array::I64(counts) => counts.iter().map(|v| *v as f64).collect::<Vec<f64>>(),
+            _ => return Err("EmpiricalCdf: counts must be numeric".into())
+        };
+
+        let num_records = match arguments.get("num_records") {
+            Some(num_records) => Some(num_records.first_i64()? as f64),
+            None => None
+        };
+
+        let cdf = empirical_cdf(&counts, num_records)?;
+        Ok(ReleaseNode::new(ArrayD::from_shape_vec(vec![cdf.len()], cdf)?.into()))
+    }
+}
@@ -0,0 +1,15 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::ReleaseNode;
+use whitenoise_validator::utilities::get_argument;
+use crate::components::Evaluable;
+use whitenoise_validator::proto;
+
+
+impl Evaluable for proto::ExpectProperty {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        // expectations are checked during validation; the data passes through unchanged
+        Ok(ReleaseNode::new(get_argument(&arguments, "data")?.clone()))
+    }
+}
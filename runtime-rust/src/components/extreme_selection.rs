@@ -0,0 +1,86 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Array, Jagged, ReleaseNode};
+use whitenoise_validator::utilities::{get_argument, broadcast_privacy_usage, get_epsilon};
+use crate::components::Evaluable;
+use crate::utilities::{to_nd, get_num_columns};
+use crate::utilities::mechanisms::exponential_mechanism;
+use whitenoise_validator::proto;
+use ndarray::arr1;
+
+
+impl Evaluable for proto::ExtremeSelection {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let sensitivity = get_argument(&arguments, "sensitivity")?.first_f64()?;
+
+        let data = match get_argument(&arguments, "data")?.array()? {
+            Array::F64(data) => data.clone(),
+            Array::I64(data) => data.mapv(|v| v as f64),
+            _ => return Err("data: atomic type must be numeric".into())
+        };
+        let data = to_nd(data, &2)?;
+        let num_columns = get_num_columns(&data)? as usize;
+
+        let candidates = match get_argument(&arguments, "candidates")?.jagged()? {
+            Jagged::F64(candidates) => candidates.clone(),
+            _ => return Err("candidates: must be floats".into())
+        };
+        if candidates.len() != num_columns {
+            return Err("candidates: must be defined for each column".into())
+        }
+
+        let is_maximum = match self.extreme.to_lowercase().as_str() {
+            "minimum" => false,
+            "maximum" => true,
+            _ => return Err("extreme: must be one of [minimum, maximum]".into())
+        };
+
+        let usages = broadcast_privacy_usage(&self.privacy_usage, num_columns)?;
+
+        let selections = data.gencolumns().into_iter()
+            .zip(candidates.into_iter())
+            .zip(usages.iter())
+            .map(|((column, candidates), usage)| select_extreme(
+                &column.to_vec(),
+                &candidates.ok_or_else(|| Error::from("candidates: must be defined for each column"))?,
+                is_maximum, get_epsilon(usage)?, sensitivity))
+            .collect::<Result<Vec<f64>>>()?;
+
+        Ok(ReleaseNode {
+            value: arr1(&selections).into_dyn().into(),
+            privacy_usages: Some(usages),
+            public: true,
+            public_shape: true,
+            public_metadata: true
+        })
+    }
+}
+
+/// Select the minimum or maximum of a column from a set of candidates via the exponential mechanism.
+///
+/// The utility of a candidate is the negated number of records beyond it,
+/// so candidates at or beyond the extreme have the greatest utility.
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::components::extreme_selection::select_extreme;
+///
+/// let data = (10..100).map(|v| v as f64).collect::<Vec<f64>>();
+/// let candidates = (0..=10).map(|v| v as f64 * 10.).collect::<Vec<f64>>();
+/// let minimum = select_extreme(&data, &candidates, false, 1., 1.).unwrap();
+/// assert!(candidates.contains(&minimum));
+/// ```
+pub fn select_extreme(
+    data: &[f64], candidates: &[f64],
+    is_maximum: bool, epsilon: f64, sensitivity: f64,
+) -> Result<f64> {
+    if candidates.is_empty() {
+        return Err("candidates: must contain at least one candidate".into())
+    }
+    let utility = |candidate: &f64| -(data.iter()
+        .filter(|v| if is_maximum { *v > candidate } else { *v < candidate })
+        .count() as f64);
+
+    exponential_mechanism(&epsilon, &sensitivity, arr1(candidates).into_dyn(), &utility)
+}
@@ -0,0 +1,29 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::ReleaseNode;
+use whitenoise_validator::utilities::get_argument;
+use crate::components::Evaluable;
+use whitenoise_validator::proto;
+use ndarray::{ArrayD, IxDyn};
+
+
+impl Evaluable for proto::GiniCoefficient {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let numerator = get_argument(&arguments, "numerator")?.array()?.f64()?;
+        let mean = get_argument(&arguments, "mean")?.array()?.f64()?;
+        if numerator.shape() != mean.shape() {
+            return Err("numerator and mean must have the same shape".into())
+        }
+
+        // noise may push the ratio outside of the range of the coefficient
+        let coefficient = numerator.iter().zip(mean.iter())
+            .map(|(numerator, mean)| match *mean > 0. {
+                true => (numerator / mean).max(0.).min(1.),
+                false => std::f64::NAN
+            })
+            .collect::<Vec<f64>>();
+        let coefficient = ArrayD::from_shape_vec(IxDyn(numerator.shape()), coefficient)?;
+        Ok(ReleaseNode::new(coefficient.into()))
+    }
+}
@@ -0,0 +1,66 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::ReleaseNode;
+use whitenoise_validator::utilities::get_argument;
+use crate::components::Evaluable;
+use ndarray::{ArrayD, Array};
+use crate::utilities::get_num_columns;
+use whitenoise_validator::proto;
+use std::cmp::Ordering;
+
+impl Evaluable for proto::GiniNumerator {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        Ok(ReleaseNode::new(gini_numerator(
+            get_argument(&arguments, "data")?.array()?.f64()?
+        )?.into()))
+    }
+}
+
+/// Calculates the numerator of the Gini coefficient of each column in the provided data.
+///
+/// The numerator is `sum_i sum_j |x_i - x_j| / (2 n^2)`, computed in `O(n log n)`
+/// as `sum_i (2 i - n - 1) x_(i) / n^2` over the sorted records `x_(1) <= ... <= x_(n)`.
+///
+/// # Arguments
+/// * `data` - Non-negative data for which you want the numerator.
+///
+/// # Return
+/// Numerator of the Gini coefficient of each column.
+///
+/// # Example
+/// ```
+/// use ndarray::prelude::*;
+/// use whitenoise_runtime::components::gini_numerator::gini_numerator;
+/// let data = arr2(&[ [1., 0.], [2., 0.], [3., 9.] ]).into_dyn();
+/// let numerators = gini_numerator(&data).unwrap();
+/// assert!(numerators == arr2(&[[4. / 9., 2.]]).into_dyn());
+/// ```
+pub fn gini_numerator(data: &ArrayD<f64>) -> Result<ArrayD<f64>> {
+    // iterate over the generalized columns
+    let numerators = data.gencolumns().into_iter()
+        .map(|column| {
+            let num_records = column.len() as f64;
+            if num_records == 0. {
+                return Err("attempted gini numerator of an empty column".into())
+            }
+            let mut column = column.to_vec();
+            column.sort_by(|l, r| l.partial_cmp(r).unwrap_or(Ordering::Equal));
+            Ok(column.iter().enumerate()
+                .map(|(index, value)| (2. * (index + 1) as f64 - num_records - 1.) * value)
+                .sum::<f64>() / num_records.powi(2))
+        })
+        .collect::<Result<Vec<f64>>>()?;
+
+    // ensure numerators are of correct dimension
+    let array = match data.ndim() {
+        1 => Array::from_shape_vec(vec![], numerators),
+        2 => Array::from_shape_vec(vec![1 as usize, get_num_columns(&data)? as usize], numerators),
+        _ => return Err("invalid data shape for GiniNumerator".into())
+    };
+
+    match array {
+        Ok(array) => Ok(array),
+        Err(_) => Err("unable to package GiniNumerator result into an array".into())
+    }
+}
@@ -19,6 +19,11 @@ impl Evaluable for proto::Index {
         let columns = get_argument(&arguments, "columns")?.array()?;
 
         let mut indexed = match data {
+            // if the cells of the hashmap are hashmaps, the data is a nested partition, indexed by a path of keys
+            Value::Hashmap(partition) if partition.values().first()
+                .map(|cell| match cell { Value::Hashmap(_) => true, _ => false })
+                .unwrap_or(false) => index_path(partition, columns, 0),
+
             // if value is a hashmap, we'll be stacking arrays column-wise
             Value::Hashmap(dataframe) => match dataframe {
                 Hashmap::Str(dataframe) => match columns {
@@ -84,13 +89,39 @@ impl Evaluable for proto::Index {
                     }
                 }
             }
-            _ => unreachable!()
+            _ => ()
         };
 
         Ok(ReleaseNode::new(indexed))
     }
 }
 
+/// Retrieve the cell of a nested partition at a path of keys, with one key for each level to traverse.
+fn index_path(partition: &Hashmap<Value>, keys: &Array, depth: usize) -> Result<Value> {
+    let (cell, num_keys) = match (partition, keys) {
+        (Hashmap::Str(cells), Array::Str(keys)) => {
+            let keys = to_name_vec(keys)?;
+            (keys.get(depth).and_then(|key| cells.get(key)), keys.len())
+        },
+        (Hashmap::I64(cells), Array::I64(keys)) => {
+            let keys = to_name_vec(keys)?;
+            (keys.get(depth).and_then(|key| cells.get(key)), keys.len())
+        },
+        (Hashmap::Bool(cells), Array::Bool(keys)) => {
+            let keys = to_name_vec(keys)?;
+            (keys.get(depth).and_then(|key| cells.get(key)), keys.len())
+        },
+        _ => return Err("keys must have the same type as the keys of the partition".into())
+    };
+    let cell = cell.ok_or_else(|| Error::from("unknown key in partition"))?;
+
+    match (cell, depth + 1 < num_keys) {
+        (Value::Hashmap(cell), true) => index_path(cell, keys, depth + 1),
+        (_, true) => Err("more keys were supplied than there are levels of nested partitions".into()),
+        (cell, false) => Ok(cell.clone())
+    }
+}
+
 fn column_stack<T: Clone + Eq + std::hash::Hash + Ord>(
     dataframe: &BTreeMap<T, Value>, column_names: &Vec<T>,
 ) -> Result<Value> {
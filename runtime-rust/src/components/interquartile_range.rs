@@ -0,0 +1,24 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::ReleaseNode;
+use whitenoise_validator::utilities::get_argument;
+use crate::components::Evaluable;
+use crate::utilities::to_nd;
+use whitenoise_validator::proto;
+use ndarray::{Axis, Ix2};
+
+
+impl Evaluable for proto::InterquartileRange {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let quartiles = to_nd(get_argument(&arguments, "data")?.array()?.f64()?.clone(), &2)?
+            .into_dimensionality::<Ix2>()?;
+        if quartiles.len_of(Axis(0)) != 2 {
+            return Err("data must contain a row for the lower and upper quartile".into())
+        }
+
+        // the quartiles of each column are released non-decreasing, so the range is non-negative
+        let range = &quartiles.index_axis(Axis(0), 1) - &quartiles.index_axis(Axis(0), 0);
+        Ok(ReleaseNode::new(range.into_dyn().into()))
+    }
+}
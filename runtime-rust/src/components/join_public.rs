@@ -0,0 +1,87 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Array, ReleaseNode};
+use whitenoise_validator::utilities::get_argument;
+use crate::components::Evaluable;
+use crate::utilities::to_nd;
+use whitenoise_validator::proto;
+use ndarray::{ArrayD, Axis, IxDyn};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+
+impl Evaluable for proto::JoinPublic {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let indices = match (get_argument(arguments, "data")?.array()?, get_argument(arguments, "keys")?.array()?) {
+            (Array::Bool(data), Array::Bool(keys)) => lookup(data, keys)?,
+            (Array::I64(data), Array::I64(keys)) => lookup(data, keys)?,
+            (Array::Str(data), Array::Str(keys)) => lookup(data, keys)?,
+            (Array::F64(_), _) => return Err("data: float data may not be used as a key".into()),
+            _ => return Err("data and keys must be homogeneously typed".into())
+        };
+
+        Ok(ReleaseNode::new(match (get_argument(arguments, "values")?.array()?, get_argument(arguments, "default")?.array()?) {
+            (Array::Bool(values), Array::Bool(default)) => gather(&indices, values, default)?.into(),
+            (Array::I64(values), Array::I64(default)) => gather(&indices, values, default)?.into(),
+            (Array::F64(values), Array::F64(default)) => gather(&indices, values, default)?.into(),
+            (Array::Str(values), Array::Str(default)) => gather(&indices, values, default)?.into(),
+            _ => return Err("values and default must be homogeneously typed".into())
+        }))
+    }
+}
+
+/// Row of the lookup table matching each record, if any.
+///
+/// # Arguments
+/// * `data` - Single column of keys to look up.
+/// * `keys` - Unique keys of the lookup table.
+///
+/// # Return
+/// Index of the matching key for each record.
+pub fn lookup<T: Eq + Hash>(data: &ArrayD<T>, keys: &ArrayD<T>) -> Result<Vec<Option<usize>>> {
+    let indices = keys.iter().enumerate()
+        .map(|(index, key)| (key, index))
+        .collect::<HashMap<&T, usize>>();
+    if indices.len() != keys.len() {
+        return Err("keys: must be unique".into())
+    }
+    Ok(data.iter().map(|value| indices.get(value).cloned()).collect())
+}
+
+/// Row of values for each record, or the default row for records without a match.
+///
+/// # Example
+/// ```
+/// use ndarray::{arr1, arr2};
+/// use whitenoise_runtime::components::join_public::{lookup, gather};
+///
+/// let indices = lookup(&arr1(&[2, 1, 7]).into_dyn(), &arr1(&[1, 2]).into_dyn()).unwrap();
+/// let joined = gather(&indices, &arr2(&[[1., 10.], [2., 20.]]).into_dyn(), &arr2(&[[0., 0.]]).into_dyn()).unwrap();
+/// assert_eq!(joined, arr2(&[[2., 20.], [1., 10.], [0., 0.]]).into_dyn());
+/// ```
+pub fn gather<T: Clone>(indices: &[Option<usize>], values: &ArrayD<T>, default: &ArrayD<T>) -> Result<ArrayD<T>> {
+    let ndim = values.ndim().max(1);
+    let values = to_nd(values.clone(), &2)?;
+    let num_columns = values.shape()[1];
+    let default = default.iter().cloned().collect::<Vec<T>>();
+    if default.len() != num_columns {
+        return Err("default: must have one value for each column of values".into())
+    }
+    if indices.iter().flatten().any(|index| *index >= values.shape()[0]) {
+        return Err("values: must have one row for each key".into())
+    }
+
+    let joined = indices.iter()
+        .flat_map(|index| match index {
+            Some(index) => values.index_axis(Axis(0), *index).iter().cloned().collect(),
+            None => default.clone()
+        })
+        .collect::<Vec<T>>();
+
+    let shape = match ndim {
+        1 => vec![indices.len()],
+        _ => vec![indices.len(), num_columns]
+    };
+    Ok(ArrayD::from_shape_vec(IxDyn(&shape), joined)?)
+}
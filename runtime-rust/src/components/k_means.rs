@@ -0,0 +1,94 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{ReleaseNode, Value};
+use whitenoise_validator::utilities::get_argument;
+use crate::components::Evaluable;
+use whitenoise_validator::proto;
+use ndarray::{ArrayD, Array2, Axis};
+
+
+impl Evaluable for proto::KMeansStatistics {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let data = get_argument(&arguments, "data")?.array()?.f64()?;
+        let centroids = get_argument(&arguments, "centroids")?.array()?.f64()?;
+
+        Ok(ReleaseNode::new(k_means_statistics(data, centroids)?.into()))
+    }
+}
+
+impl Evaluable for proto::KMeansCentroids {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let statistics = get_argument(&arguments, "data")?.array()?.f64()?;
+        let centroids = get_argument(&arguments, "centroids")?.array()?.f64()?;
+
+        let mut centroids = k_means_centroids(statistics, centroids)?;
+
+        if !self.lower.is_empty() && !self.upper.is_empty() {
+            centroids.gencolumns_mut().into_iter()
+                .zip(self.lower.iter().zip(self.upper.iter()))
+                .for_each(|(mut column, (lower, upper))| column
+                    .mapv_inplace(|v| v.max(*lower).min(*upper)));
+        }
+
+        Ok(ReleaseNode::new(Value::from(centroids.into_dyn())))
+    }
+}
+
+/// Count and sum the records nearest to each centroid.
+///
+/// Each record is assigned to the centroid with the smallest squared euclidean distance, where ties go to the first centroid.
+///
+/// # Returns
+/// A single row with the count of each cluster, followed by the sum of each column within each cluster.
+pub fn k_means_statistics(data: &ArrayD<f64>, centroids: &ArrayD<f64>) -> Result<ArrayD<f64>> {
+    let num_columns = centroids.len_of(Axis(1));
+    let num_clusters = centroids.len_of(Axis(0));
+    if data.ndim() != 2 || data.len_of(Axis(1)) != num_columns {
+        return Err("data: must have one column for each column of the centroids".into())
+    }
+
+    let mut counts = vec![0.; num_clusters];
+    let mut sums = vec![vec![0.; num_columns]; num_clusters];
+
+    data.outer_iter().for_each(|record| {
+        let nearest = centroids.outer_iter()
+            .map(|centroid| record.iter().zip(centroid.iter())
+                .map(|(l, r)| (l - r).powi(2)).sum::<f64>())
+            .enumerate()
+            .fold((0, std::f64::INFINITY), |(best, best_distance), (cluster, distance)|
+                if distance < best_distance { (cluster, distance) } else { (best, best_distance) }).0;
+
+        counts[nearest] += 1.;
+        sums[nearest].iter_mut().zip(record.iter()).for_each(|(sum, v)| *sum += v);
+    });
+
+    let statistics = counts.into_iter()
+        .chain(sums.into_iter().flatten())
+        .collect::<Vec<f64>>();
+    Ok(Array2::from_shape_vec((1, statistics.len()), statistics)?.into_dyn())
+}
+
+/// Move each centroid to the mean of its cluster, as estimated from the statistics emitted by `k_means_statistics`.
+///
+/// A cluster with a count below one keeps its previous centroid, as its mean is dominated by noise.
+pub fn k_means_centroids(statistics: &ArrayD<f64>, centroids: &ArrayD<f64>) -> Result<Array2<f64>> {
+    let num_columns = centroids.len_of(Axis(1));
+    let num_clusters = centroids.len_of(Axis(0));
+    let statistics = statistics.iter().cloned().collect::<Vec<f64>>();
+    if statistics.len() != num_clusters * (num_columns + 1) {
+        return Err("data: must contain a count and a sum of each column for every centroid".into())
+    }
+
+    let (counts, sums) = statistics.split_at(num_clusters);
+    let updated = centroids.outer_iter()
+        .zip(counts.iter().zip(sums.chunks(num_columns)))
+        .flat_map(|(centroid, (count, sums))| if *count < 1. {
+            centroid.iter().cloned().collect::<Vec<f64>>()
+        } else {
+            sums.iter().map(|sum| sum / count).collect()
+        })
+        .collect::<Vec<f64>>();
+
+    Ok(Array2::from_shape_vec((num_clusters, num_columns), updated)?)
+}
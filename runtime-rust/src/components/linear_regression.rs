@@ -0,0 +1,25 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{ReleaseNode, Value, Hashmap};
+use whitenoise_validator::utilities::get_argument;
+use whitenoise_validator::components::linear_regression::linear_regression;
+use crate::components::Evaluable;
+use whitenoise_validator::proto;
+use ndarray::arr1;
+
+
+impl Evaluable for proto::LinearRegression {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let statistics = get_argument(&arguments, "data")?.array()?.f64()?
+            .iter().cloned().collect::<Vec<f64>>();
+
+        let (coefficients, standard_errors) = linear_regression(
+            &statistics, &self.noise_variance, self.regularization, self.intercept)?;
+
+        Ok(ReleaseNode::new(Value::Hashmap(Hashmap::Str(vec![
+            ("coefficients".to_string(), arr1(&coefficients).into_dyn().into()),
+            ("standard_errors".to_string(), arr1(&standard_errors).into_dyn().into())
+        ].into_iter().collect()))))
+    }
+}
@@ -0,0 +1,81 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Array, Jagged, ReleaseNode};
+use whitenoise_validator::components::marginals::marginal_columns;
+use crate::components::Evaluable;
+use crate::components::contingency_table::contingency_table;
+use ndarray::{ArrayD, IxDyn, arr1};
+use whitenoise_validator::proto;
+use whitenoise_validator::utilities::get_argument;
+use std::hash::Hash;
+use crate::utilities::to_nd;
+
+
+impl Evaluable for proto::Marginals {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        Ok(ReleaseNode::new(match (get_argument(arguments, "data")?.array()?, get_argument(arguments, "categories")?.jagged()?) {
+            (Array::Bool(data), Jagged::Bool(categories)) =>
+                marginals(data, categories, &self.marginals)?.into(),
+            (Array::I64(data), Jagged::I64(categories)) =>
+                marginals(data, categories, &self.marginals)?.into(),
+            (Array::Str(data), Jagged::Str(categories)) =>
+                marginals(data, categories, &self.marginals)?.into(),
+            (Array::F64(_), _) => return Err("data: float data may not be categorical".into()),
+            _ => return Err("data and categories must be homogeneously typed".into())
+        }))
+    }
+}
+
+/// Count the records in each cell of each marginal of the workload, concatenated in the order of the workload.
+///
+/// Each marginal is a bitmask over the columns.
+/// Within each marginal, cells are ordered as in the [contingency_table](../contingency_table/fn.contingency_table.html).
+///
+/// # Example
+/// ```
+/// use ndarray::arr2;
+/// use whitenoise_runtime::components::marginals::marginals;
+///
+/// let data = arr2(&[[0, 1], [1, 2], [0, 2], [1, 2]]).into_dyn();
+/// let categories = vec![Some(vec![0, 1]), Some(vec![1, 2])];
+/// let counts = marginals(&data, &categories, &[0b01, 0b10]).unwrap();
+/// assert_eq!(counts.into_dimensionality::<ndarray::Ix1>().unwrap().to_vec(), vec![2, 2, 1, 3]);
+/// ```
+pub fn marginals<T: Clone + Eq + Hash>(
+    data: &ArrayD<T>, categories: &[Option<Vec<T>>], marginals: &[u64],
+) -> Result<ArrayD<i64>> {
+    let data = to_nd(data.clone(), &2)?;
+    let counts = marginals.iter()
+        .map(|marginal| {
+            let columns = marginal_columns(*marginal, categories.len())?;
+            let marginal_categories = columns.iter()
+                .map(|column| categories[*column].clone()
+                    .ok_or_else(|| Error::from("categories must be defined for every column")))
+                .collect::<Result<Vec<Vec<T>>>>()?;
+            // the columns are cloned rather than selected, because string categories are not Copy
+            let subset = ArrayD::from_shape_fn(IxDyn(&[data.shape()[0], columns.len()]),
+                |index| data[[index[0], columns[index[1]]]].clone());
+            contingency_table(&subset, &marginal_categories)
+        })
+        .collect::<Result<Vec<ArrayD<i64>>>>()?;
+
+    Ok(arr1(&counts.iter().flat_map(|table| table.iter().cloned()).collect::<Vec<i64>>()).into_dyn())
+}
+
+
+#[cfg(test)]
+mod test_marginals {
+    use ndarray::arr2;
+    use crate::components::marginals::marginals;
+
+    #[test]
+    fn test_marginals_str() {
+        let data = arr2(&[["a", "x"], ["b", "y"], ["a", "y"], ["b", "y"]]).mapv(String::from).into_dyn();
+        let categories = vec![
+            Some(vec!["a".to_string(), "b".to_string()]),
+            Some(vec!["x".to_string(), "y".to_string()])];
+        let counts = marginals(&data, &categories, &[0b01, 0b10, 0b11]).unwrap();
+        assert_eq!(counts.into_dimensionality::<ndarray::Ix1>().unwrap().to_vec(), vec![2, 2, 1, 3, 1, 1, 0, 2]);
+    }
+}
@@ -0,0 +1,52 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::ReleaseNode;
+use whitenoise_validator::utilities::get_argument;
+use crate::components::Evaluable;
+use crate::components::mean::mean;
+use ndarray::ArrayD;
+use whitenoise_validator::proto;
+
+impl Evaluable for proto::MeanAbsoluteDeviation {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        Ok(ReleaseNode::new(mean_absolute_deviation(
+            get_argument(&arguments, "data")?.array()?.f64()?,
+            get_argument(&arguments, "center")?.array()?.f64()?,
+        )?.into()))
+    }
+}
+
+/// Calculates the mean absolute deviation of each column in the provided data from its center.
+///
+/// # Arguments
+/// * `data` - Data for which you want the deviation.
+/// * `center` - One center for each column of the data.
+///
+/// # Return
+/// Mean absolute deviation of each column.
+///
+/// # Example
+/// ```
+/// use ndarray::prelude::*;
+/// use whitenoise_runtime::components::mean_absolute_deviation::mean_absolute_deviation;
+/// let data = arr2(&[ [1., 10.], [2., 20.], [6., 30.] ]).into_dyn();
+/// let center = arr2(&[[2., 20.]]).into_dyn();
+/// let deviations = mean_absolute_deviation(&data, &center).unwrap();
+/// assert!(deviations == arr2(&[[5. / 3., 20. / 3.]]).into_dyn());
+/// ```
+pub fn mean_absolute_deviation(data: &ArrayD<f64>, center: &ArrayD<f64>) -> Result<ArrayD<f64>> {
+    let center = center.iter().cloned().collect::<Vec<f64>>();
+    let mut deviations = data.clone();
+
+    // iterate over the generalized columns
+    let num_columns = deviations.gencolumns().into_iter().count();
+    if center.len() != num_columns {
+        return Err("center must have one value for each column of the data".into())
+    }
+    deviations.gencolumns_mut().into_iter().zip(center.into_iter())
+        .for_each(|(mut column, center)| column.iter_mut()
+            .for_each(|v| *v = (*v - center).abs()));
+
+    mean(&deviations)
+}
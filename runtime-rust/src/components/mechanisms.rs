@@ -1,8 +1,9 @@
 use whitenoise_validator::errors::*;
 
 use crate::NodeArguments;
-use whitenoise_validator::base::{Array, ReleaseNode};
-use whitenoise_validator::utilities::{get_argument, broadcast_privacy_usage, broadcast_ndarray, get_epsilon, get_delta};
+use whitenoise_validator::base::{Array, ReleaseNode, Value, Hashmap};
+use whitenoise_validator::utilities::{get_argument, broadcast_privacy_usage, apply_budget_fraction, broadcast_ndarray, get_epsilon, get_delta};
+use whitenoise_validator::utilities::privacy::get_calibration_epsilon;
 use crate::components::Evaluable;
 use crate::utilities;
 use whitenoise_validator::proto;
@@ -20,10 +21,12 @@ impl Evaluable for proto::LaplaceMechanism {
         let sensitivity = get_argument(&arguments, "sensitivity")?.array()?.f64()?;
 //        println!("sensitivity: {:?}", sensitivity);
 
-        let usages = broadcast_privacy_usage(&self.privacy_usage, sensitivity.len())?;
+        let usages = apply_budget_fraction(
+            broadcast_privacy_usage(&self.privacy_usage, sensitivity.len())?,
+            arguments.get("budget_fraction").cloned())?;
 
         let epsilon = ndarray::Array::from_shape_vec(
-            data.shape(), usages.iter().map(get_epsilon).collect::<Result<Vec<f64>>>()?)?;
+            data.shape(), usages.iter().map(get_calibration_epsilon).collect::<Result<Vec<f64>>>()?)?;
 //        println!("epsilon: {:?}", epsilon);
 
         data.gencolumns_mut().into_iter()
@@ -40,7 +43,9 @@ impl Evaluable for proto::LaplaceMechanism {
         Ok(ReleaseNode {
             value: data.into(),
             privacy_usages: Some(usages),
-            public: true
+            public: true,
+            public_shape: true,
+            public_metadata: true
         })
     }
 }
@@ -57,24 +62,24 @@ impl Evaluable for proto::GaussianMechanism {
         let sensitivity = get_argument(&arguments, "sensitivity")?.array()?.f64()?;
 //        println!("sensitivity: {:?}", sensitivity.shape());
 
-        let usages = broadcast_privacy_usage(&self.privacy_usage, sensitivity.len())?;
+        let usages = apply_budget_fraction(
+            broadcast_privacy_usage(&self.privacy_usage, sensitivity.len())?,
+            arguments.get("budget_fraction").cloned())?;
 
-        let epsilon = ndarray::Array::from_shape_vec(
-            data.shape(), usages.iter().map(get_epsilon).collect::<Result<Vec<f64>>>()?)?;
-//        println!("epsilon: {:?}", epsilon.shape());
-
-        let delta = ndarray::Array::from_shape_vec(
-            data.shape(), usages.iter().map(get_delta).collect::<Result<Vec<f64>>>()?)?;
-//        println!("delta: {:?}", delta.shape());
+        let usages_array = ndarray::Array::from_shape_vec(data.shape(), usages.clone())?;
+//        println!("usages: {:?}", usages_array.shape());
 
         data.gencolumns_mut().into_iter()
-            .zip(sensitivity.gencolumns().into_iter())
-            .zip(epsilon.gencolumns().into_iter().zip(delta.gencolumns().into_iter()))
-            .map(|((mut data_column, sensitivity), (epsilon, delta))| data_column.iter_mut()
-                .zip(sensitivity.iter())
-                .zip(epsilon.iter().zip(delta.iter()))
-                .map(|((v, sens), (eps, del))| {
-                    *v += utilities::mechanisms::gaussian_mechanism(&eps, &del, &sens)?;
+            .zip(sensitivity.gencolumns().into_iter().zip(usages_array.gencolumns().into_iter()))
+            .map(|(mut data_column, (sensitivity, usages))| data_column.iter_mut()
+                .zip(sensitivity.iter().zip(usages.iter()))
+                .map(|(v, (sens, usage))| {
+                    *v += match &usage.distance {
+                        // zCDP usages calibrate the noise directly to rho
+                        Some(proto::privacy_usage::Distance::Concentrated(concentrated)) =>
+                            utilities::mechanisms::gaussian_mechanism_zcdp(&concentrated.rho, &sens)?,
+                        _ => utilities::mechanisms::gaussian_mechanism(&get_epsilon(usage)?, &get_delta(usage)?, &sens)?
+                    };
                     Ok(())
                 }).collect::<Result<()>>())
             .collect::<Result<()>>()?;
@@ -82,7 +87,9 @@ impl Evaluable for proto::GaussianMechanism {
         Ok(ReleaseNode {
             value: data.into(),
             privacy_usages: Some(usages),
-            public: true
+            public: true,
+            public_shape: true,
+            public_metadata: true
         })
     }
 }
@@ -95,9 +102,11 @@ impl Evaluable for proto::SimpleGeometricMechanism {
         let sensitivity = get_argument(&arguments, "sensitivity")?.array()?.f64()?;
 //        println!("sensitivity: {:?}", sensitivity.shape());
 
-        let usages = broadcast_privacy_usage(&self.privacy_usage, sensitivity.len())?;
+        let usages = apply_budget_fraction(
+            broadcast_privacy_usage(&self.privacy_usage, sensitivity.len())?,
+            arguments.get("budget_fraction").cloned())?;
         let epsilon = ndarray::Array::from_shape_vec(
-            data.shape(), usages.iter().map(get_epsilon).collect::<Result<Vec<f64>>>()?)?;
+            data.shape(), usages.iter().map(get_calibration_epsilon).collect::<Result<Vec<f64>>>()?)?;
 //        println!("epsilon: {:?}", epsilon.shape());
 
         let lower = broadcast_ndarray(
@@ -125,7 +134,169 @@ impl Evaluable for proto::SimpleGeometricMechanism {
         Ok(ReleaseNode {
             value: data.into(),
             privacy_usages: Some(usages),
-            public: true
+            public: true,
+            public_shape: true,
+            public_metadata: true
+        })
+    }
+}
+
+impl Evaluable for proto::ReportNoisyMaxMechanism {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let counts = match get_argument(&arguments, "data")?.array()? {
+            Array::F64(data) => data.clone(),
+            Array::I64(data) => data.mapv(|v| v as f64),
+            _ => return Err("data must be numeric".into())
+        };
+
+        // the sensitivity is spread evenly over the counts
+        let sensitivity = get_argument(&arguments, "sensitivity")?.array()?.f64()?
+            .iter().sum::<f64>();
+
+        let epsilon = get_epsilon(self.privacy_usage.first()
+            .ok_or_else(|| Error::from("privacy_usage: must be defined"))?)?;
+
+        // perturb each count, and retain only the index of the largest noisy count
+        let mut index = 0;
+        let mut maximum = std::f64::NEG_INFINITY;
+        for (i, count) in counts.iter().enumerate() {
+            let noised = count + utilities::mechanisms::laplace_mechanism(&epsilon, &(2. * sensitivity))?;
+            if noised > maximum {
+                index = i;
+                maximum = noised;
+            }
+        }
+
+        let select = |len: usize| if index < len { Ok(index) } else {
+            Err(Error::from("categories: must have one category for each count"))
+        };
+        let value: Value = match get_argument(&arguments, "categories")?.array()? {
+            Array::F64(categories) => ndarray::arr1(&[categories.iter().nth(select(categories.len())?).cloned().unwrap()]).into_dyn().into(),
+            Array::I64(categories) => ndarray::arr1(&[categories.iter().nth(select(categories.len())?).cloned().unwrap()]).into_dyn().into(),
+            Array::Bool(categories) => ndarray::arr1(&[categories.iter().nth(select(categories.len())?).cloned().unwrap()]).into_dyn().into(),
+            Array::Str(categories) => ndarray::arr1(&[categories.iter().nth(select(categories.len())?).cloned().unwrap()]).into_dyn().into(),
+        };
+
+        Ok(ReleaseNode {
+            value,
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+            public_shape: true,
+            public_metadata: true
+        })
+    }
+}
+
+impl Evaluable for proto::TopKMechanism {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let counts = match get_argument(&arguments, "data")?.array()? {
+            Array::F64(data) => data.iter().cloned().collect::<Vec<f64>>(),
+            Array::I64(data) => data.iter().map(|v| *v as f64).collect::<Vec<f64>>(),
+            _ => return Err("data must be numeric".into())
+        };
+
+        // the sensitivity is spread evenly over the counts
+        let sensitivity = get_argument(&arguments, "sensitivity")?.array()?.f64()?
+            .iter().sum::<f64>();
+
+        let epsilon = get_epsilon(self.privacy_usage.first()
+            .ok_or_else(|| Error::from("privacy_usage: must be defined"))?)?;
+
+        let one_shot = match self.method.to_lowercase().as_str() {
+            "gumbel" => true,
+            "peeling" => false,
+            _ => return Err("method: must be one of Gumbel or Peeling".into())
+        };
+
+        let indices = utilities::mechanisms::top_k_mechanism(
+            &counts, self.k as usize, &epsilon, &sensitivity, one_shot)?;
+
+        let check_length = |len: usize| if counts.len() == len { Ok(()) } else {
+            Err(Error::from("categories: must have one category for each count"))
+        };
+        let categories: Value = match get_argument(&arguments, "categories")?.array()? {
+            Array::F64(categories) => { check_length(categories.len())?; ndarray::arr1(&take(categories, &indices)).into_dyn().into() },
+            Array::I64(categories) => { check_length(categories.len())?; ndarray::arr1(&take(categories, &indices)).into_dyn().into() },
+            Array::Bool(categories) => { check_length(categories.len())?; ndarray::arr1(&take(categories, &indices)).into_dyn().into() },
+            Array::Str(categories) => { check_length(categories.len())?; ndarray::arr1(&take(categories, &indices)).into_dyn().into() },
+        };
+
+        let count_usage = match self.count_privacy_usage.first() {
+            Some(count_usage) => count_usage,
+            None => return Ok(ReleaseNode {
+                value: categories,
+                privacy_usages: Some(self.privacy_usage.clone()),
+                public: true,
+                public_shape: true,
+                public_metadata: true
+            })
+        };
+
+        // the selected counts are released with a separate budget
+        let count_epsilon = get_epsilon(count_usage)?;
+        let noised = indices.iter()
+            .map(|i| Ok(counts[*i] + utilities::mechanisms::laplace_mechanism(&count_epsilon, &sensitivity)?))
+            .collect::<Result<Vec<f64>>>()?;
+
+        Ok(ReleaseNode {
+            value: Value::Hashmap(Hashmap::Str(vec![
+                ("categories".to_string(), categories),
+                ("counts".to_string(), ndarray::arr1(&noised).into_dyn().into())
+            ].into_iter().collect())),
+            privacy_usages: Some(self.privacy_usage.iter()
+                .chain(self.count_privacy_usage.iter()).cloned().collect()),
+            public: true,
+            public_shape: true,
+            public_metadata: true
         })
     }
 }
+
+impl Evaluable for proto::ObjectivePerturbationMechanism {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let data = match get_argument(&arguments, "data")?.array()? {
+            Array::F64(data) => data.clone(),
+            Array::I64(data) => data.mapv(|v| v as f64),
+            _ => return Err("data must be numeric".into())
+        };
+        let labels = match get_argument(&arguments, "target")?.array()? {
+            Array::Bool(target) => target.iter().cloned().collect::<Vec<bool>>(),
+            _ => return Err("target must be boolean".into())
+        };
+
+        let norm_bound = get_argument(&arguments, "norm_bound")?.first_f64()?;
+        let sensitivity = get_argument(&arguments, "sensitivity")?.first_f64()?;
+
+        // rows are scaled into the unit ball, with the intercept as a leading constant column
+        let features = utilities::to_nd(data, &2)?.genrows().into_iter()
+            .map(|row| if self.intercept { Some(1.) } else { None }.into_iter()
+                .chain(row.iter().cloned())
+                .map(|v| v / norm_bound)
+                .collect::<Vec<f64>>())
+            .collect::<Vec<Vec<f64>>>();
+
+        // an individual may contribute several records, so the budget is divided among them
+        let epsilon = get_epsilon(self.privacy_usage.first()
+            .ok_or_else(|| Error::from("privacy_usage: must be defined"))?)? / sensitivity;
+
+        let coefficients = utilities::mechanisms::objective_perturbation_mechanism(
+            &features, &labels, &epsilon, &self.regularization)?.into_iter()
+            // coefficients of the scaled features are rescaled to the original features
+            .map(|coefficient| coefficient / norm_bound)
+            .collect::<Vec<f64>>();
+
+        Ok(ReleaseNode {
+            value: ndarray::arr1(&coefficients).into_dyn().into(),
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+            public_shape: true,
+            public_metadata: true
+        })
+    }
+}
+
+/// Elements at the given positions of a flattened array.
+fn take<T: Clone>(array: &ndarray::ArrayD<T>, indices: &[usize]) -> Vec<T> {
+    let elements = array.iter().collect::<Vec<&T>>();
+    indices.iter().map(|i| elements[*i].clone()).collect()
+}
@@ -14,28 +14,64 @@ use whitenoise_validator::base::ReleaseNode;
 
 use whitenoise_validator::proto;
 
+pub mod annotation;
+pub mod anova_statistic;
 //pub mod bin;
+pub mod bound_contributions;
 pub mod cast;
+pub mod chi_square_statistic;
 pub mod clamp;
+pub mod confusion_matrix;
+pub mod contingency_table;
 pub mod count;
+pub mod count_distinct;
+pub mod count_true;
 pub mod covariance;
+pub mod cross_products;
+pub mod derived_metric;
 pub mod digitize;
+pub mod dp_auc;
+pub mod dp_decision_tree;
+pub mod dp_group_moments;
+pub mod dp_quantiles;
+pub mod dp_range_tree;
+pub mod dp_stability_histogram;
+pub mod dp_stochastic_gradient_descent;
+pub mod dp_synthetic_data;
+pub mod dp_welch_t_test;
+pub mod empirical_cdf;
+pub mod extreme_selection;
+pub mod expect_property;
 pub mod filter;
+pub mod gini_coefficient;
+pub mod gini_numerator;
 pub mod histogram;
 pub mod impute;
 pub mod index;
+pub mod interquartile_range;
+pub mod join_public;
+pub mod k_means;
 pub mod kth_raw_sample_moment;
+pub mod linear_regression;
 pub mod maximum;
+pub mod marginals;
 pub mod materialize;
 pub mod mean;
+pub mod mean_absolute_deviation;
 pub mod minimum;
+pub mod naive_bayes;
 pub mod partition;
+pub mod principal_components;
 pub mod quantile;
+pub mod quantile_edges;
+pub mod rebalance_partitions;
 pub mod reshape;
 pub mod mechanisms;
 pub mod resize;
+pub mod standardized_moment;
 pub mod sum;
 pub mod transforms;
+pub mod union;
 pub mod variance;
 
 /// Evaluable component trait
@@ -74,9 +110,9 @@ impl Evaluable for proto::component::Variant {
 
         evaluate!(
             // INSERT COMPONENT LIST
-            Cast, Clamp, Count, Covariance, Digitize, Filter, Histogram, Impute, Index, KthRawSampleMoment, Maximum,
-            Materialize, Mean, Minimum, Partition, Quantile, Reshape, LaplaceMechanism, GaussianMechanism,
-            SimpleGeometricMechanism, Resize, Sum, Variance,
+            Annotation, AnovaStatistic, BoundContributions, Cast, ChiSquareStatistic, Clamp, ClassMoments, ConfusionMatrix, ContingencyTable, Count, CountDistinct, CountTrue, Covariance, CrossProducts, DerivedMetric, Digitize, DpAuc, DpDecisionTree, DpGroupMoments, DpQuantiles, DpRangeTree, DpStabilityHistogram, DpStochasticGradientDescent, DpSyntheticData, DpWelchTTest, EmpiricalCdf, ExpectProperty, ExtremeSelection, Filter, GiniCoefficient, GiniNumerator, Histogram, Impute, Index, InterquartileRange, JoinPublic, KMeansCentroids, KMeansStatistics, KthRawSampleMoment, LinearRegression, Maximum,
+            Marginals, Materialize, Mean, MeanAbsoluteDeviation, Minimum, NaiveBayesModel, Partition, PrincipalComponents, Quantile, QuantileEdges, RebalancePartitions, Reshape, LaplaceMechanism, GaussianMechanism, ObjectivePerturbationMechanism, ReportNoisyMaxMechanism,
+            SimpleGeometricMechanism, TopKMechanism, Resize, StandardizedMoment, Sum, Union, Variance,
 
             Abs, Add, LogicalAnd, Divide, Equal, GreaterThan, LessThan, Log, Modulo, Multiply,
             Negate, Negative, LogicalOr, Power, RowMax, RowMin, Subtract
@@ -0,0 +1,106 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{ReleaseNode, Value, Array, Hashmap};
+use whitenoise_validator::utilities::get_argument;
+use crate::components::Evaluable;
+use whitenoise_validator::proto;
+use ndarray::{ArrayD, Array2, Axis};
+
+
+impl Evaluable for proto::ClassMoments {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let data = get_argument(&arguments, "data")?.array()?.f64()?;
+        let target = get_argument(&arguments, "target")?.array()?;
+        let categories = get_argument(&arguments, "categories")?.array()?;
+
+        let classes = match (target, categories) {
+            (Array::Bool(target), Array::Bool(categories)) => get_classes(target, categories),
+            (Array::I64(target), Array::I64(categories)) => get_classes(target, categories),
+            (Array::F64(target), Array::F64(categories)) => get_classes(target, categories),
+            (Array::Str(target), Array::Str(categories)) => get_classes(target, categories),
+            _ => return Err("target and categories must share the same atomic type".into())
+        };
+
+        Ok(ReleaseNode::new(class_moments(data, &classes, categories.num_records()?, self.power)?.into()))
+    }
+}
+
+impl Evaluable for proto::NaiveBayesModel {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let counts = match get_argument(&arguments, "counts")?.array()? {
+            Array::F64(counts) => counts.clone(),
+            Array::I64(counts) => counts.mapv(|v| v as f64),
+            _ => return Err("counts must be numeric".into())
+        };
+        let sums = get_argument(&arguments, "sums")?.array()?.f64()?;
+        let squares = get_argument(&arguments, "squares")?.array()?.f64()?;
+
+        let (priors, means, variances) = naive_bayes_model(&counts, sums, squares)?;
+
+        Ok(ReleaseNode::new(Value::Hashmap(Hashmap::Str(vec![
+            ("priors".to_string(), priors.into()),
+            ("means".to_string(), means.into()),
+            ("variances".to_string(), variances.into())
+        ].into_iter().collect()))))
+    }
+}
+
+/// Index of the category of each record, or None if the record does not match any category.
+fn get_classes<T: PartialEq>(target: &ArrayD<T>, categories: &ArrayD<T>) -> Vec<Option<usize>> {
+    target.iter()
+        .map(|value| categories.iter().position(|category| category == value))
+        .collect()
+}
+
+/// Sum each column, raised to `power`, over the records of each class.
+///
+/// # Returns
+/// Matrix with one row per class and one column per column of the data.
+pub fn class_moments(data: &ArrayD<f64>, classes: &[Option<usize>], num_classes: i64, power: u32) -> Result<ArrayD<f64>> {
+    if data.ndim() != 2 || data.len_of(Axis(0)) != classes.len() {
+        return Err("data and target must have the same number of records".into())
+    }
+    let mut moments = Array2::<f64>::zeros((num_classes as usize, data.len_of(Axis(1))));
+
+    data.outer_iter().zip(classes.iter())
+        .filter_map(|(record, class)| Some((record, (*class)?)))
+        .for_each(|(record, class)| moments.row_mut(class).iter_mut()
+            .zip(record.iter())
+            .for_each(|(moment, v)| *moment += v.powi(power as i32)));
+
+    Ok(moments.into_dyn())
+}
+
+/// Priors, means and variances of a Gaussian naive Bayes model, from the counts, sums and sums of squares of each class.
+///
+/// Counts are clipped at zero for the priors, and at one when dividing, as noisy counts may be small or negative.
+/// If every count is clipped to zero, the priors are uniform.
+pub fn naive_bayes_model(
+    counts: &ArrayD<f64>, sums: &ArrayD<f64>, squares: &ArrayD<f64>,
+) -> Result<(ArrayD<f64>, ArrayD<f64>, ArrayD<f64>)> {
+    let num_classes = sums.len_of(Axis(0));
+    let counts = counts.iter().cloned().collect::<Vec<f64>>();
+    if counts.len() != num_classes || squares.shape() != sums.shape() {
+        return Err("counts, sums and squares must have one row for each class".into())
+    }
+
+    let total = counts.iter().map(|count| count.max(0.)).sum::<f64>();
+    let priors = counts.iter()
+        .map(|count| if total > 0. { count.max(0.) / total } else { 1. / num_classes as f64 })
+        .collect::<Vec<f64>>();
+
+    let mut means = sums.to_owned();
+    let mut variances = squares.to_owned();
+    means.outer_iter_mut().zip(variances.outer_iter_mut()).zip(counts.iter())
+        .for_each(|((mut means, mut variances), count)| {
+            let count = count.max(1.);
+            means.iter_mut().zip(variances.iter_mut())
+                .for_each(|(mean, variance)| {
+                    *mean /= count;
+                    *variance = (*variance / count - mean.powi(2)).max(0.);
+                })
+        });
+
+    Ok((Array2::from_shape_vec((num_classes, 1), priors)?.into_dyn(), means, variances))
+}
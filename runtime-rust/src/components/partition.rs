@@ -15,7 +15,7 @@ use std::collections::BTreeMap;
 
 impl Evaluable for proto::Partition {
     fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
-        let data = get_argument(arguments, "data")?.array()?;
+        let data = get_argument(arguments, "data")?;
         Ok(ReleaseNode::new(match get_argument(arguments, "by") {
             Ok(_value) => return Err("partitioning by categories is not implemented".into()),
             Err(_) => {
@@ -23,25 +23,33 @@ impl Evaluable for proto::Partition {
                 let num_partitions = get_argument(arguments, "num_partitions")?
                     .array()?.first_i64()?;
 
-                match data {
-                    Array::F64(data) =>
-                        Value::Hashmap(Hashmap::<Value>::I64(partition_evenly(data, num_partitions).into_iter()
-                            .map(|(idx, data)| (idx, data.into())).collect::<BTreeMap<i64, Value>>())),
-                    Array::I64(data) =>
-                        Value::Hashmap(Hashmap::<Value>::I64(partition_evenly(data, num_partitions).into_iter()
-                            .map(|(idx, data)| (idx, data.into())).collect::<BTreeMap<i64, Value>>())),
-                    Array::Bool(data) =>
-                        Value::Hashmap(Hashmap::<Value>::I64(partition_evenly(data, num_partitions).into_iter()
-                            .map(|(idx, data)| (idx, data.into())).collect::<BTreeMap<i64, Value>>())),
-                    Array::Str(data) =>
-                        Value::Hashmap(Hashmap::<Value>::I64(partition_evenly(data, num_partitions).into_iter()
-                            .map(|(idx, data)| (idx, data.into())).collect::<BTreeMap<i64, Value>>())),
-                }
+                partition_value(data, num_partitions)?
             }
         }))
     }
 }
 
+/// Partitions data evenly, or if the data is already partitioned, partitions every cell evenly.
+fn partition_value(data: &Value, num_partitions: i64) -> Result<Value> {
+    Ok(match data {
+        Value::Hashmap(data) => Value::Hashmap(data.map_values(
+            &|cell| partition_value(cell, num_partitions))?),
+        Value::Array(Array::F64(data)) =>
+            Value::Hashmap(Hashmap::<Value>::I64(partition_evenly(data, num_partitions).into_iter()
+                .map(|(idx, data)| (idx, data.into())).collect::<BTreeMap<i64, Value>>())),
+        Value::Array(Array::I64(data)) =>
+            Value::Hashmap(Hashmap::<Value>::I64(partition_evenly(data, num_partitions).into_iter()
+                .map(|(idx, data)| (idx, data.into())).collect::<BTreeMap<i64, Value>>())),
+        Value::Array(Array::Bool(data)) =>
+            Value::Hashmap(Hashmap::<Value>::I64(partition_evenly(data, num_partitions).into_iter()
+                .map(|(idx, data)| (idx, data.into())).collect::<BTreeMap<i64, Value>>())),
+        Value::Array(Array::Str(data)) =>
+            Value::Hashmap(Hashmap::<Value>::I64(partition_evenly(data, num_partitions).into_iter()
+                .map(|(idx, data)| (idx, data.into())).collect::<BTreeMap<i64, Value>>())),
+        Value::Jagged(_) => return Err("data: jagged data may not be partitioned".into())
+    })
+}
+
 /// Partitions data evenly into num_partitions partitions
 ///
 /// The first partitions may have one more element than the latter partitions.
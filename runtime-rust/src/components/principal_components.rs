@@ -0,0 +1,27 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{ReleaseNode, Value, Hashmap};
+use whitenoise_validator::utilities::get_argument;
+use whitenoise_validator::components::principal_components::principal_components;
+use crate::components::Evaluable;
+use whitenoise_validator::proto;
+use ndarray::{arr1, Array2};
+
+
+impl Evaluable for proto::PrincipalComponents {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let upper_triangle = get_argument(&arguments, "data")?.array()?.f64()?
+            .iter().cloned().collect::<Vec<f64>>();
+
+        let (components, variances) = principal_components(&upper_triangle, self.num_components)?;
+
+        let shape = (components.len(), variances.len());
+        let components = Array2::from_shape_vec(shape, components.into_iter().flatten().collect())?;
+
+        Ok(ReleaseNode::new(Value::Hashmap(Hashmap::Str(vec![
+            ("components".to_string(), components.into_dyn().into()),
+            ("variances".to_string(), arr1(&variances).into_dyn().into())
+        ].into_iter().collect()))))
+    }
+}
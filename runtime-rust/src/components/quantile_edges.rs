@@ -0,0 +1,34 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Value, Array, Jagged, ReleaseNode};
+use whitenoise_validator::utilities::{get_argument, standardize_float_argument};
+use whitenoise_validator::components::quantile_edges::quantile_edges;
+use crate::components::Evaluable;
+use crate::utilities::{to_nd, get_num_columns};
+use whitenoise_validator::proto;
+
+
+impl Evaluable for proto::QuantileEdges {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let counts = match get_argument(&arguments, "counts")?.array()? {
+            Array::F64(counts) => counts.clone(),
+            Array::I64(counts) => counts.mapv(|v| v as f64),
+            _ => return Err("QuantileEdges: counts must be numeric".into())
+        };
+        let counts = to_nd(counts, &2)?;
+        let num_columns = get_num_columns(&counts)?;
+
+        let edges = match get_argument(&arguments, "edges")?.jagged()? {
+            Jagged::F64(edges) => standardize_float_argument(edges, &num_columns)?,
+            _ => return Err("QuantileEdges: edges must be floats".into())
+        };
+
+        let edges = counts.gencolumns().into_iter().zip(edges.iter())
+            .map(|(column, edges)| quantile_edges(
+                &column.to_vec(), edges, self.num_bins as usize).map(Some))
+            .collect::<Result<Vec<Option<Vec<f64>>>>>()?;
+
+        Ok(ReleaseNode::new(Value::Jagged(edges.into())))
+    }
+}
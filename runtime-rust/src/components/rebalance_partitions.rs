@@ -0,0 +1,78 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Array, Jagged, ReleaseNode, Value};
+use whitenoise_validator::utilities::get_argument;
+use crate::components::Evaluable;
+use crate::components::resize::{resize_float, resize_integer};
+use whitenoise_validator::proto;
+use ndarray::{ArrayD, Axis};
+use std::collections::BTreeMap;
+
+
+impl Evaluable for proto::RebalancePartitions {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let data = get_argument(&arguments, "data")?.array()?;
+        let n = get_argument(&arguments, "n")?.first_i64()?;
+        let lower = get_argument(&arguments, "lower")?.array()?;
+        let upper = get_argument(&arguments, "upper")?.array()?;
+
+        Ok(ReleaseNode::new(match (get_argument(&arguments, "by")?.array()?, get_argument(&arguments, "categories")?.jagged()?) {
+            (Array::Bool(by), Jagged::Bool(categories)) =>
+                rebalance(data, by, categories, n, lower, upper)?.into(),
+            (Array::I64(by), Jagged::I64(categories)) =>
+                rebalance(data, by, categories, n, lower, upper)?.into(),
+            (Array::Str(by), Jagged::Str(categories)) =>
+                rebalance(data, by, categories, n, lower, upper)?.into(),
+            (Array::F64(_), _) => return Err("by: float data may not be categorical".into()),
+            _ => return Err("by and categories must be homogeneously typed".into())
+        }))
+    }
+}
+
+/// Partitions data by the categories of `by`, and resizes every partition to `n` records.
+///
+/// Partitions with fewer than `n` records are padded with records imputed uniformly within the bounds,
+/// and partitions with more than `n` records are subsampled.
+/// Records whose partition is not a category are dropped.
+///
+/// # Arguments
+/// * `data` - Numeric data to be partitioned.
+/// * `by` - Single column assigning each record to a partition.
+/// * `categories` - Categories of the single column of `by`, one partition per category.
+/// * `n` - Number of records in every partition.
+/// * `lower` - Lower bound on each column of the data.
+/// * `upper` - Upper bound on each column of the data.
+///
+/// # Return
+/// Hashmap with the resized partitions.
+pub fn rebalance<T: Clone + Ord>(
+    data: &Array, by: &ArrayD<T>, categories: &[Option<Vec<T>>],
+    n: i64, lower: &Array, upper: &Array,
+) -> Result<BTreeMap<T, Value>> {
+    let categories = match categories {
+        [Some(categories)] => categories,
+        _ => return Err("categories must be defined for one column".into())
+    };
+    if by.len_of(Axis(0)) as i64 != data.num_records()? {
+        return Err("data and by must have the same number of records".into())
+    }
+
+    categories.iter()
+        .map(|category| {
+            let indices = by.iter().enumerate()
+                .filter(|(_, value)| *value == category)
+                .map(|(index, _)| index)
+                .collect::<Vec<usize>>();
+
+            Ok((category.clone(), match (data, lower, upper) {
+                (Array::F64(data), Array::F64(lower), Array::F64(upper)) => resize_float(
+                    &data.select(Axis(0), &indices), &n, &"uniform".to_string(),
+                    lower, upper, &None, &None)?.into(),
+                (Array::I64(data), Array::I64(lower), Array::I64(upper)) => resize_integer(
+                    &data.select(Axis(0), &indices), &n, lower, upper)?.into(),
+                _ => return Err("data, lower, and upper must be of a homogeneous numeric type".into())
+            }))
+        })
+        .collect()
+}
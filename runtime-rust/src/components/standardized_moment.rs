@@ -0,0 +1,38 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::ReleaseNode;
+use whitenoise_validator::utilities::get_argument;
+use whitenoise_validator::components::standardized_moment::{standardized_moment, MOMENT_NAMES};
+use crate::components::Evaluable;
+use whitenoise_validator::proto;
+use ndarray::{ArrayD, IxDyn};
+
+
+impl Evaluable for proto::StandardizedMoment {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        if self.order != 3 && self.order != 4 {
+            return Err("order: must be either 3 or 4".into())
+        }
+        let moments = MOMENT_NAMES[..self.order as usize].iter()
+            .map(|name| get_argument(&arguments, name)?.array()?.f64().map(|moment| moment.clone()))
+            .collect::<Result<Vec<ArrayD<f64>>>>()?;
+
+        let shape = moments[0].shape().to_vec();
+        if moments.iter().any(|moment| moment.shape() != shape.as_slice()) {
+            return Err("every moment must have the same shape".into())
+        }
+        let moments = moments.iter()
+            .map(|moment| moment.iter().cloned().collect())
+            .collect::<Vec<Vec<f64>>>();
+
+        // the raw moments of each column are combined independently
+        let statistics = (0..moments[0].len())
+            .map(|index| standardized_moment(self.order, &moments.iter()
+                .map(|moment| moment[index])
+                .collect::<Vec<f64>>()))
+            .collect::<Result<Vec<f64>>>()?;
+
+        Ok(ReleaseNode::new(ArrayD::from_shape_vec(IxDyn(&shape), statistics)?.into()))
+    }
+}
@@ -0,0 +1,85 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Array, ReleaseNode, Value, Hashmap};
+use whitenoise_validator::utilities::get_argument;
+use whitenoise_validator::utilities::array::slow_stack;
+use crate::components::Evaluable;
+use whitenoise_validator::proto;
+use ndarray::{ArrayD, ArrayViewD, Axis};
+
+
+impl Evaluable for proto::Union {
+    fn evaluate(&self, arguments: &NodeArguments) -> Result<ReleaseNode> {
+        let data = get_argument(arguments, "data")?.hashmap()?;
+        Ok(ReleaseNode::new(union_cells(&data.values(), self.flatten)?))
+    }
+}
+
+/// Stacks the records of the cells of a partition, in order of their keys.
+///
+/// Cells that are themselves partitions are unioned key-wise, removing only the outermost level,
+/// unless `flatten` is set, in which case every level is removed.
+///
+/// # Arguments
+/// * `cells` - Cells of a partition, all arrays, or all partitions with the same keys.
+/// * `flatten` - Whether to remove every level of nested partitions.
+///
+/// # Return
+/// The union of the cells.
+///
+/// # Example
+/// ```
+/// use ndarray::arr1;
+/// use whitenoise_validator::base::Value;
+/// use whitenoise_runtime::components::union::union_cells;
+///
+/// let cells: Vec<Value> = vec![arr1(&[1, 2]).into_dyn().into(), arr1(&[3]).into_dyn().into()];
+/// let unioned = union_cells(&cells.iter().collect::<Vec<&Value>>(), false).unwrap();
+/// assert_eq!(unioned.array().unwrap().i64().unwrap(), &arr1(&[1, 2, 3]).into_dyn());
+/// ```
+pub fn union_cells(cells: &[&Value], flatten: bool) -> Result<Value> {
+    match cells.first() {
+        Some(Value::Hashmap(first)) => {
+            let partitions = cells.iter()
+                .map(|cell| cell.hashmap())
+                .collect::<Result<Vec<&Hashmap<Value>>>>()?;
+            if partitions.iter().any(|partition| !first.has_same_keys(partition)) {
+                return Err("partitions must have the same keys".into())
+            }
+
+            if flatten {
+                return union_cells(&partitions.iter()
+                    .flat_map(|partition| partition.values())
+                    .collect::<Vec<&Value>>(), true)
+            }
+
+            let inner_cells = (0..first.keys_length() as usize)
+                .map(|index| union_cells(&partitions.iter()
+                    .map(|partition| partition.values()[index])
+                    .collect::<Vec<&Value>>(), false))
+                .collect::<Result<Vec<Value>>>()?;
+            Ok(Value::Hashmap(first.from_values(inner_cells)))
+        },
+        Some(Value::Array(first)) => {
+            let arrays = cells.iter()
+                .map(|cell| cell.array())
+                .collect::<Result<Vec<&Array>>>()?;
+            Ok(match first {
+                Array::F64(_) => stack(&arrays.iter().map(|array| array.f64()).collect::<Result<Vec<_>>>()?)?.into(),
+                Array::I64(_) => stack(&arrays.iter().map(|array| array.i64()).collect::<Result<Vec<_>>>()?)?.into(),
+                Array::Bool(_) => stack(&arrays.iter().map(|array| array.bool()).collect::<Result<Vec<_>>>()?)?.into(),
+                Array::Str(_) => slow_stack(Axis(0), &arrays.iter()
+                    .map(|array| array.string().map(|array| array.view()))
+                    .collect::<Result<Vec<ArrayViewD<String>>>>()?)?.into(),
+            })
+        },
+        Some(_) => Err("cells of the partition must be arrays or partitions".into()),
+        None => Err("partition must have at least one cell".into())
+    }
+}
+
+fn stack<T: Copy>(arrays: &[&ArrayD<T>]) -> Result<ArrayD<T>> {
+    Ok(ndarray::stack(Axis(0), &arrays.iter()
+        .map(|array| array.view()).collect::<Vec<ArrayViewD<T>>>())?)
+}
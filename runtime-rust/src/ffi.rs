@@ -31,13 +31,17 @@ pub extern "C" fn release(
     let response = proto::ResponseRelease {
         value: match proto::RequestRelease::decode(request_buffer) {
             Ok(request) => match super::release(&request) {
-                Ok((release, warnings)) => Some(proto::response_release::Value::Data(proto::response_release::Success {
-                    release: Some(release),
-                    warnings: match request.stack_trace {
-                        true => warnings,
-                        false => Vec::new()
-                    }
-                })),
+                Ok(release) => {
+                    let ((release, privacy_filter), warnings) = release.into_parts();
+                    Some(proto::response_release::Value::Data(proto::response_release::Success {
+                        release: Some(release),
+                        privacy_filter,
+                        warnings: match request.stack_trace {
+                            true => warnings,
+                            false => Vec::new()
+                        }
+                    }))
+                },
                 Err(err) => match request.stack_trace {
                     true =>
                         Some(proto::response_release::Value::Error(serialize_error(err))),
@@ -14,10 +14,12 @@ use whitenoise_validator::errors::*;
 pub mod utilities;
 pub mod components;
 pub mod ffi;
+#[cfg(feature = "budget-observer")]
+pub mod observer;
 
 extern crate libc;
 
-use whitenoise_validator::utilities::{serial, get_input_properties, get_sinks};
+use whitenoise_validator::utilities::{serial, get_input_properties, get_sinks, is_privatizing, check_contribution_bound, privacy_usage_reducer};
 
 use crate::components::*;
 
@@ -26,25 +28,44 @@ use std::vec::Vec;
 
 use itertools::Itertools;
 
-use whitenoise_validator::base::{Value, ReleaseNode};
+use whitenoise_validator::base::{Value, ReleaseNode, Warnable};
 use whitenoise_validator::utilities::serial::{parse_release, serialize_release_node};
 use std::iter::FromIterator;
 use whitenoise_validator::ffi::serialize_error;
+use whitenoise_validator::utilities::{gate, filter, optimize};
 
 pub type NodeArguments<'a> = HashMap<String, &'a Value>;
 
 /// Evaluate an analysis and release the differentially private results.
+///
+/// If a `release_key` is supplied, encrypted values in the release are decrypted before execution,
+/// and private values in the returned release are encrypted, so that they are never persisted in plaintext.
+///
+/// If a `privacy_filter` is supplied, the filter is returned with the usages realized by this release recorded on its odometer.
 pub fn release(
     request: &proto::RequestRelease
-) -> Result<(proto::Release, Vec<proto::Error>)> {
-    execute_graph(
+) -> Result<Warnable<(proto::Release, Option<proto::PrivacyFilter>)>> {
+    let release = request.release.as_ref()
+        .ok_or_else(|| Error::from("release must be defined"))?;
+    let encrypted = !request.release_key.is_empty();
+    let mut privacy_filter = request.privacy_filter.clone();
+
+    let (release, warnings) = execute_graph(
         request.analysis.as_ref()
             .ok_or_else(|| Error::from("analysis must be defined"))?,
-        request.release.as_ref()
-            .ok_or_else(|| Error::from("release must be defined"))?,
+        &match encrypted {
+            true => utilities::encryption::decrypt_release(release, &request.release_key)?,
+            false => release.clone()
+        },
         &proto::FilterLevel::from_i32(request.filter_level)
-            .ok_or_else(|| Error::from(format!("unrecognized filter level {:?}", request.filter_level)))?)
-
+            .ok_or_else(|| Error::from(format!("unrecognized filter level {:?}", request.filter_level)))?,
+        request.release_gate.as_ref(),
+        privacy_filter.as_mut())?.into_parts();
+
+    Ok(Warnable((match encrypted {
+        true => utilities::encryption::encrypt_release(&release, &request.release_key)?,
+        false => release
+    }, privacy_filter), warnings))
 }
 
 /// Given a description of computation, and some computed values, execute the computation and return computed values
@@ -56,26 +77,49 @@ pub fn release(
 /// When a component is executed, the output of the node is stored in the release
 /// When the graph completes execution, the release is filtered and returned
 ///
+/// If the privacy definition enforces its budget, the usage spent so far is tracked,
+/// and mechanisms that would exceed the epsilon or delta cap fail to expand.
+///
 /// # Arguments
 /// * `analysis` - a computational graph and definition of privacy, in prost protobuf format
 /// * `release` - a collection of precomputed values for components in the graph
 /// * `filter_level` - configure the amount of information included in the return
+/// * `release_gate` - optional release gate. If approval is required, mechanisms are only evaluated for approved analyses
+/// * `privacy_filter` - optional privacy filter. Mechanisms are only evaluated while the filter admits them, and their realized usages are recorded on its odometer
 ///
 /// # Return
-/// a collection of computed values for components in the graph
+/// a collection of computed values for components in the graph, alongside warnings for any components that failed to evaluate
 pub fn execute_graph(
     analysis: &proto::Analysis,
     release: &proto::Release,
-    filter_level: &proto::FilterLevel
-) -> Result<(proto::Release, Vec<proto::Error>)> {
+    filter_level: &proto::FilterLevel,
+    release_gate: Option<&proto::ReleaseGate>,
+    mut privacy_filter: Option<&mut proto::PrivacyFilter>,
+) -> Result<Warnable<proto::Release>> {
+
+    // an approval token that is present must be valid before anything is evaluated.
+    // Without a token, the mechanisms fail to expand if the gate requires approval
+    if let Some(release_gate) = release_gate {
+        if !analysis.approval_token.is_empty() {
+            gate::check_approval(analysis, release_gate)?;
+        }
+    }
 
     // stack for storing which nodes to evaluate next
     let computation_graph = analysis.computation_graph.to_owned()
         .ok_or_else(|| Error::from("computation_graph must be defined to execute an analysis"))?;
     let mut graph: HashMap<u32, proto::Component> = computation_graph.value;
 
+    // nodes added by expansions are derived from the nodes of the analysis, so only the nodes of the analysis are gated
+    let analysis_ids: HashSet<u32> = graph.keys().cloned().collect();
+
+    // apply the passes the validator accounted under, so that exactly the accounted components are released.
+    // Warnings from the passes are already reported by the validator when deriving properties
+    optimize::optimize_graph(&mut graph, release, analysis.optimizations.as_ref());
+
     // core state for the graph execution algorithm
-    let mut traversal: Vec<u32> = get_sinks(&graph).into_iter().collect();
+    // the traversal is popped from the end, so sinks are evaluated in ascending order of id
+    let mut traversal: Vec<u32> = get_sinks(&graph).into_iter().sorted().rev().collect();
 
     // derive properties for any private nodes in the release
     // TODO: reduce clones by removing refs from lib signatures
@@ -89,6 +133,12 @@ pub fn execute_graph(
     })?;
 
     let mut release = serial::parse_release(release)?;
+
+    // usage already spent by released mechanisms, against which the budget of the privacy definition is enforced
+    let mut spent_usage = release.values()
+        .filter_map(|release_node| release_node.privacy_usages.clone())
+        .flatten()
+        .fold1(|usage_1, usage_2| privacy_usage_reducer(&usage_1, &usage_2, &|l, r| l + r));
     let mut maximum_id = graph.keys()
         .fold1(std::cmp::max)
         .map(|x| x.clone())
@@ -121,7 +171,7 @@ pub fn execute_graph(
 
         // check if any dependencies of the current node remain unevaluated
         let mut evaluable = true;
-        for source_node_id in component.arguments.values() {
+        for source_node_id in component.arguments.values().sorted() {
             if !release.contains_key(&source_node_id) {
                 evaluable = false;
                 traversal.push(*source_node_id);
@@ -151,9 +201,18 @@ pub fn execute_graph(
             properties: node_properties,
             arguments: public_arguments,
             component_id,
-            maximum_id
+            maximum_id,
+            release_gate: release_gate.filter(|_| analysis_ids.contains(&component_id)).cloned(),
+            // the validator verifies the approval token itself, so the analysis is only needed under a gate
+            analysis: release_gate.map(|_| analysis.clone()),
+            privacy_filter: privacy_filter.as_deref().cloned(),
+            spent_usage: spent_usage.clone()
         }) {
-            Ok(expansion) => expansion,
+            Ok(expansion) => {
+                let (expansion, expansion_warnings) = expansion.into_parts();
+                warnings.extend(expansion_warnings);
+                expansion
+            },
             // TODO: propagate errors back
             Err(err) => {
                 warnings.push(serialize_error(err));
@@ -192,9 +251,19 @@ pub fn execute_graph(
         // no nodes were added to the traversal. Begin node execution
         traversal.pop();
 
+        // transforms over public arguments are folded into the release by the validator
+        if release.contains_key(&component_id) {
+            continue;
+        }
+
         // the expansion may have overwritten the current component
         let component = graph.get(&component_id).unwrap();
 
+        // refuse to privatize data whose per-individual contributions are unbounded
+        if is_privatizing(component) {
+            check_contribution_bound(&graph, &component_id)?;
+        }
+
         // collect arguments by string name to the component that will be executed
         let node_arguments = component.arguments.iter()
             .map(|(name, node_id)| (name.clone(), &release.get(node_id).unwrap().value))
@@ -205,14 +274,38 @@ pub fn execute_graph(
             .ok_or_else(|| Error::from("variant of component must be known"))?
             .evaluate(&node_arguments)?;
 
-        evaluation.public = match graph_properties.get(&component_id) {
+        let (public, public_shape, public_metadata) = match graph_properties.get(&component_id) {
             Some(property) => match property.variant.clone().unwrap() {
-                proto::value_properties::Variant::Array(v) => v.releasable,
-                proto::value_properties::Variant::Jagged(v) => v.releasable,
-                proto::value_properties::Variant::Hashmap(_) => false
+                proto::value_properties::Variant::Array(v) => (v.releasable, v.public_shape, v.public_metadata),
+                proto::value_properties::Variant::Jagged(v) => (v.releasable, false, false),
+                proto::value_properties::Variant::Hashmap(v) => (v.releasable, false, false),
+                proto::value_properties::Variant::Scalar(v) => (v.releasable, true, v.public_metadata)
             },
-            None => false
+            None => (false, false, false)
         };
+        // public values imply a public shape and public metadata
+        evaluation.public = public;
+        evaluation.public_shape = public || public_shape;
+        evaluation.public_metadata = public || public_metadata;
+
+        #[cfg(feature = "budget-observer")]
+        {
+            if let Some(privacy_usages) = &evaluation.privacy_usages {
+                observer::notify(&graph, &component_id, privacy_usages);
+            }
+        }
+
+        // the usage of this mechanism may inform which mechanisms run next
+        if let Some(usage) = evaluation.privacy_usages.iter().flatten().cloned()
+            .fold1(|usage_1, usage_2| privacy_usage_reducer(&usage_1, &usage_2, &|l, r| l + r)) {
+            if let Some(privacy_filter) = privacy_filter.as_mut() {
+                filter::record_usage(privacy_filter, usage.clone());
+            }
+            spent_usage = Some(match spent_usage {
+                Some(spent_usage) => privacy_usage_reducer(&spent_usage, &usage, &|l, r| l + r),
+                None => usage
+            });
+        }
 
         // store the evaluated `Value` enum in the release
         release.insert(component_id, evaluation);
@@ -260,5 +353,5 @@ pub fn execute_graph(
         proto::FilterLevel::All => release,
     })?;
 
-    Ok((release, warnings))
+    Ok(Warnable(release, warnings))
 }
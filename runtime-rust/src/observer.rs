@@ -0,0 +1,195 @@
+//! Hooks for observing privacy budget consumption as an analysis executes.
+//!
+//! Observers are notified each time a node releases a value with a privacy usage,
+//! so that budget burn-down may be monitored in real time rather than from the final report.
+//! Observers are registered per thread, as the runtime executes an analysis on the calling thread.
+
+use whitenoise_validator::proto;
+use whitenoise_validator::utilities::privacy_usage_reducer;
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use itertools::Itertools;
+
+/// A private data source in the lineage of a node.
+#[derive(Debug, Clone)]
+pub struct DataSource {
+    /// id of the Materialize node
+    pub node_id: u32,
+    /// file path or url of the data, if known
+    pub path: Option<String>,
+}
+
+/// Privacy usage attributed to a single node, and the data sources it was drawn from.
+#[derive(Debug, Clone)]
+pub struct BudgetEvent {
+    pub node_id: u32,
+    /// name of the component variant that consumed the budget
+    pub component: String,
+    pub data_sources: Vec<DataSource>,
+    /// privacy usage summed over the columns of the release
+    pub privacy_usage: proto::PrivacyUsage,
+}
+
+/// Receives a notification each time budget is consumed.
+pub trait BudgetObserver {
+    fn observe(&self, event: &BudgetEvent);
+}
+
+thread_local! {
+    static OBSERVERS: RefCell<Vec<Rc<dyn BudgetObserver>>> = RefCell::new(Vec::new());
+}
+
+/// Notify the observer of all budget consumed on the current thread, until cleared.
+pub fn register_observer(observer: Rc<dyn BudgetObserver>) {
+    OBSERVERS.with(|observers| observers.borrow_mut().push(observer));
+}
+
+/// Remove all observers registered on the current thread.
+pub fn clear_observers() {
+    OBSERVERS.with(|observers| observers.borrow_mut().clear());
+}
+
+/// Attribute the privacy usages of a release to a node, and notify all observers.
+pub(crate) fn notify(
+    graph: &HashMap<u32, proto::Component>,
+    node_id: &u32,
+    privacy_usages: &[proto::PrivacyUsage],
+) {
+    let privacy_usage = match privacy_usages.iter().cloned()
+        .fold1(|l, r| privacy_usage_reducer(&l, &r, &|l, r| l + r)) {
+        Some(privacy_usage) => privacy_usage,
+        None => return
+    };
+
+    let component = graph.get(node_id)
+        .and_then(|component| component.variant.as_ref())
+        // the debug representation of a variant is prefixed by its name
+        .map(|variant| format!("{:?}", variant).split('(').next().unwrap_or("").to_string())
+        .unwrap_or_default();
+
+    let event = BudgetEvent {
+        node_id: *node_id,
+        component,
+        data_sources: get_data_sources(graph, node_id),
+        privacy_usage,
+    };
+
+    OBSERVERS.with(|observers| observers.borrow().iter()
+        .for_each(|observer| observer.observe(&event)));
+}
+
+/// Private Materialize nodes in the lineage of a node.
+fn get_data_sources(
+    graph: &HashMap<u32, proto::Component>,
+    node_id: &u32,
+) -> Vec<DataSource> {
+    let mut sources = Vec::new();
+    let mut visited = HashSet::new();
+    let mut traversal = vec![*node_id];
+
+    while let Some(node_id) = traversal.pop() {
+        if !visited.insert(node_id) {
+            continue
+        }
+        let component = match graph.get(&node_id) {
+            Some(component) => component,
+            None => continue
+        };
+        match &component.variant {
+            Some(proto::component::Variant::Materialize(materialize)) => if !materialize.public {
+                use proto::data_source::Value as Source;
+                sources.push(DataSource {
+                    node_id,
+                    path: materialize.data_source.as_ref()
+                        .and_then(|data_source| match &data_source.value {
+                            Some(Source::FilePath(path)) | Some(Source::UrlPath(path)) => Some(path.clone()),
+                            _ => None
+                        })
+                })
+            },
+            _ => traversal.extend(component.arguments.values())
+        }
+    }
+    sources.sort_by_key(|source| source.node_id);
+    sources
+}
+
+/// Export each budget event as an OpenTelemetry span named `privacy_usage`.
+#[cfg(feature = "opentelemetry-exporter")]
+pub struct OpenTelemetryObserver {
+    pub tracer_name: &'static str,
+}
+
+#[cfg(feature = "opentelemetry-exporter")]
+impl Default for OpenTelemetryObserver {
+    fn default() -> Self {
+        OpenTelemetryObserver { tracer_name: "whitenoise" }
+    }
+}
+
+#[cfg(feature = "opentelemetry-exporter")]
+impl BudgetObserver for OpenTelemetryObserver {
+    fn observe(&self, event: &BudgetEvent) {
+        use opentelemetry::api::{KeyValue, Span, Tracer};
+        use proto::privacy_usage::Distance;
+
+        let (epsilon, delta) = match &event.privacy_usage.distance {
+            Some(Distance::Pure(distance)) => (distance.epsilon, 0.),
+            Some(Distance::Approximate(distance)) => (distance.epsilon, distance.delta),
+            // spans are annotated with epsilon and delta, which a zCDP usage does not define
+            Some(Distance::Concentrated(_)) | None => return
+        };
+
+        let mut attributes = vec![
+            KeyValue::new("node_id", event.node_id as i64),
+            KeyValue::new("component", event.component.clone()),
+            KeyValue::new("epsilon", epsilon),
+            KeyValue::new("delta", delta),
+        ];
+        attributes.extend(event.data_sources.iter().map(|source| KeyValue::new(
+            format!("data_source.{}", source.node_id),
+            source.path.clone().unwrap_or_default())));
+
+        let span = opentelemetry::global::tracer(self.tracer_name).start("privacy_usage");
+        span.add_event("budget consumed".to_string(), attributes);
+        span.end();
+    }
+}
+
+
+#[cfg(test)]
+mod test_observer {
+    use crate::observer::{BudgetObserver, BudgetEvent, register_observer, clear_observers, notify};
+    use whitenoise_validator::proto;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    struct Recorder(RefCell<Vec<u32>>);
+
+    impl BudgetObserver for Recorder {
+        fn observe(&self, event: &BudgetEvent) {
+            self.0.borrow_mut().push(event.node_id)
+        }
+    }
+
+    #[test]
+    fn test_notify() {
+        let recorder = Rc::new(Recorder(RefCell::new(Vec::new())));
+        register_observer(recorder.clone());
+
+        let usage = proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Pure(proto::privacy_usage::DistancePure { epsilon: 0.5 }))
+        };
+        notify(&HashMap::new(), &3, &[usage]);
+        // releases without privacy usage are not attributed
+        notify(&HashMap::new(), &4, &[]);
+        clear_observers();
+        notify(&HashMap::new(), &5, &[]);
+
+        assert_eq!(*recorder.0.borrow(), vec![3]);
+    }
+}
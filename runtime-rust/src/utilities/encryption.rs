@@ -0,0 +1,149 @@
+//! At-rest encryption of the private values in a release
+//!
+//! Private release nodes, such as the omitted intermediates of an interactive session, are encrypted with AES-256-GCM.
+//! The node id is authenticated with each value, so that encrypted values may not be exchanged between nodes.
+
+use whitenoise_validator::errors::*;
+use whitenoise_validator::proto;
+
+use openssl::rand::rand_bytes;
+use openssl::symm::{Cipher, encrypt_aead, decrypt_aead};
+use prost::Message;
+
+/// Length of the key, in bytes.
+pub const KEY_LENGTH: usize = 32;
+
+const NONCE_LENGTH: usize = 12;
+const TAG_LENGTH: usize = 16;
+
+fn check_key(key: &[u8]) -> Result<()> {
+    if key.len() != KEY_LENGTH {
+        return Err(format!("release_key: must be {} bytes, but is {} bytes", KEY_LENGTH, key.len()).into())
+    }
+    Ok(())
+}
+
+/// Encrypt the value of every private node in a release.
+///
+/// Public nodes, and nodes that are already encrypted, are left unchanged.
+///
+/// # Arguments
+/// * `release` - release to encrypt
+/// * `key` - 256-bit key, supplied by the caller
+///
+/// # Return
+/// The release, where the value of each private node is replaced by its encrypted value
+pub fn encrypt_release(release: &proto::Release, key: &[u8]) -> Result<proto::Release> {
+    check_key(key)?;
+    Ok(proto::Release {
+        values: release.values.iter()
+            .map(|(node_id, release_node)| Ok((*node_id, match (&release_node.value, release_node.public) {
+                (Some(value), false) => proto::ReleaseNode {
+                    value: None,
+                    encrypted_value: Some(encrypt_value(value, node_id, key)?),
+                    ..release_node.clone()
+                },
+                _ => release_node.clone()
+            })))
+            .collect::<Result<_>>()?
+    })
+}
+
+/// Decrypt the value of every encrypted node in a release.
+///
+/// Decryption fails if the key is incorrect, or if any encrypted value was modified or moved to another node.
+///
+/// # Arguments
+/// * `release` - release to decrypt
+/// * `key` - the 256-bit key the release was encrypted with
+///
+/// # Return
+/// The release, where every encrypted value is replaced by its plaintext value
+pub fn decrypt_release(release: &proto::Release, key: &[u8]) -> Result<proto::Release> {
+    check_key(key)?;
+    Ok(proto::Release {
+        values: release.values.iter()
+            .map(|(node_id, release_node)| Ok((*node_id, match &release_node.encrypted_value {
+                Some(encrypted) => proto::ReleaseNode {
+                    value: Some(decrypt_value(encrypted, node_id, key)?),
+                    encrypted_value: None,
+                    ..release_node.clone()
+                },
+                None => release_node.clone()
+            })))
+            .collect::<Result<_>>()?
+    })
+}
+
+fn encrypt_value(value: &proto::Value, node_id: &u32, key: &[u8]) -> Result<proto::EncryptedValue> {
+    let mut plaintext = Vec::new();
+    value.encode(&mut plaintext)
+        .map_err(|_| Error::from("unable to encode release value"))?;
+
+    // a nonce must never be reused with the same key, so each value is encrypted under a fresh random nonce
+    let mut nonce = vec![0u8; NONCE_LENGTH];
+    rand_bytes(&mut nonce)
+        .map_err(|_| Error::from("unable to sample a nonce"))?;
+
+    let mut tag = vec![0u8; TAG_LENGTH];
+    let ciphertext = encrypt_aead(
+        Cipher::aes_256_gcm(), key, Some(&nonce), &node_id.to_le_bytes(), &plaintext, &mut tag)
+        .map_err(|_| Error::from(format!("node_id {}: unable to encrypt release value", node_id)))?;
+
+    Ok(proto::EncryptedValue { nonce, ciphertext, tag })
+}
+
+fn decrypt_value(encrypted: &proto::EncryptedValue, node_id: &u32, key: &[u8]) -> Result<proto::Value> {
+    if encrypted.nonce.len() != NONCE_LENGTH || encrypted.tag.len() != TAG_LENGTH {
+        return Err(format!("node_id {}: encrypted value is malformed", node_id).into())
+    }
+    let plaintext = decrypt_aead(
+        Cipher::aes_256_gcm(), key, Some(&encrypted.nonce), &node_id.to_le_bytes(), &encrypted.ciphertext, &encrypted.tag)
+        .map_err(|_| Error::from(format!("node_id {}: unable to decrypt release value. The key may be incorrect", node_id)))?;
+
+    proto::Value::decode(plaintext)
+        .map_err(|_| Error::from(format!("node_id {}: decrypted release value is malformed", node_id)))
+}
+
+
+#[cfg(test)]
+mod test_encryption {
+    use crate::utilities::encryption::{encrypt_release, decrypt_release, KEY_LENGTH};
+    use whitenoise_validator::base::Value;
+    use whitenoise_validator::proto;
+    use whitenoise_validator::utilities::serial::serialize_value;
+    use std::collections::HashMap;
+
+    fn release_node(value: f64, public: bool) -> proto::ReleaseNode {
+        proto::ReleaseNode {
+            value: Some(serialize_value(&Value::from(value)).unwrap()),
+            privacy_usages: None,
+            public,
+            public_shape: public,
+            public_metadata: public,
+            encrypted_value: None,
+        }
+    }
+
+    #[test]
+    fn test_encrypt_release() {
+        let key = vec![7u8; KEY_LENGTH];
+        let mut values = HashMap::new();
+        values.insert(1, release_node(1., false));
+        values.insert(2, release_node(2., true));
+        let release = proto::Release { values };
+
+        let encrypted = encrypt_release(&release, &key).unwrap();
+        assert!(encrypted.values[&1].value.is_none() && encrypted.values[&1].encrypted_value.is_some());
+        assert_eq!(encrypted.values[&2], release.values[&2]);
+        assert_eq!(decrypt_release(&encrypted, &key).unwrap(), release);
+
+        // the wrong key, or a value moved to another node, fails to decrypt
+        assert!(decrypt_release(&encrypted, &[8u8; KEY_LENGTH]).is_err());
+        let mut moved = encrypted.clone();
+        let node = moved.values.remove(&1).unwrap();
+        moved.values.insert(3, node);
+        assert!(decrypt_release(&moved, &key).is_err());
+        assert!(encrypt_release(&release, &key[1..]).is_err());
+    }
+}
@@ -7,6 +7,7 @@ use rug::{float::Constant, Float, ops::Pow};
 
 use crate::utilities::noise;
 use crate::utilities;
+use whitenoise_validator::components::linear_regression::solve;
 
 /// Returns noise drawn according to the Laplace mechanism
 ///
@@ -76,6 +77,34 @@ pub fn gaussian_mechanism(epsilon: &f64, delta: &f64, sensitivity: &f64) -> Resu
     Ok(noise)
 }
 
+/// Returns noise drawn according to the Gaussian mechanism, calibrated to zero-concentrated DP.
+///
+/// Noise is drawn from a Gaussian distribution with scale sensitivity/sqrt(2*rho) and centered about 0.
+/// The mechanism satisfies rho-zCDP, as in
+/// [Bun & Steinke (2016)](https://arxiv.org/pdf/1605.02065.pdf), Proposition 1.6.
+///
+/// # Arguments
+///
+/// * `rho` - Zero-concentrated privacy loss parameter.
+/// * `sensitivity` - Upper bound on the L2 sensitivity of the function you want to privatize.
+///
+/// # Return
+/// A draw from Gaussian distribution with scale defined as above.
+///
+/// # Examples
+/// ```
+/// use whitenoise_runtime::utilities::mechanisms::gaussian_mechanism_zcdp;
+/// let n = gaussian_mechanism_zcdp(&0.05, &2.0);
+/// ```
+pub fn gaussian_mechanism_zcdp(rho: &f64, sensitivity: &f64) -> Result<f64> {
+    if rho <= &0. || sensitivity < &0. {
+        return Err(format!("rho ({}) and sensitivity ({}) must both be positive", rho, sensitivity).into());
+    }
+    let scale: f64 = sensitivity / (2. * rho).sqrt();
+    let noise: f64 = noise::sample_gaussian(&0., &scale);
+    Ok(noise)
+}
+
 /// Returns noise drawn according to the Geometric mechanism.
 ///
 /// Uses the Geometric mechanism as originally proposed in
@@ -161,4 +190,199 @@ pub fn exponential_mechanism<T>(
     let elem: T = utilities::sample_from_set(&candidate_vec, &probability_vec)?;
 
     Ok(elem)
-}
\ No newline at end of file
+}
+
+/// Returns the indices of `k` candidates with large counts, privately selected, in order of selection.
+///
+/// When `one_shot` is false, the candidates are peeled off one at a time with the exponential mechanism,
+/// where each selection uses `epsilon / k` and the utility of a candidate is its count.
+/// When `one_shot` is true, Gumbel noise of scale `2 * k * sensitivity / epsilon` is added to every count once,
+/// and the candidates with the `k` largest noisy counts are selected.
+/// The two are equal in distribution, see
+/// [Durfee and Rogers (2019)](https://arxiv.org/abs/1905.04273).
+///
+/// # Arguments
+/// * `counts` - Count of each candidate.
+/// * `k` - Number of candidates to select.
+/// * `epsilon` - Privacy loss parameter, shared by all `k` selections.
+/// * `sensitivity` - Upper bound on the L1 sensitivity of the counts.
+/// * `one_shot` - Whether to select via Gumbel noise, rather than by peeling.
+///
+/// # Return
+/// Indices of the selected candidates.
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::utilities::mechanisms::top_k_mechanism;
+/// let selected = top_k_mechanism(&[10., 200., 30., 400.], 2, &1., &1., true).unwrap();
+/// assert_eq!(selected.len(), 2);
+/// ```
+pub fn top_k_mechanism(
+    counts: &[f64], k: usize, epsilon: &f64, sensitivity: &f64, one_shot: bool,
+) -> Result<Vec<usize>> {
+    if k == 0 || k > counts.len() {
+        return Err("k must be positive, and no greater than the number of candidates".into())
+    }
+    if epsilon <= &0. {
+        return Err("epsilon must be positive".into())
+    }
+    let epsilon_round = epsilon / k as f64;
+
+    if one_shot {
+        let scale = 2. * sensitivity / epsilon_round;
+        let noised = counts.iter()
+            .map(|count| count + noise::sample_gumbel(0., scale))
+            .collect::<Vec<f64>>();
+        let mut indices = (0..counts.len()).collect::<Vec<usize>>();
+        indices.sort_by(|l, r| noised[*r].partial_cmp(&noised[*l]).unwrap_or(std::cmp::Ordering::Equal));
+        indices.truncate(k);
+        return Ok(indices)
+    }
+
+    let mut remaining = (0..counts.len()).collect::<Vec<usize>>();
+    let mut selected = Vec::with_capacity(k);
+    for _ in 0..k {
+        let index = exponential_mechanism(
+            &epsilon_round, sensitivity,
+            arr1(&remaining).into_dyn(),
+            &|index: &usize| counts[*index])?;
+        remaining.retain(|candidate| *candidate != index);
+        selected.push(index);
+    }
+    Ok(selected)
+}
+
+/// Returns the coefficients of an L2-regularized logistic regression, privatized by objective perturbation.
+///
+/// Implements Algorithm 2 of [Chaudhuri, Monteleoni and Sarwate (2011)](http://www.jmlr.org/papers/volume12/chaudhuri11a/chaudhuri11a.pdf).
+/// The coefficients minimize `(1/n) sum_i log(1 + exp(-y_i w^T x_i)) + (1/n) b^T w + ((lambda + Delta) / 2) ||w||^2`,
+/// where the perturbation `b` has density proportional to `exp(-epsilon' ||b|| / 2)`.
+/// The budget `epsilon'` is what remains of `epsilon` after correcting for the curvature of the loss.
+/// If nothing remains, the extra regularization `Delta` is added, and half of `epsilon` is spent on the perturbation.
+///
+/// # Arguments
+/// * `features` - Rows of features. Rows outside of the unit ball are projected onto it.
+/// * `labels` - Label of each row.
+/// * `epsilon` - Privacy loss parameter.
+/// * `regularization` - Strength of the L2 penalty, `lambda`.
+///
+/// # Return
+/// Coefficient of each feature.
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::utilities::mechanisms::objective_perturbation_mechanism;
+/// let features = vec![vec![0.5, 0.5], vec![0.5, -0.5], vec![0.5, 0.2], vec![0.5, -0.1]];
+/// let labels = vec![true, false, true, false];
+/// let coefficients = objective_perturbation_mechanism(&features, &labels, &1., &0.1).unwrap();
+/// assert_eq!(coefficients.len(), 2);
+/// ```
+pub fn objective_perturbation_mechanism(
+    features: &[Vec<f64>], labels: &[bool], epsilon: &f64, regularization: &f64,
+) -> Result<Vec<f64>> {
+    if features.is_empty() || features.len() != labels.len() {
+        return Err("features and labels must have the same, positive number of rows".into())
+    }
+    if epsilon <= &0. {
+        return Err("epsilon must be positive".into())
+    }
+    if regularization.is_nan() || regularization <= &0. {
+        return Err("regularization must be positive".into())
+    }
+    let dimension = features[0].len();
+    if features.iter().any(|row| row.len() != dimension) {
+        return Err("every row of features must have the same length".into())
+    }
+
+    // privacy relies on every row lying within the unit ball
+    let features = features.iter()
+        .map(|row| {
+            let norm = row.iter().map(|v| v.powi(2)).sum::<f64>().sqrt();
+            row.iter().map(|v| if norm > 1. { v / norm } else { *v }).collect::<Vec<f64>>()
+        })
+        .collect::<Vec<Vec<f64>>>();
+    let signs = labels.iter()
+        .map(|label| if *label { 1. } else { -1. })
+        .collect::<Vec<f64>>();
+
+    let num_records = features.len() as f64;
+    // the second derivative of the logistic loss is at most 1/4
+    let curvature = 0.25;
+    let mut epsilon_prime = epsilon - (1.
+        + 2. * curvature / (num_records * regularization)
+        + (curvature / (num_records * regularization)).powi(2)).ln();
+    let mut extra_regularization = 0.;
+    if epsilon_prime <= 0. {
+        extra_regularization = (curvature / (num_records * (epsilon / 4.).exp_m1()) - regularization).max(0.);
+        epsilon_prime = epsilon / 2.;
+    }
+
+    let perturbation = noise::sample_gamma_norm_vector(dimension, 2. / epsilon_prime);
+
+    minimize_logistic_loss(
+        &features, &signs, &perturbation, regularization + extra_regularization)
+}
+
+/// Minimize the mean logistic loss, plus a linear term divided by the number of rows, plus an L2 penalty.
+///
+/// The objective is strongly convex, so Newton's method with a backtracking line search converges to the unique minimum.
+fn minimize_logistic_loss(
+    features: &[Vec<f64>], signs: &[f64], linear: &[f64], regularization: f64,
+) -> Result<Vec<f64>> {
+    let num_records = features.len() as f64;
+    let dimension = linear.len();
+
+    let dot = |l: &[f64], r: &[f64]| l.iter().zip(r.iter()).map(|(l, r)| l * r).sum::<f64>();
+    // log(1 + exp(-t)), without overflow
+    let loss = |t: f64| (-t).max(0.) + (-t.abs()).exp().ln_1p();
+    let sigmoid = |t: f64| 1. / (1. + (-t).exp());
+
+    let objective = |weights: &[f64]| features.iter().zip(signs.iter())
+        .map(|(row, sign)| loss(sign * dot(row, weights)))
+        .sum::<f64>() / num_records
+        + dot(linear, weights) / num_records
+        + regularization / 2. * dot(weights, weights);
+
+    let mut weights = vec![0.; dimension];
+    for _ in 0..100 {
+        let mut gradient = linear.iter()
+            .zip(weights.iter())
+            .map(|(b, w)| b / num_records + regularization * w)
+            .collect::<Vec<f64>>();
+        let mut hessian = (0..dimension)
+            .map(|i| (0..dimension).map(|j| if i == j { regularization } else { 0. }).collect::<Vec<f64>>())
+            .collect::<Vec<Vec<f64>>>();
+
+        for (row, sign) in features.iter().zip(signs.iter()) {
+            let margin = sign * dot(row, &weights);
+            let slope = -sign * sigmoid(-margin) / num_records;
+            let curvature = sigmoid(margin) * sigmoid(-margin) / num_records;
+            gradient.iter_mut().zip(row.iter())
+                .for_each(|(gradient, x)| *gradient += slope * x);
+            hessian.iter_mut().zip(row.iter())
+                .for_each(|(hessian_row, x_i)| hessian_row.iter_mut().zip(row.iter())
+                    .for_each(|(value, x_j)| *value += curvature * x_i * x_j));
+        }
+
+        let step = solve(&hessian, &gradient)?;
+        let decrement = dot(&gradient, &step);
+        if decrement <= 1e-20 {
+            break
+        }
+
+        let current = objective(&weights);
+        let mut rate = 1.;
+        let mut candidate = weights.clone();
+        while rate > 1e-10 {
+            candidate = weights.iter().zip(step.iter())
+                .map(|(w, s)| w - rate * s)
+                .collect();
+            if objective(&candidate) <= current - rate * decrement / 2. {
+                break
+            }
+            rate /= 2.;
+        }
+        weights = candidate;
+    }
+    Ok(weights)
+}
@@ -317,6 +317,73 @@ pub fn sample_laplace(shift: f64, scale: f64) -> f64 {
     Laplace::new(shift, scale).inverse(probability)
 }
 
+/// Sample from Gumbel distribution.
+///
+/// # Arguments
+///
+/// * `shift` - The location parameter (mode) of the Gumbel distribution.
+/// * `scale` - The scaling parameter of the Gumbel distribution.
+///
+/// # Return
+/// Draw from Gumbel(shift, scale).
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::utilities::noise::sample_gumbel;
+/// let n = sample_gumbel(0.0, 2.0);
+/// ```
+pub fn sample_gumbel(shift: f64, scale: f64) -> f64 {
+    // the inverse cdf is infinite at the endpoints of the unit interval
+    let mut probability: f64 = 0.;
+    while probability <= 0. || probability >= 1. {
+        probability = sample_uniform(&0., &1.).unwrap();
+    }
+    shift - scale * (-probability.ln()).ln()
+}
+
+/// Sample a vector with density proportional to `exp(-||b||_2 / scale)`.
+///
+/// The norm of the vector is Gamma(dimension, scale) distributed, drawn as a sum of exponentials,
+/// and the direction is uniform on the sphere, drawn by normalizing a standard Gaussian vector.
+///
+/// # Arguments
+///
+/// * `dimension` - The length of the vector.
+/// * `scale` - The scaling parameter of the norm.
+///
+/// # Return
+/// Draw of a vector of the given dimension.
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::utilities::noise::sample_gamma_norm_vector;
+/// let b = sample_gamma_norm_vector(3, 2.0);
+/// assert_eq!(b.len(), 3);
+/// ```
+pub fn sample_gamma_norm_vector(dimension: usize, scale: f64) -> Vec<f64> {
+    if dimension == 0 {
+        return Vec::new()
+    }
+    let norm = (0..dimension)
+        .map(|_| {
+            // the logarithm is infinite at zero
+            let mut probability: f64 = 0.;
+            while probability <= 0. {
+                probability = sample_uniform(&0., &1.).unwrap();
+            }
+            -scale * probability.ln()
+        })
+        .sum::<f64>();
+
+    let mut direction = Vec::new();
+    let mut direction_norm = 0_f64;
+    while direction_norm == 0. || !direction_norm.is_finite() {
+        direction = (0..dimension).map(|_| sample_gaussian(&0., &1.)).collect::<Vec<f64>>();
+        direction_norm = direction.iter().map(|v| v.powi(2)).sum::<f64>().sqrt();
+    }
+    direction.into_iter().map(|v| v * norm / direction_norm).collect()
+}
+
 /// Sample from Gaussian distribution.
 ///
 /// # Arguments
@@ -465,4 +532,23 @@ pub fn sample_simple_geometric_mechanism(scale: &f64, min: &i64, max: &i64, enfo
         let geom: i64 = sample_geometric_censored(&(1. - alpha), &max_trials, enforce_constant_time).unwrap();
         return sign * geom;
     }
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod test_noise {
+    use crate::utilities::noise::sample_gamma_norm_vector;
+
+    #[test]
+    fn test_sample_gamma_norm_vector() {
+        assert!(sample_gamma_norm_vector(0, 2.).is_empty());
+
+        // the direction is resampled until it can be normalized
+        (0..3).for_each(|_| {
+            let sample = sample_gamma_norm_vector(3, 2.);
+            assert_eq!(sample.len(), 3);
+            assert!(sample.iter().all(|v| v.is_finite()));
+            assert!(sample.iter().map(|v| v.powi(2)).sum::<f64>() > 0.);
+        });
+    }
+}
@@ -11,16 +11,56 @@ typedef struct {
 
 ByteBufferValidator accuracy_to_privacy_usage(const uint8_t *request_ptr, int32_t request_length);
 
+ByteBufferValidator approve_analysis(const uint8_t *request_ptr, int32_t request_length);
+
+ByteBufferValidator allocate_budget(const uint8_t *request_ptr, int32_t request_length);
+
+ByteBufferValidator apply_metadata_answers(const uint8_t *request_ptr, int32_t request_length);
+
+ByteBufferValidator compare_releases(const uint8_t *request_ptr, int32_t request_length);
+
+ByteBufferValidator compute_epsilon_delta_curves(const uint8_t *request_ptr, int32_t request_length);
+
+ByteBufferValidator compute_node_privacy_usages(const uint8_t *request_ptr, int32_t request_length);
+
+ByteBufferValidator compute_partition_privacy_usage(const uint8_t *request_ptr, int32_t request_length);
+
 ByteBufferValidator compute_privacy_usage(const uint8_t *request_ptr, int32_t request_length);
 
+ByteBufferValidator diff_analyses(const uint8_t *request_ptr, int32_t request_length);
+
+ByteBufferValidator estimate_cost(const uint8_t *request_ptr, int32_t request_length);
+
+ByteBufferValidator expand_analysis(const uint8_t *request_ptr, int32_t request_length);
+
 ByteBufferValidator expand_component(const uint8_t *request_ptr, int32_t request_length);
 
+ByteBufferValidator export_accounting_events(const uint8_t *request_ptr, int32_t request_length);
+
+ByteBufferValidator generate_audit_cases(const uint8_t *request_ptr, int32_t request_length);
+
+ByteBufferValidator generate_metadata_questionnaire(const uint8_t *request_ptr, int32_t request_length);
+
+ByteBufferValidator generate_release_notes(const uint8_t *request_ptr, int32_t request_length);
+
+ByteBufferValidator get_execution_schedule(const uint8_t *request_ptr, int32_t request_length);
+
 ByteBufferValidator get_properties(const uint8_t *request_ptr, int32_t request_length);
 
 ByteBufferValidator generate_report(const uint8_t *request_ptr, int32_t request_length);
 
+ByteBufferValidator lint_analysis(const uint8_t *request_ptr, int32_t request_length);
+
+ByteBufferValidator merge_releases(const uint8_t *request_ptr, int32_t request_length);
+
 ByteBufferValidator privacy_usage_to_accuracy(const uint8_t *request_ptr, int32_t request_length);
 
+ByteBufferValidator render_dot(const uint8_t *request_ptr, int32_t request_length);
+
+ByteBufferValidator simulate_privacy_usage(const uint8_t *request_ptr, int32_t request_length);
+
+ByteBufferValidator slice_analysis(const uint8_t *request_ptr, int32_t request_length);
+
 ByteBufferValidator validate_analysis(const uint8_t *request_ptr, int32_t request_length);
 
 void whitenoise_validator_destroy_bytebuffer(ByteBufferValidator buffer);
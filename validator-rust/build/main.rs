@@ -4,6 +4,7 @@ extern crate prost_build;
 mod bindings;
 mod protobuf;
 mod documentation;
+mod privacy;
 
 use std::io;
 use std::path::Path;
@@ -96,6 +97,7 @@ fn main() {
                              out_dir.join("bindings_analysis.rs"),
                              out_dir.join("bindings_builders.rs"));
     documentation::build_documentation(&components, out_dir.join("components.rs"));
+    privacy::build_privacy(&components, out_dir.join("privacy_options.rs"));
     protobuf::build_protobuf(&components, proto_dir.join("components.proto"));
 
     prost_build::Config::new().compile_protos(
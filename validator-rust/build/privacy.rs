@@ -0,0 +1,38 @@
+extern crate heck;
+
+use crate::ComponentJSON;
+use std::path::PathBuf;
+use std::fs;
+use std::fs::File;
+use std::io::Write;
+use self::heck::CamelCase;
+
+
+pub fn build_privacy(components: &[ComponentJSON], output_path: PathBuf) {
+
+    // every component with a privacy usage among its options spends privacy, whether or not it is a mechanism
+    let variants = components.iter()
+        .filter(|component| component.options.keys().any(|name| name.ends_with("privacy_usage")))
+        .map(|component| format!("Some(proto::component::Variant::{}(_))", component.name.to_camel_case()))
+        .collect::<Vec<String>>().join("\n        | ");
+
+    let privacy_options = format!(r#"
+/// Whether the options of the component carry a privacy usage.
+///
+/// Such components are either mechanisms, or expand into mechanisms, so they are charged their privacy usage once evaluated.
+pub fn has_privacy_usage(component: &proto::Component) -> bool {{
+    match component.variant {{
+        {variants} => true,
+        _ => false
+    }}
+}}
+"#, variants=variants);
+
+    {
+        fs::remove_file(output_path.clone()).ok();
+        let mut file = File::create(output_path).unwrap();
+        file.write_all(privacy_options.as_bytes())
+            .expect("Unable to write privacy_options.rs file.");
+        file.flush().unwrap();
+    }
+}
@@ -0,0 +1,153 @@
+//! Mutate a valid analysis with arbitrary bytes, and validate it.
+//!
+//! Byte-level mutations of a protobuf rarely decode, so the analysis is instead mutated structurally:
+//! arguments are dropped and rewired, components are swapped between nodes, and releases are dropped or exchanged.
+//! Mutated analyses are usually invalid, and must be rejected with an error, never a panic.
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use ndarray::{arr1, Array2};
+use std::collections::HashMap;
+
+use whitenoise_validator::{proto, hashmap};
+use whitenoise_validator::base::{Array, Value};
+use whitenoise_validator::utilities::get_literal;
+use whitenoise_validator::utilities::serial::serialize_value;
+
+/// A valid analysis: the DP mean of a clamped and resized column.
+fn seed_analysis() -> (proto::Analysis, proto::Release) {
+    let mut graph = HashMap::new();
+    let mut release = HashMap::new();
+    let mut literal = |node_id: u32, value: Value| {
+        let (component, release_node) = get_literal(&value, &0).unwrap();
+        graph.insert(node_id, component);
+        release.insert(node_id, release_node);
+    };
+    literal(1, Value::Array(Array::Str(arr1(&["a".to_string()]).into_dyn())));
+    literal(3, Value::from(0.));
+    literal(4, Value::from(10.));
+    literal(6, Value::from(100_i64));
+
+    let component = |arguments: HashMap<String, u32>, variant: proto::component::Variant| proto::Component {
+        arguments,
+        variant: Some(variant),
+        omit: true,
+        batch: 0,
+    };
+    graph.insert(2, component(hashmap!["column_names".to_string() => 1],
+        proto::component::Variant::Materialize(proto::Materialize {
+            data_source: Some(proto::DataSource {
+                value: Some(proto::data_source::Value::Literal(
+                    serialize_value(&Value::Array(Array::F64(Array2::<f64>::zeros((10, 1)).into_dyn()))).unwrap())),
+                max_contributions_per_individual: 1,
+            }),
+            public: false,
+            dataset_id: None,
+            skip_row: true,
+        })));
+    graph.insert(5, component(hashmap!["data".to_string() => 2, "columns".to_string() => 1],
+        proto::component::Variant::Index(proto::Index {})));
+    graph.insert(7, component(hashmap!["data".to_string() => 5, "lower".to_string() => 3, "upper".to_string() => 4],
+        proto::component::Variant::Clamp(proto::Clamp {})));
+    graph.insert(8, component(hashmap!["data".to_string() => 7, "n".to_string() => 6, "lower".to_string() => 3, "upper".to_string() => 4],
+        proto::component::Variant::Resize(proto::Resize {})));
+    graph.insert(9, component(hashmap!["data".to_string() => 8],
+        proto::component::Variant::Mean(proto::Mean {})));
+    let mut mechanism = component(hashmap!["data".to_string() => 9],
+        proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+            privacy_usage: vec![proto::PrivacyUsage {
+                distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                    epsilon: 1.,
+                    delta: 0.,
+                }))
+            }]
+        }));
+    mechanism.omit = false;
+    graph.insert(10, mechanism);
+
+    (proto::Analysis {
+        privacy_definition: Some(proto::PrivacyDefinition {
+            group_size: 1,
+            distance: proto::privacy_definition::Distance::Approximate as i32,
+            neighboring: proto::privacy_definition::Neighboring::AddRemove as i32,
+            delta_cap: 0.,
+            delta_split: proto::privacy_definition::DeltaSplit::Equal as i32,
+            delta_allotments: HashMap::new(),
+            composition: proto::privacy_definition::Composition::Basic as i32,
+            composition_delta: 0.,
+            epsilon_floor: 0.,
+            epsilon_ceiling: 0.,
+            epsilon_exemptions: Vec::new(),
+            delta_inverse_records: false,
+            epsilon_cap: 0.,
+            enforce_budget: false
+        }),
+        computation_graph: Some(proto::ComputationGraph { value: graph }),
+        approval_token: Vec::new(),
+        external_usages: Vec::new(),
+        optimizations: None,
+    }, proto::Release { values: release })
+}
+
+/// Apply one structural mutation, chosen and parameterized by three bytes.
+fn mutate(graph: &mut HashMap<u32, proto::Component>, release: &mut HashMap<u32, proto::ReleaseNode>, bytes: &[u8]) {
+    // node ids are sorted, so that the same input always applies the same mutation
+    let mut node_ids = graph.keys().cloned().collect::<Vec<u32>>();
+    node_ids.sort();
+    if node_ids.is_empty() {
+        return
+    }
+    let (operation, first, second) = (bytes[0] % 6, bytes[1] as usize, bytes[2] as usize);
+    let node_id = node_ids[first % node_ids.len()];
+    let other_id = node_ids[second % node_ids.len()];
+
+    match operation {
+        // drop an argument
+        0 => if let Some(component) = graph.get_mut(&node_id) {
+            let mut names = component.arguments.keys().cloned().collect::<Vec<String>>();
+            names.sort();
+            if !names.is_empty() {
+                component.arguments.remove(&names[second % names.len()]);
+            }
+        },
+        // rewire an argument, possibly to a node that does not exist
+        1 => if let Some(component) = graph.get_mut(&node_id) {
+            let mut names = component.arguments.keys().cloned().collect::<Vec<String>>();
+            names.sort();
+            if !names.is_empty() {
+                let name = names[second % names.len()].clone();
+                component.arguments.insert(name, second as u32 % (node_ids.len() as u32 + 2));
+            }
+        },
+        // exchange the variants of two nodes
+        2 => {
+            let variant = graph.get(&node_id).and_then(|component| component.variant.clone());
+            let other_variant = graph.get(&other_id).and_then(|component| component.variant.clone());
+            graph.get_mut(&node_id).unwrap().variant = other_variant;
+            graph.get_mut(&other_id).unwrap().variant = variant;
+        },
+        // toggle whether the node is omitted
+        3 => if let Some(component) = graph.get_mut(&node_id) {
+            component.omit = !component.omit;
+        },
+        // drop a release
+        4 => {
+            release.remove(&node_id);
+        },
+        // exchange releases, to confuse types and shapes
+        _ => if let Some(other_release) = release.get(&other_id).cloned() {
+            release.insert(node_id, other_release);
+        }
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let (mut analysis, mut release) = seed_analysis();
+    let graph = &mut analysis.computation_graph.as_mut().unwrap().value;
+    data.chunks_exact(3).for_each(|bytes| mutate(graph, &mut release.values, bytes));
+
+    let _ = whitenoise_validator::validate_analysis(&proto::RequestValidateAnalysis {
+        analysis: Some(analysis),
+        release: Some(release),
+        release_gate: None,
+    });
+});
@@ -0,0 +1,13 @@
+//! Decode arbitrary bytes as a validation request, and validate it.
+//!
+//! Malformed requests must be rejected with an error, never a panic.
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use prost::Message;
+use whitenoise_validator::proto;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(request) = proto::RequestValidateAnalysis::decode(data) {
+        let _ = whitenoise_validator::validate_analysis(&request);
+    }
+});
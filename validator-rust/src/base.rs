@@ -45,6 +45,13 @@ impl Value {
             _ => Err("value must be Jagged".into())
         }
     }
+    /// Retrieve a Hashmap from a Value, assuming the Value contains a Hashmap
+    pub fn hashmap(&self) -> Result<&Hashmap<Value>> {
+        match self {
+            Value::Hashmap(hashmap) => Ok(hashmap),
+            _ => Err("value must be a Hashmap".into())
+        }
+    }
 
     /// Retrieve the first f64 from a Value, assuming a Value contains an ArrayND of type f64
     pub fn first_f64(&self) -> Result<f64> {
@@ -332,7 +339,7 @@ impl Array {
 /// In practice, use is limited to public categories over multiple columns, and the upper triangular covariance matrix
 ///
 /// Jagged has a one-to-one mapping to a protobuf Vector2DJagged.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Jagged {
     Bool(Vec<Option<Vec<bool>>>),
     I64(Vec<Option<Vec<i64>>>),
@@ -402,6 +409,32 @@ impl Jagged {
             .ok_or_else(|| Error::from("length is not defined for every column"))
     }
 
+    pub fn select(&self, indices: &[usize]) -> Result<Jagged> {
+        Ok(match self {
+            Jagged::Bool(x) => Jagged::Bool(take_all(x, indices)?),
+            Jagged::I64(x) => Jagged::I64(take_all(x, indices)?),
+            Jagged::F64(x) => Jagged::F64(take_all(x, indices)?),
+            Jagged::Str(x) => Jagged::Str(take_all(x, indices)?),
+        })
+    }
+    /// Stack the columns of several jagged matrices. None if the matrices differ in type.
+    pub fn concat(matrices: &[&Jagged]) -> Option<Jagged> {
+        Some(match matrices.first()? {
+            Jagged::Bool(_) => Jagged::Bool(matrices.iter()
+                .map(|v| if let Jagged::Bool(v) = v { Some(v.clone()) } else { None })
+                .collect::<Option<Vec<_>>>()?.concat()),
+            Jagged::I64(_) => Jagged::I64(matrices.iter()
+                .map(|v| if let Jagged::I64(v) = v { Some(v.clone()) } else { None })
+                .collect::<Option<Vec<_>>>()?.concat()),
+            Jagged::F64(_) => Jagged::F64(matrices.iter()
+                .map(|v| if let Jagged::F64(v) = v { Some(v.clone()) } else { None })
+                .collect::<Option<Vec<_>>>()?.concat()),
+            Jagged::Str(_) => Jagged::Str(matrices.iter()
+                .map(|v| if let Jagged::Str(v) = v { Some(v.clone()) } else { None })
+                .collect::<Option<Vec<_>>>()?.concat()),
+        })
+    }
+
     pub fn deduplicate(&self) -> Result<Jagged> {
         match self.to_owned() {
             Jagged::F64(_) =>
@@ -485,6 +518,26 @@ impl<T> Hashmap<T> {
             Hashmap::Str(value) => value.values().collect(),
         }
     }
+    /// Apply a fallible function to every value, keeping the keys.
+    pub fn map_values<U>(&self, function: &dyn Fn(&T) -> Result<U>) -> Result<Hashmap<U>> {
+        Ok(match self {
+            Hashmap::Bool(value) => Hashmap::Bool(value.iter()
+                .map(|(key, value)| Ok((*key, function(value)?))).collect::<Result<_>>()?),
+            Hashmap::I64(value) => Hashmap::I64(value.iter()
+                .map(|(key, value)| Ok((*key, function(value)?))).collect::<Result<_>>()?),
+            Hashmap::Str(value) => Hashmap::Str(value.iter()
+                .map(|(key, value)| Ok((key.clone(), function(value)?))).collect::<Result<_>>()?),
+        })
+    }
+    /// True if both hashmaps have the same type of key, and the same keys.
+    pub fn has_same_keys<U>(&self, other: &Hashmap<U>) -> bool {
+        match (self, other) {
+            (Hashmap::Bool(l), Hashmap::Bool(r)) => l.keys().eq(r.keys()),
+            (Hashmap::I64(l), Hashmap::I64(r)) => l.keys().eq(r.keys()),
+            (Hashmap::Str(l), Hashmap::Str(r)) => l.keys().eq(r.keys()),
+            _ => false
+        }
+    }
     pub fn from_values(&self, values: Vec<T>) -> Hashmap<T> where T: Clone {
         match self {
             Hashmap::Bool(value) => value.keys().cloned()
@@ -521,6 +574,7 @@ pub enum ValueProperties {
     Hashmap(HashmapProperties),
     Array(ArrayProperties),
     Jagged(JaggedProperties),
+    Scalar(ScalarProperties),
 }
 
 
@@ -546,6 +600,38 @@ impl ValueProperties {
             _ => Err("value must be a ragged matrix".into())
         }
     }
+    /// Retrieve properties corresponding to a scalar, assuming the properties are in scalar form
+    pub fn scalar(&self) -> Result<&ScalarProperties> {
+        match self {
+            ValueProperties::Scalar(value) => Ok(value),
+            _ => Err("value must be a scalar".into())
+        }
+    }
+    pub fn releasable(&self) -> bool {
+        match self {
+            ValueProperties::Hashmap(value) => value.releasable,
+            ValueProperties::Array(value) => value.releasable,
+            ValueProperties::Jagged(value) => value.releasable,
+            ValueProperties::Scalar(value) => value.releasable,
+        }
+    }
+    /// Represent zero-dimensional arrays as scalars, for storage and reporting.
+    pub fn into_scalar_form(self) -> ValueProperties {
+        match self {
+            ValueProperties::Array(value) => match value.to_scalar() {
+                Some(scalar) => ValueProperties::Scalar(scalar),
+                None => ValueProperties::Array(value)
+            },
+            value => value
+        }
+    }
+    /// Represent scalars as zero-dimensional arrays, as components expect.
+    pub fn into_array_form(self) -> ValueProperties {
+        match self {
+            ValueProperties::Scalar(value) => ValueProperties::Array(value.into()),
+            value => value
+        }
+    }
 }
 
 
@@ -567,6 +653,12 @@ impl From<JaggedProperties> for ValueProperties {
     }
 }
 
+impl From<ScalarProperties> for ValueProperties {
+    fn from(value: ScalarProperties) -> Self {
+        ValueProperties::Scalar(value)
+    }
+}
+
 
 /// Derived properties for the universal Hashmap.
 ///
@@ -580,9 +672,34 @@ pub struct HashmapProperties {
     /// properties for each of the values in the hashmap
     pub properties: Hashmap<ValueProperties>,
     pub columnar: bool,
+    /// set when the hashmap as a whole may be released, even if its keys are data-dependent
+    pub releasable: bool,
+    /// ids of the Partition components that produced each level of nested partitions, outermost first.
+    /// Empty when the hashmap is not a partition, as for dataframes
+    pub group_id: Vec<u32>,
 }
 
 impl HashmapProperties {
+    pub fn assert_is_partition(&self) -> Result<()> {
+        if self.group_id.is_empty() { Err("data must be a partition".into()) } else { Ok(()) }
+    }
+    /// Check that both partitions are nested by the same Partition components, with the same keys at every level.
+    pub fn assert_same_groups(&self, other: &HashmapProperties) -> Result<()> {
+        if self.group_id != other.group_id {
+            bail!("partitions must be nested by the same groups, but are nested by {:?} and {:?}", self.group_id, other.group_id)
+        }
+        if !self.properties.has_same_keys(&other.properties) {
+            return Err("partitions must have the same keys".into())
+        }
+        self.properties.values().into_iter().zip(other.properties.values().into_iter())
+            .map(|(left, right)| match (left, right) {
+                (ValueProperties::Hashmap(left), ValueProperties::Hashmap(right)) => left.assert_same_groups(right),
+                (ValueProperties::Hashmap(_), _) | (_, ValueProperties::Hashmap(_)) =>
+                    Err("partitions must be nested to the same depth".into()),
+                _ => Ok(())
+            })
+            .collect()
+    }
     pub fn assert_is_disjoint(&self) -> Result<()> {
         if self.disjoint { Err("partitions must be disjoint".into()) } else { Ok(()) }
     }
@@ -607,6 +724,10 @@ pub struct ArrayProperties {
     pub nullity: bool,
     /// set to true by the mechanisms. Acts as a filter on the values in the release
     pub releasable: bool,
+    /// true if the number of records and columns are public, even when the values are not
+    pub public_shape: bool,
+    /// true if the nature (bounds or categories) is public, even when the values are not
+    pub public_metadata: bool,
     /// amplification of privacy usage by unstable data transformations, or possibility of duplicated records
     pub c_stability: Vec<f64>,
     /// set when data is aggregated, used to help compute sensitivity from the mechanisms
@@ -620,11 +741,39 @@ pub struct ArrayProperties {
     pub dataset_id: Option<i64>,
     /// true if the array may not be length zero
     pub is_not_empty: bool,
+    /// maximum number of records any one identifier contributes, if known.
+    /// Set by data sources with a known bound, and by the BoundContributions component
+    pub max_contributions: Option<u32>,
     /// number of axes in the array
     pub dimensionality: u32
 }
 
 
+/// Which aspects of a value are public.
+///
+/// The shape and metadata of a value may be public even when the value itself is not,
+/// so that components may rely on them without treating the value as releasable.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct Publicness {
+    /// the value may be released
+    pub values: bool,
+    /// the number of records and columns may be released
+    pub shape: bool,
+    /// the nature of the value, its bounds or categories, may be released
+    pub metadata: bool,
+}
+
+impl Publicness {
+    /// Each aspect is public only if it is public on both operands.
+    pub fn and(&self, other: &Publicness) -> Publicness {
+        Publicness {
+            values: self.values && other.values,
+            shape: self.shape && other.shape,
+            metadata: self.metadata && other.metadata,
+        }
+    }
+}
+
 /// Derived properties for the universal Vector2DJagged.
 ///
 /// The Vector2DJagged has a one-to-one mapping to a protobuf Vector2DJagged.
@@ -633,6 +782,52 @@ pub struct JaggedProperties {
     pub releasable: bool
 }
 
+/// Derived properties for a single value, such as a literal, a count or a single statistic.
+///
+/// Scalars carry no record or column counts.
+/// The ScalarProperties has a one-to-one mapping to a protobuf ScalarProperties.
+#[derive(Clone, Debug)]
+pub struct ScalarProperties {
+    /// true if the value may be null
+    pub nullity: bool,
+    /// set to true by the mechanisms. Acts as a filter on the values in the release
+    pub releasable: bool,
+    /// true if the nature (bounds or categories) is public, even when the value is not
+    pub public_metadata: bool,
+    /// amplification of privacy usage by unstable data transformations, or possibility of duplicated records
+    pub c_stability: f64,
+    /// set when data is aggregated, used to help compute sensitivity from the mechanisms
+    pub aggregator: Option<AggregatorProperties>,
+    /// either min/max or categories, over a single column
+    pub nature: Option<Nature>,
+    /// f64, i64, bool, String
+    pub data_type: DataType,
+    /// index of last Materialize or Filter node, where dataset was created
+    pub dataset_id: Option<i64>,
+}
+
+impl From<ScalarProperties> for ArrayProperties {
+    fn from(value: ScalarProperties) -> Self {
+        ArrayProperties {
+            num_records: Some(1),
+            num_columns: Some(1),
+            nullity: value.nullity,
+            releasable: value.releasable,
+            // the shape of a scalar is known
+            public_shape: true,
+            public_metadata: value.public_metadata,
+            c_stability: vec![value.c_stability],
+            aggregator: value.aggregator,
+            nature: value.nature,
+            data_type: value.data_type,
+            dataset_id: value.dataset_id,
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 0
+        }
+    }
+}
+
 impl ArrayProperties {
     pub fn lower_f64_option(&self) -> Result<Vec<Option<f64>>> {
         match self.nature.to_owned() {
@@ -726,6 +921,20 @@ impl ArrayProperties {
     pub fn assert_is_releasable(&self) -> Result<()> {
         if self.releasable { Ok(()) } else { Err("data is not releasable when releasability is required".into()) }
     }
+    /// Which aspects of the data are public. Public values imply a public shape and public metadata.
+    pub fn publicness(&self) -> Publicness {
+        Publicness {
+            values: self.releasable,
+            shape: self.releasable || self.public_shape,
+            metadata: self.releasable || self.public_metadata,
+        }
+    }
+    pub fn assert_shape_is_public(&self) -> Result<()> {
+        if self.publicness().shape { Ok(()) } else { Err("the shape of the data is not public when a public shape is required".into()) }
+    }
+    pub fn assert_metadata_is_public(&self) -> Result<()> {
+        if self.publicness().metadata { Ok(()) } else { Err("the metadata of the data is not public when public metadata is required".into()) }
+    }
     pub fn num_columns(&self) -> Result<i64> {
         self.num_columns.ok_or_else(|| "number of columns is not defined".into())
     }
@@ -736,6 +945,109 @@ impl ArrayProperties {
         if self.aggregator.is_some() { Err("aggregated data may not be manipulated".into()) }
         else { Ok(()) }
     }
+    /// Scalar properties, if the array is zero-dimensional and the conversion is lossless.
+    pub fn to_scalar(&self) -> Option<ScalarProperties> {
+        if self.dimensionality != 0 || self.num_records != Some(1) || self.num_columns != Some(1)
+            || self.c_stability.len() != 1 || !self.is_not_empty {
+            return None
+        }
+        Some(ScalarProperties {
+            nullity: self.nullity,
+            releasable: self.releasable,
+            public_metadata: self.public_metadata,
+            c_stability: self.c_stability[0],
+            aggregator: self.aggregator.clone(),
+            nature: self.nature.clone(),
+            data_type: self.data_type.clone(),
+            dataset_id: self.dataset_id,
+        })
+    }
+    /// Retrieve the per-column properties of a single column.
+    pub fn column(&self, index: usize) -> Result<ColumnProperties> {
+        Ok(ColumnProperties {
+            c_stability: *self.c_stability.get(index)
+                .ok_or_else(|| Error::from("property column index is out of bounds"))?,
+            nature: match &self.nature {
+                Some(nature) => Some(nature.select(&[index])?),
+                None => None
+            }
+        })
+    }
+    /// Restrict the properties to the given columns, in a single pass over the per-column properties.
+    pub fn select_columns(&self, indices: &[usize]) -> Result<ArrayProperties> {
+        let mut properties = self.clone();
+        properties.c_stability = take_all(&self.c_stability, indices)?;
+        properties.nature = match &self.nature {
+            Some(nature) => Some(nature.select(indices)?),
+            None => None
+        };
+        properties.num_columns = Some(indices.len() as i64);
+        Ok(properties)
+    }
+    /// Compress the per-column properties into a template shared by most columns.
+    pub fn shared_columns(&self) -> Result<SharedColumns> {
+        SharedColumns::compress((0..self.c_stability.len())
+            .map(|index| self.column(index))
+            .collect::<Result<Vec<ColumnProperties>>>()?)
+    }
+    /// Overwrite the per-column properties.
+    ///
+    /// The nature is dropped unless every column has a nature of the same kind and type.
+    pub fn set_columns(&mut self, columns: &[ColumnProperties]) {
+        self.c_stability = columns.iter().map(|column| column.c_stability).collect();
+        self.nature = columns.iter()
+            .map(|column| column.nature.as_ref())
+            .collect::<Option<Vec<&Nature>>>()
+            .and_then(|natures| Nature::concat(&natures));
+        self.num_columns = Some(columns.len() as i64);
+    }
+}
+
+/// Properties of a single column of an array.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnProperties {
+    pub c_stability: f64,
+    /// nature of the single column
+    pub nature: Option<Nature>,
+}
+
+/// Per-column properties, stored once for the columns that share them, and separately for the columns that differ.
+///
+/// Wide data, for instance genomic data, may have thousands of columns with identical metadata.
+/// The SharedColumns has a one-to-one mapping to a protobuf SharedColumns, together with the template column of the ArrayNDProperties.
+#[derive(Clone, Debug)]
+pub struct SharedColumns {
+    pub num_columns: usize,
+    pub template: ColumnProperties,
+    /// columns that differ from the template, keyed by column index
+    pub exceptions: BTreeMap<usize, ColumnProperties>,
+}
+
+impl SharedColumns {
+    /// Use the first column as the template, and retain every column that differs from it.
+    pub fn compress(columns: Vec<ColumnProperties>) -> Result<SharedColumns> {
+        let num_columns = columns.len();
+        let mut columns = columns.into_iter().enumerate();
+        let (_, template) = columns.next()
+            .ok_or_else(|| Error::from("at least one column is required to share properties"))?;
+        let exceptions = columns
+            .filter(|(_, column)| column != &template)
+            .collect();
+        Ok(SharedColumns { num_columns, template, exceptions })
+    }
+    pub fn get(&self, index: usize) -> &ColumnProperties {
+        self.exceptions.get(&index).unwrap_or(&self.template)
+    }
+    pub fn expand(&self) -> Vec<ColumnProperties> {
+        (0..self.num_columns).map(|index| self.get(index).clone()).collect()
+    }
+}
+
+fn take_all<T: Clone>(vector: &[T], indices: &[usize]) -> Result<Vec<T>> {
+    indices.iter()
+        .map(|index| vector.get(*index).cloned()
+            .ok_or_else(|| Error::from("property column index is out of bounds")))
+        .collect()
 }
 
 /// Fundamental data types for ArrayNDs and Vector2DJagged Values.
@@ -761,7 +1073,7 @@ pub struct AggregatorProperties {
     pub properties: HashMap<String, ValueProperties>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Nature {
     Continuous(NatureContinuous),
     Categorical(NatureCategorical),
@@ -780,20 +1092,51 @@ impl Nature {
             _ => Err("nature is continuous when expecting categorical".into())
         }
     }
+    /// Restrict the nature to the given columns.
+    pub fn select(&self, indices: &[usize]) -> Result<Nature> {
+        Ok(match self {
+            Nature::Continuous(continuous) => Nature::Continuous(NatureContinuous {
+                lower: continuous.lower.select(indices)?,
+                upper: continuous.upper.select(indices)?,
+            }),
+            Nature::Categorical(categorical) => Nature::Categorical(NatureCategorical {
+                categories: categorical.categories.select(indices)?
+            })
+        })
+    }
+    /// Stack the natures of several sets of columns. None if the natures differ in kind or type.
+    pub fn concat(natures: &[&Nature]) -> Option<Nature> {
+        Some(match natures.first()? {
+            Nature::Continuous(_) => {
+                let continuous = natures.iter()
+                    .map(|nature| nature.continuous().ok())
+                    .collect::<Option<Vec<&NatureContinuous>>>()?;
+                Nature::Continuous(NatureContinuous {
+                    lower: Vector1DNull::concat(&continuous.iter().map(|v| &v.lower).collect::<Vec<_>>())?,
+                    upper: Vector1DNull::concat(&continuous.iter().map(|v| &v.upper).collect::<Vec<_>>())?,
+                })
+            },
+            Nature::Categorical(_) => Nature::Categorical(NatureCategorical {
+                categories: Jagged::concat(&natures.iter()
+                    .map(|nature| nature.categorical().ok().map(|v| &v.categories))
+                    .collect::<Option<Vec<&Jagged>>>()?)?
+            })
+        })
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct NatureCategorical {
     pub categories: Jagged
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct NatureContinuous {
     pub lower: Vector1DNull,
     pub upper: Vector1DNull,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Vector1DNull {
     Bool(Vec<Option<bool>>),
     I64(Vec<Option<i64>>),
@@ -816,6 +1159,31 @@ impl Vector1DNull {
             _ => Err("expected an integer on a non-integer Vector1DNull".into())
         }
     }
+    pub fn select(&self, indices: &[usize]) -> Result<Vector1DNull> {
+        Ok(match self {
+            Vector1DNull::Bool(x) => Vector1DNull::Bool(take_all(x, indices)?),
+            Vector1DNull::I64(x) => Vector1DNull::I64(take_all(x, indices)?),
+            Vector1DNull::F64(x) => Vector1DNull::F64(take_all(x, indices)?),
+            Vector1DNull::Str(x) => Vector1DNull::Str(take_all(x, indices)?),
+        })
+    }
+    /// Stack several vectors. None if the vectors differ in type.
+    pub fn concat(vectors: &[&Vector1DNull]) -> Option<Vector1DNull> {
+        Some(match vectors.first()? {
+            Vector1DNull::Bool(_) => Vector1DNull::Bool(vectors.iter()
+                .map(|v| if let Vector1DNull::Bool(v) = v { Some(v.clone()) } else { None })
+                .collect::<Option<Vec<_>>>()?.concat()),
+            Vector1DNull::I64(_) => Vector1DNull::I64(vectors.iter()
+                .map(|v| if let Vector1DNull::I64(v) = v { Some(v.clone()) } else { None })
+                .collect::<Option<Vec<_>>>()?.concat()),
+            Vector1DNull::F64(_) => Vector1DNull::F64(vectors.iter()
+                .map(|v| if let Vector1DNull::F64(v) = v { Some(v.clone()) } else { None })
+                .collect::<Option<Vec<_>>>()?.concat()),
+            Vector1DNull::Str(_) => Vector1DNull::Str(vectors.iter()
+                .map(|v| if let Vector1DNull::Str(v) = v { Some(v.clone()) } else { None })
+                .collect::<Option<Vec<_>>>()?.concat()),
+        })
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -842,7 +1210,11 @@ pub type Release = HashMap<u32, ReleaseNode>;
 pub struct ReleaseNode {
     pub value: Value,
     pub privacy_usages: Option<Vec<proto::PrivacyUsage>>,
-    pub public: bool
+    pub public: bool,
+    /// the shape of the value is public, even if the value is not
+    pub public_shape: bool,
+    /// the nature of the value is public, even if the value is not
+    pub public_metadata: bool
 }
 
 impl ReleaseNode {
@@ -850,10 +1222,175 @@ impl ReleaseNode {
         ReleaseNode {
             value,
             privacy_usages: None,
-            public: false
+            public: false,
+            public_shape: false,
+            public_metadata: false
         }
     }
 }
 
 // The properties for a node consists of Properties for each of its arguments.
 pub type NodeProperties = HashMap<String, ValueProperties>;
+
+
+/// A value, alongside the warnings raised while computing it.
+///
+/// Warnings are errors that did not prevent the value from being computed,
+/// like a node that could not be expanded during dynamic validation, or an unusually large privacy usage.
+/// Combinators carry the warnings along, so that callers may collect them uniformly.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Warnable<T>(pub T, pub Vec<proto::Error>);
+
+impl<T> Warnable<T> {
+    /// A value without warnings.
+    pub fn new(value: T) -> Self {
+        Warnable(value, Vec::new())
+    }
+    pub fn value(&self) -> &T {
+        &self.0
+    }
+    pub fn warnings(&self) -> &[proto::Error] {
+        &self.1
+    }
+    /// Discard the warnings.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+    pub fn into_parts(self) -> (T, Vec<proto::Error>) {
+        (self.0, self.1)
+    }
+    pub fn with_warning(mut self, warning: Error) -> Self {
+        self.1.push(crate::ffi::serialize_error(warning));
+        self
+    }
+    pub fn with_warnings(mut self, warnings: impl IntoIterator<Item=proto::Error>) -> Self {
+        self.1.extend(warnings);
+        self
+    }
+    /// Transform the value, keeping the warnings.
+    pub fn map<U>(self, function: impl FnOnce(T) -> U) -> Warnable<U> {
+        Warnable(function(self.0), self.1)
+    }
+    /// Chain a fallible computation that may raise more warnings. Warnings are kept in the order they were raised.
+    pub fn and_then<U>(self, function: impl FnOnce(T) -> Result<Warnable<U>>) -> Result<Warnable<U>> {
+        let Warnable(value, warnings) = function(self.0)?;
+        Ok(Warnable(value, self.1.into_iter().chain(warnings).collect()))
+    }
+    /// Pair two values, concatenating their warnings.
+    pub fn merge<U>(self, other: Warnable<U>) -> Warnable<(T, U)> {
+        Warnable((self.0, other.0), self.1.into_iter().chain(other.1).collect())
+    }
+}
+
+impl<T> From<T> for Warnable<T> {
+    fn from(value: T) -> Self {
+        Warnable::new(value)
+    }
+}
+
+impl<T> std::iter::FromIterator<Warnable<T>> for Warnable<Vec<T>> {
+    fn from_iter<I: IntoIterator<Item=Warnable<T>>>(iter: I) -> Self {
+        iter.into_iter().fold(Warnable::new(Vec::new()), |mut collected, Warnable(value, warnings)| {
+            collected.0.push(value);
+            collected.1.extend(warnings);
+            collected
+        })
+    }
+}
+
+/// Warnings are serialized as their messages.
+impl<T: serde::Serialize> serde::Serialize for Warnable<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("Warnable", 2)?;
+        state.serialize_field("value", &self.0)?;
+        state.serialize_field("warnings", &self.1.iter()
+            .map(|warning| warning.message.as_str()).collect::<Vec<&str>>())?;
+        state.end()
+    }
+}
+
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Warnable<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        struct WarnableMessages<T> {
+            value: T,
+            warnings: Vec<String>,
+        }
+        let WarnableMessages { value, warnings } = WarnableMessages::deserialize(deserializer)?;
+        Ok(Warnable(value, warnings.into_iter().map(|message| proto::Error { message }).collect()))
+    }
+}
+
+
+/// Options for [propagate_properties](../fn.propagate_properties.html).
+#[derive(Clone, Debug, Default)]
+pub struct PropagationOptions {
+    /// Continue past components that fail to expand or propagate, recording the failure as a warning.
+    /// Nodes that depend on a failed node are skipped. Otherwise the first failure is returned as an error.
+    pub dynamic: bool,
+    /// Treat any warning, like an unusually large privacy usage, as an error.
+    pub strict: bool,
+    /// Properties from an earlier propagation of the same analysis.
+    /// Nodes with cached properties are neither expanded nor propagated again.
+    pub cache: Option<HashMap<u32, proto::ValueProperties>>,
+}
+
+/// Properties of every node in an analysis, after expansion.
+#[derive(Clone, Debug)]
+pub struct GraphProperties {
+    /// Properties of every node in the expanded graph that propagated successfully.
+    pub properties: HashMap<u32, ValueProperties>,
+    /// The computation graph, with every component expanded.
+    pub graph: HashMap<u32, proto::Component>,
+    /// Failures of dynamic propagation, and unusually large privacy usages.
+    pub warnings: Vec<proto::Error>,
+}
+
+impl GraphProperties {
+    /// Serialize the properties into the protobuf representation returned by get_properties, discarding the graph.
+    pub fn to_proto(&self) -> proto::GraphProperties {
+        proto::GraphProperties {
+            properties: self.properties.iter()
+                .map(|(node_id, properties)| (*node_id, crate::utilities::serial::serialize_value_properties(properties)))
+                .collect(),
+            warnings: self.warnings.clone()
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test_warnable {
+    use crate::base::Warnable;
+    use crate::errors::*;
+    use crate::proto;
+
+    #[test]
+    fn test_combinators() {
+        let left = Warnable::new(1).with_warning("first".into());
+        let right = Warnable::new(2.).with_warning("second".into());
+
+        let merged = left.merge(right).map(|(l, r)| l as f64 + r);
+        assert_eq!(*merged.value(), 3.);
+        assert_eq!(merged.warnings().len(), 2);
+
+        let chained = merged.and_then(|v| Ok(Warnable::new(v * 2.).with_warning("third".into()))).unwrap();
+        let (value, warnings) = chained.into_parts();
+        assert_eq!(value, 6.);
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings.iter().zip(["first", "second", "third"].iter())
+            .all(|(warning, message)| warning.message.contains(message)));
+
+        let failed: Result<Warnable<f64>> = Warnable::new(1.).and_then(|_| Err("fatal".into()));
+        assert!(failed.is_err());
+    }
+
+    #[test]
+    fn test_serde() {
+        let warnable = Warnable(vec![1, 2], vec![proto::Error { message: "warning".to_string() }]);
+        let json = serde_json::to_string(&warnable).unwrap();
+        assert_eq!(json, r#"{"value":[1,2],"warnings":["warning"]}"#);
+        assert_eq!(serde_json::from_str::<Warnable<Vec<i64>>>(&json).unwrap(), warnable);
+    }
+}
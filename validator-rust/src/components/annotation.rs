@@ -0,0 +1,24 @@
+use crate::errors::*;
+
+use crate::components::Component;
+use std::collections::HashMap;
+use crate::base::{Value, ValueProperties, NodeProperties};
+use crate::proto;
+
+
+impl Component for proto::Annotation {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+    ) -> Result<ValueProperties> {
+        if self.note.is_empty() {
+            return Err("note: annotations may not be empty".into())
+        }
+
+        // annotations are a no-op; the data and its properties pass through unchanged
+        Ok(properties.get("data")
+            .ok_or("data: missing")?.clone())
+    }
+}
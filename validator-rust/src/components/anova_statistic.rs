@@ -0,0 +1,111 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::components::Component;
+use crate::components::dp_welch_t_test::GroupStatistics;
+use crate::base::{Value, ValueProperties, ArrayProperties, DataType};
+use crate::utilities::prepend;
+
+
+impl Component for proto::AnovaStatistic {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        // the statistic is postprocessing, and may only be computed from released moments
+        data_property.assert_is_releasable().map_err(prepend("data:"))?;
+        if data_property.data_type != DataType::F64 && data_property.data_type != DataType::I64 {
+            return Err("data: atomic type must be numeric".into())
+        }
+        if data_property.num_columns()? != 3 {
+            return Err("data: must contain the count, sum and sum of squares of each group".into())
+        }
+        if let Some(num_groups) = data_property.num_records {
+            if num_groups < 2 {
+                return Err("data: must contain at least two groups".into())
+            }
+        }
+
+        Ok(ArrayProperties {
+            num_records: Some(3),
+            num_columns: Some(1),
+            nullity: false,
+            releasable: true,
+            public_shape: true,
+            public_metadata: true,
+            c_stability: vec![1.],
+            aggregator: None,
+            nature: None,
+            data_type: DataType::F64,
+            dataset_id: data_property.dataset_id,
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 1
+        }.into())
+    }
+}
+
+/// One-way analysis of variance of the means of noisy groups.
+///
+/// Returns the F statistic, and its between-group and within-group degrees of freedom.
+/// Counts are floored at one, the total count at one more than the number of groups,
+/// and the sum of squares within each group at zero.
+pub fn anova_f_test(groups: &[GroupStatistics]) -> Result<(f64, f64, f64)> {
+    if groups.len() < 2 {
+        return Err("at least two groups are required".into())
+    }
+    let num_groups = groups.len() as f64;
+    let counts = groups.iter().map(|group| group.count.max(1.)).collect::<Vec<f64>>();
+    let total_count = counts.iter().sum::<f64>().max(num_groups + 1.);
+    let grand_mean = groups.iter().map(|group| group.sum).sum::<f64>() / total_count;
+
+    let (between, within) = groups.iter().zip(counts.iter())
+        .fold((0., 0.), |(between, within), (group, count)| (
+            between + count * (group.sum / count - grand_mean).powi(2),
+            within + (group.sum_squares - group.sum.powi(2) / count).max(0.)
+        ));
+    if within <= 0. {
+        return Err("the variance within every group is zero".into())
+    }
+
+    let (degrees_of_freedom_between, degrees_of_freedom_within) = (num_groups - 1., total_count - num_groups);
+    Ok((
+        (between / degrees_of_freedom_between) / (within / degrees_of_freedom_within),
+        degrees_of_freedom_between,
+        degrees_of_freedom_within
+    ))
+}
+
+
+#[cfg(test)]
+mod test_anova_statistic {
+    use crate::components::anova_statistic::anova_f_test;
+    use crate::components::dp_welch_t_test::GroupStatistics;
+
+    #[test]
+    fn test_anova_f_test() {
+        // records [1, 2, 3], [3, 4, 5] and [5, 6, 7]
+        let groups = [
+            GroupStatistics { count: 3., sum: 6., sum_squares: 14. },
+            GroupStatistics { count: 3., sum: 12., sum_squares: 50. },
+            GroupStatistics { count: 3., sum: 18., sum_squares: 110. }
+        ];
+        // between: 3 * (4 + 0 + 4) / 2 = 12, within: 6 / 6 = 1
+        let (statistic, degrees_of_freedom_between, degrees_of_freedom_within) = anova_f_test(&groups).unwrap();
+        assert!((statistic - 12.).abs() < 1e-12);
+        assert_eq!(degrees_of_freedom_between, 2.);
+        assert_eq!(degrees_of_freedom_within, 6.);
+
+        let constant = GroupStatistics { count: 3., sum: 3., sum_squares: 3. };
+        assert!(anova_f_test(&[constant, constant]).is_err());
+        assert!(anova_f_test(&[constant]).is_err());
+    }
+}
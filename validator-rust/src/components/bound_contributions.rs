@@ -0,0 +1,60 @@
+use crate::errors::*;
+
+use crate::components::Component;
+use std::collections::HashMap;
+use crate::base::{Value, ValueProperties, DataType};
+use crate::utilities::prepend;
+use crate::base;
+use crate::proto;
+use crate::components::transforms::propagate_binary_shape;
+
+impl Component for proto::BoundContributions {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        if self.max_contributions == 0 {
+            return Err("max_contributions: must be greater than zero".into())
+        }
+
+        let mut data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        data_property.assert_is_not_aggregated()?;
+
+        let identifier_property = properties.get("identifier")
+            .ok_or("identifier: missing")?.array()
+            .map_err(prepend("identifier:"))?.clone();
+        identifier_property.assert_is_not_aggregated()
+            .map_err(prepend("identifier:"))?;
+
+        if identifier_property.num_columns()? != 1 {
+            return Err("identifier: number of columns must be one".into())
+        }
+        if identifier_property.data_type == DataType::F64 {
+            return Err("identifier: may not be floats".into())
+        }
+
+        propagate_binary_shape(&data_property, &identifier_property)?;
+
+        // each individual now influences at most max_contributions records.
+        // The c-stability counts the records of each individual, or each record if the bound is unknown
+        let prior_contributions = data_property.max_contributions;
+        let max_contributions = prior_contributions.unwrap_or(self.max_contributions).min(self.max_contributions);
+        data_property.c_stability = data_property.c_stability.iter()
+            .map(|c_stability| c_stability / prior_contributions.unwrap_or(1) as f64 * max_contributions as f64)
+            .collect();
+        data_property.max_contributions = Some(max_contributions);
+
+        // the number of records is not known after dropping rows
+        data_property.num_records = None;
+
+        // This exists to prevent binary ops on non-conformable arrays from being approved
+        data_property.dataset_id = None;
+
+        // the data remains non-empty, because the first record of each individual is retained
+        Ok(data_property.into())
+    }
+}
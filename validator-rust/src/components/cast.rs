@@ -25,15 +25,16 @@ impl Component for proto::Cast {
         data_property.assert_is_not_aggregated()?;
         let prior_datatype = data_property.data_type.clone();
 
-        data_property.data_type = match self.atomic_type.to_lowercase().as_str() {
-            "float" => DataType::F64,
-            "real" => DataType::F64,
-            "int" => DataType::I64,
-            "integer" => DataType::I64,
-            "bool" => DataType::Bool,
-            "string" => DataType::Str,
-            "str" => DataType::Str,
-            _ => bail!("data type is not recognized. Must be one of \"float\", \"int\", \"bool\" or \"string\"")
+        data_property.data_type = parse_atomic_type(&self.atomic_type)?;
+
+        let coercion = get_coercion(&prior_datatype, &data_property.data_type);
+        match self.strictness.to_lowercase().as_str() {
+            "strict" => if coercion != Coercion::Lossless {
+                bail!("casting from {:?} to {:?} {}, which is not permitted when strict",
+                    prior_datatype, data_property.data_type, coercion.describe())
+            },
+            "lenient" | "" => (),
+            _ => bail!("strictness is not recognized. Must be one of \"lenient\" or \"strict\"")
         };
 
         let num_columns = data_property.num_columns()?;
@@ -177,6 +178,98 @@ impl Component for proto::Cast {
 
 }
 
+/// How a cast may alter the values of the data, known statically from the atomic types.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Coercion {
+    /// every value is represented exactly
+    Lossless,
+    /// distinct values may be mapped to the same value, but no value fails to cast
+    Lossy,
+    /// values that fail to cast become null
+    FailuresToNull,
+    /// values are rounded, and values that fail to cast are imputed between public bounds
+    FailuresImputed,
+}
+
+impl Coercion {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Coercion::Lossless => "represents every value exactly",
+            Coercion::Lossy => "may map distinct values to the same value",
+            Coercion::FailuresToNull => "may fail to cast values, which become null",
+            Coercion::FailuresImputed => "may round values or fail to cast values, which are imputed",
+        }
+    }
+}
+
+/// Parse the name of an atomic type, as accepted by the Cast component.
+pub fn parse_atomic_type(atomic_type: &str) -> Result<DataType> {
+    Ok(match atomic_type.to_lowercase().as_str() {
+        "float" => DataType::F64,
+        "real" => DataType::F64,
+        "int" => DataType::I64,
+        "integer" => DataType::I64,
+        "bool" => DataType::Bool,
+        "string" => DataType::Str,
+        "str" => DataType::Str,
+        _ => bail!("data type is not recognized. Must be one of \"float\", \"int\", \"bool\" or \"string\"")
+    })
+}
+
+/// Coercion applied when casting data of the prior atomic type to the target atomic type.
+pub fn get_coercion(prior: &DataType, target: &DataType) -> Coercion {
+    match (prior, target) {
+        (DataType::Str, DataType::F64) => Coercion::FailuresToNull,
+        (DataType::Str, DataType::I64) | (DataType::F64, DataType::I64) => Coercion::FailuresImputed,
+        // everything but the true label is mapped to false
+        (DataType::Str, DataType::Bool) | (DataType::F64, DataType::Bool) | (DataType::I64, DataType::Bool) => Coercion::Lossy,
+        _ => Coercion::Lossless
+    }
+}
+
+/// Data-quality summary of every cast in the graph, without revealing any values.
+///
+/// Each entry states the atomic types, the number of columns cast, and how the values may be coerced.
+pub fn coercion_summary(
+    graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, ValueProperties>,
+) -> Result<Vec<serde_json::Value>> {
+    let mut node_ids = graph.iter()
+        .filter_map(|(node_id, component)| match &component.variant {
+            Some(proto::component::Variant::Cast(_)) => Some(*node_id),
+            _ => None
+        })
+        .collect::<Vec<u32>>();
+    node_ids.sort();
+
+    node_ids.into_iter()
+        .map(|node_id| {
+            let component = graph.get(&node_id).ok_or("node: missing")?;
+            let cast = match &component.variant {
+                Some(proto::component::Variant::Cast(cast)) => cast,
+                _ => return Err("node must be a cast".into())
+            };
+            let data_property = component.arguments.get("data")
+                .and_then(|data_id| properties.get(data_id))
+                .ok_or_else(|| Error::from("data: missing"))?.array()?;
+            let target = parse_atomic_type(&cast.atomic_type)?;
+            let coercion = get_coercion(&data_property.data_type, &target);
+
+            Ok(serde_json::json!({
+                "nodeID": node_id,
+                "from": format!("{:?}", data_property.data_type),
+                "to": format!("{:?}", target),
+                "numColumns": data_property.num_columns,
+                "coercion": format!("{:?}", coercion),
+                "description": coercion.describe(),
+                "strictness": if cast.strictness.is_empty() { "lenient" } else { cast.strictness.as_str() },
+                "mayIntroduceNulls": coercion == Coercion::FailuresToNull,
+                "mayImpute": coercion == Coercion::FailuresImputed
+            }))
+        })
+        .collect()
+}
+
 macro_rules! make_expandable {
     ($variant:ident, $var_type:expr) => {
         impl Expandable for proto::$variant {
@@ -192,7 +285,8 @@ macro_rules! make_expandable {
                     computation_graph: hashmap![component_id.clone() => proto::Component {
                         arguments: component.arguments.clone(),
                         variant: Some(proto::component::Variant::Cast(proto::Cast {
-                            atomic_type: $var_type
+                            atomic_type: $var_type,
+                            strictness: "lenient".to_string()
                         })),
                         omit: false,
                         batch: component.batch,
@@ -211,3 +305,19 @@ make_expandable!(ToBool, "bool".to_string());
 make_expandable!(ToFloat, "float".to_string());
 make_expandable!(ToInt, "int".to_string());
 make_expandable!(ToString, "string".to_string());
+
+
+#[cfg(test)]
+mod test_cast {
+    use crate::components::cast::{get_coercion, Coercion};
+    use crate::base::DataType;
+
+    #[test]
+    fn test_get_coercion() {
+        assert_eq!(get_coercion(&DataType::Str, &DataType::F64), Coercion::FailuresToNull);
+        assert_eq!(get_coercion(&DataType::F64, &DataType::I64), Coercion::FailuresImputed);
+        assert_eq!(get_coercion(&DataType::I64, &DataType::Bool), Coercion::Lossy);
+        assert_eq!(get_coercion(&DataType::Bool, &DataType::I64), Coercion::Lossless);
+        assert_eq!(get_coercion(&DataType::F64, &DataType::Str), Coercion::Lossless);
+    }
+}
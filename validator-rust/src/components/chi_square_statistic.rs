@@ -0,0 +1,127 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::components::Component;
+use crate::base::{Value, ValueProperties, ArrayProperties, DataType};
+use crate::utilities::prepend;
+
+
+impl Component for proto::ChiSquareStatistic {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        // the statistic is postprocessing, and may only be computed from released counts
+        data_property.assert_is_releasable().map_err(prepend("data:"))?;
+        if data_property.data_type != DataType::F64 && data_property.data_type != DataType::I64 {
+            return Err("data: atomic type must be numeric".into())
+        }
+        if let Some(num_records) = data_property.num_records {
+            get_num_columns(num_records as usize, self.num_rows as usize)?;
+        }
+        if !self.noise_variance.is_finite() || self.noise_variance < 0. {
+            return Err("noise_variance: must be finite and non-negative".into())
+        }
+        squared_noise_ratio(&self.mechanism)?;
+
+        Ok(ArrayProperties {
+            num_records: Some(3),
+            num_columns: Some(1),
+            nullity: false,
+            releasable: true,
+            public_shape: true,
+            public_metadata: true,
+            c_stability: vec![1.],
+            aggregator: None,
+            nature: None,
+            data_type: DataType::F64,
+            dataset_id: data_property.dataset_id,
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 1
+        }.into())
+    }
+}
+
+/// Ratio of the variance of the squared noise to the squared variance of the noise, `Var(Z^2) / Var(Z)^2`.
+pub fn squared_noise_ratio(mechanism: &str) -> Result<f64> {
+    Ok(match mechanism.to_lowercase().as_str() {
+        "laplace" => 5.,
+        "gaussian" => 2.,
+        _ => return Err(format!("mechanism: {:?} is not recognized. Must be one of Laplace or Gaussian", mechanism).into())
+    })
+}
+
+fn get_num_columns(num_cells: usize, num_rows: usize) -> Result<usize> {
+    if num_rows < 2 || num_cells % num_rows != 0 || num_cells / num_rows < 2 {
+        return Err("data: the contingency table must have at least two rows and two columns".into())
+    }
+    Ok(num_cells / num_rows)
+}
+
+/// Pearson's chi-square statistic of independence of a noisy `num_rows`-row contingency table in row-major order,
+/// alongside the scale and degrees of freedom of its reference distribution.
+///
+/// Expected counts are estimated from the noisy margins, each floored at one.
+/// Under independence, noise of variance `v` on each cell inflates the mean of the statistic by `v * sum(1 / E)`,
+/// and its variance by `4 v sum((1 - p)(1 - q) / E) + r v^2 sum(1 / E^2)`,
+/// where `p` and `q` are the margin proportions of the cell and `r` is the [`squared_noise_ratio`] of the mechanism.
+/// The reference is the scaled chi-square distribution with the same mean and variance.
+/// Without noise, the reference is the usual chi-square distribution with `(rows - 1)(columns - 1)` degrees of freedom.
+pub fn chi_square_test(table: &[f64], num_rows: usize, noise_variance: f64, squared_noise_ratio: f64) -> Result<(f64, f64, f64)> {
+    let num_columns = get_num_columns(table.len(), num_rows)?;
+
+    let rows = table.chunks(num_columns)
+        .map(|row| row.iter().sum::<f64>().max(1.))
+        .collect::<Vec<f64>>();
+    let columns = (0..num_columns)
+        .map(|column| table.iter().skip(column).step_by(num_columns).sum::<f64>().max(1.))
+        .collect::<Vec<f64>>();
+    let total = table.iter().sum::<f64>().max(1.);
+
+    let (mut statistic, mut inflation, mut cross, mut quartic) = (0., 0., 0., 0.);
+    table.chunks(num_columns).zip(rows.iter()).for_each(|(row, row_total)|
+        row.iter().zip(columns.iter()).for_each(|(count, column_total)| {
+            let expected = row_total * column_total / total;
+            statistic += (count - expected).powi(2) / expected;
+            inflation += 1. / expected;
+            cross += (1. - row_total / total).max(0.) * (1. - column_total / total).max(0.) / expected;
+            quartic += expected.powi(-2);
+        }));
+
+    let degrees_of_freedom = ((num_rows - 1) * (num_columns - 1)) as f64;
+    let mean = degrees_of_freedom + noise_variance * inflation;
+    let variance = 2. * degrees_of_freedom + 4. * noise_variance * cross
+        + squared_noise_ratio * noise_variance.powi(2) * quartic;
+
+    Ok((statistic, variance / (2. * mean), 2. * mean.powi(2) / variance))
+}
+
+
+#[cfg(test)]
+mod test_chi_square_statistic {
+    use crate::components::chi_square_statistic::chi_square_test;
+
+    #[test]
+    fn test_chi_square_test() {
+        // without noise, the reference is the usual chi-square distribution
+        let (statistic, scale, degrees_of_freedom) = chi_square_test(&[10., 10., 10., 10., 10., 10.], 2, 0., 5.).unwrap();
+        assert_eq!(statistic, 0.);
+        assert!((scale - 1.).abs() < 1e-12);
+        assert!((degrees_of_freedom - 2.).abs() < 1e-12);
+
+        // noise inflates the reference distribution
+        let (_, scale, _) = chi_square_test(&[10., 10., 10., 10., 10., 10.], 2, 8., 5.).unwrap();
+        assert!(scale > 1.);
+
+        assert!(chi_square_test(&[1., 2., 3.], 3, 0., 5.).is_err());
+    }
+}
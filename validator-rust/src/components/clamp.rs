@@ -65,6 +65,8 @@ impl Component for proto::Clamp {
             };
             categories = categories.standardize(&num_columns)?;
             data_property.nature = Some(Nature::Categorical(NatureCategorical { categories }));
+            // the categories are public arguments, so the nature of the clamped data is public
+            data_property.public_metadata = true;
 
             return Ok(data_property.into());
         }
@@ -198,6 +200,11 @@ impl Component for proto::Clamp {
             _ => return Err("numeric clamping requires numeric data".into())
         }
 
+        // bounds that are public arguments make the nature of the clamped data public
+        if public_arguments.contains_key("lower") && public_arguments.contains_key("upper") {
+            data_property.public_metadata = true;
+        }
+
         Ok(data_property.into())
     }
 
@@ -0,0 +1,140 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::proto;
+
+use crate::components::{Component, Sensitivity, Expandable};
+use crate::components::contingency_table::{cell_sensitivity, num_cells};
+use crate::base::{Value, Jagged, NodeProperties, ArrayProperties, AggregatorProperties, SensitivitySpace, ValueProperties, DataType, NatureContinuous, Nature, Vector1DNull};
+use crate::utilities::{prepend, get_literal};
+
+
+impl Component for proto::ConfusionMatrix {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+    ) -> Result<ValueProperties> {
+        let (actual_property, predicted_property) = get_label_properties(properties)?;
+        let mut data_property = actual_property.clone();
+
+        data_property.num_records = Some(num_cells(&get_lengths(&actual_property, &predicted_property)?)?);
+        data_property.num_columns = Some(1);
+        data_property.c_stability = vec![get_contribution_bound(&actual_property, &predicted_property)];
+        data_property.dataset_id = None;
+        data_property.dimensionality = 1;
+
+        // save a snapshot of the state when aggregating
+        data_property.aggregator = Some(AggregatorProperties {
+            component: proto::component::Variant::ConfusionMatrix(self.clone()),
+            properties: properties.clone()
+        });
+
+        data_property.nature = Some(Nature::Continuous(NatureContinuous {
+            lower: Vector1DNull::I64(vec![Some(0)]),
+            upper: Vector1DNull::I64(vec![None]),
+        }));
+        data_property.data_type = DataType::I64;
+
+        Ok(data_property.into())
+    }
+}
+
+impl Expandable for proto::ConfusionMatrix {
+    /// Pass the categories of both columns, which are known statically
+    fn expand_component(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+        let (actual_property, predicted_property) = get_label_properties(properties)?;
+        let categories = match (actual_property.categories()?, predicted_property.categories()?) {
+            (Jagged::Bool(actual), Jagged::Bool(predicted)) => Jagged::Bool(actual.into_iter().chain(predicted).collect()),
+            (Jagged::I64(actual), Jagged::I64(predicted)) => Jagged::I64(actual.into_iter().chain(predicted).collect()),
+            (Jagged::Str(actual), Jagged::Str(predicted)) => Jagged::Str(actual.into_iter().chain(predicted).collect()),
+            _ => return Err("actual and predicted must have categories of the same, non-float atomic type".into())
+        };
+
+        // always overwrite the categories. These are not something a user may configure
+        let id_categories = *maximum_id + 1;
+        let (patch_node, categories_release) = get_literal(&Value::Jagged(categories), &component.batch)?;
+        computation_graph.insert(id_categories, patch_node);
+        releases.insert(id_categories, categories_release);
+
+        let mut matrix_component = component.clone();
+        matrix_component.arguments.insert("categories".to_string(), id_categories);
+        computation_graph.insert(*component_id, matrix_component);
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal: Vec::new()
+        })
+    }
+}
+
+impl Sensitivity for proto::ConfusionMatrix {
+    /// Each record falls into exactly one cell, so the matrix has the sensitivity of a contingency table over both columns.
+    fn compute_sensitivity(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &NodeProperties,
+        sensitivity_type: &SensitivitySpace
+    ) -> Result<Value> {
+        let (actual_property, predicted_property) = get_label_properties(properties)?;
+
+        cell_sensitivity(
+            privacy_definition,
+            num_cells(&get_lengths(&actual_property, &predicted_property)?)?,
+            get_contribution_bound(&actual_property, &predicted_property),
+            sensitivity_type)
+    }
+}
+
+/// Properties of the actual and predicted labels, each a single categorical column of the same atomic type.
+fn get_label_properties(properties: &NodeProperties) -> Result<(ArrayProperties, ArrayProperties)> {
+    let get_property = |name: &str| -> Result<ArrayProperties> {
+        let property = properties.get(name)
+            .ok_or_else(|| Error::from(format!("{}: missing", name)))?.array()
+            .map_err(prepend(&format!("{}:", name)))?.clone();
+        property.assert_is_not_aggregated()?;
+        if property.num_columns()? != 1 {
+            return Err(format!("{}: must contain a single column", name).into())
+        }
+        property.categories()
+            .map_err(|_| Error::from(format!("{}: categories must be known", name)))?;
+        Ok(property)
+    };
+    let (actual_property, predicted_property) = (get_property("actual")?, get_property("predicted")?);
+
+    if actual_property.data_type != predicted_property.data_type {
+        return Err("actual and predicted must have the same atomic type".into())
+    }
+    if let (Some(actual_num_records), Some(predicted_num_records)) = (actual_property.num_records, predicted_property.num_records) {
+        if actual_num_records != predicted_num_records {
+            return Err("actual and predicted must have the same number of records".into())
+        }
+    }
+    Ok((actual_property, predicted_property))
+}
+
+/// Number of categories of the actual, and then predicted labels.
+fn get_lengths(actual_property: &ArrayProperties, predicted_property: &ArrayProperties) -> Result<Vec<i64>> {
+    Ok(actual_property.categories()?.lengths()?.into_iter()
+        .chain(predicted_property.categories()?.lengths()?)
+        .collect())
+}
+
+fn get_contribution_bound(actual_property: &ArrayProperties, predicted_property: &ArrayProperties) -> f64 {
+    actual_property.c_stability.iter().chain(predicted_property.c_stability.iter())
+        .cloned().fold(1., f64::max)
+}
@@ -0,0 +1,158 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::proto;
+
+use crate::components::{Component, Sensitivity, Expandable};
+use crate::base::{Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, DataType, NatureContinuous, Nature, Vector1DNull};
+use crate::utilities::{prepend, get_literal};
+use ndarray::Array;
+
+
+impl Component for proto::ContingencyTable {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+    ) -> Result<ValueProperties> {
+        let mut data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+        }
+
+        let num_cells = num_cells(&data_property.categories()
+            .map_err(|_| Error::from("data: categories must be known for every column"))?.lengths()?)?;
+
+        data_property.num_records = Some(num_cells);
+        data_property.num_columns = Some(1);
+        data_property.dimensionality = 1;
+
+        // save a snapshot of the state when aggregating
+        data_property.aggregator = Some(AggregatorProperties {
+            component: proto::component::Variant::ContingencyTable(self.clone()),
+            properties: properties.clone()
+        });
+
+        data_property.nature = Some(Nature::Continuous(NatureContinuous {
+            lower: Vector1DNull::I64(vec![Some(0)]),
+            upper: Vector1DNull::I64(vec![None]),
+        }));
+        data_property.data_type = DataType::I64;
+
+        Ok(data_property.into())
+    }
+}
+
+impl Expandable for proto::ContingencyTable {
+    /// Pass the categories of each column, which are known statically
+    fn expand_component(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+        let categories = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.categories()?;
+
+        // always overwrite the categories. These are not something a user may configure
+        let id_categories = *maximum_id + 1;
+        let (patch_node, categories_release) = get_literal(&Value::Jagged(categories), &component.batch)?;
+        computation_graph.insert(id_categories, patch_node);
+        releases.insert(id_categories, categories_release);
+
+        let mut table_component = component.clone();
+        table_component.arguments.insert("categories".to_string(), id_categories);
+        computation_graph.insert(*component_id, table_component);
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal: Vec::new()
+        })
+    }
+}
+
+impl Sensitivity for proto::ContingencyTable {
+    /// Each record falls into exactly one cell, so the table has the sensitivity of a histogram over the product of the categories.
+    fn compute_sensitivity(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &NodeProperties,
+        sensitivity_type: &SensitivitySpace
+    ) -> Result<Value> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        data_property.assert_is_not_aggregated()?;
+
+        cell_sensitivity(
+            privacy_definition,
+            num_cells(&data_property.categories()?.lengths()?)?,
+            data_property.c_stability.iter().cloned().fold(1., f64::max),
+            sensitivity_type)
+    }
+}
+
+/// Sensitivity of each of the `num_cells` cells of a table into which each record falls exactly once.
+///
+/// As in the Histogram, the privacy usage is distributed evenly over all cells.
+pub fn cell_sensitivity(
+    privacy_definition: &proto::PrivacyDefinition,
+    num_cells: i64,
+    contribution_bound: f64,
+    sensitivity_type: &SensitivitySpace
+) -> Result<Value> {
+    use proto::privacy_definition::Neighboring;
+    let neighboring_type = Neighboring::from_i32(privacy_definition.neighboring)
+        .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+
+    let sensitivity = match sensitivity_type {
+        SensitivitySpace::KNorm(k) => contribution_bound * match (neighboring_type, k) {
+            // each added or removed record changes a single cell by one
+            (Neighboring::AddRemove, 1) | (Neighboring::AddRemove, 2) => 1.,
+            // a substituted record may move from one cell to another
+            (Neighboring::Substitute, 1) => 2.,
+            (Neighboring::Substitute, 2) => 2.0_f64.sqrt(),
+            _ => return Err("KNorm sensitivity is only supported in L1 and L2 spaces".into())
+        },
+        _ => return Err("table sensitivity is only implemented for KNorm".into())
+    };
+
+    Ok(Array::from_shape_vec(
+        vec![num_cells as usize, 1],
+        (0..num_cells).map(|_| sensitivity / num_cells as f64).collect())?.into_dyn().into())
+}
+
+/// Number of cells in the cross-tabulation of columns with the given numbers of categories.
+pub fn num_cells(lengths: &[i64]) -> Result<i64> {
+    if lengths.is_empty() {
+        return Err("data: must contain at least one column".into())
+    }
+    lengths.iter().try_fold(1i64, |total, length| total.checked_mul(*length))
+        .ok_or_else(|| "data: the cross-tabulation has too many cells".into())
+}
+
+
+#[cfg(test)]
+mod test_contingency_table {
+    use crate::components::contingency_table::num_cells;
+
+    #[test]
+    fn test_num_cells() {
+        assert_eq!(num_cells(&[2, 3, 4]).unwrap(), 24);
+        assert!(num_cells(&[]).is_err());
+        assert!(num_cells(&[std::i64::MAX, 2]).is_err());
+    }
+}
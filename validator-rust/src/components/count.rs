@@ -25,15 +25,23 @@ impl Component for proto::Count {
                 data_property.properties.values().first()
                     .ok_or_else(|| Error::from("dataframe must have at least one column"))?.array()?.to_owned()
             },
-            ValueProperties::Jagged(_) => return Err("Count is not implemented on jagged arrays".into())
+            ValueProperties::Jagged(_) => return Err("Count is not implemented on jagged arrays".into()),
+            ValueProperties::Scalar(_) => return Err("Count is not implemented on scalars".into())
         };
 
         if !data_property.releasable {
             data_property.assert_is_not_aggregated()?;
         }
 
+        let data_num_records = data_property.num_records;
+        // the count of data with a public shape is the public number of records
+        let count_is_public = data_property.publicness().shape && data_num_records.is_some();
+
+        // the count is a single value
         data_property.num_records = Some(1);
         data_property.num_columns = Some(1);
+        data_property.c_stability = vec![data_property.c_stability.iter().cloned().fold(1., f64::max)];
+        data_property.dimensionality = 0;
 
         // save a snapshot of the state when aggregating
         data_property.aggregator = Some(AggregatorProperties {
@@ -41,12 +49,14 @@ impl Component for proto::Count {
             properties: properties.clone()
         });
 
-        let data_num_records = data_property.num_records;
         data_property.nature = Some(Nature::Continuous(NatureContinuous {
             lower: Vector1DNull::I64(vec![data_num_records.or(Some(0))]),
             upper: Vector1DNull::I64(vec![data_num_records]),
         }));
         data_property.data_type = DataType::I64;
+        if count_is_public {
+            data_property.releasable = true;
+        }
 
         Ok(data_property.into())
     }
@@ -61,13 +71,13 @@ impl Sensitivity for proto::Count {
         sensitivity_type: &SensitivitySpace
     ) -> Result<Value> {
 
-        let num_records = match properties.get("data")
+        let (num_records, contributions) = match properties.get("data")
             .ok_or("data: missing")? {
             ValueProperties::Array(value) => {
                 value.assert_is_not_aggregated()?;
-                value.num_records
+                (value.num_records, value.c_stability.iter().cloned().fold(1., f64::max))
             },
-            ValueProperties::Hashmap(value) => value.num_records,
+            ValueProperties::Hashmap(value) => (value.num_records, 1.),
             _ => return Err("data: must not be hashmap".into())
         };
 
@@ -80,8 +90,8 @@ impl Sensitivity for proto::Count {
                 let neighboring_type = Neighboring::from_i32(privacy_definition.neighboring)
                     .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
 
-                // SENSITIVITY DERIVATIONS
-                let sensitivity: f64 = match (neighboring_type, num_records) {
+                // SENSITIVITY DERIVATIONS. An individual may contribute many records
+                let sensitivity: f64 = contributions * match (neighboring_type, num_records) {
                     // known N. Applies to any neighboring type.
                     (_, Some(_)) => 0.,
 
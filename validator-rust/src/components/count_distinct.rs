@@ -0,0 +1,78 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::proto;
+
+use crate::components::{Component, Sensitivity};
+use crate::base::{Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, DataType, NatureContinuous, Nature, Vector1DNull};
+use crate::utilities::prepend;
+use ndarray::arr1;
+
+
+impl Component for proto::CountDistinct {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+    ) -> Result<ValueProperties> {
+        let mut data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+        }
+
+        if data_property.num_columns()? != 1 {
+            return Err("data: must contain a single column".into())
+        }
+
+        let data_num_records = data_property.num_records;
+        // the count is a single value
+        data_property.num_records = Some(1);
+        data_property.dimensionality = 0;
+
+        // save a snapshot of the state when aggregating
+        data_property.aggregator = Some(AggregatorProperties {
+            component: proto::component::Variant::CountDistinct(self.clone()),
+            properties: properties.clone()
+        });
+
+        data_property.nature = Some(Nature::Continuous(NatureContinuous {
+            lower: Vector1DNull::I64(vec![Some(if data_property.is_not_empty { 1 } else { 0 })]),
+            upper: Vector1DNull::I64(vec![data_num_records]),
+        }));
+        data_property.data_type = DataType::I64;
+
+        Ok(data_property.into())
+    }
+}
+
+impl Sensitivity for proto::CountDistinct {
+    /// Each of an individual's records may add or remove at most one distinct value.
+    fn compute_sensitivity(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        properties: &NodeProperties,
+        sensitivity_type: &SensitivitySpace
+    ) -> Result<Value> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        data_property.assert_is_not_aggregated()?;
+
+        match sensitivity_type {
+            SensitivitySpace::KNorm(_k) => {
+                // there is a single column, so k has no effect on the sensitivity
+
+                // substituting a record removes at most one distinct value and adds at most one,
+                // so the count changes by at most one per record under either neighboring definition
+                let contribution_bound = data_property.c_stability.iter().cloned().fold(1., f64::max);
+                Ok(arr1(&[contribution_bound]).into_dyn().into())
+            },
+            _ => Err("CountDistinct sensitivity is only implemented for KNorm".into())
+        }
+    }
+}
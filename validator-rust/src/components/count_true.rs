@@ -0,0 +1,93 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::proto;
+
+use crate::components::{Component, Sensitivity};
+use crate::base::{Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, DataType, NatureContinuous, Nature, Vector1DNull};
+use crate::utilities::prepend;
+use ndarray::prelude::*;
+
+
+impl Component for proto::CountTrue {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+    ) -> Result<ValueProperties> {
+        let mut data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+        }
+
+        if data_property.data_type != DataType::Bool {
+            return Err("data: atomic type must be boolean".into())
+        }
+
+        let num_columns = data_property.num_columns()?;
+        let data_num_records = data_property.num_records;
+
+        // save a snapshot of the state when aggregating
+        data_property.aggregator = Some(AggregatorProperties {
+            component: proto::component::Variant::CountTrue(self.clone()),
+            properties: properties.clone(),
+        });
+
+        // each column is a count, bounded by the number of records
+        data_property.num_records = Some(1);
+        data_property.nature = Some(Nature::Continuous(NatureContinuous {
+            lower: Vector1DNull::I64(vec![Some(0); num_columns as usize]),
+            upper: Vector1DNull::I64(vec![data_num_records; num_columns as usize]),
+        }));
+        data_property.data_type = DataType::I64;
+
+        Ok(data_property.into())
+    }
+}
+
+impl Sensitivity for proto::CountTrue {
+    /// Each of an individual's records changes the count of true values in a column by at most one,
+    /// under either neighboring definition.
+    fn compute_sensitivity(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &NodeProperties,
+        sensitivity_type: &SensitivitySpace,
+    ) -> Result<Value> {
+        // a record adds or removes at most one true value, and a substitution flips at most one,
+        // so both neighboring definitions share the same sensitivity
+        use proto::privacy_definition::Neighboring;
+        Neighboring::from_i32(privacy_definition.neighboring)
+            .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+        data_property.assert_non_null()?;
+
+        match sensitivity_type {
+            SensitivitySpace::KNorm(_k) => {
+                // sensitivities are per-column, so k has no effect on the sensitivity
+                let num_columns = data_property.num_columns()? as usize;
+                let row_sensitivity = match data_property.c_stability.len() {
+                    // an individual may contribute many records to a column
+                    length if length == num_columns => data_property.c_stability.clone(),
+                    _ => vec![data_property.c_stability.iter().cloned().fold(1., f64::max); num_columns]
+                };
+
+                let mut array_sensitivity = Array::from(row_sensitivity).into_dyn();
+                array_sensitivity.insert_axis_inplace(Axis(0));
+
+                Ok(array_sensitivity.into())
+            },
+            _ => Err("CountTrue sensitivity is only implemented for KNorm".into())
+        }
+    }
+}
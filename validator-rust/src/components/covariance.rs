@@ -78,6 +78,9 @@ impl Component for proto::Covariance {
             });
 
             left_property.nature = None;
+            let publicness = left_property.publicness().and(&right_property.publicness());
+            left_property.public_shape = publicness.shape;
+            left_property.public_metadata = publicness.metadata;
             left_property.releasable = left_property.releasable && right_property.releasable;
 
             left_property.num_records = Some(1);
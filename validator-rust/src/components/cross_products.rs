@@ -0,0 +1,168 @@
+use crate::errors::*;
+
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+
+use crate::components::{Component, Sensitivity};
+use crate::base::{Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, DataType};
+use crate::utilities::prepend;
+use ndarray::prelude::*;
+
+
+impl Component for proto::CrossProducts {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let mut data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let target_property = properties.get("target")
+            .ok_or("target: missing")?.array()
+            .map_err(prepend("target:"))?.clone();
+
+        if data_property.data_type != DataType::F64 {
+            return Err("data: atomic type must be float".into());
+        }
+        if target_property.data_type != DataType::F64 {
+            return Err("target: atomic type must be float".into());
+        }
+        if target_property.num_columns()? != 1 {
+            return Err("target: must contain one column".into());
+        }
+        data_property.assert_is_not_empty()?;
+        target_property.assert_is_not_empty()?;
+
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+        }
+        if !target_property.releasable {
+            target_property.assert_is_not_aggregated()?;
+        }
+
+        // save a snapshot of the state when aggregating
+        data_property.aggregator = Some(AggregatorProperties {
+            component: proto::component::Variant::CrossProducts(self.clone()),
+            properties: properties.clone(),
+        });
+
+        let num_regressors = data_property.num_columns()? + if self.intercept { 1 } else { 0 };
+        let num_statistics = num_regressors * (num_regressors + 3) / 2;
+
+        let c_stability = data_property.c_stability.iter()
+            .chain(target_property.c_stability.iter())
+            .cloned().fold(1., f64::max);
+
+        data_property.num_records = Some(1);
+        data_property.num_columns = Some(num_statistics);
+        data_property.c_stability = vec![c_stability; num_statistics as usize];
+        let publicness = data_property.publicness().and(&target_property.publicness());
+        data_property.public_shape = publicness.shape;
+        data_property.public_metadata = publicness.metadata;
+        data_property.releasable = data_property.releasable && target_property.releasable;
+        data_property.nature = None;
+
+        Ok(data_property.into())
+    }
+}
+
+impl Sensitivity for proto::CrossProducts {
+    /// A single record changes each cross product by at most the range of the product of its two factors,
+    /// or by the largest magnitude of the product when a record is added or removed.
+    fn compute_sensitivity(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &NodeProperties,
+        sensitivity_type: &SensitivitySpace,
+    ) -> Result<Value> {
+        match sensitivity_type {
+            SensitivitySpace::KNorm(k) => {
+                if k != &1 && k != &2 {
+                    return Err("KNorm sensitivity is only supported in L1 and L2 spaces".into())
+                }
+
+                let data_property = properties.get("data")
+                    .ok_or("data: missing")?.array()
+                    .map_err(prepend("data:"))?.clone();
+                data_property.assert_is_not_aggregated()?;
+                data_property.assert_non_null()?;
+
+                let target_property = properties.get("target")
+                    .ok_or("target: missing")?.array()
+                    .map_err(prepend("target:"))?.clone();
+                target_property.assert_is_not_aggregated()?;
+                target_property.assert_non_null()?;
+
+                if let (Some(data_n), Some(target_n)) = (data_property.num_records, target_property.num_records) {
+                    if data_n != target_n {
+                        return Err("data and target must have the same number of records".into())
+                    }
+                }
+
+                // bounds of each column of Z, with the intercept column fixed at one
+                let mut bounds = if self.intercept { vec![(1., 1.)] } else { Vec::new() };
+                bounds.extend(data_property.lower_f64()?.into_iter()
+                    .zip(data_property.upper_f64()?.into_iter()));
+                let target_bounds = (target_property.lower_f64()?[0], target_property.upper_f64()?[0]);
+
+                use proto::privacy_definition::Neighboring;
+                let neighboring_type = Neighboring::from_i32(privacy_definition.neighboring)
+                    .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+
+                let sensitivity = |(lower, upper): (f64, f64)| match neighboring_type {
+                    Neighboring::AddRemove => lower.abs().max(upper.abs()),
+                    Neighboring::Substitute => upper - lower
+                };
+
+                // upper triangle of Z^T Z, followed by Z^T y
+                let row_sensitivity = (0..bounds.len())
+                    .flat_map(|i| (i..bounds.len()).map(move |j| (i, j)))
+                    .map(|(i, j)| sensitivity(product_bounds(bounds[i], bounds[j], i == j)))
+                    .chain(bounds.iter()
+                        .map(|bound| sensitivity(product_bounds(*bound, target_bounds, false))))
+                    .collect::<Vec<f64>>();
+
+                let mut array_sensitivity = Array::from(row_sensitivity).into_dyn();
+                array_sensitivity.insert_axis_inplace(Axis(0));
+
+                Ok(array_sensitivity.into())
+            }
+            _ => Err("CrossProducts sensitivity is only implemented for KNorm".into())
+        }
+    }
+}
+
+/// Lower and upper bounds on the product of two bounded values.
+///
+/// When `square` is set, the two values are the same, so the product is non-negative.
+pub fn product_bounds(left: (f64, f64), right: (f64, f64), square: bool) -> (f64, f64) {
+    let corners = [left.0 * right.0, left.0 * right.1, left.1 * right.0, left.1 * right.1];
+    let lower = corners.iter().cloned().fold(f64::INFINITY, f64::min);
+    let upper = corners.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+    let lower = match square {
+        true if left.0 <= 0. && 0. <= left.1 => 0.,
+        true => (left.0 * left.0).min(left.1 * left.1),
+        false => lower
+    };
+    (lower, upper)
+}
+
+
+#[cfg(test)]
+mod test_cross_products {
+    use crate::components::cross_products::product_bounds;
+
+    #[test]
+    fn test_product_bounds() {
+        assert_eq!(product_bounds((-1., 2.), (3., 4.), false), (-4., 8.));
+        assert_eq!(product_bounds((-1., 2.), (-1., 2.), false), (-2., 4.));
+        assert_eq!(product_bounds((-1., 2.), (-1., 2.), true), (0., 4.));
+        assert_eq!(product_bounds((2., 3.), (2., 3.), true), (4., 9.));
+    }
+}
@@ -0,0 +1,205 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::components::{Component, Report};
+use crate::components::transforms::propagate_binary_shape;
+use crate::base::{Value, ValueProperties, ArrayProperties, DataType, NodeProperties};
+use crate::utilities::prepend;
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, Accuracy, value_to_json};
+use ndarray::ArrayD;
+
+
+/// Algebraic operations available to derived metrics.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operation {
+    Sum,
+    Difference,
+    Ratio,
+    PercentChange,
+}
+
+impl Operation {
+    pub fn parse(operation: &str) -> Result<Operation> {
+        Ok(match operation.to_lowercase().as_str() {
+            "sum" => Operation::Sum,
+            "difference" => Operation::Difference,
+            "ratio" => Operation::Ratio,
+            "percent_change" => Operation::PercentChange,
+            _ => bail!("operation: {:?} is not recognized. Must be one of sum, difference, ratio or percent_change", operation)
+        })
+    }
+
+    pub fn evaluate(&self, left: f64, right: f64) -> f64 {
+        match self {
+            Operation::Sum => left + right,
+            Operation::Difference => left - right,
+            Operation::Ratio => left / right,
+            Operation::PercentChange => (right - left) / left * 100.
+        }
+    }
+
+    /// First-order (delta method) standard error of the operation, assuming independent arguments.
+    pub fn standard_error(&self, left: f64, right: f64, left_error: f64, right_error: f64) -> f64 {
+        match self {
+            Operation::Sum | Operation::Difference =>
+                (left_error.powi(2) + right_error.powi(2)).sqrt(),
+            // d(l/r) = (1/r) dl - (l/r^2) dr
+            Operation::Ratio =>
+                ((left_error / right).powi(2) + (left * right_error / right.powi(2)).powi(2)).sqrt(),
+            // 100 * (r/l - 1), so d = (100/l) dr - (100r/l^2) dl
+            Operation::PercentChange =>
+                100. * ((right_error / left).powi(2) + (right * left_error / left.powi(2)).powi(2)).sqrt()
+        }
+    }
+}
+
+
+impl Component for proto::DerivedMetric {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        Operation::parse(&self.operation)?;
+
+        let left_property = properties.get("left")
+            .ok_or("left: missing")?.array()
+            .map_err(prepend("left:"))?.clone();
+        let right_property = properties.get("right")
+            .ok_or("right: missing")?.array()
+            .map_err(prepend("right:"))?.clone();
+
+        // derived metrics are postprocessing, and may only be computed from released values
+        left_property.assert_is_releasable().map_err(prepend("left:"))?;
+        right_property.assert_is_releasable().map_err(prepend("right:"))?;
+
+        for (name, property) in &[("left", &left_property), ("right", &right_property)] {
+            if property.data_type != DataType::F64 && property.data_type != DataType::I64 {
+                return Err(format!("{}: atomic type must be numeric", name).into())
+            }
+        }
+
+        let (num_columns, num_records) = propagate_binary_shape(&left_property, &right_property)?;
+
+        Ok(ArrayProperties {
+            num_records,
+            num_columns: Some(num_columns),
+            nullity: true,
+            releasable: true,
+            public_shape: true,
+            public_metadata: true,
+            c_stability: (0..num_columns).map(|_| 1.).collect(),
+            aggregator: None,
+            nature: None,
+            data_type: DataType::F64,
+            dataset_id: left_property.dataset_id,
+            is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
+            max_contributions: None,
+            dimensionality: left_property.dimensionality
+                .max(right_property.dimensionality)
+        }.into())
+    }
+}
+
+impl Report for proto::DerivedMetric {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        public_arguments: &HashMap<String, Value>,
+        _properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let operation = Operation::parse(&self.operation)?;
+
+        let get_f64 = |name: &str| -> Result<Option<ArrayD<f64>>> {
+            public_arguments.get(name)
+                .map(get_f64_array).transpose()
+                .map_err(|e| Error::from(format!("{}: {}", name, e)))
+        };
+
+        let released = get_f64_array(release)?;
+        let shape = released.shape().to_vec();
+
+        // propagate accuracy only when the standard errors of both arguments are known
+        let accuracy = match (get_f64("left")?, get_f64("right")?,
+                              get_f64("left_standard_error")?, get_f64("right_standard_error")?) {
+            (Some(left), Some(right), Some(left_error), Some(right_error)) => {
+                let broadcast = |array: &ArrayD<f64>| -> Result<ArrayD<f64>> {
+                    Ok(array.broadcast(shape.clone())
+                        .ok_or_else(|| Error::from("arguments could not be broadcast to the shape of the release"))?
+                        .to_owned())
+                };
+                let (left, right) = (broadcast(&left)?, broadcast(&right)?);
+                let (left_error, right_error) = (broadcast(&left_error)?, broadcast(&right_error)?);
+
+                // report the widest 95% interval half-width among the elements
+                let standard_error = left.iter().zip(right.iter())
+                    .zip(left_error.iter().zip(right_error.iter()))
+                    .map(|((l, r), (le, re))| operation.standard_error(*l, *r, *le, *re))
+                    .fold(0., f64::max);
+                Some(Accuracy {
+                    accuracy_value: 1.959_964 * standard_error,
+                    alpha: 0.05
+                })
+            },
+            _ => None
+        };
+
+        Ok(Some(vec![JSONRelease {
+            description: "Derived metric information".to_string(),
+            statistic: "DerivedMetric".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: value_to_json(&release)?,
+            privacy_loss: serde_json::Value::Null,
+            accuracy,
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: true,
+            algorithm_info: AlgorithmInfo {
+                name: "".to_string(),
+                cite: "".to_string(),
+                mechanism: "".to_string(),
+                argument: serde_json::json!({
+                    "operation": self.operation,
+                    "accuracy": "delta method, assuming independent arguments"
+                })
+            }
+        }]))
+    }
+}
+
+fn get_f64_array(value: &Value) -> Result<ArrayD<f64>> {
+    match value.array()? {
+        base::Array::F64(array) => Ok(array.clone()),
+        base::Array::I64(array) => Ok(array.mapv(|v| v as f64)),
+        _ => Err("atomic type must be numeric".into())
+    }
+}
+
+
+#[cfg(test)]
+mod test_derived_metric {
+    use crate::components::derived_metric::Operation;
+
+    #[test]
+    fn test_operations() {
+        assert_eq!(Operation::parse("percent_change").unwrap().evaluate(50., 75.), 50.);
+        assert_eq!(Operation::parse("Ratio").unwrap().evaluate(3., 4.), 0.75);
+        assert!(Operation::parse("product").is_err());
+    }
+
+    #[test]
+    fn test_standard_error() {
+        assert_eq!(Operation::Difference.standard_error(10., 4., 3., 4.), 5.);
+        // a ratio of exact values is exact
+        assert_eq!(Operation::Ratio.standard_error(10., 4., 0., 0.), 0.);
+        // relative errors add in quadrature for a ratio
+        let error = Operation::Ratio.standard_error(10., 5., 0.3, 0.2);
+        assert!((error - 2. * (0.03_f64.powi(2) + 0.04_f64.powi(2)).sqrt()).abs() < 1e-12);
+    }
+}
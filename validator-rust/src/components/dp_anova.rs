@@ -0,0 +1,107 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::hashmap;
+use crate::components::{Expandable, Report};
+use crate::base::{NodeProperties, Value};
+use crate::utilities::prepend;
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
+use statrs::distribution::{FisherSnedecor, Univariate};
+
+
+impl Expandable for proto::DpAnova {
+    fn expand_component(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        _properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut maximum_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+
+        let get_argument = |name: &str| component.arguments.get(name).cloned()
+            .ok_or_else(|| Error::from(format!("{} must be provided as an argument", name)));
+
+        // noisy moments of each group
+        maximum_id += 1;
+        let id_moments = maximum_id;
+        computation_graph.insert(id_moments, proto::Component {
+            arguments: hashmap![
+                "data".to_owned() => get_argument("data")?,
+                "by".to_owned() => get_argument("by")?
+            ],
+            variant: Some(proto::component::Variant::DpGroupMoments(proto::DpGroupMoments {
+                privacy_usage: self.privacy_usage.clone()
+            })),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // test statistic, as postprocessing
+        computation_graph.insert(*component_id, proto::Component {
+            arguments: hashmap!["data".to_owned() => id_moments],
+            variant: Some(proto::component::Variant::AnovaStatistic(proto::AnovaStatistic {})),
+            omit: component.omit,
+            batch: component.batch,
+        });
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases: HashMap::new(),
+            traversal: vec![id_moments],
+        })
+    }
+}
+
+impl Report for proto::DpAnova {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let categories = properties.get("by")
+            .ok_or("by: missing")?.array()
+            .map_err(prepend("by:"))?.categories()?;
+
+        let (statistic, degrees_of_freedom_between, degrees_of_freedom_within) = match release.array()?.f64()?.iter().collect::<Vec<&f64>>().as_slice() {
+            [statistic, between, within] => (**statistic, **between, **within),
+            _ => return Err("release must contain the statistic and its two degrees of freedom".into())
+        };
+        let reference = FisherSnedecor::new(degrees_of_freedom_between, degrees_of_freedom_within)
+            .map_err(|e| Error::from(format!("reference distribution: {}", e)))?;
+
+        let privacy_usage: Vec<serde_json::Value> = self.privacy_usage.iter()
+            .map(privacy_usage_to_json).collect();
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPAnova".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json![privacy_usage],
+            accuracy: None,
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "One-way analysis of variance on noisy sufficient statistics".to_string(),
+                cite: "".to_string(),
+                mechanism: "Laplace".to_string(),
+                argument: serde_json::json!({
+                    "groups": value_to_json(&Value::Jagged(categories))?,
+                    "p_value": 1. - reference.cdf(statistic),
+                    "reference_distribution": "F distribution, assuming the noisy moments are exact"
+                }),
+            },
+        }]))
+    }
+}
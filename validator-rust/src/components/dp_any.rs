@@ -0,0 +1,121 @@
+use crate::errors::*;
+
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::hashmap;
+use crate::components::{Expandable, Report};
+
+use crate::base::{NodeProperties, Value};
+use crate::utilities::json::{JSONRelease, privacy_usage_to_json, AlgorithmInfo, value_to_json};
+use crate::utilities::get_literal;
+
+
+impl Expandable for proto::DpAny {
+    fn expand_component(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        _properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut maximum_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+        // noisy count of true values
+        maximum_id += 1;
+        let id_dp_count_true = maximum_id;
+        computation_graph.insert(id_dp_count_true, proto::Component {
+            arguments: hashmap!["data".to_owned() => *component.arguments.get("data")
+                .ok_or_else(|| Error::from("data must be provided as an argument"))?],
+            variant: Some(proto::component::Variant::DpCountTrue(proto::DpCountTrue {
+                mechanism: self.mechanism.clone(),
+                privacy_usage: self.privacy_usage.clone(),
+            })),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // everything below operates on released values, and is postprocessing
+
+        // the geometric mechanism releases integer counts, which exceed the threshold whenever they exceed its floor
+        let (id_count, threshold, traversal) = match self.mechanism.to_lowercase().as_str() {
+            "simplegeometric" => (id_dp_count_true, Value::from(self.threshold.floor() as i64), vec![id_dp_count_true]),
+            _ => {
+                maximum_id += 1;
+                let id_cast = maximum_id;
+                computation_graph.insert(id_cast, proto::Component {
+                    arguments: hashmap!["data".to_owned() => id_dp_count_true],
+                    variant: Some(proto::component::Variant::Cast(proto::Cast {
+                        atomic_type: "float".to_string(),
+                        strictness: "lenient".to_string()
+                    })),
+                    omit: true,
+                    batch: component.batch,
+                });
+                (id_cast, Value::from(self.threshold), vec![id_dp_count_true, id_cast])
+            }
+        };
+
+        maximum_id += 1;
+        let id_threshold = maximum_id;
+        let (patch_node, release) = get_literal(&threshold, &component.batch)?;
+        computation_graph.insert(id_threshold, patch_node);
+        releases.insert(id_threshold, release);
+
+        computation_graph.insert(*component_id, proto::Component {
+            arguments: hashmap![
+                "left".to_owned() => id_count,
+                "right".to_owned() => id_threshold
+            ],
+            variant: Some(proto::component::Variant::GreaterThan(proto::GreaterThan {})),
+            omit: false,
+            batch: component.batch,
+        });
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal,
+        })
+    }
+}
+
+impl Report for proto::DpAny {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        _properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPAny".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: value_to_json(&release)?,
+            privacy_loss: serde_json::json![self.privacy_usage.iter().map(privacy_usage_to_json).collect::<Vec<serde_json::Value>>()],
+            accuracy: None,
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "".to_string(),
+                cite: "".to_string(),
+                mechanism: self.mechanism.clone(),
+                argument: serde_json::json!({
+                    "postprocessing": {
+                        "operation": format!("count_true > {}", self.threshold),
+                        "privacy_loss": 0
+                    }
+                }),
+            },
+        }]))
+    }
+}
@@ -0,0 +1,246 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::components::{Component, Expandable, Report};
+use crate::components::dp_welch_t_test::{get_sensitivity, get_test_epsilon};
+use crate::base::{Value, ValueProperties, ArrayProperties, DataType, NodeProperties};
+use crate::utilities::{prepend, get_literal};
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
+
+
+impl Component for proto::DpAuc {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let labels_property = properties.get("labels")
+            .ok_or("labels: missing")?.array()
+            .map_err(prepend("labels:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+        data_property.assert_non_null()?;
+        if data_property.num_columns()? != 1 {
+            return Err("data: must contain a single column".into())
+        }
+        get_score_bounds(&data_property)?;
+
+        labels_property.assert_is_not_aggregated()?;
+        labels_property.assert_non_null()?;
+        if labels_property.num_columns()? != 1 {
+            return Err("labels: must contain a single column".into())
+        }
+        if labels_property.data_type != DataType::Bool {
+            return Err("labels: atomic type must be boolean".into())
+        }
+        if let (Some(data_num_records), Some(labels_num_records)) = (data_property.num_records, labels_property.num_records) {
+            if data_num_records != labels_num_records {
+                return Err("data and labels must have the same number of records".into())
+            }
+        }
+        if self.num_bins == 0 {
+            return Err("num_bins: must be greater than zero".into())
+        }
+        get_test_epsilon(&self.privacy_usage)?;
+
+        Ok(ArrayProperties {
+            num_records: Some(self.num_bins as i64 + 1),
+            num_columns: Some(3),
+            nullity: false,
+            releasable: true,
+            public_shape: true,
+            public_metadata: true,
+            c_stability: vec![1.; 3],
+            aggregator: None,
+            nature: None,
+            data_type: DataType::F64,
+            dataset_id: None,
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 2
+        }.into())
+    }
+}
+
+impl Expandable for proto::DpAuc {
+    fn expand_component(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut current_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let (lower, upper) = get_score_bounds(&data_property)?;
+
+        // always overwrite the bounds and sensitivity. These are not something a user may configure
+        let mut auc_component = component.clone();
+        for (name, value) in vec![
+            ("lower", Value::from(lower)),
+            ("upper", Value::from(upper)),
+            ("sensitivity", Value::from(get_sensitivity(privacy_definition, &data_property)?))
+        ] {
+            current_id += 1;
+            let (patch_node, release) = get_literal(&value, &component.batch)?;
+            computation_graph.insert(current_id, patch_node);
+            releases.insert(current_id, release);
+            auc_component.arguments.insert(name.to_string(), current_id);
+        }
+        computation_graph.insert(*component_id, auc_component);
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal: Vec::new()
+        })
+    }
+}
+
+impl Report for proto::DpAuc {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let (lower, upper) = get_score_bounds(&data_property)?;
+
+        let curve = release.array()?.f64()?.outer_iter()
+            .map(|row| match row.as_slice() {
+                Some([threshold, false_positive_rate, true_positive_rate]) =>
+                    Ok([*threshold, *false_positive_rate, *true_positive_rate]),
+                _ => Err("release must contain a threshold, false positive rate and true positive rate in each row".into())
+            })
+            .collect::<Result<Vec<[f64; 3]>>>()?;
+
+        let privacy_usage: Vec<serde_json::Value> = self.privacy_usage.iter()
+            .map(privacy_usage_to_json).collect();
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPAuc".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json![privacy_usage],
+            accuracy: None,
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "ROC curve from noisy histograms of the scores of each class".to_string(),
+                cite: "".to_string(),
+                mechanism: "Laplace".to_string(),
+                argument: serde_json::json!({
+                    "auc": area_under_curve(&curve),
+                    "num_bins": self.num_bins,
+                    "lower": lower,
+                    "upper": upper,
+                    "columns": ["threshold", "false_positive_rate", "true_positive_rate"]
+                }),
+            },
+        }]))
+    }
+}
+
+/// ROC curve of histograms of the scores of the positive and negative classes, over equal-width bins between the bounds.
+///
+/// Returns a row of `[threshold, false positive rate, true positive rate]` for each edge, from the upper bound down to the lower bound.
+/// Noisy counts are floored at zero, so the curve is monotone, and each class is floored at a total of one record.
+pub fn roc_curve(positives: &[f64], negatives: &[f64], lower: f64, upper: f64) -> Result<Vec<[f64; 3]>> {
+    if positives.len() != negatives.len() || positives.is_empty() {
+        return Err("both classes must have the same, non-zero number of bins".into())
+    }
+    let num_bins = positives.len();
+    let positives = positives.iter().map(|count| count.max(0.)).collect::<Vec<f64>>();
+    let negatives = negatives.iter().map(|count| count.max(0.)).collect::<Vec<f64>>();
+    let total_positives = positives.iter().sum::<f64>().max(1.);
+    let total_negatives = negatives.iter().sum::<f64>().max(1.);
+
+    // records in bins at or above each edge are classified as positive
+    let (mut true_positives, mut false_positives) = (0., 0.);
+    Ok((0..=num_bins).rev()
+        .map(|edge| {
+            if edge < num_bins {
+                true_positives += positives[edge];
+                false_positives += negatives[edge];
+            }
+            [
+                lower + (upper - lower) * edge as f64 / num_bins as f64,
+                false_positives / total_negatives,
+                true_positives / total_positives
+            ]
+        })
+        .collect())
+}
+
+/// Area under a ROC curve with rows of `[threshold, false positive rate, true positive rate]`, by the trapezoidal rule.
+pub fn area_under_curve(curve: &[[f64; 3]]) -> f64 {
+    curve.windows(2)
+        .map(|pair| (pair[1][1] - pair[0][1]) * (pair[0][2] + pair[1][2]) / 2.)
+        .sum()
+}
+
+/// Bounds of the scores. Bounds must be public, so that the edges of the bins do not depend on the data.
+fn get_score_bounds(data_property: &ArrayProperties) -> Result<(f64, f64)> {
+    let (lower, upper) = match data_property.data_type {
+        DataType::F64 => (data_property.lower_f64(), data_property.upper_f64()),
+        DataType::I64 => (
+            data_property.lower_i64().map(|lower| lower.into_iter().map(|v| v as f64).collect()),
+            data_property.upper_i64().map(|upper| upper.into_iter().map(|v| v as f64).collect())),
+        _ => return Err("data: atomic type must be numeric".into())
+    };
+    let (lower, upper) = match (lower, upper) {
+        (Ok(lower), Ok(upper)) => (lower[0], upper[0]),
+        _ => return Err("data: score bounds must be public. Clamp the scores to public bounds".into())
+    };
+    if lower.partial_cmp(&upper) != Some(std::cmp::Ordering::Less) {
+        return Err("data: lower bound must be less than the upper bound".into())
+    }
+    Ok((lower, upper))
+}
+
+
+#[cfg(test)]
+mod test_dp_auc {
+    use crate::components::dp_auc::{roc_curve, area_under_curve};
+
+    #[test]
+    fn test_roc_curve() {
+        // perfectly separated classes
+        let curve = roc_curve(&[0., 0., 5.], &[5., 0., 0.], 0., 3.).unwrap();
+        assert_eq!(curve.len(), 4);
+        assert_eq!(curve[0], [3., 0., 0.]);
+        assert_eq!(curve[3], [0., 1., 1.]);
+        assert!((area_under_curve(&curve) - 1.).abs() < 1e-12);
+
+        // indistinguishable classes
+        let curve = roc_curve(&[2., 2.], &[2., 2.], 0., 1.).unwrap();
+        assert!((area_under_curve(&curve) - 0.5).abs() < 1e-12);
+
+        // negative noisy counts are floored
+        let curve = roc_curve(&[-1., 1.], &[1., -1.], 0., 1.).unwrap();
+        assert!((area_under_curve(&curve) - 1.).abs() < 1e-12);
+
+        assert!(roc_curve(&[1.], &[1., 1.], 0., 1.).is_err());
+    }
+}
@@ -0,0 +1,272 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::hashmap;
+use crate::components::{Expandable, Report};
+use ndarray::arr0;
+
+use crate::base::{NodeProperties, Value, Array, Jagged, DataType};
+use crate::components::dp_quantiles::get_bounds;
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, Accuracy, privacy_usage_to_json, value_to_json};
+use crate::utilities::{prepend, get_literal, get_epsilon, get_delta, privacy_usage_check};
+
+/// Confidence level of the simultaneous accuracy bound in the report.
+const ACCURACY_ALPHA: f64 = 0.05;
+
+
+impl Expandable for proto::DpCdf {
+    fn expand_component(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut maximum_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+        let data_id = component.arguments.get("data")
+            .ok_or_else(|| Error::from("data is a required argument to DPCdf"))?.to_owned();
+
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        if data_property.num_columns()? != 1 {
+            return Err("data: must contain one column".into())
+        }
+        let (lower, upper) = get_bounds(data_property)?;
+        check_grid(&self.grid, lower[0], upper[0])?;
+
+        match self.mechanism.to_lowercase().as_str() {
+            "laplace" | "gaussian" => (),
+            _ => return Err("mechanism: must be one of Laplace or Gaussian".into())
+        }
+
+        // bins (lower, g_1], (g_1, g_2], ..., (g_m, upper]
+        let edges = std::iter::once(lower[0])
+            .chain(self.grid.iter().cloned())
+            .chain(std::iter::once(upper[0]))
+            .collect::<Vec<f64>>();
+        let edges = match data_property.data_type {
+            DataType::F64 => Jagged::F64(vec![Some(edges)]),
+            DataType::I64 => {
+                if self.grid.iter().any(|point| point.fract() != 0.) {
+                    return Err("grid: points must be integers when the data is integer".into())
+                }
+                Jagged::I64(vec![Some(edges.into_iter().map(|v| v as i64).collect())])
+            },
+            _ => return Err("data: atomic type must be numeric".into())
+        };
+
+        maximum_id += 1;
+        let id_edges = maximum_id;
+        let (patch_node, release) = get_literal(&Value::Jagged(edges), &component.batch)?;
+        computation_graph.insert(id_edges, patch_node);
+        releases.insert(id_edges, release);
+
+        maximum_id += 1;
+        let id_inclusive_left = maximum_id;
+        let (patch_node, release) = get_literal(&Value::Array(Array::Bool(arr0(false).into_dyn())), &component.batch)?;
+        computation_graph.insert(id_inclusive_left, patch_node);
+        releases.insert(id_inclusive_left, release);
+
+        // records at the lower bound fall outside every bin, and are counted in the trailing null category
+        maximum_id += 1;
+        let id_null_value = maximum_id;
+        let (patch_node, release) = get_literal(
+            &Value::Array(Array::I64(arr0(self.grid.len() as i64 + 1).into_dyn())), &component.batch)?;
+        computation_graph.insert(id_null_value, patch_node);
+        releases.insert(id_null_value, release);
+
+        // noisy counts over the bins
+        maximum_id += 1;
+        let id_histogram = maximum_id;
+        computation_graph.insert(id_histogram, proto::Component {
+            arguments: hashmap![
+                "data".to_owned() => data_id,
+                "edges".to_owned() => id_edges,
+                "inclusive_left".to_owned() => id_inclusive_left,
+                "null_value".to_owned() => id_null_value
+            ],
+            variant: Some(proto::component::Variant::DpHistogram(proto::DpHistogram {
+                mechanism: self.mechanism.clone(),
+                privacy_usage: self.privacy_usage.clone(),
+                enforce_constant_time: false,
+                binning: "".to_string(),
+                num_bins: 0,
+                binning_privacy_usage: Vec::new()
+            })),
+            omit: true,
+            batch: component.batch,
+        });
+
+        let mut cdf_arguments = hashmap!["counts".to_owned() => id_histogram];
+
+        // normalize by the public number of records when it is known
+        if let Some(num_records) = data_property.num_records {
+            maximum_id += 1;
+            let id_num_records = maximum_id;
+            let (patch_node, release) = get_literal(
+                &Value::Array(Array::I64(arr0(num_records).into_dyn())), &component.batch)?;
+            computation_graph.insert(id_num_records, patch_node);
+            releases.insert(id_num_records, release);
+            cdf_arguments.insert("num_records".to_owned(), id_num_records);
+        }
+
+        computation_graph.insert(*component_id, proto::Component {
+            arguments: cdf_arguments,
+            variant: Some(proto::component::Variant::EmpiricalCdf(proto::EmpiricalCdf {})),
+            omit: false,
+            batch: component.batch,
+        });
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal: vec![id_histogram]
+        })
+    }
+}
+
+impl Report for proto::DpCdf {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let (lower, upper) = get_bounds(&data_property)?;
+
+        let privacy_usage = self.privacy_usage.first()
+            .ok_or_else(|| Error::from("privacy_usage: must be defined"))?;
+
+        let variable_name = variable_names
+            .and_then(|names| names.first()).cloned()
+            .unwrap_or_else(|| "[Unknown]".to_string());
+
+        // the error is bounded in counts, so it is only meaningful as a proportion when N is known
+        let accuracy = match data_property.num_records {
+            Some(num_records) => Some(Accuracy {
+                accuracy_value: cumulative_count_accuracy(
+                    &self.mechanism, self.grid.len(), privacy_usage, ACCURACY_ALPHA)? / num_records as f64,
+                alpha: ACCURACY_ALPHA
+            }),
+            None => None
+        };
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPCdf".to_string(),
+            variables: serde_json::json!(variable_name),
+            release_info: value_to_json(release)?,
+            privacy_loss: privacy_usage_to_json(privacy_usage),
+            accuracy,
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "Cumulative sums of a noisy histogram".to_string(),
+                cite: "".to_string(),
+                mechanism: self.mechanism.clone(),
+                argument: serde_json::json!({
+                    "grid": self.grid,
+                    "constraint": {
+                        "lowerbound": lower[0],
+                        "upperbound": upper[0]
+                    },
+                    "postprocessing": {
+                        "operation": "isotonic regression onto non-decreasing sequences, then clipping to [0, 1]",
+                        "privacy_loss": 0
+                    }
+                }),
+            },
+        }]))
+    }
+}
+
+/// The grid must be strictly increasing, and strictly within the bounds of the data.
+pub fn check_grid(grid: &[f64], lower: f64, upper: f64) -> Result<()> {
+    if grid.is_empty() {
+        return Err("grid: at least one point must be requested".into())
+    }
+    if !grid.windows(2).all(|w| w[0] < w[1]) {
+        return Err("grid: must be strictly increasing".into())
+    }
+    if !grid.iter().all(|point| lower < *point && *point < upper) {
+        return Err("grid: points must be strictly within the bounds of the data".into())
+    }
+    Ok(())
+}
+
+/// Upper bound on the error of every cumulative count simultaneously, with probability 1 - alpha.
+///
+/// The histogram has one count per bin and a count at the lower bound, and each cell receives an even share of the budget.
+/// The cumulative count at the j-th grid point sums j + 1 noisy cells.
+/// The sensitivities are those of the histogram under substitution, so the bound holds under either neighboring definition.
+pub fn cumulative_count_accuracy(
+    mechanism: &str, num_points: usize, privacy_usage: &proto::PrivacyUsage, alpha: f64,
+) -> Result<f64> {
+    privacy_usage_check(privacy_usage)?;
+    let num_cells = (num_points + 2) as f64;
+    let num_terms = (num_points + 1) as f64;
+    let epsilon = get_epsilon(privacy_usage)?;
+
+    Ok(match mechanism.to_lowercase().as_str() {
+        "laplace" => {
+            // every cell is within its bound simultaneously, by a union bound over the cells
+            let scale = 2. / epsilon;
+            num_terms * scale * (num_cells / alpha).ln()
+        },
+        "gaussian" => {
+            let delta = get_delta(privacy_usage)?;
+            if delta == 0. {
+                return Err("privacy_usage: delta must be positive for the Gaussian mechanism".into())
+            }
+            let sigma = (2. * (1.25 * num_cells / delta).ln()).sqrt() * 2_f64.sqrt() / epsilon;
+            // a sum of gaussians is gaussian, so take a union bound over the grid points
+            sigma * num_terms.sqrt() * (2. * (2. * num_points as f64 / alpha).ln()).sqrt()
+        },
+        _ => return Err("mechanism: must be one of Laplace or Gaussian".into())
+    })
+}
+
+
+#[cfg(test)]
+mod test_dp_cdf {
+    use crate::proto;
+    use crate::components::dp_cdf::{check_grid, cumulative_count_accuracy};
+
+    #[test]
+    fn test_check_grid() {
+        assert!(check_grid(&[1., 2.], 0., 3.).is_ok());
+        assert!(check_grid(&[], 0., 3.).is_err());
+        assert!(check_grid(&[2., 1.], 0., 3.).is_err());
+        assert!(check_grid(&[0., 1.], 0., 3.).is_err());
+    }
+
+    #[test]
+    fn test_cumulative_count_accuracy() {
+        let usage = |epsilon| proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon, delta: 0.
+            }))
+        };
+        let accuracy = cumulative_count_accuracy("Laplace", 3, &usage(1.), 0.05).unwrap();
+        assert!((accuracy - 4. * 2. * 100_f64.ln()).abs() < 1e-9);
+        // error shrinks as the budget grows
+        assert!(cumulative_count_accuracy("Laplace", 3, &usage(2.), 0.05).unwrap() < accuracy);
+        assert!(cumulative_count_accuracy("Gaussian", 3, &usage(1.), 0.05).is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::hashmap;
+use crate::components::{Expandable, Report, Sensitivity};
+use crate::base::{NodeProperties, Value, SensitivitySpace};
+use crate::utilities::{prepend, privacy_usage_check, get_epsilon, get_delta, get_group_size};
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
+use statrs::distribution::{ChiSquared, Univariate};
+
+
+impl Expandable for proto::DpChiSquareTest {
+    fn expand_component(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut maximum_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+
+        let num_rows = get_num_categories(properties)?.0;
+
+        // contingency table
+        maximum_id += 1;
+        let id_table = maximum_id;
+        computation_graph.insert(id_table, proto::Component {
+            arguments: hashmap!["data".to_owned() => *component.arguments.get("data")
+                .ok_or_else(|| Error::from("data must be provided as an argument"))?],
+            variant: Some(proto::component::Variant::ContingencyTable(proto::ContingencyTable {})),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // noising
+        maximum_id += 1;
+        let id_noisy = maximum_id;
+        computation_graph.insert(id_noisy, proto::Component {
+            arguments: hashmap!["data".to_owned() => id_table],
+            variant: Some(match self.mechanism.to_lowercase().as_str() {
+                "laplace" => proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+                    privacy_usage: self.privacy_usage.clone()
+                }),
+                "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                    privacy_usage: self.privacy_usage.clone()
+                }),
+                _ => return Err(format!("mechanism: {:?} is not recognized", self.mechanism).into()),
+            }),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // test statistic, as postprocessing
+        computation_graph.insert(*component_id, proto::Component {
+            arguments: hashmap!["data".to_owned() => id_noisy],
+            variant: Some(proto::component::Variant::ChiSquareStatistic(proto::ChiSquareStatistic {
+                num_rows: num_rows as u32,
+                noise_variance: get_noise_variance(privacy_definition, properties, &self.mechanism, &self.privacy_usage)?,
+                mechanism: self.mechanism.clone(),
+            })),
+            omit: component.omit,
+            batch: component.batch,
+        });
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases: HashMap::new(),
+            traversal: vec![id_table, id_noisy],
+        })
+    }
+}
+
+impl Report for proto::DpChiSquareTest {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let (num_rows, num_columns) = get_num_categories(properties)?;
+
+        let (statistic, scale, degrees_of_freedom) = match release.array()?.f64()?.iter().collect::<Vec<&f64>>().as_slice() {
+            [statistic, scale, degrees_of_freedom] => (**statistic, **scale, **degrees_of_freedom),
+            _ => return Err("release must contain the statistic, scale and degrees of freedom".into())
+        };
+        let reference = ChiSquared::new(degrees_of_freedom)
+            .map_err(|e| Error::from(format!("reference distribution: {}", e)))?;
+
+        let privacy_usage: Vec<serde_json::Value> = self.privacy_usage.iter()
+            .map(privacy_usage_to_json).collect();
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPChiSquareTest".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json![privacy_usage],
+            accuracy: None,
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "Chi-square test of independence on a noisy contingency table".to_string(),
+                cite: "Gaboardi, Lim, Rogers and Vadhan. Differentially Private Chi-Squared Hypothesis Testing: Goodness of Fit and Independence Testing. ICML 2016".to_string(),
+                mechanism: self.mechanism.clone(),
+                argument: serde_json::json!({
+                    "categories": value_to_json(&Value::Jagged(data_property.categories()?))?,
+                    "degrees_of_freedom": (num_rows - 1) * (num_columns - 1),
+                    "reference_distribution": {
+                        "family": "scaled chi-square",
+                        "scale": scale,
+                        "degrees_of_freedom": degrees_of_freedom
+                    },
+                    "p_value": 1. - reference.cdf(statistic / scale)
+                }),
+            },
+        }]))
+    }
+}
+
+/// Number of categories of each of the two columns.
+fn get_num_categories(properties: &NodeProperties) -> Result<(i64, i64)> {
+    let data_property = properties.get("data")
+        .ok_or("data: missing")?.array()
+        .map_err(prepend("data:"))?;
+
+    match data_property.categories()
+        .map_err(|_| Error::from("data: categories must be known for every column"))?
+        .lengths()?.as_slice() {
+        [num_rows, num_columns] => Ok((*num_rows, *num_columns)),
+        _ => Err("data: must contain exactly two columns".into())
+    }
+}
+
+/// Variance of the noise the mechanism adds to each cell of the contingency table.
+fn get_noise_variance(
+    privacy_definition: &proto::PrivacyDefinition,
+    properties: &NodeProperties,
+    mechanism: &str,
+    privacy_usage: &[proto::PrivacyUsage],
+) -> Result<f64> {
+    let usage = match privacy_usage {
+        [usage] => usage,
+        _ => return Err("privacy_usage: must contain exactly one usage".into())
+    };
+    privacy_usage_check(usage)?;
+
+    let gaussian = match mechanism.to_lowercase().as_str() {
+        "laplace" => false,
+        "gaussian" => true,
+        _ => return Err(format!("mechanism: {:?} is not recognized", mechanism).into())
+    };
+    let sensitivity = proto::ContingencyTable {}.compute_sensitivity(
+        privacy_definition, properties,
+        &SensitivitySpace::KNorm(if gaussian { 2 } else { 1 }))?.first_f64()? * get_group_size(privacy_definition);
+
+    let scale = sensitivity / get_epsilon(usage)?;
+    Ok(if gaussian {
+        2. * (1.25 / get_delta(usage)?).ln() * scale.powi(2)
+    } else {
+        2. * scale.powi(2)
+    })
+}
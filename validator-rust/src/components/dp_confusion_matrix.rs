@@ -0,0 +1,110 @@
+use crate::errors::*;
+
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::hashmap;
+use crate::components::{Expandable, Report};
+
+use crate::base::{NodeProperties, Value};
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
+use crate::utilities::prepend;
+
+
+impl Expandable for proto::DpConfusionMatrix {
+    fn expand_component(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        _properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut maximum_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+
+        let get_argument = |name: &str| component.arguments.get(name).cloned()
+            .ok_or_else(|| Error::from(format!("{} must be provided as an argument", name)));
+
+        // confusion matrix
+        maximum_id += 1;
+        let id_matrix = maximum_id;
+        computation_graph.insert(id_matrix, proto::Component {
+            arguments: hashmap![
+                "actual".to_owned() => get_argument("actual")?,
+                "predicted".to_owned() => get_argument("predicted")?
+            ],
+            variant: Some(proto::component::Variant::ConfusionMatrix(proto::ConfusionMatrix {})),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // noising, with one invocation of the mechanism over all cells
+        computation_graph.insert(*component_id, proto::Component {
+            arguments: hashmap!["data".to_owned() => id_matrix],
+            variant: Some(match self.mechanism.to_lowercase().as_str() {
+                "laplace" => proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+                    privacy_usage: self.privacy_usage.clone()
+                }),
+                "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                    privacy_usage: self.privacy_usage.clone()
+                }),
+                _ => return Err(format!("mechanism: {:?} is not recognized", self.mechanism).into()),
+            }),
+            omit: false,
+            batch: component.batch,
+        });
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases: HashMap::new(),
+            traversal: vec![id_matrix],
+        })
+    }
+}
+
+impl Report for proto::DpConfusionMatrix {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let get_categories = |name: &str| -> Result<serde_json::Value> {
+            value_to_json(&Value::Jagged(properties.get(name)
+                .ok_or_else(|| Error::from(format!("{}: missing", name)))?.array()
+                .map_err(prepend(&format!("{}:", name)))?.categories()?))
+        };
+
+        let privacy_usage = self.privacy_usage.first()
+            .ok_or_else(|| Error::from("privacy_usage: must be defined"))?;
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPConfusionMatrix".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: value_to_json(&release)?,
+            // the usage is split evenly over the cells, which compose in parallel
+            privacy_loss: privacy_usage_to_json(privacy_usage),
+            accuracy: None,
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "".to_string(),
+                cite: "".to_string(),
+                mechanism: self.mechanism.clone(),
+                argument: serde_json::json!({
+                    "actual_categories": get_categories("actual")?,
+                    "predicted_categories": get_categories("predicted")?,
+                    "order": "row-major, rows are the actual labels and the predicted labels vary fastest"
+                }),
+            },
+        }]))
+    }
+}
@@ -0,0 +1,273 @@
+use crate::errors::*;
+
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::hashmap;
+use crate::components::{Expandable, Report};
+
+
+use crate::base::{NodeProperties, Value};
+use crate::utilities::json::{JSONRelease, value_to_json, AlgorithmInfo, privacy_usage_to_json};
+use crate::utilities::{prepend, get_literal, privacy_usage_reducer};
+use std::convert::TryFrom;
+
+
+impl Expandable for proto::DpCorrelation {
+    fn expand_component(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut current_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let num_columns = u32::try_from(data_property.num_columns()?)?;
+
+        let id_data = *component.arguments.get("data")
+            .ok_or_else(|| Error::from("data must be provided as an argument"))?;
+
+        // the budget is split evenly between the covariance and the variances
+        let privacy_usage = self.privacy_usage.iter()
+            .map(|usage| privacy_usage_reducer(usage, usage, &|l, _| l / 2.))
+            .collect::<Vec<proto::PrivacyUsage>>();
+
+        // covariance matrix
+        current_id += 1;
+        let id_covariance = current_id;
+        computation_graph.insert(id_covariance, proto::Component {
+            arguments: hashmap!["data".to_owned() => id_data],
+            variant: Some(proto::component::Variant::DpCovariance(proto::DpCovariance {
+                mechanism: self.mechanism.clone(),
+                privacy_usage: privacy_usage.clone(),
+                finite_sample_correction: self.finite_sample_correction
+            })),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // variances
+        current_id += 1;
+        let id_variance = current_id;
+        computation_graph.insert(id_variance, proto::Component {
+            arguments: hashmap!["data".to_owned() => id_data],
+            variant: Some(proto::component::Variant::DpVariance(proto::DpVariance {
+                mechanism: self.mechanism.clone(),
+                privacy_usage,
+                finite_sample_correction: self.finite_sample_correction
+            })),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // everything below operates on released values, and is postprocessing
+
+        // standard deviations
+        current_id += 1;
+        let id_radical = current_id;
+        let (patch_node, release) = get_literal(&Value::from(0.5), &component.batch)?;
+        computation_graph.insert(id_radical, patch_node);
+        releases.insert(id_radical, release);
+
+        current_id += 1;
+        let id_std_row = current_id;
+        computation_graph.insert(id_std_row, proto::Component {
+            arguments: hashmap![
+                "data".to_owned() => id_variance,
+                "radical".to_owned() => id_radical
+            ],
+            variant: Some(proto::component::Variant::Power(proto::Power {})),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // standard deviations as a column vector, to scale the rows
+        current_id += 1;
+        let id_std_column = current_id;
+        computation_graph.insert(id_std_column, proto::Component {
+            arguments: hashmap!["data".to_owned() => id_std_row],
+            variant: Some(proto::component::Variant::Reshape(proto::Reshape {
+                symmetric: false,
+                layout: "row".to_string(),
+                shape: vec![num_columns, 1]
+            })),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // scale the columns of the covariance matrix
+        current_id += 1;
+        let id_scaled = current_id;
+        computation_graph.insert(id_scaled, proto::Component {
+            arguments: hashmap![
+                "left".to_owned() => id_covariance,
+                "right".to_owned() => id_std_row
+            ],
+            variant: Some(proto::component::Variant::Divide(proto::Divide {})),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // scale the rows of the covariance matrix
+        computation_graph.insert(*component_id, proto::Component {
+            arguments: hashmap![
+                "left".to_owned() => id_scaled,
+                "right".to_owned() => id_std_column
+            ],
+            variant: Some(proto::component::Variant::Divide(proto::Divide {})),
+            omit: false,
+            batch: component.batch,
+        });
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal: vec![id_covariance, id_variance, id_radical, id_std_row, id_std_column, id_scaled]
+        })
+    }
+}
+
+impl Report for proto::DpCorrelation {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let privacy_usage: Vec<serde_json::Value> = self.privacy_usage.iter()
+            .map(privacy_usage_to_json).collect();
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPCorrelation".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: value_to_json(&release)?,
+            privacy_loss: serde_json::json![privacy_usage],
+            accuracy: None,
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "".to_string(),
+                cite: "".to_string(),
+                mechanism: self.mechanism.clone(),
+                argument: serde_json::json!({
+                    "n": data_property.num_records()?,
+                    "constraint": {
+                        "lowerbound": data_property.lower_f64()?,
+                        "upperbound": data_property.upper_f64()?
+                    },
+                    "postprocessing": {
+                        "operation": "covariance[i, j] / sqrt(variance[i] * variance[j])",
+                        "privacy_loss": 0
+                    }
+                })
+            }
+        }]))
+    }
+}
+
+
+#[cfg(test)]
+mod test_dp_correlation {
+    use crate::proto;
+    use crate::hashmap;
+    use crate::components::Expandable;
+    use crate::base::{ArrayProperties, DataType, Nature, NatureContinuous, ValueProperties, Vector1DNull};
+    use crate::utilities::{propagate_properties, get_charged_privacy_usages, get_epsilon, serial::serialize_value_properties};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_expansion() {
+        let num_columns = 3;
+        let data_property: ValueProperties = ArrayProperties {
+            num_records: Some(100),
+            num_columns: Some(num_columns),
+            nullity: false,
+            releasable: false,
+            public_shape: false,
+            public_metadata: false,
+            c_stability: vec![1.; num_columns as usize],
+            aggregator: None,
+            nature: Some(Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::F64(vec![Some(0.); num_columns as usize]),
+                upper: Vector1DNull::F64(vec![Some(1.); num_columns as usize]),
+            })),
+            data_type: DataType::F64,
+            dataset_id: Some(0),
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 2
+        }.into();
+        let epsilon = |usages: &[proto::PrivacyUsage]| usages.iter().map(|usage| get_epsilon(usage).unwrap()).sum::<f64>();
+
+        let dp_correlation = proto::DpCorrelation {
+            mechanism: "Laplace".to_string(),
+            privacy_usage: vec![proto::PrivacyUsage {
+                distance: Some(proto::privacy_usage::Distance::Approximate(
+                    proto::privacy_usage::DistanceApproximate { epsilon: 1., delta: 0. }))
+            }],
+            finite_sample_correction: true
+        };
+        let component = proto::Component {
+            arguments: hashmap!["data".to_string() => 1],
+            variant: Some(proto::component::Variant::DpCorrelation(dp_correlation.clone())),
+            omit: false,
+            batch: 0
+        };
+        let privacy_definition = proto::PrivacyDefinition { group_size: 1, ..Default::default() };
+
+        // the covariance and the variances each spend half of the requested usage
+        let expansion = dp_correlation.expand_component(
+            &privacy_definition, &component, &hashmap!["data".to_string() => data_property.clone()], &2, &2).unwrap();
+        let split = expansion.computation_graph.values()
+            .filter_map(|component| match component.variant.as_ref()? {
+                proto::component::Variant::DpCovariance(x) => Some(epsilon(&x.privacy_usage)),
+                proto::component::Variant::DpVariance(x) => Some(epsilon(&x.privacy_usage)),
+                _ => None
+            })
+            .collect::<Vec<f64>>();
+        assert_eq!(split, vec![0.5, 0.5]);
+
+        // once fully expanded, the mechanisms spend the requested usage, and the correlations are a square matrix
+        let analysis = proto::Analysis {
+            privacy_definition: Some(privacy_definition),
+            computation_graph: Some(proto::ComputationGraph { value: hashmap![
+                1 => proto::Component {
+                    arguments: HashMap::new(),
+                    variant: Some(proto::component::Variant::Literal(proto::Literal {})),
+                    omit: true,
+                    batch: 0
+                },
+                2 => component
+            ] }),
+            ..Default::default()
+        };
+        let release = proto::Release { values: HashMap::new() };
+        let (properties, graph) = propagate_properties(
+            &analysis, &release, Some(&hashmap![1 => serialize_value_properties(&data_property)]), false).unwrap().0;
+
+        let charged = get_charged_privacy_usages(&graph, &release).unwrap().into_iter()
+            .map(|(_, usage)| usage).collect::<Vec<proto::PrivacyUsage>>();
+        assert!((epsilon(&charged) - 1.).abs() < 1e-12);
+
+        let correlation = properties.get(&2).unwrap().array().unwrap();
+        assert_eq!((correlation.num_records, correlation.num_columns), (Some(num_columns), Some(num_columns)));
+    }
+}
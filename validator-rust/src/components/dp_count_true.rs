@@ -0,0 +1,120 @@
+use crate::errors::*;
+
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::hashmap;
+use crate::components::{Expandable, Report};
+use ndarray::arr0;
+
+use crate::base::{NodeProperties, Value};
+use crate::utilities::json::{JSONRelease, privacy_usage_to_json, AlgorithmInfo, value_to_json};
+use crate::utilities::{get_literal, prepend};
+
+
+impl Expandable for proto::DpCountTrue {
+    fn expand_component(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut maximum_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+        // count of true values
+        maximum_id += 1;
+        let id_count_true = maximum_id;
+        computation_graph.insert(id_count_true, proto::Component {
+            arguments: hashmap!["data".to_owned() => *component.arguments.get("data")
+                .ok_or_else(|| Error::from("data must be provided as an argument"))?],
+            variant: Some(proto::component::Variant::CountTrue(proto::CountTrue {})),
+            omit: true,
+            batch: component.batch,
+        });
+
+        let (arguments, variant) = match self.mechanism.to_lowercase().as_str() {
+            "simplegeometric" => {
+                let num_records = properties.get("data")
+                    .ok_or("data: missing")?.array()
+                    .map_err(prepend("data:"))?.num_records;
+
+                // the counts are bounded by the number of records
+                let mut insert_literal = |value: Value| -> Result<u32> {
+                    maximum_id += 1;
+                    let (patch_node, release) = get_literal(&value, &component.batch)?;
+                    computation_graph.insert(maximum_id, patch_node);
+                    releases.insert(maximum_id, release);
+                    Ok(maximum_id)
+                };
+                let id_lower = insert_literal(arr0(0_i64).into_dyn().into())?;
+                let id_upper = insert_literal(arr0(num_records.unwrap_or(std::i64::MAX)).into_dyn().into())?;
+
+                (hashmap![
+                    "data".to_owned() => id_count_true,
+                    "lower".to_owned() => id_lower,
+                    "upper".to_owned() => id_upper
+                ], proto::component::Variant::SimpleGeometricMechanism(proto::SimpleGeometricMechanism {
+                    privacy_usage: self.privacy_usage.clone(),
+                    enforce_constant_time: false,
+                }))
+            },
+            "laplace" => (hashmap!["data".to_owned() => id_count_true], proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+                privacy_usage: self.privacy_usage.clone()
+            })),
+            "gaussian" => (hashmap!["data".to_owned() => id_count_true], proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                privacy_usage: self.privacy_usage.clone()
+            })),
+            _ => bail!("mechanism: {:?} is not one of SimpleGeometric, Laplace or Gaussian", self.mechanism)
+        };
+
+        // noising
+        computation_graph.insert(*component_id, proto::Component {
+            arguments,
+            variant: Some(variant),
+            omit: false,
+            batch: component.batch,
+        });
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal: vec![id_count_true],
+        })
+    }
+}
+
+impl Report for proto::DpCountTrue {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        _properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPCountTrue".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: value_to_json(&release)?,
+            privacy_loss: serde_json::json![self.privacy_usage.iter().map(privacy_usage_to_json).collect::<Vec<serde_json::Value>>()],
+            accuracy: None,
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "".to_string(),
+                cite: "".to_string(),
+                mechanism: self.mechanism.clone(),
+                argument: serde_json::json!({}),
+            },
+        }]))
+    }
+}
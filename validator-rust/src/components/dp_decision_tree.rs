@@ -0,0 +1,261 @@
+use crate::errors::*;
+
+use std::collections::{HashMap, BTreeMap};
+
+use crate::{proto, base};
+use crate::components::{Component, Expandable, Report};
+use crate::base::{Value, ValueProperties, ArrayProperties, HashmapProperties, Hashmap, DataType, NodeProperties, Jagged};
+use crate::utilities::{prepend, privacy_usage_check, get_epsilon, get_literal, broadcast_privacy_usage, get_group_size};
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
+use ndarray::arr1;
+
+/// Deepest tree that may be requested, as the number of leaves grows exponentially with the depth.
+pub const MAX_DEPTH: u32 = 16;
+
+
+impl Component for proto::DpDecisionTree {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let target_property = properties.get("target")
+            .ok_or("target: missing")?.array()
+            .map_err(prepend("target:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+        data_property.assert_non_null()?;
+        target_property.assert_is_not_aggregated().map_err(prepend("target:"))?;
+        target_property.assert_non_null().map_err(prepend("target:"))?;
+
+        get_level_epsilons(&self.privacy_usage, self.depth)?;
+
+        data_property.categories()
+            .map_err(|_| Error::from("data: categories must be known for every column"))?
+            .lengths().map_err(prepend("data:"))?;
+        if data_property.data_type == DataType::F64 {
+            return Err("data: float data may not be categorical".into())
+        }
+        let num_classes = get_target_categories(&target_property)?.len() as i64;
+
+        let num_leaves = 2_i64.pow(self.depth);
+
+        let template = ArrayProperties {
+            num_records: None,
+            num_columns: None,
+            nullity: false,
+            releasable: true,
+            public_shape: true,
+            public_metadata: true,
+            c_stability: Vec::new(),
+            aggregator: None,
+            nature: None,
+            data_type: DataType::I64,
+            dataset_id: None,
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 2
+        };
+
+        let splits_property = ArrayProperties {
+            num_records: Some(num_leaves - 1),
+            num_columns: Some(2),
+            c_stability: vec![1.; 2],
+            ..template.clone()
+        };
+        let counts_property = ArrayProperties {
+            num_records: Some(num_leaves),
+            num_columns: Some(num_classes),
+            c_stability: vec![1.; num_classes as usize],
+            data_type: DataType::F64,
+            ..template
+        };
+
+        Ok(HashmapProperties {
+            num_records: None,
+            disjoint: false,
+            properties: Hashmap::<ValueProperties>::Str(vec![
+                ("splits".to_string(), splits_property.into()),
+                ("counts".to_string(), counts_property.into())
+            ].into_iter().collect::<BTreeMap<String, ValueProperties>>()),
+            columnar: false,
+            releasable: true,
+            group_id: Vec::new()
+        }.into())
+    }
+}
+
+impl Expandable for proto::DpDecisionTree {
+    fn expand_component(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut current_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let target_property = properties.get("target")
+            .ok_or("target: missing")?.array()
+            .map_err(prepend("target:"))?.clone();
+
+        use proto::privacy_definition::Neighboring;
+        let neighboring_type = Neighboring::from_i32(privacy_definition.neighboring)
+            .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+
+        // a record changes the count of one class within one node, or of two when substituted.
+        // The same bound applies to the utility of a split, the number of records classified correctly by the majority of each child
+        let sensitivity = match neighboring_type {
+            Neighboring::AddRemove => 1.,
+            Neighboring::Substitute => 2.
+        } * data_property.c_stability.iter().chain(target_property.c_stability.iter()).cloned().fold(1., f64::max)
+            * get_group_size(privacy_definition);
+
+        let target_categories: Value = match target_property.categories()? {
+            Jagged::I64(jagged) => arr1(jagged[0].as_ref().ok_or("target: categories must be known")?).into_dyn().into(),
+            Jagged::Bool(jagged) => arr1(jagged[0].as_ref().ok_or("target: categories must be known")?).into_dyn().into(),
+            Jagged::Str(jagged) => arr1(jagged[0].as_ref().ok_or("target: categories must be known")?).into_dyn().into(),
+            Jagged::F64(_) => return Err("target: float data may not be categorical".into())
+        };
+
+        // always overwrite the categories and sensitivity. These are not something a user may configure
+        let mut tree_component = component.clone();
+        for (name, value) in vec![
+            ("categories", Value::Jagged(data_property.categories()?)),
+            ("target_categories", target_categories),
+            ("sensitivity", Value::from(sensitivity))
+        ] {
+            current_id += 1;
+            let (patch_node, release) = get_literal(&value, &component.batch)?;
+            computation_graph.insert(current_id, patch_node);
+            releases.insert(current_id, release);
+            tree_component.arguments.insert(name.to_string(), current_id);
+        }
+        computation_graph.insert(*component_id, tree_component);
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal: Vec::new()
+        })
+    }
+}
+
+impl Report for proto::DpDecisionTree {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        _properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let level_usages = get_level_usages(&self.privacy_usage, self.depth)?;
+
+        let privacy_usage: Vec<serde_json::Value> = self.privacy_usage.iter()
+            .map(privacy_usage_to_json).collect();
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPDecisionTree".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json![privacy_usage],
+            accuracy: None,
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "Decision tree with exponential mechanism splits and Laplace leaf counts".to_string(),
+                cite: "Friedman and Schuster. Data Mining with Differential Privacy. KDD 2010".to_string(),
+                mechanism: "Exponential, Laplace".to_string(),
+                argument: serde_json::json!({
+                    "depth": self.depth,
+                    "split_privacy_loss": level_usages[..self.depth as usize].iter()
+                        .map(privacy_usage_to_json).collect::<Vec<serde_json::Value>>(),
+                    "count_privacy_loss": privacy_usage_to_json(&level_usages[self.depth as usize])
+                }),
+            },
+        }]))
+    }
+}
+
+/// Privacy usage of each of the `depth` levels of splits, followed by the usage of the leaf counts.
+pub fn get_level_usages(privacy_usage: &[proto::PrivacyUsage], depth: u32) -> Result<Vec<proto::PrivacyUsage>> {
+    if depth < 1 || depth > MAX_DEPTH {
+        return Err(format!("depth: must be within [1, {}]", MAX_DEPTH).into())
+    }
+    if privacy_usage.len() != 1 && privacy_usage.len() != depth as usize + 1 {
+        return Err("privacy_usage: must contain either one usage, or one usage for each level of splits followed by one for the leaf counts".into())
+    }
+    broadcast_privacy_usage(privacy_usage, depth as usize + 1)
+}
+
+/// Epsilon of each of the `depth` levels of splits, followed by the epsilon of the leaf counts.
+///
+/// The exponential and Laplace mechanisms are pure, so delta must be zero.
+pub fn get_level_epsilons(privacy_usage: &[proto::PrivacyUsage], depth: u32) -> Result<Vec<f64>> {
+    get_level_usages(privacy_usage, depth)?.iter()
+        .map(|usage| {
+            privacy_usage_check(usage)?;
+            if let Some(proto::privacy_usage::Distance::Approximate(approximate)) = &usage.distance {
+                if approximate.delta != 0. {
+                    return Err("privacy_usage: delta must be zero".into())
+                }
+            }
+            let epsilon = get_epsilon(usage)?;
+            if epsilon <= 0. {
+                return Err("privacy_usage: epsilon must be greater than zero".into())
+            }
+            Ok(epsilon)
+        })
+        .collect()
+}
+
+fn get_target_categories(target_property: &ArrayProperties) -> Result<Vec<i64>> {
+    let categories = target_property.categories()
+        .map_err(|_| Error::from("target: categories must be known"))?;
+    if categories.num_columns() != 1 {
+        return Err("target: must contain a single column".into())
+    }
+    categories.lengths()
+}
+
+
+#[cfg(test)]
+mod test_dp_decision_tree {
+    use crate::proto;
+    use crate::components::dp_decision_tree::get_level_epsilons;
+
+    fn pure(epsilon: f64) -> proto::PrivacyUsage {
+        proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Pure(proto::privacy_usage::DistancePure { epsilon }))
+        }
+    }
+
+    #[test]
+    fn test_level_epsilons() {
+        // a single usage is split over the levels of splits and the leaf counts
+        let epsilons = get_level_epsilons(&[pure(1.)], 3).unwrap();
+        assert_eq!(epsilons.len(), 4);
+        assert!(epsilons.iter().all(|epsilon| (epsilon - 0.25).abs() < 1e-12));
+
+        let epsilons = get_level_epsilons(&[pure(0.1), pure(0.2), pure(0.7)], 2).unwrap();
+        assert_eq!(epsilons, vec![0.1, 0.2, 0.7]);
+
+        assert!(get_level_epsilons(&[pure(0.1), pure(0.2)], 2).is_err());
+        assert!(get_level_epsilons(&[pure(1.)], 0).is_err());
+    }
+}
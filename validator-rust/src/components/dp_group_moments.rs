@@ -0,0 +1,163 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::components::{Component, Expandable, Report};
+use crate::base::{Value, ValueProperties, ArrayProperties, DataType, NodeProperties};
+use crate::components::dp_welch_t_test::{get_bounds, get_sensitivity, get_test_epsilon};
+use crate::utilities::{prepend, get_literal};
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
+
+
+impl Component for proto::DpGroupMoments {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let by_property = properties.get("by")
+            .ok_or("by: missing")?.array()
+            .map_err(prepend("by:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+        data_property.assert_non_null()?;
+        if data_property.num_columns()? != 1 {
+            return Err("data: must contain a single column".into())
+        }
+        get_bounds(&data_property)?;
+
+        by_property.assert_is_not_aggregated()?;
+        if by_property.num_columns()? != 1 {
+            return Err("by: must contain a single column".into())
+        }
+        let num_groups = get_num_groups(&by_property)?;
+        if let (Some(data_num_records), Some(by_num_records)) = (data_property.num_records, by_property.num_records) {
+            if data_num_records != by_num_records {
+                return Err("data and by must have the same number of records".into())
+            }
+        }
+        get_test_epsilon(&self.privacy_usage)?;
+
+        Ok(ArrayProperties {
+            num_records: Some(num_groups),
+            num_columns: Some(3),
+            nullity: false,
+            releasable: true,
+            public_shape: true,
+            public_metadata: true,
+            c_stability: vec![1.; 3],
+            aggregator: None,
+            nature: None,
+            data_type: DataType::F64,
+            dataset_id: None,
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 2
+        }.into())
+    }
+}
+
+impl Expandable for proto::DpGroupMoments {
+    fn expand_component(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut current_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let categories = properties.get("by")
+            .ok_or("by: missing")?.array()
+            .map_err(prepend("by:"))?.categories()?;
+        let (lower, upper) = get_bounds(&data_property)?;
+
+        // always overwrite the bounds, categories and sensitivity. These are not something a user may configure
+        let mut moments_component = component.clone();
+        for (name, value) in vec![
+            ("lower", Value::from(lower)),
+            ("upper", Value::from(upper)),
+            ("categories", Value::Jagged(categories)),
+            ("sensitivity", Value::from(get_sensitivity(privacy_definition, &data_property)?))
+        ] {
+            current_id += 1;
+            let (patch_node, release) = get_literal(&value, &component.batch)?;
+            computation_graph.insert(current_id, patch_node);
+            releases.insert(current_id, release);
+            moments_component.arguments.insert(name.to_string(), current_id);
+        }
+        computation_graph.insert(*component_id, moments_component);
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal: Vec::new()
+        })
+    }
+}
+
+impl Report for proto::DpGroupMoments {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let categories = properties.get("by")
+            .ok_or("by: missing")?.array()
+            .map_err(prepend("by:"))?.categories()?;
+        let (lower, upper) = get_bounds(&data_property)?;
+
+        let privacy_usage: Vec<serde_json::Value> = self.privacy_usage.iter()
+            .map(privacy_usage_to_json).collect();
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPGroupMoments".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json![privacy_usage],
+            accuracy: None,
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "".to_string(),
+                cite: "".to_string(),
+                mechanism: "Laplace".to_string(),
+                argument: serde_json::json!({
+                    "groups": value_to_json(&Value::Jagged(categories))?,
+                    "lower": lower,
+                    "upper": upper,
+                    "midpoint": (lower + upper) / 2.
+                }),
+            },
+        }]))
+    }
+}
+
+/// Number of categories of the single column of `by`. There must be at least two groups.
+fn get_num_groups(by_property: &ArrayProperties) -> Result<i64> {
+    match by_property.categories().map_err(prepend("by:"))?.lengths()?.as_slice() {
+        [num_groups] if *num_groups >= 2 => Ok(*num_groups),
+        _ => Err("by: must have at least two categories".into())
+    }
+}
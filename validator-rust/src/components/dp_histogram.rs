@@ -8,10 +8,13 @@ use crate::hashmap;
 use crate::components::{Expandable, Report};
 use ndarray::{arr0};
 
-use crate::base::{NodeProperties, Value};
+use crate::base::{NodeProperties, Value, Jagged};
 use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
 use crate::utilities::{prepend, broadcast_privacy_usage, get_ith_column, get_literal};
 
+/// Number of fine bins per output bin, when choosing quantile edges.
+const FINE_BINS_PER_BIN: u32 = 8;
+
 
 impl Expandable for proto::DpHistogram {
     fn expand_component(
@@ -33,6 +36,79 @@ impl Expandable for proto::DpHistogram {
             .ok_or("data: missing")?.array()
             .map_err(prepend("data:"))?;
 
+        let mut traversal = Vec::new();
+        let mut id_edges = component.arguments.get("edges").cloned();
+
+        // choose edges for continuous data, if neither edges nor categories are supplied
+        if id_edges.is_none() && component.arguments.get("categories").is_none() && !self.binning.is_empty() {
+            if self.num_bins == 0 {
+                return Err("num_bins: must be greater than zero".into())
+            }
+            let lower = data_property.lower_f64().map_err(prepend("data:"))?;
+            let upper = data_property.upper_f64().map_err(prepend("data:"))?;
+
+            match self.binning.to_lowercase().as_str() {
+                "equal_width" => {
+                    maximum_id += 1;
+                    let (patch_node, edges_release) = get_literal(
+                        &equal_width_edges(&lower, &upper, self.num_bins)?, &component.batch)?;
+                    computation_graph.insert(maximum_id, patch_node);
+                    releases.insert(maximum_id, edges_release);
+                    id_edges = Some(maximum_id);
+                }
+                "quantile" => {
+                    if self.binning_privacy_usage.is_empty() {
+                        return Err("binning_privacy_usage: must be supplied when binning by quantiles".into())
+                    }
+
+                    // fine grid of equal-width edges
+                    maximum_id += 1;
+                    let id_fine_edges = maximum_id;
+                    let (patch_node, edges_release) = get_literal(
+                        &equal_width_edges(&lower, &upper, self.num_bins * FINE_BINS_PER_BIN)?, &component.batch)?;
+                    computation_graph.insert(id_fine_edges, patch_node);
+                    releases.insert(id_fine_edges, edges_release);
+
+                    // DP counts over the fine grid, paid for by the binning budget
+                    let mut fine_arguments = component.arguments.clone();
+                    fine_arguments.insert("edges".to_string(), id_fine_edges);
+                    maximum_id += 1;
+                    let id_fine_histogram = maximum_id;
+                    computation_graph.insert(id_fine_histogram, proto::Component {
+                        arguments: fine_arguments,
+                        variant: Some(proto::component::Variant::DpHistogram(proto::DpHistogram {
+                            mechanism: self.mechanism.clone(),
+                            privacy_usage: self.binning_privacy_usage.clone(),
+                            enforce_constant_time: self.enforce_constant_time,
+                            binning: "".to_string(),
+                            num_bins: 0,
+                            binning_privacy_usage: Vec::new()
+                        })),
+                        omit: true,
+                        batch: component.batch,
+                    });
+
+                    // edges at quantiles of the released counts
+                    maximum_id += 1;
+                    let id_quantile_edges = maximum_id;
+                    computation_graph.insert(id_quantile_edges, proto::Component {
+                        arguments: hashmap![
+                            "counts".to_owned() => id_fine_histogram,
+                            "edges".to_owned() => id_fine_edges
+                        ],
+                        variant: Some(proto::component::Variant::QuantileEdges(proto::QuantileEdges {
+                            num_bins: self.num_bins
+                        })),
+                        omit: true,
+                        batch: component.batch,
+                    });
+                    traversal.extend(vec![id_fine_histogram, id_quantile_edges]);
+                    id_edges = Some(id_quantile_edges);
+                }
+                _ => return Err("binning: must be one of equal_width or quantile".into())
+            }
+        }
+
         // histogram
         maximum_id += 1;
         let id_histogram = maximum_id;
@@ -41,8 +117,8 @@ impl Expandable for proto::DpHistogram {
             .map(|v| histogram_arguments.insert("categories".to_string(), *v));
         component.arguments.get("null_value")
             .map(|v| histogram_arguments.insert("null_value".to_string(), *v));
-        component.arguments.get("edges")
-            .map(|v| histogram_arguments.insert("edges".to_string(), *v));
+        id_edges
+            .map(|v| histogram_arguments.insert("edges".to_string(), v));
         component.arguments.get("inclusive_left")
             .map(|v| histogram_arguments.insert("inclusive_left".to_string(), *v));
         computation_graph.insert(id_histogram, proto::Component {
@@ -110,11 +186,13 @@ impl Expandable for proto::DpHistogram {
         }
 
 
+        traversal.push(id_histogram);
+
         Ok(proto::ComponentExpansion {
             computation_graph,
             properties: HashMap::new(),
             releases,
-            traversal: vec![id_histogram]
+            traversal
         })
     }
 }
@@ -137,6 +215,11 @@ impl Report for proto::DpHistogram {
 
         let num_columns = data_property.num_columns()?;
         let privacy_usages = broadcast_privacy_usage(&self.privacy_usage, num_columns as usize)?;
+        // only quantile binning spends budget on choosing edges
+        let binning_privacy_usages = match self.binning.to_lowercase().as_str() {
+            "quantile" if !self.binning_privacy_usage.is_empty() => Some(broadcast_privacy_usage(&self.binning_privacy_usage, num_columns as usize)?),
+            _ => None
+        };
 
         for column_number in 0..(num_columns as usize) {
             let variable_name = variable_names
@@ -161,7 +244,19 @@ impl Report for proto::DpHistogram {
                     name: "".to_string(),
                     cite: "".to_string(),
                     mechanism: self.mechanism.clone(),
-                    argument: serde_json::json!({}),
+                    argument: match self.binning.is_empty() {
+                        true => serde_json::json!({}),
+                        // the budget spent choosing edges is kept apart from the budget spent on the counts
+                        false => serde_json::json!({
+                            "binning": {
+                                "strategy": self.binning,
+                                "num_bins": self.num_bins,
+                                "privacy_loss": binning_privacy_usages.as_ref()
+                                    .map(|usages| privacy_usage_to_json(&usages[column_number]))
+                                    .unwrap_or_else(|| serde_json::json!(0))
+                            }
+                        })
+                    },
                 },
             };
 
@@ -170,3 +265,17 @@ impl Report for proto::DpHistogram {
         Ok(Some(releases))
     }
 }
+
+/// Evenly spaced edges between the lower and upper bound of each column.
+pub(crate) fn equal_width_edges(lower: &[f64], upper: &[f64], num_bins: u32) -> Result<Value> {
+    Ok(Value::Jagged(Jagged::F64(lower.iter().zip(upper.iter())
+        .map(|(lower, upper)| {
+            if lower >= upper {
+                return Err("lower bound must be less than upper bound to choose edges".into())
+            }
+            Ok(Some((0..=num_bins)
+                .map(|i| lower + (upper - lower) * i as f64 / num_bins as f64)
+                .collect::<Vec<f64>>()))
+        })
+        .collect::<Result<Vec<Option<Vec<f64>>>>>()?)))
+}
@@ -0,0 +1,166 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::hashmap;
+use crate::components::{Expandable, Report, Accuracy};
+use crate::components::dp_quantiles::{get_bounds, get_num_candidates, get_quantiles_epsilon, quantile_rank_accuracy, quantile_rank_epsilon};
+use crate::base::{NodeProperties, Value};
+use crate::utilities::{prepend, get_group_size};
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
+
+/// Quartiles released jointly by the expansion.
+const QUARTILES: [f64; 2] = [0.25, 0.75];
+
+
+impl Expandable for proto::DpIqr {
+    fn expand_component(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        _properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut maximum_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+
+        // joint release of both quartiles
+        maximum_id += 1;
+        let id_quartiles = maximum_id;
+        computation_graph.insert(id_quartiles, proto::Component {
+            arguments: hashmap!["data".to_owned() => *component.arguments.get("data")
+                .ok_or_else(|| Error::from("data must be provided as an argument"))?],
+            variant: Some(proto::component::Variant::DpQuantiles(proto::DpQuantiles {
+                alphas: QUARTILES.to_vec(),
+                privacy_usage: self.privacy_usage.clone()
+            })),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // range, as postprocessing
+        computation_graph.insert(*component_id, proto::Component {
+            arguments: hashmap!["data".to_owned() => id_quartiles],
+            variant: Some(proto::component::Variant::InterquartileRange(proto::InterquartileRange {})),
+            omit: component.omit,
+            batch: component.batch,
+        });
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases: HashMap::new(),
+            traversal: vec![id_quartiles],
+        })
+    }
+}
+
+impl Report for proto::DpIqr {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let (lower, upper) = get_bounds(&data_property)?;
+
+        let privacy_usage = self.privacy_usage.first()
+            .ok_or_else(|| Error::from("privacy_usage: must be defined"))?;
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPIqr".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: value_to_json(release)?,
+            // both quartiles of every column share one budget
+            privacy_loss: privacy_usage_to_json(privacy_usage),
+            accuracy: None,
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "Difference of jointly released quartiles".to_string(),
+                cite: "Kaplan, Schnapp and Stemmer. Differentially Private Approximate Quantiles. ICML 2022".to_string(),
+                mechanism: "Exponential".to_string(),
+                argument: serde_json::json!({
+                    "alphas": QUARTILES,
+                    "constraint": {
+                        "lowerbound": lower,
+                        "upperbound": upper
+                    }
+                }),
+            },
+        }]))
+    }
+}
+
+impl Accuracy for proto::DpIqr {
+    fn accuracy_to_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &base::NodeProperties,
+        accuracies: &proto::Accuracies,
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let sensitivity = data_property.c_stability.iter().cloned().fold(1., f64::max) * get_group_size(privacy_definition);
+        let num_candidates = get_num_candidates(&data_property)?;
+        if accuracies.values.len() != num_candidates.len() {
+            return Err("accuracies: one accuracy must be supplied for each column".into())
+        }
+
+        // the quartiles of every column share one budget, so the most demanding column determines the usage
+        let epsilon = accuracies.values.iter().zip(num_candidates.iter())
+            .map(|(accuracy, num_candidates)| quantile_rank_epsilon(
+                accuracy.value / 2., sensitivity, *num_candidates, QUARTILES.len(), accuracy.alpha))
+            .fold(0., f64::max);
+
+        Ok(Some(vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon,
+                delta: 0.,
+            }))
+        }]))
+    }
+
+    /// Bound on the rank error of the range, that holds with probability at least `1 - alpha`.
+    ///
+    /// The bound on the rank error of the joint quantiles holds for both quartiles at once,
+    /// so the range is within twice that bound, without a second union bound over the quartiles.
+    fn privacy_usage_to_accuracy(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &base::NodeProperties,
+        alpha: &f64
+    ) -> Result<Option<Vec<proto::Accuracy>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let sensitivity = data_property.c_stability.iter().cloned().fold(1., f64::max) * get_group_size(privacy_definition);
+        let epsilon = get_quantiles_epsilon(&self.privacy_usage)?;
+
+        Ok(Some(get_num_candidates(&data_property)?.into_iter()
+            .map(|num_candidates| {
+                let accuracy = 2. * quantile_rank_accuracy(
+                    epsilon, sensitivity, num_candidates, QUARTILES.len(), *alpha);
+                proto::Accuracy {
+                    // the range may not span more than n ranks
+                    value: data_property.num_records
+                        .map_or(accuracy, |num_records| accuracy.min(num_records as f64)),
+                    alpha: *alpha,
+                }
+            })
+            .collect()))
+    }
+}
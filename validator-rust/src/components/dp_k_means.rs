@@ -0,0 +1,187 @@
+use crate::errors::*;
+
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::hashmap;
+use crate::components::{Expandable, Report};
+use ndarray::Array;
+
+use crate::base::{NodeProperties, Value, DataType};
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
+use crate::utilities::{prepend, get_literal, privacy_usage_reducer};
+
+
+impl Expandable for proto::DpKMeans {
+    fn expand_component(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut current_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+        let mut traversal = Vec::new();
+
+        let data_id = *component.arguments.get("data")
+            .ok_or_else(|| Error::from("data must be provided as an argument"))?;
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        if data_property.data_type != DataType::F64 {
+            return Err("data: atomic type must be float".into())
+        }
+        let lower = data_property.lower_f64().map_err(prepend("data:"))?;
+        let upper = data_property.upper_f64().map_err(prepend("data:"))?;
+
+        if self.num_clusters < 1 {
+            return Err("num_clusters: must be at least one".into())
+        }
+        if self.num_iterations < 1 {
+            return Err("num_iterations: must be at least one".into())
+        }
+        // the statistics of each iteration are released by a single mechanism call
+        if self.privacy_usage.len() != 1 {
+            return Err("privacy_usage: the privacy usage is split over the iterations, so exactly one privacy usage must be supplied".into())
+        }
+        let privacy_usage = iteration_privacy_usage(&self.privacy_usage, self.num_iterations);
+
+        // initial centroids, spread evenly along the diagonal of the bounding box
+        current_id += 1;
+        let mut id_centroids = current_id;
+        let num_clusters = self.num_clusters as usize;
+        let initial_centroids = (0..num_clusters)
+            .flat_map(|cluster| {
+                let position = (cluster as f64 + 0.5) / num_clusters as f64;
+                lower.iter().zip(upper.iter())
+                    .map(move |(lower, upper)| lower + position * (upper - lower))
+            })
+            .collect::<Vec<f64>>();
+        let value: Value = Array::from_shape_vec((num_clusters, lower.len()), initial_centroids)?
+            .into_dyn().into();
+        let (patch_node, release) = get_literal(&value, &component.batch)?;
+        computation_graph.insert(id_centroids, patch_node);
+        releases.insert(id_centroids, release);
+
+        for iteration in 0..self.num_iterations {
+            // counts and sums of each cluster
+            current_id += 1;
+            let id_statistics = current_id;
+            computation_graph.insert(id_statistics, proto::Component {
+                arguments: hashmap![
+                    "data".to_owned() => data_id,
+                    "centroids".to_owned() => id_centroids
+                ],
+                variant: Some(proto::component::Variant::KMeansStatistics(proto::KMeansStatistics {})),
+                omit: true,
+                batch: component.batch,
+            });
+
+            // noise
+            current_id += 1;
+            let id_noise = current_id;
+            computation_graph.insert(id_noise, proto::Component {
+                arguments: hashmap!["data".to_owned() => id_statistics],
+                variant: Some(match self.mechanism.to_lowercase().as_str() {
+                    "laplace" => proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+                        privacy_usage: privacy_usage.clone()
+                    }),
+                    "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                        privacy_usage: privacy_usage.clone()
+                    }),
+                    _ => return Err("mechanism: must be one of Laplace or Gaussian".into())
+                }),
+                omit: true,
+                batch: component.batch,
+            });
+            traversal.extend(vec![id_statistics, id_noise]);
+
+            // updated centroids, where the final centroids take the place of the original component
+            let is_last = iteration + 1 == self.num_iterations;
+            let id_update = if is_last { *component_id } else {
+                current_id += 1;
+                traversal.push(current_id);
+                current_id
+            };
+            computation_graph.insert(id_update, proto::Component {
+                arguments: hashmap![
+                    "data".to_owned() => id_noise,
+                    "centroids".to_owned() => id_centroids
+                ],
+                variant: Some(proto::component::Variant::KMeansCentroids(proto::KMeansCentroids {
+                    lower: lower.clone(),
+                    upper: upper.clone()
+                })),
+                omit: !is_last,
+                batch: component.batch,
+            });
+            id_centroids = id_update;
+        }
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal
+        })
+    }
+}
+
+impl Report for proto::DpKMeans {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let privacy_usage: Vec<serde_json::Value> = self.privacy_usage.iter()
+            .map(privacy_usage_to_json).collect();
+        let iteration_usage: Vec<serde_json::Value> = iteration_privacy_usage(&self.privacy_usage, self.num_iterations).iter()
+            .map(privacy_usage_to_json).collect();
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPKMeans".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json![privacy_usage],
+            accuracy: None,
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "Lloyd's algorithm over noisy cluster counts and sums".to_string(),
+                cite: "Blum, Dwork, McSherry and Nissim. Practical Privacy: The SuLQ Framework. 2005".to_string(),
+                mechanism: self.mechanism.clone(),
+                argument: serde_json::json!({
+                    "num_clusters": self.num_clusters,
+                    "num_iterations": self.num_iterations,
+                    "iteration_privacy_loss": iteration_usage,
+                    "constraint": {
+                        "lowerbound": data_property.lower_f64()?,
+                        "upperbound": data_property.upper_f64()?
+                    }
+                }),
+            },
+        }]))
+    }
+}
+
+/// Privacy usage of each iteration, when the total usage is split evenly over the iterations.
+fn iteration_privacy_usage(privacy_usage: &[proto::PrivacyUsage], num_iterations: u32) -> Vec<proto::PrivacyUsage> {
+    privacy_usage.iter()
+        .map(|usage| privacy_usage_reducer(usage, usage, &|l, _| l / num_iterations.max(1) as f64))
+        .collect()
+}
@@ -0,0 +1,203 @@
+use crate::errors::*;
+
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::hashmap;
+use crate::components::{Expandable, Report, Sensitivity};
+
+use crate::base::{NodeProperties, Value, Hashmap, SensitivitySpace};
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, Accuracy, privacy_usage_to_json};
+use crate::utilities::{prepend, broadcast_privacy_usage, get_epsilon, get_delta, get_group_size};
+
+/// Confidence level of the coefficient accuracies in the report.
+const ACCURACY_ALPHA: f64 = 0.05;
+
+
+impl Expandable for proto::DpLinearRegression {
+    fn expand_component(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut current_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+
+        let data_id = *component.arguments.get("data")
+            .ok_or_else(|| Error::from("data must be provided as an argument"))?;
+        let target_id = *component.arguments.get("target")
+            .ok_or_else(|| Error::from("target must be provided as an argument"))?;
+
+        if self.regularization < 0. {
+            return Err("regularization: must be non-negative".into())
+        }
+
+        let cross_products = proto::CrossProducts { intercept: self.intercept };
+        let noise_variance = noise_variance(
+            &self.mechanism, &self.privacy_usage, privacy_definition, &cross_products, properties)?;
+
+        // sufficient statistics
+        current_id += 1;
+        let id_cross_products = current_id;
+        computation_graph.insert(id_cross_products, proto::Component {
+            arguments: hashmap![
+                "data".to_owned() => data_id,
+                "target".to_owned() => target_id
+            ],
+            variant: Some(proto::component::Variant::CrossProducts(cross_products)),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // noise
+        current_id += 1;
+        let id_noise = current_id;
+        computation_graph.insert(id_noise, proto::Component {
+            arguments: hashmap!["data".to_owned() => id_cross_products],
+            variant: Some(match self.mechanism.to_lowercase().as_str() {
+                "laplace" => proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+                    privacy_usage: self.privacy_usage.clone()
+                }),
+                "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                    privacy_usage: self.privacy_usage.clone()
+                }),
+                _ => return Err("mechanism: must be one of Laplace or Gaussian".into())
+            }),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // solve for the coefficients
+        computation_graph.insert(*component_id, proto::Component {
+            arguments: hashmap!["data".to_owned() => id_noise],
+            variant: Some(proto::component::Variant::LinearRegression(proto::LinearRegression {
+                intercept: self.intercept,
+                regularization: self.regularization,
+                noise_variance
+            })),
+            omit: false,
+            batch: component.batch
+        });
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases: HashMap::new(),
+            traversal: vec![id_cross_products, id_noise]
+        })
+    }
+}
+
+impl Report for proto::DpLinearRegression {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let target_property = properties.get("target")
+            .ok_or("target: missing")?.array()
+            .map_err(prepend("target:"))?.clone();
+
+        let (coefficients, standard_errors) = match release {
+            Value::Hashmap(Hashmap::Str(release)) => (
+                release.get("coefficients").ok_or("coefficients: missing")?.array()?.f64()?.clone(),
+                release.get("standard_errors").ok_or("standard_errors: missing")?.array()?.f64()?.clone()),
+            _ => return Err("release must be a hashmap of coefficients and standard errors".into())
+        };
+
+        let privacy_usage: Vec<serde_json::Value> = self.privacy_usage.iter()
+            .map(privacy_usage_to_json).collect();
+
+        let lower = data_property.lower_f64()?;
+        let upper = data_property.upper_f64()?;
+        let target_lower = target_property.lower_f64()?;
+        let target_upper = target_property.upper_f64()?;
+
+        let names = (0..data_property.num_columns()? as usize)
+            .map(|column_number| variable_names
+                .and_then(|names| names.get(column_number)).cloned()
+                .unwrap_or_else(|| "[Unknown]".to_string()));
+        let names = if self.intercept { vec!["[Intercept]".to_string()] } else { Vec::new() }
+            .into_iter().chain(names).collect::<Vec<String>>();
+
+        if names.len() != coefficients.len() || standard_errors.len() != coefficients.len() {
+            return Err("release: must contain one coefficient and standard error for each regressor".into())
+        }
+
+        Ok(Some(names.into_iter()
+            .zip(coefficients.iter().zip(standard_errors.iter()))
+            .map(|(variable_name, (coefficient, standard_error))| JSONRelease {
+                description: "DP release information".to_string(),
+                statistic: "DPLinearRegression".to_string(),
+                variables: serde_json::json!(variable_name),
+                release_info: serde_json::json!(coefficient),
+                privacy_loss: serde_json::json![privacy_usage],
+                // by Chebyshev's inequality, applied to the first-order error of the coefficient
+                accuracy: Some(Accuracy {
+                    accuracy_value: standard_error / ACCURACY_ALPHA.sqrt(),
+                    alpha: ACCURACY_ALPHA
+                }),
+                batch: component.batch as u64,
+                node_id: *node_id as u64,
+                postprocess: false,
+                algorithm_info: AlgorithmInfo {
+                    name: "Sufficient statistics perturbation".to_string(),
+                    cite: "Wang. Revisiting differentially private linear regression: optimal and adaptive prediction & estimation in unbounded domain. 2018".to_string(),
+                    mechanism: self.mechanism.clone(),
+                    argument: serde_json::json!({
+                        "intercept": self.intercept,
+                        "regularization": self.regularization,
+                        "standard_error": standard_error,
+                        "constraint": {
+                            "lowerbound": lower,
+                            "upperbound": upper,
+                            "lowerbound_target": target_lower[0],
+                            "upperbound_target": target_upper[0]
+                        }
+                    }),
+                },
+            })
+            .collect()))
+    }
+}
+
+/// Variance of the noise added to each of the sufficient statistics.
+fn noise_variance(
+    mechanism: &str,
+    privacy_usage: &[proto::PrivacyUsage],
+    privacy_definition: &proto::PrivacyDefinition,
+    cross_products: &proto::CrossProducts,
+    properties: &NodeProperties,
+) -> Result<Vec<f64>> {
+    let (sensitivity_type, gaussian) = match mechanism.to_lowercase().as_str() {
+        "laplace" => (SensitivitySpace::KNorm(1), false),
+        "gaussian" => (SensitivitySpace::KNorm(2), true),
+        _ => return Err("mechanism: must be one of Laplace or Gaussian".into())
+    };
+
+    let sensitivities = cross_products.compute_sensitivity(privacy_definition, properties, &sensitivity_type)?
+        .array()?.f64()?.iter().map(|sensitivity| sensitivity * get_group_size(privacy_definition)).collect::<Vec<f64>>();
+    let usages = broadcast_privacy_usage(privacy_usage, sensitivities.len())?;
+
+    sensitivities.iter().zip(usages.iter())
+        .map(|(sensitivity, usage)| {
+            let scale = sensitivity / get_epsilon(usage)?;
+            Ok(if gaussian {
+                2. * (1.25 / get_delta(usage)?).ln() * scale.powi(2)
+            } else {
+                2. * scale.powi(2)
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,109 @@
+use crate::errors::*;
+
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::components::{Expandable, Report};
+
+use crate::base::{NodeProperties, Value};
+use crate::components::mechanism_objective_perturbation::norm_bound;
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json};
+use crate::utilities::prepend;
+
+
+impl Expandable for proto::DpLogisticRegression {
+    fn expand_component(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mechanism = proto::ObjectivePerturbationMechanism {
+            privacy_usage: self.privacy_usage.clone(),
+            regularization: self.regularization,
+            intercept: self.intercept
+        };
+
+        // sanitizing
+        let mechanism_component = proto::Component {
+            arguments: component.arguments.clone(),
+            variant: Some(proto::component::Variant::ObjectivePerturbationMechanism(mechanism.clone())),
+            omit: false,
+            batch: component.batch,
+        };
+
+        mechanism.expand_component(
+            privacy_definition,
+            &mechanism_component,
+            properties,
+            component_id,
+            maximum_id)
+    }
+}
+
+impl Report for proto::DpLogisticRegression {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let coefficients = release.array()?.f64()?.iter().cloned().collect::<Vec<f64>>();
+
+        let privacy_usage: Vec<serde_json::Value> = self.privacy_usage.iter()
+            .map(privacy_usage_to_json).collect();
+
+        let lower = data_property.lower_f64()?;
+        let upper = data_property.upper_f64()?;
+        let norm_bound = norm_bound(&data_property, self.intercept)?;
+
+        let names = (0..data_property.num_columns()? as usize)
+            .map(|column_number| variable_names
+                .and_then(|names| names.get(column_number)).cloned()
+                .unwrap_or_else(|| "[Unknown]".to_string()));
+        let names = if self.intercept { vec!["[Intercept]".to_string()] } else { Vec::new() }
+            .into_iter().chain(names).collect::<Vec<String>>();
+
+        if names.len() != coefficients.len() {
+            return Err("release: must contain one coefficient for each regressor".into())
+        }
+
+        Ok(Some(names.into_iter().zip(coefficients.into_iter())
+            .map(|(variable_name, coefficient)| JSONRelease {
+                description: "DP release information".to_string(),
+                statistic: "DPLogisticRegression".to_string(),
+                variables: serde_json::json!(variable_name),
+                release_info: serde_json::json!(coefficient),
+                privacy_loss: serde_json::json![privacy_usage],
+                accuracy: None,
+                batch: component.batch as u64,
+                node_id: *node_id as u64,
+                postprocess: false,
+                algorithm_info: AlgorithmInfo {
+                    name: "Objective perturbation".to_string(),
+                    cite: "Chaudhuri, Monteleoni, Sarwate. Differentially private empirical risk minimization. 2011".to_string(),
+                    mechanism: "ObjectivePerturbation".to_string(),
+                    argument: serde_json::json!({
+                        "intercept": self.intercept,
+                        "regularization": self.regularization,
+                        "norm_bound": norm_bound,
+                        "constraint": {
+                            "lowerbound": lower,
+                            "upperbound": upper
+                        }
+                    }),
+                },
+            })
+            .collect()))
+    }
+}
@@ -0,0 +1,115 @@
+use crate::errors::*;
+
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::hashmap;
+use crate::components::{Expandable, Report};
+use crate::components::marginals::marginal_columns;
+
+use crate::base::{NodeProperties, Value};
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
+use crate::utilities::prepend;
+
+
+impl Expandable for proto::DpMarginals {
+    fn expand_component(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        _properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut maximum_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+
+        // marginals
+        maximum_id += 1;
+        let id_marginals = maximum_id;
+        computation_graph.insert(id_marginals, proto::Component {
+            arguments: hashmap!["data".to_owned() => *component.arguments.get("data")
+                .ok_or_else(|| Error::from("data must be provided as an argument"))?],
+            variant: Some(proto::component::Variant::Marginals(proto::Marginals {
+                marginals: self.marginals.clone()
+            })),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // noising. The whole workload is a single release, so the usage is charged once
+        computation_graph.insert(*component_id, proto::Component {
+            arguments: hashmap!["data".to_owned() => id_marginals],
+            variant: Some(match self.mechanism.to_lowercase().as_str() {
+                "laplace" => proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+                    privacy_usage: self.privacy_usage.clone()
+                }),
+                "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                    privacy_usage: self.privacy_usage.clone()
+                }),
+                _ => return Err(format!("mechanism: {:?} is not recognized", self.mechanism).into()),
+            }),
+            omit: false,
+            batch: component.batch,
+        });
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases: HashMap::new(),
+            traversal: vec![id_marginals],
+        })
+    }
+}
+
+impl Report for proto::DpMarginals {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let num_columns = data_property.num_columns()? as usize;
+
+        let privacy_usage = self.privacy_usage.first()
+            .ok_or_else(|| Error::from("privacy_usage: must be defined"))?;
+
+        // name the columns of each marginal, falling back to their indices
+        let workload = self.marginals.iter()
+            .map(|marginal| Ok(marginal_columns(*marginal, num_columns)?.into_iter()
+                .map(|column| variable_names
+                    .and_then(|names| names.get(column)).cloned()
+                    .unwrap_or_else(|| column.to_string()))
+                .collect::<Vec<String>>()))
+            .collect::<Result<Vec<Vec<String>>>>()?;
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPMarginals".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: value_to_json(&release)?,
+            privacy_loss: privacy_usage_to_json(privacy_usage),
+            accuracy: None,
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "".to_string(),
+                cite: "".to_string(),
+                mechanism: self.mechanism.clone(),
+                argument: serde_json::json!({
+                    "marginals": workload,
+                    "categories": value_to_json(&Value::Jagged(data_property.categories()?))?,
+                    "order": "marginals are concatenated in the order of the workload. Within each marginal, the categories of the last variable vary fastest"
+                }),
+            },
+        }]))
+    }
+}
@@ -0,0 +1,127 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::hashmap;
+use crate::components::{Expandable, Report};
+use crate::base::{NodeProperties, Value};
+use crate::utilities::{prepend, broadcast_privacy_usage};
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
+
+
+impl Expandable for proto::DpMeanAbsoluteDeviation {
+    fn expand_component(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        _properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut maximum_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+
+        let id_data = *component.arguments.get("data")
+            .ok_or_else(|| Error::from("data must be provided as an argument"))?;
+
+        // each usage is split evenly between the median and the deviation
+        let privacy_usage = self.privacy_usage.iter()
+            .map(|usage| Ok(broadcast_privacy_usage(&[usage.clone()], 2)?.remove(0)))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()?;
+
+        // median, released first so that the deviation may be measured from it
+        maximum_id += 1;
+        let id_median = maximum_id;
+        computation_graph.insert(id_median, proto::Component {
+            arguments: hashmap!["data".to_owned() => id_data],
+            variant: Some(proto::component::Variant::DpMedian(proto::DpMedian {
+                mechanism: self.mechanism.clone(),
+                privacy_usage: privacy_usage.clone(),
+                interpolation: self.interpolation.clone(),
+            })),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // deviation
+        maximum_id += 1;
+        let id_deviation = maximum_id;
+        computation_graph.insert(id_deviation, proto::Component {
+            arguments: hashmap![
+                "data".to_owned() => id_data,
+                "center".to_owned() => id_median
+            ],
+            variant: Some(proto::component::Variant::MeanAbsoluteDeviation(proto::MeanAbsoluteDeviation {})),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // noising
+        computation_graph.insert(*component_id, proto::Component {
+            arguments: hashmap!["data".to_owned() => id_deviation],
+            variant: Some(match self.mechanism.to_lowercase().as_str() {
+                "laplace" => proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+                    privacy_usage
+                }),
+                "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                    privacy_usage
+                }),
+                _ => bail!("mechanism: unexpected value {:?}", self.mechanism)
+            }),
+            omit: component.omit,
+            batch: component.batch,
+        });
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases: HashMap::new(),
+            traversal: vec![id_median, id_deviation],
+        })
+    }
+}
+
+impl Report for proto::DpMeanAbsoluteDeviation {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let privacy_usage: Vec<serde_json::Value> = self.privacy_usage.iter()
+            .map(privacy_usage_to_json).collect();
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPMeanAbsoluteDeviation".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json![privacy_usage],
+            accuracy: None,
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "Noisy mean absolute deviation around a noisy median".to_string(),
+                cite: "".to_string(),
+                mechanism: self.mechanism.clone(),
+                argument: serde_json::json!({
+                    "n": data_property.num_records()?,
+                    "interpolation": self.interpolation,
+                    "constraint": {
+                        "lowerbound": data_property.lower_f64()?,
+                        "upperbound": data_property.upper_f64()?
+                    }
+                }),
+            },
+        }]))
+    }
+}
@@ -6,6 +6,7 @@ use std::collections::HashMap;
 use crate::{proto, base};
 use crate::hashmap;
 use crate::components::{Expandable, Report};
+use crate::components::extreme_selection::expand_extreme_selection;
 
 
 use crate::base::{NodeProperties, Value, Array};
@@ -18,10 +19,16 @@ impl Expandable for proto::DpMinimum {
         &self,
         _privacy_definition: &proto::PrivacyDefinition,
         component: &proto::Component,
-        _properties: &base::NodeProperties,
+        properties: &base::NodeProperties,
         component_id: &u32,
         maximum_id: &u32,
     ) -> Result<proto::ComponentExpansion> {
+        if self.mechanism.to_lowercase() == "exponential" {
+            return expand_extreme_selection(
+                "minimum", self.num_candidates, &self.privacy_usage,
+                component, properties, component_id, maximum_id)
+        }
+
         let mut current_id = *maximum_id;
         let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
 
@@ -105,7 +112,10 @@ impl Report for proto::DpMinimum {
                 node_id: *node_id as u64,
                 postprocess: false,
                 algorithm_info: AlgorithmInfo {
-                    name: "".to_string(),
+                    name: match self.mechanism.to_lowercase().as_str() {
+                        "exponential" => "Exponential mechanism over evenly spaced candidates".to_string(),
+                        _ => "".to_string()
+                    },
                     cite: "".to_string(),
                     mechanism: self.mechanism.clone(),
                     argument: serde_json::json!({
@@ -0,0 +1,128 @@
+use crate::errors::*;
+
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::hashmap;
+use crate::components::{Expandable, Report};
+use ndarray::arr1;
+
+use crate::base::{NodeProperties, Value, Jagged};
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
+use crate::utilities::{prepend, get_literal};
+
+
+impl Expandable for proto::DpMode {
+    fn expand_component(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut current_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        let categories = data_property.categories()
+            .map_err(|_| Error::from("data: categories must be known"))?;
+        if categories.num_columns() != 1 {
+            return Err("data: must contain a single column".into())
+        }
+
+        // categories
+        current_id += 1;
+        let id_categories = current_id;
+        let value: Value = match categories {
+            Jagged::I64(jagged) => arr1(jagged[0].as_ref().ok_or("data: categories must be known")?).into_dyn().into(),
+            Jagged::F64(jagged) => arr1(jagged[0].as_ref().ok_or("data: categories must be known")?).into_dyn().into(),
+            Jagged::Bool(jagged) => arr1(jagged[0].as_ref().ok_or("data: categories must be known")?).into_dyn().into(),
+            Jagged::Str(jagged) => arr1(jagged[0].as_ref().ok_or("data: categories must be known")?).into_dyn().into(),
+        };
+        let (patch_node, categories_release) = get_literal(&value, &component.batch)?;
+        computation_graph.insert(id_categories, patch_node);
+        releases.insert(id_categories, categories_release);
+
+        // histogram
+        current_id += 1;
+        let id_histogram = current_id;
+        computation_graph.insert(id_histogram, proto::Component {
+            arguments: hashmap!["data".to_owned() => *component.arguments.get("data")
+                .ok_or_else(|| Error::from("data: missing"))?],
+            variant: Some(proto::component::Variant::Histogram(proto::Histogram {})),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // sanitizing
+        computation_graph.insert(*component_id, proto::Component {
+            arguments: hashmap![
+                "data".to_owned() => id_histogram,
+                "categories".to_owned() => id_categories
+            ],
+            variant: Some(proto::component::Variant::TopKMechanism(proto::TopKMechanism {
+                k: 1,
+                method: "Gumbel".to_string(),
+                privacy_usage: self.privacy_usage.clone(),
+                count_privacy_usage: Vec::new()
+            })),
+            omit: false,
+            batch: component.batch,
+        });
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal: vec![id_histogram]
+        })
+    }
+}
+
+impl Report for proto::DpMode {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        let privacy_usage = self.privacy_usage.first()
+            .ok_or_else(|| Error::from("privacy_usage: must be defined"))?;
+
+        let num_categories = data_property.categories()?.lengths()?[0];
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPMode".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: value_to_json(&release)?,
+            privacy_loss: privacy_usage_to_json(privacy_usage),
+            // the accuracy depends on the neighboring definition, see privacy_usage_to_accuracy
+            accuracy: None,
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "One-shot Gumbel noise top-k".to_string(),
+                cite: "Durfee and Rogers. Practical Differentially Private Top-k Selection with Pay-what-you-get Composition. 2019".to_string(),
+                mechanism: "Exponential".to_string(),
+                argument: serde_json::json!({
+                    "num_categories": num_categories
+                }),
+            },
+        }]))
+    }
+}
@@ -0,0 +1,196 @@
+use crate::errors::*;
+
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::hashmap;
+use crate::components::{Expandable, Report};
+use ndarray::arr1;
+
+use crate::base::{NodeProperties, Value, Jagged};
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
+use crate::utilities::{prepend, get_literal, privacy_usage_reducer};
+
+
+impl Expandable for proto::DpNaiveBayes {
+    fn expand_component(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut current_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+        let data_id = *component.arguments.get("data")
+            .ok_or_else(|| Error::from("data must be provided as an argument"))?;
+        let target_id = *component.arguments.get("target")
+            .ok_or_else(|| Error::from("target must be provided as an argument"))?;
+        let target_property = properties.get("target")
+            .ok_or("target: missing")?.array()
+            .map_err(prepend("target:"))?;
+
+        let categories = target_property.categories()
+            .map_err(|_| Error::from("target: categories must be known"))?;
+        if categories.num_columns() != 1 {
+            return Err("target: must contain a single column".into())
+        }
+
+        // each of the three statistics is released by a single mechanism call
+        if self.privacy_usage.len() != 1 {
+            return Err("privacy_usage: the privacy usage is split over the statistics of the model, so exactly one privacy usage must be supplied".into())
+        }
+        let privacy_usage = statistic_privacy_usage(&self.privacy_usage);
+        let mechanism = |id_data: u32| -> Result<proto::Component> {
+            Ok(proto::Component {
+                arguments: hashmap!["data".to_owned() => id_data],
+                variant: Some(match self.mechanism.to_lowercase().as_str() {
+                    "laplace" => proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+                        privacy_usage: privacy_usage.clone()
+                    }),
+                    "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                        privacy_usage: privacy_usage.clone()
+                    }),
+                    _ => return Err("mechanism: must be one of Laplace or Gaussian".into())
+                }),
+                omit: true,
+                batch: component.batch,
+            })
+        };
+
+        // categories
+        current_id += 1;
+        let id_categories = current_id;
+        let value: Value = match categories {
+            Jagged::I64(jagged) => arr1(jagged[0].as_ref().ok_or("target: categories must be known")?).into_dyn().into(),
+            Jagged::F64(jagged) => arr1(jagged[0].as_ref().ok_or("target: categories must be known")?).into_dyn().into(),
+            Jagged::Bool(jagged) => arr1(jagged[0].as_ref().ok_or("target: categories must be known")?).into_dyn().into(),
+            Jagged::Str(jagged) => arr1(jagged[0].as_ref().ok_or("target: categories must be known")?).into_dyn().into(),
+        };
+        let (patch_node, categories_release) = get_literal(&value, &component.batch)?;
+        computation_graph.insert(id_categories, patch_node);
+        releases.insert(id_categories, categories_release);
+
+        // class counts
+        current_id += 1;
+        let id_histogram = current_id;
+        computation_graph.insert(id_histogram, proto::Component {
+            arguments: hashmap!["data".to_owned() => target_id],
+            variant: Some(proto::component::Variant::Histogram(proto::Histogram {})),
+            omit: true,
+            batch: component.batch,
+        });
+        current_id += 1;
+        let id_counts = current_id;
+        computation_graph.insert(id_counts, mechanism(id_histogram)?);
+
+        // class moments of the features
+        let mut moment_ids = Vec::new();
+        for power in 1..=2 {
+            current_id += 1;
+            let id_moments = current_id;
+            computation_graph.insert(id_moments, proto::Component {
+                arguments: hashmap![
+                    "data".to_owned() => data_id,
+                    "target".to_owned() => target_id,
+                    "categories".to_owned() => id_categories
+                ],
+                variant: Some(proto::component::Variant::ClassMoments(proto::ClassMoments { power })),
+                omit: true,
+                batch: component.batch,
+            });
+            current_id += 1;
+            computation_graph.insert(current_id, mechanism(id_moments)?);
+            moment_ids.push((id_moments, current_id));
+        }
+
+        // model
+        computation_graph.insert(*component_id, proto::Component {
+            arguments: hashmap![
+                "counts".to_owned() => id_counts,
+                "sums".to_owned() => moment_ids[0].1,
+                "squares".to_owned() => moment_ids[1].1
+            ],
+            variant: Some(proto::component::Variant::NaiveBayesModel(proto::NaiveBayesModel {})),
+            omit: false,
+            batch: component.batch,
+        });
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal: vec![id_histogram, id_counts].into_iter()
+                .chain(moment_ids.into_iter().flat_map(|(id_moments, id_noise)| vec![id_moments, id_noise]))
+                .collect()
+        })
+    }
+}
+
+impl Report for proto::DpNaiveBayes {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+        let target_property = properties.get("target")
+            .ok_or("target: missing")?.array()
+            .map_err(prepend("target:"))?;
+
+        let privacy_usage: Vec<serde_json::Value> = self.privacy_usage.iter()
+            .map(privacy_usage_to_json).collect();
+        let statistic_usage: Vec<serde_json::Value> = statistic_privacy_usage(&self.privacy_usage).iter()
+            .map(privacy_usage_to_json).collect();
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPNaiveBayes".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json![privacy_usage],
+            accuracy: None,
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "Gaussian naive Bayes from noisy class counts and moments".to_string(),
+                cite: "Vaidya, Shafiq, Basu and Hong. Differentially Private Naive Bayes Classification. 2013".to_string(),
+                mechanism: self.mechanism.clone(),
+                argument: serde_json::json!({
+                    "num_classes": target_property.categories()?.lengths()?[0],
+                    "statistics": {
+                        "counts": { "privacy_loss": statistic_usage },
+                        "sums": { "privacy_loss": statistic_usage },
+                        "squares": { "privacy_loss": statistic_usage }
+                    },
+                    "constraint": {
+                        "lowerbound": data_property.lower_f64()?,
+                        "upperbound": data_property.upper_f64()?
+                    },
+                    "postprocessing": {
+                        "operation": "priors, means and variances of each class, with counts and variances clipped at zero",
+                        "privacy_loss": 0
+                    }
+                }),
+            },
+        }]))
+    }
+}
+
+/// Privacy usage of each of the three statistics, when the total usage is split evenly between them.
+fn statistic_privacy_usage(privacy_usage: &[proto::PrivacyUsage]) -> Vec<proto::PrivacyUsage> {
+    privacy_usage.iter()
+        .map(|usage| privacy_usage_reducer(usage, usage, &|l, _| l / 3.))
+        .collect()
+}
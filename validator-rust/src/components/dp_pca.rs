@@ -0,0 +1,130 @@
+use crate::errors::*;
+
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::hashmap;
+use crate::components::{Expandable, Report};
+
+use crate::base::{NodeProperties, Value};
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
+use crate::utilities::prepend;
+
+
+impl Expandable for proto::DpPca {
+    fn expand_component(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        _properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut current_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+
+        let data_id = *component.arguments.get("data")
+            .ok_or_else(|| Error::from("data must be provided as an argument"))?;
+
+        // the covariance matrix is released by a single mechanism call
+        if self.privacy_usage.len() != 1 {
+            return Err("privacy_usage: the covariance matrix is a single release, so exactly one privacy usage must be supplied".into())
+        }
+
+        // covariance
+        current_id += 1;
+        let id_covariance = current_id;
+        computation_graph.insert(id_covariance, proto::Component {
+            arguments: hashmap!["data".to_owned() => data_id],
+            variant: Some(proto::component::Variant::Covariance(proto::Covariance {
+                finite_sample_correction: self.finite_sample_correction
+            })),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // noise
+        current_id += 1;
+        let id_noise = current_id;
+        computation_graph.insert(id_noise, proto::Component {
+            arguments: hashmap!["data".to_owned() => id_covariance],
+            variant: Some(match self.mechanism.to_lowercase().as_str() {
+                "laplace" => proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+                    privacy_usage: self.privacy_usage.clone()
+                }),
+                "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                    privacy_usage: self.privacy_usage.clone()
+                }),
+                _ => return Err("mechanism: must be one of Laplace or Gaussian".into())
+            }),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // eigendecomposition
+        computation_graph.insert(*component_id, proto::Component {
+            arguments: hashmap!["data".to_owned() => id_noise],
+            variant: Some(proto::component::Variant::PrincipalComponents(proto::PrincipalComponents {
+                num_components: self.num_components
+            })),
+            omit: false,
+            batch: component.batch
+        });
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases: HashMap::new(),
+            traversal: vec![id_covariance, id_noise]
+        })
+    }
+}
+
+impl Report for proto::DpPca {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let privacy_usage: Vec<serde_json::Value> = self.privacy_usage.iter()
+            .map(privacy_usage_to_json).collect();
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPPca".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json![privacy_usage],
+            accuracy: None,
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "Eigendecomposition of a noisy covariance matrix".to_string(),
+                cite: "".to_string(),
+                mechanism: self.mechanism.clone(),
+                argument: serde_json::json!({
+                    "n": data_property.num_records()?,
+                    "num_components": self.num_components,
+                    "constraint": {
+                        "lowerbound": data_property.lower_f64()?,
+                        "upperbound": data_property.upper_f64()?
+                    },
+                    "postprocessing": {
+                        "operation": "eigendecomposition of the covariance matrix, with variances clipped at zero",
+                        "privacy_loss": 0
+                    }
+                }),
+            },
+        }]))
+    }
+}
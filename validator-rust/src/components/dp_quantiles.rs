@@ -0,0 +1,326 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::components::{Component, Expandable, Report, Accuracy};
+use crate::base::{Value, ValueProperties, ArrayProperties, DataType, NodeProperties, Nature, NatureContinuous, Vector1DNull};
+use crate::utilities::{prepend, privacy_usage_check, get_epsilon, get_literal, get_ith_column, get_group_size};
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
+use ndarray::arr1;
+
+
+impl Component for proto::DpQuantiles {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+        data_property.assert_non_null()?;
+
+        check_alphas(&self.alphas)?;
+        get_quantiles_epsilon(&self.privacy_usage)?;
+
+        let (lower, upper) = get_bounds(&data_property)?;
+
+        Ok(ArrayProperties {
+            num_records: Some(self.alphas.len() as i64),
+            num_columns: data_property.num_columns,
+            nullity: false,
+            releasable: true,
+            public_shape: true,
+            public_metadata: true,
+            c_stability: lower.iter().map(|_| 1.).collect(),
+            aggregator: None,
+            // the estimates are sampled from within the bounds
+            nature: Some(Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::F64(lower.into_iter().map(Some).collect()),
+                upper: Vector1DNull::F64(upper.into_iter().map(Some).collect()),
+            })),
+            data_type: DataType::F64,
+            dataset_id: None,
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 2
+        }.into())
+    }
+}
+
+impl Expandable for proto::DpQuantiles {
+    fn expand_component(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut current_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let (lower, upper) = get_bounds(&data_property)?;
+
+        // each record an individual contributes may shift the rank utility by one
+        let sensitivity = data_property.c_stability.iter().cloned().fold(1., f64::max) * get_group_size(privacy_definition);
+
+        // always overwrite the bounds and sensitivity. These are not something a user may configure
+        let mut quantiles_component = component.clone();
+        for (name, value) in vec![
+            ("lower", arr1(&lower).into_dyn().into()),
+            ("upper", arr1(&upper).into_dyn().into()),
+            ("sensitivity", Value::from(sensitivity))
+        ] {
+            current_id += 1;
+            let (patch_node, release) = get_literal(&value, &component.batch)?;
+            computation_graph.insert(current_id, patch_node);
+            releases.insert(current_id, release);
+            quantiles_component.arguments.insert(name.to_string(), current_id);
+        }
+        computation_graph.insert(*component_id, quantiles_component);
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal: Vec::new()
+        })
+    }
+}
+
+impl Report for proto::DpQuantiles {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let (lower, upper) = get_bounds(&data_property)?;
+
+        let privacy_usage = self.privacy_usage.first()
+            .ok_or_else(|| Error::from("privacy_usage: must be defined"))?;
+
+        (0..lower.len()).map(|column_number| {
+            let variable_name = variable_names
+                .and_then(|names| names.get(column_number)).cloned()
+                .unwrap_or_else(|| "[Unknown]".to_string());
+
+            Ok(JSONRelease {
+                description: "DP release information".to_string(),
+                statistic: "DPQuantiles".to_string(),
+                variables: serde_json::json!(variable_name),
+                release_info: value_to_json(&get_ith_column(
+                    release.array()?.f64()?, &column_number)?.into())?,
+                // the quantiles of every column share one budget
+                privacy_loss: privacy_usage_to_json(privacy_usage),
+                accuracy: None,
+                batch: component.batch as u64,
+                node_id: *node_id as u64,
+                postprocess: false,
+                algorithm_info: AlgorithmInfo {
+                    name: "Joint exponential mechanism via recursive partitioning".to_string(),
+                    cite: "Kaplan, Schnapp and Stemmer. Differentially Private Approximate Quantiles. ICML 2022".to_string(),
+                    mechanism: "Exponential".to_string(),
+                    argument: serde_json::json!({
+                        "alphas": self.alphas,
+                        "constraint": {
+                            "lowerbound": lower[column_number],
+                            "upperbound": upper[column_number]
+                        },
+                        "levels": num_levels(self.alphas.len()),
+                        "postprocessing": {
+                            "operation": "sort each column, so that estimates are non-decreasing in alpha",
+                            "privacy_loss": 0
+                        }
+                    }),
+                },
+            })
+        }).collect::<Result<Vec<JSONRelease>>>().map(Some)
+    }
+}
+
+impl Accuracy for proto::DpQuantiles {
+    fn accuracy_to_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &base::NodeProperties,
+        accuracies: &proto::Accuracies,
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        check_alphas(&self.alphas)?;
+
+        let sensitivity = data_property.c_stability.iter().cloned().fold(1., f64::max) * get_group_size(privacy_definition);
+        let num_candidates = get_num_candidates(&data_property)?;
+        if accuracies.values.len() != num_candidates.len() {
+            return Err("accuracies: one accuracy must be supplied for each column".into())
+        }
+
+        // the quantiles of every column share one budget, so the most demanding column determines the usage
+        let epsilon = accuracies.values.iter().zip(num_candidates.iter())
+            .map(|(accuracy, num_candidates)| quantile_rank_epsilon(
+                accuracy.value, sensitivity, *num_candidates, self.alphas.len(), accuracy.alpha))
+            .fold(0., f64::max);
+
+        Ok(Some(vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon,
+                delta: 0.,
+            }))
+        }]))
+    }
+
+    fn privacy_usage_to_accuracy(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &base::NodeProperties,
+        alpha: &f64
+    ) -> Result<Option<Vec<proto::Accuracy>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        check_alphas(&self.alphas)?;
+
+        let sensitivity = data_property.c_stability.iter().cloned().fold(1., f64::max) * get_group_size(privacy_definition);
+        let epsilon = get_quantiles_epsilon(&self.privacy_usage)?;
+
+        Ok(Some(get_num_candidates(&data_property)?.into_iter()
+            .map(|num_candidates| {
+                let accuracy = quantile_rank_accuracy(
+                    epsilon, sensitivity, num_candidates, self.alphas.len(), *alpha);
+                proto::Accuracy {
+                    // no estimate may be further than n ranks from its target
+                    value: data_property.num_records
+                        .map_or(accuracy, |num_records| accuracy.min(num_records as f64)),
+                    alpha: *alpha,
+                }
+            })
+            .collect()))
+    }
+}
+
+/// Bound on the rank error of every estimate, that holds with probability at least `1 - alpha`.
+///
+/// The rank error is in excess of the error of the best unit cell of the candidate grid, which is nonzero only when records are tied.
+/// On each of the `L` levels, the exponential mechanism samples from at most `m` unit cells with `epsilon / L`,
+/// so each of the `q` estimates is within `(2 sensitivity L / epsilon) ln(m q / alpha)` ranks of the best cell of its subset
+/// with probability at least `1 - alpha / q`.
+/// The subset of an estimate is shifted by the errors of its ancestors, so the errors accumulate over the `L` levels.
+pub fn quantile_rank_accuracy(epsilon: f64, sensitivity: f64, num_candidates: f64, num_alphas: usize, alpha: f64) -> f64 {
+    let levels = num_levels(num_alphas) as f64;
+    levels.powi(2) * 2. * sensitivity * (num_candidates * num_alphas as f64 / alpha).ln() / epsilon
+}
+
+/// The epsilon at which `quantile_rank_accuracy` equals the given rank error, as the accuracy is inversely proportional to epsilon.
+pub fn quantile_rank_epsilon(accuracy: f64, sensitivity: f64, num_candidates: f64, num_alphas: usize, alpha: f64) -> f64 {
+    quantile_rank_accuracy(1., sensitivity, num_candidates, num_alphas, alpha) / accuracy
+}
+
+/// Number of unit cells in the candidate grid of each column.
+///
+/// Integer records lie on the integers within the bounds, so the grid has one cell between each consecutive pair.
+/// Float records may be arbitrarily close, so no finite grid is known, and the rank error cannot be bounded.
+pub(crate) fn get_num_candidates(data_property: &ArrayProperties) -> Result<Vec<f64>> {
+    match data_property.data_type {
+        DataType::I64 => Ok(data_property.lower_i64()?.into_iter()
+            .zip(data_property.upper_i64()?.into_iter())
+            .map(|(lower, upper)| ((upper - lower) as f64).max(1.))
+            .collect()),
+        _ => Err("data: accuracy is only available for integer data, where the candidate grid is known".into())
+    }
+}
+
+/// Alphas must be strictly increasing within (0, 1).
+pub fn check_alphas(alphas: &[f64]) -> Result<()> {
+    if alphas.is_empty() {
+        return Err("alphas: at least one quantile must be requested".into())
+    }
+    if !alphas.iter().all(|alpha| 0. < *alpha && *alpha < 1.) {
+        return Err("alphas: must be within (0, 1)".into())
+    }
+    if !alphas.windows(2).all(|w| w[0] < w[1]) {
+        return Err("alphas: must be strictly increasing".into())
+    }
+    Ok(())
+}
+
+/// Number of levels of recursion needed to estimate the given number of quantiles.
+pub fn num_levels(num_alphas: usize) -> u32 {
+    ((num_alphas + 1) as f64).log2().ceil() as u32
+}
+
+/// Epsilon shared by all quantiles. The exponential mechanism is pure, so delta must be zero.
+pub fn get_quantiles_epsilon(privacy_usage: &[proto::PrivacyUsage]) -> Result<f64> {
+    if privacy_usage.len() != 1 {
+        return Err("privacy_usage: exactly one privacy usage must be supplied".into())
+    }
+    let usage = &privacy_usage[0];
+    privacy_usage_check(usage)?;
+    if let Some(proto::privacy_usage::Distance::Approximate(approximate)) = &usage.distance {
+        if approximate.delta != 0. {
+            return Err("privacy_usage: delta must be zero".into())
+        }
+    }
+    get_epsilon(usage)
+}
+
+pub(crate) fn get_bounds(data_property: &ArrayProperties) -> Result<(Vec<f64>, Vec<f64>)> {
+    Ok(match data_property.data_type {
+        DataType::F64 => (data_property.lower_f64()?, data_property.upper_f64()?),
+        DataType::I64 => (
+            data_property.lower_i64()?.into_iter().map(|v| v as f64).collect(),
+            data_property.upper_i64()?.into_iter().map(|v| v as f64).collect()),
+        _ => return Err("data: atomic type must be numeric".into())
+    })
+}
+
+
+#[cfg(test)]
+mod test_dp_quantiles {
+    use crate::components::dp_quantiles::{check_alphas, num_levels, quantile_rank_accuracy, quantile_rank_epsilon};
+
+    #[test]
+    fn test_alphas() {
+        assert!(check_alphas(&[0.25, 0.5, 0.75]).is_ok());
+        assert!(check_alphas(&[0.5, 0.25]).is_err());
+        assert!(check_alphas(&[0., 0.5]).is_err());
+        assert!(check_alphas(&[]).is_err());
+    }
+
+    #[test]
+    fn test_levels() {
+        assert_eq!(num_levels(1), 1);
+        assert_eq!(num_levels(3), 2);
+        assert_eq!(num_levels(4), 3);
+        assert_eq!(num_levels(7), 3);
+    }
+
+    #[test]
+    fn test_rank_accuracy() {
+        let accuracy = quantile_rank_accuracy(1., 1., 100., 1, 0.05);
+        assert!((accuracy - 2. * (100. / 0.05_f64).ln()).abs() < 1e-12);
+
+        // the inversion recovers epsilon
+        let epsilon = quantile_rank_epsilon(accuracy, 1., 100., 1, 0.05);
+        assert!((epsilon - 1.).abs() < 1e-12);
+
+        // more quantiles spread the budget over more levels
+        assert!(quantile_rank_accuracy(1., 1., 100., 3, 0.05) > accuracy);
+    }
+}
@@ -0,0 +1,236 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::components::{Component, Expandable, Report};
+use crate::base::{Value, ValueProperties, ArrayProperties, DataType, NodeProperties};
+use crate::utilities::{prepend, privacy_usage_check, get_epsilon, get_literal, get_group_size};
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, Accuracy, privacy_usage_to_json, value_to_json};
+
+/// Largest number of nodes that may be released, as the tree grows exponentially with the depth.
+pub const MAX_NUM_NODES: u64 = 1 << 20;
+
+/// Significance level of the accuracy bounds in the report.
+const REPORT_ALPHA: f64 = 0.05;
+
+
+impl Component for proto::DpRangeTree {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+        data_property.assert_non_null()?;
+        if data_property.num_columns()? != 1 {
+            return Err("data: must contain a single column".into())
+        }
+        get_bounds(&data_property)?;
+        get_tree_epsilon(&self.privacy_usage)?;
+
+        Ok(ArrayProperties {
+            num_records: Some(num_nodes(self.branching, self.depth)? as i64),
+            num_columns: Some(1),
+            nullity: false,
+            releasable: true,
+            public_shape: true,
+            public_metadata: true,
+            c_stability: vec![1.],
+            aggregator: None,
+            nature: None,
+            data_type: DataType::F64,
+            dataset_id: None,
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 1
+        }.into())
+    }
+}
+
+impl Expandable for proto::DpRangeTree {
+    fn expand_component(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut current_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let (lower, upper) = get_bounds(&data_property)?;
+
+        // always overwrite the bounds and sensitivity. These are not something a user may configure
+        let mut tree_component = component.clone();
+        for (name, value) in vec![
+            ("lower", Value::from(lower)),
+            ("upper", Value::from(upper)),
+            ("sensitivity", Value::from(get_sensitivity(privacy_definition, &data_property, self.depth)?))
+        ] {
+            current_id += 1;
+            let (patch_node, release) = get_literal(&value, &component.batch)?;
+            computation_graph.insert(current_id, patch_node);
+            releases.insert(current_id, release);
+            tree_component.arguments.insert(name.to_string(), current_id);
+        }
+        computation_graph.insert(*component_id, tree_component);
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal: Vec::new()
+        })
+    }
+}
+
+impl Report for proto::DpRangeTree {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let (lower, upper) = get_bounds(&data_property)?;
+
+        let privacy_usage: Vec<serde_json::Value> = self.privacy_usage.iter()
+            .map(privacy_usage_to_json).collect();
+
+        // the privacy definition is not available when summarizing, so the bounds assume add/remove neighboring
+        let scale = (self.depth + 1) as f64 * data_property.c_stability.iter().cloned().fold(1., f64::max)
+            / get_tree_epsilon(&self.privacy_usage)?;
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPRangeTree".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json![privacy_usage],
+            accuracy: Some(Accuracy {
+                accuracy_value: node_accuracy(scale, REPORT_ALPHA),
+                alpha: REPORT_ALPHA
+            }),
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "Hierarchical histogram with consistency postprocessing".to_string(),
+                cite: "Hay, Rastogi, Miklau and Suciu. Boosting the Accuracy of Differentially Private Histograms Through Consistency. VLDB 2010".to_string(),
+                mechanism: "Laplace".to_string(),
+                argument: serde_json::json!({
+                    "depth": self.depth,
+                    "branching": self.branching,
+                    "lower": lower,
+                    "upper": upper,
+                    "rangeAccuracy": range_accuracy(scale, self.branching, self.depth, REPORT_ALPHA)
+                }),
+            },
+        }]))
+    }
+}
+
+/// Number of nodes in a complete tree with `depth` levels below the root.
+pub fn num_nodes(branching: u32, depth: u32) -> Result<u64> {
+    if branching < 2 {
+        return Err("branching: must be at least two".into())
+    }
+    if depth < 1 {
+        return Err("depth: must be at least one".into())
+    }
+    (0..=depth)
+        .try_fold(0_u64, |total, level| (branching as u64).checked_pow(level)
+            .and_then(|width| total.checked_add(width))
+            .filter(|total| *total <= MAX_NUM_NODES))
+        .ok_or_else(|| format!("the tree may contain at most {} nodes", MAX_NUM_NODES).into())
+}
+
+/// Epsilon of the release of the whole tree. The Laplace mechanism is pure, so delta must be zero.
+pub fn get_tree_epsilon(privacy_usage: &[proto::PrivacyUsage]) -> Result<f64> {
+    let usage = match privacy_usage {
+        [usage] => usage,
+        _ => return Err("privacy_usage: must contain exactly one usage".into())
+    };
+    privacy_usage_check(usage)?;
+    if let Some(proto::privacy_usage::Distance::Approximate(approximate)) = &usage.distance {
+        if approximate.delta != 0. {
+            return Err("privacy_usage: delta must be zero".into())
+        }
+    }
+    get_epsilon(usage)
+}
+
+/// L1 sensitivity of the counts of every node. A record changes the count of one node on each of the `depth + 1` levels,
+/// or of two when substituted.
+fn get_sensitivity(privacy_definition: &proto::PrivacyDefinition, data_property: &ArrayProperties, depth: u32) -> Result<f64> {
+    use proto::privacy_definition::Neighboring;
+    let neighboring_type = Neighboring::from_i32(privacy_definition.neighboring)
+        .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+
+    Ok((depth + 1) as f64 * match neighboring_type {
+        Neighboring::AddRemove => 1.,
+        Neighboring::Substitute => 2.
+    } * data_property.c_stability.iter().cloned().fold(1., f64::max) * get_group_size(privacy_definition))
+}
+
+fn get_bounds(data_property: &ArrayProperties) -> Result<(f64, f64)> {
+    let (lower, upper) = match data_property.data_type {
+        DataType::F64 => (data_property.lower_f64()?[0], data_property.upper_f64()?[0]),
+        DataType::I64 => (data_property.lower_i64()?[0] as f64, data_property.upper_i64()?[0] as f64),
+        _ => return Err("data: atomic type must be numeric".into())
+    };
+    if lower.partial_cmp(&upper) != Some(std::cmp::Ordering::Less) {
+        return Err("data: lower bound must be less than the upper bound".into())
+    }
+    Ok((lower, upper))
+}
+
+/// Half-width of the interval around the count of a single node, before consistency, at significance `alpha`.
+pub fn node_accuracy(scale: f64, alpha: f64) -> f64 {
+    scale * (1. / alpha).ln()
+}
+
+/// Half-width of the interval around the count of any range, before consistency, at significance `alpha`.
+///
+/// A range is the sum of at most `2 * (branching - 1) * depth` nodes, and a union bound over their noise is taken.
+/// Consistency only reduces the variance, so the bound remains valid after postprocessing.
+pub fn range_accuracy(scale: f64, branching: u32, depth: u32, alpha: f64) -> f64 {
+    let num_summands = (2 * (branching - 1) * depth) as f64;
+    num_summands * node_accuracy(scale, alpha / num_summands)
+}
+
+
+#[cfg(test)]
+mod test_dp_range_tree {
+    use crate::components::dp_range_tree::{num_nodes, range_accuracy, node_accuracy};
+
+    #[test]
+    fn test_num_nodes() {
+        assert_eq!(num_nodes(2, 3).unwrap(), 15);
+        assert_eq!(num_nodes(4, 2).unwrap(), 21);
+        assert!(num_nodes(1, 3).is_err());
+        assert!(num_nodes(2, 64).is_err());
+    }
+
+    #[test]
+    fn test_accuracy() {
+        // ranges sum several nodes, so are less accurate than any one node
+        assert!(range_accuracy(1., 2, 4, 0.05) > node_accuracy(1., 0.05));
+    }
+}
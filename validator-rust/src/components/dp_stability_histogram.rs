@@ -0,0 +1,159 @@
+use crate::errors::*;
+
+use std::collections::{HashMap, BTreeMap};
+
+use crate::{proto, base};
+use crate::components::{Component, Expandable, Report};
+use crate::base::{Value, ValueProperties, HashmapProperties, DataType, Hashmap, NodeProperties};
+use crate::utilities::{prepend, privacy_usage_check, get_epsilon, get_delta, get_literal, get_group_size};
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
+
+
+impl Component for proto::DpStabilityHistogram {
+    fn propagate_property(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+
+        if data_property.num_columns()? != 1 {
+            return Err("data: must contain a single column".into())
+        }
+
+        let (epsilon, delta) = get_stability_parameters(&self.privacy_usage)?;
+        // the threshold must be computable
+        stability_threshold(privacy_definition, epsilon, delta)?;
+
+        // the categories are data-dependent, so no properties are known about the individual counts
+        Ok(HashmapProperties {
+            num_records: None,
+            disjoint: false,
+            properties: match data_property.data_type {
+                DataType::Str => Hashmap::Str(BTreeMap::new()),
+                DataType::I64 => Hashmap::I64(BTreeMap::new()),
+                DataType::Bool => Hashmap::Bool(BTreeMap::new()),
+                DataType::F64 => return Err("data: categories may not be floats".into())
+            },
+            columnar: false,
+            releasable: true,
+            group_id: Vec::new()
+        }.into())
+    }
+}
+
+impl Expandable for proto::DpStabilityHistogram {
+    fn expand_component(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        _properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut current_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+        let (epsilon, delta) = get_stability_parameters(&self.privacy_usage)?;
+
+        // always overwrite the sensitivity and threshold. These are not something a user may configure
+        let mut histogram_component = component.clone();
+        for (name, value) in vec![
+            ("sensitivity", stability_sensitivity(privacy_definition)?),
+            ("threshold", stability_threshold(privacy_definition, epsilon, delta)?)
+        ] {
+            current_id += 1;
+            let (patch_node, release) = get_literal(&value.into(), &component.batch)?;
+            computation_graph.insert(current_id, patch_node);
+            releases.insert(current_id, release);
+            histogram_component.arguments.insert(name.to_string(), current_id);
+        }
+        computation_graph.insert(*component_id, histogram_component);
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal: Vec::new()
+        })
+    }
+}
+
+impl Report for proto::DpStabilityHistogram {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        _properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let privacy_usage: Vec<serde_json::Value> = self.privacy_usage.iter()
+            .map(privacy_usage_to_json).collect();
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPStabilityHistogram".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: value_to_json(&release)?,
+            privacy_loss: serde_json::json![privacy_usage],
+            accuracy: None,
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "Stability-based histogram".to_string(),
+                cite: "".to_string(),
+                mechanism: "Laplace".to_string(),
+                argument: serde_json::json!({})
+            }
+        }]))
+    }
+}
+
+/// Retrieve epsilon and delta from the privacy usage of a stability histogram.
+///
+/// Delta must be positive, as it bounds the probability that a category held by a single individual is released.
+pub fn get_stability_parameters(privacy_usage: &[proto::PrivacyUsage]) -> Result<(f64, f64)> {
+    if privacy_usage.len() != 1 {
+        return Err("privacy_usage: exactly one privacy usage must be supplied".into())
+    }
+    let usage = &privacy_usage[0];
+    privacy_usage_check(usage)?;
+
+    let delta = get_delta(usage)
+        .map_err(|_| Error::from("privacy_usage: delta must be defined"))?;
+    if delta <= 0. {
+        return Err("privacy_usage: delta must be greater than zero".into())
+    }
+    Ok((get_epsilon(usage)?, delta))
+}
+
+/// L1 sensitivity of the vector of counts. Substituting a record moves one count between two categories.
+pub fn stability_sensitivity(privacy_definition: &proto::PrivacyDefinition) -> Result<f64> {
+    use proto::privacy_definition::Neighboring;
+    Ok(match Neighboring::from_i32(privacy_definition.neighboring)
+        .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))? {
+        Neighboring::AddRemove => 1.,
+        Neighboring::Substitute => 2.
+    } * get_group_size(privacy_definition))
+}
+
+/// Noisy counts at or below this threshold are suppressed.
+///
+/// A category held by a single individual has a true count of one,
+/// and with Laplace noise of scale b the noisy count exceeds 1 + b ln(1/delta) with probability at most delta/2.
+pub fn stability_threshold(
+    privacy_definition: &proto::PrivacyDefinition, epsilon: f64, delta: f64
+) -> Result<f64> {
+    // a category held by a single group has a true count of up to the group size
+    let scale = stability_sensitivity(privacy_definition)? / epsilon;
+    Ok(get_group_size(privacy_definition) + scale * (1. / delta).ln())
+}
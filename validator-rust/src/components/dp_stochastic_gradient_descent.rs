@@ -0,0 +1,252 @@
+use crate::errors::*;
+
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::components::{Component, Report};
+use crate::base::{Value, ValueProperties, ArrayProperties, DataType, NodeProperties};
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json};
+use crate::utilities::prepend;
+
+/// Rényi orders the accountant optimizes over.
+const ORDERS: std::ops::RangeInclusive<u32> = 2..=256;
+
+
+impl Component for proto::DpStochasticGradientDescent {
+    fn propagate_property(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let target_property = properties.get("target")
+            .ok_or("target: missing")?.array()
+            .map_err(prepend("target:"))?.clone();
+
+        data_property.assert_is_not_aggregated().map_err(prepend("data:"))?;
+        data_property.assert_non_null().map_err(prepend("data:"))?;
+        target_property.assert_is_not_aggregated().map_err(prepend("target:"))?;
+        target_property.assert_non_null().map_err(prepend("target:"))?;
+
+        if data_property.data_type != DataType::F64 && data_property.data_type != DataType::I64 {
+            return Err("data: atomic type must be numeric".into())
+        }
+        if target_property.data_type != DataType::Bool {
+            return Err("target: atomic type must be boolean".into())
+        }
+        if target_property.num_columns()? != 1 {
+            return Err("target: must contain one column".into())
+        }
+
+        // the sampling rate is only meaningful relative to a public number of records
+        let num_records = data_property.num_records()
+            .map_err(|_| Error::from("data: number of records must be known. Consider resizing the data"))?;
+        if num_records < 1 {
+            return Err("data: must contain at least one record".into())
+        }
+        if let Some(target_num_records) = target_property.num_records {
+            if target_num_records != num_records {
+                return Err("data and target must have the same number of records".into())
+            }
+        }
+
+        // the subsampled Gaussian accountant bounds the loss from adding or removing a single record
+        use proto::privacy_definition::Neighboring;
+        if Neighboring::from_i32(privacy_definition.neighboring) != Some(Neighboring::AddRemove) {
+            return Err("the subsampled Gaussian accountant requires add/remove neighboring".into())
+        }
+        if privacy_definition.group_size > 1 {
+            return Err("the subsampled Gaussian accountant does not support group privacy".into())
+        }
+        if data_property.c_stability.iter().chain(target_property.c_stability.iter()).any(|c| *c > 1.) {
+            return Err("the subsampled Gaussian accountant requires each individual to contribute at most one record".into())
+        }
+
+        if !(self.clipping_norm > 0. && self.clipping_norm.is_finite()) {
+            return Err("clipping_norm: must be positive".into())
+        }
+        if !(self.learning_rate > 0. && self.learning_rate.is_finite()) {
+            return Err("learning_rate: must be positive".into())
+        }
+        sgd_privacy_usage(self)?;
+
+        let num_coefficients = data_property.num_columns()? + if self.intercept { 1 } else { 0 };
+
+        Ok(ArrayProperties {
+            num_records: Some(num_coefficients),
+            num_columns: Some(1),
+            nullity: false,
+            releasable: true,
+            public_shape: true,
+            public_metadata: true,
+            c_stability: vec![1.],
+            aggregator: None,
+            nature: None,
+            data_type: DataType::F64,
+            dataset_id: data_property.dataset_id,
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 1
+        }.into())
+    }
+}
+
+impl Report for proto::DpStochasticGradientDescent {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let coefficients = release.array()?.f64()?.iter().cloned().collect::<Vec<f64>>();
+        let privacy_usage = privacy_usage_to_json(&sgd_privacy_usage(self)?);
+
+        let names = (0..data_property.num_columns()? as usize)
+            .map(|column_number| variable_names
+                .and_then(|names| names.get(column_number)).cloned()
+                .unwrap_or_else(|| "[Unknown]".to_string()));
+        let names = if self.intercept { vec!["[Intercept]".to_string()] } else { Vec::new() }
+            .into_iter().chain(names).collect::<Vec<String>>();
+
+        if names.len() != coefficients.len() {
+            return Err("release: must contain one coefficient for each regressor".into())
+        }
+
+        Ok(Some(names.into_iter().zip(coefficients.into_iter())
+            .map(|(variable_name, coefficient)| JSONRelease {
+                description: "DP release information".to_string(),
+                statistic: "DPStochasticGradientDescent".to_string(),
+                variables: serde_json::json!(variable_name),
+                release_info: serde_json::json!(coefficient),
+                privacy_loss: serde_json::json![vec![privacy_usage.clone()]],
+                accuracy: None,
+                batch: component.batch as u64,
+                node_id: *node_id as u64,
+                postprocess: false,
+                algorithm_info: AlgorithmInfo {
+                    name: "Noisy stochastic gradient descent".to_string(),
+                    cite: "Abadi, Chu, Goodfellow, McMahan, Mironov, Talwar and Zhang. Deep Learning with Differential Privacy. 2016".to_string(),
+                    mechanism: "Gaussian".to_string(),
+                    argument: serde_json::json!({
+                        "intercept": self.intercept,
+                        "noise_multiplier": self.noise_multiplier,
+                        "sampling_rate": self.sampling_rate,
+                        "steps": self.steps,
+                        "clipping_norm": self.clipping_norm,
+                        "learning_rate": self.learning_rate
+                    }),
+                },
+            })
+            .collect()))
+    }
+}
+
+/// Privacy usage of noisy stochastic gradient descent, from its noise multiplier, sampling rate and number of steps.
+pub fn sgd_privacy_usage(component: &proto::DpStochasticGradientDescent) -> Result<proto::PrivacyUsage> {
+    let epsilon = sampled_gaussian_epsilon(
+        component.noise_multiplier, component.sampling_rate, component.steps, component.delta)?;
+
+    Ok(proto::PrivacyUsage {
+        distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+            epsilon, delta: component.delta
+        }))
+    })
+}
+
+/// Epsilon of `steps` compositions of the sampled Gaussian mechanism, at the given delta.
+///
+/// The Rényi divergence of each integer order is composed over the steps, converted to (epsilon, delta)-DP,
+/// and the smallest epsilon over the orders is returned.
+///
+/// # Example
+/// ```
+/// use whitenoise_validator::components::dp_stochastic_gradient_descent::sampled_gaussian_epsilon;
+///
+/// // subsampling amplifies privacy, so fewer records sampled on each step spends less
+/// let sampled = sampled_gaussian_epsilon(1.1, 0.01, 1000, 1e-5).unwrap();
+/// let full = sampled_gaussian_epsilon(1.1, 1., 1000, 1e-5).unwrap();
+/// assert!(sampled < full);
+/// ```
+pub fn sampled_gaussian_epsilon(noise_multiplier: f64, sampling_rate: f64, steps: u32, delta: f64) -> Result<f64> {
+    if !(noise_multiplier > 0. && noise_multiplier.is_finite()) {
+        return Err("noise_multiplier: must be positive".into())
+    }
+    if !(sampling_rate > 0. && sampling_rate <= 1.) {
+        return Err("sampling_rate: must be within (0, 1]".into())
+    }
+    if steps < 1 {
+        return Err("steps: must be at least one".into())
+    }
+    if !(delta > 0. && delta < 1.) {
+        return Err("delta: must be within (0, 1)".into())
+    }
+
+    ORDERS
+        .map(|order| steps as f64 * sampled_gaussian_rdp(noise_multiplier, sampling_rate, order)
+            + (1. / delta).ln() / (order as f64 - 1.))
+        .filter(|epsilon| epsilon.is_finite())
+        .fold(None, |minimum: Option<f64>, epsilon| Some(minimum.map_or(epsilon, |minimum| minimum.min(epsilon))))
+        .ok_or_else(|| "the privacy usage is unbounded for every Rényi order".into())
+}
+
+/// Rényi differential privacy of one step of the sampled Gaussian mechanism, at an integer order.
+///
+/// Uses the binomial expansion of Mironov, Talwar and Zhang, Rényi Differential Privacy of the Sampled Gaussian Mechanism. 2019,
+/// evaluated in log space.
+pub fn sampled_gaussian_rdp(noise_multiplier: f64, sampling_rate: f64, order: u32) -> f64 {
+    let variance = noise_multiplier.powi(2);
+    let alpha = order as f64;
+    if sampling_rate >= 1. {
+        return alpha / (2. * variance)
+    }
+
+    let mut log_binomial = 0.;
+    let log_terms = (0..=order)
+        .map(|k| {
+            if k > 0 {
+                log_binomial += (alpha - k as f64 + 1.).ln() - (k as f64).ln();
+            }
+            let k = k as f64;
+            log_binomial + (alpha - k) * (1. - sampling_rate).ln() + k * sampling_rate.ln()
+                + (k * k - k) / (2. * variance)
+        })
+        .collect::<Vec<f64>>();
+
+    let maximum = log_terms.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let log_moment = maximum + log_terms.iter().map(|term| (term - maximum).exp()).sum::<f64>().ln();
+
+    log_moment / (alpha - 1.)
+}
+
+
+#[cfg(test)]
+mod test_dp_stochastic_gradient_descent {
+    use crate::components::dp_stochastic_gradient_descent::{sampled_gaussian_rdp, sampled_gaussian_epsilon};
+
+    #[test]
+    fn test_sampled_gaussian() {
+        // without subsampling, the Rényi divergence of the Gaussian mechanism is alpha / (2 sigma^2)
+        assert!((sampled_gaussian_rdp(2., 1., 4) - 0.5).abs() < 1e-12);
+        // the expansion agrees with the closed form as the sampling rate approaches one
+        assert!((sampled_gaussian_rdp(2., 1. - 1e-12, 4) - 0.5).abs() < 1e-6);
+
+        // more steps spend more
+        let few = sampled_gaussian_epsilon(1., 0.01, 100, 1e-5).unwrap();
+        let many = sampled_gaussian_epsilon(1., 0.01, 10000, 1e-5).unwrap();
+        assert!(few < many);
+
+        assert!(sampled_gaussian_epsilon(1., 0., 100, 1e-5).is_err());
+        assert!(sampled_gaussian_epsilon(1., 0.01, 100, 0.).is_err());
+    }
+}
@@ -6,21 +6,23 @@ use crate::{proto, base};
 use crate::hashmap;
 use crate::components::{Expandable, Report};
 
-use crate::base::{NodeProperties, Value, Array};
+use crate::base::{NodeProperties, Value, Array, DataType};
 use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
-use crate::utilities::{prepend, broadcast_privacy_usage, get_ith_column};
+use crate::utilities::{prepend, broadcast_privacy_usage, get_ith_column, get_literal};
+use ndarray::arr1;
 
 impl Expandable for proto::DpSum {
     fn expand_component(
         &self,
         _privacy_definition: &proto::PrivacyDefinition,
         component: &proto::Component,
-        _properties: &base::NodeProperties,
+        properties: &base::NodeProperties,
         component_id: &u32,
         maximum_id: &u32,
     ) -> Result<proto::ComponentExpansion> {
         let mut maximum_id = *maximum_id;
         let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
 
         // sum
         maximum_id += 1;
@@ -34,10 +36,36 @@ impl Expandable for proto::DpSum {
         });
 
         if self.mechanism.to_lowercase().as_str() == "simplegeometric" {
-            let sum_max_id = *component.arguments.get("upper")
-                .ok_or_else(|| Error::from("upper must be defined for geometric mechanism"))?;
-            let sum_min_id = *component.arguments.get("lower")
-                .ok_or_else(|| Error::from("lower must be defined for geometric mechanism"))?;
+            let data_property = properties.get("data")
+                .ok_or("data: missing")?.array()
+                .map_err(prepend("data:"))?.clone();
+
+            if data_property.data_type != DataType::I64 {
+                return Err("data: atomic type must be integer for the geometric mechanism".into())
+            }
+
+            // when not supplied, bounds on the sum are derived from the integer bounds on the data
+            let mut get_sum_bound = |name: &str, bounds: Result<Vec<i64>>| -> Result<u32> {
+                if let Some(id) = component.arguments.get(name) {
+                    return Ok(*id)
+                }
+                let num_records = data_property.num_records()
+                    .map_err(|_| Error::from(format!("{} must be defined for geometric mechanism when the number of records is unknown", name)))?;
+                let sum_bounds = bounds?.into_iter()
+                    .map(|bound| bound.checked_mul(num_records)
+                        .ok_or_else(|| Error::from(format!("{}: integer overflow when bounding the sum", name))))
+                    .collect::<Result<Vec<i64>>>()?;
+
+                maximum_id += 1;
+                let id_bound = maximum_id;
+                let (patch_node, release) = get_literal(&arr1(&sum_bounds).into_dyn().into(), &component.batch)?;
+                computation_graph.insert(id_bound, patch_node);
+                releases.insert(id_bound, release);
+                Ok(id_bound)
+            };
+
+            let sum_min_id = get_sum_bound("lower", data_property.lower_i64())?;
+            let sum_max_id = get_sum_bound("upper", data_property.upper_i64())?;
 
             // noising
             computation_graph.insert(component_id.clone(), proto::Component {
@@ -77,7 +105,7 @@ impl Expandable for proto::DpSum {
         Ok(proto::ComponentExpansion {
             computation_graph,
             properties: HashMap::new(),
-            releases: HashMap::new(),
+            releases,
             traversal: vec![id_sum]
         })
     }
@@ -99,8 +127,14 @@ impl Report for proto::DpSum {
 
         let mut releases = Vec::new();
 
-        let minimums = data_property.lower_f64()?;
-        let maximums = data_property.upper_f64()?;
+        let (minimums, maximums) = match data_property.data_type {
+            DataType::I64 => (
+                data_property.lower_i64()?.into_iter().map(serde_json::Value::from).collect::<Vec<_>>(),
+                data_property.upper_i64()?.into_iter().map(serde_json::Value::from).collect::<Vec<_>>()),
+            _ => (
+                data_property.lower_f64()?.into_iter().map(serde_json::Value::from).collect::<Vec<_>>(),
+                data_property.upper_f64()?.into_iter().map(serde_json::Value::from).collect::<Vec<_>>())
+        };
 
         let num_columns = data_property.num_columns()?;
         let privacy_usages = broadcast_privacy_usage(&self.privacy_usage, num_columns as usize)?;
@@ -0,0 +1,188 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::components::{Component, Expandable, Report};
+use crate::components::marginals::marginal_columns;
+use crate::base::{Value, ValueProperties, DataType, NodeProperties};
+use crate::utilities::{prepend, privacy_usage_check, get_epsilon, get_literal, get_group_size};
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json};
+
+/// Largest cross product of the categories that may be synthesized, as the synthetic distribution is stored densely.
+pub const MAX_DOMAIN_SIZE: i64 = 1 << 20;
+
+
+impl Component for proto::DpSyntheticData {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let mut data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+        data_property.assert_non_null()?;
+
+        if data_property.data_type == DataType::F64 {
+            return Err("data: float data may not be categorical".into())
+        }
+        // the multiplicative weights are normalized to the number of records
+        data_property.num_records()
+            .map_err(|_| Error::from("data: number of records must be known. Consider resizing the data"))?;
+
+        let lengths = data_property.categories()
+            .map_err(|_| Error::from("data: categories must be known for every column"))?
+            .lengths().map_err(prepend("data:"))?;
+        let domain_size = lengths.iter()
+            .try_fold(1_i64, |size, length| size.checked_mul(*length).filter(|size| *size <= MAX_DOMAIN_SIZE))
+            .ok_or_else(|| Error::from(format!("data: the cross product of the categories may contain at most {} cells", MAX_DOMAIN_SIZE)))?;
+        if domain_size < 1 {
+            return Err("data: every column must have at least one category".into())
+        }
+
+        if self.marginals.is_empty() {
+            return Err("marginals: the workload must contain at least one marginal".into())
+        }
+        self.marginals.iter()
+            .try_for_each(|marginal| marginal_columns(*marginal, lengths.len()).map(|_| ()))?;
+
+        get_iteration_epsilons(&self.privacy_usage, self.iterations)?;
+
+        // the synthetic records are post-processed from the privatized measurements
+        data_property.releasable = true;
+        data_property.c_stability = vec![1.; lengths.len()];
+
+        Ok(data_property.into())
+    }
+}
+
+impl Expandable for proto::DpSyntheticData {
+    fn expand_component(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut current_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        // a record changes the count of one cell of each marginal by one, in either neighboring definition.
+        // The same bound applies to the error of a cell, the utility of the selection
+        let sensitivity = data_property.c_stability.iter().cloned().fold(1., f64::max) * get_group_size(privacy_definition);
+
+        // always overwrite the categories and sensitivity. These are not something a user may configure
+        let mut synthetic_component = component.clone();
+        for (name, value) in vec![
+            ("categories", Value::Jagged(data_property.categories()?)),
+            ("sensitivity", Value::from(sensitivity))
+        ] {
+            current_id += 1;
+            let (patch_node, release) = get_literal(&value, &component.batch)?;
+            computation_graph.insert(current_id, patch_node);
+            releases.insert(current_id, release);
+            synthetic_component.arguments.insert(name.to_string(), current_id);
+        }
+        computation_graph.insert(*component_id, synthetic_component);
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal: Vec::new()
+        })
+    }
+}
+
+impl Report for proto::DpSyntheticData {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        _properties: &NodeProperties,
+        _release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let privacy_usage: Vec<serde_json::Value> = self.privacy_usage.iter()
+            .map(privacy_usage_to_json).collect();
+
+        // the synthetic records may be large, so they are not repeated in the report
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPSyntheticData".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: serde_json::Value::Null,
+            privacy_loss: serde_json::json![privacy_usage],
+            accuracy: None,
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "Multiplicative weights exponential mechanism".to_string(),
+                cite: "Hardt, Ligett and McSherry. A Simple and Practical Algorithm for Differentially Private Data Release. NeurIPS 2012".to_string(),
+                mechanism: "Exponential, Laplace".to_string(),
+                argument: serde_json::json!({
+                    "marginals": self.marginals,
+                    "iterations": self.iterations
+                }),
+            },
+        }]))
+    }
+}
+
+/// Epsilon of the selection and of the measurement within each iteration.
+///
+/// The usage is split evenly over the iterations, and within each iteration evenly between the exponential and Laplace mechanisms,
+/// so the usage must be pure.
+pub fn get_iteration_epsilons(privacy_usage: &[proto::PrivacyUsage], iterations: u32) -> Result<(f64, f64)> {
+    if iterations < 1 {
+        return Err("iterations: must be at least one".into())
+    }
+    let usage = match privacy_usage {
+        [usage] => usage,
+        _ => return Err("privacy_usage: must contain exactly one usage".into())
+    };
+    privacy_usage_check(usage)?;
+    if let Some(proto::privacy_usage::Distance::Approximate(approximate)) = &usage.distance {
+        if approximate.delta != 0. {
+            return Err("privacy_usage: delta must be zero".into())
+        }
+    }
+    let epsilon = get_epsilon(usage)? / iterations as f64 / 2.;
+    Ok((epsilon, epsilon))
+}
+
+
+#[cfg(test)]
+mod test_dp_synthetic_data {
+    use crate::proto;
+    use crate::components::dp_synthetic_data::get_iteration_epsilons;
+
+    fn usage(epsilon: f64, delta: f64) -> proto::PrivacyUsage {
+        proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate { epsilon, delta }))
+        }
+    }
+
+    #[test]
+    fn test_iteration_epsilons() {
+        let (select, measure) = get_iteration_epsilons(&[usage(1., 0.)], 5).unwrap();
+        assert!((select - 0.1).abs() < 1e-12);
+        assert!((measure - 0.1).abs() < 1e-12);
+
+        assert!(get_iteration_epsilons(&[usage(1., 1e-6)], 5).is_err());
+        assert!(get_iteration_epsilons(&[usage(1., 0.)], 0).is_err());
+        assert!(get_iteration_epsilons(&[usage(1., 0.), usage(1., 0.)], 5).is_err());
+    }
+}
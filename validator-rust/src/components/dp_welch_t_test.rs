@@ -0,0 +1,272 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::components::{Component, Expandable, Report};
+use crate::base::{Value, ValueProperties, ArrayProperties, DataType, NodeProperties};
+use crate::utilities::{prepend, privacy_usage_check, get_epsilon, get_literal, get_group_size};
+use crate::utilities::json::{JSONRelease, AlgorithmInfo, Accuracy, privacy_usage_to_json, value_to_json};
+use statrs::distribution::{StudentsT, Univariate};
+
+/// Significance level of the accuracy in the report.
+const REPORT_ALPHA: f64 = 0.05;
+
+
+impl Component for proto::DpWelchTTest {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let by_property = properties.get("by")
+            .ok_or("by: missing")?.array()
+            .map_err(prepend("by:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+        data_property.assert_non_null()?;
+        if data_property.num_columns()? != 1 {
+            return Err("data: must contain a single column".into())
+        }
+        get_bounds(&data_property)?;
+
+        by_property.assert_is_not_aggregated()?;
+        if by_property.num_columns()? != 1 {
+            return Err("by: must contain a single column".into())
+        }
+        if by_property.categories().map_err(prepend("by:"))?.lengths()? != vec![2] {
+            return Err("by: must have exactly two categories".into())
+        }
+        if let (Some(data_num_records), Some(by_num_records)) = (data_property.num_records, by_property.num_records) {
+            if data_num_records != by_num_records {
+                return Err("data and by must have the same number of records".into())
+            }
+        }
+        get_test_epsilon(&self.privacy_usage)?;
+
+        Ok(ArrayProperties {
+            num_records: Some(3),
+            num_columns: Some(1),
+            nullity: false,
+            releasable: true,
+            public_shape: true,
+            public_metadata: true,
+            c_stability: vec![1.],
+            aggregator: None,
+            nature: None,
+            data_type: DataType::F64,
+            dataset_id: None,
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 1
+        }.into())
+    }
+}
+
+impl Expandable for proto::DpWelchTTest {
+    fn expand_component(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut current_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let categories = properties.get("by")
+            .ok_or("by: missing")?.array()
+            .map_err(prepend("by:"))?.categories()?;
+        let (lower, upper) = get_bounds(&data_property)?;
+
+        // always overwrite the bounds, categories and sensitivity. These are not something a user may configure
+        let mut test_component = component.clone();
+        for (name, value) in vec![
+            ("lower", Value::from(lower)),
+            ("upper", Value::from(upper)),
+            ("categories", Value::Jagged(categories)),
+            ("sensitivity", Value::from(get_sensitivity(privacy_definition, &data_property)?))
+        ] {
+            current_id += 1;
+            let (patch_node, release) = get_literal(&value, &component.batch)?;
+            computation_graph.insert(current_id, patch_node);
+            releases.insert(current_id, release);
+            test_component.arguments.insert(name.to_string(), current_id);
+        }
+        computation_graph.insert(*component_id, test_component);
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal: Vec::new()
+        })
+    }
+}
+
+impl Report for proto::DpWelchTTest {
+    fn summarize(
+        &self,
+        node_id: &u32,
+        component: &proto::Component,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<String>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let categories = properties.get("by")
+            .ok_or("by: missing")?.array()
+            .map_err(prepend("by:"))?.categories()?;
+        let (lower, upper) = get_bounds(&data_property)?;
+
+        let (statistic, degrees_of_freedom, noise_deviation) = match release.array()?.f64()?.iter().collect::<Vec<&f64>>().as_slice() {
+            [statistic, degrees_of_freedom, noise_deviation] => (**statistic, **degrees_of_freedom, **noise_deviation),
+            _ => return Err("release must contain the statistic, degrees of freedom and noise deviation".into())
+        };
+        let reference = StudentsT::new(0., 1., degrees_of_freedom)
+            .map_err(|e| Error::from(format!("reference distribution: {}", e)))?;
+
+        let privacy_usage: Vec<serde_json::Value> = self.privacy_usage.iter()
+            .map(privacy_usage_to_json).collect();
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPWelchTTest".to_string(),
+            variables: serde_json::json!(variable_names.cloned().unwrap_or_else(Vec::new)),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json![privacy_usage],
+            // Chebyshev's inequality holds for the noise of any distribution
+            accuracy: Some(Accuracy {
+                accuracy_value: noise_deviation / REPORT_ALPHA.sqrt(),
+                alpha: REPORT_ALPHA
+            }),
+            batch: component.batch as u64,
+            node_id: *node_id as u64,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "Welch's t-test on noisy sufficient statistics".to_string(),
+                cite: "".to_string(),
+                mechanism: "Laplace".to_string(),
+                argument: serde_json::json!({
+                    "groups": value_to_json(&Value::Jagged(categories))?,
+                    "lower": lower,
+                    "upper": upper,
+                    "p_value": 2. * (1. - reference.cdf(statistic.abs())),
+                    "accuracy": "first-order noise of the means only, assuming the noisy counts and variances are exact"
+                }),
+            },
+        }]))
+    }
+}
+
+/// Noisy sufficient statistics of a group, with the records centered at the midpoint of the bounds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GroupStatistics {
+    pub count: f64,
+    pub sum: f64,
+    pub sum_squares: f64,
+}
+
+/// Epsilon of the release of the statistics of each group. The Laplace mechanism is pure, so delta must be zero.
+///
+/// By parallel composition, each group spends the whole usage, which is split evenly over its three statistics.
+pub fn get_test_epsilon(privacy_usage: &[proto::PrivacyUsage]) -> Result<f64> {
+    let usage = match privacy_usage {
+        [usage] => usage,
+        _ => return Err("privacy_usage: must contain exactly one usage".into())
+    };
+    privacy_usage_check(usage)?;
+    if let Some(proto::privacy_usage::Distance::Approximate(approximate)) = &usage.distance {
+        if approximate.delta != 0. {
+            return Err("privacy_usage: delta must be zero".into())
+        }
+    }
+    get_epsilon(usage)
+}
+
+/// Welch's t-test of the difference of the mean of the first group less the mean of the second.
+///
+/// Returns the statistic, its Welch-Satterthwaite degrees of freedom,
+/// and the first-order standard deviation of the noise in the statistic due to Laplace noise of scale `sum_scale` on each sum.
+/// Counts are floored at two, and variances at zero.
+pub fn welch_t_test(groups: &[GroupStatistics; 2], sum_scale: f64) -> Result<(f64, f64, f64)> {
+    let moments = groups.iter()
+        .map(|group| {
+            let count = group.count.max(2.);
+            let variance = ((group.sum_squares - group.sum.powi(2) / count) / (count - 1.)).max(0.);
+            (count, group.sum / count, variance / count)
+        })
+        .collect::<Vec<(f64, f64, f64)>>();
+    let (count_1, mean_1, error_1) = moments[0];
+    let (count_2, mean_2, error_2) = moments[1];
+
+    let standard_error = (error_1 + error_2).sqrt();
+    if standard_error <= 0. {
+        return Err("the variance of both groups is zero".into())
+    }
+
+    let degrees_of_freedom = (error_1 + error_2).powi(2)
+        / (error_1.powi(2) / (count_1 - 1.) + error_2.powi(2) / (count_2 - 1.));
+    let noise_deviation = sum_scale * (2. * (count_1.powi(-2) + count_2.powi(-2))).sqrt() / standard_error;
+
+    Ok(((mean_1 - mean_2) / standard_error, degrees_of_freedom, noise_deviation))
+}
+
+/// Multiplier on the sensitivity of each statistic. A substituted record may move from one group to another.
+pub fn get_sensitivity(privacy_definition: &proto::PrivacyDefinition, data_property: &ArrayProperties) -> Result<f64> {
+    use proto::privacy_definition::Neighboring;
+    let neighboring_type = Neighboring::from_i32(privacy_definition.neighboring)
+        .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+
+    Ok(match neighboring_type {
+        Neighboring::AddRemove => 1.,
+        Neighboring::Substitute => 2.
+    } * data_property.c_stability.iter().cloned().fold(1., f64::max) * get_group_size(privacy_definition))
+}
+
+/// Lower and upper bounds of a single numeric column.
+pub fn get_bounds(data_property: &ArrayProperties) -> Result<(f64, f64)> {
+    let (lower, upper) = match data_property.data_type {
+        DataType::F64 => (data_property.lower_f64()?[0], data_property.upper_f64()?[0]),
+        DataType::I64 => (data_property.lower_i64()?[0] as f64, data_property.upper_i64()?[0] as f64),
+        _ => return Err("data: atomic type must be numeric".into())
+    };
+    if lower.partial_cmp(&upper) != Some(std::cmp::Ordering::Less) {
+        return Err("data: lower bound must be less than the upper bound".into())
+    }
+    Ok((lower, upper))
+}
+
+
+#[cfg(test)]
+mod test_dp_welch_t_test {
+    use crate::components::dp_welch_t_test::{welch_t_test, GroupStatistics};
+
+    #[test]
+    fn test_welch_t_test() {
+        // records [1, 2, 3] and [3, 4, 5]
+        let groups = [
+            GroupStatistics { count: 3., sum: 6., sum_squares: 14. },
+            GroupStatistics { count: 3., sum: 12., sum_squares: 50. }
+        ];
+        let (statistic, degrees_of_freedom, noise_deviation) = welch_t_test(&groups, 0.).unwrap();
+        assert!((statistic + 2. / (2. / 3.0_f64).sqrt()).abs() < 1e-12);
+        assert!((degrees_of_freedom - 4.).abs() < 1e-12);
+        assert_eq!(noise_deviation, 0.);
+
+        let constant = GroupStatistics { count: 3., sum: 3., sum_squares: 3. };
+        assert!(welch_t_test(&[constant, constant], 1.).is_err());
+    }
+}
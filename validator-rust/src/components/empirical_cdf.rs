@@ -0,0 +1,130 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::components::Component;
+use crate::base::{Value, ValueProperties, DataType, Nature, NatureContinuous, Vector1DNull};
+use crate::utilities::prepend;
+
+
+impl Component for proto::EmpiricalCdf {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let mut counts_property = properties.get("counts")
+            .ok_or("counts: missing")?.array()
+            .map_err(prepend("counts:"))?.clone();
+
+        // the cdf is computed from released counts, so this is postprocessing
+        counts_property.assert_is_releasable().map_err(prepend("counts:"))?;
+
+        if counts_property.data_type != DataType::F64 && counts_property.data_type != DataType::I64 {
+            return Err("counts: atomic type must be numeric".into())
+        }
+        if counts_property.num_columns()? != 1 {
+            return Err("counts: must contain one column".into())
+        }
+
+        if properties.contains_key("num_records") {
+            let num_records = public_arguments.get("num_records")
+                .ok_or_else(|| Error::from("num_records: must be public"))?.first_i64()?;
+            if num_records < 1 {
+                return Err("num_records: must be positive".into())
+            }
+        }
+
+        // one count per bin, and a trailing count for records at the left edge
+        let num_points = counts_property.num_records()? - 2;
+        if num_points < 1 {
+            return Err("counts: must contain at least three counts".into())
+        }
+
+        counts_property.num_records = Some(num_points);
+        counts_property.aggregator = None;
+        counts_property.data_type = DataType::F64;
+        counts_property.nature = Some(Nature::Continuous(NatureContinuous {
+            lower: Vector1DNull::F64(vec![Some(0.)]),
+            upper: Vector1DNull::F64(vec![Some(1.)]),
+        }));
+
+        Ok(counts_property.into())
+    }
+}
+
+/// Cumulative proportions of records at or below the right edge of every bin but the last.
+///
+/// `counts` holds one count per bin, followed by the count of records at the left edge of the first bin.
+/// The proportions are projected onto non-decreasing sequences and clipped to [0, 1].
+/// Neither step may increase the maximum distance to the true cumulative proportions.
+pub fn empirical_cdf(counts: &[f64], num_records: Option<f64>) -> Result<Vec<f64>> {
+    if counts.len() < 3 {
+        return Err("at least three counts are required".into())
+    }
+    let (left_edge, bins) = counts.split_last().unwrap();
+
+    let total = num_records.unwrap_or_else(|| bins.iter().sum::<f64>() + left_edge);
+    // an empty or overwhelmingly negative noisy total leaves nothing to normalize by
+    let total = total.max(1.);
+
+    let cumulative = bins[..bins.len() - 1].iter()
+        .scan(*left_edge, |cumulative, count| {
+            *cumulative += count;
+            Some(*cumulative / total)
+        })
+        .collect::<Vec<f64>>();
+
+    Ok(isotonic_regression(&cumulative).into_iter()
+        .map(|v| v.max(0.).min(1.))
+        .collect())
+}
+
+/// Least-squares projection onto non-decreasing sequences, via the pool adjacent violators algorithm.
+pub fn isotonic_regression(values: &[f64]) -> Vec<f64> {
+    // each block is a (mean, size) pair
+    let mut blocks: Vec<(f64, usize)> = Vec::with_capacity(values.len());
+
+    for value in values {
+        blocks.push((*value, 1));
+        // merge with preceding blocks until the block means are non-decreasing
+        while blocks.len() > 1 && blocks[blocks.len() - 2].0 > blocks[blocks.len() - 1].0 {
+            let (mean_r, size_r) = blocks.pop().unwrap();
+            let (mean_l, size_l) = blocks.pop().unwrap();
+            let size = size_l + size_r;
+            blocks.push(((mean_l * size_l as f64 + mean_r * size_r as f64) / size as f64, size));
+        }
+    }
+
+    blocks.into_iter()
+        .flat_map(|(mean, size)| std::iter::repeat(mean).take(size))
+        .collect()
+}
+
+
+#[cfg(test)]
+mod test_empirical_cdf {
+    use crate::components::empirical_cdf::{empirical_cdf, isotonic_regression};
+
+    #[test]
+    fn test_isotonic_regression() {
+        assert_eq!(isotonic_regression(&[1., 3., 2., 4.]), vec![1., 2.5, 2.5, 4.]);
+        assert_eq!(isotonic_regression(&[3., 2., 1.]), vec![2., 2., 2.]);
+        assert_eq!(isotonic_regression(&[]), Vec::<f64>::new());
+    }
+
+    #[test]
+    fn test_empirical_cdf() {
+        // bins (0, 1], (1, 2], (2, 3], with two records at 0
+        let counts = vec![2., 4., 2., 2.];
+        assert_eq!(empirical_cdf(&counts, None).unwrap(), vec![0.4, 0.8]);
+        assert_eq!(empirical_cdf(&counts, Some(20.)).unwrap(), vec![0.2, 0.4]);
+
+        // noise that breaks monotonicity is pooled, and negative proportions are clipped
+        let counts = vec![-3., -1., 4., 0.];
+        assert_eq!(empirical_cdf(&counts, Some(10.)).unwrap(), vec![0., 0.]);
+        assert!(empirical_cdf(&[1., 1.], None).is_err());
+    }
+}
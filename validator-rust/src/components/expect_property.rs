@@ -0,0 +1,130 @@
+use crate::errors::*;
+
+use crate::components::Component;
+use std::collections::HashMap;
+use crate::base::{Value, Array, ArrayProperties, ValueProperties, NodeProperties, DataType};
+use crate::utilities::prepend;
+use crate::proto;
+
+
+impl Component for proto::ExpectProperty {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+    ) -> Result<ValueProperties> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let mut differences = Vec::new();
+
+        for name in &["lower", "upper"] {
+            if let Some(expected) = public_arguments.get(*name) {
+                let expected = get_expected_bounds(expected.array()?, data_property.num_columns)
+                    .map_err(prepend(&format!("{}:", name)))?;
+                let found = get_bounds(&data_property, name == &"lower");
+                if found.as_ref() != Some(&expected) {
+                    differences.push(format!("{}: expected {:?}, found {}", name, expected, match found {
+                        Some(found) => format!("{:?}", found),
+                        None => "unknown bounds".to_string()
+                    }));
+                }
+            }
+        }
+
+        if let Some(expected) = public_arguments.get("num_records") {
+            let expected = expected.first_i64().map_err(prepend("num_records:"))?;
+            if data_property.num_records != Some(expected) {
+                differences.push(format!("num_records: expected {}, found {}", expected, match data_property.num_records {
+                    Some(found) => found.to_string(),
+                    None => "an unknown number of records".to_string()
+                }));
+            }
+        }
+
+        if let Some(expected) = public_arguments.get("public") {
+            let expected = expected.first_bool().map_err(prepend("public:"))?;
+            if data_property.releasable != expected {
+                differences.push(format!("public: expected {}, found {}", expected, data_property.releasable));
+            }
+        }
+
+        let publicness = data_property.publicness();
+        for (name, found) in &[("public_shape", publicness.shape), ("public_metadata", publicness.metadata)] {
+            if let Some(expected) = public_arguments.get(*name) {
+                let expected = expected.first_bool().map_err(prepend(&format!("{}:", name)))?;
+                if *found != expected {
+                    differences.push(format!("{}: expected {}, found {}", name, expected, found));
+                }
+            }
+        }
+
+        if !differences.is_empty() {
+            return Err(format!("expectations do not hold:\n    {}", differences.join("\n    ")).into())
+        }
+
+        // expectations are a no-op; the data and its properties pass through unchanged
+        Ok(data_property.into())
+    }
+}
+
+/// Expected bounds as floats, broadcast to the number of columns if a single bound is given.
+fn get_expected_bounds(expected: &Array, num_columns: Option<i64>) -> Result<Vec<f64>> {
+    let expected = match expected {
+        Array::F64(expected) => expected.iter().cloned().collect::<Vec<f64>>(),
+        Array::I64(expected) => expected.iter().map(|v| *v as f64).collect(),
+        _ => return Err("bounds must be numeric".into())
+    };
+    Ok(match (expected.as_slice(), num_columns) {
+        ([bound], Some(num_columns)) => vec![*bound; num_columns as usize],
+        _ => expected
+    })
+}
+
+/// Propagated bounds as floats, if every bound is known.
+fn get_bounds(property: &ArrayProperties, lower: bool) -> Option<Vec<f64>> {
+    match (&property.data_type, lower) {
+        (DataType::F64, true) => property.lower_f64().ok(),
+        (DataType::F64, false) => property.upper_f64().ok(),
+        (DataType::I64, true) => property.lower_i64().ok()
+            .map(|bounds| bounds.into_iter().map(|v| v as f64).collect()),
+        (DataType::I64, false) => property.upper_i64().ok()
+            .map(|bounds| bounds.into_iter().map(|v| v as f64).collect()),
+        _ => None
+    }
+}
+
+
+#[cfg(test)]
+mod test_expect_property {
+    use crate::components::Component;
+    use crate::base::{Value, ValueProperties};
+    use crate::proto;
+    use crate::utilities::inference::infer_property;
+    use std::collections::HashMap;
+    use ndarray::arr0;
+
+    #[test]
+    fn test_expectations() {
+        let data: Value = ndarray::arr2(&[[1., 2.], [3., 4.]]).into_dyn().into();
+        let mut properties = HashMap::<String, ValueProperties>::new();
+        properties.insert("data".to_string(), infer_property(&data).unwrap());
+
+        let mut public_arguments = HashMap::<String, Value>::new();
+        public_arguments.insert("num_records".to_string(), arr0(2).into_dyn().into());
+        public_arguments.insert("public".to_string(), arr0(true).into_dyn().into());
+        assert!(proto::ExpectProperty {}
+            .propagate_property(&proto::PrivacyDefinition::default(), &public_arguments, &properties).is_ok());
+
+        public_arguments.insert("lower".to_string(), arr0(0.).into_dyn().into());
+        public_arguments.insert("num_records".to_string(), arr0(3).into_dyn().into());
+        let message = proto::ExpectProperty {}
+            .propagate_property(&proto::PrivacyDefinition::default(), &public_arguments, &properties)
+            .unwrap_err().to_string();
+        assert!(message.contains("lower: expected [0.0, 0.0], found [1.0, 2.0]"));
+        assert!(message.contains("num_records: expected 3, found 2"));
+        assert!(!message.contains("public"));
+    }
+}
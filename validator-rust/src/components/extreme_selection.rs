@@ -0,0 +1,182 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::hashmap;
+use crate::components::{Component, Expandable};
+use crate::base::{Value, ValueProperties, ArrayProperties, DataType, Jagged, Nature, NatureContinuous, Vector1DNull};
+use crate::utilities::{prepend, broadcast_privacy_usage, privacy_usage_check, get_literal, get_group_size};
+use crate::components::dp_histogram::equal_width_edges;
+use crate::components::dp_quantiles::get_bounds;
+
+
+impl Component for proto::ExtremeSelection {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+        data_property.assert_non_null()?;
+
+        if data_property.data_type != DataType::F64 && data_property.data_type != DataType::I64 {
+            return Err("data: atomic type must be numeric".into())
+        }
+        check_extreme(&self.extreme)?;
+
+        let num_columns = data_property.num_columns()?;
+
+        // the candidates must not reveal anything about the data
+        let candidates = match public_arguments.get("candidates") {
+            Some(candidates) => candidates.jagged()?.clone(),
+            None => return Err("candidates: must be public".into())
+        };
+        let candidates = match candidates {
+            Jagged::F64(candidates) => candidates,
+            _ => return Err("candidates: must be floats".into())
+        };
+        if candidates.len() as i64 != num_columns {
+            return Err("candidates: must be defined for each column".into())
+        }
+
+        let (lower, upper) = candidates.iter()
+            .map(|column| match column {
+                Some(column) if !column.is_empty() => Ok((
+                    column.iter().cloned().fold(std::f64::INFINITY, f64::min),
+                    column.iter().cloned().fold(std::f64::NEG_INFINITY, f64::max))),
+                _ => Err("candidates: each column must have at least one candidate".into())
+            })
+            .collect::<Result<Vec<(f64, f64)>>>()?
+            .into_iter().unzip::<f64, f64, Vec<f64>, Vec<f64>>();
+
+        let usages = broadcast_privacy_usage(&self.privacy_usage, num_columns as usize)?;
+        usages.iter().map(privacy_usage_check).collect::<Result<()>>()?;
+
+        Ok(ArrayProperties {
+            num_records: Some(1),
+            num_columns: Some(num_columns),
+            nullity: false,
+            releasable: true,
+            public_shape: true,
+            public_metadata: true,
+            c_stability: (0..num_columns).map(|_| 1.).collect(),
+            aggregator: None,
+            // the release is always one of the candidates
+            nature: Some(Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::F64(lower.into_iter().map(Some).collect()),
+                upper: Vector1DNull::F64(upper.into_iter().map(Some).collect()),
+            })),
+            data_type: DataType::F64,
+            dataset_id: None,
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 1
+        }.into())
+    }
+}
+
+impl Expandable for proto::ExtremeSelection {
+    fn expand_component(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut current_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        // an individual may shift the count on either side of a candidate by each of their records
+        let sensitivity = data_property.c_stability.iter().cloned().fold(1., f64::max) * get_group_size(privacy_definition);
+
+        // always overwrite the sensitivity. This is not something a user may configure
+        current_id += 1;
+        let (patch_node, release) = get_literal(&sensitivity.into(), &component.batch)?;
+        computation_graph.insert(current_id, patch_node);
+        releases.insert(current_id, release);
+
+        let mut selection_component = component.clone();
+        selection_component.arguments.insert("sensitivity".to_string(), current_id);
+        computation_graph.insert(*component_id, selection_component);
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal: Vec::new()
+        })
+    }
+}
+
+pub fn check_extreme(extreme: &str) -> Result<()> {
+    match extreme.to_lowercase().as_str() {
+        "minimum" | "maximum" => Ok(()),
+        _ => Err(format!("extreme: {:?} must be one of [minimum, maximum]", extreme).into())
+    }
+}
+
+/// Expand a DP extreme into a selection over evenly spaced candidates between the bounds of the data.
+pub(crate) fn expand_extreme_selection(
+    extreme: &str,
+    num_candidates: u32,
+    privacy_usage: &[proto::PrivacyUsage],
+    component: &proto::Component,
+    properties: &base::NodeProperties,
+    component_id: &u32,
+    maximum_id: &u32,
+) -> Result<proto::ComponentExpansion> {
+    let mut current_id = *maximum_id;
+    let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+    let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+    let data_property = properties.get("data")
+        .ok_or("data: missing")?.array()
+        .map_err(prepend("data:"))?.clone();
+    let (lower, upper) = get_bounds(&data_property)?;
+
+    if num_candidates < 2 {
+        return Err("num_candidates: must be at least two".into())
+    }
+
+    // candidates
+    current_id += 1;
+    let id_candidates = current_id;
+    let (patch_node, release) = get_literal(
+        &equal_width_edges(&lower, &upper, num_candidates - 1)?, &component.batch)?;
+    computation_graph.insert(id_candidates, patch_node);
+    releases.insert(id_candidates, release);
+
+    // sanitizing
+    computation_graph.insert(*component_id, proto::Component {
+        arguments: hashmap![
+            "data".to_owned() => *component.arguments.get("data")
+                .ok_or_else(|| Error::from("data: missing"))?,
+            "candidates".to_owned() => id_candidates
+        ],
+        variant: Some(proto::component::Variant::ExtremeSelection(proto::ExtremeSelection {
+            extreme: extreme.to_string(),
+            privacy_usage: privacy_usage.to_vec()
+        })),
+        omit: false,
+        batch: component.batch,
+    });
+
+    Ok(proto::ComponentExpansion {
+        computation_graph,
+        properties: HashMap::new(),
+        releases,
+        traversal: vec![id_candidates]
+    })
+}
@@ -0,0 +1,57 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::components::Component;
+use crate::base::{Value, ValueProperties, ArrayProperties, DataType, Nature, NatureContinuous, Vector1DNull};
+use crate::utilities::prepend;
+
+
+impl Component for proto::GiniCoefficient {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let (numerator_property, mean_property) = match (properties.get("numerator"), properties.get("mean")) {
+            (Some(numerator), Some(mean)) => (
+                numerator.array().map_err(prepend("numerator:"))?.clone(),
+                mean.array().map_err(prepend("mean:"))?.clone()),
+            _ => return Err("numerator and mean must both be provided".into())
+        };
+
+        // the coefficient is postprocessing, and may only be computed from released values
+        numerator_property.assert_is_releasable().map_err(prepend("numerator:"))?;
+        mean_property.assert_is_releasable().map_err(prepend("mean:"))?;
+        if numerator_property.data_type != DataType::F64 || mean_property.data_type != DataType::F64 {
+            return Err("numerator and mean: atomic type must be float".into())
+        }
+        let num_columns = numerator_property.num_columns()?;
+        if mean_property.num_columns != Some(num_columns) {
+            return Err("numerator and mean must have the same number of columns".into())
+        }
+
+        Ok(ArrayProperties {
+            num_records: Some(1),
+            num_columns: Some(num_columns),
+            // the coefficient is undefined where the noisy mean is not positive
+            nullity: true,
+            releasable: true,
+            public_shape: true,
+            public_metadata: true,
+            c_stability: vec![1.; num_columns as usize],
+            aggregator: None,
+            nature: Some(Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::F64(vec![Some(0.); num_columns as usize]),
+                upper: Vector1DNull::F64(vec![Some(1.); num_columns as usize]),
+            })),
+            data_type: DataType::F64,
+            dataset_id: numerator_property.dataset_id,
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: numerator_property.dimensionality
+        }.into())
+    }
+}
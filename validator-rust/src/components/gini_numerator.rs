@@ -0,0 +1,85 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+
+use crate::components::{Component, Sensitivity};
+use crate::base::{Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, DataType};
+use crate::utilities::prepend;
+use ndarray::prelude::*;
+
+impl Component for proto::GiniNumerator {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let mut data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        if data_property.data_type != DataType::F64 {
+            return Err("data: atomic type must be float".into())
+        }
+
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+        }
+        data_property.assert_is_not_empty()?;
+        if data_property.lower_f64()?.iter().any(|lower| *lower < 0.) {
+            return Err("data: lower bound must be non-negative".into())
+        }
+
+        // save a snapshot of the state when aggregating
+        data_property.aggregator = Some(AggregatorProperties {
+            component: proto::component::Variant::GiniNumerator(self.clone()),
+            properties: properties.clone()
+        });
+        data_property.num_records = Some(1);
+        Ok(data_property.into())
+    }
+}
+
+impl Sensitivity for proto::GiniNumerator {
+    /// Changing one record alters the absolute differences of `2 (n - 1)` ordered pairs,
+    /// each by at most the upper bound, as the data is non-negative.
+    fn compute_sensitivity(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &NodeProperties,
+        sensitivity_type: &SensitivitySpace
+    ) -> Result<Value> {
+        // the number of records is known, and an added or removed record is resized into a substitution,
+        // so both neighboring definitions share the same sensitivity
+        use proto::privacy_definition::Neighboring;
+        Neighboring::from_i32(privacy_definition.neighboring)
+            .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+
+        match sensitivity_type {
+            SensitivitySpace::KNorm(k) => {
+                let data_property = properties.get("data")
+                    .ok_or("data: missing")?.array()
+                    .map_err(prepend("data:"))?.clone();
+
+                data_property.assert_non_null()?;
+                data_property.assert_is_not_aggregated()?;
+                let data_upper = data_property.upper_f64()?;
+                let data_n = data_property.num_records()? as f64;
+
+                let row_sensitivity = match k {
+                    1 | 2 => data_upper.iter()
+                        .map(|max| (data_n - 1.) * max / data_n.powi(2))
+                        .collect::<Vec<f64>>(),
+                    _ => return Err("KNorm sensitivity is only supported in L1 and L2 spaces".into())
+                };
+
+                let mut array_sensitivity = Array::from(row_sensitivity).into_dyn();
+                array_sensitivity.insert_axis_inplace(Axis(0));
+
+                Ok(array_sensitivity.into())
+            },
+            _ => Err("GiniNumerator sensitivity is only implemented for KNorm".into())
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use crate::errors::*;
 
 use std::collections::HashMap;
-use crate::base::{Array, Value, ValueProperties, Hashmap, ArrayProperties, Nature, NatureContinuous, NatureCategorical, Vector1DNull, Jagged};
+use crate::base::{Array, Value, ValueProperties, Hashmap, ArrayProperties, HashmapProperties};
 
 use crate::{proto, base};
 use crate::components::{Component, Named};
@@ -27,6 +27,10 @@ impl Component for proto::Index {
 
         let properties = match data_property {
             ValueProperties::Hashmap(data_property) => {
+                // partitions are indexed by a path of keys, rather than stacked
+                if !data_property.group_id.is_empty() {
+                    return index_partition(&data_property, &column_names)
+                }
                 // TODO: Should columnar stacking of partitions be allowed?
                 if !data_property.columnar {
                     return Err("data to Index must be columnar".into())
@@ -95,18 +99,28 @@ impl Component for proto::Index {
                     data_property.assert_is_not_aggregated()?;
                 }
 
-                match column_names {
+                let indices = match column_names {
                     Array::I64(indices) => to_name_vec(&indices)?.into_iter()
-                        .map(|index| select_properties(&data_property, &(index as usize)))
-                        .collect::<Result<Vec<ValueProperties>>>(),
+                        .map(|index| index as usize).collect::<Vec<usize>>(),
                     Array::Bool(mask) => to_name_vec(&mask)?.into_iter()
                         .enumerate().filter(|(_, mask)| *mask)
-                        .map(|(idx, _)| select_properties(&data_property, &idx))
-                        .collect::<Result<Vec<ValueProperties>>>(),
+                        .map(|(idx, _)| idx).collect::<Vec<usize>>(),
                     _ => return Err("when indexing an array, the data type of the indices must be integer column number(s) or a boolean mask".into())
+                };
+
+                if data_property.aggregator.is_some() {
+                    return Err("indexing is not currently supported on aggregated data".into())
                 }
+
+                // select all columns at once, rather than cloning and re-stacking the properties once per column
+                let mut data_property = data_property.select_columns(&indices)?;
+                data_property.dimensionality = dimensionality;
+                // this is a library-wide assumption - that datasets have more than zero rows
+                data_property.is_not_empty = true;
+                return Ok(ValueProperties::Array(data_property))
             },
-            ValueProperties::Jagged(_) => Err("indexing is not supported on vectors".into())
+            ValueProperties::Jagged(_) => Err("indexing is not supported on vectors".into()),
+            ValueProperties::Scalar(_) => Err("indexing is not supported on scalars".into())
         }?;
 
         stack_properties(&properties, dimensionality)
@@ -141,6 +155,49 @@ impl Named for proto::Index {
     }
 }
 
+/// Properties of the cell of a partition at a path of keys, with one key for each level of nested partitions to traverse.
+///
+/// The cell may itself be a partition, if fewer keys are supplied than there are levels.
+/// As when indexing a dataframe, a scalar key drops the column axis of the cell.
+pub fn index_partition(data_property: &HashmapProperties, keys: &Array) -> Result<ValueProperties> {
+    fn get_cell(data_property: &HashmapProperties, keys: &Array, depth: usize) -> Result<ValueProperties> {
+        let (cell, num_keys) = match (&data_property.properties, keys) {
+            (Hashmap::Str(cells), Array::Str(keys)) => {
+                let keys = to_name_vec(keys)?;
+                (keys.get(depth).and_then(|key| cells.get(key)), keys.len())
+            },
+            (Hashmap::I64(cells), Array::I64(keys)) => {
+                let keys = to_name_vec(keys)?;
+                (keys.get(depth).and_then(|key| cells.get(key)), keys.len())
+            },
+            (Hashmap::Bool(cells), Array::Bool(keys)) => {
+                let keys = to_name_vec(keys)?;
+                (keys.get(depth).and_then(|key| cells.get(key)), keys.len())
+            },
+            _ => return Err("columns: keys must have the same type as the keys of the partition".into())
+        };
+        let cell = cell.ok_or_else(|| Error::from("columns: unknown key in partition"))?;
+
+        match (cell, depth + 1 < num_keys) {
+            (ValueProperties::Hashmap(cell), true) => get_cell(cell, keys, depth + 1),
+            (_, true) => Err("columns: more keys were supplied than there are levels of nested partitions".into()),
+            (cell, false) => Ok(cell.clone())
+        }
+    }
+
+    let cell = get_cell(data_property, keys, 0)?;
+    match (cell, keys.shape().is_empty()) {
+        (ValueProperties::Array(mut cell), true) => {
+            if cell.num_columns != Some(1) {
+                return Err("columns: a scalar key may only index cells with one column. Pass a vector of keys to retain every column".into())
+            }
+            cell.dimensionality = 1;
+            Ok(cell.into())
+        },
+        (cell, _) => Ok(cell)
+    }
+}
+
 pub fn to_name_vec<T: Clone>(columns: &ArrayD<T>) -> Result<Vec<T>> {
     match columns.ndim() {
         0 => Ok(vec![columns.first()
@@ -163,44 +220,6 @@ pub fn mask_columns<T: Clone>(column_names: &[T], mask: &[bool]) -> Result<Vec<T
         .collect::<Vec<T>>())
 }
 
-fn take<T: Clone>(vector: &[T], index: &usize) -> Result<T> {
-    match vector.get(*index) {
-        Some(value) => Ok(value.clone()),
-        None => Err("property column index is out of bounds".into())
-    }
-}
-
-fn select_properties(properties: &ArrayProperties, index: &usize) -> Result<ValueProperties> {
-    let mut properties = properties.clone();
-    properties.c_stability = vec![take(&properties.c_stability, index)?];
-    properties.num_columns = Some(1);
-    if let Some(nature) = &properties.nature {
-        properties.nature = Some(match nature {
-            Nature::Continuous(continuous) => Nature::Continuous(NatureContinuous {
-                lower: match &continuous.lower {
-                    Vector1DNull::F64(lower) => Vector1DNull::F64(vec![take(lower, index)?]),
-                    Vector1DNull::I64(lower) => Vector1DNull::I64(vec![take(lower, index)?]),
-                    _ => return Err("lower must be numeric".into())
-                },
-                upper: match &continuous.upper {
-                    Vector1DNull::F64(upper) => Vector1DNull::F64(vec![take(upper, index)?]),
-                    Vector1DNull::I64(upper) => Vector1DNull::I64(vec![take(upper, index)?]),
-                    _ => return Err("upper must be numeric".into())
-                },
-            }),
-            Nature::Categorical(categorical) => Nature::Categorical(NatureCategorical {
-                categories: match &categorical.categories {
-                    Jagged::F64(cats) => Jagged::F64(vec![take(&cats, index)?]),
-                    Jagged::I64(cats) => Jagged::I64(vec![take(&cats, index)?]),
-                    Jagged::Bool(cats) => Jagged::Bool(vec![take(&cats, index)?]),
-                    Jagged::Str(cats) => Jagged::Str(vec![take(&cats, index)?]),
-                }
-            })
-        })
-    }
-    Ok(ValueProperties::Array(properties))
-}
-
 fn get_common_value<T: Clone + Eq>(values: &Vec<T>) -> Option<T> {
     if values.windows(2).all(|w| w[0] == w[1]) {
         values.first().cloned()
@@ -237,7 +256,13 @@ fn stack_properties(all_properties: &Vec<ValueProperties>, dimensionality: u32)
             }),
         nullity: get_common_value(&all_properties.iter().map(|prop| prop.nullity).collect()).unwrap_or(true),
         releasable: get_common_value(&all_properties.iter().map(|prop| prop.releasable).collect()).unwrap_or(true),
+        public_shape: all_properties.iter().all(|prop| prop.publicness().shape),
+        public_metadata: all_properties.iter().all(|prop| prop.publicness().metadata),
         c_stability: all_properties.iter().flat_map(|prop| prop.c_stability.clone()).collect(),
+        // the indexed columns share their records, so the bound is only known if every column is bounded
+        max_contributions: all_properties.iter()
+            .map(|prop| prop.max_contributions)
+            .try_fold(0, |bound, max_contributions| Some(bound.max(max_contributions?))),
         aggregator: None,
         nature: None,
         data_type: get_common_value(&all_properties.iter().map(|prop| prop.data_type.clone()).collect())
@@ -0,0 +1,59 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::components::Component;
+use crate::base::{Value, ValueProperties, ArrayProperties, DataType, Nature, NatureContinuous, Vector1DNull};
+use crate::utilities::prepend;
+
+
+impl Component for proto::InterquartileRange {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        // the range is postprocessing, and may only be computed from released quartiles
+        data_property.assert_is_releasable().map_err(prepend("data:"))?;
+        if data_property.data_type != DataType::F64 {
+            return Err("data: atomic type must be float".into())
+        }
+        if data_property.num_records != Some(2) {
+            return Err("data: must contain a row for the lower and upper quartile".into())
+        }
+        let num_columns = data_property.num_columns()?;
+
+        // the released quartiles lie within the bounds of the data, so the range is at most their width
+        let nature = match (data_property.lower_f64(), data_property.upper_f64()) {
+            (Ok(lower), Ok(upper)) => Some(Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::F64(lower.iter().map(|_| Some(0.)).collect()),
+                upper: Vector1DNull::F64(lower.iter().zip(upper.iter())
+                    .map(|(lower, upper)| Some(upper - lower)).collect()),
+            })),
+            _ => None
+        };
+
+        Ok(ArrayProperties {
+            num_records: Some(1),
+            num_columns: Some(num_columns),
+            nullity: false,
+            releasable: true,
+            public_shape: true,
+            public_metadata: true,
+            c_stability: vec![1.; num_columns as usize],
+            aggregator: None,
+            nature,
+            data_type: DataType::F64,
+            dataset_id: None,
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 1
+        }.into())
+    }
+}
@@ -0,0 +1,119 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::components::Component;
+use crate::base::{Value, Array, Jagged, ValueProperties, DataType, Nature, NatureContinuous, NatureCategorical, Vector1DNull};
+use crate::utilities::{prepend, deduplicate};
+use crate::utilities::inference::infer_nullity;
+use ndarray::ArrayD;
+use std::hash::Hash;
+
+
+impl Component for proto::JoinPublic {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let mut data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+        data_property.assert_non_null()?;
+        if data_property.num_columns()? != 1 {
+            return Err("data: must contain a single column".into())
+        }
+
+        let get_public = |name: &str| public_arguments.get(name)
+            .ok_or_else(|| Error::from(format!("{}: must be public", name)))?.array()
+            .map_err(prepend(&format!("{}:", name)));
+        let (keys, values, default) = (get_public("keys")?, get_public("values")?, get_public("default")?);
+
+        if keys.num_columns()? != 1 {
+            return Err("keys: must contain a single column".into())
+        }
+        // unique keys match each record to at most one row, so the join cannot duplicate records
+        let num_keys = match (keys, &data_property.data_type) {
+            (Array::Bool(keys), DataType::Bool) => count_unique(keys),
+            (Array::I64(keys), DataType::I64) => count_unique(keys),
+            (Array::Str(keys), DataType::Str) => count_unique(keys),
+            (Array::F64(_), _) => return Err("keys: float data may not be used as a key".into()),
+            _ => return Err("data and keys must be homogeneously typed".into())
+        };
+        if num_keys != keys.num_records()? {
+            return Err("keys: must be unique".into())
+        }
+        if values.num_records()? != num_keys {
+            return Err("values: must have one row for each key".into())
+        }
+
+        // the default row matches the remaining records, so the join cannot drop records
+        let num_columns = values.num_columns()?;
+        if default.num_records()? != 1 || default.num_columns()? != num_columns {
+            return Err("default: must be a single row with one value for each column of values".into())
+        }
+
+        // the joined data is aligned with the records of the private data, so the dataset id and stability are kept
+        let contribution_bound = data_property.c_stability.iter().cloned().fold(1., f64::max);
+        data_property.c_stability = vec![contribution_bound; num_columns as usize];
+        data_property.num_columns = Some(num_columns);
+        data_property.dimensionality = values.shape().len().max(1) as u32;
+        data_property.nullity = infer_nullity(&Value::Array(values.clone()))? || infer_nullity(&Value::Array(default.clone()))?;
+        data_property.nature = Some(get_nature(values, default, num_columns)?);
+        data_property.data_type = match values {
+            Array::Bool(_) => DataType::Bool,
+            Array::I64(_) => DataType::I64,
+            Array::F64(_) => DataType::F64,
+            Array::Str(_) => DataType::Str,
+        };
+
+        Ok(data_property.into())
+    }
+}
+
+fn count_unique<T: Clone + Eq + Hash + Ord>(keys: &ArrayD<T>) -> i64 {
+    deduplicate(keys.iter().cloned().collect()).len() as i64
+}
+
+/// Nature of the joined data, which takes the values of the lookup table or the default.
+fn get_nature(values: &Array, default: &Array, num_columns: i64) -> Result<Nature> {
+    Ok(match (values, default) {
+        (Array::F64(values), Array::F64(default)) => {
+            let columns = get_columns(values, default, num_columns);
+            Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::F64(columns.iter()
+                    .map(|column| Some(column.iter().cloned().fold(std::f64::INFINITY, f64::min))).collect()),
+                upper: Vector1DNull::F64(columns.iter()
+                    .map(|column| Some(column.iter().cloned().fold(std::f64::NEG_INFINITY, f64::max))).collect()),
+            })
+        }
+        (Array::I64(values), Array::I64(default)) => {
+            let columns = get_columns(values, default, num_columns);
+            Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::I64(columns.iter().map(|column| column.iter().min().cloned()).collect()),
+                upper: Vector1DNull::I64(columns.iter().map(|column| column.iter().max().cloned()).collect()),
+            })
+        }
+        (Array::Bool(values), Array::Bool(default)) => Nature::Categorical(NatureCategorical {
+            categories: Jagged::Bool(get_columns(values, default, num_columns).into_iter()
+                .map(|column| Some(deduplicate(column))).collect())
+        }),
+        (Array::Str(values), Array::Str(default)) => Nature::Categorical(NatureCategorical {
+            categories: Jagged::Str(get_columns(values, default, num_columns).into_iter()
+                .map(|column| Some(deduplicate(column))).collect())
+        }),
+        _ => return Err("values and default must be homogeneously typed".into())
+    })
+}
+
+/// All values of each column of the lookup table, including the default.
+fn get_columns<T: Clone>(values: &ArrayD<T>, default: &ArrayD<T>, num_columns: i64) -> Vec<Vec<T>> {
+    let mut columns = vec![Vec::new(); num_columns as usize];
+    values.iter().chain(default.iter()).enumerate()
+        .for_each(|(index, value)| columns[index % num_columns as usize].push(value.clone()));
+    columns
+}
@@ -0,0 +1,168 @@
+use crate::errors::*;
+
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+
+use crate::components::{Component, Sensitivity};
+use crate::base::{Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, DataType, Nature, NatureContinuous, Vector1DNull};
+use crate::utilities::prepend;
+use ndarray::prelude::*;
+
+
+impl Component for proto::KMeansStatistics {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let mut data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let centroids_property = properties.get("centroids")
+            .ok_or("centroids: missing")?.array()
+            .map_err(prepend("centroids:"))?.clone();
+
+        if data_property.data_type != DataType::F64 {
+            return Err("data: atomic type must be float".into())
+        }
+        data_property.assert_is_not_empty()?;
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+        }
+
+        // the assignment of records to clusters must not depend on private data
+        centroids_property.assert_is_releasable().map_err(prepend("centroids:"))?;
+        if centroids_property.data_type != DataType::F64 {
+            return Err("centroids: atomic type must be float".into())
+        }
+
+        let num_columns = data_property.num_columns()?;
+        if centroids_property.num_columns()? != num_columns {
+            return Err("centroids: must have one column for each column of the data".into())
+        }
+        let num_clusters = centroids_property.num_records()?;
+        if num_clusters < 1 {
+            return Err("centroids: must contain at least one centroid".into())
+        }
+
+        // save a snapshot of the state when aggregating
+        data_property.aggregator = Some(AggregatorProperties {
+            component: proto::component::Variant::KMeansStatistics(self.clone()),
+            properties: properties.clone(),
+        });
+
+        let num_statistics = num_clusters * (num_columns + 1);
+        let c_stability = data_property.c_stability.iter().cloned().fold(1., f64::max);
+
+        data_property.num_records = Some(1);
+        data_property.num_columns = Some(num_statistics);
+        data_property.c_stability = vec![c_stability; num_statistics as usize];
+        data_property.data_type = DataType::F64;
+        data_property.nature = None;
+
+        Ok(data_property.into())
+    }
+}
+
+impl Sensitivity for proto::KMeansStatistics {
+    /// Each record falls into exactly one cluster, so it changes one count by one, and the sums of one cluster by its features.
+    /// When a record is substituted, it may move to another cluster, changing each sum by at most the magnitude of the features.
+    fn compute_sensitivity(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &NodeProperties,
+        sensitivity_type: &SensitivitySpace,
+    ) -> Result<Value> {
+        match sensitivity_type {
+            SensitivitySpace::KNorm(k) => {
+                if k != &1 && k != &2 {
+                    return Err("KNorm sensitivity is only supported in L1 and L2 spaces".into())
+                }
+
+                let data_property = properties.get("data")
+                    .ok_or("data: missing")?.array()
+                    .map_err(prepend("data:"))?.clone();
+                data_property.assert_is_not_aggregated()?;
+                data_property.assert_non_null()?;
+
+                let centroids_property = properties.get("centroids")
+                    .ok_or("centroids: missing")?.array()
+                    .map_err(prepend("centroids:"))?.clone();
+                let num_clusters = centroids_property.num_records()? as usize;
+
+                use proto::privacy_definition::Neighboring;
+                let neighboring_type = Neighboring::from_i32(privacy_definition.neighboring)
+                    .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+
+                let sum_sensitivities = data_property.lower_f64()?.into_iter()
+                    .zip(data_property.upper_f64()?.into_iter())
+                    .map(|(lower, upper)| match neighboring_type {
+                        Neighboring::AddRemove => lower.abs().max(upper.abs()),
+                        Neighboring::Substitute => (upper - lower).max(lower.abs()).max(upper.abs())
+                    })
+                    .collect::<Vec<f64>>();
+
+                // counts of each cluster, followed by the sums of each cluster
+                let row_sensitivity = (0..num_clusters).map(|_| 1.)
+                    .chain((0..num_clusters).flat_map(|_| sum_sensitivities.iter().cloned()))
+                    .collect::<Vec<f64>>();
+
+                let mut array_sensitivity = Array::from(row_sensitivity).into_dyn();
+                array_sensitivity.insert_axis_inplace(Axis(0));
+
+                Ok(array_sensitivity.into())
+            }
+            _ => Err("KMeansStatistics sensitivity is only implemented for KNorm".into())
+        }
+    }
+}
+
+impl Component for proto::KMeansCentroids {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let mut centroids_property = properties.get("centroids")
+            .ok_or("centroids: missing")?.array()
+            .map_err(prepend("centroids:"))?.clone();
+
+        // the centroids are updated from released statistics, so this is postprocessing
+        data_property.assert_is_releasable().map_err(prepend("data:"))?;
+        centroids_property.assert_is_releasable().map_err(prepend("centroids:"))?;
+
+        let num_clusters = centroids_property.num_records()?;
+        let num_columns = centroids_property.num_columns()?;
+        if data_property.num_records()? != 1 || data_property.num_columns()? != num_clusters * (num_columns + 1) {
+            return Err("data: must contain a single row, with a count and a sum of each column for every centroid".into())
+        }
+
+        centroids_property.nature = match (self.lower.is_empty(), self.upper.is_empty()) {
+            (true, true) => None,
+            (false, false) => {
+                if self.lower.len() as i64 != num_columns || self.upper.len() as i64 != num_columns {
+                    return Err("lower and upper: must have one bound for each column".into())
+                }
+                if self.lower.iter().zip(self.upper.iter()).any(|(lower, upper)| lower > upper) {
+                    return Err("lower: must not be greater than upper".into())
+                }
+                Some(Nature::Continuous(NatureContinuous {
+                    lower: Vector1DNull::F64(self.lower.iter().cloned().map(Some).collect()),
+                    upper: Vector1DNull::F64(self.upper.iter().cloned().map(Some).collect()),
+                }))
+            },
+            _ => return Err("lower and upper: must either both be set, or both be unset".into())
+        };
+        centroids_property.aggregator = None;
+        centroids_property.nullity = false;
+
+        Ok(centroids_property.into())
+    }
+}
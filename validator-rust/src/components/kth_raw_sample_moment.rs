@@ -28,6 +28,9 @@ impl Component for proto::KthRawSampleMoment {
             data_property.assert_is_not_aggregated()?;
         }
         data_property.assert_is_not_empty()?;
+        if self.k == 0 {
+            return Err("k: must be greater than zero".into())
+        }
 
         // save a snapshot of the state when aggregating
         data_property.aggregator = Some(AggregatorProperties {
@@ -42,10 +45,16 @@ impl Component for proto::KthRawSampleMoment {
 impl Sensitivity for proto::KthRawSampleMoment {
     fn compute_sensitivity(
         &self,
-        _privacy_definition: &proto::PrivacyDefinition,
+        privacy_definition: &proto::PrivacyDefinition,
         properties: &NodeProperties,
         sensitivity_type: &SensitivitySpace
     ) -> Result<Value> {
+        // the number of records is known, and an added or removed record is resized into a substitution,
+        // so both neighboring definitions share the same sensitivity
+        use proto::privacy_definition::Neighboring;
+        Neighboring::from_i32(privacy_definition.neighboring)
+            .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+
         let data_property = properties.get("data")
             .ok_or("data: missing")?.array()
             .map_err(prepend("data:"))?.clone();
@@ -60,7 +69,7 @@ impl Sensitivity for proto::KthRawSampleMoment {
                 let num_records = data_property.num_records()?;
 
                 let row_sensitivity = lower.iter().zip(upper.iter())
-                    .map(|(min, max)| (max - min).powi(self.k as i32) / (num_records as f64))
+                    .map(|(min, max)| power_range(*min, *max, self.k) / (num_records as f64))
                     .collect::<Vec<f64>>();
 
                 let mut array_sensitivity = Array::from(row_sensitivity).into_dyn();
@@ -71,4 +80,31 @@ impl Sensitivity for proto::KthRawSampleMoment {
             _ => Err("KthRawSampleMoment sensitivity is only implemented for KNorm of 1".into())
         }
     }
-}
\ No newline at end of file
+}
+/// Width of the range of `x^k` for `x` within `[lower, upper]`.
+///
+/// The extremes of `x^k` are at the bounds, or at zero when an even power is taken over bounds that straddle zero.
+pub fn power_range(lower: f64, upper: f64, k: u32) -> f64 {
+    let (lower_power, upper_power) = (lower.powi(k as i32), upper.powi(k as i32));
+    let minimum = match k % 2 == 0 && lower < 0. && upper > 0. {
+        true => 0.,
+        false => lower_power.min(upper_power)
+    };
+    lower_power.max(upper_power) - minimum
+}
+
+
+#[cfg(test)]
+mod test_kth_raw_sample_moment {
+    use crate::components::kth_raw_sample_moment::power_range;
+
+    #[test]
+    fn test_power_range() {
+        // the width of the bounds underestimates the range of the power away from zero
+        assert_eq!(power_range(1., 2., 2), 3.);
+        assert_eq!(power_range(-1., 2., 2), 4.);
+        assert_eq!(power_range(-2., 1., 3), 9.);
+        assert_eq!(power_range(-3., -1., 2), 8.);
+        assert_eq!(power_range(0., 5., 1), 5.);
+    }
+}
@@ -0,0 +1,203 @@
+use crate::errors::*;
+
+use std::collections::{HashMap, BTreeMap};
+
+use crate::{proto, base};
+use crate::components::Component;
+use crate::base::{Value, ValueProperties, HashmapProperties, Hashmap, DataType};
+use crate::utilities::prepend;
+
+
+impl Component for proto::LinearRegression {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let mut data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        // the coefficients are solved from released statistics, so this is postprocessing
+        data_property.assert_is_releasable().map_err(prepend("data:"))?;
+
+        if data_property.data_type != DataType::F64 {
+            return Err("data: atomic type must be float".into())
+        }
+        if data_property.num_records()? != 1 {
+            return Err("data: must contain a single row of statistics".into())
+        }
+        let num_statistics = data_property.num_columns()? as usize;
+        let num_regressors = num_regressors(num_statistics).map_err(prepend("data:"))?;
+
+        if self.noise_variance.len() != num_statistics {
+            return Err("noise_variance: must contain one variance for each statistic".into())
+        }
+        if self.regularization < 0. {
+            return Err("regularization: must be non-negative".into())
+        }
+
+        data_property.num_records = Some(num_regressors as i64);
+        data_property.num_columns = Some(1);
+        data_property.c_stability = vec![1.];
+        data_property.aggregator = None;
+        data_property.nature = None;
+        data_property.dimensionality = 1;
+
+        Ok(HashmapProperties {
+            num_records: Some(num_regressors as i64),
+            disjoint: false,
+            properties: Hashmap::<ValueProperties>::Str(vec![
+                ("coefficients".to_string(), data_property.clone().into()),
+                ("standard_errors".to_string(), data_property.into())
+            ].into_iter().collect::<BTreeMap<String, ValueProperties>>()),
+            columnar: true,
+            releasable: true,
+            group_id: Vec::new()
+        }.into())
+    }
+}
+
+/// Number of regressors `q`, when there are `q (q + 3) / 2` statistics.
+pub fn num_regressors(num_statistics: usize) -> Result<usize> {
+    (1..).map(|q| (q, q * (q + 3) / 2))
+        .take_while(|(_, length)| *length <= num_statistics)
+        .find(|(_, length)| *length == num_statistics)
+        .map(|(q, _)| q)
+        .ok_or_else(|| "number of statistics does not match the layout of CrossProducts".into())
+}
+
+/// Position of the cross product of regressors `i <= j` in the flattened upper triangle.
+fn triangle_index(i: usize, j: usize, num_regressors: usize) -> usize {
+    i * num_regressors - i * i.saturating_sub(1) / 2 + (j - i)
+}
+
+/// Least-squares coefficients, and their approximate standard errors due to the noise on the statistics.
+///
+/// `statistics` is laid out as in CrossProducts: the upper triangle of `Z^T Z`, followed by `Z^T y`.
+/// The ridge penalty is added to the diagonal of `Z^T Z`, except for the intercept.
+///
+/// The coefficients solve `A b = c`. Perturbations `E` of `A` and `e` of `c` move the coefficients by about `A^-1 (e - E b)`,
+/// so the standard errors are the square roots of the diagonal of `A^-1 Cov(e - E b) A^-1`, evaluated at the noisy `A` and `b`.
+pub fn linear_regression(
+    statistics: &[f64], noise_variance: &[f64], regularization: f64, intercept: bool,
+) -> Result<(Vec<f64>, Vec<f64>)> {
+    let q = num_regressors(statistics.len())?;
+    if noise_variance.len() != statistics.len() {
+        return Err("noise_variance: must contain one variance for each statistic".into())
+    }
+    let offset = q * (q + 1) / 2;
+
+    let symmetric = |values: &[f64], i: usize, j: usize| values[triangle_index(i.min(j), i.max(j), q)];
+
+    let gram = (0..q)
+        .map(|i| (0..q).map(|j| symmetric(statistics, i, j)
+            + if i == j && !(intercept && i == 0) { regularization } else { 0. })
+            .collect::<Vec<f64>>())
+        .collect::<Vec<Vec<f64>>>();
+
+    let coefficients = solve(&gram, &statistics[offset..])?;
+
+    // covariance of e - E b
+    let perturbation = (0..q)
+        .map(|k| (0..q).map(|l| if k == l {
+            noise_variance[offset + k] + (0..q)
+                .map(|m| symmetric(noise_variance, k, m) * coefficients[m].powi(2))
+                .sum::<f64>()
+        } else {
+            symmetric(noise_variance, k, l) * coefficients[k] * coefficients[l]
+        }).collect::<Vec<f64>>())
+        .collect::<Vec<Vec<f64>>>();
+
+    // columns of A^-1, which is symmetric
+    let inverse = (0..q)
+        .map(|i| solve(&gram, &(0..q).map(|j| if i == j { 1. } else { 0. }).collect::<Vec<f64>>()))
+        .collect::<Result<Vec<Vec<f64>>>>()?;
+
+    let standard_errors = inverse.iter()
+        .map(|row| (0..q)
+            .map(|k| (0..q).map(|l| row[k] * perturbation[k][l] * row[l]).sum::<f64>())
+            .sum::<f64>().max(0.).sqrt())
+        .collect();
+
+    Ok((coefficients, standard_errors))
+}
+
+/// Solve the linear system `A x = b` by Gaussian elimination with partial pivoting.
+pub fn solve(matrix: &[Vec<f64>], vector: &[f64]) -> Result<Vec<f64>> {
+    let n = vector.len();
+    if matrix.len() != n || matrix.iter().any(|row| row.len() != n) {
+        return Err("matrix must be square, with one row for each element of the vector".into())
+    }
+
+    // augmented matrix
+    let mut rows = matrix.iter().zip(vector.iter())
+        .map(|(row, value)| row.iter().cloned().chain(std::iter::once(*value)).collect::<Vec<f64>>())
+        .collect::<Vec<Vec<f64>>>();
+
+    let scale = matrix.iter().flatten().cloned().fold(0_f64, |max, v: f64| max.max(v.abs()));
+    let tolerance = scale * n as f64 * std::f64::EPSILON;
+
+    for column in 0..n {
+        let pivot = (column..n)
+            .max_by(|l, r| rows[*l][column].abs().partial_cmp(&rows[*r][column].abs())
+                .unwrap_or(std::cmp::Ordering::Equal))
+            .unwrap();
+        if rows[pivot][column].is_nan() || rows[pivot][column].abs() <= tolerance {
+            return Err("matrix is singular. Consider increasing the regularization".into())
+        }
+        rows.swap(column, pivot);
+
+        let pivot_row = rows[column].clone();
+        for row in rows.iter_mut().skip(column + 1) {
+            let factor = row[column] / pivot_row[column];
+            row.iter_mut().zip(pivot_row.iter()).skip(column)
+                .for_each(|(value, pivot_value)| *value -= factor * pivot_value);
+        }
+    }
+
+    let mut solution = vec![0.; n];
+    for (row, values) in rows.iter().enumerate().rev() {
+        let residual = values[n] - (row + 1..n).map(|k| values[k] * solution[k]).sum::<f64>();
+        solution[row] = residual / values[row];
+    }
+    Ok(solution)
+}
+
+
+#[cfg(test)]
+mod test_linear_regression {
+    use crate::components::linear_regression::{linear_regression, num_regressors, solve};
+
+    #[test]
+    fn test_num_regressors() {
+        assert_eq!(num_regressors(2).unwrap(), 1);
+        assert_eq!(num_regressors(5).unwrap(), 2);
+        assert_eq!(num_regressors(9).unwrap(), 3);
+        assert!(num_regressors(4).is_err());
+    }
+
+    #[test]
+    fn test_solve() {
+        let matrix = [vec![2., 1.], vec![1., 3.]];
+        let solution = solve(&matrix, &[3., 5.]).unwrap();
+        assert!((solution[0] - 0.8).abs() < 1e-12);
+        assert!((solution[1] - 1.4).abs() < 1e-12);
+        assert!(solve(&[vec![1., 2.], vec![2., 4.]], &[1., 2.]).is_err());
+    }
+
+    #[test]
+    fn test_linear_regression() {
+        // y = 1 + 2x, at x = 0, 1, 2
+        // Z^T Z = [[3, 3], [3, 5]], Z^T y = [9, 13]
+        let statistics = [3., 3., 5., 9., 13.];
+        let (coefficients, standard_errors) = linear_regression(&statistics, &[0.; 5], 0., true).unwrap();
+        assert!((coefficients[0] - 1.).abs() < 1e-12);
+        assert!((coefficients[1] - 2.).abs() < 1e-12);
+        assert!(standard_errors.iter().all(|error| *error == 0.));
+
+        let (_, standard_errors) = linear_regression(&statistics, &[1.; 5], 0., true).unwrap();
+        assert!(standard_errors.iter().all(|error| *error > 0.));
+    }
+}
@@ -0,0 +1,166 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::proto;
+
+use crate::components::{Component, Sensitivity, Expandable};
+use crate::components::contingency_table::num_cells;
+use crate::base::{Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, DataType, NatureContinuous, Nature, Vector1DNull};
+use crate::utilities::{prepend, get_literal};
+use ndarray::Array;
+
+
+impl Component for proto::Marginals {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &NodeProperties,
+    ) -> Result<ValueProperties> {
+        let mut data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+        }
+
+        let lengths = data_property.categories()
+            .map_err(|_| Error::from("data: categories must be known for every column"))?.lengths()?;
+
+        data_property.num_records = Some(workload_cells(&self.marginals, &lengths)?.iter().sum());
+        data_property.num_columns = Some(1);
+        data_property.dimensionality = 1;
+
+        // save a snapshot of the state when aggregating
+        data_property.aggregator = Some(AggregatorProperties {
+            component: proto::component::Variant::Marginals(self.clone()),
+            properties: properties.clone()
+        });
+
+        data_property.nature = Some(Nature::Continuous(NatureContinuous {
+            lower: Vector1DNull::I64(vec![Some(0)]),
+            upper: Vector1DNull::I64(vec![None]),
+        }));
+        data_property.data_type = DataType::I64;
+
+        Ok(data_property.into())
+    }
+}
+
+impl Expandable for proto::Marginals {
+    /// Pass the categories of each column, which are known statically
+    fn expand_component(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+        let categories = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.categories()?;
+
+        // always overwrite the categories. These are not something a user may configure
+        let id_categories = *maximum_id + 1;
+        let (patch_node, categories_release) = get_literal(&Value::Jagged(categories), &component.batch)?;
+        computation_graph.insert(id_categories, patch_node);
+        releases.insert(id_categories, categories_release);
+
+        let mut marginals_component = component.clone();
+        marginals_component.arguments.insert("categories".to_string(), id_categories);
+        computation_graph.insert(*component_id, marginals_component);
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal: Vec::new()
+        })
+    }
+}
+
+impl Sensitivity for proto::Marginals {
+    /// Each record falls into exactly one cell of each marginal, so each marginal has the sensitivity of a contingency table.
+    /// The norms of the marginals add up in the L1 space, and add up in quadrature in the L2 space.
+    fn compute_sensitivity(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &NodeProperties,
+        sensitivity_type: &SensitivitySpace
+    ) -> Result<Value> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        data_property.assert_is_not_aggregated()?;
+
+        use proto::privacy_definition::Neighboring;
+        let neighboring_type = Neighboring::from_i32(privacy_definition.neighboring)
+            .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+
+        let num_cells = workload_cells(&self.marginals, &data_property.categories()?.lengths()?)?
+            .iter().sum::<i64>();
+        let num_marginals = self.marginals.len() as f64;
+        let contribution_bound = data_property.c_stability.iter().cloned().fold(1., f64::max);
+
+        let sensitivity = match sensitivity_type {
+            SensitivitySpace::KNorm(k) => contribution_bound * match (neighboring_type, k) {
+                (Neighboring::AddRemove, 1) => num_marginals,
+                (Neighboring::AddRemove, 2) => num_marginals.sqrt(),
+                (Neighboring::Substitute, 1) => 2. * num_marginals,
+                (Neighboring::Substitute, 2) => (2. * num_marginals).sqrt(),
+                _ => return Err("KNorm sensitivity is only supported in L1 and L2 spaces".into())
+            },
+            _ => return Err("Marginals sensitivity is only implemented for KNorm".into())
+        };
+
+        // as in the ContingencyTable, the privacy usage is distributed evenly over all cells
+        Ok(Array::from_shape_vec(
+            vec![num_cells as usize, 1],
+            (0..num_cells).map(|_| sensitivity / num_cells as f64).collect())?.into_dyn().into())
+    }
+}
+
+/// Indices of the columns in a marginal, from its bitmask.
+pub fn marginal_columns(marginal: u64, num_columns: usize) -> Result<Vec<usize>> {
+    if marginal == 0 {
+        return Err("marginals: each marginal must contain at least one column".into())
+    }
+    if num_columns < 64 && marginal >> num_columns != 0 {
+        return Err(format!("marginals: {:#b} refers to a column beyond the {} columns of the data", marginal, num_columns).into())
+    }
+    Ok((0..64).filter(|column| (marginal >> column) & 1 == 1).collect())
+}
+
+/// Number of cells in each marginal of the workload, given the number of categories of each column.
+fn workload_cells(marginals: &[u64], lengths: &[i64]) -> Result<Vec<i64>> {
+    if marginals.is_empty() {
+        return Err("marginals: the workload must contain at least one marginal".into())
+    }
+    marginals.iter()
+        .map(|marginal| num_cells(&marginal_columns(*marginal, lengths.len())?.into_iter()
+            .map(|column| lengths[column])
+            .collect::<Vec<i64>>()))
+        .collect()
+}
+
+
+#[cfg(test)]
+mod test_marginals {
+    use crate::components::marginals::{marginal_columns, workload_cells};
+
+    #[test]
+    fn test_workload() {
+        assert_eq!(marginal_columns(0b101, 3).unwrap(), vec![0, 2]);
+        assert!(marginal_columns(0, 3).is_err());
+        assert!(marginal_columns(0b1000, 3).is_err());
+
+        assert_eq!(workload_cells(&[0b001, 0b110], &[2, 3, 4]).unwrap(), vec![2, 12]);
+        assert!(workload_cells(&[], &[2, 3, 4]).is_err());
+    }
+}
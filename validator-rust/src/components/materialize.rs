@@ -23,6 +23,13 @@ impl Component for proto::Materialize {
         let data_source = self.data_source.clone()
             .ok_or_else(|| Error::from("data source must be supplied"))?;
 
+        // each individual may influence up to this many records
+        let c_stability = data_source.max_contributions_per_individual.max(1) as f64;
+        let max_contributions = match data_source.max_contributions_per_individual {
+            0 => None,
+            bound => Some(bound)
+        };
+
         match data_source.value.as_ref()
             .ok_or_else(|| Error::from("data_source variant must be defined"))? {
             proto::data_source::Value::Literal(value) => {
@@ -50,16 +57,21 @@ impl Component for proto::Materialize {
                                 num_columns: Some(1),
                                 nullity: true,
                                 releasable: self.public,
-                                c_stability: vec![1.],
+                                public_shape: self.public,
+                                public_metadata: self.public,
+                                c_stability: vec![c_stability],
                                 aggregator: None,
                                 nature: None,
                                 data_type: data_type.clone(),
                                 dataset_id: self.dataset_id.as_ref().and_then(parse_i64_null),
                                 // this is a library-wide assumption - that datasets initially have more than zero rows
                                 is_not_empty: true,
+                                max_contributions,
                                 dimensionality: 1
                             }))).collect()),
-                            columnar: true
+                            columnar: true,
+                            releasable: false,
+                            group_id: Vec::new()
                         })),
                         true => return Err("column_names on value-materialized public data is not currently supported. Use num_columns instead.".into())
                     }
@@ -70,13 +82,16 @@ impl Component for proto::Materialize {
                             num_columns: Some(column_names.len() as i64),
                             nullity: true,
                             releasable: false,
-                            c_stability: column_names.iter().map(|_| 1.).collect(),
+                            public_shape: false,
+                            public_metadata: false,
+                            c_stability: column_names.iter().map(|_| c_stability).collect(),
                             aggregator: None,
                             nature: None,
                             data_type,
                             dataset_id: self.dataset_id.as_ref().and_then(parse_i64_null),
                             // this is a library-wide assumption - that datasets initially have more than zero rows
                             is_not_empty: true,
+                            max_contributions,
                             dimensionality: array.shape.len() as u32
                         })),
                         true => infer_property(&parse_value(value)?)
@@ -92,16 +107,21 @@ impl Component for proto::Materialize {
                         num_columns: Some(1),
                         nullity: true,
                         releasable: self.public,
-                        c_stability: vec![1.],
+                        public_shape: self.public,
+                        public_metadata: self.public,
+                        c_stability: vec![c_stability],
                         aggregator: None,
                         nature: None,
                         data_type: DataType::Str,
                         dataset_id: self.dataset_id.as_ref().and_then(parse_i64_null),
                         // this is a library-wide assumption - that datasets initially have more than zero rows
                         is_not_empty: true,
+                        max_contributions,
                         dimensionality: 1
                     }))).collect()),
                 columnar: true,
+                releasable: false,
+                group_id: Vec::new(),
             }.into()),
             data_source => Err(format!("data source format is not supported: {:?}", data_source).into())
         }
@@ -47,10 +47,16 @@ impl Component for proto::Maximum {
 impl Sensitivity for proto::Maximum {
     fn compute_sensitivity(
         &self,
-        _privacy_definition: &proto::PrivacyDefinition,
+        privacy_definition: &proto::PrivacyDefinition,
         properties: &NodeProperties,
         sensitivity_type: &SensitivitySpace
     ) -> Result<Value> {
+        // an added, removed or substituted record may each move the maximum across the entire range,
+        // so both neighboring definitions share the same sensitivity
+        use proto::privacy_definition::Neighboring;
+        Neighboring::from_i32(privacy_definition.neighboring)
+            .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+
         let data_property = properties.get("data")
             .ok_or("data: missing")?.array()
             .map_err(prepend("data:"))?.clone();
@@ -7,7 +7,7 @@ use crate::{proto, base};
 
 use crate::components::{Component, Sensitivity};
 use crate::base::{Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, DataType};
-use crate::utilities::prepend;
+use crate::utilities::{prepend, get_contribution_bounds};
 use ndarray::prelude::*;
 
 impl Component for proto::Mean {
@@ -48,10 +48,16 @@ impl Sensitivity for proto::Mean {
     /// Mean sensitivities [are backed by the the proofs here](https://github.com/opendifferentialprivacy/whitenoise-core/blob/955703e3d80405d175c8f4642597ccdf2c00332a/whitepapers/sensitivities/mean/mean.pdf).
     fn compute_sensitivity(
         &self,
-        _privacy_definition: &proto::PrivacyDefinition,
+        privacy_definition: &proto::PrivacyDefinition,
         properties: &NodeProperties,
         sensitivity_type: &SensitivitySpace,
     ) -> Result<Value> {
+        // the number of records is known, and an added or removed record is resized into a substitution,
+        // so both neighboring definitions share the same sensitivity
+        use proto::privacy_definition::Neighboring;
+        Neighboring::from_i32(privacy_definition.neighboring)
+            .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+
         match sensitivity_type {
             SensitivitySpace::KNorm(k) => {
                 let data_property = properties.get("data")
@@ -64,11 +70,10 @@ impl Sensitivity for proto::Mean {
                 let data_upper = data_property.upper_f64()?;
                 let data_n = data_property.num_records()? as f64;
 
-                // AddRemove vs. Substitute share the same bounds
-
                 let row_sensitivity = match k {
-                    1 | 2 => data_lower.iter().zip(data_upper.iter())
-                        .map(|(min, max)| ((max - min) / data_n))
+                    // an individual may contribute many records to each column
+                    1 | 2 => data_lower.iter().zip(data_upper.iter()).zip(get_contribution_bounds(&data_property)?)
+                        .map(|((min, max), contributions)| ((max - min) * contributions / data_n))
                         .collect::<Vec<f64>>(),
                     _ => return Err("KNorm sensitivity is only supported in L1 and L2 spaces".into())
                 };
@@ -0,0 +1,94 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+
+use crate::components::{Component, Sensitivity};
+use crate::base::{Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, DataType};
+use crate::utilities::prepend;
+use ndarray::prelude::*;
+
+impl Component for proto::MeanAbsoluteDeviation {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let mut data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let center_property = properties.get("center")
+            .ok_or("center: missing")?.array()
+            .map_err(prepend("center:"))?.clone();
+
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+        }
+        data_property.assert_is_not_empty()?;
+        if data_property.data_type != DataType::F64 || center_property.data_type != DataType::F64 {
+            return Err("data and center: atomic type must be float".into())
+        }
+
+        // the sensitivity only holds if the center is the same on neighboring datasets
+        center_property.assert_is_releasable().map_err(prepend("center:"))?;
+        if center_property.num_columns != Some(data_property.num_columns()?) {
+            return Err("center: must have one value for each column of the data".into())
+        }
+
+        // save a snapshot of the state when aggregating
+        data_property.aggregator = Some(AggregatorProperties {
+            component: proto::component::Variant::MeanAbsoluteDeviation(self.clone()),
+            properties: properties.clone(),
+        });
+        data_property.nature = None;
+        data_property.num_records = Some(1);
+
+        Ok(data_property.into())
+    }
+}
+
+impl Sensitivity for proto::MeanAbsoluteDeviation {
+    /// By the triangle inequality, `||x - center| - |x' - center||` is at most `|x - x'|`,
+    /// so the sensitivity matches that of the mean, wherever the released center lies.
+    fn compute_sensitivity(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &NodeProperties,
+        sensitivity_type: &SensitivitySpace,
+    ) -> Result<Value> {
+        // the number of records is known, and an added or removed record is resized into a substitution,
+        // so both neighboring definitions share the same sensitivity
+        use proto::privacy_definition::Neighboring;
+        Neighboring::from_i32(privacy_definition.neighboring)
+            .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+
+        match sensitivity_type {
+            SensitivitySpace::KNorm(k) => {
+                let data_property = properties.get("data")
+                    .ok_or("data: missing")?.array()
+                    .map_err(prepend("data:"))?.clone();
+
+                data_property.assert_non_null()?;
+                data_property.assert_is_not_aggregated()?;
+                let data_lower = data_property.lower_f64()?;
+                let data_upper = data_property.upper_f64()?;
+                let data_n = data_property.num_records()? as f64;
+
+                let row_sensitivity = match k {
+                    1 | 2 => data_lower.iter().zip(data_upper.iter())
+                        .map(|(min, max)| (max - min) / data_n)
+                        .collect::<Vec<f64>>(),
+                    _ => return Err("KNorm sensitivity is only supported in L1 and L2 spaces".into())
+                };
+
+                let mut array_sensitivity = Array::from(row_sensitivity).into_dyn();
+                array_sensitivity.insert_axis_inplace(Axis(0));
+
+                Ok(array_sensitivity.into())
+            }
+            _ => Err("MeanAbsoluteDeviation sensitivity is only implemented for KNorm".into())
+        }
+    }
+}
@@ -9,14 +9,15 @@ use crate::{proto, base};
 
 use crate::components::{Component, Expandable};
 use crate::base::{Value, SensitivitySpace, ValueProperties, DataType};
-use crate::utilities::{prepend, expand_mechanism, broadcast_privacy_usage, get_epsilon, get_delta};
+use crate::utilities::{prepend, expand_mechanism, broadcast_privacy_usage, check_budget_fraction, get_epsilon, get_delta};
+use crate::utilities::privacy::{check_usage_distance, get_rho};
 
 
 impl Component for proto::GaussianMechanism {
     fn propagate_property(
         &self,
         privacy_definition: &proto::PrivacyDefinition,
-        _public_arguments: &HashMap<String, Value>,
+        public_arguments: &HashMap<String, Value>,
         properties: &base::NodeProperties,
     ) -> Result<ValueProperties> {
         let mut data_property = properties.get("data")
@@ -42,50 +43,45 @@ impl Component for proto::GaussianMechanism {
             data_property.releasable = false;
         } else {
             let usages = broadcast_privacy_usage(&self.privacy_usage, sensitivities.len())?;
-            let epsilons = usages.iter().map(get_epsilon).collect::<Result<Vec<f64>>>()?;
-            let deltas = usages.iter().map(get_delta).collect::<Result<Vec<f64>>>()?;
+            check_usage_distance(privacy_definition, &usages)?;
 
-            // epsilons must be greater than 0 and less than 1.
-            for epsilon in epsilons.into_iter() {
-                if epsilon <= 0.0 || epsilon >= 1.0 {
-                    return Err("epsilon: privacy parameter epsilon must be greater than 0".into());
-                };
-                if epsilon >= 1.0 {
-                    println!("Warning: A privacy parameter of epsilon = {} is in use. Privacy is only \
-                    guaranteed for the Gaussian mechanism as implemented in the rust runtime for epsilon \
-                    between 0 and 1.", epsilon);
+            if privacy_definition.distance == proto::privacy_definition::Distance::ZeroConcentrated as i32 {
+                // under zCDP, the noise is calibrated directly to rho
+                if usages.iter().map(get_rho).collect::<Result<Vec<f64>>>()?.iter().any(|rho| *rho <= 0.0) {
+                    return Err("rho: privacy parameter rho must be greater than 0".into());
                 }
-            }
+            } else {
+                let epsilons = usages.iter().map(get_epsilon).collect::<Result<Vec<f64>>>()?;
+                let deltas = usages.iter().map(get_delta).collect::<Result<Vec<f64>>>()?;
 
+                // epsilons must be greater than 0 and less than 1.
+                if epsilons.iter().any(|epsilon| *epsilon <= 0.0 || *epsilon >= 1.0) {
+                    return Err("epsilon: privacy parameter epsilon must be greater than 0".into());
+                }
 
-            // Check delta value; checks depend on whether or not number of records is statically known.
-            match data_property.num_records {
-                Some(n) => {
-                    let n = n as f64;
-                    for delta in deltas.into_iter() {
-                        if delta <= 0.0 {
-                            return Err("delta: privacy parameter delta must be greater than 0".into());
-                        };
-                        if delta > 1.0 / n {
-                            println!("Warning: A large delta of delta = {} is in use.", delta);
-                        }
-                    }
-                },
-                None => {
-                    for delta in deltas.into_iter() {
-                        if delta <= 0.0 {
-                            return Err("delta: privacy parameter delta must be greater than 0".into());
-                        } else {
-                            println!("Warning: Cannot determine if delta is reasonable due to statically \
-                            unknown number of records.");
+                // Check delta value; checks depend on whether or not number of records is statically known.
+                // Columns are checked at once, to warn at most once for wide aggregates
+                if deltas.iter().any(|delta| *delta <= 0.0) {
+                    return Err("delta: privacy parameter delta must be greater than 0".into());
+                }
+                match data_property.num_records {
+                    Some(n) => {
+                        let max_delta = deltas.iter().cloned().fold(0., f64::max);
+                        if max_delta > 1.0 / n as f64 {
+                            println!("Warning: A large delta of delta = {} is in use.", max_delta);
                         }
-                    }
+                    },
+                    None => println!("Warning: Cannot determine if delta is reasonable due to statically \
+                                unknown number of records.")
                 }
             }
 
             data_property.releasable = true;
         }
 
+        // the budget fraction may depend on earlier releases, but never on private data
+        check_budget_fraction(&self.privacy_usage, public_arguments, properties)?;
+
         data_property.aggregator = None;
 
         Ok(data_property.into())
@@ -9,14 +9,15 @@ use crate::{proto, base};
 
 use crate::components::{Component, Expandable};
 use crate::base::{Value, SensitivitySpace, ValueProperties, DataType};
-use crate::utilities::{prepend, expand_mechanism, broadcast_privacy_usage, get_epsilon};
+use crate::utilities::{prepend, expand_mechanism, broadcast_privacy_usage, check_budget_fraction, get_epsilon};
+use crate::utilities::privacy::{check_usage_distance, get_calibration_epsilon};
 
 
 impl Component for proto::LaplaceMechanism {
     fn propagate_property(
         &self,
         privacy_definition: &proto::PrivacyDefinition,
-        _public_arguments: &HashMap<String, Value>,
+        public_arguments: &HashMap<String, Value>,
         properties: &base::NodeProperties,
     ) -> Result<ValueProperties> {
         let mut data_property = properties.get("data")
@@ -42,21 +43,25 @@ impl Component for proto::LaplaceMechanism {
             data_property.releasable = false;
         } else {
             let usages = broadcast_privacy_usage(&self.privacy_usage, sensitivities.len())?;
-            let epsilons = usages.iter().map(get_epsilon).collect::<Result<Vec<f64>>>()?;
-
-            // epsilons must be greater than 0.
-            for epsilon in epsilons.into_iter(){
-                if epsilon <= 0.0 {
-                    return Err("epsilon: privacy parameter epsilon must be greater than 0".into());
-                };
-                if epsilon > 1.0   {
-                    println!("Warning: A large privacy parameter of epsilon = {} is in use", epsilon.to_string());
-                }
+            check_usage_distance(privacy_definition, &usages)?;
+            // a zCDP usage is met by calibrating to the epsilon that implies it
+            let epsilons = usages.iter().map(get_calibration_epsilon).collect::<Result<Vec<f64>>>()?;
+
+            // epsilons must be greater than 0. Columns are checked at once, to warn at most once for wide aggregates
+            if epsilons.iter().any(|epsilon| *epsilon <= 0.0) {
+                return Err("epsilon: privacy parameter epsilon must be greater than 0".into());
+            }
+            let max_epsilon = epsilons.iter().cloned().fold(0., f64::max);
+            if max_epsilon > 1.0 {
+                println!("Warning: A large privacy parameter of epsilon = {} is in use", max_epsilon.to_string());
             }
 
             data_property.releasable = true;
         }
 
+        // the budget fraction may depend on earlier releases, but never on private data
+        check_budget_fraction(&self.privacy_usage, public_arguments, properties)?;
+
         data_property.aggregator = None;
 
         Ok(data_property.into())
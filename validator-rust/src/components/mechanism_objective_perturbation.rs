@@ -0,0 +1,162 @@
+use crate::errors::*;
+
+
+use std::collections::HashMap;
+
+
+use crate::{proto, base};
+
+use crate::components::{Component, Expandable};
+use crate::base::{Value, ValueProperties, ArrayProperties, DataType};
+use crate::utilities::{prepend, privacy_usage_check, get_literal, get_group_size};
+
+
+impl Component for proto::ObjectivePerturbationMechanism {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let target_property = properties.get("target")
+            .ok_or("target: missing")?.array()
+            .map_err(prepend("target:"))?.clone();
+
+        data_property.assert_is_not_aggregated().map_err(prepend("data:"))?;
+        data_property.assert_non_null().map_err(prepend("data:"))?;
+        target_property.assert_is_not_aggregated().map_err(prepend("target:"))?;
+        target_property.assert_non_null().map_err(prepend("target:"))?;
+
+        if data_property.data_type != DataType::F64 && data_property.data_type != DataType::I64 {
+            return Err("data: atomic type must be numeric".into())
+        }
+        if target_property.data_type != DataType::Bool {
+            return Err("target: atomic type must be boolean".into())
+        }
+        if target_property.num_columns()? != 1 {
+            return Err("target: must contain one column".into())
+        }
+
+        // the correction to the budget depends on the number of records
+        let num_records = data_property.num_records()
+            .map_err(|_| Error::from("data: number of records must be known. Consider resizing the data"))?;
+        if num_records < 1 {
+            return Err("data: must contain at least one record".into())
+        }
+        if let Some(target_num_records) = target_property.num_records {
+            if target_num_records != num_records {
+                return Err("data and target must have the same number of records".into())
+            }
+        }
+
+        // every row must lie within a ball of known radius
+        norm_bound(&data_property, self.intercept).map_err(prepend("data:"))?;
+
+        if self.regularization.is_nan() || self.regularization <= 0. {
+            return Err("regularization: must be positive".into())
+        }
+
+        let usage = self.privacy_usage.first()
+            .ok_or_else(|| Error::from("privacy_usage: must be defined"))?;
+        if self.privacy_usage.len() != 1 {
+            return Err("privacy_usage: the coefficients are a single release, so exactly one privacy usage must be supplied".into())
+        }
+        privacy_usage_check(usage)?;
+
+        let num_coefficients = data_property.num_columns()? + if self.intercept { 1 } else { 0 };
+
+        Ok(ArrayProperties {
+            num_records: Some(num_coefficients),
+            num_columns: Some(1),
+            nullity: false,
+            releasable: true,
+            public_shape: true,
+            public_metadata: true,
+            c_stability: vec![1.],
+            aggregator: None,
+            nature: None,
+            data_type: DataType::F64,
+            dataset_id: data_property.dataset_id,
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 1
+        }.into())
+    }
+}
+
+
+impl Expandable for proto::ObjectivePerturbationMechanism {
+    fn expand_component(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut current_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let target_property = properties.get("target")
+            .ok_or("target: missing")?.array()
+            .map_err(prepend("target:"))?.clone();
+
+        // always overwrite the norm bound and sensitivity. These are not something a user may configure
+        current_id += 1;
+        let id_norm_bound = current_id;
+        let (patch_node, release) = get_literal(
+            &norm_bound(&data_property, self.intercept)?.into(), &component.batch)?;
+        computation_graph.insert(id_norm_bound, patch_node);
+        releases.insert(id_norm_bound, release);
+
+        // an individual may contribute as many records as the greatest c-stability, in each member of a group
+        let sensitivity = data_property.c_stability.iter()
+            .chain(target_property.c_stability.iter())
+            .cloned().fold(1., f64::max) * get_group_size(privacy_definition);
+
+        current_id += 1;
+        let id_sensitivity = current_id;
+        let (patch_node, release) = get_literal(&sensitivity.into(), &component.batch)?;
+        computation_graph.insert(id_sensitivity, patch_node);
+        releases.insert(id_sensitivity, release);
+
+        let mut mechanism_component = component.clone();
+        mechanism_component.arguments.insert("norm_bound".to_string(), id_norm_bound);
+        mechanism_component.arguments.insert("sensitivity".to_string(), id_sensitivity);
+        computation_graph.insert(*component_id, mechanism_component);
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal: Vec::new()
+        })
+    }
+}
+
+/// Upper bound on the L2 norm of any row of features, including the constant intercept column.
+pub fn norm_bound(data_property: &ArrayProperties, intercept: bool) -> Result<f64> {
+    let lower = data_property.lower_f64()
+        .map_err(|_| Error::from("lower bounds must be known. Consider clamping the data"))?;
+    let upper = data_property.upper_f64()
+        .map_err(|_| Error::from("upper bounds must be known. Consider clamping the data"))?;
+
+    let squared_norm = lower.iter().zip(upper.iter())
+        .map(|(lower, upper)| lower.abs().max(upper.abs()).powi(2))
+        .sum::<f64>() + if intercept { 1. } else { 0. };
+
+    if !squared_norm.is_finite() {
+        return Err("bounds must be finite".into())
+    }
+    if squared_norm == 0. {
+        return Err("bounds must not all be zero".into())
+    }
+    Ok(squared_norm.sqrt())
+}
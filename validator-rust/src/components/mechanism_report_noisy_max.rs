@@ -0,0 +1,177 @@
+use crate::errors::*;
+
+
+use std::collections::HashMap;
+
+
+use crate::components::{Sensitivity, Accuracy};
+use crate::{proto, base};
+
+use crate::components::{Component, Expandable};
+use crate::base::{Value, SensitivitySpace, ValueProperties, ArrayProperties, DataType, Nature, NatureCategorical, Jagged};
+use crate::utilities::{prepend, expand_mechanism, privacy_usage_check, get_epsilon};
+
+
+impl Component for proto::ReportNoisyMaxMechanism {
+    fn propagate_property(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if data_property.data_type != DataType::F64 && data_property.data_type != DataType::I64 {
+            return Err("data: atomic type must be numeric".into())
+        }
+
+        let aggregator = data_property.aggregator.clone()
+            .ok_or_else(|| Error::from("aggregator: missing"))?;
+
+        // sensitivity must be computable
+        aggregator.component.compute_sensitivity(
+            &privacy_definition,
+            &aggregator.properties,
+            &SensitivitySpace::KNorm(1))?;
+
+        let categories = public_arguments.get("categories")
+            .ok_or_else(|| Error::from("categories: must be public"))?.array()?;
+        if Some(categories.num_records()?) != data_property.num_records {
+            return Err("categories: must have one category for each count".into())
+        }
+
+        let usage = self.privacy_usage.first()
+            .ok_or_else(|| Error::from("privacy_usage: must be defined"))?;
+        if self.privacy_usage.len() != 1 {
+            return Err("privacy_usage: only one category is selected, so exactly one privacy usage must be supplied".into())
+        }
+        privacy_usage_check(usage)?;
+
+        let (categories, data_type) = to_jagged(categories);
+
+        Ok(ArrayProperties {
+            num_records: Some(1),
+            num_columns: Some(1),
+            nullity: false,
+            releasable: true,
+            public_shape: true,
+            public_metadata: true,
+            c_stability: vec![1.],
+            aggregator: None,
+            nature: Some(Nature::Categorical(NatureCategorical {
+                categories
+            })),
+            data_type,
+            dataset_id: data_property.dataset_id,
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 1
+        }.into())
+    }
+}
+
+
+impl Expandable for proto::ReportNoisyMaxMechanism {
+    fn expand_component(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        expand_mechanism(
+            &SensitivitySpace::KNorm(1),
+            privacy_definition,
+            component,
+            properties,
+            component_id,
+            maximum_id
+        )
+    }
+}
+
+impl Accuracy for proto::ReportNoisyMaxMechanism {
+    fn accuracy_to_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &base::NodeProperties,
+        accuracies: &proto::Accuracies,
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let (sensitivity, num_categories) = get_sensitivity(privacy_definition, properties)?;
+
+        Ok(Some(accuracies.values.iter()
+            .map(|accuracy| proto::PrivacyUsage {
+                distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                    // the accuracy is inversely proportional to epsilon
+                    epsilon: noisy_max_accuracy(sensitivity, num_categories, 1., accuracy.alpha) / accuracy.value,
+                    delta: 0.,
+                }))
+            })
+            .collect()))
+    }
+
+    fn privacy_usage_to_accuracy(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &base::NodeProperties,
+        alpha: &f64
+    ) -> Result<Option<Vec<proto::Accuracy>>> {
+        let (sensitivity, num_categories) = get_sensitivity(privacy_definition, properties)?;
+
+        let epsilon = get_epsilon(self.privacy_usage.first()
+            .ok_or_else(|| Error::from("privacy_usage: must be defined"))?)?;
+
+        Ok(Some(vec![proto::Accuracy {
+            value: noisy_max_accuracy(sensitivity, num_categories, epsilon, *alpha),
+            alpha: *alpha,
+        }]))
+    }
+}
+
+/// Bound on the shortfall of the count of the selected category from the greatest count.
+///
+/// Each of the `k` Laplace noises of scale `b = 2 * sensitivity / epsilon` exceeds `b ln(k / alpha)` in magnitude
+/// with probability at most `alpha / k`, so with probability at least `1 - alpha`
+/// the selected count is within `2b ln(k / alpha)` of the greatest count.
+pub fn noisy_max_accuracy(sensitivity: f64, num_categories: f64, epsilon: f64, alpha: f64) -> f64 {
+    4. * sensitivity * (num_categories / alpha).ln() / epsilon
+}
+
+/// Sensitivity of the counts, and the number of counts.
+///
+/// The histogram spreads its sensitivity evenly over the counts, so the sensitivity of the counts is the sum.
+fn get_sensitivity(
+    privacy_definition: &proto::PrivacyDefinition,
+    properties: &base::NodeProperties,
+) -> Result<(f64, f64)> {
+    let data_property = properties.get("data")
+        .ok_or("data: missing")?.array()
+        .map_err(prepend("data:"))?.clone();
+
+    let aggregator = data_property.aggregator.clone()
+        .ok_or_else(|| Error::from("aggregator: missing"))?;
+
+    let sensitivity = aggregator.component.compute_sensitivity(
+        &privacy_definition,
+        &aggregator.properties,
+        &SensitivitySpace::KNorm(1))?
+        .array()?.f64()?.iter().sum::<f64>();
+
+    let num_categories = data_property.num_records
+        .ok_or_else(|| Error::from("data: number of counts must be known"))?;
+
+    Ok((sensitivity, num_categories as f64))
+}
+
+/// The categories of a single column, and their atomic type.
+pub(crate) fn to_jagged(categories: &base::Array) -> (Jagged, DataType) {
+    match categories {
+        base::Array::Bool(categories) => (Jagged::Bool(vec![Some(categories.iter().cloned().collect())]), DataType::Bool),
+        base::Array::I64(categories) => (Jagged::I64(vec![Some(categories.iter().cloned().collect())]), DataType::I64),
+        base::Array::F64(categories) => (Jagged::F64(vec![Some(categories.iter().cloned().collect())]), DataType::F64),
+        base::Array::Str(categories) => (Jagged::Str(vec![Some(categories.iter().cloned().collect())]), DataType::Str),
+    }
+}
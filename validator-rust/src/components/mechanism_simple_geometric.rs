@@ -8,14 +8,15 @@ use crate::{proto, base};
 
 use crate::components::{Component, Expandable};
 use crate::base::{Value, SensitivitySpace, ValueProperties, DataType};
-use crate::utilities::{prepend, expand_mechanism, broadcast_privacy_usage, get_epsilon};
+use crate::utilities::{prepend, expand_mechanism, broadcast_privacy_usage, check_budget_fraction, get_epsilon};
+use crate::utilities::privacy::{check_usage_distance, get_calibration_epsilon};
 
 
 impl Component for proto::SimpleGeometricMechanism {
     fn propagate_property(
         &self,
         privacy_definition: &proto::PrivacyDefinition,
-        _public_arguments: &HashMap<String, Value>,
+        public_arguments: &HashMap<String, Value>,
         properties: &base::NodeProperties,
     ) -> Result<ValueProperties> {
         let mut data_property = properties.get("data")
@@ -42,20 +43,24 @@ impl Component for proto::SimpleGeometricMechanism {
             data_property.releasable = false;
         } else {
             let usages = broadcast_privacy_usage(&self.privacy_usage, sensitivities.len())?;
-            let epsilons = usages.iter().map(get_epsilon).collect::<Result<Vec<f64>>>()?;
-
-            // epsilons must be greater than 0.
-            for epsilon in epsilons.into_iter() {
-                if epsilon <= 0.0 {
-                    return Err("epsilon: privacy parameter epsilon must be greater than 0".into());
-                };
-                if epsilon > 1.0 {
-                    println!("Warning: A large privacy parameter of epsilon = {} is in use", epsilon.to_string());
-                }
+            check_usage_distance(privacy_definition, &usages)?;
+            // a zCDP usage is met by calibrating to the epsilon that implies it
+            let epsilons = usages.iter().map(get_calibration_epsilon).collect::<Result<Vec<f64>>>()?;
+
+            // epsilons must be greater than 0. Columns are checked at once, to warn at most once for wide aggregates
+            if epsilons.iter().any(|epsilon| *epsilon <= 0.0) {
+                return Err("epsilon: privacy parameter epsilon must be greater than 0".into());
+            }
+            let max_epsilon = epsilons.iter().cloned().fold(0., f64::max);
+            if max_epsilon > 1.0 {
+                println!("Warning: A large privacy parameter of epsilon = {} is in use", max_epsilon.to_string());
             }
             data_property.releasable = true;
         }
 
+        // the budget fraction may depend on earlier releases, but never on private data
+        check_budget_fraction(&self.privacy_usage, public_arguments, properties)?;
+
         data_property.aggregator = None;
 
         Ok(data_property.into())
@@ -110,7 +115,7 @@ impl Accuracy for proto::SimpleGeometricMechanism {
         Ok(Some(sensitivities.into_iter().zip(accuracies.values.iter())
             .map(|(sensitivity, accuracy)| proto::PrivacyUsage {
                 distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
-                    epsilon: (1. / accuracy.alpha).ln() * (sensitivity / accuracy.value),
+                    epsilon: geometric_accuracy_to_epsilon(*sensitivity, accuracy.value, accuracy.alpha),
                     delta: 0.,
                 }))
             })
@@ -142,12 +147,42 @@ impl Accuracy for proto::SimpleGeometricMechanism {
         let epsilon = usages.iter().map(get_epsilon).collect::<Result<Vec<f64>>>()?;
 
         Ok(Some(sensitivities.into_iter().zip(epsilon.into_iter())
-            .map(|(sensitivity, epsilon)| {
-                let unrounded_accuracy = (1. / *alpha).ln() * (sensitivity / epsilon);
-                proto::Accuracy {
-                    value: round::ceil(unrounded_accuracy, 0),
-                    alpha: *alpha,
-            }
-        }).collect()))
+            .map(|(sensitivity, epsilon)| proto::Accuracy {
+                value: geometric_epsilon_to_accuracy(*sensitivity, epsilon, *alpha),
+                alpha: *alpha,
+            }).collect()))
+    }
+}
+
+/// Smallest integer accuracy `k` such that the geometric noise exceeds `k` in magnitude with probability at most alpha.
+///
+/// With `a = exp(-epsilon / sensitivity)`, the two-sided geometric distribution has `P(|Z| > k) = 2a^(k+1) / (1 + a)`.
+pub fn geometric_epsilon_to_accuracy(sensitivity: f64, epsilon: f64, alpha: f64) -> f64 {
+    let a = (-epsilon / sensitivity).exp();
+    let unrounded_accuracy = (2. / (alpha * (1. + a))).ln() * (sensitivity / epsilon) - 1.;
+    round::ceil(unrounded_accuracy, 0).max(0.)
+}
+
+/// Epsilon sufficient for the geometric noise to exceed the integer accuracy `k` with probability at most alpha.
+///
+/// Solving `2a^(k+1) / (1 + a) <= alpha` exactly has no closed form, so the `(1 + a)` term is bounded below by one.
+pub fn geometric_accuracy_to_epsilon(sensitivity: f64, accuracy: f64, alpha: f64) -> f64 {
+    (2. / alpha).ln() * sensitivity / (accuracy.floor() + 1.)
+}
+
+
+#[cfg(test)]
+mod test_geometric_accuracy {
+    use crate::components::mechanism_simple_geometric::{geometric_epsilon_to_accuracy, geometric_accuracy_to_epsilon};
+
+    #[test]
+    fn test_accuracy_round_trip() {
+        let (sensitivity, alpha) = (3., 0.05);
+        for accuracy in vec![0., 1., 10., 100.] {
+            let epsilon = geometric_accuracy_to_epsilon(sensitivity, accuracy, alpha);
+            // the conservative epsilon never yields a worse accuracy than requested
+            assert!(geometric_epsilon_to_accuracy(sensitivity, epsilon, alpha) <= accuracy);
+        }
+        assert_eq!(geometric_epsilon_to_accuracy(1., 1e6, alpha), 0.);
     }
 }
@@ -0,0 +1,134 @@
+use crate::errors::*;
+
+
+use std::collections::{HashMap, BTreeMap};
+
+
+use crate::components::Sensitivity;
+use crate::{proto, base};
+
+use crate::components::{Component, Expandable};
+use crate::base::{Value, SensitivitySpace, ValueProperties, ArrayProperties, HashmapProperties, Hashmap, DataType, Nature, NatureCategorical};
+use crate::components::mechanism_report_noisy_max::to_jagged;
+use crate::utilities::{prepend, expand_mechanism, privacy_usage_check};
+
+
+impl Component for proto::TopKMechanism {
+    fn propagate_property(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if data_property.data_type != DataType::F64 && data_property.data_type != DataType::I64 {
+            return Err("data: atomic type must be numeric".into())
+        }
+
+        let aggregator = data_property.aggregator.clone()
+            .ok_or_else(|| Error::from("aggregator: missing"))?;
+
+        // sensitivity must be computable
+        aggregator.component.compute_sensitivity(
+            &privacy_definition,
+            &aggregator.properties,
+            &SensitivitySpace::KNorm(1))?;
+
+        let categories = public_arguments.get("categories")
+            .ok_or_else(|| Error::from("categories: must be public"))?.array()?;
+        let num_categories = categories.num_records()?;
+        if Some(num_categories) != data_property.num_records {
+            return Err("categories: must have one category for each count".into())
+        }
+
+        if self.k < 1 || self.k as i64 > num_categories {
+            return Err("k: must be at least one, and no greater than the number of categories".into())
+        }
+
+        match self.method.to_lowercase().as_str() {
+            "gumbel" | "peeling" => (),
+            _ => return Err("method: must be one of Gumbel or Peeling".into())
+        }
+
+        let usage = self.privacy_usage.first()
+            .ok_or_else(|| Error::from("privacy_usage: must be defined"))?;
+        if self.privacy_usage.len() != 1 {
+            return Err("privacy_usage: the selection is a single release, so exactly one privacy usage must be supplied".into())
+        }
+        privacy_usage_check(usage)?;
+
+        if self.count_privacy_usage.len() > 1 {
+            return Err("count_privacy_usage: the counts are a single release, so at most one privacy usage may be supplied".into())
+        }
+        self.count_privacy_usage.iter().try_for_each(privacy_usage_check)
+            .map_err(prepend("count_privacy_usage:"))?;
+
+        let (categories, data_type) = to_jagged(categories);
+        let num_records = self.k as i64;
+
+        let categories_property = ArrayProperties {
+            num_records: Some(num_records),
+            num_columns: Some(1),
+            nullity: false,
+            releasable: true,
+            public_shape: true,
+            public_metadata: true,
+            c_stability: vec![1.],
+            aggregator: None,
+            nature: Some(Nature::Categorical(NatureCategorical {
+                categories
+            })),
+            data_type,
+            dataset_id: data_property.dataset_id,
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 1
+        };
+
+        if self.count_privacy_usage.is_empty() {
+            return Ok(categories_property.into())
+        }
+
+        let counts_property = ArrayProperties {
+            nature: None,
+            data_type: DataType::F64,
+            ..categories_property.clone()
+        };
+
+        Ok(HashmapProperties {
+            num_records: Some(num_records),
+            disjoint: false,
+            properties: Hashmap::<ValueProperties>::Str(vec![
+                ("categories".to_string(), categories_property.into()),
+                ("counts".to_string(), counts_property.into())
+            ].into_iter().collect::<BTreeMap<String, ValueProperties>>()),
+            columnar: true,
+            releasable: true,
+            group_id: Vec::new()
+        }.into())
+    }
+}
+
+
+impl Expandable for proto::TopKMechanism {
+    fn expand_component(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        expand_mechanism(
+            &SensitivitySpace::KNorm(1),
+            privacy_definition,
+            component,
+            properties,
+            component_id,
+            maximum_id
+        )
+    }
+}
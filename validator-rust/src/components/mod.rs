@@ -11,41 +11,102 @@ use crate::errors::*;
 
 
 mod transforms;
+mod annotation;
+pub mod anova_statistic;
 //mod bin;
-mod cast;
+mod bound_contributions;
+pub mod cast;
+pub mod chi_square_statistic;
 mod clamp;
+mod confusion_matrix;
 mod count;
+mod count_true;
+mod contingency_table;
+mod count_distinct;
 mod covariance;
+mod cross_products;
+pub mod derived_metric;
 mod digitize;
+mod dp_any;
+mod dp_anova;
+pub mod dp_auc;
+mod dp_cdf;
+mod dp_chi_square_test;
+mod dp_confusion_matrix;
+mod dp_contingency_table;
+mod dp_correlation;
 mod dp_count;
+mod dp_count_distinct;
+mod dp_count_true;
 mod dp_variance;
 mod dp_covariance;
+pub mod dp_decision_tree;
+mod dp_gini;
+mod dp_group_moments;
 mod dp_histogram;
+mod dp_iqr;
+mod dp_k_means;
+mod dp_kurtosis;
+mod dp_linear_regression;
+mod dp_logistic_regression;
+mod dp_marginals;
 mod dp_maximum;
 mod dp_median;
 mod dp_minimum;
 mod dp_mean;
+mod dp_mean_absolute_deviation;
+mod dp_mode;
 mod dp_moment_raw;
+mod dp_naive_bayes;
+mod dp_pca;
+mod dp_skewness;
+pub mod dp_quantiles;
+pub mod dp_range_tree;
+mod dp_stability_histogram;
+pub mod dp_stochastic_gradient_descent;
+pub mod dp_synthetic_data;
+mod dp_top_k;
 mod dp_sum;
+pub mod dp_welch_t_test;
+pub mod empirical_cdf;
+mod extreme_selection;
+mod expect_property;
 mod filter;
+mod gini_coefficient;
+mod gini_numerator;
 mod histogram;
 mod impute;
 pub mod index;
+mod interquartile_range;
+mod join_public;
+mod k_means;
 mod kth_raw_sample_moment;
+pub mod linear_regression;
 mod literal;
+pub mod marginals;
 mod maximum;
 mod materialize;
 mod minimum;
+mod naive_bayes;
 pub mod partition;
+pub mod principal_components;
 mod quantile;
+pub mod quantile_edges;
+pub mod rebalance_partitions;
 mod reshape;
 mod mean;
+mod mean_absolute_deviation;
 // mod mechanism_exponential;
 mod mechanism_gaussian;
 mod mechanism_laplace;
+mod mechanism_objective_perturbation;
+mod mechanism_report_noisy_max;
 mod mechanism_simple_geometric;
+mod mechanism_top_k;
 mod resize;
+pub mod standardized_moment;
 mod sum;
+mod union;
 mod variance;
 
 use std::collections::HashMap;
@@ -53,6 +114,7 @@ use std::collections::HashMap;
 use crate::base::{Value, NodeProperties, SensitivitySpace, ValueProperties};
 use crate::proto;
 use crate::utilities::json::{JSONRelease};
+use crate::utilities::scale_sensitivity;
 
 /// Universal Component trait
 ///
@@ -226,13 +288,13 @@ impl Component for proto::component::Variant {
 
         propagate_property!(
             // INSERT COMPONENT LIST
-            Cast, Clamp, Count, Covariance, Digitize,
+            Annotation, AnovaStatistic, BoundContributions, Cast, ChiSquareStatistic, Clamp, ClassMoments, ConfusionMatrix, ContingencyTable, Count, CountDistinct, CountTrue, Covariance, CrossProducts, DerivedMetric, Digitize,
 
-            Filter, Histogram, Impute, Index, KthRawSampleMoment, Materialize, Maximum, Mean,
+            DpAuc, DpDecisionTree, DpGroupMoments, DpQuantiles, DpRangeTree, DpStabilityHistogram, DpStochasticGradientDescent, DpSyntheticData, DpWelchTTest, EmpiricalCdf, ExpectProperty, ExtremeSelection, Filter, GiniCoefficient, GiniNumerator, Histogram, Impute, Index, InterquartileRange, JoinPublic, KMeansCentroids, KMeansStatistics, KthRawSampleMoment, LinearRegression, Marginals, Materialize, Maximum, Mean, MeanAbsoluteDeviation,
 
-            GaussianMechanism, LaplaceMechanism, SimpleGeometricMechanism,
+            GaussianMechanism, LaplaceMechanism, ObjectivePerturbationMechanism, ReportNoisyMaxMechanism, SimpleGeometricMechanism, TopKMechanism,
 
-            Minimum, Partition, Quantile, Reshape, Resize, Sum, Variance,
+            Minimum, NaiveBayesModel, Partition, PrincipalComponents, Quantile, QuantileEdges, RebalancePartitions, Reshape, Resize, StandardizedMoment, Sum, Union, Variance,
 
             Abs, Add, LogicalAnd, Divide, Equal, GreaterThan, LessThan, Log, Modulo, Multiply,
             Negate, Negative, LogicalOr, Power, RowMax, RowMin, Subtract
@@ -269,9 +331,9 @@ impl Expandable for proto::component::Variant {
 
         expand_component!(
             // INSERT COMPONENT LIST
-            Clamp, Digitize, DpCount, DpCovariance, DpHistogram, DpMaximum, DpMean, DpMedian,
-            DpMinimum, DpMomentRaw, DpSum, DpVariance, Histogram, Impute, GaussianMechanism,
-            LaplaceMechanism, SimpleGeometricMechanism, Resize,
+            Clamp, ConfusionMatrix, ContingencyTable, Digitize, DpAnova, DpAny, DpAuc, DpCdf, DpChiSquareTest, DpConfusionMatrix, DpContingencyTable, DpCorrelation, DpCount, DpCountDistinct, DpCountTrue, DpCovariance, DpDecisionTree, DpGini, DpGroupMoments, DpHistogram, DpIqr, DpKMeans, DpKurtosis, DpLinearRegression, DpLogisticRegression, DpMarginals, DpMaximum, DpMean, DpMeanAbsoluteDeviation, DpMedian,
+            DpMinimum, DpMode, DpMomentRaw, DpNaiveBayes, DpPca, DpQuantiles, DpRangeTree, DpSkewness, DpStabilityHistogram, DpSum, DpSyntheticData, DpTopK, DpVariance, DpWelchTTest, ExtremeSelection, Histogram, Impute, GaussianMechanism,
+            LaplaceMechanism, Marginals, ObjectivePerturbationMechanism, RebalancePartitions, ReportNoisyMaxMechanism, SimpleGeometricMechanism, TopKMechanism, Resize,
 
             ToBool, ToFloat, ToInt, ToString
         );
@@ -290,7 +352,8 @@ impl Expandable for proto::component::Variant {
 impl Sensitivity for proto::component::Variant {
     /// Utility implementation on the enum containing all variants of a component.
     ///
-    /// This utility delegates evaluation to the concrete implementation of each component variant.
+    /// This utility delegates evaluation to the concrete implementation of each component variant,
+    /// and scales the sensitivity of a single record by the group size of the privacy definition.
     fn compute_sensitivity(
         &self,
         privacy_definition: &proto::PrivacyDefinition,
@@ -303,6 +366,7 @@ impl Sensitivity for proto::component::Variant {
                     $(
                        if let proto::component::Variant::$variant(x) = self {
                             return x.compute_sensitivity(privacy_definition, properties, sensitivity_type)
+                                .and_then(|sensitivity| scale_sensitivity(privacy_definition, sensitivity))
                                 .chain_err(|| format!("node specification {:?}:", self))
                        }
                     )*
@@ -312,7 +376,7 @@ impl Sensitivity for proto::component::Variant {
 
         compute_sensitivity!(
             // INSERT COMPONENT LIST
-            Count, Covariance, Histogram, KthRawSampleMoment, Maximum, Mean, Minimum, Quantile, Sum, Variance
+            ClassMoments, ConfusionMatrix, ContingencyTable, Count, CountDistinct, CountTrue, Covariance, CrossProducts, GiniNumerator, Histogram, KMeansStatistics, KthRawSampleMoment, Marginals, Maximum, Mean, MeanAbsoluteDeviation, Minimum, Quantile, Sum, Variance
         );
 
         Err(format!("sensitivity is not implemented for proto component {:?}", self).into())
@@ -343,8 +407,11 @@ impl Accuracy for proto::component::Variant {
         }
 
         accuracy_to_privacy_usage!(
+             DpIqr,
+             DpQuantiles,
              LaplaceMechanism,
              GaussianMechanism,
+             ReportNoisyMaxMechanism,
              SimpleGeometricMechanism
         );
 
@@ -374,8 +441,11 @@ impl Accuracy for proto::component::Variant {
         }
 
         privacy_usage_to_accuracy!(
+            DpIqr,
+            DpQuantiles,
             LaplaceMechanism,
             GaussianMechanism,
+            ReportNoisyMaxMechanism,
             SimpleGeometricMechanism
         );
 
@@ -413,8 +483,8 @@ impl Report for proto::component::Variant {
 
         summarize!(
             // INSERT COMPONENT LIST
-            DpCount, DpCovariance, DpHistogram, DpMaximum, DpMean, DpMinimum, DpMomentRaw,
-            DpSum, DpVariance
+            DerivedMetric, DpAnova, DpAny, DpAuc, DpCdf, DpChiSquareTest, DpConfusionMatrix, DpContingencyTable, DpCorrelation, DpCount, DpCountDistinct, DpCountTrue, DpCovariance, DpDecisionTree, DpGini, DpGroupMoments, DpHistogram, DpIqr, DpKMeans, DpKurtosis, DpLinearRegression, DpLogisticRegression, DpMarginals, DpMaximum, DpMean, DpMeanAbsoluteDeviation, DpMinimum,
+            DpMode, DpMomentRaw, DpNaiveBayes, DpPca, DpQuantiles, DpRangeTree, DpSkewness, DpStabilityHistogram, DpStochasticGradientDescent, DpSum, DpSyntheticData, DpTopK, DpVariance, DpWelchTTest
         );
 
         Ok(None)
@@ -487,4 +557,62 @@ impl Utility for proto::component::Variant {
 
         Err(format!("sensitivity is not implemented for proto component {:?}", self).into())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test_sensitivity {
+    use crate::proto;
+    use crate::hashmap;
+    use crate::components::Sensitivity;
+    use crate::base::{ArrayProperties, DataType, Nature, NatureContinuous, SensitivitySpace, Vector1DNull};
+    use proto::component::Variant;
+    use proto::privacy_definition::Neighboring;
+
+    #[test]
+    fn test_neighboring_sensitivities() {
+        let data_property = ArrayProperties {
+            num_records: Some(10),
+            num_columns: Some(1),
+            nullity: false,
+            releasable: false,
+            public_shape: false,
+            public_metadata: false,
+            c_stability: vec![1.],
+            aggregator: None,
+            nature: Some(Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::F64(vec![Some(0.)]),
+                upper: Vector1DNull::F64(vec![Some(2.)]),
+            })),
+            data_type: DataType::F64,
+            dataset_id: Some(0),
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 2
+        };
+        let properties = hashmap!["data".to_string() => data_property.into()];
+        let sensitivity = |variant: &Variant, neighboring: i32| variant.compute_sensitivity(
+            &proto::PrivacyDefinition { neighboring, group_size: 1, ..Default::default() },
+            &properties, &SensitivitySpace::KNorm(1))
+            .map(|sensitivity| sensitivity.array().unwrap().f64().unwrap().iter().cloned().collect::<Vec<f64>>());
+
+        // ten records in [0, 2]
+        let aggregators = vec![
+            (Variant::CountTrue(proto::CountTrue {}), 1.),
+            (Variant::GiniNumerator(proto::GiniNumerator {}), 0.18),
+            (Variant::KthRawSampleMoment(proto::KthRawSampleMoment { k: 2 }), 0.4),
+            (Variant::Maximum(proto::Maximum {}), 2.),
+            (Variant::Mean(proto::Mean {}), 0.2),
+            (Variant::MeanAbsoluteDeviation(proto::MeanAbsoluteDeviation {}), 0.2),
+            (Variant::Minimum(proto::Minimum {}), 2.),
+        ];
+
+        for (variant, expected) in aggregators {
+            for neighboring in [Neighboring::Substitute, Neighboring::AddRemove] {
+                let sensitivity = sensitivity(&variant, neighboring as i32).unwrap();
+                assert!((sensitivity[0] - expected).abs() < 1e-12, "{:?} under {:?}: {:?}", variant, neighboring, sensitivity);
+            }
+            // an unknown neighboring definition is rejected, rather than defaulting to either
+            assert!(sensitivity(&variant, 7).is_err());
+        }
+    }
+}
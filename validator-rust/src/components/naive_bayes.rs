@@ -0,0 +1,201 @@
+use crate::errors::*;
+
+
+use std::collections::{HashMap, BTreeMap};
+
+use crate::{proto, base};
+
+use crate::components::{Component, Sensitivity};
+use crate::base::{Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, HashmapProperties, Hashmap, DataType};
+use crate::utilities::prepend;
+use ndarray::prelude::*;
+
+
+impl Component for proto::ClassMoments {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let mut data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let target_property = properties.get("target")
+            .ok_or("target: missing")?.array()
+            .map_err(prepend("target:"))?.clone();
+        let categories_property = properties.get("categories")
+            .ok_or("categories: missing")?.array()
+            .map_err(prepend("categories:"))?.clone();
+
+        if data_property.data_type != DataType::F64 {
+            return Err("data: atomic type must be float".into())
+        }
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+        }
+        if !target_property.releasable {
+            target_property.assert_is_not_aggregated().map_err(prepend("target:"))?;
+        }
+        if self.power < 1 {
+            return Err("power: must be at least one".into())
+        }
+
+        let categories = target_property.categories()
+            .map_err(|_| Error::from("target: categories must be known"))?;
+        if categories.num_columns() != 1 {
+            return Err("target: must contain a single column".into())
+        }
+        categories_property.assert_is_releasable().map_err(prepend("categories:"))?;
+
+        // save a snapshot of the state when aggregating
+        data_property.aggregator = Some(AggregatorProperties {
+            component: proto::component::Variant::ClassMoments(self.clone()),
+            properties: properties.clone(),
+        });
+
+        data_property.num_records = Some(categories.lengths()?[0]);
+        data_property.nature = None;
+        data_property.dataset_id = None;
+
+        Ok(data_property.into())
+    }
+}
+
+impl Sensitivity for proto::ClassMoments {
+    /// Each record falls into at most one class, so it changes the sums of one class by its features raised to the power.
+    /// When a record is substituted, it may either stay within its class, or move to another class.
+    fn compute_sensitivity(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &NodeProperties,
+        sensitivity_type: &SensitivitySpace,
+    ) -> Result<Value> {
+        match sensitivity_type {
+            SensitivitySpace::KNorm(k) => {
+                if k != &1 && k != &2 {
+                    return Err("KNorm sensitivity is only supported in L1 and L2 spaces".into())
+                }
+
+                let data_property = properties.get("data")
+                    .ok_or("data: missing")?.array()
+                    .map_err(prepend("data:"))?.clone();
+                data_property.assert_is_not_aggregated()?;
+                data_property.assert_non_null()?;
+
+                let target_property = properties.get("target")
+                    .ok_or("target: missing")?.array()
+                    .map_err(prepend("target:"))?.clone();
+                let num_classes = target_property.categories()?.lengths()?[0] as usize;
+
+                use proto::privacy_definition::Neighboring;
+                let neighboring_type = Neighboring::from_i32(privacy_definition.neighboring)
+                    .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+
+                let column_sensitivities = data_property.lower_f64()?.into_iter()
+                    .zip(data_property.upper_f64()?.into_iter())
+                    .map(|(lower, upper)| {
+                        let (minimum, maximum) = power_range(lower, upper, self.power);
+                        let magnitude = minimum.abs().max(maximum.abs());
+                        match neighboring_type {
+                            Neighboring::AddRemove => magnitude,
+                            Neighboring::Substitute => (maximum - minimum).max(magnitude)
+                        }
+                    })
+                    .collect::<Vec<f64>>();
+
+                let sensitivities = (0..num_classes)
+                    .flat_map(|_| column_sensitivities.iter().cloned())
+                    .collect::<Vec<f64>>();
+
+                Ok(Array::from_shape_vec((num_classes, column_sensitivities.len()), sensitivities)?
+                    .into_dyn().into())
+            }
+            _ => Err("ClassMoments sensitivity is only implemented for KNorm".into())
+        }
+    }
+}
+
+/// Range of `x^power`, when `x` is within `[lower, upper]`.
+fn power_range(lower: f64, upper: f64, power: u32) -> (f64, f64) {
+    let (lower_power, upper_power) = (lower.powi(power as i32), upper.powi(power as i32));
+    if power % 2 == 0 && lower < 0. && upper > 0. {
+        (0., lower_power.max(upper_power))
+    } else {
+        (lower_power.min(upper_power), lower_power.max(upper_power))
+    }
+}
+
+impl Component for proto::NaiveBayesModel {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let counts_property = properties.get("counts")
+            .ok_or("counts: missing")?.array()
+            .map_err(prepend("counts:"))?.clone();
+        let sums_property = properties.get("sums")
+            .ok_or("sums: missing")?.array()
+            .map_err(prepend("sums:"))?.clone();
+        let squares_property = properties.get("squares")
+            .ok_or("squares: missing")?.array()
+            .map_err(prepend("squares:"))?.clone();
+
+        // the model is fit from released statistics, so this is postprocessing
+        counts_property.assert_is_releasable().map_err(prepend("counts:"))?;
+        sums_property.assert_is_releasable().map_err(prepend("sums:"))?;
+        squares_property.assert_is_releasable().map_err(prepend("squares:"))?;
+
+        let num_classes = sums_property.num_records()?;
+        let num_columns = sums_property.num_columns()?;
+        if counts_property.num_records()? != num_classes || counts_property.num_columns()? != 1 {
+            return Err("counts: must contain a single column, with one row for each class".into())
+        }
+        if squares_property.num_records()? != num_classes || squares_property.num_columns()? != num_columns {
+            return Err("squares: must have the same shape as sums".into())
+        }
+
+        let mut model_property = sums_property;
+        model_property.aggregator = None;
+        model_property.nature = None;
+        model_property.dataset_id = None;
+        model_property.nullity = false;
+        model_property.data_type = DataType::F64;
+        model_property.dimensionality = 2;
+
+        let mut priors_property = model_property.clone();
+        priors_property.num_columns = Some(1);
+        priors_property.c_stability = vec![1.];
+
+        model_property.c_stability = vec![1.; num_columns as usize];
+
+        Ok(HashmapProperties {
+            num_records: None,
+            disjoint: false,
+            properties: Hashmap::<ValueProperties>::Str(vec![
+                ("priors".to_string(), priors_property.into()),
+                ("means".to_string(), model_property.clone().into()),
+                ("variances".to_string(), model_property.into())
+            ].into_iter().collect::<BTreeMap<String, ValueProperties>>()),
+            columnar: false,
+            releasable: true,
+            group_id: Vec::new()
+        }.into())
+    }
+}
+
+
+#[cfg(test)]
+mod test_naive_bayes {
+    use crate::components::naive_bayes::power_range;
+
+    #[test]
+    fn test_power_range() {
+        assert_eq!(power_range(-2., 3., 1), (-2., 3.));
+        assert_eq!(power_range(-2., 3., 2), (0., 9.));
+        assert_eq!(power_range(-3., -2., 2), (4., 9.));
+        assert_eq!(power_range(-2., 3., 3), (-8., 27.));
+    }
+}
@@ -17,63 +17,113 @@ impl Component for proto::Partition {
         public_arguments: &HashMap<String, Value>,
         properties: &base::NodeProperties,
     ) -> Result<ValueProperties> {
-        let mut data_property = properties.get("data")
-            .ok_or("data: missing")?.array()
-            .map_err(prepend("data:"))?.clone();
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?;
 
-        Ok(match properties.get("by") {
+        let num_partitions = match properties.get("by") {
+            Some(_) => None,
+            None => Some(public_arguments.get("num_partitions")
+                .ok_or("num_partitions or by must be passed to Partition")?.array()?.first_i64()?)
+        };
+
+        partition_property(data_property, properties.get("by"), num_partitions)
+    }
+}
+
+/// Partition the data, or if the data is already partitioned, partition every cell of the data.
+///
+/// When partitioning a partition by categories, `by` must be partitioned by the same groups as the data,
+/// so that the categories of each cell are drawn from the same records as the cell.
+/// The id of the Partition component is added to the groups of the result once propagated, in `apply_partition_group`.
+fn partition_property(
+    data_property: &ValueProperties, by_property: Option<&ValueProperties>, num_partitions: Option<i64>,
+) -> Result<ValueProperties> {
+    if let ValueProperties::Hashmap(data_property) = data_property {
+        data_property.assert_is_partition().map_err(prepend("data:"))?;
+        let by_property = match by_property {
             Some(by_property) => {
-                let by_property = by_property.array()
-                    .map_err(prepend("by:"))?.clone();
-                let by_num_columns= by_property.num_columns
-                    .ok_or_else(|| Error::from("number of columns must be known on by"))?;
-                if by_num_columns != 1 {
-                    return Err("Partition's by argument must contain a single column".into());
-                }
-                let categories = by_property.categories()
-                    .map_err(prepend("by:"))?;
-                data_property.num_records = None;
-
-                HashmapProperties {
-                    num_records: data_property.num_records,
-                    disjoint: true,
-                    properties: match categories {
-                        Jagged::Bool(categories) => broadcast_partitions(&categories, &data_property)?.into(),
-                        Jagged::Str(categories) => broadcast_partitions(&categories, &data_property)?.into(),
-                        Jagged::I64(categories) => broadcast_partitions(&categories, &data_property)?.into(),
-                        _ => return Err("partitioning based on floats is not supported".into())
-                    },
-                    columnar: false
-                }
+                let by_property = by_property.hashmap()
+                    .map_err(prepend("by: must be partitioned when data is partitioned:"))?;
+                data_property.assert_same_groups(by_property).map_err(prepend("by:"))?;
+                Some(by_property.properties.values())
             },
-            None => {
-
-                let num_partitions = public_arguments.get("num_partitions")
-                    .ok_or("num_partitions or by must be passed to Partition")?.array()?.first_i64()?;
-
-                let lengths = match data_property.num_records {
-                    Some(num_records) => even_split_lengths(num_records, num_partitions)
-                        .into_iter().map(Some).collect(),
-                    None => (0..num_partitions)
-                        .map(|_| None)
-                        .collect::<Vec<Option<i64>>>()
-                };
-
-                HashmapProperties {
-                    num_records: data_property.num_records,
-                    disjoint: false,
-                    properties: lengths.iter().enumerate().map(|(index, partition_num_records)| {
-                        let mut partition_property = data_property.clone();
-                        partition_property.num_records = *partition_num_records;
-                        (index as i64, ValueProperties::Array(partition_property))
-                    }).collect::<BTreeMap<i64, ValueProperties>>().into(),
-                    columnar: false
-                }
-            }
+            None => None
+        };
+
+        let cells = data_property.properties.values().into_iter().enumerate()
+            .map(|(index, cell)| partition_property(
+                cell, by_property.as_ref().map(|by_property| by_property[index]), num_partitions))
+            .collect::<Result<Vec<ValueProperties>>>()?;
+
+        // cells at the innermost level are disjoint only if every level partitions disjointly
+        let disjoint = data_property.disjoint && cells.iter().all(|cell| match cell {
+            ValueProperties::Hashmap(cell) => cell.disjoint,
+            _ => true
+        });
+
+        return Ok(HashmapProperties {
+            properties: data_property.properties.from_values(cells),
+            disjoint,
+            ..data_property.clone()
         }.into())
     }
 
-
+    let mut data_property = data_property.array()
+        .map_err(prepend("data:"))?.clone();
+
+    Ok(match by_property {
+        Some(by_property) => {
+            let by_property = by_property.array()
+                .map_err(prepend("by:"))?.clone();
+            let by_num_columns= by_property.num_columns
+                .ok_or_else(|| Error::from("number of columns must be known on by"))?;
+            if by_num_columns != 1 {
+                return Err("Partition's by argument must contain a single column".into());
+            }
+            let categories = by_property.categories()
+                .map_err(prepend("by:"))?;
+            data_property.num_records = None;
+
+            HashmapProperties {
+                num_records: data_property.num_records,
+                disjoint: true,
+                properties: match categories {
+                    Jagged::Bool(categories) => broadcast_partitions(&categories, &data_property)?.into(),
+                    Jagged::Str(categories) => broadcast_partitions(&categories, &data_property)?.into(),
+                    Jagged::I64(categories) => broadcast_partitions(&categories, &data_property)?.into(),
+                    _ => return Err("partitioning based on floats is not supported".into())
+                },
+                columnar: false,
+                releasable: false,
+                group_id: Vec::new()
+            }
+        },
+        None => {
+            let num_partitions = num_partitions
+                .ok_or("num_partitions or by must be passed to Partition")?;
+
+            let lengths = match data_property.num_records {
+                Some(num_records) => even_split_lengths(num_records, num_partitions)
+                    .into_iter().map(Some).collect(),
+                None => (0..num_partitions)
+                    .map(|_| None)
+                    .collect::<Vec<Option<i64>>>()
+            };
+
+            HashmapProperties {
+                num_records: data_property.num_records,
+                disjoint: false,
+                properties: lengths.iter().enumerate().map(|(index, partition_num_records)| {
+                    let mut partition_property = data_property.clone();
+                    partition_property.num_records = *partition_num_records;
+                    (index as i64, ValueProperties::Array(partition_property))
+                }).collect::<BTreeMap<i64, ValueProperties>>().into(),
+                columnar: false,
+                releasable: false,
+                group_id: Vec::new()
+            }
+        }
+    }.into())
 }
 
 pub fn even_split_lengths(num_records: i64, num_partitions: i64) -> Vec<i64> {
@@ -0,0 +1,220 @@
+use crate::errors::*;
+
+use std::collections::{HashMap, BTreeMap};
+
+use crate::{proto, base};
+use crate::components::Component;
+use crate::base::{Value, ValueProperties, HashmapProperties, Hashmap, DataType};
+use crate::utilities::prepend;
+
+
+impl Component for proto::PrincipalComponents {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let mut data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        // the components are computed from a released covariance, so this is postprocessing
+        data_property.assert_is_releasable().map_err(prepend("data:"))?;
+
+        if data_property.data_type != DataType::F64 {
+            return Err("data: atomic type must be float".into())
+        }
+        if data_property.num_records()? != 1 {
+            return Err("data: must contain a single row of covariances".into())
+        }
+        let num_variables = num_variables(data_property.num_columns()? as usize)
+            .map_err(prepend("data:"))?;
+        let num_components = get_num_components(self.num_components, num_variables)?;
+
+        data_property.aggregator = None;
+        data_property.nature = None;
+        data_property.dataset_id = None;
+
+        let mut components_property = data_property.clone();
+        components_property.num_records = Some(num_variables as i64);
+        components_property.num_columns = Some(num_components as i64);
+        components_property.c_stability = vec![1.; num_components];
+        components_property.dimensionality = 2;
+
+        let mut variances_property = data_property;
+        variances_property.num_records = Some(num_components as i64);
+        variances_property.num_columns = Some(1);
+        variances_property.c_stability = vec![1.];
+        variances_property.dimensionality = 1;
+
+        Ok(HashmapProperties {
+            num_records: None,
+            disjoint: false,
+            properties: Hashmap::<ValueProperties>::Str(vec![
+                ("components".to_string(), components_property.into()),
+                ("variances".to_string(), variances_property.into())
+            ].into_iter().collect::<BTreeMap<String, ValueProperties>>()),
+            columnar: false,
+            releasable: true,
+            group_id: Vec::new()
+        }.into())
+    }
+}
+
+/// Number of variables `d`, when there are `d (d + 1) / 2` entries in the upper triangle of the covariance.
+pub fn num_variables(num_statistics: usize) -> Result<usize> {
+    (1..).map(|d| (d, d * (d + 1) / 2))
+        .take_while(|(_, length)| *length <= num_statistics)
+        .find(|(_, length)| *length == num_statistics)
+        .map(|(d, _)| d)
+        .ok_or_else(|| "number of statistics does not match the upper triangle of a covariance matrix".into())
+}
+
+/// Number of components to keep, where zero keeps every component.
+pub fn get_num_components(num_components: u32, num_variables: usize) -> Result<usize> {
+    match num_components as usize {
+        0 => Ok(num_variables),
+        k if k > num_variables => Err("num_components: must be no greater than the number of variables".into()),
+        k => Ok(k)
+    }
+}
+
+/// Principal components and their variances, from the upper triangle of a covariance matrix flattened by row.
+///
+/// # Returns
+/// * `0` - Matrix with one row per variable, and one column per component
+/// * `1` - Variance along each component, in decreasing order
+pub fn principal_components(
+    upper_triangle: &[f64], num_components: u32,
+) -> Result<(Vec<Vec<f64>>, Vec<f64>)> {
+    let num_variables = num_variables(upper_triangle.len())?;
+    let num_components = get_num_components(num_components, num_variables)?;
+
+    let mut matrix = vec![vec![0.; num_variables]; num_variables];
+    (0..num_variables)
+        .flat_map(|i| (i..num_variables).map(move |j| (i, j)))
+        .zip(upper_triangle.iter())
+        .for_each(|((i, j), value)| {
+            matrix[i][j] = *value;
+            matrix[j][i] = *value;
+        });
+
+    let (eigenvalues, eigenvectors) = symmetric_eigen(&matrix)?;
+
+    let mut order = (0..num_variables).collect::<Vec<usize>>();
+    order.sort_by(|l, r| eigenvalues[*r].partial_cmp(&eigenvalues[*l])
+        .unwrap_or(std::cmp::Ordering::Equal));
+    order.truncate(num_components);
+
+    // sign each component so that its largest entry in magnitude is positive
+    let signs = order.iter()
+        .map(|column| {
+            let largest = eigenvectors.iter().map(|row| row[*column])
+                .fold(0., |largest: f64, v| if v.abs() > largest.abs() { v } else { largest });
+            if largest < 0. { -1. } else { 1. }
+        })
+        .collect::<Vec<f64>>();
+
+    let components = eigenvectors.iter()
+        .map(|row| order.iter().zip(signs.iter())
+            .map(|(column, sign)| row[*column] * sign)
+            .collect())
+        .collect();
+    let variances = order.iter()
+        .map(|column| eigenvalues[*column].max(0.))
+        .collect();
+
+    Ok((components, variances))
+}
+
+/// Eigendecomposition of a symmetric matrix via cyclic Jacobi rotations.
+///
+/// # Returns
+/// * `0` - Eigenvalues, in no particular order
+/// * `1` - Matrix whose columns are the corresponding unit eigenvectors
+pub fn symmetric_eigen(matrix: &[Vec<f64>]) -> Result<(Vec<f64>, Vec<Vec<f64>>)> {
+    let n = matrix.len();
+    if matrix.iter().any(|row| row.len() != n) {
+        return Err("matrix must be square".into())
+    }
+    if matrix.iter().flatten().any(|v| !v.is_finite()) {
+        return Err("matrix must be finite".into())
+    }
+
+    let mut values = matrix.to_vec();
+    let mut vectors = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1. } else { 0. }).collect::<Vec<f64>>())
+        .collect::<Vec<Vec<f64>>>();
+
+    let scale = matrix.iter().flatten().map(|v| v.powi(2)).sum::<f64>();
+    let tolerance = scale * std::f64::EPSILON.powi(2);
+
+    for _ in 0..100 {
+        let off_diagonal = (0..n)
+            .flat_map(|p| (p + 1..n).map(move |q| (p, q)))
+            .map(|(p, q)| values[p][q].powi(2))
+            .sum::<f64>();
+        if off_diagonal <= tolerance {
+            break
+        }
+
+        for (p, q) in (0..n).flat_map(|p| (p + 1..n).map(move |q| (p, q))) {
+            if values[p][q] == 0. {
+                continue
+            }
+            // rotate by the angle that zeroes the (p, q) entry
+            let theta = (values[q][q] - values[p][p]) / (2. * values[p][q]);
+            let sign = if theta >= 0. { 1. } else { -1. };
+            let tangent = sign / (theta.abs() + (theta.powi(2) + 1.).sqrt());
+            let cosine = 1. / (tangent.powi(2) + 1.).sqrt();
+            let sine = tangent * cosine;
+
+            let rotate_columns = |rows: &mut Vec<Vec<f64>>| rows.iter_mut().for_each(|row| {
+                let (left, right) = (row[p], row[q]);
+                row[p] = cosine * left - sine * right;
+                row[q] = sine * left + cosine * right;
+            });
+            rotate_columns(&mut values);
+            rotate_columns(&mut vectors);
+
+            let (row_p, row_q) = (values[p].clone(), values[q].clone());
+            values[p] = row_p.iter().zip(row_q.iter()).map(|(l, r)| cosine * l - sine * r).collect();
+            values[q] = row_p.iter().zip(row_q.iter()).map(|(l, r)| sine * l + cosine * r).collect();
+        }
+    }
+
+    Ok(((0..n).map(|i| values[i][i]).collect(), vectors))
+}
+
+
+#[cfg(test)]
+mod test_principal_components {
+    use crate::components::principal_components::{num_variables, principal_components};
+
+    #[test]
+    fn test_num_variables() {
+        assert_eq!(num_variables(1).unwrap(), 1);
+        assert_eq!(num_variables(3).unwrap(), 2);
+        assert_eq!(num_variables(6).unwrap(), 3);
+        assert!(num_variables(4).is_err());
+    }
+
+    #[test]
+    fn test_principal_components() {
+        // [[2, 1], [1, 2]] has eigenvalues 3 and 1, along (1, 1) and (1, -1)
+        let (components, variances) = principal_components(&[2., 1., 2.], 0).unwrap();
+        assert!((variances[0] - 3.).abs() < 1e-12);
+        assert!((variances[1] - 1.).abs() < 1e-12);
+        let root_half = 0.5_f64.sqrt();
+        assert!((components[0][0] - root_half).abs() < 1e-12);
+        assert!((components[1][0] - root_half).abs() < 1e-12);
+        assert!((components[0][1].abs() - root_half).abs() < 1e-12);
+        assert!((components[0][1] + components[1][1]).abs() < 1e-12);
+
+        let (components, variances) = principal_components(&[2., 1., 2.], 1).unwrap();
+        assert_eq!(variances.len(), 1);
+        assert!(components.iter().all(|row| row.len() == 1));
+        assert!(principal_components(&[2., 1., 2.], 3).is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::components::Component;
+use crate::base::{Value, ValueProperties, JaggedProperties, Jagged, DataType};
+use crate::utilities::prepend;
+
+
+impl Component for proto::QuantileEdges {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        if self.num_bins == 0 {
+            return Err("num_bins: must be greater than zero".into())
+        }
+
+        let counts_property = properties.get("counts")
+            .ok_or("counts: missing")?.array()
+            .map_err(prepend("counts:"))?.clone();
+
+        // the edges are chosen from released counts, so this is postprocessing
+        counts_property.assert_is_releasable().map_err(prepend("counts:"))?;
+
+        if counts_property.data_type != DataType::F64 && counts_property.data_type != DataType::I64 {
+            return Err("counts: atomic type must be numeric".into())
+        }
+
+        match public_arguments.get("edges")
+            .ok_or_else(|| Error::from("edges: missing, must be public"))?.jagged()? {
+            Jagged::F64(edges) => if !edges.iter().all(|column| column.as_ref()
+                .map(|column| column.len() > 1 && column.windows(2).all(|w| w[0] <= w[1]))
+                .unwrap_or(false)) {
+                return Err("edges: every column must contain at least two sorted edges".into())
+            },
+            _ => return Err("edges: must be floats".into())
+        };
+
+        Ok(JaggedProperties {
+            releasable: true
+        }.into())
+    }
+}
+
+/// Place edges at interpolated quantiles of a histogram over a fine grid of bins.
+///
+/// Negative counts (from noise) are treated as empty bins.
+/// Repeated edges are collapsed, so fewer than `num_bins` bins may be returned.
+pub fn quantile_edges(counts: &[f64], edges: &[f64], num_bins: usize) -> Result<Vec<f64>> {
+    if edges.len() < 2 {
+        return Err("at least two edges are required".into())
+    }
+    let num_fine = edges.len() - 1;
+    if counts.len() < num_fine {
+        return Err("there must be a count for every bin".into())
+    }
+    let counts = counts[..num_fine].iter().map(|v| v.max(0.)).collect::<Vec<f64>>();
+    let total: f64 = counts.iter().sum();
+
+    let mut quantile_edges = vec![edges[0]];
+    for j in 1..num_bins {
+        let edge = if total > 0. {
+            let target = total * j as f64 / num_bins as f64;
+            let mut cumulative = 0.;
+            let mut edge = edges[num_fine];
+            for (i, count) in counts.iter().enumerate() {
+                if *count > 0. && cumulative + count >= target {
+                    let fraction = (target - cumulative) / count;
+                    edge = edges[i] + fraction * (edges[i + 1] - edges[i]);
+                    break
+                }
+                cumulative += count;
+            }
+            edge
+        } else {
+            // nothing was counted, so fall back to equal-width bins
+            edges[0] + (edges[num_fine] - edges[0]) * j as f64 / num_bins as f64
+        };
+        quantile_edges.push(edge);
+    }
+    quantile_edges.push(edges[num_fine]);
+
+    quantile_edges.dedup_by(|a, b| a <= b);
+    Ok(quantile_edges)
+}
+
+
+#[cfg(test)]
+mod test_quantile_edges {
+    use crate::components::quantile_edges::quantile_edges;
+
+    #[test]
+    fn test_quantile_edges() {
+        let edges = vec![0., 1., 2., 3., 4.];
+        // all mass in the first fine bin
+        assert_eq!(quantile_edges(&[4., 0., 0., 0., 7.], &edges, 2).unwrap(), vec![0., 0.5, 4.]);
+        // uniform mass recovers equal-width edges
+        assert_eq!(quantile_edges(&[1., 1., 1., 1.], &edges, 4).unwrap(), edges);
+        // negative counts are ignored, and empty histograms are equal-width
+        assert_eq!(quantile_edges(&[-1., -2., 0., 0.], &edges, 2).unwrap(), vec![0., 2., 4.]);
+        assert!(quantile_edges(&[1.], &edges, 2).is_err());
+    }
+}
@@ -0,0 +1,200 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::hashmap;
+use crate::components::{Component, Expandable};
+use crate::components::partition::broadcast_partitions;
+use crate::base::{Value, Array, Jagged, ValueProperties, HashmapProperties, ArrayProperties, DataType};
+use crate::utilities::{prepend, get_literal};
+use ndarray::arr1;
+
+/// Estimated share of imputed records in a partition above which a warning is raised.
+const IMPUTATION_WARNING_FRACTION: f64 = 0.05;
+
+
+impl Component for proto::RebalancePartitions {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let mut data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let by_property = properties.get("by")
+            .ok_or("by: missing")?.array()
+            .map_err(prepend("by:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+        get_bounds(&data_property)?;
+
+        by_property.assert_is_not_aggregated()?;
+        if by_property.num_columns()? != 1 {
+            return Err("by: must contain a single column".into())
+        }
+        if let (Some(data_num_records), Some(by_num_records)) = (data_property.num_records, by_property.num_records) {
+            if data_num_records != by_num_records {
+                return Err("data and by must have the same number of records".into())
+            }
+        }
+
+        let num_records = public_arguments.get("n")
+            .ok_or("n must be passed to RebalancePartitions")?.first_i64()?;
+        if num_records < 1 {
+            return Err("n must be greater than zero".into())
+        }
+
+        // every partition is resized to the public size
+        data_property.num_records = Some(num_records);
+        data_property.is_not_empty = true;
+
+        Ok(HashmapProperties {
+            num_records: None,
+            disjoint: true,
+            properties: match by_property.categories().map_err(prepend("by:"))? {
+                Jagged::Bool(categories) => broadcast_partitions(&categories, &data_property)?.into(),
+                Jagged::Str(categories) => broadcast_partitions(&categories, &data_property)?.into(),
+                Jagged::I64(categories) => broadcast_partitions(&categories, &data_property)?.into(),
+                _ => return Err("partitioning based on floats is not supported".into())
+            },
+            columnar: false,
+            releasable: false,
+            group_id: Vec::new()
+        }.into())
+    }
+}
+
+impl Expandable for proto::RebalancePartitions {
+    fn expand_component(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        component: &proto::Component,
+        properties: &base::NodeProperties,
+        component_id: &u32,
+        maximum_id: &u32,
+    ) -> Result<proto::ComponentExpansion> {
+        let mut maximum_id = *maximum_id;
+        let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+        let mut releases: HashMap<u32, proto::ReleaseNode> = HashMap::new();
+
+        // the sizes have already been estimated
+        if component.arguments.contains_key("counts") {
+            return Ok(proto::ComponentExpansion {
+                computation_graph,
+                properties: HashMap::new(),
+                releases,
+                traversal: Vec::new()
+            })
+        }
+
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let categories = properties.get("by")
+            .ok_or("by: missing")?.array()
+            .map_err(prepend("by:"))?.categories()?;
+        let id_by = *component.arguments.get("by")
+            .ok_or_else(|| Error::from("by must be provided as an argument"))?;
+
+        // histogram of the partition sizes
+        maximum_id += 1;
+        let id_histogram = maximum_id;
+        computation_graph.insert(id_histogram, proto::Component {
+            arguments: hashmap!["data".to_owned() => id_by],
+            variant: Some(proto::component::Variant::Histogram(proto::Histogram {})),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // noising
+        maximum_id += 1;
+        let id_counts = maximum_id;
+        computation_graph.insert(id_counts, proto::Component {
+            arguments: hashmap!["data".to_owned() => id_histogram],
+            variant: Some(proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+                privacy_usage: self.privacy_usage.clone()
+            })),
+            omit: true,
+            batch: component.batch,
+        });
+
+        // always overwrite the bounds and categories. These are not something a user may configure
+        let (lower, upper) = get_bounds(&data_property)?;
+        let mut rebalance_component = component.clone();
+        for (name, value) in vec![
+            ("lower", lower),
+            ("upper", upper),
+            ("categories", Value::Jagged(categories))
+        ] {
+            maximum_id += 1;
+            let (patch_node, release) = get_literal(&value, &component.batch)?;
+            computation_graph.insert(maximum_id, patch_node);
+            releases.insert(maximum_id, release);
+            rebalance_component.arguments.insert(name.to_string(), maximum_id);
+        }
+        rebalance_component.arguments.insert("counts".to_string(), id_counts);
+        computation_graph.insert(*component_id, rebalance_component);
+
+        Ok(proto::ComponentExpansion {
+            computation_graph,
+            properties: HashMap::new(),
+            releases,
+            traversal: vec![id_histogram, id_counts]
+        })
+    }
+}
+
+/// Warnings for the partitions whose released size implies a substantial share of imputed records, tagged with the node id.
+///
+/// Only raised once the sizes have been released, so that the warnings are postprocessing.
+pub fn get_bias_warnings(public_arguments: &HashMap<String, Value>, node_id: &u32) -> Result<Vec<proto::Error>> {
+    let counts = match public_arguments.get("counts") {
+        Some(counts) => match counts.array()? {
+            Array::F64(counts) => counts.iter().cloned().collect::<Vec<f64>>(),
+            Array::I64(counts) => counts.iter().map(|v| *v as f64).collect::<Vec<f64>>(),
+            _ => return Err("counts: must be numeric".into())
+        },
+        None => return Ok(Vec::new())
+    };
+    let num_records = public_arguments.get("n")
+        .ok_or("n must be passed to RebalancePartitions")?.first_i64()? as f64;
+    let categories = match public_arguments.get("categories").ok_or("categories: missing")?.jagged()? {
+        Jagged::Bool(categories) => to_names(categories),
+        Jagged::I64(categories) => to_names(categories),
+        Jagged::Str(categories) => to_names(categories),
+        Jagged::F64(_) => return Err("partitioning based on floats is not supported".into())
+    }?;
+
+    Ok(categories.iter().zip(counts.iter())
+        .map(|(category, count)| (category, ((num_records - count) / num_records).max(0.).min(1.)))
+        .filter(|(_, imputed)| *imputed > IMPUTATION_WARNING_FRACTION)
+        .map(|(category, imputed)| proto::Error {
+            message: format!(
+                "at node_id {:?}: partition {}: an estimated {:.0}% of the {} records are imputed, which biases its statistics toward the imputation distribution",
+                node_id, category, imputed * 100., num_records)
+        })
+        .collect())
+}
+
+fn to_names<T: ToString>(categories: &[Option<Vec<T>>]) -> Result<Vec<String>> {
+    match categories {
+        [Some(categories)] => Ok(categories.iter().map(ToString::to_string).collect()),
+        _ => Err("categories: must be defined for one column".into())
+    }
+}
+
+/// Lower and upper bounds of every column of numeric data, as literal arrays.
+fn get_bounds(data_property: &ArrayProperties) -> Result<(Value, Value)> {
+    Ok(match data_property.data_type {
+        DataType::F64 => (
+            arr1(&data_property.lower_f64().map_err(prepend("data:"))?).into_dyn().into(),
+            arr1(&data_property.upper_f64().map_err(prepend("data:"))?).into_dyn().into()),
+        DataType::I64 => (
+            arr1(&data_property.lower_i64().map_err(prepend("data:"))?).into_dyn().into(),
+            arr1(&data_property.upper_i64().map_err(prepend("data:"))?).into_dyn().into()),
+        _ => return Err("data: atomic type must be numeric".into())
+    })
+}
@@ -41,6 +41,7 @@ impl Component for proto::Resize {
             // TODO: propagation of categories through imputation and resize
             data_property.nature = None;
             data_property.num_records = Some(num_records);
+            data_property.public_shape = true;
             return Ok(data_property.into());
         }
 
@@ -181,6 +182,8 @@ impl Component for proto::Resize {
 
         data_property.num_records = Some(num_records);
         data_property.is_not_empty = num_records > 0;
+        // n is a public argument, so the shape of the resized data is public
+        data_property.public_shape = true;
         Ok(data_property.into())
     }
 
@@ -0,0 +1,162 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::hashmap;
+use crate::components::Component;
+use crate::base::{Value, ValueProperties, ArrayProperties, DataType};
+use crate::utilities::{prepend, broadcast_privacy_usage};
+
+/// Names of the arguments holding the raw moments, in increasing order.
+pub const MOMENT_NAMES: [&str; 4] = ["first", "second", "third", "fourth"];
+
+
+impl Component for proto::StandardizedMoment {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        if self.order != 3 && self.order != 4 {
+            return Err("order: must be either 3 or 4".into())
+        }
+
+        let moment_properties = MOMENT_NAMES[..self.order as usize].iter()
+            .map(|name| {
+                let moment_property = properties.get(*name)
+                    .ok_or_else(|| Error::from(format!("{}: missing", name)))?.array()
+                    .map_err(prepend(&format!("{}:", name)))?.clone();
+                // the statistic is postprocessing, and may only be computed from released moments
+                moment_property.assert_is_releasable().map_err(prepend(&format!("{}:", name)))?;
+                if moment_property.data_type != DataType::F64 {
+                    return Err(format!("{}: atomic type must be float", name).into())
+                }
+                Ok(moment_property)
+            })
+            .collect::<Result<Vec<ArrayProperties>>>()?;
+
+        let first_property = &moment_properties[0];
+        let num_columns = first_property.num_columns()?;
+        if moment_properties.iter().any(|moment_property| moment_property.num_columns != Some(num_columns)) {
+            return Err("every moment must have the same number of columns".into())
+        }
+
+        Ok(ArrayProperties {
+            num_records: first_property.num_records,
+            num_columns: Some(num_columns),
+            // the moment is undefined where the noisy variance is not positive
+            nullity: true,
+            releasable: true,
+            public_shape: true,
+            public_metadata: true,
+            c_stability: vec![1.; num_columns as usize],
+            aggregator: None,
+            nature: None,
+            data_type: DataType::F64,
+            dataset_id: first_property.dataset_id,
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: first_property.dimensionality
+        }.into())
+    }
+}
+
+/// Standardized moment of the given order, from the raw moments of orders one through `order`.
+///
+/// Returns NaN if the variance implied by the raw moments is not positive.
+pub fn standardized_moment(order: u32, raw_moments: &[f64]) -> Result<f64> {
+    let (mean, variance) = match raw_moments {
+        [first, second, ..] => (*first, second - first.powi(2)),
+        _ => return Err("at least two raw moments are required".into())
+    };
+    if variance.is_nan() || variance <= 0. {
+        return Ok(std::f64::NAN)
+    }
+
+    let central_moment = match (order, raw_moments) {
+        (3, [_, second, third]) =>
+            third - 3. * mean * second + 2. * mean.powi(3),
+        (4, [_, second, third, fourth]) =>
+            fourth - 4. * mean * third + 6. * mean.powi(2) * second - 3. * mean.powi(4),
+        _ => return Err("order must be either 3 or 4, with one raw moment of each order up to it".into())
+    };
+    Ok(central_moment / variance.powf(order as f64 / 2.))
+}
+
+/// Expand a DP standardized moment into DP raw moments of orders one through `order`,
+/// followed by the StandardizedMoment postprocessing at `component_id`.
+///
+/// The privacy usage is split evenly over the raw moments.
+pub fn expand_standardized_moment(
+    order: u32,
+    mechanism: &str,
+    privacy_usage: &[proto::PrivacyUsage],
+    component: &proto::Component,
+    component_id: &u32,
+    maximum_id: &u32,
+) -> Result<proto::ComponentExpansion> {
+    let mut maximum_id = *maximum_id;
+    let mut computation_graph: HashMap<u32, proto::Component> = HashMap::new();
+
+    let id_data = *component.arguments.get("data")
+        .ok_or_else(|| Error::from("data must be provided as an argument"))?;
+    let privacy_usage = match privacy_usage {
+        [privacy_usage] => broadcast_privacy_usage(&[privacy_usage.clone()], order as usize)?,
+        _ => return Err("privacy_usage: must contain a single usage, which is split evenly over the raw moments".into())
+    };
+
+    let mut moment_component = proto::Component {
+        arguments: HashMap::new(),
+        variant: Some(proto::component::Variant::StandardizedMoment(proto::StandardizedMoment { order })),
+        omit: component.omit,
+        batch: component.batch,
+    };
+
+    // noisy raw moments
+    let mut traversal = Vec::new();
+    for (name, (moment_order, usage)) in MOMENT_NAMES.iter().zip((1..=order).zip(privacy_usage)) {
+        maximum_id += 1;
+        computation_graph.insert(maximum_id, proto::Component {
+            arguments: hashmap!["data".to_owned() => id_data],
+            variant: Some(proto::component::Variant::DpMomentRaw(proto::DpMomentRaw {
+                order: moment_order,
+                mechanism: mechanism.to_string(),
+                privacy_usage: vec![usage],
+            })),
+            omit: true,
+            batch: component.batch,
+        });
+        moment_component.arguments.insert(name.to_string(), maximum_id);
+        traversal.push(maximum_id);
+    }
+
+    // standardized moment, as postprocessing
+    computation_graph.insert(*component_id, moment_component);
+
+    Ok(proto::ComponentExpansion {
+        computation_graph,
+        properties: HashMap::new(),
+        releases: HashMap::new(),
+        traversal,
+    })
+}
+
+
+#[cfg(test)]
+mod test_standardized_moment {
+    use crate::components::standardized_moment::standardized_moment;
+
+    #[test]
+    fn test_standardized_moment() {
+        // records [0, 0, 0, 4]: mean 1, variance 3, third central moment 6, fourth central moment 21
+        let raw_moments = [1., 4., 16., 64.];
+        assert!((standardized_moment(3, &raw_moments[..3]).unwrap() - 6. / 3_f64.powf(1.5)).abs() < 1e-12);
+        assert!((standardized_moment(4, &raw_moments).unwrap() - 21. / 9.).abs() < 1e-12);
+
+        // noisy moments may imply a negative variance
+        assert!(standardized_moment(3, &[2., 1., 1.]).unwrap().is_nan());
+        assert!(standardized_moment(4, &raw_moments[..3]).is_err());
+    }
+}
@@ -7,7 +7,7 @@ use crate::{proto, base};
 
 use crate::components::{Component, Sensitivity};
 use crate::base::{Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, DataType};
-use crate::utilities::prepend;
+use crate::utilities::{prepend, get_contribution_bounds};
 use ndarray::prelude::*;
 
 
@@ -64,25 +64,57 @@ impl Sensitivity for proto::Sum {
 
                 data_property.assert_is_not_aggregated()?;
                 data_property.assert_non_null()?;
-                let data_lower = data_property.lower_f64()?;
-                let data_upper = data_property.upper_f64()?;
 
                 use proto::privacy_definition::Neighboring;
                 let neighboring_type = Neighboring::from_i32(privacy_definition.neighboring)
                     .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
 
-                let row_sensitivity = match k {
-                    1|2 => match neighboring_type {
-                        Neighboring::AddRemove => data_lower.iter().zip(data_upper.iter())
-                            .map(|(min, max)| min.abs().max(max.abs()))
-                            .collect::<Vec<f64>>(),
-                        Neighboring::Substitute => data_lower.iter().zip(data_upper.iter())
-                            .map(|(min, max)| max - min)
-                            .collect::<Vec<f64>>()
+                if k != &1 && k != &2 {
+                    return Err("KNorm sensitivity is only supported in L1 and L2 spaces".into())
+                }
+
+                let row_sensitivity = match data_property.data_type {
+                    // integer sums have integer sensitivities, so compute them exactly
+                    DataType::I64 => {
+                        let data_lower = data_property.lower_i64()?;
+                        let data_upper = data_property.upper_i64()?;
+
+                        data_lower.iter().zip(data_upper.iter())
+                            .map(|(min, max)| {
+                                let sensitivity = match neighboring_type {
+                                    Neighboring::AddRemove => min.checked_abs()
+                                        .and_then(|min| Some(min.max(max.checked_abs()?))),
+                                    Neighboring::Substitute => max.checked_sub(*min)
+                                }.ok_or_else(|| Error::from("sensitivity: integer overflow"))?;
+
+                                // f64 represents all integers up to 2^53 exactly
+                                if sensitivity > 2_i64.pow(53) {
+                                    return Err("sensitivity: integer sensitivity is too large to represent exactly".into())
+                                }
+                                Ok(sensitivity as f64)
+                            })
+                            .collect::<Result<Vec<f64>>>()?
+                    },
+                    _ => {
+                        let data_lower = data_property.lower_f64()?;
+                        let data_upper = data_property.upper_f64()?;
+
+                        match neighboring_type {
+                            Neighboring::AddRemove => data_lower.iter().zip(data_upper.iter())
+                                .map(|(min, max)| min.abs().max(max.abs()))
+                                .collect::<Vec<f64>>(),
+                            Neighboring::Substitute => data_lower.iter().zip(data_upper.iter())
+                                .map(|(min, max)| max - min)
+                                .collect::<Vec<f64>>()
+                        }
                     }
-                    _ => return Err("KNorm sensitivity is only supported in L1 and L2 spaces".into())
                 };
 
+                // an individual may contribute many records to each column
+                let row_sensitivity = row_sensitivity.iter().zip(get_contribution_bounds(&data_property)?)
+                    .map(|(sensitivity, contributions)| sensitivity * contributions)
+                    .collect::<Vec<f64>>();
+
                 let mut array_sensitivity = Array::from(row_sensitivity).into_dyn();
                 array_sensitivity.insert_axis_inplace(Axis(0));
 
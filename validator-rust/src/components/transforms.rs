@@ -95,6 +95,8 @@ impl Component for proto::Add {
         Ok(ArrayProperties {
             nullity: left_property.nullity || right_property.nullity,
             releasable: left_property.releasable && right_property.releasable,
+            public_shape: left_property.publicness().shape && right_property.publicness().shape,
+            public_metadata: left_property.publicness().metadata && right_property.publicness().metadata,
             nature: propagate_binary_nature(&left_property, &right_property, &BinaryOperators {
                 f64: Some(Box::new(|l: &f64, r: &f64|
                     Ok(l + r))),
@@ -130,6 +132,7 @@ impl Component for proto::Add {
             data_type: left_property.data_type,
             dataset_id: left_property.dataset_id,
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
+            max_contributions: propagate_contributions(left_property.max_contributions, right_property.max_contributions),
             dimensionality: left_property.dimensionality
                 .max(right_property.dimensionality)
         }.into())
@@ -162,6 +165,11 @@ impl Component for proto::And {
             return Err("left and right arguments must share the same data types".into())
         }
 
+        let publicness = left_property.publicness().and(&right_property.publicness());
+
+        left_property.public_shape = publicness.shape;
+
+        left_property.public_metadata = publicness.metadata;
         left_property.releasable = left_property.releasable && right_property.releasable;
         left_property.nature = propagate_binary_nature(
             &left_property, &right_property,
@@ -243,6 +251,8 @@ impl Component for proto::Divide {
         Ok(ArrayProperties {
             nullity: left_property.nullity || right_property.nullity || float_denominator_may_span_zero,
             releasable: left_property.releasable && right_property.releasable,
+            public_shape: left_property.publicness().shape && right_property.publicness().shape,
+            public_metadata: left_property.publicness().metadata && right_property.publicness().metadata,
             nature: propagate_binary_nature(&left_property, &right_property, &BinaryOperators {
                 f64: Some(Box::new(|l: &f64, r: &f64| {
                     let category = l / r;
@@ -335,6 +345,7 @@ impl Component for proto::Divide {
             data_type: left_property.data_type,
             dataset_id: left_property.dataset_id,
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
+            max_contributions: propagate_contributions(left_property.max_contributions, right_property.max_contributions),
             dimensionality: left_property.dimensionality
                 .max(right_property.dimensionality)
         }.into())
@@ -371,6 +382,8 @@ impl Component for proto::Equal {
         Ok(ArrayProperties {
             nullity: false,
             releasable: left_property.releasable && right_property.releasable,
+            public_shape: left_property.publicness().shape && right_property.publicness().shape,
+            public_metadata: left_property.publicness().metadata && right_property.publicness().metadata,
             nature: Some(Nature::Categorical(NatureCategorical {
                 categories: Jagged::Bool((0..num_columns).map(|_| Some(vec![true, false])).collect())
             })),
@@ -383,6 +396,7 @@ impl Component for proto::Equal {
             data_type: DataType::Bool,
             dataset_id: left_property.dataset_id,
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
+            max_contributions: propagate_contributions(left_property.max_contributions, right_property.max_contributions),
             dimensionality: left_property.dimensionality.max(right_property.dimensionality)
         }.into())
     }
@@ -425,6 +439,8 @@ impl Component for proto::GreaterThan {
         Ok(ArrayProperties {
             nullity: false,
             releasable: left_property.releasable && right_property.releasable,
+            public_shape: left_property.publicness().shape && right_property.publicness().shape,
+            public_metadata: left_property.publicness().metadata && right_property.publicness().metadata,
             nature: Some(Nature::Categorical(NatureCategorical {
                 categories: Jagged::Bool((0..num_columns).map(|_| Some(vec![true, false])).collect())
             })),
@@ -437,6 +453,7 @@ impl Component for proto::GreaterThan {
             data_type: DataType::Bool,
             dataset_id: left_property.dataset_id,
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
+            max_contributions: propagate_contributions(left_property.max_contributions, right_property.max_contributions),
             dimensionality: left_property.dimensionality
                 .max(right_property.dimensionality)
         }.into())
@@ -480,6 +497,8 @@ impl Component for proto::LessThan {
         Ok(ArrayProperties {
             nullity: false,
             releasable: left_property.releasable && right_property.releasable,
+            public_shape: left_property.publicness().shape && right_property.publicness().shape,
+            public_metadata: left_property.publicness().metadata && right_property.publicness().metadata,
             nature: Some(Nature::Categorical(NatureCategorical {
                 categories: Jagged::Bool((0..num_columns).map(|_| Some(vec![true, false])).collect())
             })),
@@ -492,6 +511,7 @@ impl Component for proto::LessThan {
             data_type: DataType::Bool,
             dataset_id: left_property.dataset_id,
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
+            max_contributions: propagate_contributions(left_property.max_contributions, right_property.max_contributions),
             dimensionality: left_property.dimensionality
                 .max(right_property.dimensionality)
         }.into())
@@ -658,6 +678,8 @@ impl Component for proto::Multiply {
         Ok(ArrayProperties {
             nullity: left_property.nullity || right_property.nullity,
             releasable: left_property.releasable && right_property.releasable,
+            public_shape: left_property.publicness().shape && right_property.publicness().shape,
+            public_metadata: left_property.publicness().metadata && right_property.publicness().metadata,
             nature: propagate_binary_nature(&left_property, &right_property, &BinaryOperators {
                 f64: Some(Box::new(|l: &f64, r: &f64| {
                     let category = l * r;
@@ -748,6 +770,7 @@ impl Component for proto::Multiply {
             aggregator: None,
             dataset_id: left_property.dataset_id,
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
+            max_contributions: propagate_contributions(left_property.max_contributions, right_property.max_contributions),
             dimensionality: left_property.dimensionality
                 .max(right_property.dimensionality)
         }.into())
@@ -845,6 +868,11 @@ impl Component for proto::Or {
             return Err("left and right arguments must share the same data types".into())
         }
 
+        let publicness = left_property.publicness().and(&right_property.publicness());
+
+        left_property.public_shape = publicness.shape;
+
+        left_property.public_metadata = publicness.metadata;
         left_property.releasable = left_property.releasable && right_property.releasable;
         left_property.nature = propagate_binary_nature(
             &left_property, &right_property,
@@ -968,6 +996,8 @@ impl Component for proto::RowMax {
         Ok(ArrayProperties {
             nullity: left_property.nullity || right_property.nullity,
             releasable: left_property.releasable && right_property.releasable,
+            public_shape: left_property.publicness().shape && right_property.publicness().shape,
+            public_metadata: left_property.publicness().metadata && right_property.publicness().metadata,
             nature: propagate_binary_nature(&left_property, &right_property, &BinaryOperators {
                 f64: Some(Box::new(|l: &f64, r: &f64|
                     Ok(l.max(*r)))),
@@ -1010,6 +1040,7 @@ impl Component for proto::RowMax {
             data_type: left_property.data_type,
             dataset_id: left_property.dataset_id,
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
+            max_contributions: propagate_contributions(left_property.max_contributions, right_property.max_contributions),
             dimensionality: left_property.dimensionality
                 .max(right_property.dimensionality)
         }.into())
@@ -1045,6 +1076,8 @@ impl Component for proto::RowMin {
         Ok(ArrayProperties {
             nullity: left_property.nullity || right_property.nullity,
             releasable: left_property.releasable && right_property.releasable,
+            public_shape: left_property.publicness().shape && right_property.publicness().shape,
+            public_metadata: left_property.publicness().metadata && right_property.publicness().metadata,
             nature: propagate_binary_nature(&left_property, &right_property, &BinaryOperators {
                 f64: Some(Box::new(|l: &f64, r: &f64|
                     Ok(l.min(*r)))),
@@ -1087,6 +1120,7 @@ impl Component for proto::RowMin {
             data_type: left_property.data_type,
             dataset_id: left_property.dataset_id,
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
+            max_contributions: propagate_contributions(left_property.max_contributions, right_property.max_contributions),
             dimensionality: left_property.dimensionality
                 .max(right_property.dimensionality)
         }.into())
@@ -1122,6 +1156,8 @@ impl Component for proto::Subtract {
         Ok(ArrayProperties {
             nullity: left_property.nullity || right_property.nullity,
             releasable: left_property.releasable && right_property.releasable,
+            public_shape: left_property.publicness().shape && right_property.publicness().shape,
+            public_metadata: left_property.publicness().metadata && right_property.publicness().metadata,
             nature: propagate_binary_nature(&left_property, &right_property, &BinaryOperators {
                 f64: Some(Box::new(|l: &f64, r: &f64|
                     Ok(l - r))),
@@ -1157,6 +1193,7 @@ impl Component for proto::Subtract {
             data_type: left_property.data_type,
             dataset_id: left_property.dataset_id,
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
+            max_contributions: propagate_contributions(left_property.max_contributions, right_property.max_contributions),
             dimensionality: left_property.dimensionality
                 .max(right_property.dimensionality)
         }.into())
@@ -1444,6 +1481,11 @@ pub fn propagate_binary_nature(
     })
 }
 
+/// Contribution bound of the records of a binary operation, known only if it is known for both operands.
+fn propagate_contributions(left: Option<u32>, right: Option<u32>) -> Option<u32> {
+    Some(left?.max(right?))
+}
+
 fn broadcast<T: Clone>(data: &[T], length: &i64) -> Result<Vec<T>> {
     if data.len() as i64 == *length {
         return Ok(data.to_owned());
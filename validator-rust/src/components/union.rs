@@ -0,0 +1,167 @@
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::{proto, base};
+use crate::components::Component;
+use crate::base::{Value, ValueProperties, HashmapProperties, ArrayProperties};
+use crate::utilities::prepend;
+
+
+impl Component for proto::Union {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        _public_arguments: &HashMap<String, Value>,
+        properties: &base::NodeProperties,
+    ) -> Result<ValueProperties> {
+        let data_property = properties.get("data")
+            .ok_or("data: missing")?.hashmap()
+            .map_err(prepend("data:"))?.clone();
+        data_property.assert_is_partition().map_err(prepend("data:"))?;
+
+        union_cells(&data_property.properties.values(), self.flatten)
+            .map_err(prepend("data:"))
+    }
+}
+
+/// Properties of the union of the cells of a partition.
+///
+/// Cells that are themselves partitions are unioned key-wise, removing only the outermost level,
+/// unless `flatten` is set, in which case every level is removed.
+fn union_cells(cells: &[&ValueProperties], flatten: bool) -> Result<ValueProperties> {
+    match cells.first() {
+        Some(ValueProperties::Hashmap(first)) => {
+            let partitions = cells.iter()
+                .map(|cell| cell.hashmap())
+                .collect::<Result<Vec<&HashmapProperties>>>()?;
+            partitions.iter().map(|partition| first.assert_same_groups(partition)).collect::<Result<()>>()?;
+
+            if flatten {
+                return union_cells(&partitions.iter()
+                    .flat_map(|partition| partition.properties.values())
+                    .collect::<Vec<&ValueProperties>>(), true)
+            }
+
+            let inner_cells = (0..first.properties.keys_length() as usize)
+                .map(|index| union_cells(&partitions.iter()
+                    .map(|partition| partition.properties.values()[index])
+                    .collect::<Vec<&ValueProperties>>(), false))
+                .collect::<Result<Vec<ValueProperties>>>()?;
+
+            Ok(HashmapProperties {
+                num_records: partitions.iter().map(|partition| partition.num_records).sum(),
+                disjoint: partitions.iter().all(|partition| partition.disjoint),
+                properties: first.properties.from_values(inner_cells),
+                releasable: partitions.iter().all(|partition| partition.releasable),
+                ..(*first).clone()
+            }.into())
+        },
+        Some(ValueProperties::Array(_)) => union_arrays(&cells.iter()
+            .map(|cell| cell.array())
+            .collect::<Result<Vec<&ArrayProperties>>>()?).map(ValueProperties::Array),
+        Some(_) => Err("cells of the partition must be arrays or partitions".into()),
+        None => Err("partition must have at least one cell".into())
+    }
+}
+
+/// Properties of the records of several arrays, stacked.
+///
+/// Stacked records are no longer row-aligned with the dataset they were partitioned from.
+fn union_arrays(arrays: &[&ArrayProperties]) -> Result<ArrayProperties> {
+    let first = *arrays.first().ok_or("partition must have at least one cell")?;
+    if arrays.iter().any(|array| array.data_type != first.data_type) {
+        return Err("cells must share a data type".into())
+    }
+    if arrays.iter().any(|array| array.num_columns != first.num_columns || array.num_columns.is_none()) {
+        return Err("cells must have the same, known number of columns".into())
+    }
+    if arrays.iter().any(|array| array.aggregator.is_some()) {
+        return Err("cells may not be aggregated".into())
+    }
+
+    Ok(ArrayProperties {
+        num_records: arrays.iter().map(|array| array.num_records).sum(),
+        num_columns: first.num_columns,
+        nullity: arrays.iter().any(|array| array.nullity),
+        releasable: arrays.iter().all(|array| array.releasable),
+        public_shape: arrays.iter().all(|array| array.publicness().shape),
+        public_metadata: arrays.iter().all(|array| array.publicness().metadata),
+        // an individual contributes no more records to the union than to the data that was partitioned
+        c_stability: first.c_stability.iter().enumerate()
+            .map(|(index, c_stability)| arrays.iter()
+                .filter_map(|array| array.c_stability.get(index))
+                .fold(*c_stability, |l, r| l.max(*r)))
+            .collect(),
+        aggregator: None,
+        nature: match arrays.iter().all(|array| array.nature == first.nature) {
+            true => first.nature.clone(),
+            false => None
+        },
+        data_type: first.data_type.clone(),
+        dataset_id: None,
+        is_not_empty: arrays.iter().any(|array| array.is_not_empty),
+        max_contributions: arrays.iter().map(|array| array.max_contributions).collect::<Option<Vec<u32>>>()
+            .and_then(|bounds| bounds.into_iter().max()),
+        dimensionality: first.dimensionality
+    })
+}
+
+
+#[cfg(test)]
+mod test_union {
+    use crate::base::{ValueProperties, HashmapProperties, ArrayProperties, DataType};
+    use crate::components::union::union_cells;
+
+    fn cell(num_records: i64) -> ArrayProperties {
+        ArrayProperties {
+            num_records: Some(num_records),
+            num_columns: Some(2),
+            nullity: false,
+            releasable: false,
+            public_shape: false,
+            public_metadata: false,
+            c_stability: vec![1., 1.],
+            aggregator: None,
+            nature: None,
+            data_type: DataType::F64,
+            dataset_id: Some(0),
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 2
+        }
+    }
+
+    fn partition(cells: Vec<ValueProperties>, group_id: Vec<u32>) -> ValueProperties {
+        HashmapProperties {
+            num_records: None,
+            disjoint: true,
+            properties: (0..cells.len() as i64).zip(cells).collect::<std::collections::BTreeMap<i64, ValueProperties>>().into(),
+            columnar: false,
+            releasable: false,
+            group_id
+        }.into()
+    }
+
+    #[test]
+    fn test_nested_union() {
+        // two states, each partitioned into two age bands
+        let age_bands = |num_records: i64| partition(
+            vec![cell(num_records).into(), cell(num_records + 1).into()], vec![2]);
+        let states = [age_bands(3), age_bands(5)];
+
+        // removing the state level leaves a partition by age band, over all states
+        let by_age = union_cells(&states.iter().collect::<Vec<_>>(), false).unwrap();
+        let by_age = by_age.hashmap().unwrap();
+        assert_eq!(by_age.group_id, vec![2]);
+        assert_eq!(by_age.properties.values()[1].array().unwrap().num_records, Some(10));
+
+        let flattened = union_cells(&states.iter().collect::<Vec<_>>(), true).unwrap();
+        assert_eq!(flattened.array().unwrap().num_records, Some(18));
+        assert_eq!(flattened.array().unwrap().dataset_id, None);
+
+        // cells partitioned by different groups may not be unioned
+        let mismatched = [age_bands(3), partition(vec![cell(3).into(), cell(4).into()], vec![4])];
+        assert!(union_cells(&mismatched.iter().collect::<Vec<_>>(), false).is_err());
+    }
+}
@@ -75,21 +75,86 @@ pub extern "C" fn compute_privacy_usage(
 ) -> ffi_support::ByteBuffer {
     let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
 
-    let response = proto::ResponseComputePrivacyUsage {
-        value: match proto::RequestComputePrivacyUsage::decode(request_buffer) {
-            Ok(request) => match super::compute_privacy_usage(&request) {
+    let (value, warnings, accountant_bounds) = match proto::RequestComputePrivacyUsage::decode(request_buffer) {
+        Ok(request) => match super::compute_privacy_usage(&request) {
+            Ok(x) => {
+                let (x, warnings) = x.into_parts();
+                // the bounds of the other accountants are disclosed alongside the tightest
+                let accountant_bounds = match request.hybrid_accounting {
+                    true => super::compute_accountant_bounds(&request).ok(),
+                    false => None
+                };
+                (Some(proto::response_compute_privacy_usage::Value::Data(x)), warnings, accountant_bounds)
+            },
+            Err(err) =>
+                (Some(proto::response_compute_privacy_usage::Value::Error(serialize_error(err))), Vec::new(), None),
+        }
+        Err(_) =>
+            (Some(proto::response_compute_privacy_usage::Value::Error(serialize_error("unable to parse protobuf".into()))), Vec::new(), None)
+    };
+    let response = proto::ResponseComputePrivacyUsage { value, warnings, accountant_bounds };
+    buffer_to_ptr(response)
+}
+
+/// FFI wrapper for [compute_node_privacy_usages](../fn.compute_node_privacy_usages.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestComputeNodePrivacyUsages](../proto/struct.RequestComputeNodePrivacyUsages.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseComputeNodePrivacyUsages](../proto/struct.ResponseComputeNodePrivacyUsages.html)
+#[no_mangle]
+pub extern "C" fn compute_node_privacy_usages(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseComputeNodePrivacyUsages {
+        value: match proto::RequestComputeNodePrivacyUsages::decode(request_buffer) {
+            Ok(request) => match super::compute_node_privacy_usages(&request) {
                 Ok(x) =>
-                    Some(proto::response_compute_privacy_usage::Value::Data(x)),
+                    Some(proto::response_compute_node_privacy_usages::Value::Data(x)),
                 Err(err) =>
-                    Some(proto::response_compute_privacy_usage::Value::Error(serialize_error(err))),
+                    Some(proto::response_compute_node_privacy_usages::Value::Error(serialize_error(err))),
             }
             Err(_) =>
-                Some(proto::response_compute_privacy_usage::Value::Error(serialize_error("unable to parse protobuf".into())))
+                Some(proto::response_compute_node_privacy_usages::Value::Error(serialize_error("unable to parse protobuf".into())))
         }
     };
     buffer_to_ptr(response)
 }
 
+/// FFI wrapper for [simulate_privacy_usage](../fn.simulate_privacy_usage.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestSimulatePrivacyUsage](../proto/struct.RequestSimulatePrivacyUsage.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseSimulatePrivacyUsage](../proto/struct.ResponseSimulatePrivacyUsage.html)
+#[no_mangle]
+pub extern "C" fn simulate_privacy_usage(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let (value, warnings) = match proto::RequestSimulatePrivacyUsage::decode(request_buffer) {
+        Ok(request) => match super::simulate_privacy_usage(&request) {
+            Ok(x) => {
+                let (x, warnings) = x.into_parts();
+                (Some(proto::response_simulate_privacy_usage::Value::Data(x)), warnings)
+            },
+            Err(err) =>
+                (Some(proto::response_simulate_privacy_usage::Value::Error(serialize_error(err))), Vec::new()),
+        }
+        Err(_) =>
+            (Some(proto::response_simulate_privacy_usage::Value::Error(serialize_error("unable to parse protobuf".into()))), Vec::new())
+    };
+    let response = proto::ResponseSimulatePrivacyUsage { value, warnings };
+    buffer_to_ptr(response)
+}
+
 /// FFI wrapper for [generate_report](../fn.generate_report.html)
 ///
 /// # Arguments
@@ -119,6 +184,209 @@ pub extern "C" fn generate_report(
     buffer_to_ptr(response)
 }
 
+/// FFI wrapper for [export_accounting_events](../fn.export_accounting_events.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestExportAccountingEvents](../proto/struct.RequestExportAccountingEvents.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseExportAccountingEvents](../proto/struct.ResponseExportAccountingEvents.html)
+#[no_mangle]
+pub extern "C" fn export_accounting_events(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseExportAccountingEvents {
+        value: match proto::RequestExportAccountingEvents::decode(request_buffer) {
+            Ok(request) => match super::export_accounting_events(&request) {
+                Ok(x) =>
+                    Some(proto::response_export_accounting_events::Value::Data(x)),
+                Err(err) =>
+                    Some(proto::response_export_accounting_events::Value::Error(serialize_error(err))),
+            }
+            Err(_) =>
+                Some(proto::response_export_accounting_events::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
+/// FFI wrapper for [render_dot](../fn.render_dot.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestRenderDot](../proto/struct.RequestRenderDot.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseRenderDot](../proto/struct.ResponseRenderDot.html)
+#[no_mangle]
+pub extern "C" fn render_dot(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseRenderDot {
+        value: match proto::RequestRenderDot::decode(request_buffer) {
+            Ok(request) => match super::render_dot(&request) {
+                Ok(x) =>
+                    Some(proto::response_render_dot::Value::Data(x)),
+                Err(err) =>
+                    Some(proto::response_render_dot::Value::Error(serialize_error(err))),
+            }
+            Err(_) =>
+                Some(proto::response_render_dot::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
+/// FFI wrapper for [compute_partition_privacy_usage](../fn.compute_partition_privacy_usage.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestComputePartitionPrivacyUsage](../proto/struct.RequestComputePartitionPrivacyUsage.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseComputePartitionPrivacyUsage](../proto/struct.ResponseComputePartitionPrivacyUsage.html)
+#[no_mangle]
+pub extern "C" fn compute_partition_privacy_usage(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseComputePartitionPrivacyUsage {
+        value: match proto::RequestComputePartitionPrivacyUsage::decode(request_buffer) {
+            Ok(request) => match super::compute_partition_privacy_usage(&request) {
+                Ok(x) =>
+                    Some(proto::response_compute_partition_privacy_usage::Value::Data(x)),
+                Err(err) =>
+                    Some(proto::response_compute_partition_privacy_usage::Value::Error(serialize_error(err))),
+            }
+            Err(_) =>
+                Some(proto::response_compute_partition_privacy_usage::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
+/// FFI wrapper for [lint_analysis](../fn.lint_analysis.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestLintAnalysis](../proto/struct.RequestLintAnalysis.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseLintAnalysis](../proto/struct.ResponseLintAnalysis.html)
+#[no_mangle]
+pub extern "C" fn lint_analysis(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseLintAnalysis {
+        value: match proto::RequestLintAnalysis::decode(request_buffer) {
+            Ok(request) => match super::lint_analysis(&request) {
+                Ok(x) =>
+                    Some(proto::response_lint_analysis::Value::Data(x)),
+                Err(err) =>
+                    Some(proto::response_lint_analysis::Value::Error(serialize_error(err))),
+            }
+            Err(_) =>
+                Some(proto::response_lint_analysis::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
+/// FFI wrapper for [diff_analyses](../fn.diff_analyses.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestDiffAnalyses](../proto/struct.RequestDiffAnalyses.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseDiffAnalyses](../proto/struct.ResponseDiffAnalyses.html)
+#[no_mangle]
+pub extern "C" fn diff_analyses(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseDiffAnalyses {
+        value: match proto::RequestDiffAnalyses::decode(request_buffer) {
+            Ok(request) => match super::diff_analyses(&request) {
+                Ok(x) =>
+                    Some(proto::response_diff_analyses::Value::Data(x)),
+                Err(err) =>
+                    Some(proto::response_diff_analyses::Value::Error(serialize_error(err))),
+            }
+            Err(_) =>
+                Some(proto::response_diff_analyses::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
+/// FFI wrapper for [compare_releases](../fn.compare_releases.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestCompareReleases](../proto/struct.RequestCompareReleases.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseCompareReleases](../proto/struct.ResponseCompareReleases.html)
+#[no_mangle]
+pub extern "C" fn compare_releases(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseCompareReleases {
+        value: match proto::RequestCompareReleases::decode(request_buffer) {
+            Ok(request) => match super::compare_releases(&request) {
+                Ok(x) =>
+                    Some(proto::response_compare_releases::Value::Data(x)),
+                Err(err) =>
+                    Some(proto::response_compare_releases::Value::Error(serialize_error(err))),
+            }
+            Err(_) =>
+                Some(proto::response_compare_releases::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
+/// FFI wrapper for [allocate_budget](../fn.allocate_budget.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestAllocateBudget](../proto/struct.RequestAllocateBudget.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseAllocateBudget](../proto/struct.ResponseAllocateBudget.html)
+#[no_mangle]
+pub extern "C" fn allocate_budget(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseAllocateBudget {
+        value: match proto::RequestAllocateBudget::decode(request_buffer) {
+            Ok(request) => match super::allocate_budget(&request) {
+                Ok(x) =>
+                    Some(proto::response_allocate_budget::Value::Data(x)),
+                Err(err) =>
+                    Some(proto::response_allocate_budget::Value::Error(serialize_error(err))),
+            }
+            Err(_) =>
+                Some(proto::response_allocate_budget::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
 /// FFI wrapper for [accuracy_to_privacy_usage](../fn.accuracy_to_privacy_usage.html)
 ///
 /// # Arguments
@@ -178,6 +446,296 @@ pub extern "C" fn privacy_usage_to_accuracy(
     buffer_to_ptr(response)
 }
 
+/// FFI wrapper for [estimate_cost](../fn.estimate_cost.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestEstimateCost](../proto/struct.RequestEstimateCost.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseEstimateCost](../proto/struct.ResponseEstimateCost.html)
+#[no_mangle]
+pub extern "C" fn estimate_cost(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseEstimateCost {
+        value: match proto::RequestEstimateCost::decode(request_buffer) {
+            Ok(request) => match super::estimate_cost(&request) {
+                Ok(x) =>
+                    Some(proto::response_estimate_cost::Value::Data(x)),
+                Err(err) =>
+                    Some(proto::response_estimate_cost::Value::Error(serialize_error(err))),
+            }
+            Err(_) =>
+                Some(proto::response_estimate_cost::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
+/// FFI wrapper for [compute_epsilon_delta_curves](../fn.compute_epsilon_delta_curves.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestComputeEpsilonDeltaCurves](../proto/struct.RequestComputeEpsilonDeltaCurves.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseComputeEpsilonDeltaCurves](../proto/struct.ResponseComputeEpsilonDeltaCurves.html)
+#[no_mangle]
+pub extern "C" fn compute_epsilon_delta_curves(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseComputeEpsilonDeltaCurves {
+        value: match proto::RequestComputeEpsilonDeltaCurves::decode(request_buffer) {
+            Ok(request) => match super::compute_epsilon_delta_curves(&request) {
+                Ok(x) =>
+                    Some(proto::response_compute_epsilon_delta_curves::Value::Data(x)),
+                Err(err) =>
+                    Some(proto::response_compute_epsilon_delta_curves::Value::Error(serialize_error(err))),
+            }
+            Err(_) =>
+                Some(proto::response_compute_epsilon_delta_curves::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
+/// FFI wrapper for [generate_audit_cases](../fn.generate_audit_cases.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestGenerateAuditCases](../proto/struct.RequestGenerateAuditCases.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseGenerateAuditCases](../proto/struct.ResponseGenerateAuditCases.html)
+#[no_mangle]
+pub extern "C" fn generate_audit_cases(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseGenerateAuditCases {
+        value: match proto::RequestGenerateAuditCases::decode(request_buffer) {
+            Ok(request) => match super::generate_audit_cases(&request) {
+                Ok(x) =>
+                    Some(proto::response_generate_audit_cases::Value::Data(x)),
+                Err(err) =>
+                    Some(proto::response_generate_audit_cases::Value::Error(serialize_error(err))),
+            }
+            Err(_) =>
+                Some(proto::response_generate_audit_cases::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
+/// FFI wrapper for [approve_analysis](../fn.approve_analysis.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestApproveAnalysis](../proto/struct.RequestApproveAnalysis.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseApproveAnalysis](../proto/struct.ResponseApproveAnalysis.html)
+#[no_mangle]
+pub extern "C" fn approve_analysis(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseApproveAnalysis {
+        value: match proto::RequestApproveAnalysis::decode(request_buffer) {
+            Ok(request) => match super::approve_analysis(&request) {
+                Ok(x) =>
+                    Some(proto::response_approve_analysis::Value::Data(x)),
+                Err(err) =>
+                    Some(proto::response_approve_analysis::Value::Error(serialize_error(err))),
+            }
+            Err(_) =>
+                Some(proto::response_approve_analysis::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
+/// FFI wrapper for [merge_releases](../fn.merge_releases.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestMergeReleases](../proto/struct.RequestMergeReleases.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseMergeReleases](../proto/struct.ResponseMergeReleases.html)
+#[no_mangle]
+pub extern "C" fn merge_releases(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseMergeReleases {
+        value: match proto::RequestMergeReleases::decode(request_buffer) {
+            Ok(request) => match super::merge_releases(&request) {
+                Ok(x) =>
+                    Some(proto::response_merge_releases::Value::Data(x)),
+                Err(err) =>
+                    Some(proto::response_merge_releases::Value::Error(serialize_error(err))),
+            }
+            Err(_) =>
+                Some(proto::response_merge_releases::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
+/// FFI wrapper for [generate_release_notes](../fn.generate_release_notes.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestGenerateReleaseNotes](../proto/struct.RequestGenerateReleaseNotes.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseGenerateReleaseNotes](../proto/struct.ResponseGenerateReleaseNotes.html)
+#[no_mangle]
+pub extern "C" fn generate_release_notes(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseGenerateReleaseNotes {
+        value: match proto::RequestGenerateReleaseNotes::decode(request_buffer) {
+            Ok(request) => match super::generate_release_notes(&request) {
+                Ok(x) =>
+                    Some(proto::response_generate_release_notes::Value::Data(x)),
+                Err(err) =>
+                    Some(proto::response_generate_release_notes::Value::Error(serialize_error(err))),
+            }
+            Err(_) =>
+                Some(proto::response_generate_release_notes::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
+/// FFI wrapper for [get_execution_schedule](../fn.get_execution_schedule.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestGetExecutionSchedule](../proto/struct.RequestGetExecutionSchedule.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseGetExecutionSchedule](../proto/struct.ResponseGetExecutionSchedule.html)
+#[no_mangle]
+pub extern "C" fn get_execution_schedule(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseGetExecutionSchedule {
+        value: match proto::RequestGetExecutionSchedule::decode(request_buffer) {
+            Ok(request) => match super::get_execution_schedule(&request) {
+                Ok(x) =>
+                    Some(proto::response_get_execution_schedule::Value::Data(x)),
+                Err(err) =>
+                    Some(proto::response_get_execution_schedule::Value::Error(serialize_error(err))),
+            }
+            Err(_) =>
+                Some(proto::response_get_execution_schedule::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
+/// FFI wrapper for [slice_analysis](../fn.slice_analysis.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestSliceAnalysis](../proto/struct.RequestSliceAnalysis.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseSliceAnalysis](../proto/struct.ResponseSliceAnalysis.html)
+#[no_mangle]
+pub extern "C" fn slice_analysis(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseSliceAnalysis {
+        value: match proto::RequestSliceAnalysis::decode(request_buffer) {
+            Ok(request) => match super::slice_analysis(&request) {
+                Ok(x) =>
+                    Some(proto::response_slice_analysis::Value::Data(x)),
+                Err(err) =>
+                    Some(proto::response_slice_analysis::Value::Error(serialize_error(err))),
+            }
+            Err(_) =>
+                Some(proto::response_slice_analysis::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
+/// FFI wrapper for [generate_metadata_questionnaire](../fn.generate_metadata_questionnaire.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestGenerateMetadataQuestionnaire](../proto/struct.RequestGenerateMetadataQuestionnaire.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseGenerateMetadataQuestionnaire](../proto/struct.ResponseGenerateMetadataQuestionnaire.html)
+#[no_mangle]
+pub extern "C" fn generate_metadata_questionnaire(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseGenerateMetadataQuestionnaire {
+        value: match proto::RequestGenerateMetadataQuestionnaire::decode(request_buffer) {
+            Ok(request) => match super::generate_metadata_questionnaire(&request) {
+                Ok(x) =>
+                    Some(proto::response_generate_metadata_questionnaire::Value::Data(x)),
+                Err(err) =>
+                    Some(proto::response_generate_metadata_questionnaire::Value::Error(serialize_error(err))),
+            }
+            Err(_) =>
+                Some(proto::response_generate_metadata_questionnaire::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
+/// FFI wrapper for [apply_metadata_answers](../fn.apply_metadata_answers.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestApplyMetadataAnswers](../proto/struct.RequestApplyMetadataAnswers.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseApplyMetadataAnswers](../proto/struct.ResponseApplyMetadataAnswers.html)
+#[no_mangle]
+pub extern "C" fn apply_metadata_answers(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseApplyMetadataAnswers {
+        value: match proto::RequestApplyMetadataAnswers::decode(request_buffer) {
+            Ok(request) => match super::apply_metadata_answers(&request) {
+                Ok(x) =>
+                    Some(proto::response_apply_metadata_answers::Value::Data(x)),
+                Err(err) =>
+                    Some(proto::response_apply_metadata_answers::Value::Error(serialize_error(err))),
+            }
+            Err(_) =>
+                Some(proto::response_apply_metadata_answers::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
 /// FFI wrapper for [get_properties](../fn.get_properties.html)
 ///
 /// # Arguments
@@ -207,6 +765,36 @@ pub extern "C" fn get_properties(
     buffer_to_ptr(response)
 }
 
+/// FFI wrapper for [expand_analysis](../fn.expand_analysis.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestExpandAnalysis](../proto/struct.RequestExpandAnalysis.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseExpandAnalysis](../proto/struct.ResponseExpandAnalysis.html)
+#[no_mangle]
+pub extern "C" fn expand_analysis(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let (value, warnings) = match proto::RequestExpandAnalysis::decode(request_buffer) {
+        Ok(request) => match super::expand_analysis(&request) {
+            Ok(x) => {
+                let (x, warnings) = x.into_parts();
+                (Some(proto::response_expand_analysis::Value::Data(x)), warnings)
+            },
+            Err(err) =>
+                (Some(proto::response_expand_analysis::Value::Error(serialize_error(err))), Vec::new()),
+        }
+        Err(_) =>
+            (Some(proto::response_expand_analysis::Value::Error(serialize_error("unable to parse protobuf".into()))), Vec::new())
+    };
+    let response = proto::ResponseExpandAnalysis { value, warnings };
+    buffer_to_ptr(response)
+}
+
 /// FFI wrapper for [expand_component](../fn.expand_component.html)
 ///
 /// # Arguments
@@ -221,18 +809,19 @@ pub extern "C" fn expand_component(
 ) -> ffi_support::ByteBuffer {
     let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
 
-    let response = proto::ResponseExpandComponent {
-        value: match proto::RequestExpandComponent::decode(request_buffer) {
-            Ok(request) => match super::expand_component(&request) {
-                Ok(x) =>
-                    Some(proto::response_expand_component::Value::Data(x)),
-                Err(err) =>
-                    Some(proto::response_expand_component::Value::Error(serialize_error(err))),
-            }
-            Err(_) =>
-                Some(proto::response_expand_component::Value::Error(serialize_error("unable to parse protobuf".into())))
+    let (value, warnings) = match proto::RequestExpandComponent::decode(request_buffer) {
+        Ok(request) => match super::expand_component(&request) {
+            Ok(x) => {
+                let (x, warnings) = x.into_parts();
+                (Some(proto::response_expand_component::Value::Data(x)), warnings)
+            },
+            Err(err) =>
+                (Some(proto::response_expand_component::Value::Error(serialize_error(err))), Vec::new()),
         }
+        Err(_) =>
+            (Some(proto::response_expand_component::Value::Error(serialize_error("unable to parse protobuf".into()))), Vec::new())
     };
+    let response = proto::ResponseExpandComponent { value, warnings };
     buffer_to_ptr(response)
 }
 
@@ -18,7 +18,35 @@ extern crate error_chain;
 #[doc(hidden)]
 pub mod errors {
     // Create the Error, ErrorKind, ResultExt, and Result types
-    error_chain! {}
+    error_chain! {
+        errors {
+            // node id, delta and allotment of each mechanism that overspends delta under the delta splitting policy
+            DeltaAllotmentExceeded(violations: Vec<(u32, f64, f64)>) {
+                description("delta exceeds the allotment of the delta splitting policy")
+                display("{}", violations.iter()
+                    .map(|(node_id, delta, allotment)| format!(
+                        "node {}: delta ({}) exceeds the allotment ({}) of the delta splitting policy",
+                        node_id, delta, allotment))
+                    .collect::<Vec<String>>().join("\n"))
+            }
+            // total delta of the analysis, and the delta budget of the privacy definition
+            DeltaBudgetExceeded(total: f64, budget: f64) {
+                description("total delta exceeds the delta budget of the privacy definition")
+                display("total delta ({}) exceeds the delta budget ({}) of the privacy definition", total, budget)
+            }
+            // node id of the mechanism refused by the privacy filter, and why
+            BudgetExhausted(node_id: u32, reason: String) {
+                description("the privacy filter halts the analysis, as the privacy budget would be exhausted")
+                display("node {}: privacy budget exhausted, as {}", node_id, reason)
+            }
+            // node id and epsilon of a mechanism invocation outside of the epsilon floor and ceiling of the privacy definition
+            EpsilonOutOfBounds(node_id: u32, epsilon: f64, floor: f64, ceiling: f64) {
+                description("epsilon of a mechanism invocation is outside of the epsilon floor and ceiling of the privacy definition")
+                display("node {}: epsilon ({}) is outside of the floor ({}) and ceiling ({}) of the privacy definition. \
+                    If this is intended, exempt the node in epsilon_exemptions", node_id, epsilon, floor, ceiling)
+            }
+        }
+    }
 }
 
 #[doc(hidden)]
@@ -31,13 +59,13 @@ pub mod utilities;
 pub mod components;
 pub mod ffi;
 pub mod docs;
+pub mod session;
 
 // import all trait implementations
 use crate::components::*;
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
-use crate::utilities::serial::serialize_value_properties;
-use crate::base::{ReleaseNode, Value};
+use crate::base::{ReleaseNode, Value, Warnable};
 use std::iter::FromIterator;
 
 // for accuracy guarantees
@@ -66,6 +94,11 @@ macro_rules! hashmap {
 ///
 /// Checks that the graph is a DAG.
 /// Checks that static properties are met on all components.
+/// Checks that every mechanism is preceded by a contribution bound on the data sources it draws from.
+/// Checks that every terminal node is public or a mechanism, and is not omitted.
+/// Checks the approval token of the analysis against the release gate, if one is supplied.
+/// Checks that no mechanism spends more delta than the delta splitting policy of the privacy definition allots it.
+/// Checks that the total delta does not exceed the delta cap, or one over the number of records if the privacy definition requires it.
 ///
 /// Useful for static validation of an analysis.
 /// Since some components require public arguments, mechanisms that depend on other mechanisms cannot be verified until the components they depend on have been validated.
@@ -80,7 +113,14 @@ pub fn validate_analysis(
     let release = request.release.clone()
         .ok_or_else(|| Error::from("release must be defined"))?;
 
-    utilities::propagate_properties(&analysis, &release, None, false)?;
+    let (properties, graph) = utilities::propagate_properties(&analysis, &release, None, false)?.into_inner();
+
+    utilities::check_expanded_graph(analysis.privacy_definition.as_ref(), &graph, &properties, &release)?;
+
+    // the approval token must match the analysis, and be present if the release gate requires approval
+    if let Some(release_gate) = &request.release_gate {
+        utilities::gate::check_approval(&analysis, release_gate)?;
+    }
 
     Ok(proto::response_validate_analysis::Validated {
         value: true,
@@ -91,29 +131,83 @@ pub fn validate_analysis(
 
 /// Compute overall privacy usage of an analysis.
 ///
-/// The privacy usage is sum of the privacy usages for each node.
+/// The privacy usage is sum of the privacy usages for each node,
+/// except that mechanisms over disjoint cells of a partition compose in parallel:
+/// each partition is charged the maximum usage over its cells, times the number of cells an individual may contribute to.
 /// The Release's actual privacy usage, if defined, takes priority over the maximum allowable privacy usage defined in the Analysis.
+/// Mechanisms whose budget fraction depends on an earlier release are charged their maximum allowable privacy usage.
+/// External usages declared on the analysis, spent outside of the system on the same dataset, are added to the total.
+/// Unusually large privacy usages, on any mechanism or in total, are returned as warnings.
+/// If `hybrid_accounting` is requested, the tightest bound of every accountant is returned instead of the linear sum,
+/// as in [compute_accountant_bounds](fn.compute_accountant_bounds.html).
+/// Under zCDP the usages are summed in rho, and if `zcdp_delta` is positive, the total is converted to (epsilon, delta)-DP.
+/// If the privacy definition selects advanced or optimal composition, the usages instead compose under that composition theorem,
+/// at the total delta `composition_delta`. Under GDP composition, the usages compose exactly in the mu of Gaussian differential privacy,
+/// and the total is converted to (epsilon, delta)-DP at `composition_delta`.
 pub fn compute_privacy_usage(
     request: &proto::RequestComputePrivacyUsage
-) -> Result<proto::PrivacyUsage> {
+) -> Result<Warnable<proto::PrivacyUsage>> {
     let analysis = request.analysis.as_ref()
         .ok_or_else(|| Error::from("analysis must be defined"))?;
     let release = request.release.as_ref()
         .ok_or_else(|| Error::from("release must be defined"))?;
 
-    let (_, graph, _) = utilities::propagate_properties(analysis, release, None, false)?;
+    let ((properties, graph), warnings) = utilities::propagate_properties(analysis, release, None, false)?.into_parts();
 
-    let usage_option = graph.iter()
-        // return the privacy usage from the release, else from the analysis
-        .filter_map(|(node_id, component)| utilities::get_component_privacy_usage(component, release.values.get(node_id)))
-        // linear sum
-        .fold1(|usage_1, usage_2| utilities::privacy_usage_reducer(
-            &usage_1, &usage_2, &|l, r| l + r));
+    if request.hybrid_accounting {
+        let invocations = utilities::composition::graph_invocations(&graph, release, &analysis.external_usages)?;
+        let bounds = utilities::composition::hybrid_accounting(&invocations, request.hybrid_delta)?;
+        let selected = bounds.selected;
+        let privacy_usage = bounds.bounds.into_iter()
+            .find(|bound| bound.accountant == selected)
+            .and_then(|bound| bound.privacy_usage)
+            .ok_or_else(|| Error::from("the selected accountant must have a bound"))?;
+        utilities::privacy_usage_check(&privacy_usage)?;
+        let total_warnings = utilities::privacy_usage_warnings(&privacy_usage);
+        return Ok(Warnable(privacy_usage, warnings).with_warnings(total_warnings))
+    }
+
+    use proto::privacy_definition::{Composition, Distance};
+    if let Some(privacy_definition) = &analysis.privacy_definition {
+        match Composition::from_i32(privacy_definition.composition)
+            .ok_or_else(|| Error::from("composition: must be one of Basic, Advanced, Optimal or Gdp"))? {
+            Composition::Basic => (),
+            composition => {
+                if privacy_definition.distance == Distance::ZeroConcentrated as i32 {
+                    return Err("composition: zero-concentrated usages compose by summing rho, and may only use Basic composition".into())
+                }
+                let invocations = utilities::composition::graph_invocations(&graph, release, &analysis.external_usages)?;
+                let privacy_usage = match composition {
+                    Composition::Optimal => utilities::composition::optimal_composition(&invocations, privacy_definition.composition_delta)?,
+                    Composition::Gdp => utilities::composition::gdp_composition(&invocations, privacy_definition.composition_delta)?,
+                    _ => utilities::composition::advanced_composition(&invocations, privacy_definition.composition_delta)?
+                };
+                utilities::privacy_usage_check(&privacy_usage)?;
+                let total_warnings = utilities::privacy_usage_warnings(&privacy_usage);
+                return Ok(Warnable(privacy_usage, warnings).with_warnings(total_warnings))
+            }
+        }
+    }
+
+    // linear sum, with parallel composition over the cells of disjoint partitions
+    let usage_option = utilities::privacy::individual_privacy_usage(&graph, &properties, release)?.worst_case;
+
+    // usage spent outside of the system on the same dataset counts towards the total
+    let usage_option = match (usage_option, utilities::external::external_privacy_usage(&analysis.external_usages)?) {
+        (Some(usage), Some(external)) => Some(utilities::external::compose_usages(&usage, &external)),
+        (usage, external) => usage.or(external)
+    };
 
     match usage_option {
         Some(privacy_usage) => {
+            let privacy_usage = match privacy_usage.distance {
+                Some(proto::privacy_usage::Distance::Concentrated(_)) if request.zcdp_delta > 0. =>
+                    utilities::privacy::concentrated_to_approximate(&privacy_usage, request.zcdp_delta)?,
+                _ => privacy_usage
+            };
             utilities::privacy_usage_check(&privacy_usage)?;
-            Ok(privacy_usage)
+            let total_warnings = utilities::privacy_usage_warnings(&privacy_usage);
+            Ok(Warnable(privacy_usage, warnings).with_warnings(total_warnings))
         },
         None => Err("no information is released; privacy usage is none".into())
     }
@@ -122,20 +216,167 @@ pub fn compute_privacy_usage(
 }
 
 
+/// Bound the total privacy usage of an analysis with every accountant, and select the tightest.
+///
+/// Basic composition sums the usages, as in compute_privacy_usage.
+/// Advanced composition, Renyi DP, zCDP and optimal composition are evaluated at `hybrid_delta`, or at the delta of basic composition if unset,
+/// and only apply when the delta of the mechanisms leaves some slack.
+/// The heterogeneous bound composes each family of mechanisms under its tightest accountant, and records the accountant of each family.
+pub fn compute_accountant_bounds(
+    request: &proto::RequestComputePrivacyUsage
+) -> Result<proto::AccountantBounds> {
+    let analysis = request.analysis.as_ref()
+        .ok_or_else(|| Error::from("analysis must be defined"))?;
+    let release = request.release.as_ref()
+        .ok_or_else(|| Error::from("release must be defined"))?;
+
+    let (_, graph) = utilities::propagate_properties(analysis, release, None, false)?.into_inner();
+    let invocations = utilities::composition::graph_invocations(&graph, release, &analysis.external_usages)?;
+    utilities::composition::hybrid_accounting(&invocations, request.hybrid_delta)
+}
+
+
+/// Break the total privacy usage of an analysis down by node, so that clients may display which statistics consume the budget.
+///
+/// Each privatizing node of the expanded analysis is charged its usage, as in compute_privacy_usage, keyed by its node id.
+/// Mechanisms that replace a component when it is expanded keep the id of the component.
+/// When a node releases a vector of statistics, the usage of each column is also returned.
+pub fn compute_node_privacy_usages(
+    request: &proto::RequestComputeNodePrivacyUsages
+) -> Result<proto::NodePrivacyUsages> {
+    let analysis = request.analysis.as_ref()
+        .ok_or_else(|| Error::from("analysis must be defined"))?;
+    let release = request.release.as_ref()
+        .ok_or_else(|| Error::from("release must be defined"))?;
+
+    let (_, graph) = utilities::propagate_properties(analysis, release, None, false)?.into_inner();
+
+    Ok(proto::NodePrivacyUsages {
+        values: utilities::privacy::node_privacy_usages(&graph, release)?
+    })
+}
+
+/// Compute the total privacy usage of an analysis as if the hypothetical components were appended to it, without modifying the analysis.
+///
+/// The usage is composed as in compute_privacy_usage, both with and without the hypothetical components,
+/// so that the cost of a query may be shown before it is submitted.
+/// If the privacy definition caps epsilon, the budget that would remain is also returned, which is negative if a cap would be exceeded.
+pub fn simulate_privacy_usage(
+    request: &proto::RequestSimulatePrivacyUsage
+) -> Result<Warnable<proto::SimulatedPrivacyUsage>> {
+    let analysis = request.analysis.as_ref()
+        .ok_or_else(|| Error::from("analysis must be defined"))?;
+    let release = request.release.as_ref()
+        .ok_or_else(|| Error::from("release must be defined"))?;
+    let graph = &analysis.computation_graph.as_ref()
+        .ok_or_else(|| Error::from("computation_graph must be defined"))?.value;
+
+    let collisions = request.hypothetical_nodes.keys()
+        .filter(|node_id| graph.contains_key(node_id))
+        .sorted().collect::<Vec<&u32>>();
+    if !collisions.is_empty() {
+        bail!("hypothetical_nodes: node ids {:?} are already in the analysis", collisions)
+    }
+
+    let current = total_privacy_usage(analysis, release)?.map(|usage| usage.0);
+
+    let mut simulated_analysis = analysis.clone();
+    simulated_analysis.computation_graph = Some(proto::ComputationGraph {
+        value: graph.clone().into_iter().chain(request.hypothetical_nodes.clone()).collect()
+    });
+    let (simulated, warnings) = compute_privacy_usage(&proto::RequestComputePrivacyUsage {
+        analysis: Some(simulated_analysis),
+        release: Some(release.clone()),
+        ..Default::default()
+    })?.into_parts();
+
+    let remaining = match &analysis.privacy_definition {
+        Some(privacy_definition) if privacy_definition.epsilon_cap > 0. => {
+            let epsilon = privacy_definition.epsilon_cap - utilities::get_epsilon(&simulated)?;
+            Some(proto::PrivacyUsage {
+                distance: Some(match privacy_definition.delta_cap > 0. {
+                    true => proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                        epsilon, delta: privacy_definition.delta_cap - utilities::get_delta(&simulated).unwrap_or(0.)
+                    }),
+                    false => proto::privacy_usage::Distance::Pure(proto::privacy_usage::DistancePure { epsilon })
+                })
+            })
+        },
+        _ => None
+    };
+
+    Ok(Warnable(proto::SimulatedPrivacyUsage {
+        current,
+        simulated: Some(simulated),
+        remaining
+    }, warnings))
+}
+
+
+/// Total privacy usage of an analysis, as in compute_privacy_usage, or None if the analysis does not spend any budget.
+fn total_privacy_usage(
+    analysis: &proto::Analysis,
+    release: &proto::Release,
+) -> Result<Option<Warnable<proto::PrivacyUsage>>> {
+    let (_, graph) = utilities::propagate_properties(analysis, release, None, false)?.into_inner();
+    if !graph.values().any(utilities::is_privatizing) && analysis.external_usages.is_empty() {
+        return Ok(None)
+    }
+    compute_privacy_usage(&proto::RequestComputePrivacyUsage {
+        analysis: Some(analysis.clone()),
+        release: Some(release.clone()),
+        ..Default::default()
+    }).map(Some)
+}
+
+
 /// Generate a json string with a summary/report of the Analysis and Release
+///
+/// If `individual_privacy_loss` is requested, the releases are nested under `releases`,
+/// alongside the worst-case cumulative privacy loss of any one individual under `individualPrivacyLoss`.
+/// Likewise, if the analysis declares external usages, they are disclosed under `externalUsage`,
+/// if `data_quality` is requested, the coercions of every cast are summarized under `dataQuality`,
+/// and if `hybrid_accounting` is requested, the bound of every accountant, the tightest, and the accountant of each family of mechanisms are recorded under `privacyAccounting`,
+/// alongside the composed Renyi curve of the analysis, and the mu of its Gaussian differential privacy, if it has one.
+/// Releases from a Gaussian mechanism carry their epsilon-delta trade-off curve under `epsilonDeltaCurve`.
+///
+/// The release may be omitted before the analysis is executed.
+/// Mechanisms that have not been released yet are listed under `plannedReleases`,
+/// with their expected accuracy and budget allocation, so that the report may serve as a pre-registration of the analysis.
 pub fn generate_report(
     request: &proto::RequestGenerateReport
 ) -> Result<String> {
     let analysis = request.analysis.as_ref()
         .ok_or_else(|| Error::from("analysis must be defined"))?;
-    let release = request.release.as_ref()
-        .ok_or_else(|| Error::from("release must be defined"))?;
+    let release = request.release.clone().unwrap_or_default();
+    let release = &release;
 
     let graph = analysis.computation_graph.to_owned()
         .ok_or("the computation graph must be defined in an analysis")?
         .value;
 
-    let graph_properties = utilities::propagate_properties(analysis, release, None, false)?.0;
+    let (graph_properties, expanded_graph) = utilities::propagate_properties(analysis, release, None, false)?.into_inner();
+    let individual_privacy_usage = match request.individual_privacy_loss {
+        true => Some(utilities::privacy::individual_privacy_usage(&expanded_graph, &graph_properties, release)?),
+        false => None
+    };
+    let data_quality = match request.data_quality {
+        true => Some(components::cast::coercion_summary(&expanded_graph, &graph_properties)?),
+        false => None
+    };
+    let accountant_bounds = match request.hybrid_accounting {
+        true => {
+            let invocations = utilities::composition::graph_invocations(&expanded_graph, release, &analysis.external_usages)?;
+            Some((utilities::composition::hybrid_accounting(&invocations, request.hybrid_delta)?,
+                  utilities::composition::renyi_curve(&invocations),
+                  utilities::composition::gdp_mu(&invocations).ok()))
+        },
+        false => None
+    };
+    let planned_releases = planned_releases(
+        analysis.privacy_definition.as_ref().ok_or_else(|| Error::from("privacy definition must be defined"))?,
+        &expanded_graph, &graph_properties, release,
+        if request.alpha == 0. { 0.05 } else { request.alpha })?;
     let release = utilities::serial::parse_release(&release)?;
 
     // variable names
@@ -170,9 +411,10 @@ pub fn generate_report(
         .ok();
 
     let release_schemas = graph.iter()
+        .sorted_by_key(|(node_id, _)| *node_id)
         .map(|(node_id, component)| {
             let public_arguments = utilities::get_public_arguments(&component, &release)?;
-            let input_properties = utilities::get_input_properties(&component, &graph_properties)?;
+            let input_properties = utilities::get_component_properties(&component, &graph_properties)?;
             let variable_names = nodes_varnames.get(&node_id);
             // ignore nodes without released values
             let node_release = match release.get(node_id) {
@@ -194,116 +436,803 @@ pub fn generate_report(
         .filter_map(|v| v).flat_map(|v| v)
         .collect::<Vec<utilities::json::JSONRelease>>();
 
-    match serde_json::to_string(&release_schemas) {
+    // when a format is requested, attach localized renderings alongside the raw values
+    let mut report = match &request.format {
+        Some(format) => utilities::format::format_report(&release_schemas, format)?,
+        None => serde_json::to_value(&release_schemas)
+            .map_err(|_| Error::from("unable to parse report into json"))?
+    };
+
+    // analyst notes, keyed by the id of the annotated node
+    let mut annotations = graph.iter()
+        .filter_map(|(node_id, component)| match &component.variant {
+            Some(proto::component::Variant::Annotation(annotation)) =>
+                Some((*node_id, *component.arguments.get("data")?, annotation)),
+            _ => None
+        })
+        .collect::<Vec<(u32, u32, &proto::Annotation)>>();
+    annotations.sort_by_key(|(node_id, _, _)| *node_id);
+
+    if let Some(report) = report.as_array_mut() {
+        report.iter_mut()
+            .filter_map(|release| release.as_object_mut())
+            .for_each(|release| {
+                let notes = annotations.iter()
+                    .filter(|(_, annotated_id, _)| release.get("nodeID")
+                        .and_then(|node_id| node_id.as_u64()) == Some(*annotated_id as u64))
+                    .map(|(_, _, annotation)| serde_json::json!({
+                        "note": annotation.note,
+                        "author": annotation.author
+                    }))
+                    .collect::<Vec<serde_json::Value>>();
+                if !notes.is_empty() {
+                    release.insert("annotations".to_string(), serde_json::Value::Array(notes));
+                }
+
+                // gaussian releases satisfy a whole curve of (epsilon, delta) pairs, not only the pair they were calibrated with
+                let is_gaussian = release.get("algorithmInfo")
+                    .and_then(|algorithm_info| algorithm_info.get("mechanism"))
+                    .and_then(|mechanism| mechanism.as_str())
+                    .map_or(false, |mechanism| mechanism.eq_ignore_ascii_case("gaussian"));
+                if is_gaussian {
+                    if let Some(curve) = release.get("privacyLoss").and_then(utilities::tradeoff::privacy_loss_to_curve) {
+                        release.insert("epsilonDeltaCurve".to_string(), curve);
+                    }
+                }
+            });
+    }
+
+    // the releases are nested alongside the per-individual accounting and the external usages, which summarize the analysis as a whole
+    if individual_privacy_usage.is_some() || !analysis.external_usages.is_empty() || data_quality.is_some() || accountant_bounds.is_some() || !planned_releases.is_empty() {
+        let mut summary = serde_json::Map::new();
+        summary.insert("releases".to_string(), report);
+        if !planned_releases.is_empty() {
+            summary.insert("plannedReleases".to_string(), serde_json::Value::Array(planned_releases));
+        }
+        if let Some(individual_privacy_usage) = individual_privacy_usage {
+            summary.insert("individualPrivacyLoss".to_string(), individual_privacy_usage.to_json());
+        }
+        if !analysis.external_usages.is_empty() {
+            summary.insert("externalUsage".to_string(), utilities::external::external_usages_to_json(&analysis.external_usages)?);
+        }
+        if let Some(data_quality) = data_quality {
+            summary.insert("dataQuality".to_string(), serde_json::Value::Array(data_quality));
+        }
+        if let Some((accountant_bounds, renyi_curve, gdp_mu)) = accountant_bounds {
+            let mut accounting = utilities::composition::accountant_bounds_to_json(&accountant_bounds);
+            accounting["renyiCurve"] = renyi_curve.to_json();
+            accounting["gdpMu"] = serde_json::json!(gdp_mu);
+            summary.insert("privacyAccounting".to_string(), accounting);
+        }
+        report = serde_json::Value::Object(summary);
+    }
+
+    match serde_json::to_string(&report) {
         Ok(serialized) => Ok(serialized),
         Err(_) => Err("unable to parse report into json".into())
     }
 }
 
 
+/// Planned release of each mechanism that has not been released yet.
+///
+/// Each entry records the requested privacy usage, its share of the epsilon of all planned releases,
+/// the delta allotted under the delta splitting policy, and the expected accuracy at significance level `alpha`.
+/// Nodes whose accuracy cannot be estimated are listed with a null accuracy.
+fn planned_releases(
+    privacy_definition: &proto::PrivacyDefinition,
+    graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, base::ValueProperties>,
+    release: &proto::Release,
+    alpha: f64,
+) -> Result<Vec<serde_json::Value>> {
+    let planned = privatizing_nodes(graph).into_iter()
+        .filter(|(node_id, _)| !release.values.contains_key(node_id))
+        .map(|(node_id, component)| Ok((node_id, component, utilities::get_charged_privacy_usage(graph, &node_id, release)?)))
+        .collect::<Result<Vec<(u32, &proto::Component, Option<proto::PrivacyUsage>)>>>()?;
+
+    let total_epsilon = planned.iter()
+        .filter_map(|(_, _, usage)| utilities::get_epsilon(usage.as_ref()?).ok())
+        .sum::<f64>();
+    let delta_allotments = utilities::get_delta_allotments(privacy_definition, graph, release)?;
+
+    planned.into_iter()
+        .map(|(node_id, component, usage)| {
+            let accuracies = utilities::get_component_properties(component, properties)
+                .and_then(|component_properties| component.variant.as_ref()
+                    .ok_or_else(|| Error::from("component variant must be defined"))?
+                    .privacy_usage_to_accuracy(privacy_definition, &component_properties, &alpha))
+                .unwrap_or(None);
+
+            let epsilon = usage.as_ref().and_then(|usage| utilities::get_epsilon(usage).ok());
+            Ok(serde_json::json!({
+                "nodeID": node_id,
+                "batch": component.batch,
+                "privacyLoss": usage.as_ref().map(utilities::json::privacy_usage_to_json),
+                "budgetShare": epsilon.filter(|_| total_epsilon > 0.).map(|epsilon| epsilon / total_epsilon),
+                "deltaAllotment": delta_allotments.as_ref().and_then(|allotments| allotments.get(&node_id)),
+                "accuracy": accuracies.map(|accuracies| accuracies.iter()
+                    .map(|accuracy| serde_json::json!({"accuracyValue": accuracy.value, "alpha": accuracy.alpha}))
+                    .collect::<Vec<serde_json::Value>>())
+            }))
+        })
+        .collect()
+}
+
+/// Check an analysis for likely mistakes that do not invalidate it.
+///
+/// Each lint is reported under a stable code:
+/// * `WN001` - a mechanism releases an aggregate of data that was never clamped
+/// * `WN002` - a mechanism spends a delta of at least one over the number of records it aggregates
+/// * `WN003` - a single mechanism spends most of the epsilon of the analysis
+///
+/// Lints are advisory. An analysis must still pass validate_analysis to run.
+pub fn lint_analysis(
+    request: &proto::RequestLintAnalysis
+) -> Result<proto::Lints> {
+    let analysis = request.analysis.as_ref()
+        .ok_or_else(|| Error::from("analysis must be defined"))?;
+    let release = request.release.clone().unwrap_or_default();
+
+    let (properties, graph) = utilities::propagate_properties(analysis, &release, None, false)?.into_inner();
+
+    Ok(proto::Lints { values: utilities::lint::lint_analysis(&graph, &properties, &release)? })
+}
+
+/// Summarize the modifications between two versions of an analysis, for review.
+///
+/// Components are compared by node id. A component present in both analyses is modified if any argument or option differs.
+/// The total privacy usage of each version is computed as in compute_privacy_usage, alongside the change in usage.
+/// The change is unset if the versions measure privacy usage differently.
+pub fn diff_analyses(
+    request: &proto::RequestDiffAnalyses
+) -> Result<proto::AnalysisDiff> {
+    let old_analysis = request.old_analysis.as_ref()
+        .ok_or_else(|| Error::from("old analysis must be defined"))?;
+    let new_analysis = request.new_analysis.as_ref()
+        .ok_or_else(|| Error::from("new analysis must be defined"))?;
+    let get_graph = |analysis: &proto::Analysis, name: &str| -> Result<HashMap<u32, proto::Component>> {
+        Ok(analysis.computation_graph.as_ref()
+            .ok_or_else(|| Error::from(format!("the computation graph must be defined in the {} analysis", name)))?
+            .value.clone())
+    };
+    let old_graph = get_graph(old_analysis, "old")?;
+    let new_graph = get_graph(new_analysis, "new")?;
+
+    let added_node_ids = new_graph.keys()
+        .filter(|node_id| !old_graph.contains_key(node_id))
+        .cloned().sorted().collect::<Vec<u32>>();
+    let removed_node_ids = old_graph.keys()
+        .filter(|node_id| !new_graph.contains_key(node_id))
+        .cloned().sorted().collect::<Vec<u32>>();
+    let modified_node_ids = old_graph.iter()
+        .filter(|(node_id, component)| new_graph.get(node_id)
+            .map(|new_component| new_component != *component).unwrap_or(false))
+        .map(|(node_id, _)| *node_id)
+        .sorted().collect::<Vec<u32>>();
+
+    let old_usage = total_privacy_usage(old_analysis, &request.old_release.clone().unwrap_or_default())?
+        .map(|usage| usage.0);
+    let new_usage = total_privacy_usage(new_analysis, &request.new_release.clone().unwrap_or_default())?
+        .map(|usage| usage.0);
+
+    let usage_change = match (&old_usage, &new_usage) {
+        (Some(old_usage), Some(new_usage)) => Some(utilities::privacy_usage_reducer(new_usage, old_usage, &|l, r| l - r)),
+        (Some(old_usage), None) => Some(utilities::privacy_usage_reducer(old_usage, old_usage, &|l, _| -l)),
+        (None, new_usage) => new_usage.clone()
+    }.filter(|usage_change| usage_change.distance.is_some());
+
+    Ok(proto::AnalysisDiff {
+        added_node_ids,
+        removed_node_ids,
+        modified_node_ids,
+        privacy_definition_changed: old_analysis.privacy_definition != new_analysis.privacy_definition,
+        old_usage,
+        new_usage,
+        usage_change,
+    })
+}
+
+/// Check that a re-release of an analysis did not alter any previously published values.
+///
+/// Published statistics are write-once. Every public node in the old release must be present in the new release,
+/// with the same component definition and the same value. Public nodes that only exist in the new release are permitted.
+pub fn compare_releases(
+    request: &proto::RequestCompareReleases
+) -> Result<proto::ReleaseComparison> {
+    let get_graph = |analysis: &Option<proto::Analysis>, name: &str| -> Result<HashMap<u32, proto::Component>> {
+        Ok(analysis.as_ref()
+            .ok_or_else(|| Error::from(format!("{} analysis must be defined", name)))?
+            .computation_graph.as_ref()
+            .ok_or_else(|| Error::from(format!("the computation graph must be defined in the {} analysis", name)))?
+            .value.clone())
+    };
+    let old_graph = get_graph(&request.old_analysis, "old")?;
+    let new_graph = get_graph(&request.new_analysis, "new")?;
+
+    let old_release = &request.old_release.as_ref()
+        .ok_or_else(|| Error::from("old release must be defined"))?.values;
+    let new_release = &request.new_release.as_ref()
+        .ok_or_else(|| Error::from("new release must be defined"))?.values;
+
+    let mut unchanged_node_ids = old_release.iter()
+        .filter(|(_, release_node)| release_node.public)
+        .map(|(node_id, old_node)| {
+            if old_graph.get(node_id) != new_graph.get(node_id) {
+                return Err(format!("node {} was previously published, but its component has changed", node_id).into())
+            }
+            let new_node = new_release.get(node_id)
+                .ok_or_else(|| Error::from(format!("node {} was previously published, but is missing from the new release", node_id)))?;
+            if old_node.value != new_node.value {
+                return Err(format!("node {} was previously published, but its value has changed", node_id).into())
+            }
+            Ok(*node_id)
+        })
+        .collect::<Result<Vec<u32>>>()?;
+    unchanged_node_ids.sort();
+
+    let mut added_node_ids = new_release.iter()
+        .filter(|(node_id, release_node)| release_node.public
+            && !old_release.get(node_id).map(|old_node| old_node.public).unwrap_or(false))
+        .map(|(node_id, _)| *node_id)
+        .collect::<Vec<u32>>();
+    added_node_ids.sort();
+
+    Ok(proto::ReleaseComparison {
+        unchanged_node_ids,
+        added_node_ids
+    })
+}
+
+
+/// Merge a batch of newly evaluated nodes into an existing release.
+///
+/// A node may only be released once. A node in both releases must be identical, or the merge fails.
+/// Realized privacy usages must be measured as the privacy definition requires,
+/// and may not exceed the usage requested by the component of the analysis they were released from.
+/// Nodes added by expansions are not in the analysis, and are only checked for how their usages are measured.
+/// The cumulative usage is the sum of the realized usages in the merged release, and is unset if nothing has been spent.
+pub fn merge_releases(
+    request: &proto::RequestMergeReleases
+) -> Result<proto::MergedRelease> {
+    let analysis = request.analysis.as_ref()
+        .ok_or_else(|| Error::from("analysis must be defined"))?;
+    let privacy_definition = analysis.privacy_definition.as_ref()
+        .ok_or_else(|| Error::from("privacy definition must be defined"))?;
+    let graph = &analysis.computation_graph.as_ref()
+        .ok_or_else(|| Error::from("the computation graph must be defined in an analysis"))?.value;
+    let mut release = request.base_release.clone().unwrap_or_default();
+    let delta_release = request.delta_release.as_ref()
+        .ok_or_else(|| Error::from("delta release must be defined"))?;
+
+    let conflicts = delta_release.values.iter()
+        .filter(|(node_id, release_node)| release.values.get(node_id)
+            .map(|base_node| base_node != *release_node).unwrap_or(false))
+        .map(|(node_id, _)| *node_id)
+        .sorted().collect::<Vec<u32>>();
+    if !conflicts.is_empty() {
+        bail!("node ids {:?} were already released with different values", conflicts)
+    }
+
+    for (node_id, release_node) in delta_release.values.iter().sorted_by_key(|(node_id, _)| *node_id) {
+        let usages = match &release_node.privacy_usages {
+            Some(usages) => &usages.values,
+            None => continue
+        };
+        utilities::privacy::check_usage_distance(privacy_definition, usages)
+            .chain_err(|| format!("at node_id {:?}", node_id))?;
+
+        let (component, realized) = match (graph.get(node_id), usages.iter().cloned()
+            .fold1(|l, r| utilities::privacy_usage_reducer(&l, &r, &|l, r| l + r))) {
+            (Some(component), Some(realized)) => (component, realized),
+            _ => continue
+        };
+        let requested = utilities::get_component_privacy_usage(component, None)?
+            .ok_or_else(|| Error::from(format!("node {} was released with a privacy usage, but is not a mechanism", node_id)))?;
+
+        use proto::privacy_usage::Distance;
+        let exceeded = match utilities::privacy_usage_reducer(&realized, &requested, &|l, r| l - r).distance {
+            Some(Distance::Pure(excess)) => excess.epsilon > 1e-12,
+            Some(Distance::Approximate(excess)) => excess.epsilon > 1e-12 || excess.delta > 1e-12,
+            Some(Distance::Concentrated(excess)) => excess.rho > 1e-12,
+            None => bail!("node {}: the realized privacy usage is measured differently than the requested privacy usage", node_id)
+        };
+        if exceeded {
+            bail!("node {}: the realized privacy usage exceeds the requested privacy usage", node_id)
+        }
+    }
+
+    release.values.extend(delta_release.values.clone());
+
+    let cumulative_usage = release.values.values()
+        .filter_map(|release_node| release_node.privacy_usages.as_ref())
+        .flat_map(|usages| usages.values.iter().cloned())
+        .fold1(|l, r| utilities::privacy_usage_reducer(&l, &r, &|l, r| l + r));
+
+    Ok(proto::MergedRelease {
+        release: Some(release),
+        cumulative_usage,
+    })
+}
+
+
+/// Generate release notes for a periodic publication, from the releases of two successive periods of the same analysis template.
+///
+/// The notes list the statistics that are new or discontinued in the current period, the statistics whose accuracy changed,
+/// and the privacy usage of each period, alongside the cumulative usage of every period to date.
+/// Accuracies are compared at `alpha`, or at 0.05 if unset.
+pub fn generate_release_notes(
+    request: &proto::RequestGenerateReleaseNotes
+) -> Result<proto::ReleaseNotes> {
+    let previous_analysis = request.previous_analysis.as_ref()
+        .ok_or_else(|| Error::from("previous analysis must be defined"))?;
+    let previous_release = request.previous_release.as_ref()
+        .ok_or_else(|| Error::from("previous release must be defined"))?;
+    let analysis = request.analysis.as_ref()
+        .ok_or_else(|| Error::from("analysis must be defined"))?;
+    let release = request.release.as_ref()
+        .ok_or_else(|| Error::from("release must be defined"))?;
+    let privacy_definition = analysis.privacy_definition.as_ref()
+        .ok_or_else(|| Error::from("privacy definition must be defined"))?;
+
+    if previous_analysis.privacy_definition.as_ref() != Some(privacy_definition) {
+        return Err("the privacy definition must be the same in both periods".into())
+    }
+
+    let (previous_properties, previous_graph) = utilities::propagate_properties(
+        previous_analysis, previous_release, None, false
+    )?.into_inner();
+    let (properties, graph) = utilities::propagate_properties(
+        analysis, release, None, false
+    )?.into_inner();
+
+    utilities::release_notes::generate_release_notes(
+        privacy_definition,
+        &utilities::release_notes::Period { graph: &previous_graph, properties: &previous_properties, release: previous_release },
+        &utilities::release_notes::Period { graph: &graph, properties: &properties, release },
+        request.prior_usage.as_ref(),
+        if request.alpha == 0. { 0.05 } else { request.alpha })
+}
+
+/// Export the privacy accounting of an analysis as DP events, serialized to a json string.
+///
+/// Every mechanism of the expanded analysis is described by the distribution of its noise, in the event format of the `dp_accounting` library,
+/// so that the (epsilon, delta) of the analysis may be independently recomputed by another accountant.
+/// External usages declared on the analysis are exported alongside the mechanisms.
+pub fn export_accounting_events(
+    request: &proto::RequestExportAccountingEvents
+) -> Result<String> {
+    let analysis = request.analysis.as_ref()
+        .ok_or_else(|| Error::from("analysis must be defined"))?;
+    let release = request.release.as_ref()
+        .ok_or_else(|| Error::from("release must be defined"))?;
+
+    let (_, graph) = utilities::propagate_properties(analysis, release, None, false)?.into_inner();
+
+    serde_json::to_string_pretty(&utilities::accounting::accounting_events(&graph, release, &analysis.external_usages)?)
+        .map_err(|e| Error::from(format!("unable to serialize the accounting events: {}", e)))
+}
+
+/// Report the privacy usage absorbed by each cell of the disjoint partitions of an analysis, serialized to a json string.
+///
+/// Cells are identified by the lineage of group ids of the partitions they are drawn from, outermost first,
+/// so that the usage of every stratum of the data may be reviewed separately.
+/// The usage of mechanisms that are not drawn from a single cell is absorbed by every individual, and is reported as unpartitioned.
+pub fn compute_partition_privacy_usage(
+    request: &proto::RequestComputePartitionPrivacyUsage
+) -> Result<String> {
+    let analysis = request.analysis.as_ref()
+        .ok_or_else(|| Error::from("analysis must be defined"))?;
+    let release = request.release.as_ref()
+        .ok_or_else(|| Error::from("release must be defined"))?;
+
+    let (properties, graph) = utilities::propagate_properties(analysis, release, None, false)?.into_inner();
+
+    serde_json::to_string_pretty(&utilities::privacy::partitioned_privacy_usage(&graph, &properties, release)?.to_json())
+        .map_err(|e| Error::from(format!("unable to serialize the partition privacy usage: {}", e)))
+}
+
+/// Render the computation graph of an analysis to the DOT language of GraphViz.
+///
+/// If `expand` is set, the graph is rendered after every component has been expanded, as it is when executed.
+/// Nodes may be annotated with a summary of their propagated properties, and with the privacy usage charged to them.
+pub fn render_dot(
+    request: &proto::RequestRenderDot
+) -> Result<String> {
+    let analysis = request.analysis.as_ref()
+        .ok_or_else(|| Error::from("analysis must be defined"))?;
+    let release = request.release.clone().unwrap_or_default();
+
+    let (properties, expanded_graph) = utilities::propagate_properties(analysis, &release, None, false)?.into_inner();
+    let graph = match request.expand {
+        true => expanded_graph.clone(),
+        false => analysis.computation_graph.as_ref()
+            .ok_or_else(|| Error::from("the computation graph must be defined in an analysis"))?
+            .value.clone()
+    };
+    // components that are expanded into mechanisms keep their node id, so usages of the expanded graph also label the unexpanded graph
+    let usages = utilities::privacy::node_privacy_usages(&expanded_graph, &release)?;
+
+    Ok(utilities::dot::render_dot(
+        &graph,
+        if request.properties { Some(&properties) } else { None },
+        if request.privacy_usage { Some(&usages) } else { None }))
+}
+
+/// Split a total privacy budget across the target nodes of an analysis, to minimize the expected error.
+///
+/// The expected error of each target is modeled as its sensitivity over its epsilon.
+/// The objective either minimizes the sum of the expected errors, or the largest expected error.
+/// The analysis is returned with the allocated privacy usage written into the options of each target.
+pub fn allocate_budget(
+    request: &proto::RequestAllocateBudget
+) -> Result<proto::BudgetAllocation> {
+    let analysis = request.analysis.as_ref()
+        .ok_or_else(|| Error::from("analysis must be defined"))?;
+    let budget = request.budget.as_ref()
+        .ok_or_else(|| Error::from("budget must be defined"))?;
+    let objective = proto::AllocationObjective::from_i32(request.objective)
+        .ok_or_else(|| Error::from("objective must be one of TotalError or MaxError"))?;
+
+    utilities::allocation::allocate_budget(analysis, budget, &request.targets, objective)
+}
+
 /// Estimate the privacy usage necessary to bound accuracy to a given value.
 ///
 /// No context about the analysis is necessary, just the privacy definition and properties of the arguments of the component.
+/// The component is expanded, and each privatizing node is estimated separately.
+/// Nodes without an estimate are reported as unsupported, alongside the estimates of the other nodes.
+///
+/// If an adjustment is requested, the alpha of each accuracy is family-wise over every accuracy of every privatizing node.
 pub fn accuracy_to_privacy_usage(
     request: &proto::RequestAccuracyToPrivacyUsage
-) -> Result<proto::PrivacyUsages> {
+) -> Result<proto::PrivacyUsageEstimates> {
     let component: &proto::Component = request.component.as_ref()
         .ok_or_else(|| Error::from("component must be defined"))?;
     let privacy_definition: &proto::PrivacyDefinition = request.privacy_definition.as_ref()
         .ok_or_else(|| Error::from("privacy definition must be defined"))?;
     let accuracies: &proto::Accuracies = request.accuracies.as_ref()
         .ok_or_else(|| Error::from("accuracies must be defined"))?;
+    let adjustment = proto::AlphaAdjustment::from_i32(request.adjustment)
+        .ok_or_else(|| Error::from(format!("unrecognized alpha adjustment {:?}", request.adjustment)))?;
 
-    let proto_properties = component.arguments.iter()
-        .filter_map(|(name, idx)| Some((idx.clone(), request.properties.get(name)?.clone())))
-        .collect::<HashMap<u32, proto::ValueProperties>>();
+    let (properties, graph) = propagate_component(
+        component, privacy_definition, &request.properties)?;
 
-    let (properties, graph, _) = utilities::propagate_properties(
-        &proto::Analysis {
-            computation_graph: Some(proto::ComputationGraph {
-                value: hashmap![component.arguments.values().max().cloned().unwrap_or(0) + 1 => component.clone()]
-            }),
-            privacy_definition: Some(privacy_definition.clone()),
-        },
-        &proto::Release { values: HashMap::new() },
-        Some(&proto_properties),
-        false
-    )?;
+    // the family contains every accuracy of every privatizing node
+    let nodes = privatizing_nodes(&graph);
+    let family_size = nodes.len() * accuracies.values.len();
+    let accuracies = proto::Accuracies {
+        values: accuracies.values.iter()
+            .map(|accuracy| Ok(proto::Accuracy {
+                value: accuracy.value,
+                alpha: utilities::adjust_alpha(accuracy.alpha, family_size, adjustment)?
+            }))
+            .collect::<Result<Vec<proto::Accuracy>>>()?
+    };
 
-    let privacy_usages = graph.iter().map(|(idx, component)| {
-        let component_properties = component.arguments.iter()
-            .filter_map(|(name, idx)| Some((name.clone(), properties.get(idx)?.clone())))
-            .collect::<HashMap<String, base::ValueProperties>>();
+    let mut estimates = proto::PrivacyUsageEstimates::default();
+    for (node_id, component) in nodes {
+        let estimate = utilities::get_component_properties(component, &properties)
+            .and_then(|component_properties| component.variant.as_ref()
+                .ok_or_else(|| Error::from("component variant must be defined"))?
+                .accuracy_to_privacy_usage(privacy_definition, &component_properties, &accuracies));
 
-        Ok(match component.variant.as_ref()
-            .ok_or_else(|| Error::from("component variant must be defined"))?
-            .accuracy_to_privacy_usage(privacy_definition, &component_properties, &accuracies)? {
-            Some(accuracies) => Some((idx.clone(), accuracies)),
-            None => None
-        })
-    })
-        .collect::<Result<Vec<Option<(u32, Vec<proto::PrivacyUsage>)>>>>()?
-        .into_iter().filter_map(|v| v)
-        .collect::<HashMap<u32, Vec<proto::PrivacyUsage>>>();
-
-    Ok(proto::PrivacyUsages {
-        values: privacy_usages.into_iter().map(|(_, v)| v).collect::<Vec<Vec<proto::PrivacyUsage>>>()
-            .first()
-            .ok_or_else(|| Error::from("privacy usage is not defined"))?.clone()
-    })
+        match estimate {
+            Ok(Some(values)) => {
+                estimates.values.insert(node_id, proto::PrivacyUsages { values });
+            },
+            Ok(None) => {
+                estimates.unsupported.insert(node_id, accuracy_not_implemented());
+            },
+            Err(err) => {
+                estimates.unsupported.insert(node_id, accuracy_failed(err));
+            }
+        }
+    }
+    Ok(estimates)
 }
 
 
 /// Estimate the accuracy of the release of a component, based on a privacy usage.
 ///
 /// No context about the analysis is necessary, just the properties of the arguments of the component.
+/// The component is expanded, and each privatizing node is estimated separately.
+/// Nodes without an estimate are reported as unsupported, alongside the estimates of the other nodes.
+///
+/// Accuracies may be estimated at several family-wise alphas at once, and adjusted so that the intervals of every value
+/// of every privatizing node hold simultaneously. Each matrix holds one row per value, and one column per alpha.
 pub fn privacy_usage_to_accuracy(
     request: &proto::RequestPrivacyUsageToAccuracy
-) -> Result<proto::Accuracies> {
+) -> Result<proto::AccuracyEstimates> {
 
     let component: &proto::Component = request.component.as_ref()
         .ok_or_else(|| Error::from("component must be defined"))?;
     let privacy_definition: &proto::PrivacyDefinition = request.privacy_definition.as_ref()
         .ok_or_else(|| Error::from("privacy definition must be defined"))?;
 
+    let alphas = match request.alphas.is_empty() {
+        true => vec![request.alpha],
+        false => request.alphas.clone()
+    };
+    let adjustment = proto::AlphaAdjustment::from_i32(request.adjustment)
+        .ok_or_else(|| Error::from(format!("unrecognized alpha adjustment {:?}", request.adjustment)))?;
+
+    let (properties, graph) = propagate_component(
+        component, privacy_definition, &request.properties)?;
+
+    let estimate = |component: &proto::Component, alpha: &f64| utilities::get_component_properties(component, &properties)
+        .and_then(|component_properties| component.variant.as_ref()
+            .ok_or_else(|| Error::from("component variant must be defined"))?
+            .privacy_usage_to_accuracy(privacy_definition, &component_properties, alpha));
+
+    // estimate once at the first alpha, to find the supported nodes and the number of intervals in the family
+    let mut estimates = proto::AccuracyEstimates::default();
+    let mut supported = Vec::new();
+    for (node_id, component) in privatizing_nodes(&graph) {
+        match estimate(component, &alphas[0]) {
+            Ok(Some(values)) => supported.push((node_id, component, values.len())),
+            Ok(None) => {
+                estimates.unsupported.insert(node_id, accuracy_not_implemented());
+            },
+            Err(err) => {
+                estimates.unsupported.insert(node_id, accuracy_failed(err));
+            }
+        }
+    }
+
+    let family_size = supported.iter().map(|(_, _, num_intervals)| num_intervals).sum();
+    let adjusted_alphas = alphas.iter()
+        .map(|alpha| utilities::adjust_alpha(*alpha, family_size, adjustment))
+        .collect::<Result<Vec<f64>>>()?;
+
+    for (node_id, component, num_intervals) in supported {
+        let columns = adjusted_alphas.iter()
+            .map(|alpha| estimate(component, alpha)?
+                .ok_or_else(|| Error::from("accuracy is not implemented for this component")))
+            .collect::<Result<Vec<Vec<proto::Accuracy>>>>();
+
+        match columns {
+            Ok(columns) => {
+                estimates.values.insert(node_id, proto::Accuracies { values: columns[0].clone() });
+                estimates.matrices.insert(node_id, proto::AccuracyMatrix {
+                    rows: (0..num_intervals)
+                        .map(|row| proto::Accuracies {
+                            values: columns.iter().filter_map(|column| column.get(row).cloned()).collect()
+                        })
+                        .collect()
+                });
+            },
+            Err(err) => {
+                estimates.unsupported.insert(node_id, accuracy_failed(err));
+            }
+        }
+    }
+    estimates.alphas = alphas;
+    Ok(estimates)
+}
+
+/// Expand a lone component, whose arguments are described only by their properties.
+fn propagate_component(
+    component: &proto::Component,
+    privacy_definition: &proto::PrivacyDefinition,
+    argument_properties: &HashMap<String, proto::ValueProperties>,
+) -> Result<(HashMap<u32, base::ValueProperties>, HashMap<u32, proto::Component>)> {
     let proto_properties = component.arguments.iter()
-        .filter_map(|(name, idx)| Some((idx.clone(), request.properties.get(name)?.clone())))
+        .filter_map(|(name, idx)| Some((idx.clone(), argument_properties.get(name)?.clone())))
         .collect::<HashMap<u32, proto::ValueProperties>>();
 
-    let (properties, graph, _) = utilities::propagate_properties(
+    let (properties, graph) = utilities::propagate_properties(
         &proto::Analysis {
             computation_graph: Some(proto::ComputationGraph {
                 value: hashmap![component.arguments.values().max().cloned().unwrap_or(0) + 1 => component.clone()]
             }),
             privacy_definition: Some(privacy_definition.clone()),
+            approval_token: Vec::new(),
+            external_usages: Vec::new(),
+            optimizations: None,
         },
         &proto::Release { values: HashMap::new() },
         Some(&proto_properties),
-        false,
-    )?;
+        false
+    )?.into_inner();
+    Ok((properties, graph))
+}
 
-    let accuracies = graph.iter().map(|(idx, component)| {
-        let component_properties = component.arguments.iter()
-            .filter_map(|(name, idx)| Some((name.clone(), properties.get(idx)?.clone())))
-            .collect::<HashMap<String, base::ValueProperties>>();
+/// Nodes of an expanded component that are charged a privacy usage, in order of node id.
+fn privatizing_nodes(graph: &HashMap<u32, proto::Component>) -> Vec<(u32, &proto::Component)> {
+    graph.iter()
+        .filter(|(_, component)| utilities::is_privatizing(component))
+        .map(|(node_id, component)| (*node_id, component))
+        .sorted_by_key(|(node_id, _)| *node_id)
+        .collect()
+}
 
-        Ok(match component.variant.as_ref()
-            .ok_or_else(|| Error::from("component variant must be defined"))?
-            .privacy_usage_to_accuracy(privacy_definition, &component_properties, &request.alpha)? {
-            Some(accuracies) => Some((idx.clone(), accuracies)),
-            None => None
-        })
-    })
-        .collect::<Result<Vec<Option<(u32, Vec<proto::Accuracy>)>>>>()?
-        .into_iter().filter_map(|v| v)
-        .collect::<HashMap<u32, Vec<proto::Accuracy>>>();
-
-    Ok(proto::Accuracies {
-        values: accuracies.into_iter().map(|(_, v)| v).collect::<Vec<Vec<proto::Accuracy>>>()
-            // TODO: propagate/combine accuracies, don't just take the first
-            .first()
-            .ok_or_else(|| Error::from("accuracy is not defined"))?.clone()
-    })
+fn accuracy_not_implemented() -> proto::AccuracyUnsupported {
+    proto::AccuracyUnsupported {
+        reason: proto::accuracy_unsupported::Reason::NotImplemented as i32,
+        error: Some(proto::Error { message: "accuracy is not implemented for this component".to_string() }),
+    }
+}
+
+fn accuracy_failed(err: Error) -> proto::AccuracyUnsupported {
+    proto::AccuracyUnsupported {
+        reason: proto::accuracy_unsupported::Reason::Failed as i32,
+        error: Some(ffi::serialize_error(err)),
+    }
+}
+
+/// Estimate the computational cost of evaluating an analysis, before it is executed.
+///
+/// Each node in the expanded graph is charged by a cost model for its component variant, scaled by the number of records and columns of its arguments.
+/// Properties supplied in the request take priority over the propagated properties,
+/// so that the size of a data source may be given even when it is not public.
+pub fn estimate_cost(
+    request: &proto::RequestEstimateCost
+) -> Result<proto::CostEstimate> {
+    let analysis = request.analysis.as_ref()
+        .ok_or_else(|| Error::from("analysis must be defined"))?;
+    let release = request.release.as_ref()
+        .ok_or_else(|| Error::from("release must be defined"))?;
+
+    let (mut properties, graph) = utilities::propagate_properties(
+        analysis, release, None, true
+    )?.into_inner();
+    properties.extend(request.properties.iter()
+        .map(|(node_id, props)| Ok((*node_id, utilities::serial::parse_value_properties(props)?)))
+        .collect::<Result<HashMap<u32, base::ValueProperties>>>()?);
+
+    utilities::cost::estimate_cost(&graph, &properties)
+}
+
+/// Compute the epsilon-delta trade-off curve of every Gaussian mechanism in an analysis.
+///
+/// A Gaussian mechanism satisfies (epsilon, delta)-DP for a whole curve of pairs, not only the pair it is charged.
+/// The curves are sampled at the deltas in [CURVE_DELTAS](utilities/tradeoff/constant.CURVE_DELTAS.html).
+pub fn compute_epsilon_delta_curves(
+    request: &proto::RequestComputeEpsilonDeltaCurves
+) -> Result<proto::EpsilonDeltaCurves> {
+    let analysis = request.analysis.as_ref()
+        .ok_or_else(|| Error::from("analysis must be defined"))?;
+    let release = request.release.as_ref()
+        .ok_or_else(|| Error::from("release must be defined"))?;
+
+    let (properties, graph) = utilities::propagate_properties(analysis, release, None, false)?.into_inner();
+
+    utilities::tradeoff::epsilon_delta_curves(&graph, &properties, release)
+}
+
+/// Generate neighboring-dataset fixtures for empirically auditing the privacy guarantee of an analysis.
+///
+/// Each privatizing node in the statically expanded graph yields test cases that perturb the data by a single extreme record.
+/// The runtime may evaluate the node repeatedly on both neighboring datasets,
+/// and compare the empirical divergence of the outputs against the `max_divergence` of the case.
+pub fn generate_audit_cases(
+    request: &proto::RequestGenerateAuditCases
+) -> Result<proto::AuditCases> {
+    let analysis = request.analysis.as_ref()
+        .ok_or_else(|| Error::from("analysis must be defined"))?;
+    let release = request.release.as_ref()
+        .ok_or_else(|| Error::from("release must be defined"))?;
+    let privacy_definition = analysis.privacy_definition.as_ref()
+        .ok_or_else(|| Error::from("privacy definition must be defined"))?;
+
+    let (properties, graph) = utilities::propagate_properties(
+        analysis, release, None, false
+    )?.into_inner();
+
+    utilities::audit::generate_audit_cases(privacy_definition, &graph, &properties, release)
+}
+
+
+/// Approve an analysis for release, by signing its fingerprint with the curator key.
+///
+/// The returned token is stored in the `approval_token` of the analysis.
+/// Validation and release check the token against the release gate, so any later change to the analysis invalidates it.
+pub fn approve_analysis(
+    request: &proto::RequestApproveAnalysis
+) -> Result<Vec<u8>> {
+    let analysis = request.analysis.as_ref()
+        .ok_or_else(|| Error::from("analysis must be defined"))?;
+
+    utilities::gate::sign_analysis(analysis, &request.curator_key)
+}
+
+/// Compute a schedule for evaluating an analysis, grouping the nodes into levels that may be evaluated in parallel.
+///
+/// Nodes are placed one level after the latest of their arguments, and nodes that have already been released are not scheduled.
+/// Nodes that cannot be statically validated, because they depend on the release of an earlier mechanism, or that expand into smaller components,
+/// require a validator round-trip via expand_component before evaluation. All other nodes may be evaluated directly.
+pub fn get_execution_schedule(
+    request: &proto::RequestGetExecutionSchedule
+) -> Result<proto::ExecutionSchedule> {
+    let analysis = request.analysis.as_ref()
+        .ok_or_else(|| Error::from("analysis must be defined"))?;
+    let release = request.release.as_ref()
+        .ok_or_else(|| Error::from("release must be defined"))?;
+    let graph = &analysis.computation_graph.as_ref()
+        .ok_or_else(|| Error::from("computation graph must be defined"))?.value;
+
+    let (properties, expanded_graph) = utilities::propagate_properties(
+        analysis, release, None, true
+    )?.into_inner();
+
+    let mut node_levels = HashMap::<u32, usize>::new();
+    for node_id in utilities::get_traversal(graph)? {
+        if release.values.contains_key(&node_id) {
+            continue
+        }
+        let level = graph.get(&node_id).unwrap().arguments.values()
+            .filter_map(|argument_id| node_levels.get(argument_id))
+            .map(|level| level + 1)
+            .max().unwrap_or(0);
+        node_levels.insert(node_id, level);
+    }
+
+    let num_levels = node_levels.values().max().map(|level| level + 1).unwrap_or(0);
+    let mut levels = vec![proto::ExecutionLevel::default(); num_levels];
+
+    node_levels.into_iter().sorted().for_each(|(node_id, level)| {
+        levels[level].node_ids.push(node_id);
+        if !properties.contains_key(&node_id) || expanded_graph.get(&node_id) != graph.get(&node_id) {
+            levels[level].round_trip_node_ids.push(node_id);
+        }
+    });
+
+    Ok(proto::ExecutionSchedule { levels })
+}
+
+/// Slice an analysis down to the nodes needed to produce the target nodes, so that they may be released before the rest of the analysis.
+///
+/// The delta cap of the slice is re-scoped, so that each mechanism is allotted the same delta as in the whole analysis,
+/// and the privacy usage of the deferred mechanisms is returned alongside the slice.
+/// The slice does not carry the approval token of the analysis, as the token does not match the slice.
+pub fn slice_analysis(
+    request: &proto::RequestSliceAnalysis
+) -> Result<proto::AnalysisSlice> {
+    let analysis = request.analysis.as_ref()
+        .ok_or_else(|| Error::from("analysis must be defined"))?;
+    let release = request.release.as_ref()
+        .ok_or_else(|| Error::from("release must be defined"))?;
+
+    utilities::slice::slice_analysis(analysis, release, &request.node_ids)
+}
+
+/// Generate a json questionnaire of the metadata missing from the columns of every data source in an analysis.
+///
+/// Properties are propagated dynamically, so that nodes which fail to validate for lack of metadata do not prevent the questionnaire.
+/// Front-ends may present the questions to the curator, and apply the answers with [apply_metadata_answers](fn.apply_metadata_answers.html).
+pub fn generate_metadata_questionnaire(
+    request: &proto::RequestGenerateMetadataQuestionnaire
+) -> Result<String> {
+    let analysis = request.analysis.as_ref()
+        .ok_or_else(|| Error::from("analysis must be defined"))?;
+    let release = request.release.as_ref()
+        .ok_or_else(|| Error::from("release must be defined"))?;
+    let graph = &analysis.computation_graph.as_ref()
+        .ok_or_else(|| Error::from("computation graph must be defined"))?.value;
+
+    let (properties, _) = utilities::propagate_properties(analysis, release, None, true)?.into_inner();
+
+    serde_json::to_string(&utilities::questionnaire::generate_questionnaire(graph, &properties, release)?)
+        .map_err(|e| Error::from(format!("unable to serialize the questionnaire: {}", e)))
+}
+
+/// Apply the json answers to a metadata questionnaire, by clamping and resizing the answered columns of the analysis.
+pub fn apply_metadata_answers(
+    request: &proto::RequestApplyMetadataAnswers
+) -> Result<proto::AnsweredAnalysis> {
+    let analysis = request.analysis.as_ref()
+        .ok_or_else(|| Error::from("analysis must be defined"))?;
+    let release = request.release.as_ref()
+        .ok_or_else(|| Error::from("release must be defined"))?;
+    let answers: serde_json::Value = serde_json::from_str(&request.answers)
+        .map_err(|e| Error::from(format!("answers: unable to parse json: {}", e)))?;
+
+    let (properties, _) = utilities::propagate_properties(analysis, release, None, true)?.into_inner();
+
+    let (analysis, release) = utilities::questionnaire::apply_answers(analysis, release, &properties, &answers)?;
+    Ok(proto::AnsweredAnalysis { analysis: Some(analysis), release: Some(release) })
 }
 
 /// Retrieve the static properties from every reachable node on the graph.
@@ -333,6 +1262,9 @@ pub fn get_properties(
                     .collect::<HashMap<u32, proto::Component>>()
             }),
             privacy_definition: analysis.privacy_definition,
+            approval_token: analysis.approval_token,
+            external_usages: analysis.external_usages,
+            optimizations: analysis.optimizations,
         };
         release = proto::Release {
             values: release.values.iter()
@@ -342,16 +1274,71 @@ pub fn get_properties(
         };
     }
 
-    let (properties, _graph, warnings) = utilities::propagate_properties(
-        &analysis, &release, None, true
-    )?;
+    Ok(propagate_properties(&analysis, &release, &base::PropagationOptions {
+        dynamic: true, ..Default::default()
+    })?.to_proto())
+}
 
-    Ok(proto::GraphProperties {
-        properties: properties.iter()
-            .map(|(node_id, properties)| (*node_id, serialize_value_properties(properties)))
-            .collect::<HashMap<u32, proto::ValueProperties>>(),
-        warnings
-    })
+/// Propagate properties over every node of an analysis, expanding components along the way.
+///
+/// Unlike [get_properties](fn.get_properties.html), the properties are typed,
+/// and the expanded computation graph is returned alongside them.
+/// Static validation fails on the first component that cannot be expanded or propagated,
+/// while dynamic propagation skips the component and its dependents, and records the failure as a warning.
+///
+/// # Arguments
+/// * `analysis` - computation graph and privacy definition
+/// * `release` - values of nodes that have already been evaluated
+/// * `options` - dynamic or static propagation, strictness, and properties cached from an earlier propagation
+pub fn propagate_properties(
+    analysis: &proto::Analysis,
+    release: &proto::Release,
+    options: &base::PropagationOptions,
+) -> Result<base::GraphProperties> {
+    let ((properties, graph), warnings) = utilities::propagate_properties(
+        analysis, release, options.cache.as_ref(), options.dynamic
+    )?.into_parts();
+
+    if options.strict && !warnings.is_empty() {
+        bail!("strict propagation raised warnings: {}", warnings.iter()
+            .map(|warning| warning.message.as_str()).collect::<Vec<&str>>().join("; "))
+    }
+
+    Ok(base::GraphProperties { properties, graph, warnings })
+}
+
+
+/// Expand every component of an analysis, without evaluating anything, to inspect the mechanisms that will execute.
+///
+/// Components are expanded recursively, as the runtime would, until only components that are evaluated directly remain.
+/// Components that depend on the release of a mechanism can't be expanded before it runs, and are left unexpanded with a warning.
+/// The dry run does not validate the analysis, and failures to expand are likewise returned as warnings.
+///
+/// The lowered analysis no longer matches the approval token of the analysis, so the token is cleared.
+/// The release is extended with the public values added by expansions, so that the lowered analysis may be executed as-is.
+pub fn expand_analysis(
+    request: &proto::RequestExpandAnalysis
+) -> Result<Warnable<proto::ExpandedAnalysis>> {
+    let analysis = request.analysis.as_ref()
+        .ok_or_else(|| Error::from("analysis must be defined"))?;
+    let release = request.release.clone().unwrap_or_default();
+
+    let ((_, graph, release), warnings) = utilities::expand_graph(analysis, &release, None, true)?.into_parts();
+
+    let privatizing_node_ids = graph.iter()
+        .filter(|(_, component)| utilities::is_privatizing(component))
+        .map(|(node_id, _)| *node_id)
+        .sorted().collect::<Vec<u32>>();
+
+    Ok(Warnable(proto::ExpandedAnalysis {
+        analysis: Some(proto::Analysis {
+            computation_graph: Some(proto::ComputationGraph { value: graph }),
+            approval_token: Vec::new(),
+            ..analysis.clone()
+        }),
+        release: Some(utilities::serial::serialize_release(&release)?),
+        privatizing_node_ids,
+    }, warnings))
 }
 
 
@@ -359,16 +1346,23 @@ pub fn get_properties(
 ///
 /// This is function may be called interactively from the runtime as the runtime executes the computational graph, to allow for dynamic graph validation.
 /// This is opposed to statically validating a graph, where the nodes in the graph that are dependent on the releases of mechanisms cannot be known and validated until the first release is made.
+///
+/// If the release gate requires approval, mechanisms are only expanded if they are part of an analysis with a valid approval token.
+/// Mechanisms must spend an epsilon within the floor and ceiling of the privacy definition, and unusually large privacy usages on the expanded component are returned as warnings.
+/// Deprecated components are expanded as their replacement, which overwrites the component in the returned patch, with a warning.
+/// If a privacy filter is supplied, mechanisms are only expanded if the filter admits them, given the usages on its odometer.
+/// If the privacy definition enforces its budget, mechanisms are only expanded if their release, together with the spent usage, stays within the epsilon and delta caps.
+/// Elementwise transforms whose arguments are all public are folded into a public release of the component.
 pub fn expand_component(
     request: &proto::RequestExpandComponent
-) -> Result<proto::ComponentExpansion> {
+) -> Result<Warnable<proto::ComponentExpansion>> {
     let public_arguments = request.arguments.iter()
         .map(|(k, v)| Ok((k.to_owned(), utilities::serial::parse_release_node(&v)?)))
         .collect::<Result<HashMap<String, ReleaseNode>>>()?;
 
     let mut properties: base::NodeProperties = request.properties.iter()
-        .map(|(k, v)| (k.to_owned(), utilities::serial::parse_value_properties(v)))
-        .collect();
+        .map(|(k, v)| Ok((k.to_owned(), utilities::serial::parse_value_properties(v)?.into_array_form())))
+        .collect::<Result<base::NodeProperties>>()?;
 
     for (k, v) in &public_arguments {
         // this if should be redundant, no private data should be passed to the validator
@@ -383,6 +1377,31 @@ pub fn expand_component(
         .ok_or_else(|| Error::from("component must be defined"))?;
     let component_id = request.component_id;
 
+    // a deprecated component is expanded as its replacement, which is returned in the patch to overwrite it
+    let (component, deprecation_warnings) = match utilities::deprecation::rewrite_deprecated(component, &component_id) {
+        Some((rewritten, deprecation_warnings)) => (rewritten, deprecation_warnings),
+        None => (component.clone(), Vec::new())
+    };
+    let component = &component;
+
+    if let Some(release_gate) = &request.release_gate {
+        utilities::gate::check_expansion(component, component_id, release_gate, request.analysis.as_ref())?;
+    }
+
+    let requested_usage = utilities::get_component_privacy_usage(component, None)?;
+
+    // the filter is checked against the requested usage, which bounds the usage the mechanism may realize
+    if let (Some(privacy_filter), Some(usage)) = (&request.privacy_filter, &requested_usage) {
+        utilities::filter::check_filter(privacy_filter, component_id, usage)?;
+    }
+
+    // likewise, the budget is enforced against the requested usage, before the mechanism runs
+    if privacy_definition.enforce_budget {
+        if let Some(usage) = &requested_usage {
+            utilities::check_budget(privacy_definition, component_id, request.spent_usage.as_ref(), usage)?;
+        }
+    }
+
     let result = component.variant.as_ref()
         .ok_or_else(|| Error::from("component variant must be defined"))?.expand_component(
         privacy_definition,
@@ -392,24 +1411,170 @@ pub fn expand_component(
         &request.maximum_id,
     ).chain_err(|| format!("at node_id {:?}", component_id))?;
 
+    // private arguments are withheld, so that transforms of private data are never folded into a public release
     let public_values = public_arguments.into_iter()
+        .filter(|(_, release_node)| release_node.public)
         .map(|(name, release_node)| (name.clone(), release_node.value.clone()))
         .collect::<HashMap<String, Value>>();
 
     let mut patch_properties = result.properties;
+    let mut computation_graph = result.computation_graph;
+    let mut releases = result.releases;
+    if !deprecation_warnings.is_empty() {
+        computation_graph.entry(component_id).or_insert_with(|| component.clone());
+    }
+    let mut warnings = deprecation_warnings;
     if result.traversal.is_empty() {
+        utilities::check_epsilon_bounds(privacy_definition, component, component_id)?;
+        warnings.extend(utilities::get_privacy_usage_warnings(component, &component_id)?);
+        if let Some(proto::component::Variant::RebalancePartitions(_)) = &component.variant {
+            warnings.extend(components::rebalance_partitions::get_bias_warnings(&public_values, &component_id)?);
+        }
+
         let propagated_property = component.clone().variant.as_ref()
             .ok_or_else(|| Error::from("component variant must be defined"))?
             .propagate_property(&privacy_definition, &public_values, &properties)
             .chain_err(|| format!("at node_id {:?}", component_id))?;
+        let propagated_property = utilities::apply_partition_group(propagated_property, component, component_id);
 
-        patch_properties.insert(component_id.to_owned(), utilities::serial::serialize_value_properties(&propagated_property));
+        // transforms over public arguments are evaluated here, so that the components that depend on them see a public value
+        let propagated_property = match utilities::optimize::fold_constant(component, &public_values) {
+            Some(value) => {
+                releases.insert(component_id, utilities::serial::serialize_release_node(&ReleaseNode {
+                    value: value.clone(),
+                    privacy_usages: None,
+                    public: true,
+                    public_shape: true,
+                    public_metadata: true,
+                })?);
+                utilities::inference::infer_property(&value)?
+            },
+            None => propagated_property
+        };
+
+        patch_properties.insert(component_id.to_owned(), utilities::serial::serialize_value_properties(&propagated_property.into_scalar_form()));
     }
 
-    Ok(proto::ComponentExpansion {
-        computation_graph: result.computation_graph,
+    Ok(Warnable(proto::ComponentExpansion {
+        computation_graph,
         properties: patch_properties,
-        releases: result.releases,
+        releases,
         traversal: result.traversal,
-    })
-}
\ No newline at end of file
+    }, warnings))
+}
+
+
+#[cfg(test)]
+mod test_expand_component {
+    use crate::{proto, expand_component};
+    use crate::base::{ReleaseNode, Value};
+    use crate::utilities::{serial, inference};
+    use ndarray::arr2;
+    use std::collections::HashMap;
+
+    fn request(component: proto::Component, arguments: HashMap<String, ReleaseNode>) -> proto::RequestExpandComponent {
+        proto::RequestExpandComponent {
+            component: Some(component),
+            properties: arguments.iter()
+                .map(|(name, node)| (name.clone(), serial::serialize_value_properties(&inference::infer_property(&node.value).unwrap())))
+                .collect(),
+            arguments: arguments.iter()
+                .map(|(name, node)| (name.clone(), serial::serialize_release_node(node).unwrap()))
+                .collect(),
+            privacy_definition: Some(proto::PrivacyDefinition::default()),
+            component_id: 2,
+            maximum_id: 2,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_fold_public_arguments() {
+        let component = proto::Component {
+            arguments: hashmap!["data".to_string() => 1],
+            variant: Some(proto::component::Variant::Abs(proto::Abs {})),
+            omit: false,
+            batch: 0
+        };
+        let data = |public: bool| hashmap!["data".to_string() => ReleaseNode {
+            public,
+            ..ReleaseNode::new(Value::from(arr2(&[[-1.], [2.]]).into_dyn()))
+        }];
+
+        // a transform of a private argument is not released
+        let expansion = expand_component(&request(component.clone(), data(false))).unwrap().0;
+        assert!(expansion.releases.is_empty());
+
+        let expansion = expand_component(&request(component, data(true))).unwrap().0;
+        let release = serial::parse_release_node(expansion.releases.get(&2).unwrap()).unwrap();
+        assert!(release.public);
+        assert_eq!(release.value.array().unwrap().f64().unwrap(), &arr2(&[[1.], [2.]]).into_dyn());
+    }
+    #[test]
+    fn test_budget_enforcement() {
+        use crate::errors::ErrorKind;
+        use crate::base::{ArrayProperties, AggregatorProperties, DataType, Nature, NatureContinuous, Vector1DNull};
+
+        let data_property = ArrayProperties {
+            num_records: Some(100),
+            num_columns: Some(1),
+            nullity: false,
+            releasable: false,
+            public_shape: false,
+            public_metadata: false,
+            c_stability: vec![1.],
+            aggregator: None,
+            nature: Some(Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::F64(vec![Some(0.)]),
+                upper: Vector1DNull::F64(vec![Some(1.)]),
+            })),
+            data_type: DataType::F64,
+            dataset_id: Some(0),
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 2
+        };
+        let mut mean_property = data_property.clone();
+        mean_property.aggregator = Some(AggregatorProperties {
+            component: proto::component::Variant::Mean(proto::Mean {}),
+            properties: hashmap!["data".to_string() => data_property.into()]
+        });
+
+        let usage = |epsilon: f64, delta: f64| proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(
+                proto::privacy_usage::DistanceApproximate { epsilon, delta }))
+        };
+        let request = |spent_usage: proto::PrivacyUsage| proto::RequestExpandComponent {
+            component: Some(proto::Component {
+                arguments: hashmap!["data".to_string() => 1],
+                variant: Some(proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+                    privacy_usage: vec![usage(0.5, 0.)]
+                })),
+                omit: false,
+                batch: 0
+            }),
+            properties: hashmap!["data".to_string() => serial::serialize_value_properties(&mean_property.clone().into())],
+            privacy_definition: Some(proto::PrivacyDefinition {
+                group_size: 1,
+                epsilon_cap: 1.,
+                delta_cap: 1e-6,
+                enforce_budget: true,
+                ..Default::default()
+            }),
+            component_id: 2,
+            maximum_id: 2,
+            spent_usage: Some(spent_usage),
+            ..Default::default()
+        };
+
+        assert!(expand_component(&request(usage(0.5, 1e-6))).is_ok());
+
+        // the spent usage and the requested usage together exceed the epsilon cap, or the delta cap
+        for spent_usage in [usage(0.75, 0.), usage(0.25, 2e-6)] {
+            match expand_component(&request(spent_usage)).unwrap_err().kind() {
+                ErrorKind::BudgetExhausted(node_id, _) => assert_eq!(*node_id, 2),
+                _ => panic!("expected the budget to be exhausted")
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Incremental validation of an analysis that grows one component at a time
+//!
+//! Interactive clients add components to an analysis, and validate it after every addition.
+//! A session keeps the expanded graph and the properties of the analysis validated so far,
+//! so that only the components added since are expanded and propagated.
+
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::proto;
+use crate::utilities;
+use crate::base::{ValueProperties, Warnable};
+use crate::utilities::serial::serialize_value_properties;
+
+use itertools::Itertools;
+
+/// An analysis that has been validated, along with its expanded graph and properties.
+pub struct ValidatorSession {
+    analysis: proto::Analysis,
+    release: proto::Release,
+    graph: HashMap<u32, proto::Component>,
+    properties: HashMap<u32, ValueProperties>,
+}
+
+impl ValidatorSession {
+    /// Validate an analysis in full, as in validate_analysis, and start a session from it.
+    pub fn new(analysis: proto::Analysis, release: proto::Release) -> Result<Warnable<ValidatorSession>> {
+        let ((properties, graph), warnings) = utilities::propagate_properties(&analysis, &release, None, false)?.into_parts();
+        utilities::check_expanded_graph(analysis.privacy_definition.as_ref(), &graph, &properties, &release)?;
+
+        Ok(Warnable(ValidatorSession { analysis, release, graph, properties }, warnings))
+    }
+
+    /// Add components to the analysis, expanding and propagating only the added components.
+    ///
+    /// Components already in the session can't depend on the added components, so their properties are reused.
+    /// The invariants over the whole expanded graph are checked again, as the added components may change which nodes are terminal.
+    /// If validation fails, the session is left unchanged.
+    pub fn add_components(&mut self, components: HashMap<u32, proto::Component>) -> Result<Vec<proto::Error>> {
+        // ids must also be distinct from the ids of the components added by earlier expansions
+        let collisions = components.keys()
+            .filter(|node_id| self.graph.contains_key(node_id))
+            .sorted().collect::<Vec<&u32>>();
+        if !collisions.is_empty() {
+            bail!("node ids {:?} are already in the analysis", collisions)
+        }
+
+        let cache = self.properties.iter()
+            .map(|(node_id, properties)| (*node_id, serialize_value_properties(properties)))
+            .collect::<HashMap<u32, proto::ValueProperties>>();
+        let expanded = proto::Analysis {
+            computation_graph: Some(proto::ComputationGraph {
+                value: self.graph.clone().into_iter().chain(components.clone()).collect()
+            }),
+            ..self.analysis.clone()
+        };
+
+        let ((properties, graph), warnings) = utilities::propagate_properties(&expanded, &self.release, Some(&cache), false)?.into_parts();
+        utilities::check_expanded_graph(self.analysis.privacy_definition.as_ref(), &graph, &properties, &self.release)?;
+
+        self.analysis.computation_graph.get_or_insert_with(proto::ComputationGraph::default)
+            .value.extend(components);
+        self.graph = graph;
+        self.properties = properties;
+        Ok(warnings)
+    }
+
+    /// The analysis, including every component added to the session.
+    pub fn analysis(&self) -> &proto::Analysis {
+        &self.analysis
+    }
+
+    /// Properties of every node in the expanded graph.
+    pub fn properties(&self) -> &HashMap<u32, ValueProperties> {
+        &self.properties
+    }
+
+    /// The computation graph, with every component expanded.
+    pub fn graph(&self) -> &HashMap<u32, proto::Component> {
+        &self.graph
+    }
+}
+
+
+#[cfg(test)]
+mod test_session {
+    use crate::proto;
+    use crate::hashmap;
+    use crate::base::{Value, Array};
+    use crate::session::ValidatorSession;
+    use crate::utilities::get_literal;
+    use ndarray::arr1;
+
+    #[test]
+    fn test_add_components() {
+        let (literal, literal_release) = get_literal(&Value::Array(Array::F64(arr1(&[1., 2.]).into_dyn())), &0).unwrap();
+        // the literal is the output of the analysis, so it may not be omitted
+        let literal = proto::Component { omit: false, ..literal };
+        let analysis = proto::Analysis {
+            privacy_definition: Some(proto::PrivacyDefinition { group_size: 1, ..Default::default() }),
+            computation_graph: Some(proto::ComputationGraph { value: hashmap![1 => literal.clone()] }),
+            ..Default::default()
+        };
+        let release = proto::Release { values: hashmap![1 => literal_release] };
+
+        let mut session = ValidatorSession::new(analysis, release).unwrap().0;
+
+        // an id that is already taken leaves the session unchanged
+        assert!(session.add_components(hashmap![1 => literal]).is_err());
+        assert_eq!(session.analysis().computation_graph.as_ref().unwrap().value.len(), 1);
+        assert!(session.properties().contains_key(&1));
+    }
+}
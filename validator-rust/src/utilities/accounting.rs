@@ -0,0 +1,184 @@
+//! Export of privacy accounting as DP events
+//!
+//! Other DP accounting libraries describe the mechanisms of an analysis as a tree of DP events,
+//! from which an accountant of their own recomputes the (epsilon, delta) of the whole analysis.
+//! Events are serialized as the named tuples of the `dp_accounting` library,
+//! tagged with the `module_name` and `class_name` of each event.
+//!
+//! The noise multiplier of an event is the ratio of the scale of the noise to the sensitivity of the query,
+//! under the neighboring definition of the analysis.
+
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::proto;
+use crate::utilities::{get_requested_privacy_usages, is_adaptive, get_epsilon, get_delta};
+use crate::utilities::external::{ExternalMechanism, get_count, invocation_usage};
+
+use itertools::Itertools;
+
+const MODULE_NAME: &str = "dp_accounting.dp_event";
+
+/// Compose the DP events of every privatizing node of an expanded computation graph, in order of node id,
+/// followed by the events of the external usages.
+///
+/// Usages are taken from the release, else from the analysis, as in compute_privacy_usage.
+pub fn accounting_events(
+    graph: &HashMap<u32, proto::Component>,
+    release: &proto::Release,
+    external_usages: &[proto::ExternalUsage],
+) -> Result<serde_json::Value> {
+    let events = graph.keys().sorted()
+        .filter_map(|node_id| node_events(graph, node_id, release).transpose())
+        .chain(external_usages.iter().map(|usage| external_event(usage).map(|event| vec![event])))
+        .collect::<Result<Vec<Vec<serde_json::Value>>>>()?
+        .into_iter().flatten().collect::<Vec<serde_json::Value>>();
+
+    if events.is_empty() {
+        return Err("no information is released; there are no events to account".into())
+    }
+    Ok(event("ComposedDpEvent", serde_json::json!({"events": events})))
+}
+
+/// Usages of a single node, taken from the release, else from the analysis, or None if the node does not privatize.
+///
+/// A node whose budget fraction depends on an earlier release is charged the usages requested in the analysis.
+pub(crate) fn node_usages(
+    graph: &HashMap<u32, proto::Component>,
+    node_id: &u32,
+    release: &proto::Release,
+) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+    let mut usages = match graph.get(node_id).map(get_requested_privacy_usages).transpose()?.flatten() {
+        Some(usages) => usages,
+        None => return Ok(None)
+    };
+    if !is_adaptive(graph, node_id) {
+        if let Some(released) = release.values.get(node_id).and_then(|node| node.privacy_usages.clone()) {
+            usages = released.values
+        }
+    }
+    Ok(Some(usages))
+}
+
+/// Events of a single node, one for each of its privacy usages, or None if the node does not privatize.
+fn node_events(
+    graph: &HashMap<u32, proto::Component>,
+    node_id: &u32,
+    release: &proto::Release,
+) -> Result<Option<Vec<serde_json::Value>>> {
+    let (component, usages) = match (graph.get(node_id), node_usages(graph, node_id, release)?) {
+        (Some(component), Some(usages)) => (component, usages),
+        _ => return Ok(None)
+    };
+
+    use proto::component::Variant;
+    Ok(Some(match component.variant.as_ref() {
+        // the Laplace mechanism is released with noise of scale sensitivity / epsilon
+        Some(Variant::LaplaceMechanism(_)) | Some(Variant::DpRangeTree(_)) | Some(Variant::DpAuc(_)) => usages.iter()
+            .map(|usage| Ok(event("LaplaceDpEvent", serde_json::json!({
+                "noise_multiplier": 1. / get_epsilon(usage)?
+            }))))
+            .collect::<Result<Vec<serde_json::Value>>>()?,
+
+        // the count, sum and sum of squares of each group are released with a third of the usage, and the groups compose in parallel
+        Some(Variant::DpWelchTTest(_)) | Some(Variant::DpGroupMoments(_)) => usages.iter()
+            .map(|usage| Ok(vec![event("LaplaceDpEvent", serde_json::json!({
+                "noise_multiplier": 3. / get_epsilon(usage)?
+            })); 3]))
+            .collect::<Result<Vec<Vec<serde_json::Value>>>>()?
+            .into_iter().flatten().collect(),
+
+        // the Gaussian mechanism is released with noise of scale sensitivity * sqrt(2 ln(1.25 / delta)) / epsilon
+        Some(Variant::GaussianMechanism(_)) => usages.iter()
+            .map(|usage| Ok(event("GaussianDpEvent", serde_json::json!({
+                "noise_multiplier": (2. * (1.25 / get_delta(usage)?).ln()).sqrt() / get_epsilon(usage)?
+            }))))
+            .collect::<Result<Vec<serde_json::Value>>>()?,
+
+        // each step samples records with the sampling rate, and adds Gaussian noise to the clipped gradients
+        Some(Variant::DpStochasticGradientDescent(sgd)) => vec![event("SelfComposedDpEvent", serde_json::json!({
+            "event": event("PoissonSampledDpEvent", serde_json::json!({
+                "sampling_probability": sgd.sampling_rate,
+                "event": event("GaussianDpEvent", serde_json::json!({
+                    "noise_multiplier": sgd.noise_multiplier
+                }))
+            })),
+            "count": sgd.steps
+        }))],
+
+        // mechanisms without a noise distribution known to other libraries are accounted by their usage alone
+        _ => usages.iter()
+            .map(|usage| Ok(event("EpsilonDeltaDpEvent", serde_json::json!({
+                "epsilon": get_epsilon(usage)?,
+                "delta": get_delta(usage).unwrap_or(0.)
+            }))))
+            .collect::<Result<Vec<serde_json::Value>>>()?
+    }))
+}
+
+/// Event of an external usage, self-composed over its invocations.
+fn external_event(external_usage: &proto::ExternalUsage) -> Result<serde_json::Value> {
+    // validate the parameters of the usage
+    invocation_usage(external_usage)?;
+
+    let invocation = match ExternalMechanism::parse(&external_usage.mechanism)? {
+        ExternalMechanism::Laplace => event("LaplaceDpEvent", serde_json::json!({
+            "noise_multiplier": external_usage.noise_multiplier
+        })),
+        ExternalMechanism::Gaussian => event("GaussianDpEvent", serde_json::json!({
+            "noise_multiplier": external_usage.noise_multiplier
+        })),
+        ExternalMechanism::EpsilonDelta => event("EpsilonDeltaDpEvent", serde_json::json!({
+            "epsilon": external_usage.epsilon,
+            "delta": external_usage.delta
+        }))
+    };
+    Ok(match get_count(external_usage) {
+        1 => invocation,
+        count => event("SelfComposedDpEvent", serde_json::json!({"event": invocation, "count": count}))
+    })
+}
+
+/// Tag the fields of an event with its module and class names.
+fn event(class_name: &str, fields: serde_json::Value) -> serde_json::Value {
+    let mut event = serde_json::json!({
+        "module_name": MODULE_NAME,
+        "class_name": class_name
+    });
+    if let (Some(event), serde_json::Value::Object(fields)) = (event.as_object_mut(), fields) {
+        event.extend(fields);
+    }
+    event
+}
+
+
+#[cfg(test)]
+mod test_accounting {
+    use std::collections::HashMap;
+
+    use crate::proto;
+    use crate::hashmap;
+    use crate::utilities::accounting::accounting_events;
+
+    #[test]
+    fn test_accounting_events() {
+        let graph = hashmap![1 => proto::Component {
+            arguments: HashMap::new(),
+            variant: Some(proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+                privacy_usage: vec![proto::PrivacyUsage {
+                    distance: Some(proto::privacy_usage::Distance::Pure(proto::privacy_usage::DistancePure { epsilon: 0.5 }))
+                }]
+            })),
+            omit: false,
+            batch: 0,
+        }];
+
+        let events = accounting_events(&graph, &proto::Release::default(), &[]).unwrap();
+        assert_eq!(events["class_name"], "ComposedDpEvent");
+        assert_eq!(events["events"][0]["class_name"], "LaplaceDpEvent");
+        assert_eq!(events["events"][0]["noise_multiplier"], 2.);
+
+        assert!(accounting_events(&HashMap::new(), &proto::Release::default(), &[]).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+//! Allocation of a privacy budget across the mechanisms of an analysis
+//!
+//! The noise of a mechanism scales with the sensitivity of its query over its epsilon,
+//! so the expected error of each node is modeled as `c / epsilon`, where c is the sensitivity of the node.
+//! Under basic composition, the split of epsilon that minimizes the total error sets each epsilon proportional to `sqrt(c)`,
+//! and the split that minimizes the largest error sets each epsilon proportional to `c`.
+
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::proto;
+use crate::utilities::{get_epsilon, get_delta};
+
+/// Replace the privacy usage in the options of a component, erroring if the component has no privacy usage.
+macro_rules! set_privacy_usage {
+    ($variant:expr, $usage:expr, $($name:ident),*) => {
+        match $variant {
+            $(proto::component::Variant::$name(options) => options.privacy_usage = $usage,)*
+            _ => return Err("component does not have a privacy usage to allocate".into())
+        }
+    }
+}
+
+/// Split a total budget across the target nodes of an analysis, to minimize the expected error under the objective.
+///
+/// The delta of the budget is split evenly among the targets.
+/// Gaussian mechanisms are scaled by the noise their share of delta requires, as every other mechanism spends its epsilon alone.
+///
+/// # Returns
+/// The analysis, with the allocated privacy usage written into the options of each target,
+/// alongside the allocated usage and the expected error of each target.
+pub fn allocate_budget(
+    analysis: &proto::Analysis,
+    budget: &proto::PrivacyUsage,
+    targets: &[proto::AllocationTarget],
+    objective: proto::AllocationObjective,
+) -> Result<proto::BudgetAllocation> {
+    if targets.is_empty() {
+        return Err("targets: at least one node must be allocated a budget".into())
+    }
+    let epsilon = get_epsilon(budget).chain_err(|| "budget: must be a pure or approximate privacy usage")?;
+    if epsilon.is_nan() || epsilon <= 0. {
+        return Err("budget: epsilon must be positive".into())
+    }
+    let delta = match &budget.distance {
+        Some(proto::privacy_usage::Distance::Approximate(_)) => Some(get_delta(budget)? / targets.len() as f64),
+        _ => None
+    };
+
+    let mut graph = analysis.computation_graph.as_ref()
+        .ok_or_else(|| Error::from("computation_graph must be defined"))?.value.clone();
+
+    // coefficient c of the expected error c / epsilon of each target
+    let coefficients = targets.iter()
+        .map(|target| {
+            let component = graph.get(&target.node_id)
+                .ok_or_else(|| Error::from(format!("targets: node {} is not in the analysis", target.node_id)))?;
+            if !target.sensitivity.is_finite() || target.sensitivity <= 0. {
+                bail!("targets: the sensitivity of node {} must be positive", target.node_id)
+            }
+            Ok(target.sensitivity * match (&component.variant, delta) {
+                (Some(proto::component::Variant::GaussianMechanism(_)), Some(delta)) if delta > 0. =>
+                    (2. * (1.25 / delta).ln()).sqrt(),
+                (Some(proto::component::Variant::GaussianMechanism(_)), _) =>
+                    bail!("budget: the Gaussian mechanism at node {} requires a budget with positive delta", target.node_id),
+                _ => 1.
+            })
+        })
+        .collect::<Result<Vec<f64>>>()?;
+
+    let weights = coefficients.iter()
+        .map(|coefficient| match objective {
+            proto::AllocationObjective::TotalError => coefficient.sqrt(),
+            proto::AllocationObjective::MaxError => *coefficient
+        })
+        .collect::<Vec<f64>>();
+    let total_weight = weights.iter().sum::<f64>();
+
+    let mut privacy_usages = HashMap::new();
+    let mut expected_errors = HashMap::new();
+    for ((target, coefficient), weight) in targets.iter().zip(coefficients).zip(weights) {
+        let target_epsilon = epsilon * weight / total_weight;
+        let usage = proto::PrivacyUsage {
+            distance: Some(match delta {
+                Some(delta) => proto::privacy_usage::Distance::Approximate(
+                    proto::privacy_usage::DistanceApproximate { epsilon: target_epsilon, delta }),
+                None => proto::privacy_usage::Distance::Pure(
+                    proto::privacy_usage::DistancePure { epsilon: target_epsilon })
+            })
+        };
+
+        let component = graph.get_mut(&target.node_id).unwrap();
+        let variant = component.variant.as_mut()
+            .ok_or_else(|| Error::from("component variant must be defined"))?;
+        set_privacy_usage!(variant, vec![usage.clone()],
+            DpAnova, DpAny, DpAuc, DpCdf, DpChiSquareTest, DpConfusionMatrix, DpContingencyTable, DpCorrelation,
+            DpCount, DpCountDistinct, DpCountTrue, DpCovariance, DpDecisionTree, DpGini, DpGroupMoments, DpHistogram,
+            DpIqr, DpKMeans, DpKurtosis, DpLinearRegression, DpLogisticRegression, DpMarginals, DpMaximum, DpMean,
+            DpMeanAbsoluteDeviation, DpMedian, DpMinimum, DpMode, DpMomentRaw, DpNaiveBayes, DpPca, DpQuantiles,
+            DpRangeTree, DpSkewness, DpStabilityHistogram, DpSum, DpSyntheticData, DpTopK, DpVariance, DpWelchTTest,
+            ExtremeSelection, GaussianMechanism, LaplaceMechanism, ObjectivePerturbationMechanism, RebalancePartitions,
+            ReportNoisyMaxMechanism, SimpleGeometricMechanism, TopKMechanism);
+
+        privacy_usages.insert(target.node_id, usage);
+        expected_errors.insert(target.node_id, coefficient / target_epsilon);
+    }
+
+    Ok(proto::BudgetAllocation {
+        analysis: Some(proto::Analysis {
+            computation_graph: Some(proto::ComputationGraph { value: graph }),
+            ..analysis.clone()
+        }),
+        privacy_usages,
+        expected_errors,
+    })
+}
+
+
+#[cfg(test)]
+mod test_allocation {
+    use crate::proto;
+    use crate::hashmap;
+    use crate::utilities::allocation::allocate_budget;
+    use crate::utilities::get_epsilon;
+    use std::collections::HashMap;
+
+    fn laplace() -> proto::Component {
+        proto::Component {
+            arguments: HashMap::new(),
+            variant: Some(proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism { privacy_usage: Vec::new() })),
+            omit: false,
+            batch: 0
+        }
+    }
+
+    #[test]
+    fn test_allocate_budget() {
+        let analysis = proto::Analysis {
+            computation_graph: Some(proto::ComputationGraph { value: hashmap![1 => laplace(), 2 => laplace()] }),
+            ..Default::default()
+        };
+        let budget = proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Pure(proto::privacy_usage::DistancePure { epsilon: 3. }))
+        };
+        let targets = vec![
+            proto::AllocationTarget { node_id: 1, sensitivity: 1. },
+            proto::AllocationTarget { node_id: 2, sensitivity: 4. },
+        ];
+
+        // the total error is minimized at epsilons proportional to the square root of the sensitivity
+        let allocation = allocate_budget(&analysis, &budget, &targets, proto::AllocationObjective::TotalError).unwrap();
+        assert!((get_epsilon(allocation.privacy_usages.get(&1).unwrap()).unwrap() - 1.).abs() < 1e-12);
+        assert!((get_epsilon(allocation.privacy_usages.get(&2).unwrap()).unwrap() - 2.).abs() < 1e-12);
+        match &allocation.analysis.unwrap().computation_graph.unwrap().value.get(&2).unwrap().variant {
+            Some(proto::component::Variant::LaplaceMechanism(mechanism)) => assert_eq!(mechanism.privacy_usage.len(), 1),
+            _ => panic!("the target must remain a Laplace mechanism")
+        }
+
+        // the largest error is minimized when every error is equal
+        let allocation = allocate_budget(&analysis, &budget, &targets, proto::AllocationObjective::MaxError).unwrap();
+        assert!((allocation.expected_errors.get(&1).unwrap() - allocation.expected_errors.get(&2).unwrap()).abs() < 1e-12);
+
+        assert!(allocate_budget(&analysis, &budget, &[proto::AllocationTarget { node_id: 3, sensitivity: 1. }],
+                                proto::AllocationObjective::TotalError).is_err());
+    }
+}
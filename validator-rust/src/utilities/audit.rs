@@ -0,0 +1,208 @@
+//! Fixtures for empirically auditing the privacy guarantee of an analysis
+//!
+//! Each privatizing node is paired with neighboring datasets that differ by a single extreme record.
+//! The runtime may evaluate the node over many trials on both datasets, and check that the empirical divergence
+//! between the two output distributions does not exceed the privacy usage the node is charged.
+//! Passing an audit does not prove the guarantee, but failing one reveals a flaw in the sensitivity or the noise.
+
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::proto;
+use crate::base::{ValueProperties, ArrayProperties, AggregatorProperties, Value, Jagged, DataType, SensitivitySpace};
+use crate::components::Sensitivity;
+use crate::utilities::get_charged_privacy_usage;
+use crate::utilities::serial::serialize_value;
+
+use itertools::Itertools;
+use ndarray::{Array, ArrayD};
+
+/// Generate adversarial neighboring-dataset test cases for every privatizing node of an expanded computation graph.
+///
+/// The perturbed records are the corners of the bounding box of the data, or the first and last categories of each column,
+/// as these records move the aggregates the most.
+/// Under add/remove neighboring, each extreme record is added and removed.
+/// Under substitute neighboring, each extreme record is substituted for the opposite extreme.
+pub fn generate_audit_cases(
+    privacy_definition: &proto::PrivacyDefinition,
+    graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, ValueProperties>,
+    release: &proto::Release,
+) -> Result<proto::AuditCases> {
+    use proto::privacy_definition::Neighboring;
+    use proto::audit_case::Perturbation;
+
+    let neighboring = Neighboring::from_i32(privacy_definition.neighboring)
+        .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+
+    let mut cases = Vec::new();
+
+    for node_id in graph.keys().sorted() {
+        let max_divergence = match get_charged_privacy_usage(graph, node_id, release)? {
+            Some(usage) => usage,
+            None => continue
+        };
+        let component = graph.get(node_id).unwrap();
+
+        let data_property = component.arguments.get("data")
+            .and_then(|data_id| properties.get(data_id))
+            .and_then(|property| property.array().ok());
+
+        // aggregated data is audited against the records of the data that was aggregated
+        let (record_property, sensitivity) = match data_property {
+            Some(data_property) => match &data_property.aggregator {
+                Some(aggregator) => (
+                    aggregator.properties.get("data").and_then(|property| property.array().ok()),
+                    get_sensitivity(privacy_definition, component, aggregator)?),
+                None => (Some(data_property), Vec::new())
+            },
+            None => (None, Vec::new())
+        };
+
+        let records = match record_property {
+            Some(record_property) => get_extreme_records(record_property)?,
+            None => Vec::new()
+        };
+        if records.is_empty() {
+            // the runtime may still audit the node against an arbitrary record
+            cases.push(proto::AuditCase {
+                node_id: *node_id,
+                perturbation: match neighboring {
+                    Neighboring::AddRemove => Perturbation::Add,
+                    Neighboring::Substitute => Perturbation::Substitute
+                } as i32,
+                record: None,
+                replacement: None,
+                sensitivity,
+                max_divergence: Some(max_divergence)
+            });
+            continue
+        }
+
+        let perturbations = match neighboring {
+            Neighboring::AddRemove => records.iter()
+                .flat_map(|record| vec![
+                    (Perturbation::Add, record.clone(), None),
+                    (Perturbation::Remove, record.clone(), None)
+                ])
+                .collect::<Vec<_>>(),
+            Neighboring::Substitute => records.iter().zip(records.iter().rev())
+                .map(|(record, replacement)| (Perturbation::Substitute, record.clone(), Some(replacement.clone())))
+                .collect()
+        };
+
+        cases.extend(perturbations.into_iter()
+            .map(|(perturbation, record, replacement)| proto::AuditCase {
+                node_id: *node_id,
+                perturbation: perturbation as i32,
+                record: Some(record),
+                replacement,
+                sensitivity: sensitivity.clone(),
+                max_divergence: Some(max_divergence.clone())
+            }));
+    }
+
+    Ok(proto::AuditCases { cases })
+}
+
+/// Sensitivity of the aggregate under the norm the mechanism is calibrated to, if the mechanism adds noise to an aggregate.
+fn get_sensitivity(
+    privacy_definition: &proto::PrivacyDefinition,
+    mechanism: &proto::Component,
+    aggregator: &AggregatorProperties,
+) -> Result<Vec<f64>> {
+    use proto::component::Variant;
+    let sensitivity_type = match mechanism.variant {
+        Some(Variant::LaplaceMechanism(_)) | Some(Variant::SimpleGeometricMechanism(_)) => SensitivitySpace::KNorm(1),
+        Some(Variant::GaussianMechanism(_)) => SensitivitySpace::KNorm(2),
+        _ => return Ok(Vec::new())
+    };
+
+    let sensitivity = aggregator.component
+        .compute_sensitivity(privacy_definition, &aggregator.properties, &sensitivity_type)?;
+
+    Ok(match sensitivity.array()? {
+        crate::base::Array::F64(sensitivity) => sensitivity.iter().cloned().collect(),
+        crate::base::Array::I64(sensitivity) => sensitivity.iter().map(|v| *v as f64).collect(),
+        _ => return Err("sensitivity must be numeric".into())
+    })
+}
+
+/// The lower and upper corners of the bounding box of the data, or the records made of the first and last category of each column.
+///
+/// Each record has a single row. If the bounds or categories are not all known, no records are returned.
+fn get_extreme_records(property: &ArrayProperties) -> Result<Vec<proto::Value>> {
+    fn to_records<T: Clone>(extremes: Option<(Vec<T>, Vec<T>)>) -> Result<Option<Vec<Value>>>
+        where ArrayD<T>: Into<Value> {
+        let (lower, upper) = match extremes {
+            Some(extremes) => extremes,
+            None => return Ok(None)
+        };
+        Ok(Some(vec![
+            Array::from_shape_vec((1, lower.len()), lower)?.into_dyn().into(),
+            Array::from_shape_vec((1, upper.len()), upper)?.into_dyn().into()
+        ]))
+    }
+    fn category_extremes<T: Clone>(categories: &[Option<Vec<T>>]) -> Option<(Vec<T>, Vec<T>)> {
+        categories.iter()
+            .map(|column| column.as_ref().and_then(|column| column.first().cloned().zip(column.last().cloned())))
+            .collect::<Option<Vec<(T, T)>>>()
+            .map(|extremes| extremes.into_iter().unzip())
+    }
+
+    let records = match property.categories() {
+        Ok(Jagged::Bool(categories)) => to_records(category_extremes(&categories))?,
+        Ok(Jagged::I64(categories)) => to_records(category_extremes(&categories))?,
+        Ok(Jagged::F64(categories)) => to_records(category_extremes(&categories))?,
+        Ok(Jagged::Str(categories)) => to_records(category_extremes(&categories))?,
+        Err(_) => match property.data_type {
+            DataType::F64 => to_records(property.lower_f64().ok().zip(property.upper_f64().ok()))?,
+            DataType::I64 => to_records(property.lower_i64().ok().zip(property.upper_i64().ok()))?,
+            _ => None
+        }
+    }.unwrap_or_else(Vec::new);
+
+    records.iter().map(serialize_value).collect()
+}
+
+
+#[cfg(test)]
+mod test_audit {
+    use crate::base::{ArrayProperties, DataType, Nature, NatureContinuous, Vector1DNull};
+    use crate::utilities::audit::get_extreme_records;
+    use crate::utilities::serial::parse_value;
+
+    #[test]
+    fn test_extreme_records() {
+        let mut property = ArrayProperties {
+            num_records: None,
+            num_columns: Some(2),
+            nullity: false,
+            releasable: false,
+            public_shape: false,
+            public_metadata: false,
+            c_stability: vec![1., 1.],
+            aggregator: None,
+            nature: Some(Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::F64(vec![Some(0.), Some(-1.)]),
+                upper: Vector1DNull::F64(vec![Some(10.), Some(1.)]),
+            })),
+            data_type: DataType::F64,
+            dataset_id: None,
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 2
+        };
+
+        let records = get_extreme_records(&property).unwrap();
+        assert_eq!(records.len(), 2);
+        let upper = parse_value(&records[1]).unwrap().array().unwrap().f64().unwrap().clone();
+        assert_eq!(upper.shape(), &[1, 2]);
+        assert_eq!(upper.iter().cloned().collect::<Vec<f64>>(), vec![10., 1.]);
+
+        // unknown bounds yield no records
+        property.nature = None;
+        assert!(get_extreme_records(&property).unwrap().is_empty());
+    }
+}
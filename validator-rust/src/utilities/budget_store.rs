@@ -0,0 +1,226 @@
+//! Durable storage of the privacy budget consumed on each dataset
+//!
+//! An accountant shared between processes reads the usage of a dataset, adds the usage of a release,
+//! and writes the sum back only if no other process has charged the dataset in the meantime.
+
+use crate::errors::*;
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::{thread, time};
+
+use crate::proto;
+use crate::utilities::privacy_usage_reducer;
+
+/// Storage for the cumulative privacy usage of each dataset.
+pub trait BudgetStore {
+    /// Cumulative usage of the dataset, or None if nothing has been charged to it.
+    fn get(&self, dataset_id: &str) -> Result<Option<proto::PrivacyUsage>>;
+
+    /// Replace the usage of the dataset with `new` only if it is currently `expected`.
+    ///
+    /// Returns false, without modifying the store, if the usage was changed by someone else.
+    fn compare_and_swap(
+        &self,
+        dataset_id: &str,
+        expected: Option<&proto::PrivacyUsage>,
+        new: &proto::PrivacyUsage,
+    ) -> Result<bool>;
+}
+
+/// Add a usage to the cumulative usage of a dataset, retrying until no concurrent charge interferes.
+///
+/// Returns the new cumulative usage.
+pub fn charge(
+    store: &dyn BudgetStore,
+    dataset_id: &str,
+    usage: &proto::PrivacyUsage,
+) -> Result<proto::PrivacyUsage> {
+    loop {
+        let current = store.get(dataset_id)?;
+        let new = match &current {
+            Some(current) => privacy_usage_reducer(current, usage, &|l, r| l + r),
+            None => usage.clone()
+        };
+        if store.compare_and_swap(dataset_id, current.as_ref(), &new)? {
+            return Ok(new)
+        }
+    }
+}
+
+/// Budget state held in memory, shared between the threads of a single process.
+#[derive(Default)]
+pub struct MemoryBudgetStore {
+    usages: Mutex<HashMap<String, proto::PrivacyUsage>>,
+}
+
+impl BudgetStore for MemoryBudgetStore {
+    fn get(&self, dataset_id: &str) -> Result<Option<proto::PrivacyUsage>> {
+        let usages = self.usages.lock()
+            .map_err(|_| Error::from("budget store is poisoned"))?;
+        Ok(usages.get(dataset_id).cloned())
+    }
+
+    fn compare_and_swap(
+        &self,
+        dataset_id: &str,
+        expected: Option<&proto::PrivacyUsage>,
+        new: &proto::PrivacyUsage,
+    ) -> Result<bool> {
+        let mut usages = self.usages.lock()
+            .map_err(|_| Error::from("budget store is poisoned"))?;
+        if usages.get(dataset_id) != expected {
+            return Ok(false)
+        }
+        usages.insert(dataset_id.to_string(), new.clone());
+        Ok(true)
+    }
+}
+
+/// Budget state persisted as JSON in a single file, shared between processes on the same filesystem.
+///
+/// Writers hold a lock file beside the store while comparing and swapping.
+/// The new state is written to a temporary file and renamed over the store, so readers never see a partial write.
+pub struct FileBudgetStore {
+    path: PathBuf,
+}
+
+/// Number of attempts to take the lock before giving up, waiting LOCK_WAIT_MS between attempts.
+const LOCK_ATTEMPTS: u32 = 1000;
+const LOCK_WAIT_MS: u64 = 10;
+
+impl FileBudgetStore {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        FileBudgetStore { path: path.into() }
+    }
+
+    fn with_extension(&self, extension: &str) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(extension);
+        path.into()
+    }
+
+    fn read(&self) -> Result<HashMap<String, proto::PrivacyUsage>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(ref err) if err.kind() == ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(err) => return Err(format!("budget store: {}", err).into())
+        };
+        let parsed: HashMap<String, (f64, Option<f64>)> = serde_json::from_str(&contents)
+            .map_err(|err| Error::from(format!("budget store: {}", err)))?;
+
+        Ok(parsed.into_iter()
+            .map(|(dataset_id, (epsilon, delta))| (dataset_id, proto::PrivacyUsage {
+                distance: Some(match delta {
+                    Some(delta) => proto::privacy_usage::Distance::Approximate(
+                        proto::privacy_usage::DistanceApproximate { epsilon, delta }),
+                    None => proto::privacy_usage::Distance::Pure(
+                        proto::privacy_usage::DistancePure { epsilon })
+                })
+            }))
+            .collect())
+    }
+
+    fn write(&self, usages: &HashMap<String, proto::PrivacyUsage>) -> Result<()> {
+        let serialized = usages.iter()
+            .map(|(dataset_id, usage)| Ok((dataset_id.clone(), match &usage.distance {
+                Some(proto::privacy_usage::Distance::Pure(distance)) => (distance.epsilon, None),
+                Some(proto::privacy_usage::Distance::Approximate(distance)) => (distance.epsilon, Some(distance.delta)),
+                Some(proto::privacy_usage::Distance::Concentrated(_)) =>
+                    return Err("budget store: usages must be converted from zCDP to (epsilon, delta) before they are stored".into()),
+                None => return Err("distance must be defined on a PrivacyUsage".into())
+            })))
+            .collect::<Result<HashMap<String, (f64, Option<f64>)>>>()?;
+
+        let temporary = self.with_extension(".tmp");
+        fs::write(&temporary, serde_json::to_string(&serialized)
+            .map_err(|err| Error::from(format!("budget store: {}", err)))?)
+            .map_err(|err| Error::from(format!("budget store: {}", err)))?;
+        fs::rename(&temporary, &self.path)
+            .map_err(|err| Error::from(format!("budget store: {}", err)))?;
+        Ok(())
+    }
+
+    /// Take the lock file, run the closure, and release the lock.
+    fn locked<T>(&self, function: impl FnOnce() -> Result<T>) -> Result<T> {
+        let lock = self.with_extension(".lock");
+        let mut attempts = 0;
+        while let Err(err) = OpenOptions::new().write(true).create_new(true).open(&lock) {
+            attempts += 1;
+            if err.kind() != ErrorKind::AlreadyExists || attempts >= LOCK_ATTEMPTS {
+                return Err(format!("budget store: unable to lock {:?}: {}", lock, err).into())
+            }
+            thread::sleep(time::Duration::from_millis(LOCK_WAIT_MS));
+        }
+        let result = function();
+        fs::remove_file(&lock)
+            .map_err(|err| Error::from(format!("budget store: unable to unlock {:?}: {}", lock, err)))?;
+        result
+    }
+}
+
+impl BudgetStore for FileBudgetStore {
+    fn get(&self, dataset_id: &str) -> Result<Option<proto::PrivacyUsage>> {
+        Ok(self.read()?.remove(dataset_id))
+    }
+
+    fn compare_and_swap(
+        &self,
+        dataset_id: &str,
+        expected: Option<&proto::PrivacyUsage>,
+        new: &proto::PrivacyUsage,
+    ) -> Result<bool> {
+        self.locked(|| {
+            let mut usages = self.read()?;
+            if usages.get(dataset_id) != expected {
+                return Ok(false)
+            }
+            usages.insert(dataset_id.to_string(), new.clone());
+            self.write(&usages)?;
+            Ok(true)
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod test_budget_store {
+    use crate::proto;
+    use crate::utilities::budget_store::{BudgetStore, MemoryBudgetStore, FileBudgetStore, charge};
+
+    fn approximate(epsilon: f64, delta: f64) -> proto::PrivacyUsage {
+        proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(
+                proto::privacy_usage::DistanceApproximate { epsilon, delta }))
+        }
+    }
+
+    fn check_store(store: &dyn BudgetStore) {
+        assert_eq!(store.get("a").unwrap(), None);
+        charge(store, "a", &approximate(0.5, 1e-6)).unwrap();
+        charge(store, "a", &approximate(0.25, 1e-6)).unwrap();
+        assert_eq!(store.get("a").unwrap(), Some(approximate(0.75, 2e-6)));
+
+        // a stale expectation is rejected
+        assert!(!store.compare_and_swap("a", None, &approximate(1., 0.)).unwrap());
+        assert_eq!(store.get("b").unwrap(), None);
+    }
+
+    #[test]
+    fn test_memory_store() {
+        check_store(&MemoryBudgetStore::default());
+    }
+
+    #[test]
+    fn test_file_store() {
+        let path = std::env::temp_dir().join(format!("whitenoise_budget_{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        check_store(&FileBudgetStore::new(path.clone()));
+        // the state persists across instances
+        assert!(FileBudgetStore::new(path.clone()).get("a").unwrap().is_some());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,622 @@
+//! Accountants for the composition of the mechanisms of an analysis
+//!
+//! Summing the (epsilon, delta) of every mechanism is always valid, but overcounts when many mechanisms are composed.
+//! Each accountant here bounds the same composition differently, and hybrid accounting keeps the tightest bound.
+//! Every accountant but basic composition is evaluated at a common total delta, so that the bounds are comparable.
+//! When an analysis mixes families of mechanisms, each family may be bounded by a different accountant, and the bounds summed.
+
+use crate::errors::*;
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::proto;
+use crate::utilities::{get_epsilon, get_delta};
+use crate::utilities::accounting::node_usages;
+use crate::utilities::external::{ExternalMechanism, get_count, invocation_usage};
+use crate::utilities::json::privacy_usage_to_json;
+use crate::utilities::privacy::RenyiCurve;
+use crate::utilities::tradeoff::gaussian_epsilon;
+
+use itertools::Itertools;
+use statrs::function::erf;
+use statrs::function::factorial::ln_binomial;
+
+/// Noise distribution of a mechanism, as a ratio of the scale of the noise to the sensitivity of the query.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Noise {
+    Laplace(f64),
+    Gaussian(f64),
+    /// steps of a Gaussian mechanism, each over a Poisson sample of the records
+    SubsampledGaussian { noise_multiplier: f64, sampling_rate: f64, steps: f64 },
+    /// only the (epsilon, delta) of the mechanism is known
+    Opaque,
+}
+
+/// Invocations of a mechanism, each of which satisfies (epsilon, delta)-DP.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Invocation {
+    pub epsilon: f64,
+    pub delta: f64,
+    pub noise: Noise,
+    pub count: f64,
+}
+
+impl Invocation {
+    fn new(usage: &proto::PrivacyUsage, noise: Noise) -> Result<Invocation> {
+        Ok(Invocation {
+            epsilon: get_epsilon(usage)?,
+            delta: get_delta(usage).unwrap_or(0.),
+            noise,
+            count: 1.,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Accountant {
+    /// sum of the epsilons and deltas
+    Basic,
+    /// advanced composition theorem, for heterogeneous mechanisms
+    Advanced,
+    /// Renyi differential privacy, minimized over a grid of orders
+    Renyi,
+    /// zero-concentrated differential privacy
+    Zcdp,
+    /// optimal composition theorem, exact for mechanisms whose noise is not known
+    Optimal,
+    /// Gaussian differential privacy, exact for Gaussian mechanisms
+    Gdp,
+}
+
+/// Accountants evaluated by hybrid accounting. Ties are broken in this order.
+pub const ACCOUNTANTS: [Accountant; 6] = [Accountant::Basic, Accountant::Advanced, Accountant::Renyi, Accountant::Zcdp, Accountant::Optimal, Accountant::Gdp];
+
+/// Optimal composition enumerates every number of invocations of each distinct epsilon that may be in the privacy loss tail.
+/// Beyond this many enumerations, the invocations are bounded by the largest epsilon instead.
+const MAX_OPTIMAL_ENUMERATIONS: f64 = 1e5;
+
+impl Accountant {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Accountant::Basic => "basic",
+            Accountant::Advanced => "advanced",
+            Accountant::Renyi => "renyi",
+            Accountant::Zcdp => "zcdp",
+            Accountant::Optimal => "optimal",
+            Accountant::Gdp => "gdp"
+        }
+    }
+
+    /// Epsilon and delta of the composition of the invocations.
+    ///
+    /// Every accountant but basic composition spends the slack between `delta` and the delta of its mechanisms.
+    /// An error describes why the accountant does not apply.
+    pub fn compose(&self, invocations: &[Invocation], delta: f64) -> Result<(f64, f64)> {
+        let slack = |mechanism_delta: f64| match delta - mechanism_delta {
+            slack if slack > 0. => Ok(slack),
+            _ => Err(Error::from("the delta of the mechanisms leaves no slack for the accountant"))
+        };
+
+        Ok(match self {
+            Accountant::Basic => (
+                invocations.iter().map(|invocation| invocation.count * invocation.epsilon).sum(),
+                invocations.iter().map(|invocation| invocation.count * invocation.delta).sum()),
+
+            // Kairouz, Oh and Viswanath. The Composition Theorem for Differential Privacy. ICML 2015, Theorem 3.5
+            Accountant::Advanced => {
+                let slack = slack(invocations.iter().map(|invocation| invocation.count * invocation.delta).sum())?;
+                let expected: f64 = invocations.iter()
+                    .map(|invocation| invocation.count * invocation.epsilon * (invocation.epsilon / 2.).tanh())
+                    .sum();
+                let squared: f64 = invocations.iter()
+                    .map(|invocation| invocation.count * invocation.epsilon.powi(2))
+                    .sum();
+                (expected + (2. * (1. / slack).ln() * squared).sqrt(), delta)
+            },
+
+            // Mironov. Renyi Differential Privacy. CSF 2017, Proposition 3
+            Accountant::Renyi => {
+                let slack = slack(opaque_delta(invocations))?;
+                (renyi_curve(invocations).epsilon(slack)?, delta)
+            },
+
+            // Bun and Steinke. Concentrated Differential Privacy. TCC 2016, Proposition 1.3
+            Accountant::Zcdp => {
+                let slack = slack(opaque_delta(invocations))?;
+                let rho: f64 = invocations.iter()
+                    .map(|invocation| invocation.count * match invocation.noise {
+                        Noise::Gaussian(noise_multiplier) => 1. / (2. * noise_multiplier.powi(2)),
+                        // subsampling is postprocessing of the Gaussian mechanism over all records, which bounds its rho
+                        Noise::SubsampledGaussian { noise_multiplier, steps, .. } => steps / (2. * noise_multiplier.powi(2)),
+                        _ => invocation.epsilon.powi(2) / 2.
+                    })
+                    .sum();
+                (rho + 2. * (rho * (1. / slack).ln()).sqrt(), delta)
+            },
+
+            // Kairouz, Oh and Viswanath. The Composition Theorem for Differential Privacy. ICML 2015, Theorem 3.3,
+            // for heterogeneous mechanisms as in Murtagh and Vadhan. The Complexity of Computing the Optimal Composition of Differential Privacy. TCC 2016, Theorem 1.5
+            Accountant::Optimal => {
+                let mechanism_delta = 1. - invocations.iter()
+                    .map(|invocation| (1. - invocation.delta).powf(invocation.count))
+                    .product::<f64>();
+                slack(mechanism_delta)?;
+                // the composition fails with probability delta if any mechanism fails, or the privacy loss of the epsilons exceeds epsilon
+                let epsilon_delta = 1. - (1. - delta) / (1. - mechanism_delta);
+                (optimal_epsilon(&epsilon_groups(invocations), epsilon_delta), delta)
+            },
+
+            // Dong, Roth and Su. Gaussian Differential Privacy. JRSS-B 2022, Corollary 2.13.
+            // The composition is mu-GDP, which is the privacy profile of a Gaussian mechanism with noise multiplier 1 / mu
+            Accountant::Gdp => {
+                let mu = gdp_mu(invocations)?;
+                if delta <= 0. {
+                    return Err("a Gaussian differential privacy guarantee only implies approximate differential privacy".into())
+                }
+                (match mu > 0. {
+                    true => gaussian_epsilon(1. / mu, delta),
+                    false => 0.
+                }, delta)
+            }
+        })
+    }
+}
+
+/// Delta of the invocations whose noise is not known.
+///
+/// An (epsilon, delta)-DP mechanism is delta-approximately (epsilon^2 / 2)-zCDP, so its delta is added to the total.
+fn opaque_delta(invocations: &[Invocation]) -> f64 {
+    invocations.iter()
+        .filter(|invocation| invocation.noise == Noise::Opaque)
+        .map(|invocation| invocation.count * invocation.delta)
+        .sum()
+}
+
+/// Mu of the Gaussian differential privacy of the composition of the invocations.
+///
+/// Gaussian mechanisms are `1 / noise_multiplier`-GDP, and the squared mus of composed mechanisms add.
+/// A pure epsilon-DP mechanism is `2 Phi^-1(e^epsilon / (1 + e^epsilon))`-GDP, as its trade-off function dominates that of the Gaussian through the same fixed point.
+/// Mechanisms with delta and unknown noise have no GDP guarantee.
+pub fn gdp_mu(invocations: &[Invocation]) -> Result<f64> {
+    if invocations.is_empty() {
+        return Err("no information is released; privacy usage is none".into())
+    }
+    let mu = invocations.iter()
+        .map(|invocation| Ok(invocation.count * match invocation.noise {
+            Noise::Gaussian(noise_multiplier) => noise_multiplier.powi(-2),
+            // subsampling is postprocessing of the Gaussian mechanism over all records, which bounds its mu
+            Noise::SubsampledGaussian { noise_multiplier, steps, .. } => steps * noise_multiplier.powi(-2),
+            _ if invocation.delta > 0. =>
+                return Err("mechanisms that spend delta have no Gaussian differential privacy guarantee, unless they are Gaussian".into()),
+            // Phi^-1(e^epsilon / (1 + e^epsilon)) = sqrt(2) erf^-1(tanh(epsilon / 2))
+            _ => 8. * erf::erf_inv((invocation.epsilon / 2.).tanh()).powi(2)
+        }))
+        .collect::<Result<Vec<f64>>>()?
+        .into_iter().sum::<f64>().sqrt();
+
+    if !mu.is_finite() {
+        return Err("the mechanisms are too weak to have a Gaussian differential privacy guarantee".into())
+    }
+    Ok(mu)
+}
+
+/// Distinct epsilons of the invocations, with the number of invocations of each.
+///
+/// Fractional counts are rounded up, as composing more invocations is conservative.
+/// If there are too many combinations of counts to enumerate, every invocation is bounded by the largest epsilon.
+fn epsilon_groups(invocations: &[Invocation]) -> Vec<(f64, u64)> {
+    let mut groups = BTreeMap::<u64, u64>::new();
+    invocations.iter()
+        .filter(|invocation| invocation.epsilon > 0.)
+        .for_each(|invocation| *groups.entry(invocation.epsilon.to_bits()).or_insert(0) += invocation.count.ceil() as u64);
+    let groups = groups.into_iter()
+        .map(|(epsilon, count)| (f64::from_bits(epsilon), count))
+        .collect::<Vec<(f64, u64)>>();
+
+    let enumerations = groups.iter().map(|(_, count)| *count as f64 + 1.).product::<f64>();
+    match enumerations > MAX_OPTIMAL_ENUMERATIONS {
+        true => vec![(
+            groups.iter().map(|(epsilon, _)| *epsilon).fold(0., f64::max),
+            groups.iter().map(|(_, count)| count).sum())],
+        false => groups
+    }
+}
+
+/// Smallest delta for which the composition of pure mechanisms with the given epsilons is (epsilon, delta)-DP.
+///
+/// `delta(epsilon) = sum over subsets S of max(e^(eps(S)) - e^epsilon e^(eps(not S)), 0) / prod(1 + e^eps_i)`,
+/// where subsets are enumerated by the number of invocations of each distinct epsilon in S, and terms are summed in log-space.
+pub fn optimal_delta(groups: &[(f64, u64)], epsilon: f64) -> f64 {
+    let softplus = |x: f64| x.max(0.) + (-x.abs()).exp().ln_1p();
+    let log_normalizer = groups.iter().map(|(group_epsilon, count)| *count as f64 * softplus(*group_epsilon)).sum::<f64>();
+    let total_epsilon = groups.iter().map(|(group_epsilon, count)| *count as f64 * group_epsilon).sum::<f64>();
+
+    let mut delta = 0.;
+    let mut counts = vec![0u64; groups.len()];
+    loop {
+        let included = groups.iter().zip(counts.iter())
+            .map(|((group_epsilon, _), count)| *count as f64 * group_epsilon).sum::<f64>();
+        let excluded = total_epsilon - included;
+        if included - excluded > epsilon {
+            let log_multiplicity = groups.iter().zip(counts.iter())
+                .map(|((_, group_count), count)| ln_binomial(*group_count, *count)).sum::<f64>();
+            delta += (log_multiplicity + included - log_normalizer + (-(epsilon + excluded - included).exp()).ln_1p()).exp();
+        }
+
+        // advance to the next combination of counts
+        let mut index = 0;
+        loop {
+            if index == groups.len() {
+                return delta.min(1.)
+            }
+            if counts[index] < groups[index].1 {
+                counts[index] += 1;
+                break
+            }
+            counts[index] = 0;
+            index += 1;
+        }
+    }
+}
+
+/// Smallest epsilon for which the composition of pure mechanisms with the given epsilons is (epsilon, delta)-DP.
+///
+/// The smallest delta is decreasing in epsilon, and zero at the sum of the epsilons, so epsilon is found by bisection.
+pub fn optimal_epsilon(groups: &[(f64, u64)], delta: f64) -> f64 {
+    let mut upper = groups.iter().map(|(group_epsilon, count)| *count as f64 * group_epsilon).sum::<f64>();
+    let mut lower = 0.;
+    if optimal_delta(groups, lower) <= delta {
+        return lower
+    }
+    (0..100).for_each(|_| {
+        let middle = (lower + upper) / 2.;
+        match optimal_delta(groups, middle) > delta {
+            true => lower = middle,
+            false => upper = middle
+        }
+    });
+    upper
+}
+
+/// Renyi curve of the composition of the invocations.
+///
+/// The delta of invocations whose noise is not known is not captured by the curve, and must be spent separately.
+pub fn renyi_curve(invocations: &[Invocation]) -> RenyiCurve {
+    invocations.iter()
+        .map(|invocation| match invocation.noise {
+            Noise::Gaussian(noise_multiplier) => RenyiCurve::gaussian(noise_multiplier),
+            Noise::SubsampledGaussian { noise_multiplier, sampling_rate, steps } =>
+                RenyiCurve::subsampled_gaussian(noise_multiplier, sampling_rate).repeat(steps),
+            Noise::Laplace(noise_multiplier) => RenyiCurve::laplace(noise_multiplier),
+            Noise::Opaque => RenyiCurve::pure(invocation.epsilon)
+        }.repeat(invocation.count))
+        .fold(RenyiCurve::zero(), |total, curve| total.compose(&curve))
+}
+
+/// Invocations of every privatizing node of an expanded computation graph, and of the external usages.
+pub fn graph_invocations(
+    graph: &HashMap<u32, proto::Component>,
+    release: &proto::Release,
+    external_usages: &[proto::ExternalUsage],
+) -> Result<Vec<Invocation>> {
+    use proto::component::Variant;
+
+    let mut invocations = Vec::new();
+    for node_id in graph.keys().sorted() {
+        let (component, usages) = match (graph.get(node_id), node_usages(graph, node_id, release)?) {
+            (Some(component), Some(usages)) => (component, usages),
+            _ => continue
+        };
+        for usage in &usages {
+            let epsilon = get_epsilon(usage)?;
+            invocations.push(match component.variant.as_ref() {
+                Some(Variant::LaplaceMechanism(_)) | Some(Variant::DpRangeTree(_)) | Some(Variant::DpAuc(_)) =>
+                    Invocation::new(usage, Noise::Laplace(1. / epsilon))?,
+                Some(Variant::GaussianMechanism(_)) =>
+                    Invocation::new(usage, Noise::Gaussian((2. * (1.25 / get_delta(usage)?).ln()).sqrt() / epsilon))?,
+                Some(Variant::DpStochasticGradientDescent(sgd)) => Invocation::new(usage, Noise::SubsampledGaussian {
+                    noise_multiplier: sgd.noise_multiplier,
+                    sampling_rate: sgd.sampling_rate,
+                    steps: sgd.steps as f64,
+                })?,
+                // sampling is not modeled, so the charged usage of other subsampled mechanisms is composed as-is
+                _ => Invocation::new(usage, Noise::Opaque)?
+            })
+        }
+    }
+
+    for external_usage in external_usages {
+        let usage = invocation_usage(external_usage)?;
+        let mut invocation = Invocation::new(&usage, match ExternalMechanism::parse(&external_usage.mechanism)? {
+            ExternalMechanism::Laplace => Noise::Laplace(external_usage.noise_multiplier),
+            ExternalMechanism::Gaussian => Noise::Gaussian(external_usage.noise_multiplier),
+            ExternalMechanism::EpsilonDelta => Noise::Opaque
+        })?;
+        invocation.count = get_count(external_usage) as f64;
+        invocations.push(invocation);
+    }
+    Ok(invocations)
+}
+
+/// Evaluate every accountant on the invocations, and select the tightest bound.
+///
+/// Accountants are compared at `delta`, or at the delta of basic composition if `delta` is zero.
+/// Basic composition always applies, so a bound is always selected.
+/// When the mechanisms are of several families of noise, each family is also bounded by its own tightest accountant,
+/// and the family bounds are summed into the "heterogeneous" bound.
+pub fn hybrid_accounting(invocations: &[Invocation], delta: f64) -> Result<proto::AccountantBounds> {
+    if invocations.is_empty() {
+        return Err("no information is released; privacy usage is none".into())
+    }
+    let (_, basic_delta) = Accountant::Basic.compose(invocations, 0.)?;
+    let delta = match delta {
+        delta if delta == 0. => basic_delta,
+        delta if delta.is_finite() && basic_delta <= delta && delta < 1. => delta,
+        _ => bail!("delta: must be at least the delta of basic composition, {}, and less than one", basic_delta)
+    };
+
+    let to_bound = |name: &str, composed: Result<(f64, f64)>| match composed {
+        Ok((epsilon, delta)) => proto::AccountantBound {
+            accountant: name.to_string(),
+            privacy_usage: Some(to_usage(epsilon, delta)),
+            reason: "".to_string(),
+        },
+        Err(err) => proto::AccountantBound {
+            accountant: name.to_string(),
+            privacy_usage: None,
+            reason: err.to_string(),
+        }
+    };
+
+    let mut bounds = ACCOUNTANTS.iter()
+        .map(|accountant| to_bound(accountant.name(), accountant.compose(invocations, delta)))
+        .collect::<Vec<proto::AccountantBound>>();
+
+    // each family of mechanisms may be bounded by a different accountant
+    let (families, heterogeneous) = match family_bounds(invocations, delta) {
+        Ok((families, total)) => (families, Ok(total)),
+        Err(err) => (Vec::new(), Err(err))
+    };
+    bounds.push(to_bound("heterogeneous", heterogeneous));
+
+    let selected = bounds.iter()
+        .filter_map(|bound| Some((bound, get_epsilon(bound.privacy_usage.as_ref()?).ok()?)))
+        .filter(|(_, epsilon)| epsilon.is_finite())
+        .fold1(|best, candidate| if candidate.1 < best.1 { candidate } else { best })
+        .map(|(bound, _)| bound.accountant.clone())
+        .ok_or_else(|| Error::from("no accountant produced a finite bound"))?;
+
+    Ok(proto::AccountantBounds { selected, bounds, families })
+}
+
+/// Family of the noise distribution of a mechanism. Mechanisms of a family are composed by the same accountant.
+fn family(noise: &Noise) -> &'static str {
+    match noise {
+        Noise::Laplace(_) => "laplace",
+        Noise::Gaussian(_) => "gaussian",
+        Noise::SubsampledGaussian { .. } => "subsampled_gaussian",
+        Noise::Opaque => "opaque"
+    }
+}
+
+/// Bound each family of mechanisms with its tightest accountant, to be composed across families by summation.
+///
+/// The slack between `delta` and the delta of the mechanisms is divided evenly among the families.
+/// Only applies to mechanisms of at least two families, as a single family is already bounded by every accountant.
+///
+/// # Returns
+/// The bound of each family, and the (epsilon, delta) of their sum.
+fn family_bounds(invocations: &[Invocation], delta: f64) -> Result<(Vec<proto::FamilyBound>, (f64, f64))> {
+    let families = invocations.iter()
+        .map(|invocation| (family(&invocation.noise), invocation.clone()))
+        .into_group_map();
+    if families.len() < 2 {
+        return Err("the mechanisms are of a single family, which is bounded by every other accountant".into())
+    }
+
+    let (_, basic_delta) = Accountant::Basic.compose(invocations, 0.)?;
+    let slack = (delta - basic_delta).max(0.) / families.len() as f64;
+
+    let mut total = (0., 0.);
+    let bounds = families.into_iter()
+        .sorted_by_key(|(name, _)| *name)
+        .map(|(name, invocations)| {
+            let (_, family_delta) = Accountant::Basic.compose(&invocations, 0.)?;
+            let (accountant, (epsilon, delta)) = ACCOUNTANTS.iter()
+                .filter_map(|accountant| Some((accountant, accountant.compose(&invocations, family_delta + slack).ok()?)))
+                .filter(|(_, (epsilon, _))| epsilon.is_finite())
+                .fold1(|best, candidate| if (candidate.1).0 < (best.1).0 { candidate } else { best })
+                .ok_or_else(|| Error::from(format!("no accountant produced a finite bound for the {} mechanisms", name)))?;
+            total = (total.0 + epsilon, total.1 + delta);
+            Ok(proto::FamilyBound {
+                family: name.to_string(),
+                accountant: accountant.name().to_string(),
+                privacy_usage: Some(to_usage(epsilon, delta)),
+            })
+        })
+        .collect::<Result<Vec<proto::FamilyBound>>>()?;
+    Ok((bounds, total))
+}
+
+/// A pure usage if delta is zero, else an approximate usage.
+fn to_usage(epsilon: f64, delta: f64) -> proto::PrivacyUsage {
+    match delta {
+        delta if delta == 0. => proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Pure(proto::privacy_usage::DistancePure { epsilon }))
+        },
+        delta => proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate { epsilon, delta }))
+        }
+    }
+}
+
+/// Total privacy usage of the invocations under the advanced composition theorem, at a total `delta`.
+///
+/// Advanced composition only improves on basic composition once many mechanisms are composed,
+/// and basic composition remains valid at any larger delta, so the smaller of the two epsilons is returned.
+pub fn advanced_composition(invocations: &[Invocation], delta: f64) -> Result<proto::PrivacyUsage> {
+    compose_at_delta(Accountant::Advanced, invocations, delta)
+}
+
+/// Total privacy usage of the invocations under the optimal composition theorem, at a total `delta`.
+///
+/// The bound is exact for mechanisms whose noise is unknown, so it is never looser than basic or advanced composition.
+pub fn optimal_composition(invocations: &[Invocation], delta: f64) -> Result<proto::PrivacyUsage> {
+    compose_at_delta(Accountant::Optimal, invocations, delta)
+}
+
+/// Total privacy usage of the invocations under Gaussian differential privacy, converted to (epsilon, delta)-DP at a total `delta`.
+///
+/// The conversion from mu-GDP is exact, so the delta of the mechanisms needs no slack.
+pub fn gdp_composition(invocations: &[Invocation], delta: f64) -> Result<proto::PrivacyUsage> {
+    compose_at_delta(Accountant::Gdp, invocations, delta)
+}
+
+fn compose_at_delta(accountant: Accountant, invocations: &[Invocation], delta: f64) -> Result<proto::PrivacyUsage> {
+    if invocations.is_empty() {
+        return Err("no information is released; privacy usage is none".into())
+    }
+    if delta.is_nan() || delta <= 0. || delta >= 1. {
+        bail!("composition_delta: must be within (0, 1) under {} composition", accountant.name())
+    }
+    let (basic_epsilon, _) = Accountant::Basic.compose(invocations, 0.)?;
+    let (epsilon, delta) = accountant.compose(invocations, delta)
+        .chain_err(|| format!("composition_delta: {} composition does not apply at this delta", accountant.name()))?;
+
+    Ok(proto::PrivacyUsage {
+        distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+            epsilon: basic_epsilon.min(epsilon),
+            delta,
+        }))
+    })
+}
+
+/// Bounds of every accountant, as disclosed in the report.
+pub fn accountant_bounds_to_json(bounds: &proto::AccountantBounds) -> serde_json::Value {
+    serde_json::json!({
+        "selected": bounds.selected,
+        "bounds": bounds.bounds.iter()
+            .map(|bound| serde_json::json!({
+                "accountant": bound.accountant,
+                "privacyLoss": bound.privacy_usage.as_ref().map(privacy_usage_to_json),
+                "reason": bound.reason
+            }))
+            .collect::<Vec<serde_json::Value>>(),
+        "families": bounds.families.iter()
+            .map(|family| serde_json::json!({
+                "family": family.family,
+                "accountant": family.accountant,
+                "privacyLoss": family.privacy_usage.as_ref().map(privacy_usage_to_json)
+            }))
+            .collect::<Vec<serde_json::Value>>()
+    })
+}
+
+
+#[cfg(test)]
+mod test_composition {
+    use crate::utilities::composition::{Accountant, Invocation, Noise, hybrid_accounting, advanced_composition, optimal_composition, optimal_delta, gdp_composition, gdp_mu};
+    use crate::utilities::{get_epsilon, get_delta};
+
+    fn gaussian(noise_multiplier: f64, count: f64) -> Invocation {
+        Invocation {
+            epsilon: (2. * (1.25 / 1e-7_f64).ln()).sqrt() / noise_multiplier,
+            delta: 1e-7,
+            noise: Noise::Gaussian(noise_multiplier),
+            count,
+        }
+    }
+
+    #[test]
+    fn test_hybrid_accounting() {
+        // many gaussian releases are badly overcounted by basic composition
+        let invocations = vec![gaussian(20., 100.)];
+        let bounds = hybrid_accounting(&invocations, 1e-4).unwrap();
+        assert_ne!(bounds.selected, "basic");
+
+        let (basic, _) = Accountant::Basic.compose(&invocations, 1e-4).unwrap();
+        let (renyi, _) = Accountant::Renyi.compose(&invocations, 1e-4).unwrap();
+        assert!(renyi < basic);
+
+        // a single pure mechanism has no delta to spend
+        let laplace = vec![Invocation { epsilon: 1., delta: 0., noise: Noise::Laplace(1.), count: 1. }];
+        let bounds = hybrid_accounting(&laplace, 0.).unwrap();
+        assert_eq!(bounds.selected, "basic");
+        assert!(bounds.bounds.iter().filter(|bound| bound.accountant != "basic").all(|bound| bound.privacy_usage.is_none()));
+
+        // the common delta may not be smaller than the delta already spent
+        assert!(hybrid_accounting(&invocations, 1e-6).is_err());
+    }
+
+    #[test]
+    fn test_heterogeneous_accounting() {
+        // gaussian releases are bounded tightly in mu, and a single laplace release by its sum
+        let laplace = Invocation { epsilon: 1., delta: 0., noise: Noise::Laplace(1.), count: 1. };
+        let invocations = vec![gaussian(20., 100.), laplace];
+        let bounds = hybrid_accounting(&invocations, 1e-4).unwrap();
+
+        assert_eq!(bounds.families.iter().map(|family| family.family.as_str()).collect::<Vec<&str>>(), vec!["gaussian", "laplace"]);
+        assert_ne!(bounds.families[0].accountant, "basic");
+        let heterogeneous = bounds.bounds.iter().find(|bound| bound.accountant == "heterogeneous").unwrap();
+        assert!(get_delta(heterogeneous.privacy_usage.as_ref().unwrap()).unwrap() <= 1e-4 * (1. + 1e-9));
+    }
+
+    #[test]
+    fn test_advanced_composition() {
+        // many small releases compose to less than their sum
+        let laplace = vec![Invocation { epsilon: 0.01, delta: 0., noise: Noise::Laplace(100.), count: 1000. }];
+        let usage = advanced_composition(&laplace, 1e-6).unwrap();
+        assert!(get_epsilon(&usage).unwrap() < 10.);
+        assert_eq!(get_delta(&usage).unwrap(), 1e-6);
+
+        // a single release is bounded no worse than by basic composition
+        let laplace = vec![Invocation { epsilon: 1., delta: 0., noise: Noise::Laplace(1.), count: 1. }];
+        assert_eq!(get_epsilon(&advanced_composition(&laplace, 1e-6).unwrap()).unwrap(), 1.);
+
+        // the total delta must leave slack over the delta of the mechanisms
+        assert!(advanced_composition(&[gaussian(20., 20.)], 1e-6).is_err());
+        assert!(advanced_composition(&laplace, 0.).is_err());
+    }
+
+    #[test]
+    fn test_optimal_composition() {
+        let laplace = |epsilon: f64, count: f64| Invocation { epsilon, delta: 0., noise: Noise::Laplace(1. / epsilon), count };
+
+        // homogeneous composition is tighter than both basic and advanced composition
+        let homogeneous = vec![laplace(0.1, 100.)];
+        let optimal = get_epsilon(&optimal_composition(&homogeneous, 1e-6).unwrap()).unwrap();
+        let advanced = get_epsilon(&advanced_composition(&homogeneous, 1e-6).unwrap()).unwrap();
+        assert!(optimal < advanced && advanced < 10.);
+        assert!((optimal_delta(&[(0.1, 100)], optimal) - 1e-6).abs() < 1e-9);
+
+        // as is heterogeneous composition
+        let heterogeneous = vec![laplace(0.1, 50.), laplace(0.2, 30.)];
+        let optimal = get_epsilon(&optimal_composition(&heterogeneous, 1e-6).unwrap()).unwrap();
+        let advanced = get_epsilon(&advanced_composition(&heterogeneous, 1e-6).unwrap()).unwrap();
+        assert!(optimal < advanced && advanced < 11.);
+
+        // a single mechanism gains almost nothing from the slack
+        let single = get_epsilon(&optimal_composition(&[laplace(1., 1.)], 1e-6).unwrap()).unwrap();
+        assert!(single > 0.99 && single <= 1.);
+
+        // the mechanisms' own delta must leave slack
+        assert!(optimal_composition(&[gaussian(20., 20.)], 1e-6).is_err());
+    }
+
+    #[test]
+    fn test_gdp_composition() {
+        // gaussian mechanisms compose exactly in mu
+        let invocations = vec![gaussian(20., 100.)];
+        assert!((gdp_mu(&invocations).unwrap() - 0.5).abs() < 1e-12);
+        let gdp = get_epsilon(&gdp_composition(&invocations, 1e-4).unwrap()).unwrap();
+        let (renyi, _) = Accountant::Renyi.compose(&invocations, 1e-4).unwrap();
+        assert!(gdp < renyi);
+
+        // pure mechanisms are GDP, but mechanisms with delta and unknown noise are not
+        let laplace = Invocation { epsilon: 1., delta: 0., noise: Noise::Laplace(1.), count: 1. };
+        assert!(gdp_mu(&[laplace.clone()]).unwrap() > 0.);
+        let opaque = Invocation { epsilon: 1., delta: 1e-6, noise: Noise::Opaque, count: 1. };
+        assert!(gdp_mu(&[laplace, opaque]).is_err());
+        assert!(gdp_composition(&invocations, 0.).is_err());
+    }
+}
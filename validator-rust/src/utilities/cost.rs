@@ -0,0 +1,158 @@
+//! Rough estimates of the computational cost of evaluating an analysis
+//!
+//! The estimates are meant for queueing and limiting submissions before execution, not for precise accounting.
+//! Each component variant is assigned a cost model, scaled by the number of records and columns of its largest argument.
+
+use crate::errors::*;
+
+use std::collections::{HashMap, BTreeMap};
+
+use crate::proto;
+use crate::base::ValueProperties;
+use crate::utilities::get_traversal;
+
+/// Floating point operations to securely sample one noise value.
+const NOISE_FLOPS: f64 = 1000.;
+
+/// Newton iterations budgeted for minimizing a perturbed objective.
+const OPTIMIZER_ITERATIONS: f64 = 100.;
+
+/// Number of records and columns of a value, if known.
+type Shape = (Option<f64>, Option<f64>);
+
+/// Estimate the cost of every node in an expanded computation graph.
+///
+/// Nodes without a known number of records inherit the largest number of records among their arguments,
+/// so the number of records only needs to be known at the data sources.
+/// Nodes whose size still cannot be determined are listed as unknown, and excluded from the totals.
+pub fn estimate_cost(
+    graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, ValueProperties>,
+) -> Result<proto::CostEstimate> {
+    let mut shapes = HashMap::<u32, Shape>::new();
+    let mut nodes = BTreeMap::<u32, proto::NodeCost>::new();
+    let mut unknown_node_ids = Vec::new();
+
+    for node_id in get_traversal(graph)? {
+        let component = graph.get(&node_id).unwrap();
+
+        let argument_shapes = component.arguments.values()
+            .map(|argument_id| shapes.get(argument_id).cloned().unwrap_or((None, None)))
+            .collect::<Vec<Shape>>();
+        let inherited_records = argument_shapes.iter()
+            .filter_map(|(num_records, _)| *num_records)
+            .fold(None, |max: Option<f64>, v| Some(max.map_or(v, |max| max.max(v))));
+
+        let (num_records, num_columns) = properties.get(&node_id).map(get_shape).unwrap_or((None, None));
+        let shape = (num_records.or(inherited_records), num_columns);
+        shapes.insert(node_id, shape);
+
+        // the largest argument drives the cost, or the node itself if it has no arguments
+        let input = argument_shapes.into_iter()
+            .filter_map(|(num_records, num_columns)| Some((num_records?, num_columns?)))
+            .max_by(|l, r| (l.0 * l.1).partial_cmp(&(r.0 * r.1)).unwrap_or(std::cmp::Ordering::Equal))
+            .or_else(|| Some((shape.0?, shape.1?)));
+        let output = match shape {
+            (Some(num_records), Some(num_columns)) => num_records * num_columns,
+            _ => 1.
+        };
+
+        match (input, component.variant.as_ref()) {
+            (Some(input), Some(variant)) => {
+                nodes.insert(node_id, variant_cost(variant, input, output));
+            }
+            _ => unknown_node_ids.push(node_id)
+        }
+    }
+    unknown_node_ids.sort();
+
+    Ok(proto::CostEstimate {
+        flops: nodes.values().map(|cost| cost.flops).sum(),
+        row_passes: nodes.values().map(|cost| cost.row_passes).sum(),
+        nodes: nodes.into_iter().collect(),
+        unknown_node_ids
+    })
+}
+
+/// Cost of a single component, given the number of records and columns of its largest argument, and the number of values it outputs.
+fn variant_cost(variant: &proto::component::Variant, (num_records, num_columns): (f64, f64), num_outputs: f64) -> proto::NodeCost {
+    use proto::component::Variant;
+    let cells = num_records * num_columns;
+
+    let (flops, row_passes) = match variant {
+        // no computation over the data
+        Variant::Literal(_) | Variant::Index(_) | Variant::Annotation(_) => (0., 0.),
+
+        // sorting each column
+        Variant::Quantile(_) | Variant::QuantileEdges(_) | Variant::DpQuantiles(_) | Variant::CountDistinct(_) =>
+            (cells * num_records.max(2.).log2(), 1.),
+
+        // outer products of every row
+        Variant::Covariance(_) | Variant::CrossProducts(_) =>
+            (num_records * num_columns.powi(2), 1.),
+
+        // factorizations of a released matrix of statistics, with about sqrt(2 * cells) variables
+        Variant::LinearRegression(_) | Variant::PrincipalComponents(_) =>
+            ((2. * cells).powf(1.5), 0.),
+
+        // a Newton step forms and solves the Hessian
+        Variant::ObjectivePerturbationMechanism(_) =>
+            (OPTIMIZER_ITERATIONS * (num_records * num_columns.powi(2) + num_columns.powi(3)), OPTIMIZER_ITERATIONS),
+
+        // one secure noise sample per released value
+        Variant::LaplaceMechanism(_) | Variant::GaussianMechanism(_) | Variant::SimpleGeometricMechanism(_) =>
+            (num_outputs * NOISE_FLOPS, 0.),
+
+        // one secure noise sample per candidate
+        Variant::ReportNoisyMaxMechanism(_) | Variant::TopKMechanism(_) =>
+            (cells * NOISE_FLOPS, 0.),
+
+        // one pass over the data
+        _ => (cells, 1.)
+    };
+    proto::NodeCost { flops, row_passes }
+}
+
+/// Number of records and columns of a value. Hashmaps count the columns of every partition or column.
+fn get_shape(properties: &ValueProperties) -> Shape {
+    match properties {
+        ValueProperties::Array(array) => (
+            array.num_records.map(|v| v as f64),
+            array.num_columns.map(|v| v as f64)),
+        ValueProperties::Scalar(_) => (Some(1.), Some(1.)),
+        ValueProperties::Jagged(_) => (None, None),
+        ValueProperties::Hashmap(hashmap) => {
+            let shapes = hashmap.properties.values().into_iter()
+                .map(get_shape).collect::<Vec<Shape>>();
+            let num_records = hashmap.num_records.map(|v| v as f64).or_else(|| shapes.iter()
+                .map(|(num_records, _)| *num_records)
+                .collect::<Option<Vec<f64>>>()
+                .map(|num_records| num_records.into_iter().fold(0., f64::max)));
+            let num_columns = shapes.iter()
+                .map(|(_, num_columns)| *num_columns)
+                .collect::<Option<Vec<f64>>>()
+                .map(|num_columns| num_columns.into_iter().sum());
+            (num_records, num_columns)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test_cost {
+    use crate::proto;
+    use crate::utilities::cost::variant_cost;
+
+    #[test]
+    fn test_variant_cost() {
+        let mean = variant_cost(&proto::component::Variant::Mean(proto::Mean {}), (100., 2.), 2.);
+        assert_eq!(mean.flops, 200.);
+        assert_eq!(mean.row_passes, 1.);
+
+        // sorting is superlinear
+        let quantile = variant_cost(&proto::component::Variant::Quantile(proto::Quantile {
+            alpha: 0.5, interpolation: "midpoint".to_string()
+        }), (100., 2.), 2.);
+        assert!(quantile.flops > mean.flops);
+    }
+}
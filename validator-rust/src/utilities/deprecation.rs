@@ -0,0 +1,89 @@
+//! Registry of deprecated components, and their replacements
+//!
+//! Stored analyses may contain components that have since been superseded.
+//! Each deprecated component is rewritten to its replacement when it is expanded, and a warning is raised,
+//! so that components may evolve without breaking analyses that were built against older versions.
+
+use crate::proto;
+use crate::proto::component::Variant;
+
+/// A deprecated component, and how to rewrite it into its replacement.
+pub struct Deprecation {
+    /// name of the deprecated component
+    pub component: &'static str,
+    /// name of the component that replaces it
+    pub replacement: &'static str,
+    /// the replacement of a variant, or None if the variant is not the deprecated component
+    pub rewrite: fn(&Variant) -> Option<Variant>,
+}
+
+/// Deprecated components, oldest first. A replacement may itself be deprecated by a later entry.
+pub const DEPRECATIONS: &[Deprecation] = &[
+    Deprecation {
+        component: "ReportNoisyMaxMechanism",
+        replacement: "TopKMechanism",
+        rewrite: report_noisy_max_to_top_k
+    },
+];
+
+/// Report noisy max is a top-k selection of a single category, under the same privacy usage.
+fn report_noisy_max_to_top_k(variant: &Variant) -> Option<Variant> {
+    match variant {
+        Variant::ReportNoisyMaxMechanism(mechanism) => Some(Variant::TopKMechanism(proto::TopKMechanism {
+            k: 1,
+            method: "Gumbel".to_string(),
+            privacy_usage: mechanism.privacy_usage.clone(),
+            count_privacy_usage: Vec::new(),
+        })),
+        _ => None
+    }
+}
+
+/// Rewrite a component if it is deprecated.
+///
+/// # Returns
+/// The rewritten component, and a warning for each deprecation that was applied,
+/// or None if the component is not deprecated.
+pub fn rewrite_deprecated(component: &proto::Component, node_id: &u32) -> Option<(proto::Component, Vec<proto::Error>)> {
+    let mut variant = component.variant.clone()?;
+    let mut warnings = Vec::new();
+    for deprecation in DEPRECATIONS {
+        if let Some(replacement) = (deprecation.rewrite)(&variant) {
+            warnings.push(proto::Error {
+                message: format!("at node_id {:?}: {} is deprecated, and was rewritten to {}",
+                                 node_id, deprecation.component, deprecation.replacement)
+            });
+            variant = replacement;
+        }
+    }
+
+    match warnings.is_empty() {
+        true => None,
+        false => Some((proto::Component { variant: Some(variant), ..component.clone() }, warnings))
+    }
+}
+
+
+#[cfg(test)]
+mod test_deprecation {
+    use crate::proto;
+    use crate::utilities::deprecation::rewrite_deprecated;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_rewrite_deprecated() {
+        let component = |variant: proto::component::Variant| proto::Component {
+            arguments: HashMap::new(), variant: Some(variant), omit: false, batch: 0
+        };
+
+        let (rewritten, warnings) = rewrite_deprecated(&component(proto::component::Variant::ReportNoisyMaxMechanism(
+            proto::ReportNoisyMaxMechanism { privacy_usage: Vec::new() })), &1).unwrap();
+        assert_eq!(warnings.len(), 1);
+        match rewritten.variant {
+            Some(proto::component::Variant::TopKMechanism(mechanism)) => assert_eq!(mechanism.k, 1),
+            _ => panic!("report noisy max must be rewritten to top-k")
+        }
+
+        assert!(rewrite_deprecated(&component(proto::component::Variant::Mean(proto::Mean {})), &1).is_none());
+    }
+}
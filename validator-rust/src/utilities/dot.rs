@@ -0,0 +1,116 @@
+//! Rendering of computation graphs to the DOT language of GraphViz
+//!
+//! Each node is labeled with its id and component, and optionally with a summary of its properties and its privacy usage.
+//! Each edge points from an argument to the component that consumes it, and is labeled with the name of the argument.
+
+use std::collections::HashMap;
+
+use crate::proto;
+use crate::base::ValueProperties;
+use crate::utilities::is_privatizing;
+
+use itertools::Itertools;
+
+/// Render a computation graph to a DOT digraph, in order of node id.
+///
+/// Properties and privacy usages are keyed by node id. Nodes missing from either map are left unannotated.
+/// Privatizing nodes are drawn as boxes, so that the mechanisms stand out from the rest of the expanded graph.
+pub fn render_dot(
+    graph: &HashMap<u32, proto::Component>,
+    properties: Option<&HashMap<u32, ValueProperties>>,
+    usages: Option<&HashMap<u32, proto::NodePrivacyUsage>>,
+) -> String {
+    let mut lines = vec!["digraph analysis {".to_string()];
+
+    graph.iter().sorted_by_key(|(node_id, _)| *node_id).for_each(|(node_id, component)| {
+        let mut label = vec![format!("{}: {}", node_id, component_name(component))];
+        if let Some(node_properties) = properties.and_then(|properties| properties.get(node_id)) {
+            label.push(properties_label(node_properties));
+        }
+        if let Some(total) = usages.and_then(|usages| usages.get(node_id)).and_then(|usage| usage.total.as_ref()) {
+            label.push(usage_label(total));
+        }
+
+        lines.push(format!("    {} [label=\"{}\", shape={}];",
+                           node_id,
+                           label.iter().map(String::as_str).map(escape).join("\\n"),
+                           if is_privatizing(component) { "box" } else { "ellipse" }));
+
+        component.arguments.iter().sorted_by_key(|(name, _)| *name).for_each(|(name, argument_id)|
+            lines.push(format!("    {} -> {} [label=\"{}\"];", argument_id, node_id, escape(name))));
+    });
+
+    lines.push("}".to_string());
+    lines.join("\n")
+}
+
+/// Name of the variant of a component, as it appears in the protobuf definitions.
+fn component_name(component: &proto::Component) -> String {
+    match &component.variant {
+        Some(variant) => format!("{:?}", variant).split('(').next().unwrap_or_default().to_string(),
+        None => "undefined".to_string()
+    }
+}
+
+/// Summary of the shape and type of a value, and whether it may be released.
+fn properties_label(properties: &ValueProperties) -> String {
+    let shape = |value: Option<i64>| value.map(|value| value.to_string()).unwrap_or_else(|| "?".to_string());
+    match properties {
+        ValueProperties::Array(array) => format!("{:?} [{} x {}]{}",
+                                                 array.data_type, shape(array.num_records), shape(array.num_columns),
+                                                 if array.releasable { ", releasable" } else { "" }),
+        ValueProperties::Hashmap(hashmap) => format!("Hashmap{}", if hashmap.disjoint { ", disjoint" } else { "" }),
+        ValueProperties::Jagged(jagged) => format!("Jagged{}", if jagged.releasable { ", releasable" } else { "" }),
+        ValueProperties::Scalar(scalar) => format!("Scalar{}", if scalar.releasable { ", releasable" } else { "" }),
+    }
+}
+
+fn usage_label(usage: &proto::PrivacyUsage) -> String {
+    match &usage.distance {
+        Some(proto::privacy_usage::Distance::Pure(distance)) =>
+            format!("epsilon = {}", distance.epsilon),
+        Some(proto::privacy_usage::Distance::Approximate(distance)) =>
+            format!("epsilon = {}, delta = {}", distance.epsilon, distance.delta),
+        Some(proto::privacy_usage::Distance::Concentrated(distance)) =>
+            format!("rho = {}", distance.rho),
+        None => "privacy usage undefined".to_string()
+    }
+}
+
+/// Escape a string for use within a quoted DOT identifier.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+
+#[cfg(test)]
+mod test_dot {
+    use crate::proto;
+    use crate::hashmap;
+    use crate::utilities::dot::render_dot;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_render_dot() {
+        let component = |arguments: HashMap<String, u32>, variant: proto::component::Variant| proto::Component {
+            arguments, variant: Some(variant), omit: false, batch: 0
+        };
+        let graph = hashmap![
+            1 => component(HashMap::new(), proto::component::Variant::Mean(proto::Mean {})),
+            2 => component(hashmap!["data".to_string() => 1], proto::component::Variant::LaplaceMechanism(
+                proto::LaplaceMechanism { privacy_usage: Vec::new() }))
+        ];
+        let usages = hashmap![2 => proto::NodePrivacyUsage {
+            total: Some(proto::PrivacyUsage {
+                distance: Some(proto::privacy_usage::Distance::Pure(proto::privacy_usage::DistancePure { epsilon: 0.5 }))
+            }),
+            columns: Vec::new()
+        }];
+
+        let dot = render_dot(&graph, None, Some(&usages));
+        assert!(dot.starts_with("digraph analysis {"));
+        assert!(dot.contains("1 [label=\"1: Mean\", shape=ellipse];"));
+        assert!(dot.contains("2 [label=\"2: LaplaceMechanism\\nepsilon = 0.5\", shape=box];"));
+        assert!(dot.contains("1 -> 2 [label=\"data\"];"));
+    }
+}
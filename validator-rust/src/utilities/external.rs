@@ -0,0 +1,156 @@
+//! Privacy usage spent outside of the system
+//!
+//! Other systems may release statistics about the same dataset.
+//! An analysis declares the mechanisms they invoked as external usages,
+//! so that the total privacy usage of the analysis accounts for them.
+
+use crate::errors::*;
+
+use crate::proto;
+use crate::utilities::{privacy_usage_check, privacy_usage_reducer};
+use crate::utilities::json::privacy_usage_to_json;
+
+use itertools::Itertools;
+
+/// Mechanisms an external usage may be declared with.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExternalMechanism {
+    Laplace,
+    Gaussian,
+    EpsilonDelta,
+}
+
+impl ExternalMechanism {
+    pub fn parse(mechanism: &str) -> Result<ExternalMechanism> {
+        Ok(match mechanism.to_lowercase().as_str() {
+            "laplace" => ExternalMechanism::Laplace,
+            "gaussian" => ExternalMechanism::Gaussian,
+            "epsilon_delta" => ExternalMechanism::EpsilonDelta,
+            _ => bail!("mechanism: {:?} is not recognized. Must be one of laplace, gaussian or epsilon_delta", mechanism)
+        })
+    }
+}
+
+/// Number of invocations of an external mechanism. An unset count is a single invocation.
+pub fn get_count(external_usage: &proto::ExternalUsage) -> u32 {
+    external_usage.count.max(1)
+}
+
+/// Privacy usage of a single invocation of an external mechanism.
+///
+/// The Gaussian mechanism is converted with the classical calibration `sigma = sqrt(2 ln(1.25 / delta)) / epsilon`,
+/// as in the Gaussian mechanism of the runtime.
+pub fn invocation_usage(external_usage: &proto::ExternalUsage) -> Result<proto::PrivacyUsage> {
+    let mechanism = ExternalMechanism::parse(&external_usage.mechanism)?;
+    let noise_multiplier = external_usage.noise_multiplier;
+    if mechanism != ExternalMechanism::EpsilonDelta && !(noise_multiplier > 0. && noise_multiplier.is_finite()) {
+        return Err("noise_multiplier: must be positive".into())
+    }
+
+    let usage = match (mechanism, external_usage.delta) {
+        (ExternalMechanism::Laplace, _) => pure(1. / noise_multiplier),
+        (ExternalMechanism::Gaussian, delta) => {
+            if !(delta > 0. && delta < 1.) {
+                return Err("delta: must be within (0, 1) for the gaussian mechanism".into())
+            }
+            approximate((2. * (1.25 / delta).ln()).sqrt() / noise_multiplier, delta)
+        },
+        (ExternalMechanism::EpsilonDelta, delta) if delta == 0. => pure(external_usage.epsilon),
+        (ExternalMechanism::EpsilonDelta, delta) => approximate(external_usage.epsilon, delta)
+    };
+    privacy_usage_check(&usage)?;
+    Ok(usage)
+}
+
+/// Privacy usage of every invocation of an external mechanism, composed linearly.
+pub fn external_usage(external_usage: &proto::ExternalUsage) -> Result<proto::PrivacyUsage> {
+    let count = get_count(external_usage) as f64;
+    let usage = invocation_usage(external_usage)?;
+    Ok(privacy_usage_reducer(&usage, &usage, &|l, _| l * count))
+}
+
+/// Privacy usage of every external mechanism, composed linearly, or None if no usages are declared.
+pub fn external_privacy_usage(external_usages: &[proto::ExternalUsage]) -> Result<Option<proto::PrivacyUsage>> {
+    Ok(external_usages.iter()
+        .map(external_usage)
+        .collect::<Result<Vec<proto::PrivacyUsage>>>()?.into_iter()
+        .fold1(|l, r| compose_usages(&l, &r)))
+}
+
+/// Sum two privacy usages. When summed with an approximate usage, a pure usage is approximate with delta zero.
+pub fn compose_usages(left: &proto::PrivacyUsage, right: &proto::PrivacyUsage) -> proto::PrivacyUsage {
+    use proto::privacy_usage::Distance;
+    let to_approximate = |usage: &proto::PrivacyUsage| match &usage.distance {
+        Some(Distance::Pure(pure)) => approximate(pure.epsilon, 0.),
+        _ => usage.clone()
+    };
+
+    match (&left.distance, &right.distance) {
+        (Some(Distance::Pure(_)), Some(Distance::Approximate(_))) | (Some(Distance::Approximate(_)), Some(Distance::Pure(_))) =>
+            privacy_usage_reducer(&to_approximate(left), &to_approximate(right), &|l, r| l + r),
+        _ => privacy_usage_reducer(left, right, &|l, r| l + r)
+    }
+}
+
+/// Disclosure of the external usages in the report.
+pub fn external_usages_to_json(external_usages: &[proto::ExternalUsage]) -> Result<serde_json::Value> {
+    Ok(serde_json::Value::Array(external_usages.iter()
+        .map(|usage| Ok(serde_json::json!({
+            "mechanism": usage.mechanism,
+            "count": get_count(usage),
+            "description": usage.description,
+            "privacyLoss": privacy_usage_to_json(&external_usage(usage)?)
+        })))
+        .collect::<Result<Vec<serde_json::Value>>>()?))
+}
+
+fn pure(epsilon: f64) -> proto::PrivacyUsage {
+    proto::PrivacyUsage {
+        distance: Some(proto::privacy_usage::Distance::Pure(proto::privacy_usage::DistancePure { epsilon }))
+    }
+}
+
+fn approximate(epsilon: f64, delta: f64) -> proto::PrivacyUsage {
+    proto::PrivacyUsage {
+        distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate { epsilon, delta }))
+    }
+}
+
+
+#[cfg(test)]
+mod test_external {
+    use crate::proto;
+    use crate::utilities::get_epsilon;
+    use crate::utilities::external::{external_privacy_usage, external_usage};
+
+    fn laplace(noise_multiplier: f64, count: u32) -> proto::ExternalUsage {
+        proto::ExternalUsage {
+            mechanism: "laplace".to_string(),
+            noise_multiplier,
+            epsilon: 0.,
+            delta: 0.,
+            count,
+            description: "".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_external_usage() {
+        // an unset count is a single invocation
+        assert_eq!(get_epsilon(&external_usage(&laplace(2., 0)).unwrap()).unwrap(), 0.5);
+        assert_eq!(get_epsilon(&external_usage(&laplace(2., 3)).unwrap()).unwrap(), 1.5);
+
+        let mut gaussian = laplace(4., 1);
+        gaussian.mechanism = "gaussian".to_string();
+        gaussian.delta = 1e-6;
+        let total = external_privacy_usage(&[laplace(2., 1), gaussian]).unwrap().unwrap();
+        // pure usages are approximate once summed with approximate usages
+        match total.distance {
+            Some(proto::privacy_usage::Distance::Approximate(approximate)) => assert_eq!(approximate.delta, 1e-6),
+            _ => panic!("the total usage must be approximate")
+        }
+
+        assert!(external_usage(&laplace(0., 1)).is_err());
+        assert!(external_privacy_usage(&[]).unwrap().is_none());
+    }
+}
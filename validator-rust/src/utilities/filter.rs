@@ -0,0 +1,136 @@
+//! Privacy odometers and filters for adaptive analyses
+//!
+//! When mechanisms are expanded dynamically, the privacy usage of each mechanism may depend on earlier releases.
+//! Summing the realized usages after the fact does not bound the privacy loss of every composition theorem under such adaptivity.
+//! A filter instead decides, before each mechanism runs, whether it may run given the usages realized so far,
+//! so that the adaptive interaction as a whole satisfies the budget of the filter.
+//! The odometer is the record of the realized usages, in order of release.
+
+use crate::errors::*;
+
+use crate::proto;
+use crate::utilities::{get_epsilon, get_delta, privacy_usage_reducer};
+
+use itertools::Itertools;
+
+/// Realized (epsilon, delta) of the usages, erroring on usages that are not epsilon-delta.
+fn parameters(usages: &[proto::PrivacyUsage]) -> Result<Vec<(f64, f64)>> {
+    usages.iter()
+        .map(|usage| Ok((
+            get_epsilon(usage).chain_err(|| "privacy filters require pure or approximate privacy usages")?,
+            get_delta(usage).unwrap_or(0.))))
+        .collect()
+}
+
+/// Total privacy usage recorded by the odometer of the filter, or None if nothing has been released.
+///
+/// The linear sum is a valid odometer under adaptivity.
+pub fn odometer_usage(filter: &proto::PrivacyFilter) -> Option<proto::PrivacyUsage> {
+    filter.odometer.iter().cloned()
+        .fold1(|usage_1, usage_2| privacy_usage_reducer(&usage_1, &usage_2, &|l, r| l + r))
+}
+
+/// Record the realized usage of a release on the odometer of the filter.
+pub fn record_usage(filter: &mut proto::PrivacyFilter, usage: proto::PrivacyUsage) {
+    filter.odometer.push(usage);
+}
+
+/// Check that a mechanism with the requested usage may run, given the usages already realized by the filter.
+///
+/// # Returns
+/// A BudgetExhausted error if the filter halts the analysis before the mechanism at `node_id`.
+pub fn check_filter(filter: &proto::PrivacyFilter, node_id: u32, usage: &proto::PrivacyUsage) -> Result<()> {
+    use proto::privacy_filter::Rule;
+
+    if filter.epsilon.is_nan() || filter.epsilon <= 0. {
+        return Err("privacy_filter: epsilon must be positive".into())
+    }
+    if filter.delta.is_nan() || filter.delta < 0. || filter.delta >= 1. {
+        return Err("privacy_filter: delta must be within [0, 1)".into())
+    }
+
+    let mut usages = parameters(&filter.odometer)?;
+    usages.extend(parameters(&[usage.clone()])?);
+
+    let total_delta = usages.iter().map(|(_, delta)| delta).sum::<f64>();
+
+    match Rule::from_i32(filter.rule)
+        .ok_or_else(|| Error::from("privacy_filter: rule must be one of Basic or Advanced"))? {
+        Rule::Basic => {
+            let total_epsilon = usages.iter().map(|(epsilon, _)| epsilon).sum::<f64>();
+            if total_epsilon > filter.epsilon {
+                bail!(ErrorKind::BudgetExhausted(node_id, format!("epsilon would be {}, beyond the budget of {}", total_epsilon, filter.epsilon)))
+            }
+            if total_delta > filter.delta {
+                bail!(ErrorKind::BudgetExhausted(node_id, format!("delta would be {}, beyond the budget of {}", total_delta, filter.delta)))
+            }
+        },
+
+        // Rogers, Roth, Ullman and Vadhan. Privacy Odometers and Filters: Pay-as-you-Go Composition. NeurIPS 2016, Theorem 5.1
+        Rule::Advanced => {
+            if filter.delta <= 0. || filter.delta >= (-1_f64).exp() {
+                return Err("privacy_filter: the advanced filter requires delta within (0, 1/e)".into())
+            }
+            // half of delta is spent by the mechanisms, and the other half by the concentration bound
+            if total_delta > filter.delta / 2. {
+                bail!(ErrorKind::BudgetExhausted(node_id, format!("delta would be {}, beyond half of the budget of {}", total_delta, filter.delta)))
+            }
+            let squared = usages.iter().map(|(epsilon, _)| epsilon.powi(2)).sum::<f64>();
+            let expected = usages.iter().map(|(epsilon, _)| epsilon * epsilon.exp_m1() / 2.).sum::<f64>();
+            let h = filter.epsilon.powi(2) / (28.04 * (1. / filter.delta).ln());
+            let bound = expected + (2. * (squared + h) * (1. + (squared / h + 1.).ln() / 2.) * (2. / filter.delta).ln()).sqrt();
+            if bound > filter.epsilon {
+                bail!(ErrorKind::BudgetExhausted(node_id, format!("epsilon would be bounded by {}, beyond the budget of {}", bound, filter.epsilon)))
+            }
+        }
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod test_filter {
+    use crate::proto;
+    use crate::errors::ErrorKind;
+    use crate::utilities::filter::{check_filter, record_usage, odometer_usage};
+    use crate::utilities::get_epsilon;
+
+    fn usage(epsilon: f64) -> proto::PrivacyUsage {
+        proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate { epsilon, delta: 0. }))
+        }
+    }
+
+    #[test]
+    fn test_basic_filter() {
+        let mut filter = proto::PrivacyFilter {
+            rule: proto::privacy_filter::Rule::Basic as i32,
+            epsilon: 1.,
+            delta: 0.,
+            odometer: Vec::new()
+        };
+        check_filter(&filter, 1, &usage(0.6)).unwrap();
+        record_usage(&mut filter, usage(0.6));
+        assert_eq!(get_epsilon(&odometer_usage(&filter).unwrap()).unwrap(), 0.6);
+
+        // a smaller mechanism may still run after a larger one is refused
+        match check_filter(&filter, 2, &usage(0.5)).unwrap_err().kind() {
+            ErrorKind::BudgetExhausted(node_id, _) => assert_eq!(*node_id, 2),
+            _ => panic!("the filter must halt with a structured error")
+        }
+        check_filter(&filter, 3, &usage(0.3)).unwrap();
+    }
+
+    #[test]
+    fn test_advanced_filter() {
+        // many small mechanisms sum beyond the budget, but are admitted by the advanced filter
+        let filter = proto::PrivacyFilter {
+            rule: proto::privacy_filter::Rule::Advanced as i32,
+            epsilon: 2.,
+            delta: 1e-6,
+            odometer: (0..299).map(|_| usage(0.01)).collect()
+        };
+        check_filter(&filter, 1, &usage(0.01)).unwrap();
+        assert!(check_filter(&filter, 1, &usage(1.)).is_err());
+    }
+}
@@ -0,0 +1,216 @@
+//! Localized rendering of report values
+//!
+//! Formatting is purely presentational. The raw values in each release are left untouched,
+//! and the rendered strings are attached under a separate `formatted` key.
+
+use crate::errors::*;
+
+use crate::proto;
+use crate::utilities::json::JSONRelease;
+use crate::utilities::serial::parse_i64_null;
+
+use serde_json::Value;
+
+/// Number rendering rules derived from a proto::ReportFormat.
+pub struct NumberFormat {
+    pub decimal_separator: String,
+    pub grouping_separator: String,
+    pub precision: Option<usize>,
+    pub percent: bool,
+}
+
+impl NumberFormat {
+    pub fn new(format: &proto::ReportFormat) -> Result<NumberFormat> {
+        let (decimal_separator, grouping_separator) = locale_separators(&format.locale)?;
+
+        let precision = match format.precision.as_ref().and_then(parse_i64_null) {
+            Some(precision) if precision < 0 => return Err("precision must be non-negative".into()),
+            Some(precision) => Some(precision as usize),
+            None => None
+        };
+
+        Ok(NumberFormat {
+            decimal_separator: if format.decimal_separator.is_empty() {
+                decimal_separator.to_string()
+            } else { format.decimal_separator.clone() },
+            grouping_separator: if format.grouping_separator.is_empty() {
+                grouping_separator.to_string()
+            } else { format.grouping_separator.clone() },
+            precision,
+            percent: format.percent,
+        })
+    }
+
+    /// Render an integer with grouped thousands.
+    pub fn integer(&self, value: i64) -> String {
+        // the magnitude of i64::MIN does not fit in an i64
+        let digits = value.unsigned_abs().to_string();
+        let sign = if value < 0 { "-" } else { "" };
+        format!("{}{}", sign, group_digits(&digits, &self.grouping_separator))
+    }
+
+    /// Render a float with grouped thousands, the locale decimal separator and the configured precision.
+    pub fn float(&self, value: f64) -> String {
+        if !value.is_finite() {
+            return value.to_string();
+        }
+        let text = match self.precision {
+            Some(precision) => format!("{:.*}", precision, value.abs()),
+            None => value.abs().to_string()
+        };
+
+        let mut parts = text.splitn(2, '.');
+        let integer = parts.next().unwrap_or("0");
+        let fraction = parts.next();
+
+        // avoid rendering "-0" when rounding removes all significant digits
+        let sign = if value < 0. && text.chars().any(|c| c.is_digit(10) && c != '0') { "-" } else { "" };
+
+        match fraction {
+            Some(fraction) => format!("{}{}{}{}", sign,
+                                      group_digits(integer, &self.grouping_separator),
+                                      self.decimal_separator, fraction),
+            None => format!("{}{}", sign, group_digits(integer, &self.grouping_separator))
+        }
+    }
+
+    /// Render a proportion, as a percentage if requested.
+    pub fn proportion(&self, value: f64) -> String {
+        if self.percent {
+            format!("{} %", self.float(value * 100.))
+        } else {
+            self.float(value)
+        }
+    }
+
+    /// Replace every number in a json value with its rendered string, preserving the structure.
+    pub fn json(&self, value: &Value) -> Value {
+        match value {
+            Value::Number(number) => Value::String(match number.as_i64() {
+                Some(integer) => self.integer(integer),
+                None => self.float(number.as_f64().unwrap_or(std::f64::NAN))
+            }),
+            Value::Array(values) => Value::Array(values.iter()
+                .map(|value| self.json(value)).collect()),
+            Value::Object(object) => Value::Object(object.iter()
+                .map(|(key, value)| (key.clone(), self.json(value))).collect()),
+            _ => value.clone()
+        }
+    }
+}
+
+/// Default (decimal, grouping) separators for the language subtag of a locale.
+fn locale_separators(locale: &str) -> Result<(&'static str, &'static str)> {
+    let language = locale.split(|c| c == '-' || c == '_').next()
+        .unwrap_or("").to_lowercase();
+
+    Ok(match language.as_str() {
+        "" | "en" | "ja" | "ko" | "zh" | "he" | "th" => (".", ","),
+        "de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" | "el" => (",", "."),
+        // narrow no-break space, per CLDR
+        "fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "uk" | "hu" => (",", "\u{202f}"),
+        _ => bail!("locale {:?} is not recognized; set decimal_separator and grouping_separator explicitly", locale)
+    })
+}
+
+fn group_digits(digits: &str, separator: &str) -> String {
+    let length = digits.len();
+    digits.chars().enumerate().fold(String::new(), |mut grouped, (i, digit)| {
+        if i > 0 && (length - i) % 3 == 0 {
+            grouped.push_str(separator);
+        }
+        grouped.push(digit);
+        grouped
+    })
+}
+
+/// Render an ISO 8601 date (YYYY-MM-DD) according to a pattern built from the tokens YYYY, MM and DD.
+pub fn format_date(date: &str, pattern: &str) -> Result<String> {
+    let parts = date.split('-').collect::<Vec<&str>>();
+    if parts.len() != 3 || parts[0].len() != 4 || parts[1].len() != 2 || parts[2].len() != 2
+        || !parts.iter().all(|part| part.chars().all(|c| c.is_digit(10))) {
+        bail!("date {:?} must be in ISO 8601 form (YYYY-MM-DD)", date)
+    }
+    if pattern.is_empty() {
+        return Ok(date.to_string());
+    }
+    Ok(pattern
+        .replace("YYYY", parts[0])
+        .replace("MM", parts[1])
+        .replace("DD", parts[2]))
+}
+
+/// Attach localized renderings to each release in a report.
+///
+/// Each release keeps its machine-readable fields, and gains a `formatted` object containing
+/// the rendered `releaseInfo`, `privacyLoss`, `accuracy` and publication `date`.
+pub fn format_report(
+    releases: &[JSONRelease],
+    format: &proto::ReportFormat,
+) -> Result<Value> {
+    let number_format = NumberFormat::new(format)?;
+    let date = match format.date.is_empty() {
+        true => None,
+        false => Some(format_date(&format.date, &format.date_format)?)
+    };
+
+    Ok(Value::Array(releases.iter().map(|release| {
+        let mut formatted = serde_json::Map::new();
+        formatted.insert("releaseInfo".to_string(), number_format.json(&release.release_info));
+        formatted.insert("privacyLoss".to_string(), number_format.json(&release.privacy_loss));
+        if let Some(accuracy) = &release.accuracy {
+            formatted.insert("accuracy".to_string(), serde_json::json!({
+                "accuracyValue": number_format.float(accuracy.accuracy_value),
+                "alpha": number_format.proportion(accuracy.alpha)
+            }));
+        }
+        if let Some(date) = &date {
+            formatted.insert("date".to_string(), Value::String(date.clone()));
+        }
+
+        let mut release = serde_json::to_value(release)
+            .map_err(|e| Error::from(format!("unable to serialize release: {}", e)))?;
+        release.as_object_mut()
+            .ok_or_else(|| Error::from("release must serialize to a json object"))?
+            .insert("formatted".to_string(), Value::Object(formatted));
+        Ok(release)
+    }).collect::<Result<Vec<Value>>>()?))
+}
+
+
+#[cfg(test)]
+mod test_format {
+    use crate::proto;
+    use crate::utilities::format::{NumberFormat, format_date};
+
+    fn number_format(locale: &str, precision: Option<i64>) -> NumberFormat {
+        NumberFormat::new(&proto::ReportFormat {
+            locale: locale.to_string(),
+            precision: Some(proto::I64Null {
+                data: precision.map(proto::i64_null::Data::Option)
+            }),
+            percent: true,
+            ..Default::default()
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_locales() {
+        assert_eq!(number_format("en-US", Some(2)).float(1234567.891), "1,234,567.89");
+        assert_eq!(number_format("de-DE", Some(2)).float(-1234567.891), "-1.234.567,89");
+        assert_eq!(number_format("de-DE", Some(1)).float(-0.01), "0,0");
+        assert_eq!(number_format("en", None).integer(-1000), "-1,000");
+        assert_eq!(number_format("en", None).integer(i64::MIN), "-9,223,372,036,854,775,808");
+        assert_eq!(number_format("en", Some(0)).proportion(0.05), "5 %");
+        assert!(NumberFormat::new(&proto::ReportFormat {
+            locale: "xx".to_string(), ..Default::default()
+        }).is_err());
+    }
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(format_date("2020-03-07", "DD.MM.YYYY").unwrap(), "07.03.2020");
+        assert_eq!(format_date("2020-03-07", "").unwrap(), "2020-03-07");
+        assert!(format_date("03/07/2020", "DD.MM.YYYY").is_err());
+    }
+}
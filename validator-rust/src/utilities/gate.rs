@@ -0,0 +1,229 @@
+//! Release gate for two-person release workflows
+//!
+//! An analyst builds an analysis, and a curator who holds a secret key reviews and approves it.
+//! The approval token is an HMAC-SHA256 over the fingerprint of the analysis, keyed by the curator key.
+//! Any change to the analysis after approval invalidates the token.
+
+use crate::errors::*;
+
+use crate::proto;
+use crate::utilities::is_privatizing;
+use crate::utilities::deprecation::rewrite_deprecated;
+
+use hmac::{Hmac, Mac};
+use sha2::{Sha256, Digest};
+use prost::Message;
+use itertools::Itertools;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// SHA-256 fingerprint of an analysis, excluding its approval token.
+///
+/// Protobuf encodings of maps are not ordered, so map entries are sorted by key before hashing.
+pub fn fingerprint_analysis(analysis: &proto::Analysis) -> Result<Vec<u8>> {
+    let mut hasher = Sha256::new();
+    hasher.input(canonical_bytes(analysis)?);
+    Ok(hasher.result().to_vec())
+}
+
+/// Approval token for an analysis, signed with the curator key.
+pub fn sign_analysis(analysis: &proto::Analysis, curator_key: &[u8]) -> Result<Vec<u8>> {
+    let mut mac = new_mac(curator_key)?;
+    mac.input(&fingerprint_analysis(analysis)?);
+    Ok(mac.result().code().to_vec())
+}
+
+/// Whether the analysis carries an approval token that was signed with the curator key.
+///
+/// The comparison is constant-time.
+pub fn is_approved(analysis: &proto::Analysis, curator_key: &[u8]) -> Result<bool> {
+    if analysis.approval_token.is_empty() {
+        return Ok(false)
+    }
+    let mut mac = new_mac(curator_key)?;
+    mac.input(&fingerprint_analysis(analysis)?);
+    Ok(mac.verify(&analysis.approval_token).is_ok())
+}
+
+/// Check the approval token of an analysis against the release gate.
+///
+/// A token that is present must be valid.
+/// A token must be present if the gate requires approval.
+pub fn check_approval(analysis: &proto::Analysis, release_gate: &proto::ReleaseGate) -> Result<()> {
+    if analysis.approval_token.is_empty() {
+        return match release_gate.require_approval {
+            true => Err("approval_token: the release gate requires the analysis to be approved by a curator".into()),
+            false => Ok(())
+        }
+    }
+    match is_approved(analysis, &release_gate.curator_key)? {
+        true => Ok(()),
+        false => Err("approval_token: the token does not match the analysis. The analysis may have changed since it was approved".into())
+    }
+}
+
+/// Check that a component may be expanded under the release gate.
+///
+/// When approval is required, mechanisms may not be expanded unless the analysis they belong to carries a token signed with the curator key,
+/// and the mechanism is the node of the approved analysis at `component_id`, either as approved or as rewritten from a deprecated component.
+/// Arguments are not compared, because the optimizations the analysis was approved with may rewire them.
+pub fn check_expansion(
+    component: &proto::Component, component_id: u32, release_gate: &proto::ReleaseGate, analysis: Option<&proto::Analysis>,
+) -> Result<()> {
+    if !release_gate.require_approval || !is_privatizing(component) {
+        return Ok(())
+    }
+    let analysis = match analysis {
+        Some(analysis) if is_approved(analysis, &release_gate.curator_key)? => analysis,
+        _ => return Err(format!("node {}: mechanisms may not be expanded without a valid approval token", component_id).into())
+    };
+
+    let matches = |approved: &proto::Component| approved.variant == component.variant
+        && approved.omit == component.omit && approved.batch == component.batch;
+    let approved = analysis.computation_graph.as_ref()
+        .and_then(|computation_graph| computation_graph.value.get(&component_id));
+    match approved {
+        Some(approved) if matches(approved) || rewrite_deprecated(approved, &component_id)
+            .map(|(rewritten, _)| matches(&rewritten)).unwrap_or(false) => Ok(()),
+        _ => Err(format!("node {}: the mechanism is not part of the approved analysis", component_id).into())
+    }
+}
+
+fn new_mac(curator_key: &[u8]) -> Result<HmacSha256> {
+    if curator_key.is_empty() {
+        return Err("curator_key: must be defined to verify approval tokens".into())
+    }
+    HmacSha256::new_varkey(curator_key)
+        .map_err(|_| Error::from("curator_key: invalid key length"))
+}
+
+/// Deterministic encoding of the privacy definition, computation graph, external usages and optimizations of an analysis.
+///
+/// Each encoded message is prefixed by its length, so that adjacent messages may not be confused.
+fn canonical_bytes(analysis: &proto::Analysis) -> Result<Vec<u8>> {
+    fn push_message(bytes: &mut Vec<u8>, message: &impl Message) -> Result<()> {
+        let mut buffer = Vec::new();
+        message.encode(&mut buffer)
+            .map_err(|_| Error::from("unable to encode analysis"))?;
+        bytes.extend(&(buffer.len() as u64).to_le_bytes());
+        bytes.extend(buffer);
+        Ok(())
+    }
+
+    let mut bytes = Vec::new();
+
+    if let Some(privacy_definition) = &analysis.privacy_definition {
+        let mut definition = privacy_definition.clone();
+        definition.delta_allotments.clear();
+        push_message(&mut bytes, &definition)?;
+        privacy_definition.delta_allotments.iter()
+            .sorted_by_key(|(node_id, _)| **node_id)
+            .for_each(|(node_id, fraction)| {
+                bytes.extend(&node_id.to_le_bytes());
+                bytes.extend(&fraction.to_bits().to_le_bytes());
+            });
+    }
+
+    if let Some(computation_graph) = &analysis.computation_graph {
+        for (node_id, component) in computation_graph.value.iter().sorted_by_key(|(node_id, _)| **node_id) {
+            bytes.extend(&node_id.to_le_bytes());
+            let mut variant = component.clone();
+            variant.arguments.clear();
+            push_message(&mut bytes, &variant)?;
+            for (name, argument_id) in component.arguments.iter().sorted() {
+                bytes.extend(&(name.len() as u64).to_le_bytes());
+                bytes.extend(name.as_bytes());
+                bytes.extend(&argument_id.to_le_bytes());
+            }
+        }
+    }
+
+    // external usages are declared by the analyst, so are approved alongside the graph
+    for external_usage in &analysis.external_usages {
+        push_message(&mut bytes, external_usage)?;
+    }
+
+    // optimizations change which components are evaluated and charged. Absent optimizations are encoded as disabled
+    push_message(&mut bytes, &analysis.optimizations.clone().unwrap_or_default())?;
+
+    Ok(bytes)
+}
+
+
+#[cfg(test)]
+mod test_gate {
+    use crate::proto;
+    use crate::hashmap;
+    use crate::utilities::gate::{sign_analysis, check_approval, check_expansion};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_approval() {
+        let mut analysis = proto::Analysis {
+            privacy_definition: None,
+            computation_graph: Some(proto::ComputationGraph {
+                value: hashmap![1 => proto::Component {
+                    arguments: HashMap::new(),
+                    variant: Some(proto::component::Variant::Mean(proto::Mean {})),
+                    omit: false,
+                    batch: 0
+                }]
+            }),
+            approval_token: Vec::new(),
+            external_usages: Vec::new(),
+            optimizations: None
+        };
+        let gate = proto::ReleaseGate { curator_key: b"curator".to_vec(), require_approval: true };
+
+        assert!(check_approval(&analysis, &gate).is_err());
+
+        analysis.approval_token = sign_analysis(&analysis, &gate.curator_key).unwrap();
+        assert!(check_approval(&analysis, &gate).is_ok());
+
+        // a token signed by another key is rejected
+        let forged = proto::ReleaseGate { curator_key: b"analyst".to_vec(), require_approval: true };
+        assert!(check_approval(&analysis, &forged).is_err());
+
+        // enabling an optimization changes which components are released, so it invalidates the token
+        let mut optimized = analysis.clone();
+        optimized.optimizations = Some(proto::Optimizations { eliminate_dead_nodes: true, eliminate_common_subexpressions: false });
+        assert!(check_approval(&optimized, &gate).is_err());
+
+        // changing the analysis invalidates the token
+        analysis.computation_graph.as_mut().unwrap().value.get_mut(&1).unwrap().omit = true;
+        assert!(check_approval(&analysis, &gate).is_err());
+    }
+
+    #[test]
+    fn test_expansion() {
+        let mechanism = proto::Component {
+            arguments: HashMap::new(),
+            variant: Some(proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism { privacy_usage: Vec::new() })),
+            omit: false,
+            batch: 0
+        };
+        let mut analysis = proto::Analysis {
+            computation_graph: Some(proto::ComputationGraph { value: hashmap![1 => mechanism.clone()] }),
+            ..Default::default()
+        };
+        let gate = proto::ReleaseGate { curator_key: b"curator".to_vec(), require_approval: true };
+
+        // the token of the analysis is verified, rather than trusting the caller
+        assert!(check_expansion(&mechanism, 1, &gate, None).is_err());
+        assert!(check_expansion(&mechanism, 1, &gate, Some(&analysis)).is_err());
+
+        analysis.approval_token = sign_analysis(&analysis, &gate.curator_key).unwrap();
+        assert!(check_expansion(&mechanism, 1, &gate, Some(&analysis)).is_ok());
+
+        // an approved analysis does not unlock mechanisms that are not part of it
+        let foreign = proto::Component {
+            variant: Some(proto::component::Variant::GaussianMechanism(proto::GaussianMechanism { privacy_usage: Vec::new() })),
+            ..mechanism.clone()
+        };
+        assert!(check_expansion(&foreign, 1, &gate, Some(&analysis)).is_err());
+        assert!(check_expansion(&mechanism, 2, &gate, Some(&analysis)).is_err());
+
+        analysis.approval_token = sign_analysis(&analysis, b"analyst").unwrap();
+        assert!(check_expansion(&mechanism, 1, &gate, Some(&analysis)).is_err());
+    }
+}
@@ -217,6 +217,8 @@ pub fn infer_property(value: &Value) -> Result<ValueProperties> {
         Value::Array(array) => ArrayProperties {
             nullity: infer_nullity(&value)?,
             releasable: true,
+            public_shape: true,
+            public_metadata: true,
             nature: infer_nature(&value)?,
             c_stability: infer_c_stability(&array)?,
             num_columns: Some(array.num_columns()?),
@@ -235,6 +237,7 @@ pub fn infer_property(value: &Value) -> Result<ValueProperties> {
                 Array::I64(array) => array.len(),
                 Array::Str(array) => array.len(),
             } != 0,
+            max_contributions: None,
             dimensionality: array.shape().len() as u32,
         }.into(),
         Value::Hashmap(hashmap) => {
@@ -256,6 +259,8 @@ pub fn infer_property(value: &Value) -> Result<ValueProperties> {
                         .collect::<Result<BTreeMap<bool, ValueProperties>>>()?.into(),
                 },
                 columnar: false,
+                releasable: true,
+                group_id: Vec::new(),
             }.into()
         }
         Value::Jagged(_jagged) => JaggedProperties {
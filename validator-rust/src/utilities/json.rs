@@ -75,7 +75,19 @@ pub fn value_to_json(value: &base::Value) -> Result<serde_json::Value> {
             base::Array::Str(value) => arraynd_to_json(value),
             base::Array::Bool(value) => arraynd_to_json(value)
         },
-        _ => Err("only arrayND to json is implemented".into())
+        // json object keys must be strings
+        base::Value::Hashmap(hashmap) => Ok(serde_json::Value::Object(match hashmap {
+            base::Hashmap::Str(hashmap) => hashmap.iter()
+                .map(|(key, value)| Ok((key.clone(), value_to_json(value)?)))
+                .collect::<Result<serde_json::Map<String, serde_json::Value>>>()?,
+            base::Hashmap::I64(hashmap) => hashmap.iter()
+                .map(|(key, value)| Ok((key.to_string(), value_to_json(value)?)))
+                .collect::<Result<serde_json::Map<String, serde_json::Value>>>()?,
+            base::Hashmap::Bool(hashmap) => hashmap.iter()
+                .map(|(key, value)| Ok((key.to_string(), value_to_json(value)?)))
+                .collect::<Result<serde_json::Map<String, serde_json::Value>>>()?,
+        })),
+        _ => Err("only arrayND and hashmap to json is implemented".into())
     }
 }
 
@@ -97,6 +109,8 @@ pub fn privacy_usage_to_json(privacy_usage: &proto::PrivacyUsage) -> serde_json:
         proto::privacy_usage::Distance::Pure(distance) =>
             serde_json::json!({"name": "pure", "epsilon": distance.epsilon}),
         proto::privacy_usage::Distance::Approximate(distance) =>
-            serde_json::json!({"name": "approximate", "epsilon": distance.epsilon, "delta": distance.delta})
+            serde_json::json!({"name": "approximate", "epsilon": distance.epsilon, "delta": distance.delta}),
+        proto::privacy_usage::Distance::Concentrated(distance) =>
+            serde_json::json!({"name": "concentrated", "rho": distance.rho})
     }
 }
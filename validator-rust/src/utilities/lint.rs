@@ -0,0 +1,135 @@
+//! Advisory checks of an analysis
+//!
+//! Lints flag analyses that are valid, but are likely to be mistaken or to waste their budget.
+//! Unlike validation errors, lints never prevent an analysis from running.
+//! Each lint is reported under a stable code, so that interfaces may document, filter or suppress them.
+
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::proto;
+use crate::base::ValueProperties;
+use crate::utilities::{get_charged_privacy_usages, get_epsilon, get_delta};
+use crate::utilities::slice::get_ancestors;
+
+/// A mechanism releases an aggregate of data that was never clamped.
+pub const UNCLAMPED_DATA: &str = "WN001";
+/// A mechanism spends a delta of at least one over the number of records it aggregates.
+pub const DELTA_EXCEEDS_INVERSE_RECORDS: &str = "WN002";
+/// A single mechanism spends most of the epsilon of the analysis.
+pub const SKEWED_BUDGET: &str = "WN003";
+
+/// Share of the total epsilon beyond which the budget is considered skewed to one mechanism.
+const SKEWED_SHARE: f64 = 0.8;
+
+/// Lint every privatizing node of an expanded computation graph.
+///
+/// # Returns
+/// The lints, in order of code and then node id.
+pub fn lint_analysis(
+    graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, ValueProperties>,
+    release: &proto::Release,
+) -> Result<Vec<proto::Lint>> {
+    let usages = get_charged_privacy_usages(graph, release)?;
+
+    let mut lints = Vec::new();
+
+    // counts are bounded by the number of records, so only aggregates of the values themselves need clamping
+    usages.iter()
+        .filter(|(node_id, _)| aggregators(graph, properties, node_id).iter().any(|aggregator| match aggregator {
+            proto::component::Variant::Count(_) | proto::component::Variant::CountDistinct(_)
+            | proto::component::Variant::CountTrue(_) | proto::component::Variant::Histogram(_) => false,
+            _ => true
+        }))
+        .filter(|(node_id, _)| !get_ancestors(graph, &[*node_id]).iter()
+            .any(|ancestor_id| match graph.get(ancestor_id).and_then(|ancestor| ancestor.variant.as_ref()) {
+                Some(proto::component::Variant::Clamp(_)) => true,
+                _ => false
+            }))
+        .for_each(|(node_id, _)| lints.push(lint(UNCLAMPED_DATA, vec![*node_id], format!(
+            "node {} releases an aggregate of data that was never clamped, so its sensitivity rests on the declared bounds of the data",
+            node_id))));
+
+    for (node_id, usage) in &usages {
+        let delta = get_delta(usage).unwrap_or(0.);
+        let num_records = graph.get(node_id).into_iter()
+            .flat_map(|component| component.arguments.values())
+            .filter_map(|argument_id| properties.get(argument_id)?.array().ok()?.aggregator.as_ref())
+            .flat_map(|aggregator| aggregator.properties.values())
+            .filter_map(|property| property.array().ok()?.num_records)
+            .max();
+        if let Some(num_records) = num_records {
+            if delta > 0. && delta * num_records.max(1) as f64 >= 1. {
+                lints.push(lint(DELTA_EXCEEDS_INVERSE_RECORDS, vec![*node_id], format!(
+                    "node {} spends a delta of {}, which is at least one over the {} records it aggregates, and permits releasing a record outright",
+                    node_id, delta, num_records)));
+            }
+        }
+    }
+
+    let epsilons = usages.iter()
+        .filter_map(|(node_id, usage)| Some((*node_id, get_epsilon(usage).ok()?)))
+        .collect::<Vec<(u32, f64)>>();
+    let total_epsilon = epsilons.iter().map(|(_, epsilon)| epsilon).sum::<f64>();
+    if epsilons.len() > 1 && total_epsilon > 0. {
+        epsilons.iter()
+            .filter(|(_, epsilon)| epsilon / total_epsilon > SKEWED_SHARE)
+            .for_each(|(node_id, epsilon)| lints.push(lint(SKEWED_BUDGET, vec![*node_id], format!(
+                "node {} spends {:.0}% of the epsilon of the analysis, leaving little for the other {} mechanisms",
+                node_id, 100. * epsilon / total_epsilon, epsilons.len() - 1))));
+    }
+
+    Ok(lints)
+}
+
+/// Variants of the aggregators released by a mechanism.
+fn aggregators(
+    graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, ValueProperties>,
+    node_id: &u32,
+) -> Vec<proto::component::Variant> {
+    graph.get(node_id).into_iter()
+        .flat_map(|component| component.arguments.values())
+        .filter_map(|argument_id| Some(properties.get(argument_id)?.array().ok()?.aggregator.as_ref()?.component.clone()))
+        .collect()
+}
+
+fn lint(code: &str, node_ids: Vec<u32>, message: String) -> proto::Lint {
+    proto::Lint { code: code.to_string(), node_ids, message }
+}
+
+
+#[cfg(test)]
+mod test_lint {
+    use crate::proto;
+    use crate::hashmap;
+    use crate::utilities::lint::{lint_analysis, SKEWED_BUDGET};
+    use std::collections::HashMap;
+
+    fn laplace(epsilon: f64) -> proto::Component {
+        proto::Component {
+            arguments: HashMap::new(),
+            variant: Some(proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+                privacy_usage: vec![proto::PrivacyUsage {
+                    distance: Some(proto::privacy_usage::Distance::Pure(proto::privacy_usage::DistancePure { epsilon }))
+                }]
+            })),
+            omit: false,
+            batch: 0
+        }
+    }
+
+    #[test]
+    fn test_skewed_budget() {
+        let release = proto::Release { values: HashMap::new() };
+
+        let lints = lint_analysis(&hashmap![1 => laplace(0.1), 2 => laplace(0.1), 3 => laplace(1.8)], &HashMap::new(), &release).unwrap();
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].code, SKEWED_BUDGET);
+        assert_eq!(lints[0].node_ids, vec![3]);
+
+        assert!(lint_analysis(&hashmap![1 => laplace(1.), 2 => laplace(1.)], &HashMap::new(), &release).unwrap().is_empty());
+    }
+}
@@ -2,13 +2,32 @@ pub mod json;
 pub mod serial;
 pub mod inference;
 pub mod array;
+pub mod format;
+pub mod privacy;
+pub mod budget_store;
+pub mod cost;
+pub mod audit;
+pub mod gate;
+pub mod release_notes;
+pub mod accounting;
+pub mod composition;
+pub mod external;
+pub mod tradeoff;
+pub mod slice;
+pub mod questionnaire;
+pub mod deprecation;
+pub mod filter;
+pub mod allocation;
+pub mod dot;
+pub mod optimize;
+pub mod lint;
 
 use crate::errors::*;
 
 use crate::proto;
 
-use crate::base::{Release, Value, ValueProperties, SensitivitySpace, NodeProperties, ReleaseNode};
-use std::collections::{HashMap, HashSet};
+use crate::base::{Release, Value, ValueProperties, HashmapProperties, ArrayProperties, SensitivitySpace, NodeProperties, ReleaseNode, Warnable};
+use std::collections::{HashMap, HashSet, BTreeSet};
 use std::hash::Hash;
 use crate::utilities::serial::{parse_release, parse_value_properties, serialize_value, parse_release_node};
 use crate::utilities::inference::infer_property;
@@ -64,12 +83,26 @@ pub fn get_input_properties<T>(
     Ok(properties)
 }
 
+/// Retrieve the ValueProperties for each of the arguments of a component, with scalars in the array form components expect.
+pub fn get_component_properties(
+    component: &proto::Component,
+    graph_properties: &HashMap<u32, ValueProperties>,
+) -> Result<NodeProperties> {
+    Ok(get_input_properties(component, graph_properties)?.into_iter()
+        .map(|(name, property)| (name, property.into_array_form()))
+        .collect())
+}
+
 /// Given an analysis and release, attempt to propagate properties across the entire computation graph.
 ///
+/// Zero-dimensional arrays are stored in scalar form.
+///
 /// The graph is traversed, and every node is attempted to be expanded, so that validation occurs at the most granular level.
 /// Each component in the graph implements the Component trait, which contains the propagate_properties function.
 /// While traversing, properties are checked and propagated forward at every point in the graph.
 /// If the requirements for any node are not met, the propagation fails, and the analysis is not valid.
+/// Nodes with supplied `properties` are taken as already propagated, and are not expanded again.
+/// The optimization passes enabled on the analysis are applied to the computation graph before it is traversed.
 ///
 /// # Returns
 /// * `0` - Properties for every node in the expanded graph
@@ -80,12 +113,33 @@ pub fn propagate_properties(
     properties: Option<&HashMap<u32, proto::ValueProperties>>,
     dynamic: bool
 
-) -> Result<(HashMap<u32, ValueProperties>, HashMap<u32, proto::Component>, Vec<proto::Error>)> {
+) -> Result<Warnable<(HashMap<u32, ValueProperties>, HashMap<u32, proto::Component>)>> {
+    expand_graph(analysis, release, properties, dynamic)
+        .map(|expansion| expansion.map(|(properties, graph, _)| (properties, graph)))
+}
+
+/// Propagate properties as in propagate_properties, and also return the release of the expanded graph.
+///
+/// # Returns
+/// * `0` - Properties for every node in the expanded graph
+/// * `1` - The expanded graph
+/// * `2` - The release, extended with the public values added by expansions and constant folding
+pub fn expand_graph(
+    analysis: &proto::Analysis,
+    release: &proto::Release,
+    properties: Option<&HashMap<u32, proto::ValueProperties>>,
+    dynamic: bool
+) -> Result<Warnable<(HashMap<u32, ValueProperties>, HashMap<u32, proto::Component>, Release)>> {
 
     let privacy_definition = analysis.privacy_definition.to_owned()
         .ok_or_else(|| Error::from("privacy definition must be defined"))?;
     let mut graph: HashMap<u32, proto::Component> = analysis.computation_graph.to_owned()
         .ok_or_else(|| Error::from("computation graph be defined"))?.value;
+
+    let mut warnings = Vec::new();
+
+    warnings.extend(optimize::optimize_graph(&mut graph, release, analysis.optimizations.as_ref()));
+
     let mut traversal: Vec<u32> = get_traversal(&graph)?;
 
     // extend and pop from the end of the traversal
@@ -95,15 +149,16 @@ pub fn propagate_properties(
 
     let mut graph_properties = match properties {
         Some(properties) => properties.iter()
-            .map(|(idx, props)| (idx.clone(), parse_value_properties(props)))
-            .collect::<HashMap<u32, ValueProperties>>(),
+            .map(|(idx, props)| Ok((idx.clone(), parse_value_properties(props)?.into_scalar_form())))
+            .collect::<Result<HashMap<u32, ValueProperties>>>()?,
         None => HashMap::new()
     };
+    let cached_ids: HashSet<u32> = graph_properties.keys().cloned().collect();
 
     // infer properties on public evaluations
     graph_properties.extend(graph_evaluation.iter()
         .filter(|(_, release_node)| release_node.public)
-        .map(|(node_id, release_node)| Ok((*node_id, infer_property(&release_node.value)?)))
+        .map(|(node_id, release_node)| Ok((*node_id, infer_property(&release_node.value)?.into_scalar_form())))
         .collect::<Result<HashMap<u32, ValueProperties>>>()?);
 
     let mut maximum_id = graph.keys().cloned()
@@ -111,19 +166,29 @@ pub fn propagate_properties(
 
     let mut failed_ids = HashSet::new();
 
-    let mut warnings = Vec::new();
-
     while !traversal.is_empty() {
         let node_id = *traversal.last().unwrap();
 
-        let component: proto::Component = graph.get(&node_id).unwrap().to_owned();
+        if cached_ids.contains(&node_id) {
+            traversal.pop();
+            continue
+        }
+
+        let mut component: proto::Component = graph.get(&node_id).unwrap().to_owned();
+
+        // deprecated components are replaced in the graph before they are expanded
+        if let Some((rewritten, deprecation_warnings)) = deprecation::rewrite_deprecated(&component, &node_id) {
+            graph.insert(node_id, rewritten.clone());
+            warnings.extend(deprecation_warnings);
+            component = rewritten;
+        }
 
         if component.arguments.values().any(|v| failed_ids.contains(v)) {
             failed_ids.insert(traversal.pop().unwrap());
             continue
         }
 
-        let input_properties = get_input_properties(&component, &graph_properties)?;
+        let input_properties = get_component_properties(&component, &graph_properties)?;
         let public_arguments = get_public_arguments(&component, &graph_evaluation)?;
 
         let mut expansion = match (dynamic, component.clone().variant
@@ -148,8 +213,8 @@ pub fn propagate_properties(
         // patch the computation graph
         graph.extend(expansion.computation_graph.clone());
         graph_properties.extend(expansion.properties.iter()
-            .map(|(node_id, props)| (*node_id, parse_value_properties(props)))
-            .collect::<HashMap<u32, ValueProperties>>());
+            .map(|(node_id, props)| Ok((*node_id, parse_value_properties(props)?.into_scalar_form())))
+            .collect::<Result<HashMap<u32, ValueProperties>>>()?);
         graph_evaluation.extend(expansion.releases.iter()
             .map(|(node_id, release)| Ok((*node_id, parse_release_node(&release)?)))
             .collect::<Result<HashMap<u32, ReleaseNode>>>()?);
@@ -176,6 +241,7 @@ pub fn propagate_properties(
                         .ok_or_else(|| Error::from("privacy definition must be defined"))?
                         .propagate_property(
                             &privacy_definition, &public_arguments, &input_properties)
+                        .and_then(|properties| apply_release_publicness(properties, release_node))
                         .chain_err(|| format!("at node_id {:?}", node_id))
                 }
             }
@@ -183,9 +249,22 @@ pub fn propagate_properties(
             // if node has not been evaluated, propagate properties over it
             None => {
                 let component: proto::Component = graph.get(&node_id).unwrap().to_owned();
-                component.clone().variant.unwrap().propagate_property(
-                    &privacy_definition, &public_arguments, &input_properties)
+                component.clone().variant
+                    .ok_or_else(|| Error::from("component variant must be defined"))?
+                    .propagate_property(
+                        &privacy_definition, &public_arguments, &input_properties)
                     .chain_err(|| format!("at node_id {:?}", node_id))
+                    // transforms over public arguments are folded, as they are when expanded by the runtime
+                    .and_then(|properties| match optimize::fold_constant(&component, &public_arguments) {
+                        Some(value) => {
+                            let properties = infer_property(&value);
+                            graph_evaluation.insert(node_id, ReleaseNode {
+                                value, privacy_usages: None, public: true, public_shape: true, public_metadata: true
+                            });
+                            properties
+                        },
+                        None => Ok(properties)
+                    })
             }
         };
 
@@ -199,29 +278,96 @@ pub fn propagate_properties(
             (false, Err(err)) => return Err(err)
         };
 
+        // mechanisms that remain after expansion must spend an epsilon within the policy of the privacy definition
+        match (dynamic, check_epsilon_bounds(&privacy_definition, graph.get(&node_id).unwrap(), node_id)) {
+            (_, Ok(())) => (),
+            (true, Err(err)) => {
+                failed_ids.insert(node_id);
+                warnings.push(serialize_error(err));
+                continue
+            },
+            (false, Err(err)) => return Err(err)
+        }
+
+        // flag unusually large privacy usages on the mechanisms that remain after expansion
+        warnings.extend(get_privacy_usage_warnings(graph.get(&node_id).unwrap(), &node_id)?);
+        let component_properties = apply_partition_group(component_properties, graph.get(&node_id).unwrap(), node_id);
+
 //        println!("graph evaluation in prop {:?}", graph_evaluation);
-        graph_properties.insert(node_id.clone(), component_properties);
+        graph_properties.insert(node_id.clone(), component_properties.into_scalar_form());
+    }
+    Ok(Warnable((graph_properties, graph, graph_evaluation), warnings))
+}
+
+/// Refine the properties of a private release with the aspects of it that are public.
+///
+/// A public shape fixes the number of records and columns to those of the released value.
+/// Public metadata marks the propagated nature as public, without inferring a tighter nature from the private value.
+pub fn apply_release_publicness(properties: ValueProperties, release_node: &ReleaseNode) -> Result<ValueProperties> {
+    let mut properties = match properties {
+        ValueProperties::Array(properties) => properties,
+        properties => return Ok(properties)
+    };
+    if release_node.public_shape {
+        let released_properties = infer_property(&release_node.value)?.array()?.clone();
+        properties.num_records = released_properties.num_records;
+        properties.num_columns = released_properties.num_columns;
+        properties.public_shape = true;
+    }
+    if release_node.public_metadata {
+        properties.public_metadata = true;
+    }
+    Ok(properties.into())
+}
+
+/// Identify the partitions produced by a component with the id of its node.
+///
+/// The id is added as the innermost group of every level of the partition,
+/// so that partitions of partitions carry the ids of each Partition they were nested by.
+pub fn apply_partition_group(properties: ValueProperties, component: &proto::Component, node_id: u32) -> ValueProperties {
+    fn push_group(properties: &mut HashmapProperties, node_id: u32) {
+        properties.group_id.push(node_id);
+        let cells = properties.properties.values().into_iter().cloned()
+            .map(|cell| match cell {
+                ValueProperties::Hashmap(mut cell) => {
+                    push_group(&mut cell, node_id);
+                    ValueProperties::Hashmap(cell)
+                },
+                cell => cell
+            })
+            .collect();
+        properties.properties = properties.properties.from_values(cells);
+    }
+
+    match (&component.variant, properties) {
+        (Some(proto::component::Variant::Partition(_)), ValueProperties::Hashmap(mut properties)) |
+        (Some(proto::component::Variant::RebalancePartitions(_)), ValueProperties::Hashmap(mut properties)) => {
+            push_group(&mut properties, node_id);
+            properties.into()
+        },
+        (_, properties) => properties
     }
-    Ok((graph_properties, graph, warnings))
 }
 
 /// Given a computation graph, return an ordering of nodes that ensures all dependencies of any node have been visited
 ///
 /// The traversal also fails upon detecting cyclic dependencies,
 /// and attempts to optimize traversal order to minimize caching of intermediate results.
+/// The traversal only depends on the graph, and not on the iteration order of its hashmaps,
+/// so that the ids of expanded nodes and the order of warnings are reproducible.
 pub fn get_traversal(
     graph: &HashMap<u32, proto::Component>
 ) -> Result<Vec<u32>> {
 
     // track node parents
-    let mut parents = HashMap::<u32, HashSet<u32>>::new();
+    let mut parents = HashMap::<u32, BTreeSet<u32>>::new();
     graph.iter().for_each(|(node_id, component)| {
         parents.entry(*node_id)
-            .or_insert_with(HashSet::<u32>::new);
+            .or_insert_with(BTreeSet::<u32>::new);
 
         component.arguments.values().for_each(|argument_node_id| {
             parents.entry(*argument_node_id)
-                .or_insert_with(HashSet::<u32>::new)
+                .or_insert_with(BTreeSet::<u32>::new)
                 .insert(*node_id);
         });
     });
@@ -230,10 +376,12 @@ pub fn get_traversal(
     let mut traversal = Vec::new();
 
     // collect all sources (nodes with zero arguments)
+    // the queue is popped from the end, so sources are visited in ascending order of id
     let mut queue: Vec<u32> = graph.iter()
         .filter(|(_node_id, component)| component.arguments.is_empty()
             || component.arguments.values().all(|arg_idx| !graph.contains_key(arg_idx)))
-        .map(|(node_id, _component)| node_id.to_owned()).collect();
+        .map(|(node_id, _component)| node_id.to_owned())
+        .sorted().rev().collect();
 
     let mut visited = HashMap::new();
 
@@ -466,7 +614,10 @@ pub fn get_literal(value: &Value, batch: &u32) -> Result<(proto::Component, prot
         proto::ReleaseNode {
             value: Some(serialize_value(value)?),
             privacy_usages: None,
-            public: true
+            public: true,
+            public_shape: true,
+            public_metadata: true,
+            encrypted_value: None
         }))
 }
 
@@ -474,15 +625,12 @@ pub fn get_literal(value: &Value, batch: &u32) -> Result<(proto::Component, prot
 pub fn get_component_privacy_usage(
     component: &proto::Component,
     release_node: Option<&proto::ReleaseNode>,
-) -> Option<proto::PrivacyUsage> {
+) -> Result<Option<proto::PrivacyUsage>> {
 
     // get the maximum possible usage allowed to the component
-    let mut privacy_usage: Vec<proto::PrivacyUsage> = match component.to_owned().variant? {
-        proto::component::Variant::LaplaceMechanism(x) => x.privacy_usage,
-        proto::component::Variant::GaussianMechanism(x) => x.privacy_usage,
-//        proto::component::Variant::ExponentialMechanism(x) => x.privacy_usage,
-        proto::component::Variant::SimpleGeometricMechanism(x) => x.privacy_usage,
-        _ => return None
+    let mut privacy_usage = match get_requested_privacy_usages(component)? {
+        Some(privacy_usage) => privacy_usage,
+        None => return Ok(None)
     };
 
     // if release usage is defined, then use the actual eps, etc. from the release
@@ -491,9 +639,398 @@ pub fn get_component_privacy_usage(
     });
 
     // sum privacy usage within the node
-    privacy_usage.into_iter()
+    Ok(privacy_usage.into_iter()
         .fold1(|usage_a, usage_b|
-            privacy_usage_reducer(&usage_a, &usage_b, &|a, b| a + b))
+            privacy_usage_reducer(&usage_a, &usage_b, &|a, b| a + b)))
+}
+
+/// Whether the component privatizes its data, and is charged a privacy usage.
+///
+/// A component whose privacy usage fails to be computed still privatizes its data.
+pub fn is_privatizing(component: &proto::Component) -> bool {
+    !matches!(get_requested_privacy_usages(component), Ok(None))
+}
+
+include!(concat!(env!("OUT_DIR"), "/privacy_options.rs"));
+
+/// Warnings for the privacy usages requested by a privatizing component, tagged with its node id.
+pub fn get_privacy_usage_warnings(component: &proto::Component, node_id: &u32) -> Result<Vec<proto::Error>> {
+    Ok(get_requested_privacy_usages(component)?.unwrap_or_else(Vec::new).iter()
+        .flat_map(privacy_usage_warnings)
+        .map(|warning| proto::Error { message: format!("at node_id {:?}: {}", node_id, warning.message) })
+        .collect())
+}
+
+/// Privacy usages in the options of a privatizing component, or None if the component does not privatize.
+///
+/// Errors if the usage of the component is computed, rather than supplied, and the computation fails.
+pub(crate) fn get_requested_privacy_usages(component: &proto::Component) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+    let variant = match component.to_owned().variant {
+        Some(variant) => variant,
+        None => return Ok(None)
+    };
+    Ok(Some(match variant {
+        proto::component::Variant::LaplaceMechanism(x) => x.privacy_usage,
+        proto::component::Variant::GaussianMechanism(x) => x.privacy_usage,
+//        proto::component::Variant::ExponentialMechanism(x) => x.privacy_usage,
+        proto::component::Variant::SimpleGeometricMechanism(x) => x.privacy_usage,
+        proto::component::Variant::ObjectivePerturbationMechanism(x) => x.privacy_usage,
+        proto::component::Variant::ReportNoisyMaxMechanism(x) => x.privacy_usage,
+        proto::component::Variant::TopKMechanism(x) => x.privacy_usage.into_iter()
+            .chain(x.count_privacy_usage).collect(),
+        proto::component::Variant::DpStabilityHistogram(x) => x.privacy_usage,
+        proto::component::Variant::DpQuantiles(x) => x.privacy_usage,
+        proto::component::Variant::DpRangeTree(x) => x.privacy_usage,
+        proto::component::Variant::DpAuc(x) => x.privacy_usage,
+        proto::component::Variant::DpDecisionTree(x) => x.privacy_usage,
+        proto::component::Variant::DpGroupMoments(x) => x.privacy_usage,
+        proto::component::Variant::DpSyntheticData(x) => x.privacy_usage,
+        proto::component::Variant::DpWelchTTest(x) => x.privacy_usage,
+        // the usage is not supplied, but computed by the subsampled Gaussian accountant
+        proto::component::Variant::DpStochasticGradientDescent(x) =>
+            vec![crate::components::dp_stochastic_gradient_descent::sgd_privacy_usage(&x)?],
+        proto::component::Variant::ExtremeSelection(x) => x.privacy_usage,
+        _ => return Ok(None)
+    }))
+}
+
+/// Ids of the private data sources in the lineage of a node that are not covered by a contribution bound.
+///
+/// The search stops at BoundContributions components, and at data sources with a known `max_contributions_per_individual`.
+pub fn get_unbounded_sources(
+    graph: &HashMap<u32, proto::Component>,
+    node_id: &u32,
+) -> Vec<u32> {
+    let mut unbounded = HashSet::new();
+    let mut visited = HashSet::new();
+    let mut traversal = vec![*node_id];
+
+    while let Some(node_id) = traversal.pop() {
+        if !visited.insert(node_id) {
+            continue
+        }
+        let component = match graph.get(&node_id) {
+            Some(component) => component,
+            None => continue
+        };
+        match &component.variant {
+            Some(proto::component::Variant::BoundContributions(_)) => (),
+            Some(proto::component::Variant::Materialize(materialize)) => {
+                let is_bounded = materialize.data_source.as_ref()
+                    .map(|data_source| data_source.max_contributions_per_individual > 0)
+                    .unwrap_or(false);
+                if !materialize.public && !is_bounded {
+                    unbounded.insert(node_id);
+                }
+            }
+            _ => traversal.extend(component.arguments.values())
+        }
+    }
+    unbounded.into_iter().sorted().collect()
+}
+
+/// Check that the number of records each individual contributes is bounded before a mechanism is applied.
+///
+/// Without a bound, the sensitivity of the mechanism is computed as if each individual contributes a single record.
+pub fn check_contribution_bound(
+    graph: &HashMap<u32, proto::Component>,
+    node_id: &u32,
+) -> Result<()> {
+    match get_unbounded_sources(graph, node_id).as_slice() {
+        [] => Ok(()),
+        sources => Err(format!(
+            "node {}: the data sources at nodes {:?} do not set max_contributions_per_individual. \
+            Add a BoundContributions component between the data sources and the mechanism",
+            node_id, sources).into())
+    }
+}
+
+/// Check the invariants of an analysis that hold over its whole expanded graph, after properties are propagated.
+pub fn check_expanded_graph(
+    privacy_definition: Option<&proto::PrivacyDefinition>,
+    graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, ValueProperties>,
+    release: &proto::Release,
+) -> Result<()> {
+    // each individual must contribute a bounded number of records to every mechanism
+    check_contribution_bounds(graph)?;
+
+    // expansions must not leave private intermediates exposed as outputs
+    check_terminal_nodes(graph, properties)?;
+
+    // mechanisms may not spend more delta than the delta splitting policy allots them
+    if let Some(privacy_definition) = privacy_definition {
+        check_delta_allotments(privacy_definition, graph, release)?;
+        check_delta_budget(privacy_definition, graph, properties, release)?;
+    }
+    Ok(())
+}
+
+/// Check the contribution bound of every mechanism in the graph, reporting all violations at once.
+pub fn check_contribution_bounds(
+    graph: &HashMap<u32, proto::Component>
+) -> Result<()> {
+    let violations = graph.iter()
+        .filter(|(_, component)| is_privatizing(component))
+        .map(|(node_id, _)| *node_id)
+        .sorted()
+        .filter_map(|node_id| check_contribution_bound(graph, &node_id).err())
+        .map(|err| err.to_string())
+        .collect::<Vec<String>>();
+
+    match violations.is_empty() {
+        true => Ok(()),
+        false => Err(violations.join("\n").into())
+    }
+}
+
+/// Check that every terminal node of the graph is a requested, releasable output.
+///
+/// Omitted nodes are intermediate values, so they may not be terminal.
+/// A terminal node must either be public, or be a mechanism that privatizes its data.
+/// Otherwise a private intermediate is left exposed as an output of the analysis.
+pub fn check_terminal_nodes(
+    graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, ValueProperties>,
+) -> Result<()> {
+    let violations = get_sinks(graph).into_iter()
+        .sorted()
+        .filter_map(|node_id| {
+            let component = graph.get(&node_id)?;
+            if component.omit {
+                return Some(format!(
+                    "node {}: omitted nodes are intermediate values, and may not be terminal", node_id))
+            }
+            let is_public = properties.get(&node_id)
+                .map(|property| property.releasable())
+                .unwrap_or(false);
+            if !is_public && !is_privatizing(component) {
+                return Some(format!(
+                    "node {}: terminal nodes must be public or a mechanism, but this node is private", node_id))
+            }
+            None
+        })
+        .collect::<Vec<String>>();
+
+    match violations.is_empty() {
+        true => Ok(()),
+        false => Err(violations.join("\n").into())
+    }
+}
+
+/// Delta allotted to each node that spends delta, under the delta splitting policy of the privacy definition.
+///
+/// Returns None if the privacy definition does not cap delta.
+pub fn get_delta_allotments(
+    privacy_definition: &proto::PrivacyDefinition,
+    graph: &HashMap<u32, proto::Component>,
+    release: &proto::Release,
+) -> Result<Option<HashMap<u32, f64>>> {
+    let delta_cap = privacy_definition.delta_cap;
+    if delta_cap == 0. {
+        return Ok(None)
+    }
+    if !(0. ..=1.).contains(&delta_cap) {
+        return Err("delta_cap: must be within (0, 1]".into())
+    }
+
+    // (node id, epsilon) of every node that spends delta
+    let spenders = get_charged_privacy_usages(graph, release)?.into_iter()
+        .filter(|(_, usage)| get_delta(usage).unwrap_or(0.) > 0.)
+        .map(|(node_id, usage)| Ok((node_id, get_epsilon(&usage)?)))
+        .collect::<Result<Vec<(u32, f64)>>>()?;
+
+    use proto::privacy_definition::DeltaSplit;
+    Ok(Some(match DeltaSplit::from_i32(privacy_definition.delta_split)
+        .ok_or_else(|| Error::from("delta_split: must be one of Equal, Proportional or UserSpecified"))? {
+        DeltaSplit::Equal => spenders.iter()
+            .map(|(node_id, _)| (*node_id, delta_cap / spenders.len() as f64))
+            .collect(),
+        DeltaSplit::Proportional => {
+            let total_epsilon = spenders.iter().map(|(_, epsilon)| epsilon).sum::<f64>();
+            spenders.iter()
+                .map(|(node_id, epsilon)| (*node_id, delta_cap * epsilon / total_epsilon))
+                .collect()
+        },
+        DeltaSplit::UserSpecified => {
+            let allotments = &privacy_definition.delta_allotments;
+            if allotments.values().any(|fraction| !(0. ..=1.).contains(fraction)) {
+                return Err("delta_allotments: each fraction must be within [0, 1]".into())
+            }
+            if allotments.values().sum::<f64>() > 1. + 1e-12 {
+                return Err("delta_allotments: fractions may not sum to more than one".into())
+            }
+            spenders.iter()
+                .map(|(node_id, _)| (*node_id, delta_cap * allotments.get(node_id).cloned().unwrap_or(0.)))
+                .collect()
+        }
+    }))
+}
+
+/// Check that no node spends more delta than it is allotted by the delta splitting policy, reporting all violations at once.
+pub fn check_delta_allotments(
+    privacy_definition: &proto::PrivacyDefinition,
+    graph: &HashMap<u32, proto::Component>,
+    release: &proto::Release,
+) -> Result<()> {
+    let allotments = match get_delta_allotments(privacy_definition, graph, release)? {
+        Some(allotments) => allotments,
+        None => return Ok(())
+    };
+
+    let usages = get_charged_privacy_usages(graph, release)?.into_iter()
+        .collect::<HashMap<u32, proto::PrivacyUsage>>();
+    let violations = allotments.into_iter()
+        .sorted_by_key(|(node_id, _)| *node_id)
+        .filter_map(|(node_id, allotment)| {
+            let delta = get_delta(usages.get(&node_id)?).ok()?;
+            // tolerate rounding error from splitting the cap
+            if delta > allotment * (1. + 1e-9) { Some((node_id, delta, allotment)) } else { None }
+        })
+        .collect::<Vec<(u32, f64, f64)>>();
+
+    match violations.is_empty() {
+        true => Ok(()),
+        false => Err(ErrorKind::DeltaAllotmentExceeded(violations).into())
+    }
+}
+
+/// Total delta spent by all mechanisms in the analysis, tracked independently of the composition of epsilon.
+///
+/// Usages in rho do not define a delta, and are not counted.
+pub fn get_total_delta(
+    graph: &HashMap<u32, proto::Component>,
+    release: &proto::Release,
+) -> Result<f64> {
+    Ok(get_charged_privacy_usages(graph, release)?.iter()
+        .filter_map(|(_, usage)| get_delta(usage).ok())
+        .sum())
+}
+
+/// Upper bound on the total delta of the analysis, under the policies of the privacy definition.
+///
+/// The bound is the smaller of the delta cap and, if `delta_inverse_records` is set,
+/// one over the largest number of records aggregated by a mechanism that spends delta.
+/// Returns None if the privacy definition does not bound delta.
+pub fn get_delta_budget(
+    privacy_definition: &proto::PrivacyDefinition,
+    graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, ValueProperties>,
+    release: &proto::Release,
+) -> Result<Option<f64>> {
+    let mut budget = match privacy_definition.delta_cap {
+        cap if cap == 0. => None,
+        cap if (0. ..=1.).contains(&cap) => Some(cap),
+        _ => return Err("delta_cap: must be within (0, 1]".into())
+    };
+
+    if privacy_definition.delta_inverse_records {
+        let num_records = get_charged_privacy_usages(graph, release)?.into_iter()
+            .filter(|(_, usage)| get_delta(usage).map(|delta| delta > 0.).unwrap_or(false))
+            // number of records of the data aggregated by each argument of the mechanism
+            .filter_map(|(node_id, _)| graph.get(&node_id))
+            .flat_map(|component| component.arguments.values())
+            .filter_map(|argument_id| properties.get(argument_id)?.array().ok()?.aggregator.as_ref())
+            .flat_map(|aggregator| aggregator.properties.values())
+            .filter_map(|property| property.array().ok()?.num_records)
+            .max();
+
+        if let Some(num_records) = num_records {
+            let inverse = 1. / num_records.max(1) as f64;
+            budget = Some(budget.map(|cap| cap.min(inverse)).unwrap_or(inverse));
+        } else if get_total_delta(graph, release)? > 0. {
+            return Err("delta_inverse_records: the number of records aggregated by mechanisms that spend delta must be known".into())
+        }
+    }
+    Ok(budget)
+}
+
+/// Check that the total delta spent by all mechanisms does not exceed the delta budget of the privacy definition.
+pub fn check_delta_budget(
+    privacy_definition: &proto::PrivacyDefinition,
+    graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, ValueProperties>,
+    release: &proto::Release,
+) -> Result<()> {
+    let budget = match get_delta_budget(privacy_definition, graph, properties, release)? {
+        Some(budget) => budget,
+        None => return Ok(())
+    };
+
+    let total_delta = get_total_delta(graph, release)?;
+    // tolerate rounding error from summing the deltas of many mechanisms
+    match total_delta > budget * (1. + 1e-9) {
+        true => Err(ErrorKind::DeltaBudgetExceeded(total_delta, budget).into()),
+        false => Ok(())
+    }
+}
+
+/// Check that a mechanism with the requested usage may be released without exceeding the epsilon or delta cap,
+/// given the usage already spent by the analysis.
+///
+/// # Returns
+/// A BudgetExhausted error if the release of the mechanism at `node_id` would exceed a cap.
+pub fn check_budget(
+    privacy_definition: &proto::PrivacyDefinition,
+    node_id: u32,
+    spent_usage: Option<&proto::PrivacyUsage>,
+    usage: &proto::PrivacyUsage,
+) -> Result<()> {
+    if privacy_definition.epsilon_cap.is_nan() || privacy_definition.epsilon_cap < 0. {
+        return Err("epsilon_cap: must be non-negative".into())
+    }
+    if !(0. ..=1.).contains(&privacy_definition.delta_cap) {
+        return Err("delta_cap: must be within (0, 1]".into())
+    }
+
+    let total = match spent_usage {
+        Some(spent_usage) => privacy_usage_reducer(spent_usage, usage, &|l, r| l + r),
+        None => usage.clone()
+    };
+    let epsilon = get_epsilon(&total).chain_err(|| "budget enforcement requires pure or approximate privacy usages")?;
+    let delta = get_delta(&total).unwrap_or(0.);
+
+    if privacy_definition.epsilon_cap > 0. && epsilon > privacy_definition.epsilon_cap {
+        bail!(ErrorKind::BudgetExhausted(node_id, format!("epsilon would be {}, beyond the cap of {}", epsilon, privacy_definition.epsilon_cap)))
+    }
+    if privacy_definition.delta_cap > 0. && delta > privacy_definition.delta_cap {
+        bail!(ErrorKind::BudgetExhausted(node_id, format!("delta would be {}, beyond the cap of {}", delta, privacy_definition.delta_cap)))
+    }
+    Ok(())
+}
+
+/// Check that each invocation of a mechanism spends an epsilon within the floor and ceiling of the privacy definition.
+///
+/// A floor or ceiling of zero is unset. Mechanisms in `epsilon_exemptions` are not checked,
+/// and usages in rho are not checked, as they do not define an epsilon.
+pub fn check_epsilon_bounds(
+    privacy_definition: &proto::PrivacyDefinition,
+    component: &proto::Component,
+    node_id: u32,
+) -> Result<()> {
+    let floor = privacy_definition.epsilon_floor;
+    let ceiling = privacy_definition.epsilon_ceiling;
+    if floor.is_nan() || floor < 0. {
+        return Err("epsilon_floor: must be non-negative".into())
+    }
+    if ceiling.is_nan() || ceiling < 0. {
+        return Err("epsilon_ceiling: must be non-negative".into())
+    }
+    if ceiling > 0. && floor > ceiling {
+        return Err("epsilon_floor: may not exceed the epsilon_ceiling".into())
+    }
+    if privacy_definition.epsilon_exemptions.contains(&node_id) {
+        return Ok(())
+    }
+
+    let out_of_bounds = get_requested_privacy_usages(component)?.unwrap_or_else(Vec::new).iter()
+        .filter_map(|usage| get_epsilon(usage).ok())
+        .find(|epsilon| *epsilon < floor || (ceiling > 0. && *epsilon > ceiling));
+
+    match out_of_bounds {
+        Some(epsilon) => Err(ErrorKind::EpsilonOutOfBounds(node_id, epsilon, floor, ceiling).into()),
+        None => Ok(())
+    }
 }
 
 pub fn privacy_usage_reducer(
@@ -512,6 +1049,9 @@ pub fn privacy_usage_reducer(
                 epsilon: operator(x.epsilon, y.epsilon),
                 delta: operator(x.delta, y.delta),
             })),
+            (Distance::Concentrated(x), Distance::Concentrated(y)) => Some(Distance::Concentrated(proto::privacy_usage::DistanceConcentrated {
+                rho: operator(x.rho, y.rho)
+            })),
             _ => None
         }
     }
@@ -525,8 +1065,6 @@ pub fn privacy_usage_check(
     let check_epsilon = |privacy_param: f64| -> Result<()> {
         if privacy_param <= 0.0 {
             return Err("Privacy parameter epsilon must be greater than 0.".into())
-        } else if privacy_param > 1.0{
-            println!("Large value of privacy parameter epsilon in use.");
         }
         Ok(())
     };
@@ -546,17 +1084,80 @@ pub fn privacy_usage_check(
         Distance::Approximate(x) => {
             check_epsilon(x.epsilon)?;
             check_delta(x.delta)?;
+        },
+        Distance::Concentrated(x) => if x.rho <= 0.0 {
+            return Err("Privacy parameter rho must be greater than 0.".into())
         }
     };
     Ok(())
 }
 
+/// Warnings for privacy parameters that are valid, but unusually large.
+///
+/// Property propagation collects these as warnings, rather than rejecting the usage as [privacy_usage_check](fn.privacy_usage_check.html) would.
+pub fn privacy_usage_warnings(
+    privacy: &proto::PrivacyUsage
+) -> Vec<proto::Error> {
+    match get_epsilon(privacy) {
+        Ok(epsilon) if epsilon > 1.0 => vec![proto::Error {
+            message: format!("Large value of privacy parameter epsilon in use: {}", epsilon)
+        }],
+        _ => Vec::new()
+    }
+}
+
+/// Significance level of each interval in a family of `family_size` intervals, so that all hold simultaneously at `alpha`.
+pub fn adjust_alpha(alpha: f64, family_size: usize, adjustment: proto::AlphaAdjustment) -> Result<f64> {
+    if !(alpha > 0. && alpha < 1.) {
+        return Err(format!("alpha ({}) must be within (0, 1)", alpha).into())
+    }
+    let family_size = family_size.max(1) as f64;
+    Ok(match adjustment {
+        proto::AlphaAdjustment::None => alpha,
+        proto::AlphaAdjustment::Bonferroni => alpha / family_size,
+        // computed in log space, as 1 - (1 - alpha)^(1 / m) cancels catastrophically for large families
+        proto::AlphaAdjustment::Sidak => -((-alpha).ln_1p() / family_size).exp_m1()
+    })
+}
+
+/// Number of records any one individual may contribute to each column, from the c-stability of the data.
+///
+/// Sensitivities derived for a single record are multiplied by these bounds.
+pub fn get_contribution_bounds(property: &ArrayProperties) -> Result<Vec<f64>> {
+    let num_columns = property.num_columns()? as usize;
+    Ok(match property.c_stability.len() {
+        length if length == num_columns => property.c_stability.clone(),
+        _ => vec![property.c_stability.iter().cloned().fold(1., f64::max); num_columns]
+    })
+}
+
+/// Number of records in which neighboring datasets may differ, under the group privacy of the privacy definition.
+///
+/// Sensitivities bound the change from a single record, and are multiplied by the group size before noise is calibrated to them.
+/// A group size of zero is taken as one.
+pub fn get_group_size(privacy_definition: &proto::PrivacyDefinition) -> f64 {
+    privacy_definition.group_size.max(1) as f64
+}
+
+/// Multiply a sensitivity by the group size of the privacy definition.
+pub fn scale_sensitivity(privacy_definition: &proto::PrivacyDefinition, sensitivity: Value) -> Result<Value> {
+    let group_size = privacy_definition.group_size.max(1);
+    if group_size == 1 {
+        return Ok(sensitivity)
+    }
+    Ok(match sensitivity.array()? {
+        crate::base::Array::F64(array) => array.mapv(|v| v * group_size as f64).into(),
+        crate::base::Array::I64(array) => array.mapv(|v| v.saturating_mul(group_size as i64)).into(),
+        _ => return Err("sensitivity must be numeric".into())
+    })
+}
+
 pub fn get_epsilon(usage: &proto::PrivacyUsage) -> Result<f64> {
     match usage.distance.clone()
         .ok_or_else(|| Error::from("distance must be defined on a PrivacyUsage"))? {
         proto::privacy_usage::Distance::Pure(distance) => Ok(distance.epsilon),
         proto::privacy_usage::Distance::Approximate(distance) => Ok(distance.epsilon),
-//        _ => Err("epsilon is not defined".into())
+        _ => Err("epsilon is not defined".into())
     }
 }
 
@@ -568,6 +1169,112 @@ pub fn get_delta(usage: &proto::PrivacyUsage) -> Result<f64> {
     }
 }
 
+/// Scale broadcasted privacy usages by the public budget fraction of a mechanism, if one is supplied.
+///
+/// The fraction may be a scalar, or contain one value per usage. Each value must lie in (0, 1].
+pub fn apply_budget_fraction(
+    usages: Vec<proto::PrivacyUsage>, budget_fraction: Option<&Value>
+) -> Result<Vec<proto::PrivacyUsage>> {
+    let budget_fraction = match budget_fraction {
+        Some(budget_fraction) => budget_fraction.array()?.vec_f64(Some(1))
+            .map_err(prepend("budget_fraction:"))?,
+        None => return Ok(usages)
+    };
+
+    if !budget_fraction.iter().all(|fraction| 0. < *fraction && *fraction <= 1.) {
+        return Err("budget_fraction: must be within (0, 1]".into())
+    }
+
+    let budget_fraction = match budget_fraction.len() {
+        1 => vec![budget_fraction[0]; usages.len()],
+        length if length == usages.len() => budget_fraction,
+        length => bail!("budget_fraction: {} values passed when {} were required", length, usages.len())
+    };
+
+    Ok(usages.iter().zip(budget_fraction.into_iter())
+        .map(|(usage, fraction)| privacy_usage_reducer(usage, usage, &|l, _| l * fraction))
+        .collect())
+}
+
+/// Check that the budget fraction of a mechanism, if supplied, is derived only from released values.
+///
+/// A budget fraction computed from an earlier release makes the composition adaptive.
+/// The fraction is then data-dependent, but remains a public function of released values.
+pub fn check_budget_fraction(
+    privacy_usage: &[proto::PrivacyUsage],
+    public_arguments: &HashMap<String, Value>,
+    properties: &NodeProperties,
+) -> Result<()> {
+    if let Some(property) = properties.get("budget_fraction") {
+        property.array().map_err(prepend("budget_fraction:"))?
+            .assert_is_releasable().map_err(prepend("budget_fraction:"))?;
+    }
+    match public_arguments.get("budget_fraction") {
+        Some(budget_fraction) if !privacy_usage.is_empty() => {
+            let length = budget_fraction.array()?.vec_f64(Some(1))
+                .map_err(prepend("budget_fraction:"))?.len().max(privacy_usage.len());
+            apply_budget_fraction(broadcast_privacy_usage(privacy_usage, length)?, Some(budget_fraction))?;
+        }
+        _ => ()
+    }
+    Ok(())
+}
+
+/// Check if the budget fraction of a mechanism depends on the output of another mechanism.
+pub fn is_adaptive(
+    graph: &HashMap<u32, proto::Component>,
+    node_id: &u32,
+) -> bool {
+    let mut traversal = match graph.get(node_id).and_then(|component| component.arguments.get("budget_fraction")) {
+        Some(budget_fraction_id) => vec![*budget_fraction_id],
+        None => return false
+    };
+    let mut visited = HashSet::new();
+
+    while let Some(node_id) = traversal.pop() {
+        if !visited.insert(node_id) {
+            continue
+        }
+        if let Some(component) = graph.get(&node_id) {
+            if is_privatizing(component) {
+                return true
+            }
+            traversal.extend(component.arguments.values());
+        }
+    }
+    false
+}
+
+/// The privacy usage charged against the budget for a node.
+///
+/// When the budget fraction of a mechanism depends on an earlier release, the realized usage is itself data-dependent.
+/// Summing realized usages is then not a valid composition bound, so the maximum usage permitted by the analysis is charged.
+pub fn get_charged_privacy_usage(
+    graph: &HashMap<u32, proto::Component>,
+    node_id: &u32,
+    release: &proto::Release,
+) -> Result<Option<proto::PrivacyUsage>> {
+    let component = match graph.get(node_id) {
+        Some(component) => component,
+        None => return Ok(None)
+    };
+    match is_adaptive(graph, node_id) {
+        true => get_component_privacy_usage(component, None),
+        false => get_component_privacy_usage(component, release.values.get(node_id))
+    }
+}
+
+/// The privacy usage charged against the budget for every privatizing node, in order of node id.
+pub fn get_charged_privacy_usages(
+    graph: &HashMap<u32, proto::Component>,
+    release: &proto::Release,
+) -> Result<Vec<(u32, proto::PrivacyUsage)>> {
+    Ok(graph.keys().sorted()
+        .map(|node_id| Ok(get_charged_privacy_usage(graph, node_id, release)?.map(|usage| (*node_id, usage))))
+        .collect::<Result<Vec<Option<(u32, proto::PrivacyUsage)>>>>()?
+        .into_iter().flatten().collect())
+}
+
 pub fn broadcast_privacy_usage(usages: &[proto::PrivacyUsage], length: usize) -> Result<Vec<proto::PrivacyUsage>> {
     if usages.len() == length {
         return Ok(usages.to_owned());
@@ -590,6 +1297,12 @@ pub fn broadcast_privacy_usage(usages: &[proto::PrivacyUsage], length: usize) ->
                     epsilon: approx.epsilon / (length as f64),
                     delta: approx.delta / (length as f64),
                 }))
+            }).collect(),
+        proto::privacy_usage::Distance::Concentrated(concentrated) => (0..length)
+            .map(|_| proto::PrivacyUsage {
+                distance: Some(proto::privacy_usage::Distance::Concentrated(proto::privacy_usage::DistanceConcentrated {
+                    rho: concentrated.rho / (length as f64)
+                }))
             }).collect()
     })
 }
@@ -616,6 +1329,10 @@ pub fn prepend(text: &str) -> impl Fn(Error) -> Error + '_ {
 
 
 /// Utility function for building component expansions for dp mechanisms
+///
+/// A single mechanism node privatizes every column of the aggregate.
+/// The sensitivities of all columns are computed at once, and attached as one literal,
+/// so the size of the expansion does not grow with the number of columns.
 pub fn expand_mechanism(
     sensitivity_type: &SensitivitySpace,
     privacy_definition: &proto::PrivacyDefinition,
@@ -687,10 +1404,405 @@ pub fn deduplicate<T: Eq + Hash + Ord + Clone>(values: Vec<T>) -> Vec<T> {
 #[cfg(test)]
 mod test_utilities {
     use crate::utilities;
+
+    #[test]
+    fn test_adjust_alpha() {
+        use crate::proto::AlphaAdjustment;
+        assert_eq!(utilities::adjust_alpha(0.05, 10, AlphaAdjustment::None).unwrap(), 0.05);
+        assert!((utilities::adjust_alpha(0.05, 10, AlphaAdjustment::Bonferroni).unwrap() - 0.005).abs() < 1e-15);
+
+        // Šidák is slightly less conservative than Bonferroni, and exact for independent intervals
+        let sidak = utilities::adjust_alpha(0.05, 10, AlphaAdjustment::Sidak).unwrap();
+        assert!(sidak > 0.005);
+        assert!((1. - (1. - sidak).powi(10) - 0.05).abs() < 1e-12);
+
+        assert!(utilities::adjust_alpha(0., 10, AlphaAdjustment::None).is_err());
+    }
     #[test]
     fn test_deduplicate() {
         let values = vec![2, 0, 1, 0];
         let deduplicated = utilities::deduplicate(values.clone());
         assert!(deduplicated == vec![2, 0, 1]);
     }
+
+    #[test]
+    fn test_check_terminal_nodes() {
+        use crate::proto;
+        use crate::hashmap;
+        use std::collections::HashMap;
+
+        let component = |arguments: HashMap<String, u32>, omit: bool| proto::Component {
+            arguments,
+            variant: Some(proto::component::Variant::Mean(proto::Mean {})),
+            omit,
+            batch: 0
+        };
+        let graph = hashmap![
+            1 => component(HashMap::new(), false),
+            2 => component(hashmap!["data".to_string() => 1], true)
+        ];
+
+        // private, and omitted
+        let errors = utilities::check_terminal_nodes(&graph, &HashMap::new()).unwrap_err().to_string();
+        assert!(errors.contains("node 2: omitted"));
+
+        let graph = hashmap![1 => component(HashMap::new(), false)];
+        let errors = utilities::check_terminal_nodes(&graph, &HashMap::new()).unwrap_err().to_string();
+        assert!(errors.contains("node 1: terminal nodes must be public"));
+    }
+
+    #[test]
+    fn test_traversal_order() {
+        use crate::proto;
+        use crate::hashmap;
+        use std::collections::HashMap;
+
+        let component = |arguments: HashMap<String, u32>| proto::Component {
+            arguments,
+            variant: Some(proto::component::Variant::Mean(proto::Mean {})),
+            omit: true,
+            batch: 0
+        };
+
+        // every hashmap is seeded differently, so the traversal must not depend on iteration order
+        for _ in 0..10 {
+            let graph = hashmap![
+                3 => component(HashMap::new()),
+                1 => component(HashMap::new()),
+                2 => component(HashMap::new()),
+                4 => component(hashmap!["left".to_string() => 1, "right".to_string() => 2])
+            ];
+            assert_eq!(utilities::get_traversal(&graph).unwrap(), vec![1, 2, 4, 3]);
+        }
+    }
+
+    #[test]
+    fn test_budget_fraction() {
+        use crate::proto;
+        use crate::base::Value;
+
+        let usage = proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Pure(proto::privacy_usage::DistancePure { epsilon: 1. }))
+        };
+        let scaled = utilities::apply_budget_fraction(vec![usage.clone(); 2], Some(&Value::from(0.25))).unwrap();
+        assert!(scaled.iter().all(|usage| utilities::get_epsilon(usage).unwrap() == 0.25));
+
+        assert!(utilities::apply_budget_fraction(vec![usage.clone()], Some(&Value::from(0.))).is_err());
+        assert!(utilities::apply_budget_fraction(vec![usage], Some(&Value::from(1.5))).is_err());
+    }
+
+    #[test]
+    fn test_expand_mechanism() {
+        use crate::proto;
+        use crate::hashmap;
+        use crate::base::{ArrayProperties, AggregatorProperties, DataType, Nature, NatureContinuous, SensitivitySpace, Vector1DNull};
+        use std::collections::HashMap;
+
+        let num_columns = 1000;
+        let data_property = ArrayProperties {
+            num_records: Some(100),
+            num_columns: Some(num_columns),
+            nullity: false,
+            releasable: false,
+            public_shape: false,
+            public_metadata: false,
+            c_stability: vec![1.; num_columns as usize],
+            aggregator: None,
+            nature: Some(Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::F64(vec![Some(0.); num_columns as usize]),
+                upper: Vector1DNull::F64(vec![Some(1.); num_columns as usize]),
+            })),
+            data_type: DataType::F64,
+            dataset_id: Some(0),
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 2
+        };
+        let mut mean_property = data_property.clone();
+        mean_property.aggregator = Some(AggregatorProperties {
+            component: proto::component::Variant::Mean(proto::Mean {}),
+            properties: hashmap!["data".to_string() => data_property.into()]
+        });
+
+        let component = proto::Component {
+            arguments: hashmap!["data".to_string() => 1],
+            variant: Some(proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+                privacy_usage: Vec::new()
+            })),
+            omit: false,
+            batch: 0
+        };
+        let privacy_definition = proto::PrivacyDefinition {
+            group_size: 1,
+            distance: proto::privacy_definition::Distance::Pure as i32,
+            neighboring: proto::privacy_definition::Neighboring::AddRemove as i32,
+            delta_cap: 0.,
+            delta_split: proto::privacy_definition::DeltaSplit::Equal as i32,
+            delta_allotments: HashMap::new(),
+            composition: proto::privacy_definition::Composition::Basic as i32,
+            composition_delta: 0.,
+            epsilon_floor: 0.,
+            epsilon_ceiling: 0.,
+            epsilon_exemptions: Vec::new(),
+            delta_inverse_records: false,
+            epsilon_cap: 0.,
+            enforce_budget: false
+        };
+
+        // a single mechanism node and sensitivity literal, regardless of the number of columns
+        let expansion = utilities::expand_mechanism(
+            &SensitivitySpace::KNorm(1), &privacy_definition, &component,
+            &hashmap!["data".to_string() => mean_property.into()], &2, &2).unwrap();
+        assert_eq!(expansion.computation_graph.len(), 2);
+        assert_eq!(expansion.releases.len(), 1);
+        let sensitivity = utilities::serial::parse_release_node(expansion.releases.values().next().unwrap()).unwrap();
+        assert_eq!(sensitivity.value.array().unwrap().f64().unwrap().len(), num_columns as usize);
+    }
+
+    #[test]
+    fn test_release_publicness() {
+        use crate::base::{ArrayProperties, DataType, ReleaseNode, Value};
+        use ndarray::{Array, Ix2};
+
+        let data_property = ArrayProperties {
+            num_records: None,
+            num_columns: Some(2),
+            nullity: false,
+            releasable: false,
+            public_shape: false,
+            public_metadata: false,
+            c_stability: vec![1.; 2],
+            aggregator: None,
+            nature: None,
+            data_type: DataType::F64,
+            dataset_id: Some(0),
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 2
+        };
+        let mut release_node = ReleaseNode::new(Value::from(Array::<f64, Ix2>::zeros((3, 2)).into_dyn()));
+
+        // a private release does not refine the properties
+        let properties = utilities::apply_release_publicness(data_property.clone().into(), &release_node).unwrap();
+        assert_eq!(properties.array().unwrap().num_records, None);
+
+        // a public shape fixes the number of records, while the values remain private
+        release_node.public_shape = true;
+        let properties = utilities::apply_release_publicness(data_property.into(), &release_node).unwrap();
+        let properties = properties.array().unwrap();
+        assert_eq!(properties.num_records, Some(3));
+        assert!(properties.publicness().shape && !properties.publicness().values && !properties.publicness().metadata);
+        assert!(properties.assert_shape_is_public().is_ok() && properties.assert_metadata_is_public().is_err());
+    }
+
+    #[test]
+    fn test_delta_allotments() {
+        use crate::proto;
+        use crate::hashmap;
+        use crate::errors::ErrorKind;
+        use std::collections::HashMap;
+        use proto::privacy_definition::DeltaSplit;
+
+        let mechanism = |epsilon: f64, delta: f64| proto::Component {
+            arguments: HashMap::new(),
+            variant: Some(proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                privacy_usage: vec![proto::PrivacyUsage {
+                    distance: Some(proto::privacy_usage::Distance::Approximate(
+                        proto::privacy_usage::DistanceApproximate { epsilon, delta }))
+                }]
+            })),
+            omit: false,
+            batch: 0
+        };
+        let graph = hashmap![1 => mechanism(1., 1e-6), 2 => mechanism(3., 1e-6)];
+        let release = proto::Release { values: HashMap::new() };
+        let mut privacy_definition = proto::PrivacyDefinition {
+            group_size: 1,
+            distance: proto::privacy_definition::Distance::Approximate as i32,
+            neighboring: proto::privacy_definition::Neighboring::AddRemove as i32,
+            delta_cap: 0.,
+            delta_split: DeltaSplit::Equal as i32,
+            delta_allotments: HashMap::new(),
+            composition: proto::privacy_definition::Composition::Basic as i32,
+            composition_delta: 0.,
+            epsilon_floor: 0.,
+            epsilon_ceiling: 0.,
+            epsilon_exemptions: Vec::new(),
+            delta_inverse_records: false,
+            epsilon_cap: 0.,
+            enforce_budget: false
+        };
+
+        // uncapped
+        assert!(utilities::check_delta_allotments(&privacy_definition, &graph, &release).is_ok());
+
+        privacy_definition.delta_cap = 2e-6;
+        assert!(utilities::check_delta_allotments(&privacy_definition, &graph, &release).is_ok());
+
+        // node 1 is allotted a quarter of the cap
+        privacy_definition.delta_split = DeltaSplit::Proportional as i32;
+        match utilities::check_delta_allotments(&privacy_definition, &graph, &release).unwrap_err().kind() {
+            ErrorKind::DeltaAllotmentExceeded(violations) => assert_eq!(violations.iter()
+                .map(|(node_id, _, _)| *node_id).collect::<Vec<u32>>(), vec![1]),
+            _ => panic!("expected a delta allotment violation")
+        }
+
+        privacy_definition.delta_split = DeltaSplit::UserSpecified as i32;
+        privacy_definition.delta_allotments = hashmap![1 => 0.5, 2 => 0.5];
+        assert!(utilities::check_delta_allotments(&privacy_definition, &graph, &release).is_ok());
+        privacy_definition.delta_allotments = hashmap![1 => 0.5];
+        assert!(utilities::check_delta_allotments(&privacy_definition, &graph, &release).is_err());
+        privacy_definition.delta_allotments = hashmap![1 => 0.75, 2 => 0.75];
+        assert!(utilities::check_delta_allotments(&privacy_definition, &graph, &release).is_err());
+
+        // the total delta is tracked independently of the allotments
+        let properties = HashMap::new();
+        assert!(utilities::check_delta_budget(&privacy_definition, &graph, &properties, &release).is_ok());
+        privacy_definition.delta_cap = 1e-6;
+        match utilities::check_delta_budget(&privacy_definition, &graph, &properties, &release).unwrap_err().kind() {
+            ErrorKind::DeltaBudgetExceeded(total, budget) => assert!(*total > *budget),
+            _ => panic!("expected a delta budget violation")
+        }
+
+        // the number of records is unknown, so the inverse cannot be bounded
+        privacy_definition.delta_cap = 0.;
+        privacy_definition.delta_inverse_records = true;
+        assert!(utilities::check_delta_budget(&privacy_definition, &graph, &properties, &release).is_err());
+    }
+
+    #[test]
+    fn test_budget_enforcement() {
+        use crate::proto;
+        use crate::errors::ErrorKind;
+
+        let usage = |epsilon: f64, delta: f64| proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(
+                proto::privacy_usage::DistanceApproximate { epsilon, delta }))
+        };
+        let privacy_definition = proto::PrivacyDefinition {
+            epsilon_cap: 1.,
+            delta_cap: 1e-6,
+            enforce_budget: true,
+            ..Default::default()
+        };
+
+        assert!(utilities::check_budget(&privacy_definition, 1, None, &usage(0.5, 0.)).is_ok());
+        assert!(utilities::check_budget(&privacy_definition, 1, Some(&usage(0.5, 0.)), &usage(0.5, 1e-6)).is_ok());
+        match utilities::check_budget(&privacy_definition, 2, Some(&usage(0.75, 0.)), &usage(0.5, 0.)).unwrap_err().kind() {
+            ErrorKind::BudgetExhausted(node_id, _) => assert_eq!(*node_id, 2),
+            _ => panic!("expected the budget to be exhausted")
+        }
+        assert!(utilities::check_budget(&privacy_definition, 3, Some(&usage(0.1, 1e-6)), &usage(0.1, 1e-6)).is_err());
+    }
+
+    fn contribution_chain(bound: bool, max_contributions_per_individual: u32) -> std::collections::HashMap<u32, crate::proto::Component> {
+        use crate::proto;
+        use crate::hashmap;
+        use std::collections::HashMap;
+
+        let component = |arguments: HashMap<String, u32>, variant: proto::component::Variant| proto::Component {
+            arguments, variant: Some(variant), omit: false, batch: 0
+        };
+        let mut graph = hashmap![
+            1 => component(HashMap::new(), proto::component::Variant::Materialize(proto::Materialize {
+                data_source: Some(proto::DataSource { value: None, max_contributions_per_individual }),
+                ..Default::default()
+            })),
+            2 => component(hashmap!["data".to_string() => 1], proto::component::Variant::Mean(proto::Mean {})),
+            3 => component(hashmap!["data".to_string() => 2], proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+                privacy_usage: Vec::new()
+            }))
+        ];
+        if bound {
+            graph.insert(4, component(hashmap!["data".to_string() => 1],
+                                      proto::component::Variant::BoundContributions(proto::BoundContributions { max_contributions: 1 })));
+            graph.get_mut(&2).unwrap().arguments.insert("data".to_string(), 4);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_bounded_contributions() {
+        // the contributions are bounded by a component, or by the data source
+        assert!(utilities::check_contribution_bound(&contribution_chain(true, 0), &3).is_ok());
+        assert!(utilities::check_contribution_bound(&contribution_chain(false, 2), &3).is_ok());
+        assert!(utilities::check_contribution_bounds(&contribution_chain(true, 0)).is_ok());
+    }
+
+    #[test]
+    fn test_unbounded_contributions() {
+        let graph = contribution_chain(false, 0);
+        assert_eq!(utilities::get_unbounded_sources(&graph, &3), vec![1]);
+        assert!(utilities::check_contribution_bound(&graph, &3).is_err());
+        assert!(utilities::check_contribution_bounds(&graph).is_err());
+    }
+
+    #[test]
+    fn test_epsilon_bounds() {
+        use crate::proto;
+        use crate::errors::ErrorKind;
+        use std::collections::HashMap;
+
+        let mechanism = |epsilon: f64| proto::Component {
+            arguments: HashMap::new(),
+            variant: Some(proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+                privacy_usage: vec![proto::PrivacyUsage {
+                    distance: Some(proto::privacy_usage::Distance::Pure(proto::privacy_usage::DistancePure { epsilon }))
+                }]
+            })),
+            omit: false,
+            batch: 0
+        };
+        let mut privacy_definition = proto::PrivacyDefinition {
+            epsilon_floor: 0.01,
+            epsilon_ceiling: 10.,
+            ..Default::default()
+        };
+
+        assert!(utilities::check_epsilon_bounds(&privacy_definition, &mechanism(1.), 1).is_ok());
+        assert!(utilities::check_epsilon_bounds(&privacy_definition, &mechanism(1e-9), 1).is_err());
+        match utilities::check_epsilon_bounds(&privacy_definition, &mechanism(100.), 2).unwrap_err().kind() {
+            ErrorKind::EpsilonOutOfBounds(node_id, _, _, ceiling) => assert!(*node_id == 2 && *ceiling > 0.),
+            _ => panic!("expected an epsilon out of bounds")
+        }
+
+        // a reviewed mechanism may be exempted from the policy
+        privacy_definition.epsilon_exemptions = vec![2];
+        assert!(utilities::check_epsilon_bounds(&privacy_definition, &mechanism(100.), 2).is_ok());
+    }
+
+    #[test]
+    fn test_sgd_privacy_usage() {
+        use crate::proto;
+        use std::collections::HashMap;
+
+        let sgd = |noise_multiplier: f64| proto::Component {
+            arguments: HashMap::new(),
+            variant: Some(proto::component::Variant::DpStochasticGradientDescent(proto::DpStochasticGradientDescent {
+                noise_multiplier, sampling_rate: 0.01, steps: 100, delta: 1e-5, ..Default::default()
+            })),
+            omit: false,
+            batch: 0
+        };
+        assert!(utilities::get_component_privacy_usage(&sgd(1.1), None).unwrap().is_some());
+
+        // a failure to account for the usage is an error, rather than a component that does not privatize
+        assert!(utilities::get_component_privacy_usage(&sgd(0.), None).is_err());
+        assert!(utilities::is_privatizing(&sgd(0.)));
+    }
+
+    #[test]
+    fn test_group_size() {
+        use crate::proto;
+        use ndarray::arr1;
+
+        let mut privacy_definition = proto::PrivacyDefinition::default();
+        let sensitivity = utilities::scale_sensitivity(&privacy_definition, arr1(&[1., 2.]).into_dyn().into()).unwrap();
+        assert_eq!(sensitivity.array().unwrap().f64().unwrap(), &arr1(&[1., 2.]).into_dyn());
+
+        // neighboring datasets differ in three rows
+        privacy_definition.group_size = 3;
+        let sensitivity = utilities::scale_sensitivity(&privacy_definition, arr1(&[1_i64, 2]).into_dyn().into()).unwrap();
+        assert_eq!(sensitivity.array().unwrap().i64().unwrap(), &arr1(&[3_i64, 6]).into_dyn());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,334 @@
+//! Optimization passes over computation graphs
+//!
+//! Passes rewrite the computation graph of an analysis before it is expanded,
+//! so that validation and accounting only visit the components that contribute to the release.
+//! Each pass is enabled by the optimizations of the analysis, and preserves the ids of the components it keeps.
+//! The runtime applies the same passes before evaluating, so that it releases exactly the components that were accounted.
+//!
+//! Constant folding is always applied while expanding, so that components downstream of public transforms see concrete public arguments.
+
+use std::collections::HashMap;
+
+use crate::proto;
+use crate::base::{Value, Array};
+use crate::utilities::{is_privatizing, has_privacy_usage};
+use crate::utilities::slice::get_ancestors;
+
+use itertools::Itertools;
+use ndarray::ArrayD;
+
+/// Apply the passes enabled by the optimizations of an analysis, in the same order in the validator and the runtime.
+///
+/// # Returns
+/// Warnings that list the ids of the components each pass removed.
+pub fn optimize_graph(
+    graph: &mut HashMap<u32, proto::Component>,
+    release: &proto::Release,
+    optimizations: Option<&proto::Optimizations>,
+) -> Vec<proto::Error> {
+    let optimizations = optimizations.cloned().unwrap_or_default();
+    let mut warnings = Vec::new();
+
+    if optimizations.eliminate_common_subexpressions {
+        let merged_ids = eliminate_common_subexpressions(graph, release);
+        if !merged_ids.is_empty() {
+            warnings.push(proto::Error { message: format!("node ids {:?} were merged into identical components", merged_ids) });
+        }
+    }
+    if optimizations.eliminate_dead_nodes {
+        let dead_ids = eliminate_dead_nodes(graph, release);
+        if !dead_ids.is_empty() {
+            warnings.push(proto::Error { message: format!("node ids {:?} were removed, as no release depends on them", dead_ids) });
+        }
+    }
+    warnings
+}
+
+/// Remove the components that no released, non-omitted or charged node depends on.
+///
+/// Components with a privacy usage are always kept, even if omitted, as the runtime still evaluates them and they are charged their privacy usage.
+/// This includes aggregates like DpMean that have not yet expanded into their mechanisms.
+///
+/// # Returns
+/// The ids of the removed components, in ascending order.
+pub fn eliminate_dead_nodes(
+    graph: &mut HashMap<u32, proto::Component>,
+    release: &proto::Release,
+) -> Vec<u32> {
+    let roots = graph.iter()
+        .filter(|(node_id, component)| !component.omit
+            || release.values.contains_key(node_id)
+            || is_charged(component))
+        .map(|(node_id, _)| *node_id)
+        .collect::<Vec<u32>>();
+    let live = get_ancestors(graph, &roots);
+
+    let dead = graph.keys()
+        .filter(|node_id| !live.contains(node_id))
+        .cloned().sorted().collect::<Vec<u32>>();
+    dead.iter().for_each(|node_id| { graph.remove(node_id); });
+    dead
+}
+
+/// Merge components that apply the same operation to the same arguments, so that shared preprocessing is only evaluated once.
+///
+/// Released components, and components that are charged a privacy usage, are never merged, as each is a separate release.
+/// This includes aggregates like DpMean that have not yet expanded into their mechanisms.
+/// Duplicates are merged into the component with the smallest id, which stays omitted only if every duplicate was omitted.
+/// Merging repeats until no duplicates remain, so that identical chains of components collapse into one.
+///
+/// # Returns
+/// The ids of the removed components, in ascending order.
+pub fn eliminate_common_subexpressions(
+    graph: &mut HashMap<u32, proto::Component>,
+    release: &proto::Release,
+) -> Vec<u32> {
+    let mut removed = Vec::new();
+    loop {
+        // the component with the smallest id among its duplicates, for each duplicate
+        let replacements = {
+            let mut representatives: Vec<(u32, &proto::Component)> = Vec::new();
+            let mut replacements = HashMap::<u32, u32>::new();
+            graph.iter()
+                .filter(|(node_id, component)| !is_charged(component) && !release.values.contains_key(node_id))
+                .sorted_by_key(|(node_id, _)| *node_id)
+                .for_each(|(node_id, component)| match representatives.iter()
+                    .find(|(_, representative)| is_equivalent(representative, component)) {
+                    Some((representative_id, _)) => { replacements.insert(*node_id, *representative_id); },
+                    None => representatives.push((*node_id, component))
+                });
+            replacements
+        };
+        if replacements.is_empty() {
+            break
+        }
+
+        replacements.iter().for_each(|(node_id, representative_id)| {
+            let omit = graph.remove(node_id).map(|component| component.omit).unwrap_or(true);
+            if let Some(representative) = graph.get_mut(representative_id) {
+                representative.omit &= omit;
+            }
+        });
+        graph.values_mut().for_each(|component| component.arguments.values_mut()
+            .for_each(|argument_id| if let Some(representative_id) = replacements.get(argument_id) {
+                *argument_id = *representative_id
+            }));
+        removed.extend(replacements.keys());
+    }
+    removed.sort_unstable();
+    removed
+}
+
+/// Evaluate an elementwise transform over public arguments, as the runtime would.
+///
+/// Only transforms whose arguments all share the same shape are folded, and integer arithmetic that would overflow is left to the runtime.
+///
+/// # Returns
+/// The value of the component, or None if the component can't be folded.
+pub fn fold_constant(component: &proto::Component, public_arguments: &HashMap<String, Value>) -> Option<Value> {
+    use proto::component::Variant;
+
+    if component.arguments.keys().any(|name| !public_arguments.contains_key(name)) {
+        return None
+    }
+    let unary = || match public_arguments.get("data")? {
+        Value::Array(data) => Some(data),
+        _ => None
+    };
+    let binary = || match (public_arguments.get("left")?, public_arguments.get("right")?) {
+        (Value::Array(left), Value::Array(right)) if left.shape() == right.shape() => Some((left, right)),
+        _ => None
+    };
+
+    Some(Value::Array(match component.variant.as_ref()? {
+        Variant::Abs(_) => match unary()? {
+            Array::F64(data) => Array::F64(data.mapv(f64::abs)),
+            Array::I64(data) => Array::I64(map_checked(data, |v| v.checked_abs())?),
+            _ => return None
+        },
+        Variant::Negative(_) => match unary()? {
+            Array::F64(data) => Array::F64(data.mapv(|v| -v)),
+            Array::I64(data) => Array::I64(map_checked(data, |v| v.checked_neg())?),
+            _ => return None
+        },
+        Variant::Negate(_) => match unary()? {
+            Array::Bool(data) => Array::Bool(data.mapv(|v| !v)),
+            _ => return None
+        },
+        Variant::Add(_) => match binary()? {
+            (Array::F64(left), Array::F64(right)) => Array::F64(zip_checked(left, right, |l, r| Some(l + r))?),
+            (Array::I64(left), Array::I64(right)) => Array::I64(zip_checked(left, right, |l, r| l.checked_add(*r))?),
+            (Array::Str(left), Array::Str(right)) => Array::Str(zip_checked(left, right, |l, r| Some(format!("{}{}", l, r)))?),
+            _ => return None
+        },
+        Variant::Subtract(_) => match binary()? {
+            (Array::F64(left), Array::F64(right)) => Array::F64(zip_checked(left, right, |l, r| Some(l - r))?),
+            (Array::I64(left), Array::I64(right)) => Array::I64(zip_checked(left, right, |l, r| l.checked_sub(*r))?),
+            _ => return None
+        },
+        Variant::Multiply(_) => match binary()? {
+            (Array::F64(left), Array::F64(right)) => Array::F64(zip_checked(left, right, |l, r| Some(l * r))?),
+            (Array::I64(left), Array::I64(right)) => Array::I64(zip_checked(left, right, |l, r| l.checked_mul(*r))?),
+            _ => return None
+        },
+        // integer division by zero is imputed at random by the runtime, so only floats are folded
+        Variant::Divide(_) => match binary()? {
+            (Array::F64(left), Array::F64(right)) => Array::F64(zip_checked(left, right, |l, r| Some(l / r))?),
+            _ => return None
+        },
+        Variant::LogicalAnd(_) => match binary()? {
+            (Array::Bool(left), Array::Bool(right)) => Array::Bool(zip_checked(left, right, |l, r| Some(*l && *r))?),
+            _ => return None
+        },
+        Variant::LogicalOr(_) => match binary()? {
+            (Array::Bool(left), Array::Bool(right)) => Array::Bool(zip_checked(left, right, |l, r| Some(*l || *r))?),
+            _ => return None
+        },
+        Variant::Equal(_) => Array::Bool(match binary()? {
+            (Array::Bool(left), Array::Bool(right)) => zip_checked(left, right, |l, r| Some(l == r))?,
+            (Array::I64(left), Array::I64(right)) => zip_checked(left, right, |l, r| Some(l == r))?,
+            (Array::F64(left), Array::F64(right)) => zip_checked(left, right, |l, r| Some(l == r))?,
+            (Array::Str(left), Array::Str(right)) => zip_checked(left, right, |l, r| Some(l == r))?,
+            _ => return None
+        }),
+        _ => return None
+    }))
+}
+
+fn map_checked<T, U>(data: &ArrayD<T>, function: impl Fn(&T) -> Option<U>) -> Option<ArrayD<U>> {
+    ArrayD::from_shape_vec(data.shape(), data.iter().map(function).collect::<Option<Vec<U>>>()?).ok()
+}
+
+fn zip_checked<T, U>(left: &ArrayD<T>, right: &ArrayD<T>, function: impl Fn(&T, &T) -> Option<U>) -> Option<ArrayD<U>> {
+    ArrayD::from_shape_vec(left.shape(), left.iter().zip(right.iter())
+        .map(|(l, r)| function(l, r)).collect::<Option<Vec<U>>>()?).ok()
+}
+
+/// Whether the component is charged a privacy usage, either as a mechanism or by expanding into mechanisms.
+fn is_charged(component: &proto::Component) -> bool {
+    is_privatizing(component) || has_privacy_usage(component)
+}
+
+/// Whether two components evaluate to the same value, regardless of whether they are omitted.
+fn is_equivalent(left: &proto::Component, right: &proto::Component) -> bool {
+    left.variant.is_some() && left.variant == right.variant
+        && left.arguments == right.arguments
+        && left.batch == right.batch
+}
+
+
+#[cfg(test)]
+mod test_optimize {
+    use crate::proto;
+    use crate::hashmap;
+    use crate::utilities::optimize::{eliminate_dead_nodes, eliminate_common_subexpressions, fold_constant};
+    use crate::base::{Value, Array};
+    use std::collections::HashMap;
+    use itertools::Itertools;
+    use ndarray::arr1;
+
+    fn component(arguments: HashMap<String, u32>, variant: proto::component::Variant, omit: bool) -> proto::Component {
+        proto::Component { arguments, variant: Some(variant), omit, batch: 0 }
+    }
+
+    #[test]
+    fn test_eliminate_dead_nodes() {
+        let mut graph = hashmap![
+            1 => component(HashMap::new(), proto::component::Variant::Mean(proto::Mean {}), true),
+            2 => component(hashmap!["data".to_string() => 1], proto::component::Variant::LaplaceMechanism(
+                proto::LaplaceMechanism { privacy_usage: Vec::new() }), true),
+            // an orphaned chain of omitted components
+            3 => component(HashMap::new(), proto::component::Variant::Mean(proto::Mean {}), true),
+            4 => component(hashmap!["data".to_string() => 3], proto::component::Variant::Mean(proto::Mean {}), true),
+            // a non-omitted component is kept
+            5 => component(HashMap::new(), proto::component::Variant::Mean(proto::Mean {}), false),
+            // an omitted aggregate is charged before it expands into a mechanism, so it is kept along with its data
+            6 => component(HashMap::new(), proto::component::Variant::Mean(proto::Mean {}), true),
+            7 => component(hashmap!["data".to_string() => 6], proto::component::Variant::DpMean(proto::DpMean {
+                implementation: "resized".to_string(), mechanism: "Laplace".to_string(), privacy_usage: Vec::new()
+            }), true)
+        ];
+
+        assert_eq!(eliminate_dead_nodes(&mut graph, &proto::Release { values: HashMap::new() }), vec![3, 4]);
+        assert_eq!(graph.keys().cloned().sorted().collect::<Vec<u32>>(), vec![1, 2, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_eliminate_common_subexpressions() {
+        let mut graph = hashmap![
+            1 => component(HashMap::new(), proto::component::Variant::Mean(proto::Mean {}), true),
+            // two identical chains over the same source
+            2 => component(hashmap!["data".to_string() => 1], proto::component::Variant::Mean(proto::Mean {}), true),
+            3 => component(hashmap!["data".to_string() => 1], proto::component::Variant::Mean(proto::Mean {}), false),
+            4 => component(hashmap!["data".to_string() => 2], proto::component::Variant::Mean(proto::Mean {}), true),
+            5 => component(hashmap!["data".to_string() => 3], proto::component::Variant::Mean(proto::Mean {}), true),
+            // mechanisms are separate releases, even over the same data
+            6 => component(hashmap!["data".to_string() => 4], proto::component::Variant::LaplaceMechanism(
+                proto::LaplaceMechanism { privacy_usage: Vec::new() }), false),
+            7 => component(hashmap!["data".to_string() => 5], proto::component::Variant::LaplaceMechanism(
+                proto::LaplaceMechanism { privacy_usage: Vec::new() }), false)
+        ];
+
+        assert_eq!(eliminate_common_subexpressions(&mut graph, &proto::Release { values: HashMap::new() }), vec![3, 5]);
+        assert!(!graph.get(&2).unwrap().omit);
+        assert_eq!(graph.get(&7).unwrap().arguments.get("data"), Some(&4));
+        assert_eq!(graph.len(), 5);
+    }
+
+    #[test]
+    fn test_eliminate_common_subexpressions_charged() {
+        let dp_mean = |data_id: u32| component(hashmap!["data".to_string() => data_id],
+            proto::component::Variant::DpMean(proto::DpMean {
+                implementation: "resized".to_string(),
+                mechanism: "Laplace".to_string(),
+                privacy_usage: vec![proto::PrivacyUsage {
+                    distance: Some(proto::privacy_usage::Distance::Pure(proto::privacy_usage::DistancePure { epsilon: 1. }))
+                }]
+            }), false);
+        let mut graph = hashmap![
+            1 => component(HashMap::new(), proto::component::Variant::Mean(proto::Mean {}), true),
+            // identical aggregates are charged and released separately, even before they expand into mechanisms
+            2 => dp_mean(1),
+            3 => dp_mean(1)
+        ];
+
+        assert!(eliminate_common_subexpressions(&mut graph, &proto::Release { values: HashMap::new() }).is_empty());
+        assert_eq!(graph.len(), 3);
+    }
+
+    #[test]
+    fn test_fold_constant() {
+        let add = component(hashmap!["left".to_string() => 1, "right".to_string() => 2],
+                            proto::component::Variant::Add(proto::Add {}), true);
+        let value = |values: &[i64]| Value::Array(Array::I64(arr1(values).into_dyn()));
+
+        match fold_constant(&add, &hashmap!["left".to_string() => value(&[1, 2]), "right".to_string() => value(&[3, 4])]) {
+            Some(Value::Array(Array::I64(sum))) => assert_eq!(sum, arr1(&[4, 6]).into_dyn()),
+            _ => panic!("the sum of public integers must be folded")
+        }
+
+        // overflow, mismatched shapes and private arguments are left to the runtime
+        assert!(fold_constant(&add, &hashmap!["left".to_string() => value(&[i64::MAX]), "right".to_string() => value(&[1])]).is_none());
+        assert!(fold_constant(&add, &hashmap!["left".to_string() => value(&[1, 2]), "right".to_string() => value(&[3])]).is_none());
+        assert!(fold_constant(&add, &hashmap!["left".to_string() => value(&[1])]).is_none());
+    }
+
+    #[test]
+    fn test_fold_logical() {
+        let arguments = hashmap!["left".to_string() => 1, "right".to_string() => 2];
+        let value = |values: &[bool]| Value::Array(Array::Bool(arr1(values).into_dyn()));
+        let public_arguments = hashmap![
+            "left".to_string() => value(&[true, true, false]),
+            "right".to_string() => value(&[true, false, false])
+        ];
+
+        match fold_constant(&component(arguments.clone(), proto::component::Variant::LogicalAnd(proto::And {}), true), &public_arguments) {
+            Some(Value::Array(Array::Bool(and))) => assert_eq!(and, arr1(&[true, false, false]).into_dyn()),
+            _ => panic!("the conjunction of public booleans must be folded")
+        }
+        match fold_constant(&component(arguments, proto::component::Variant::LogicalOr(proto::Or {}), true), &public_arguments) {
+            Some(Value::Array(Array::Bool(or))) => assert_eq!(or, arr1(&[true, true, false]).into_dyn()),
+            _ => panic!("the disjunction of public booleans must be folded")
+        }
+    }
+}
@@ -0,0 +1,700 @@
+//! Privacy accounting from the perspective of a single individual
+//!
+//! The linear total sums the usage of every mechanism.
+//! An individual whose records fall into one cell of a disjoint partition is only exposed to the mechanisms over that cell,
+//! so their worst-case loss may be much smaller than the total.
+//! The worst-case loss is the privacy usage of an analysis under basic composition.
+//!
+//! Under zero-concentrated differential privacy, usages are measured in rho, which also composes by summation.
+//! Rényi curves of mechanisms likewise compose by summation at each order, and are converted to (epsilon, delta) when reported.
+
+use crate::errors::*;
+
+use std::collections::{HashMap, BTreeMap};
+
+use crate::proto;
+use crate::base::ValueProperties;
+use crate::utilities::{get_charged_privacy_usages, privacy_usage_reducer, get_epsilon, get_delta};
+use crate::utilities::accounting::node_usages;
+use crate::utilities::tradeoff::gaussian_noise_multiplier;
+use crate::utilities::serial::parse_value;
+use crate::components::dp_stochastic_gradient_descent::sampled_gaussian_rdp;
+use crate::utilities::json::{value_to_json, privacy_usage_to_json};
+
+use itertools::Itertools;
+
+/// A cell of a disjoint partition: the id of the Partition node, and the serialized key of the cell.
+pub type Cell = (u32, String);
+
+/// Worst-case privacy usage of any one individual, within the cells of each disjoint partition.
+pub struct IndividualPrivacyUsage {
+    /// Privacy usage summed over every mechanism, without parallel composition.
+    pub total: Option<proto::PrivacyUsage>,
+    /// Worst-case cumulative privacy usage of any one individual.
+    pub worst_case: Option<proto::PrivacyUsage>,
+    /// Worst-case usage contributed by each outermost partition, keyed by the Partition node id.
+    pub partitions: BTreeMap<u32, PartitionUsage>,
+}
+
+pub struct PartitionUsage {
+    pub num_cells: usize,
+    /// Maximum number of cells a single individual may contribute to.
+    pub contribution_bound: u32,
+    pub privacy_usage: Option<proto::PrivacyUsage>,
+}
+
+/// Compute the worst-case privacy usage of any one individual over an expanded computation graph.
+///
+/// Mechanisms whose data is drawn from a single cell of a disjoint partition compose in parallel with the other cells.
+/// An individual may contribute to as many cells as the c-stability of the partitioned data,
+/// so the loss within a partition is bounded by that many times the loss of the most expensive cell.
+/// Any mechanism whose lineage cannot be attributed to a single cell is charged to every individual.
+pub fn individual_privacy_usage(
+    graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, ValueProperties>,
+    release: &proto::Release,
+) -> Result<IndividualPrivacyUsage> {
+    let usages = get_usages_by_cell(graph, properties, release)?;
+    let contribution_bounds = get_contribution_bounds(graph, properties)?;
+
+    let total = usages.iter().map(|(_, usage)| usage.clone())
+        .fold1(|l, r| privacy_usage_reducer(&l, &r, &|l, r| l + r));
+
+    let mut partitions = BTreeMap::new();
+    let worst_case = worst_case_usage(&usages, &contribution_bounds, &mut Some(&mut partitions));
+
+    Ok(IndividualPrivacyUsage { total, worst_case, partitions })
+}
+
+impl IndividualPrivacyUsage {
+    pub fn to_json(&self) -> serde_json::Value {
+        let usage_to_json = |usage: &Option<proto::PrivacyUsage>| usage.as_ref()
+            .map(privacy_usage_to_json).unwrap_or(serde_json::Value::Null);
+
+        serde_json::json!({
+            "total": usage_to_json(&self.total),
+            "worstCase": usage_to_json(&self.worst_case),
+            "partitions": self.partitions.iter()
+                .map(|(node_id, partition)| serde_json::json!({
+                    "nodeID": node_id,
+                    "numCells": partition.num_cells,
+                    "contributionBound": partition.contribution_bound,
+                    "privacyLoss": usage_to_json(&partition.privacy_usage)
+                }))
+                .collect::<Vec<serde_json::Value>>()
+        })
+    }
+}
+
+/// Privacy usage absorbed by each cell of the disjoint partitions of an analysis.
+pub struct PartitionedPrivacyUsage {
+    /// Privacy usage of the mechanisms that are not drawn from a single cell, absorbed by every individual.
+    pub unpartitioned: Option<proto::PrivacyUsage>,
+    /// Worst-case usage of any one individual in each cell, keyed by the path of cells from the outermost partition.
+    pub cells: BTreeMap<Vec<Cell>, Option<proto::PrivacyUsage>>,
+}
+
+/// Compute the privacy usage absorbed by each cell of the disjoint partitions of an expanded computation graph.
+///
+/// The usage of a cell is the worst-case usage of an individual whose records fall into the cell,
+/// from the mechanisms drawn from within the cell, including those over the cells of nested partitions.
+/// Mechanisms that cannot be attributed to a single cell are reported as unpartitioned.
+pub fn partitioned_privacy_usage(
+    graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, ValueProperties>,
+    release: &proto::Release,
+) -> Result<PartitionedPrivacyUsage> {
+    let usages = get_usages_by_cell(graph, properties, release)?;
+    let contribution_bounds = get_contribution_bounds(graph, properties)?;
+
+    let unpartitioned = usages.iter()
+        .filter(|(path, _)| path.is_empty())
+        .map(|(_, usage)| usage.clone())
+        .fold1(|l, r| privacy_usage_reducer(&l, &r, &|l, r| l + r));
+
+    Ok(PartitionedPrivacyUsage { unpartitioned, cells: cell_usages(&usages, &contribution_bounds) })
+}
+
+impl PartitionedPrivacyUsage {
+    pub fn to_json(&self) -> serde_json::Value {
+        let usage_to_json = |usage: &Option<proto::PrivacyUsage>| usage.as_ref()
+            .map(privacy_usage_to_json).unwrap_or(serde_json::Value::Null);
+
+        serde_json::json!({
+            "unpartitioned": usage_to_json(&self.unpartitioned),
+            "cells": self.cells.iter()
+                .map(|(path, usage)| serde_json::json!({
+                    "path": path.iter()
+                        .map(|(partition_id, key)| serde_json::json!({
+                            "nodeID": partition_id,
+                            "key": serde_json::from_str::<serde_json::Value>(key)
+                                .unwrap_or_else(|_| serde_json::Value::String(key.clone()))
+                        }))
+                        .collect::<Vec<serde_json::Value>>(),
+                    "privacyLoss": usage_to_json(usage)
+                }))
+                .collect::<Vec<serde_json::Value>>()
+        })
+    }
+}
+
+/// Privacy usage charged to every privatizing node of an expanded computation graph, keyed by node id.
+///
+/// Usages are taken from the release, else from the analysis, as in compute_privacy_usage.
+/// A node that releases a vector of statistics is also broken down into the usage of each column.
+pub fn node_privacy_usages(
+    graph: &HashMap<u32, proto::Component>,
+    release: &proto::Release,
+) -> Result<HashMap<u32, proto::NodePrivacyUsage>> {
+    Ok(graph.keys()
+        .map(|node_id| Ok((*node_id, node_usages(graph, node_id, release)?)))
+        .collect::<Result<Vec<(u32, Option<Vec<proto::PrivacyUsage>>)>>>()?.into_iter()
+        .filter_map(|(node_id, usages)| {
+            let usages = usages?;
+            let total = usages.iter().cloned()
+                .fold1(|l, r| privacy_usage_reducer(&l, &r, &|l, r| l + r))?;
+            Some((node_id, proto::NodePrivacyUsage {
+                total: Some(total),
+                columns: match usages.len() {
+                    1 => Vec::new(),
+                    _ => usages
+                }
+            }))
+        })
+        .collect())
+}
+
+/// Charged usage of every privatizing node, alongside the cells it is drawn from, in order of node id.
+fn get_usages_by_cell(
+    graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, ValueProperties>,
+    release: &proto::Release,
+) -> Result<Vec<(Vec<Cell>, proto::PrivacyUsage)>> {
+    get_charged_privacy_usages(graph, release)?.into_iter()
+        .map(|(node_id, usage)| Ok((get_cells(graph, properties, release, node_id)?, usage)))
+        .collect()
+}
+
+/// Maximum number of cells an individual may contribute to, keyed by the Partition node id.
+///
+/// The bound is derived from the properties of the partitioned data, so an error is returned if they are unknown.
+fn get_contribution_bounds(
+    graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, ValueProperties>,
+) -> Result<HashMap<u32, u32>> {
+    graph.iter()
+        .filter(|(_, component)| match component.variant {
+            Some(proto::component::Variant::Partition(_)) => true,
+            _ => false
+        })
+        .map(|(node_id, component)| Ok((*node_id, component.arguments.get("data")
+            .and_then(|data_id| properties.get(data_id))
+            .map(|property| max_c_stability(property).ceil() as u32)
+            .ok_or_else(|| Error::from(format!(
+                "node {}: the contribution bound of the partition is unknown, as the properties of its data are unknown", node_id)))?)))
+        .collect()
+}
+
+/// Worst-case usage within every cell that any usage is drawn from, at every level of nesting.
+fn cell_usages(
+    usages: &[(Vec<Cell>, proto::PrivacyUsage)],
+    contribution_bounds: &HashMap<u32, u32>,
+) -> BTreeMap<Vec<Cell>, Option<proto::PrivacyUsage>> {
+    usages.iter()
+        .flat_map(|(path, _)| (1..=path.len()).map(move |length| path[..length].to_vec()))
+        .unique()
+        .map(|prefix| {
+            // the usages within the cell, relative to the cell
+            let within = usages.iter()
+                .filter(|(path, _)| path.starts_with(&prefix))
+                .map(|(path, usage)| (path[prefix.len()..].to_vec(), usage.clone()))
+                .collect::<Vec<(Vec<Cell>, proto::PrivacyUsage)>>();
+            (prefix, worst_case_usage(&within, contribution_bounds, &mut None))
+        })
+        .collect()
+}
+
+/// Sum the usages without a cell, and add the worst-case usage of each partition.
+fn worst_case_usage(
+    usages: &[(Vec<Cell>, proto::PrivacyUsage)],
+    contribution_bounds: &HashMap<u32, u32>,
+    partitions: &mut Option<&mut BTreeMap<u32, PartitionUsage>>,
+) -> Option<proto::PrivacyUsage> {
+    let mut cells: BTreeMap<u32, BTreeMap<String, Vec<(Vec<Cell>, proto::PrivacyUsage)>>> = BTreeMap::new();
+    let mut sequential = Vec::new();
+
+    usages.iter().for_each(|(path, usage)| match path.split_first() {
+        Some(((partition_id, key), remainder)) => cells
+            .entry(*partition_id).or_insert_with(BTreeMap::new)
+            .entry(key.clone()).or_insert_with(Vec::new)
+            .push((remainder.to_vec(), usage.clone())),
+        None => sequential.push(usage.clone())
+    });
+
+    for (partition_id, partition_cells) in cells {
+        // without a known bound, an individual may contribute to every cell
+        let contribution_bound = contribution_bounds.get(&partition_id).cloned()
+            .unwrap_or(partition_cells.len() as u32)
+            .min(partition_cells.len() as u32);
+
+        let usage = partition_cells.values()
+            .filter_map(|cell_usages| worst_case_usage(cell_usages, contribution_bounds, &mut None))
+            .fold1(|l, r| privacy_usage_reducer(&l, &r, &|l, r| l.max(r)))
+            .map(|usage| privacy_usage_reducer(&usage, &usage, &|l, _| l * contribution_bound as f64));
+
+        if let Some(partitions) = partitions {
+            partitions.insert(partition_id, PartitionUsage {
+                num_cells: partition_cells.len(),
+                contribution_bound,
+                privacy_usage: usage.clone(),
+            });
+        }
+        sequential.extend(usage);
+    }
+
+    sequential.into_iter()
+        .fold1(|l, r| privacy_usage_reducer(&l, &r, &|l, r| l + r))
+}
+
+/// Walk the data lineage of a node, collecting the cells of disjoint partitions it is drawn from, outermost first.
+///
+/// If any other private argument joins the lineage, the node may depend on several cells, and no cells are returned.
+fn get_cells(
+    graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, ValueProperties>,
+    release: &proto::Release,
+    node_id: u32,
+) -> Result<Vec<Cell>> {
+    let is_public = |node_id: &u32| match properties.get(node_id) {
+        Some(ValueProperties::Array(property)) => property.releasable,
+        Some(ValueProperties::Hashmap(property)) => property.releasable,
+        Some(ValueProperties::Jagged(property)) => property.releasable,
+        Some(ValueProperties::Scalar(property)) => property.releasable,
+        None => false
+    };
+
+    let mut cells = Vec::new();
+    let mut current_id = node_id;
+
+    while let Some(component) = graph.get(&current_id) {
+        let data_id = match component.arguments.get("data") {
+            Some(data_id) => *data_id,
+            None => break
+        };
+
+        let is_partition = match component.variant {
+            Some(proto::component::Variant::Partition(_)) => true,
+            _ => false
+        };
+        // the partition key is drawn from the same records as the data
+        if component.arguments.iter()
+            .any(|(name, id)| name != "data" && !(is_partition && name == "by") && !is_public(id)) {
+            return Ok(Vec::new())
+        }
+
+        if let Some(proto::component::Variant::Index(_)) = component.variant {
+            let partition = graph.get(&data_id).and_then(|data| match data.variant {
+                Some(proto::component::Variant::Partition(_)) => properties.get(&data_id)
+                    .and_then(|property| property.hashmap().ok())
+                    .filter(|property| property.disjoint),
+                _ => None
+            });
+            if let Some(partition) = partition {
+                let key = component.arguments.get("columns")
+                    .and_then(|columns_id| release.values.get(columns_id))
+                    .and_then(|release_node| release_node.value.as_ref());
+                // a scalar key and a vector of one key identify the same cell
+                let path = match key.map(|key| value_to_json(&parse_value(key)?)).transpose()? {
+                    Some(serde_json::Value::Array(path)) => path,
+                    Some(key) => vec![key],
+                    // a cell that cannot be identified may coincide with any other cell
+                    None => return Ok(Vec::new())
+                };
+                // a path that stops short of the innermost level selects many cells
+                if path.len() != partition.group_id.len().max(1) {
+                    return Ok(Vec::new())
+                }
+                cells.push((data_id, serde_json::Value::Array(path).to_string()))
+            }
+        }
+        current_id = data_id;
+    }
+
+    cells.reverse();
+    Ok(cells)
+}
+
+
+/// Largest c-stability of the data, or of any cell of a partition of the data.
+fn max_c_stability(property: &ValueProperties) -> f64 {
+    match property {
+        ValueProperties::Array(property) => property.c_stability.iter().cloned().fold(1., f64::max),
+        ValueProperties::Hashmap(property) => property.properties.values().into_iter()
+            .map(max_c_stability).fold(1., f64::max),
+        _ => 1.
+    }
+}
+
+
+/// Rho of a zCDP usage.
+///
+/// A pure usage of epsilon is also (epsilon^2 / 2)-zCDP. An approximate usage with positive delta has no rho.
+/// Bun and Steinke. Concentrated Differential Privacy. TCC 2016, Proposition 1.4
+pub fn get_rho(usage: &proto::PrivacyUsage) -> Result<f64> {
+    use proto::privacy_usage::Distance;
+    match usage.distance.as_ref().ok_or_else(|| Error::from("distance must be defined on a PrivacyUsage"))? {
+        Distance::Concentrated(concentrated) => Ok(concentrated.rho),
+        Distance::Pure(pure) => Ok(pure.epsilon.powi(2) / 2.),
+        Distance::Approximate(approximate) if approximate.delta <= 0. => Ok(approximate.epsilon.powi(2) / 2.),
+        Distance::Approximate(_) => Err("rho is not defined for a usage with positive delta".into())
+    }
+}
+
+/// Epsilon at which a pure-DP mechanism is calibrated to meet the usage.
+///
+/// An epsilon-DP mechanism is (epsilon^2 / 2)-zCDP, so a zCDP usage of rho is met at epsilon = sqrt(2 rho).
+pub fn get_calibration_epsilon(usage: &proto::PrivacyUsage) -> Result<f64> {
+    match &usage.distance {
+        Some(proto::privacy_usage::Distance::Concentrated(concentrated)) => Ok((2. * concentrated.rho).sqrt()),
+        _ => get_epsilon(usage)
+    }
+}
+
+/// Ratio of the noise scale of a Gaussian mechanism to its L2 sensitivity, to meet the usage.
+///
+/// A zCDP usage of rho is met exactly with noise multiplier `1 / sqrt(2 rho)`,
+/// and an approximate usage with the classical calibration.
+pub fn get_gaussian_noise_multiplier(usage: &proto::PrivacyUsage) -> Result<f64> {
+    match &usage.distance {
+        Some(proto::privacy_usage::Distance::Concentrated(concentrated)) => match concentrated.rho {
+            rho if rho > 0. => Ok((2. * rho).sqrt().recip()),
+            _ => Err("rho must be positive".into())
+        },
+        _ => gaussian_noise_multiplier(get_epsilon(usage)?, get_delta(usage)?)
+    }
+}
+
+/// Convert a zCDP usage into an approximate usage at the given delta.
+///
+/// `epsilon = rho + 2 sqrt(rho ln(1 / delta))`.
+/// Bun and Steinke. Concentrated Differential Privacy. TCC 2016, Proposition 1.3
+pub fn concentrated_to_approximate(usage: &proto::PrivacyUsage, delta: f64) -> Result<proto::PrivacyUsage> {
+    if delta.is_nan() || delta <= 0. || delta >= 1. {
+        return Err("delta must be within (0, 1)".into())
+    }
+    let rho = get_rho(usage)?;
+    Ok(proto::PrivacyUsage {
+        distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+            epsilon: rho + 2. * (rho * (1. / delta).ln()).sqrt(),
+            delta,
+        }))
+    })
+}
+
+/// Check that the usages are measured as the privacy definition requires.
+///
+/// Under zCDP every usage must be in rho, and rho may only be used under zCDP, so that the usages of an analysis compose.
+pub fn check_usage_distance(privacy_definition: &proto::PrivacyDefinition, usages: &[proto::PrivacyUsage]) -> Result<()> {
+    let concentrated_definition = privacy_definition.distance == proto::privacy_definition::Distance::ZeroConcentrated as i32;
+    for usage in usages {
+        let concentrated_usage = match usage.distance {
+            Some(proto::privacy_usage::Distance::Concentrated(_)) => true,
+            _ => false
+        };
+        match (concentrated_definition, concentrated_usage) {
+            (true, false) => return Err("privacy_usage: usages must be in rho under zero-concentrated differential privacy".into()),
+            (false, true) => return Err("privacy_usage: usages in rho require zero-concentrated differential privacy".into()),
+            _ => ()
+        }
+    }
+    Ok(())
+}
+
+
+/// Orders at which Rényi differential privacy is evaluated.
+pub const RENYI_ORDERS: [f64; 16] = [1.25, 1.5, 1.75, 2., 2.5, 3., 4., 5., 6., 8., 12., 16., 32., 64., 128., 256.];
+
+/// Rényi differential privacy of a mechanism: its epsilon at each of the `RENYI_ORDERS`.
+///
+/// Curves compose by summation at each order, which is much tighter than summing (epsilon, delta)
+/// when many Gaussian mechanisms are composed.
+/// Mironov. Rényi Differential Privacy. CSF 2017
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenyiCurve(pub Vec<f64>);
+
+impl RenyiCurve {
+    /// Curve of a mechanism that releases nothing.
+    pub fn zero() -> RenyiCurve {
+        RenyiCurve(vec![0.; RENYI_ORDERS.len()])
+    }
+
+    /// Curve of a Laplace mechanism, whose noise scale is `noise_multiplier` times the L1 sensitivity.
+    ///
+    /// Mironov, Proposition 6, computed in log space, as the exponentials overflow for small noise multipliers.
+    pub fn laplace(noise_multiplier: f64) -> RenyiCurve {
+        RenyiCurve(RENYI_ORDERS.iter()
+            .map(|order| {
+                let (left, right) = (
+                    (order / (2. * order - 1.)).ln() + (order - 1.) / noise_multiplier,
+                    ((order - 1.) / (2. * order - 1.)).ln() - order / noise_multiplier);
+                let maximum = left.max(right);
+                ((maximum + ((left - maximum).exp() + (right - maximum).exp()).ln()) / (order - 1.))
+                    .min(noise_multiplier.recip())
+            })
+            .collect())
+    }
+
+    /// Curve of a Gaussian mechanism, whose noise scale is `noise_multiplier` times the L2 sensitivity.
+    pub fn gaussian(noise_multiplier: f64) -> RenyiCurve {
+        RenyiCurve(RENYI_ORDERS.iter()
+            .map(|order| order / (2. * noise_multiplier.powi(2)))
+            .collect())
+    }
+
+    /// Curve of a Gaussian mechanism over a Poisson sample of the records, drawn with probability `sampling_rate`.
+    ///
+    /// The divergence is only computed at integer orders, and is nondecreasing in the order,
+    /// so each fractional order is bounded by the next integer order.
+    pub fn subsampled_gaussian(noise_multiplier: f64, sampling_rate: f64) -> RenyiCurve {
+        RenyiCurve(RENYI_ORDERS.iter()
+            .map(|order| sampled_gaussian_rdp(noise_multiplier, sampling_rate, order.ceil().max(2.) as u32))
+            .collect())
+    }
+
+    /// Curve of any epsilon-DP mechanism, which is also (epsilon^2 / 2)-zCDP.
+    pub fn pure(epsilon: f64) -> RenyiCurve {
+        RenyiCurve(RENYI_ORDERS.iter()
+            .map(|order| epsilon.min(order * epsilon.powi(2) / 2.))
+            .collect())
+    }
+
+    /// Curve of `count` invocations of the mechanism.
+    pub fn repeat(&self, count: f64) -> RenyiCurve {
+        RenyiCurve(self.0.iter().map(|epsilon| count * epsilon).collect())
+    }
+
+    /// Curve of the composition of two mechanisms.
+    pub fn compose(&self, other: &RenyiCurve) -> RenyiCurve {
+        RenyiCurve(self.0.iter().zip(other.0.iter()).map(|(l, r)| l + r).collect())
+    }
+
+    /// Smallest epsilon for which the curve implies (epsilon, delta)-DP, over the orders.
+    ///
+    /// Mironov, Proposition 3
+    pub fn epsilon(&self, delta: f64) -> Result<f64> {
+        if delta.is_nan() || delta <= 0. || delta >= 1. {
+            return Err("delta must be within (0, 1)".into())
+        }
+        Ok(RENYI_ORDERS.iter().zip(self.0.iter())
+            .map(|(order, epsilon)| epsilon + (1. / delta).ln() / (order - 1.))
+            .fold(std::f64::INFINITY, f64::min))
+    }
+
+    /// Convert the curve into an approximate usage at the given delta.
+    pub fn to_approximate(&self, delta: f64) -> Result<proto::PrivacyUsage> {
+        Ok(proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: self.epsilon(delta)?,
+                delta,
+            }))
+        })
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!(RENYI_ORDERS.iter().zip(self.0.iter())
+            .map(|(order, epsilon)| serde_json::json!({"order": order, "epsilon": epsilon}))
+            .collect::<Vec<serde_json::Value>>())
+    }
+}
+
+#[cfg(test)]
+mod test_privacy {
+    use crate::proto;
+    use crate::utilities::privacy::{worst_case_usage, cell_usages, node_privacy_usages, individual_privacy_usage, Cell, get_rho, get_gaussian_noise_multiplier, concentrated_to_approximate, RenyiCurve};
+    use crate::utilities::{privacy_usage_reducer, get_epsilon};
+    use std::collections::HashMap;
+
+    fn pure(epsilon: f64) -> proto::PrivacyUsage {
+        proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Pure(proto::privacy_usage::DistancePure { epsilon }))
+        }
+    }
+
+    fn epsilon(usage: Option<proto::PrivacyUsage>) -> f64 {
+        match usage.unwrap().distance.unwrap() {
+            proto::privacy_usage::Distance::Pure(distance) => distance.epsilon,
+            _ => panic!("expected a pure privacy usage")
+        }
+    }
+
+    #[test]
+    fn test_parallel_composition() {
+        let cell = |key: &str| -> Vec<Cell> { vec![(1, key.to_string())] };
+        let usages = vec![
+            (cell("a"), pure(0.5)),
+            (cell("a"), pure(0.25)),
+            (cell("b"), pure(1.)),
+            (vec![], pure(0.1)),
+        ];
+
+        // an individual is in at most one cell
+        let bounds = vec![(1, 1)].into_iter().collect();
+        assert!((epsilon(worst_case_usage(&usages, &bounds, &mut None)) - 1.1).abs() < 1e-12);
+
+        // without a known bound, an individual may contribute to every cell
+        assert!((epsilon(worst_case_usage(&usages, &HashMap::new(), &mut None)) - 2.1).abs() < 1e-12);
+
+        // an individual may contribute to both cells
+        let bounds = vec![(1, 3)].into_iter().collect();
+        assert!((epsilon(worst_case_usage(&usages, &bounds, &mut None)) - 2.1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_partition_lineage() {
+        use crate::hashmap;
+        use crate::base::{Value, ValueProperties, ArrayProperties, HashmapProperties, Hashmap};
+        use crate::utilities::{inference::infer_property, serial::serialize_value};
+        use ndarray::arr2;
+
+        let component = |arguments: HashMap<String, u32>, variant: proto::component::Variant| proto::Component {
+            arguments, variant: Some(variant), omit: false, batch: 0
+        };
+        let laplace = |data_id: u32, epsilon: f64| component(
+            hashmap!["data".to_string() => data_id],
+            proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism { privacy_usage: vec![pure(epsilon)] }));
+        let index = |columns_id: u32| component(
+            hashmap!["data".to_string() => 3, "columns".to_string() => columns_id],
+            proto::component::Variant::Index(proto::Index {}));
+
+        // mechanisms over the cells "a" and "b" of a partition of the data
+        let mut graph = hashmap![
+            1 => component(HashMap::new(), proto::component::Variant::Literal(proto::Literal {})),
+            2 => component(HashMap::new(), proto::component::Variant::Literal(proto::Literal {})),
+            3 => component(hashmap!["data".to_string() => 1, "by".to_string() => 2], proto::component::Variant::Partition(proto::Partition {})),
+            4 => component(HashMap::new(), proto::component::Variant::Literal(proto::Literal {})),
+            5 => index(4),
+            6 => laplace(5, 0.5),
+            7 => component(HashMap::new(), proto::component::Variant::Literal(proto::Literal {})),
+            8 => index(7),
+            9 => laplace(8, 1.)
+        ];
+
+        let private = |property: ValueProperties| -> ValueProperties {
+            ArrayProperties { releasable: false, ..property.array().unwrap().clone() }.into()
+        };
+        let data_property = private(infer_property(&Value::from(arr2(&[[1.], [2.]]).into_dyn())).unwrap());
+        let key = |key: &str| Value::from(key.to_string());
+        let mut properties: HashMap<u32, ValueProperties> = hashmap![
+            1 => data_property.clone(),
+            3 => HashmapProperties {
+                num_records: None,
+                disjoint: true,
+                properties: Hashmap::Str(vec![
+                    ("a".to_string(), data_property.clone()),
+                    ("b".to_string(), data_property)
+                ].into_iter().collect()),
+                columnar: false,
+                releasable: false,
+                group_id: vec![3]
+            }.into(),
+            4 => infer_property(&key("a")).unwrap(),
+            7 => infer_property(&key("b")).unwrap()
+        ];
+        let release = proto::Release {
+            values: hashmap![
+                4 => proto::ReleaseNode { value: Some(serialize_value(&key("a")).unwrap()), public: true, ..Default::default() },
+                7 => proto::ReleaseNode { value: Some(serialize_value(&key("b")).unwrap()), public: true, ..Default::default() }
+            ]
+        };
+
+        // each individual is in one cell, so is only charged the most expensive cell
+        let usage = individual_privacy_usage(&graph, &properties, &release).unwrap();
+        assert!((epsilon(usage.total) - 1.5).abs() < 1e-12);
+        assert!((epsilon(usage.worst_case) - 1.).abs() < 1e-12);
+        assert_eq!(usage.partitions.get(&3).unwrap().num_cells, 2);
+
+        // a private key may select any cell, so the mechanism over it composes sequentially
+        let mut private_key = properties.clone();
+        private_key.insert(4, private(infer_property(&key("a")).unwrap()));
+        assert!((epsilon(individual_privacy_usage(&graph, &private_key, &release).unwrap().worst_case) - 1.5).abs() < 1e-12);
+
+        // likewise for any other private argument that joins the lineage
+        graph.get_mut(&6).unwrap().arguments.insert("lower".to_string(), 1);
+        assert!((epsilon(individual_privacy_usage(&graph, &properties, &release).unwrap().worst_case) - 1.5).abs() < 1e-12);
+
+        // the contribution bound of a partition over data with unknown properties is unknown
+        properties.remove(&1);
+        assert!(individual_privacy_usage(&graph, &properties, &release).is_err());
+    }
+
+    #[test]
+    fn test_node_privacy_usages() {
+        let laplace = |privacy_usage: Vec<proto::PrivacyUsage>| proto::Component {
+            arguments: HashMap::new(),
+            variant: Some(proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism { privacy_usage })),
+            omit: false,
+            batch: 0
+        };
+        let graph = vec![(1, laplace(vec![pure(0.5)])), (2, laplace(vec![pure(0.25), pure(0.5)]))].into_iter().collect();
+        let usages = node_privacy_usages(&graph, &proto::Release { values: HashMap::new() }).unwrap();
+
+        assert!(usages.get(&1).unwrap().columns.is_empty());
+        let vector = usages.get(&2).unwrap();
+        assert_eq!(vector.columns.len(), 2);
+        assert!((epsilon(vector.total.clone()) - 0.75).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_cell_usages() {
+        let a: Vec<Cell> = vec![(1, "\"a\"".to_string())];
+        let nested = |key: &str| -> Vec<Cell> { vec![a[0].clone(), (2, key.to_string())] };
+        let usages = vec![
+            (a.clone(), pure(0.5)),
+            (nested("x"), pure(0.25)),
+            (nested("y"), pure(0.75)),
+            (vec![], pure(0.1)),
+        ];
+
+        let cells = cell_usages(&usages, &vec![(1, 1), (2, 1)].into_iter().collect());
+        assert_eq!(cells.len(), 3);
+        // the cell absorbs its own usage, and the worst of its nested cells
+        assert!((epsilon(cells.get(&a).cloned().unwrap()) - 1.25).abs() < 1e-12);
+        assert!((epsilon(cells.get(&nested("x")).cloned().unwrap()) - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_zero_concentrated() {
+        let concentrated = |rho: f64| proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Concentrated(proto::privacy_usage::DistanceConcentrated { rho }))
+        };
+
+        // rho composes by summation
+        let total = privacy_usage_reducer(&concentrated(0.125), &concentrated(0.375), &|l, r| l + r);
+        assert!((get_rho(&total).unwrap() - 0.5).abs() < 1e-12);
+        assert!((get_rho(&pure(1.)).unwrap() - 0.5).abs() < 1e-12);
+
+        // a Gaussian at rho = 1/2 has unit noise multiplier
+        assert!((get_gaussian_noise_multiplier(&total).unwrap() - 1.).abs() < 1e-12);
+
+        let approximate = concentrated_to_approximate(&total, 1e-5).unwrap();
+        assert!((get_epsilon(&approximate).unwrap() - (0.5 + 2. * (0.5 * 1e5_f64.ln()).sqrt())).abs() < 1e-12);
+        assert!(concentrated_to_approximate(&total, 0.).is_err());
+    }
+
+    #[test]
+    fn test_renyi_curve() {
+        // a hundred gaussian releases are badly overcounted by summing their (epsilon, delta)
+        let composed = RenyiCurve::gaussian(20.).repeat(100.);
+        let epsilon = composed.epsilon(1e-5).unwrap();
+        assert!(epsilon < 100. * (2. * (1.25 / 1e-7_f64).ln()).sqrt() / 20.);
+        assert!((get_epsilon(&composed.to_approximate(1e-5).unwrap()).unwrap() - epsilon).abs() < 1e-12);
+
+        // subsampling amplifies privacy, and a Laplace mechanism is no worse than its pure guarantee
+        let subsampled = RenyiCurve::subsampled_gaussian(2., 0.01);
+        assert!(subsampled.0.iter().zip(RenyiCurve::gaussian(2.).0.iter()).all(|(l, r)| l <= r));
+        assert!(RenyiCurve::laplace(1.).0.iter().zip(RenyiCurve::pure(1.).0.iter()).all(|(l, r)| *l <= r + 1e-12));
+
+        assert_eq!(RenyiCurve::zero().compose(&subsampled), subsampled);
+        assert!(composed.epsilon(0.).is_err());
+    }
+}
@@ -0,0 +1,383 @@
+//! Metadata questionnaires, for analyses over data that arrives without metadata
+//!
+//! Mechanisms need bounds, categories or a number of records on the columns they privatize,
+//! which a curator often knows about the data, but which the analysis does not yet state.
+//! The questionnaire asks, for every column read from a data source, for the metadata its properties lack.
+//! Answers are applied back to the analysis by clamping and resizing the column,
+//! so that the runtime enforces whatever the curator asserts about the data.
+
+use crate::errors::*;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::proto;
+use crate::base::{Value, Jagged, ValueProperties, DataType};
+use crate::utilities::{get_literal, is_privatizing};
+use crate::utilities::serial::parse_value;
+use crate::utilities::json::value_to_json;
+
+use itertools::Itertools;
+
+/// Ids of the Index nodes that read columns directly from each Materialize node.
+fn source_columns(graph: &HashMap<u32, proto::Component>) -> BTreeMap<u32, Vec<u32>> {
+    let mut sources = BTreeMap::<u32, Vec<u32>>::new();
+    graph.iter()
+        .filter(|(_, component)| match component.variant {
+            Some(proto::component::Variant::Materialize(_)) => true,
+            _ => false
+        })
+        .for_each(|(source_id, _)| { sources.insert(*source_id, Vec::new()); });
+
+    graph.iter().sorted_by_key(|(node_id, _)| **node_id)
+        .filter(|(_, component)| match component.variant {
+            Some(proto::component::Variant::Index(_)) => true,
+            _ => false
+        })
+        .for_each(|(node_id, component)| if let Some(columns) = component.arguments.get("data")
+            .and_then(|data_id| sources.get_mut(data_id)) {
+            columns.push(*node_id)
+        });
+    sources
+}
+
+/// Ids of the privatizing nodes downstream of a node.
+fn privatizing_descendants(graph: &HashMap<u32, proto::Component>, node_id: u32) -> Vec<u32> {
+    let mut descendants = HashSet::new();
+    let mut traversal = vec![node_id];
+    while let Some(node_id) = traversal.pop() {
+        graph.iter()
+            .filter(|(_, component)| component.arguments.values().any(|argument_id| *argument_id == node_id))
+            .for_each(|(child_id, _)| if descendants.insert(*child_id) {
+                traversal.push(*child_id)
+            });
+    }
+    descendants.into_iter()
+        .filter(|descendant_id| is_privatizing(&graph[descendant_id]))
+        .sorted().collect()
+}
+
+/// Questionnaire of the metadata missing from every column read from a data source.
+///
+/// Each data source asks for a contribution bound if none is known.
+/// Each column asks for `bounds` if numeric, and `categories` if not floating-point, when its nature is unknown,
+/// and for `numRecords` when its number of records is unknown. Integer columns may be answered with either.
+/// Columns list the privatizing nodes they flow into under `usedBy`, as metadata is only needed on those columns.
+pub fn generate_questionnaire(
+    graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, ValueProperties>,
+    release: &proto::Release,
+) -> Result<serde_json::Value> {
+    let sources = source_columns(graph).into_iter()
+        .map(|(source_id, column_ids)| {
+            let is_bounded = match &graph[&source_id].variant {
+                Some(proto::component::Variant::Materialize(materialize)) => materialize.data_source.as_ref()
+                    .map(|data_source| data_source.max_contributions_per_individual > 0)
+                    .unwrap_or(false),
+                _ => false
+            };
+            let questions = if is_bounded { vec![] } else { vec!["contributionBound"] };
+
+            Ok(serde_json::json!({
+                "nodeId": source_id,
+                "questions": questions,
+                "columns": column_ids.into_iter()
+                    .map(|column_id| column_questions(graph, properties, release, column_id))
+                    .collect::<Result<Vec<serde_json::Value>>>()?
+            }))
+        })
+        .collect::<Result<Vec<serde_json::Value>>>()?;
+
+    Ok(serde_json::json!({"sources": sources}))
+}
+
+fn column_questions(
+    graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, ValueProperties>,
+    release: &proto::Release,
+    column_id: u32,
+) -> Result<serde_json::Value> {
+    let column_property = properties.get(&column_id)
+        .ok_or_else(|| Error::from(format!("node {}: properties of the column are unknown", column_id)))?
+        .array()?;
+
+    // name of the column, if the columns argument is a released literal
+    let column = match graph[&column_id].arguments.get("columns")
+        .and_then(|columns_id| release.values.get(columns_id))
+        .and_then(|release_node| release_node.value.as_ref()) {
+        Some(value) => value_to_json(&parse_value(value)?)?,
+        None => serde_json::Value::Null
+    };
+
+    let mut questions = Vec::new();
+    if column_property.nature.is_none() {
+        match column_property.data_type {
+            DataType::F64 | DataType::I64 => questions.push("bounds"),
+            _ => ()
+        }
+        if column_property.data_type != DataType::F64 {
+            questions.push("categories")
+        }
+    }
+    if column_property.num_records.is_none() {
+        questions.push("numRecords")
+    }
+
+    Ok(serde_json::json!({
+        "nodeId": column_id,
+        "column": column,
+        "dataType": format!("{:?}", column_property.data_type),
+        "questions": questions,
+        "usedBy": privatizing_descendants(graph, column_id)
+    }))
+}
+
+/// Apply the answers to a questionnaire to the analysis.
+///
+/// Answers mirror the questionnaire: `{"sources": [{"nodeId", "contributionBound", "columns": [{"nodeId", ...}]}]}`.
+/// A column may be answered with `lower` and `upper`, or with `categories` and `nullValue`, and with `numRecords`.
+/// The column is clamped to the answered bounds or categories, imputed if it is a float, and resized to the answered number of records,
+/// and every consumer of the column reads the clamped and resized column instead.
+/// The analysis changes, so it no longer carries its approval token.
+pub fn apply_answers(
+    analysis: &proto::Analysis,
+    release: &proto::Release,
+    properties: &HashMap<u32, ValueProperties>,
+    answers: &serde_json::Value,
+) -> Result<(proto::Analysis, proto::Release)> {
+    let mut graph = analysis.computation_graph.as_ref()
+        .ok_or_else(|| Error::from("computation graph must be defined"))?.value.clone();
+    let mut release = release.clone();
+    let mut maximum_id = graph.keys().cloned().max().unwrap_or(0);
+
+    let sources = answers.get("sources").and_then(|sources| sources.as_array())
+        .ok_or_else(|| Error::from("answers: must contain a list of sources"))?;
+
+    for source in sources {
+        let source_id = get_node_id(source)?;
+        if let Some(contribution_bound) = source.get("contributionBound") {
+            let contribution_bound = contribution_bound.as_u64().filter(|bound| *bound > 0)
+                .ok_or_else(|| Error::from("contributionBound: must be a positive integer"))?;
+            match graph.get_mut(&source_id).and_then(|component| component.variant.as_mut()) {
+                Some(proto::component::Variant::Materialize(materialize)) => materialize.data_source.as_mut()
+                    .ok_or_else(|| Error::from("data source must be supplied"))?
+                    .max_contributions_per_individual = contribution_bound as u32,
+                _ => bail!("node {}: contribution bounds may only be answered for Materialize nodes", source_id)
+            }
+        }
+
+        for column in source.get("columns").and_then(|columns| columns.as_array()).map(Vec::as_slice).unwrap_or(&[]) {
+            apply_column_answer(&mut graph, &mut release, properties, column, &mut maximum_id)?;
+        }
+    }
+
+    Ok((proto::Analysis {
+        computation_graph: Some(proto::ComputationGraph { value: graph }),
+        approval_token: Vec::new(),
+        ..analysis.clone()
+    }, release))
+}
+
+fn get_node_id(answer: &serde_json::Value) -> Result<u32> {
+    answer.get("nodeId").and_then(|node_id| node_id.as_u64())
+        .map(|node_id| node_id as u32)
+        .ok_or_else(|| Error::from("answers: every source and column must have a nodeId"))
+}
+
+fn apply_column_answer(
+    graph: &mut HashMap<u32, proto::Component>,
+    release: &mut proto::Release,
+    properties: &HashMap<u32, ValueProperties>,
+    answer: &serde_json::Value,
+    maximum_id: &mut u32,
+) -> Result<()> {
+    let column_id = get_node_id(answer)?;
+    let data_type = properties.get(&column_id)
+        .ok_or_else(|| Error::from(format!("node {}: properties of the column are unknown", column_id)))?
+        .array()?.data_type.clone();
+    let batch = graph.get(&column_id)
+        .ok_or_else(|| Error::from(format!("node {}: not in the computation graph", column_id)))?.batch;
+
+    let mut new_ids = Vec::new();
+    let mut add_node = |graph: &mut HashMap<u32, proto::Component>, (component, release_node): (proto::Component, Option<proto::ReleaseNode>)| {
+        *maximum_id += 1;
+        graph.insert(*maximum_id, component);
+        if let Some(release_node) = release_node {
+            release.values.insert(*maximum_id, release_node);
+        }
+        new_ids.push(*maximum_id);
+        *maximum_id
+    };
+    let literal = |value: Value| -> Result<(proto::Component, Option<proto::ReleaseNode>)> {
+        let (component, release_node) = get_literal(&value, &batch)?;
+        Ok((component, Some(release_node)))
+    };
+    let component = |arguments: HashMap<String, u32>, variant: proto::component::Variant| (proto::Component {
+        arguments, variant: Some(variant), omit: true, batch,
+    }, None);
+
+    // arguments that describe the support of the column, shared by Clamp and Resize
+    let mut support = HashMap::<String, u32>::new();
+    if let (Some(lower), Some(upper)) = (answer.get("lower"), answer.get("upper")) {
+        support.insert("lower".to_string(), add_node(graph, literal(json_to_scalar(lower, &data_type)?)?));
+        support.insert("upper".to_string(), add_node(graph, literal(json_to_scalar(upper, &data_type)?)?));
+    } else if let Some(categories) = answer.get("categories") {
+        let null_value = answer.get("nullValue")
+            .ok_or_else(|| Error::from("nullValue: must be answered alongside categories"))?;
+        support.insert("categories".to_string(), add_node(graph, literal(json_to_categories(categories, &data_type)?)?));
+        support.insert("null_value".to_string(), add_node(graph, literal(json_to_scalar(null_value, &data_type)?)?));
+    }
+
+    if support.is_empty() {
+        if answer.get("numRecords").is_some() {
+            return Err("numRecords: must be answered alongside bounds or categories, which are used to impute records".into())
+        }
+        return Ok(())
+    }
+
+    let mut arguments = support.clone();
+    arguments.insert("data".to_string(), column_id);
+    let mut output_id = add_node(graph, component(arguments, proto::component::Variant::Clamp(proto::Clamp {})));
+
+    // floats may be null after clamping, so they are imputed within the answered bounds
+    if data_type == DataType::F64 {
+        let mut arguments = support.clone();
+        arguments.insert("data".to_string(), output_id);
+        output_id = add_node(graph, component(arguments, proto::component::Variant::Impute(proto::Impute {})));
+    }
+
+    if let Some(num_records) = answer.get("numRecords") {
+        let num_records = num_records.as_i64().filter(|num_records| *num_records > 0)
+            .ok_or_else(|| Error::from("numRecords: must be a positive integer"))?;
+        let mut arguments = support;
+        // the null value is only needed to clamp, not to impute
+        arguments.remove("null_value");
+        arguments.insert("data".to_string(), output_id);
+        arguments.insert("n".to_string(), add_node(graph, literal(Value::from(num_records))?));
+        output_id = add_node(graph, component(arguments, proto::component::Variant::Resize(proto::Resize {})));
+    }
+
+    // every prior consumer of the column now reads the clamped column
+    graph.iter_mut()
+        .filter(|(node_id, _)| !new_ids.contains(node_id))
+        .for_each(|(_, component)| component.arguments.values_mut()
+            .filter(|argument_id| **argument_id == column_id)
+            .for_each(|argument_id| *argument_id = output_id));
+    Ok(())
+}
+
+fn json_to_scalar(value: &serde_json::Value, data_type: &DataType) -> Result<Value> {
+    let scalar = match data_type {
+        DataType::F64 => value.as_f64().map(Value::from),
+        DataType::I64 => value.as_i64().map(Value::from),
+        DataType::Bool => value.as_bool().map(Value::from),
+        DataType::Str => value.as_str().map(|value| Value::from(value.to_string())),
+    };
+    scalar.ok_or_else(|| Error::from(format!("answers: {} must have the data type of the column, {:?}", value, data_type)))
+}
+
+fn json_to_categories(categories: &serde_json::Value, data_type: &DataType) -> Result<Value> {
+    let categories = categories.as_array()
+        .ok_or_else(|| Error::from("categories: must be a list"))?;
+    let error = || Error::from(format!("categories: must have the data type of the column, {:?}", data_type));
+    Ok(Value::Jagged(match data_type {
+        DataType::I64 => Jagged::I64(vec![Some(categories.iter()
+            .map(|category| category.as_i64()).collect::<Option<Vec<i64>>>().ok_or_else(error)?)]),
+        DataType::Bool => Jagged::Bool(vec![Some(categories.iter()
+            .map(|category| category.as_bool()).collect::<Option<Vec<bool>>>().ok_or_else(error)?)]),
+        DataType::Str => Jagged::Str(vec![Some(categories.iter()
+            .map(|category| category.as_str().map(String::from)).collect::<Option<Vec<String>>>().ok_or_else(error)?)]),
+        DataType::F64 => return Err("categories: floating-point columns may not be categorical".into())
+    }))
+}
+
+
+#[cfg(test)]
+mod test_questionnaire {
+    use crate::proto;
+    use crate::hashmap;
+    use crate::base::{Array, Value};
+    use crate::utilities::{get_literal, propagate_properties};
+    use crate::utilities::serial::serialize_value;
+    use crate::utilities::questionnaire::{generate_questionnaire, apply_answers};
+    use ndarray::{arr1, Array2};
+    use std::collections::HashMap;
+
+    /// The DP mean of a column, without bounds or a number of records.
+    fn analysis() -> (proto::Analysis, proto::Release) {
+        let (literal, literal_release) = get_literal(&Value::Array(Array::Str(arr1(&["a".to_string()]).into_dyn())), &0).unwrap();
+        let component = |arguments: HashMap<String, u32>, variant: proto::component::Variant| proto::Component {
+            arguments, variant: Some(variant), omit: true, batch: 0,
+        };
+        let graph = hashmap![
+            1 => literal,
+            2 => component(hashmap!["column_names".to_string() => 1], proto::component::Variant::Materialize(proto::Materialize {
+                data_source: Some(proto::DataSource {
+                    value: Some(proto::data_source::Value::Literal(
+                        serialize_value(&Value::Array(Array::F64(Array2::<f64>::zeros((10, 1)).into_dyn()))).unwrap())),
+                    max_contributions_per_individual: 0,
+                }),
+                public: false,
+                dataset_id: Some(proto::I64Null { data: Some(proto::i64_null::Data::Option(0)) }),
+                skip_row: true,
+            })),
+            3 => component(hashmap!["data".to_string() => 2, "columns".to_string() => 1], proto::component::Variant::Index(proto::Index {})),
+            4 => component(hashmap!["data".to_string() => 3], proto::component::Variant::Mean(proto::Mean {})),
+            5 => component(hashmap!["data".to_string() => 4], proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+                privacy_usage: vec![proto::PrivacyUsage {
+                    distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                        epsilon: 1., delta: 0.
+                    }))
+                }]
+            }))
+        ];
+
+        (proto::Analysis {
+            privacy_definition: Some(proto::PrivacyDefinition {
+                group_size: 1,
+                distance: proto::privacy_definition::Distance::Approximate as i32,
+                neighboring: proto::privacy_definition::Neighboring::AddRemove as i32,
+                delta_cap: 0.,
+                delta_split: proto::privacy_definition::DeltaSplit::Equal as i32,
+                delta_allotments: HashMap::new(),
+                composition: proto::privacy_definition::Composition::Basic as i32,
+                composition_delta: 0.,
+                epsilon_floor: 0.,
+                epsilon_ceiling: 0.,
+                epsilon_exemptions: Vec::new(),
+                delta_inverse_records: false,
+                epsilon_cap: 0.,
+                enforce_budget: false
+            }),
+            computation_graph: Some(proto::ComputationGraph { value: graph }),
+            approval_token: Vec::new(),
+            external_usages: Vec::new(),
+            optimizations: None,
+        }, proto::Release { values: hashmap![1 => literal_release] })
+    }
+
+    #[test]
+    fn test_questionnaire() {
+        let (analysis, release) = analysis();
+        // the mean fails to validate without metadata
+        assert!(propagate_properties(&analysis, &release, None, false).is_err());
+
+        let (properties, _) = propagate_properties(&analysis, &release, None, true).unwrap().into_inner();
+        let graph = &analysis.computation_graph.as_ref().unwrap().value;
+        let questionnaire = generate_questionnaire(graph, &properties, &release).unwrap();
+        let source = &questionnaire["sources"][0];
+        assert_eq!(source["questions"], serde_json::json!(["contributionBound"]));
+        assert_eq!(source["columns"][0]["questions"], serde_json::json!(["bounds", "numRecords"]));
+        assert_eq!(source["columns"][0]["usedBy"], serde_json::json!([5]));
+
+        let answers = serde_json::json!({"sources": [{
+            "nodeId": 2, "contributionBound": 1,
+            "columns": [{"nodeId": 3, "lower": 0., "upper": 10., "numRecords": 10}]
+        }]});
+        let (answered, answered_release) = apply_answers(&analysis, &release, &properties, &answers).unwrap();
+        assert!(propagate_properties(&answered, &answered_release, None, false).is_ok());
+
+        // records can't be imputed without a support
+        let answers = serde_json::json!({"sources": [{"nodeId": 2, "columns": [{"nodeId": 3, "numRecords": 10}]}]});
+        assert!(apply_answers(&analysis, &release, &properties, &answers).is_err());
+    }
+}
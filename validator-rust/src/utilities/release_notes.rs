@@ -0,0 +1,160 @@
+//! Release notes for periodic publications
+//!
+//! A periodic bulletin re-runs the same analysis template on the data of each period.
+//! Node ids of the template are stable across periods, so the statistics of two periods may be matched by node id.
+
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::proto;
+use crate::base::ValueProperties;
+use crate::components::Accuracy;
+use crate::utilities::{get_charged_privacy_usages, get_component_properties, is_privatizing, privacy_usage_reducer};
+
+use itertools::Itertools;
+
+/// The expanded computation graph of a period, alongside its properties and release.
+pub struct Period<'a> {
+    pub graph: &'a HashMap<u32, proto::Component>,
+    pub properties: &'a HashMap<u32, ValueProperties>,
+    pub release: &'a proto::Release,
+}
+
+impl<'a> Period<'a> {
+    /// Ids of the privatizing nodes that were released in the period, in order.
+    fn released_node_ids(&self) -> Vec<u32> {
+        self.release.values.keys()
+            .filter(|node_id| self.graph.get(node_id).map(is_privatizing).unwrap_or(false))
+            .cloned().sorted().collect()
+    }
+
+    /// Privacy usage summed over every mechanism, without parallel composition.
+    fn privacy_usage(&self) -> Result<Option<proto::PrivacyUsage>> {
+        Ok(get_charged_privacy_usages(self.graph, self.release)?.into_iter()
+            .map(|(_, usage)| usage)
+            .fold1(|l, r| privacy_usage_reducer(&l, &r, &|l, r| l + r)))
+    }
+
+    /// Accuracy of a released node at the given significance level, or None if the component does not relate accuracy to privacy usage.
+    fn accuracy(&self, privacy_definition: &proto::PrivacyDefinition, node_id: &u32, alpha: &f64) -> Option<Vec<proto::Accuracy>> {
+        let component = self.graph.get(node_id)?;
+        let properties = get_component_properties(component, self.properties).ok()?;
+        component.variant.as_ref()?
+            .privacy_usage_to_accuracy(privacy_definition, &properties, alpha)
+            .ok().flatten()
+    }
+}
+
+/// Summarize the changes from the previous period to the current period.
+///
+/// Statistics are the privatizing nodes in each release. A statistic released in both periods is listed under
+/// the accuracy changes if its accuracy at `alpha` is known in both periods, and differs.
+/// The cumulative usage adds the usage of both periods to the `prior_usage` of all earlier periods.
+pub fn generate_release_notes(
+    privacy_definition: &proto::PrivacyDefinition,
+    previous: &Period,
+    current: &Period,
+    prior_usage: Option<&proto::PrivacyUsage>,
+    alpha: f64,
+) -> Result<proto::ReleaseNotes> {
+    if !(alpha > 0. && alpha < 1.) {
+        return Err("alpha: must be within (0, 1)".into())
+    }
+
+    let previous_node_ids = previous.released_node_ids();
+    let current_node_ids = current.released_node_ids();
+
+    let new_node_ids = current_node_ids.iter()
+        .filter(|node_id| !previous_node_ids.contains(node_id))
+        .cloned().collect();
+    let discontinued_node_ids = previous_node_ids.iter()
+        .filter(|node_id| !current_node_ids.contains(node_id))
+        .cloned().collect();
+
+    let accuracy_changes = current_node_ids.iter()
+        .filter(|node_id| previous_node_ids.contains(node_id))
+        .filter_map(|node_id| Some(proto::AccuracyChange {
+            node_id: *node_id,
+            previous: previous.accuracy(privacy_definition, node_id, &alpha)?,
+            current: current.accuracy(privacy_definition, node_id, &alpha)?,
+        }))
+        .filter(|change| change.previous != change.current)
+        .collect();
+
+    let previous_usage = previous.privacy_usage()?;
+    let current_usage = current.privacy_usage()?;
+
+    let cumulative_usage = prior_usage.cloned().into_iter()
+        .chain(previous_usage.clone())
+        .chain(current_usage.clone())
+        .fold1(|l, r| privacy_usage_reducer(&l, &r, &|l, r| l + r));
+    if let Some(usage) = &cumulative_usage {
+        if usage.distance.is_none() {
+            return Err("the privacy usages of every period must be of the same type to be accumulated".into())
+        }
+    }
+
+    Ok(proto::ReleaseNotes {
+        new_node_ids,
+        discontinued_node_ids,
+        accuracy_changes,
+        previous_usage,
+        current_usage,
+        cumulative_usage
+    })
+}
+
+
+#[cfg(test)]
+mod test_release_notes {
+    use std::collections::HashMap;
+
+    use crate::proto;
+    use crate::hashmap;
+    use crate::utilities::release_notes::{Period, generate_release_notes};
+
+    fn laplace(epsilon: f64) -> proto::Component {
+        proto::Component {
+            arguments: HashMap::new(),
+            variant: Some(proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+                privacy_usage: vec![pure(epsilon)]
+            })),
+            omit: false,
+            batch: 0,
+        }
+    }
+
+    fn pure(epsilon: f64) -> proto::PrivacyUsage {
+        proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Pure(proto::privacy_usage::DistancePure { epsilon }))
+        }
+    }
+
+    fn released(node_ids: &[u32]) -> proto::Release {
+        proto::Release {
+            values: node_ids.iter()
+                .map(|node_id| (*node_id, proto::ReleaseNode::default()))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_release_notes() {
+        let previous_graph = hashmap![1 => laplace(0.5), 2 => laplace(0.25)];
+        let current_graph = hashmap![2 => laplace(0.25), 3 => laplace(0.5)];
+        let properties = HashMap::new();
+        let (previous_release, current_release) = (released(&[1, 2]), released(&[2, 3]));
+
+        let notes = generate_release_notes(
+            &proto::PrivacyDefinition::default(),
+            &Period { graph: &previous_graph, properties: &properties, release: &previous_release },
+            &Period { graph: &current_graph, properties: &properties, release: &current_release },
+            Some(&pure(1.)), 0.05).unwrap();
+
+        assert_eq!(notes.new_node_ids, vec![3]);
+        assert_eq!(notes.discontinued_node_ids, vec![1]);
+        assert!(notes.accuracy_changes.is_empty());
+        assert_eq!(notes.cumulative_usage, Some(pure(2.5)));
+    }
+}
@@ -1,10 +1,15 @@
 //! Serialization and deserialization between prost protobuf structs and internal representations
+//!
+//! Parsers return errors on malformed protobufs, rather than panicking, as requests may be untrusted.
 
 use crate::errors::*;
 
 use crate::proto;
 use std::collections::{HashMap, BTreeMap};
-use crate::base::{Release, Nature, Jagged, Vector1D, Value, Array, Vector1DNull, NatureCategorical, NatureContinuous, AggregatorProperties, ValueProperties, HashmapProperties, JaggedProperties, DataType, Hashmap, ArrayProperties, ReleaseNode};
+use crate::base::{Release, Nature, Jagged, Vector1D, Value, Array, Vector1DNull, NatureCategorical, NatureContinuous, AggregatorProperties, ValueProperties, HashmapProperties, JaggedProperties, DataType, Hashmap, ArrayProperties, ReleaseNode, ColumnProperties, SharedColumns, ScalarProperties};
+
+/// Minimum number of columns before per-column properties are serialized as a shared template with exceptions
+pub const SHARED_COLUMNS_THRESHOLD: usize = 32;
 
 // PARSERS
 pub fn parse_bool_null(value: &proto::BoolNull) -> Option<bool> {
@@ -52,13 +57,13 @@ pub fn parse_array1d_str_null(value: &proto::Array1dStrNull) -> Vec<Option<Strin
     value.data.iter().map(parse_str_null).collect()
 }
 
-pub fn parse_array1d_null(value: &proto::Array1dNull) -> Vector1DNull {
-    match value.data.to_owned().unwrap() {
+pub fn parse_array1d_null(value: &proto::Array1dNull) -> Result<Vector1DNull> {
+    Ok(match value.data.to_owned().ok_or("array1d_null: data must be defined")? {
         proto::array1d_null::Data::Bool(vector) => Vector1DNull::Bool(parse_array1d_bool_null(&vector)),
         proto::array1d_null::Data::I64(vector) => Vector1DNull::I64(parse_array1d_i64_null(&vector)),
         proto::array1d_null::Data::F64(vector) => Vector1DNull::F64(parse_array1d_f64_null(&vector)),
         proto::array1d_null::Data::String(vector) => Vector1DNull::Str(parse_array1d_str_null(&vector)),
-    }
+    })
 }
 
 
@@ -71,51 +76,52 @@ pub fn parse_array1d_f64(value: &proto::Array1dF64) -> Vec<f64> { value.data.to_
 pub fn parse_array1d_str(value: &proto::Array1dStr) -> Vec<String> { value.data.to_owned() }
 
 
-pub fn parse_array1d(value: &proto::Array1d) -> Vector1D {
-    match value.data.to_owned().unwrap() {
+pub fn parse_array1d(value: &proto::Array1d) -> Result<Vector1D> {
+    Ok(match value.data.to_owned().ok_or("array1d: data must be defined")? {
         proto::array1d::Data::Bool(vector) => Vector1D::Bool(parse_array1d_bool(&vector)),
         proto::array1d::Data::I64(vector) => Vector1D::I64(parse_array1d_i64(&vector)),
         proto::array1d::Data::F64(vector) => Vector1D::F64(parse_array1d_f64(&vector)),
         proto::array1d::Data::String(vector) => Vector1D::Str(parse_array1d_str(&vector)),
-    }
+    })
 }
 
 
-pub fn parse_arraynd(value: &proto::ArrayNd) -> Array {
+pub fn parse_arraynd(value: &proto::ArrayNd) -> Result<Array> {
     let shape: Vec<usize> = value.shape.iter().map(|x| *x as usize).collect();
-    match parse_array1d(&value.flattened.to_owned().unwrap()) {
-        Vector1D::Bool(vector) => Array::Bool(ndarray::Array::from_shape_vec(shape, vector).unwrap().into_dyn()),
-        Vector1D::I64(vector) => Array::I64(ndarray::Array::from_shape_vec(shape, vector).unwrap().into_dyn()),
-        Vector1D::F64(vector) => Array::F64(ndarray::Array::from_shape_vec(shape, vector).unwrap().into_dyn()),
-        Vector1D::Str(vector) => Array::Str(ndarray::Array::from_shape_vec(shape, vector).unwrap().into_dyn()),
-    }
+    let shape_error = |_| Error::from("arraynd: shape must match the number of elements");
+    Ok(match parse_array1d(value.flattened.as_ref().ok_or("arraynd: flattened must be defined")?)? {
+        Vector1D::Bool(vector) => Array::Bool(ndarray::Array::from_shape_vec(shape, vector).map_err(shape_error)?.into_dyn()),
+        Vector1D::I64(vector) => Array::I64(ndarray::Array::from_shape_vec(shape, vector).map_err(shape_error)?.into_dyn()),
+        Vector1D::F64(vector) => Array::F64(ndarray::Array::from_shape_vec(shape, vector).map_err(shape_error)?.into_dyn()),
+        Vector1D::Str(vector) => Array::Str(ndarray::Array::from_shape_vec(shape, vector).map_err(shape_error)?.into_dyn()),
+    })
 }
 
-pub fn parse_hashmap_str(value: &proto::HashmapStr) -> BTreeMap<String, Value> {
-    value.data.iter().map(|(name, data)| (name.clone(), parse_value(data).unwrap())).collect()
+pub fn parse_hashmap_str(value: &proto::HashmapStr) -> Result<BTreeMap<String, Value>> {
+    value.data.iter().map(|(name, data)| Ok((name.clone(), parse_value(data)?))).collect()
 }
-pub fn parse_hashmap_i64(value: &proto::HashmapI64) -> BTreeMap<i64, Value> {
-    value.data.iter().map(|(name, data)| (*name, parse_value(data).unwrap())).collect()
+pub fn parse_hashmap_i64(value: &proto::HashmapI64) -> Result<BTreeMap<i64, Value>> {
+    value.data.iter().map(|(name, data)| Ok((*name, parse_value(data)?))).collect()
 }
-pub fn parse_hashmap_bool(value: &proto::HashmapBool) -> BTreeMap<bool, Value> {
-    value.data.iter().map(|(name, data)| (*name, parse_value(data).unwrap())).collect()
+pub fn parse_hashmap_bool(value: &proto::HashmapBool) -> Result<BTreeMap<bool, Value>> {
+    value.data.iter().map(|(name, data)| Ok((*name, parse_value(data)?))).collect()
 }
 
-pub fn parse_hashmap(value: &proto::Hashmap) -> Hashmap<Value> {
-    match value.variant.clone().unwrap() {
-        proto::hashmap::Variant::String(value) => Hashmap::Str(parse_hashmap_str(&value)),
-        proto::hashmap::Variant::I64(value) => Hashmap::I64(parse_hashmap_i64(&value)),
-        proto::hashmap::Variant::Bool(value) => Hashmap::Bool(parse_hashmap_bool(&value)),
-    }
+pub fn parse_hashmap(value: &proto::Hashmap) -> Result<Hashmap<Value>> {
+    Ok(match value.variant.as_ref().ok_or("hashmap: variant must be defined")? {
+        proto::hashmap::Variant::String(value) => Hashmap::Str(parse_hashmap_str(value)?),
+        proto::hashmap::Variant::I64(value) => Hashmap::I64(parse_hashmap_i64(value)?),
+        proto::hashmap::Variant::Bool(value) => Hashmap::Bool(parse_hashmap_bool(value)?),
+    })
 }
 
-pub fn parse_array1d_option(value: &proto::Array1dOption) -> Option<Vector1D> {
-    match value.data.to_owned() {
+pub fn parse_array1d_option(value: &proto::Array1dOption) -> Result<Option<Vector1D>> {
+    Ok(match value.data.to_owned() {
         Some(data) => match data {
-            proto::array1d_option::Data::Option(data) => Some(parse_array1d(&data)),
+            proto::array1d_option::Data::Option(data) => Some(parse_array1d(&data)?),
         },
         None => None
-    }
+    })
 }
 
 pub fn parse_data_type(value: proto::DataType) -> DataType {
@@ -127,51 +133,48 @@ pub fn parse_data_type(value: proto::DataType) -> DataType {
     }
 }
 
-pub fn parse_array2d_jagged(value: &proto::Array2dJagged) -> Jagged {
-    match proto::DataType::from_i32(value.data_type).unwrap() {
-        proto::DataType::Bool => Jagged::Bool(value.data.iter()
-            .map(|column| match parse_array1d_option(column) {
-                Some(vector) => Some(match vector {
-                    Vector1D::Bool(vector) => vector,
-                    _ => panic!()
-                }),
-                None => None
-            }).collect::<Vec<Option<Vec<bool>>>>()),
-        proto::DataType::F64 => Jagged::F64(value.data.iter()
-            .map(|column| match parse_array1d_option(column) {
-                Some(vector) => Some(match vector {
-                    Vector1D::F64(vector) => vector,
-                    _ => panic!()
-                }),
-                None => None
-            }).collect::<Vec<Option<Vec<f64>>>>()),
-        proto::DataType::I64 => Jagged::I64(value.data.iter()
-            .map(|column| match parse_array1d_option(column) {
-                Some(vector) => Some(match vector {
-                    Vector1D::I64(vector) => vector,
-                    _ => panic!()
-                }),
-                None => None
-            }).collect::<Vec<Option<Vec<i64>>>>()),
-        proto::DataType::String => Jagged::Str(value.data.iter()
-            .map(|column| match parse_array1d_option(column) {
-                Some(vector) => Some(match vector {
-                    Vector1D::Str(vector) => vector,
-                    _ => panic!()
-                }),
-                None => None
-            }).collect::<Vec<Option<Vec<String>>>>()),
-    }
+pub fn parse_array2d_jagged(value: &proto::Array2dJagged) -> Result<Jagged> {
+    let columns = value.data.iter()
+        .map(parse_array1d_option)
+        .collect::<Result<Vec<Option<Vector1D>>>>()?;
+    let type_error = || Error::from("array2d_jagged: every column must match the data type");
+
+    Ok(match proto::DataType::from_i32(value.data_type).ok_or("array2d_jagged: unknown data type")? {
+        proto::DataType::Bool => Jagged::Bool(columns.into_iter()
+            .map(|column| match column {
+                Some(Vector1D::Bool(vector)) => Ok(Some(vector)),
+                Some(_) => Err(type_error()),
+                None => Ok(None)
+            }).collect::<Result<Vec<Option<Vec<bool>>>>>()?),
+        proto::DataType::F64 => Jagged::F64(columns.into_iter()
+            .map(|column| match column {
+                Some(Vector1D::F64(vector)) => Ok(Some(vector)),
+                Some(_) => Err(type_error()),
+                None => Ok(None)
+            }).collect::<Result<Vec<Option<Vec<f64>>>>>()?),
+        proto::DataType::I64 => Jagged::I64(columns.into_iter()
+            .map(|column| match column {
+                Some(Vector1D::I64(vector)) => Ok(Some(vector)),
+                Some(_) => Err(type_error()),
+                None => Ok(None)
+            }).collect::<Result<Vec<Option<Vec<i64>>>>>()?),
+        proto::DataType::String => Jagged::Str(columns.into_iter()
+            .map(|column| match column {
+                Some(Vector1D::Str(vector)) => Ok(Some(vector)),
+                Some(_) => Err(type_error()),
+                None => Ok(None)
+            }).collect::<Result<Vec<Option<Vec<String>>>>>()?),
+    })
 }
 
 pub fn parse_value(value: &proto::Value) -> Result<Value> {
-    Ok(match value.data.to_owned().unwrap() {
+    Ok(match value.data.as_ref().ok_or("value: data must be defined")? {
         proto::value::Data::Array(data) =>
-            Value::Array(parse_arraynd(&data)),
+            Value::Array(parse_arraynd(data)?),
         proto::value::Data::Hashmap(data) =>
-            Value::Hashmap(parse_hashmap(&data)),
+            Value::Hashmap(parse_hashmap(data)?),
         proto::value::Data::Jagged(data) =>
-            Value::Jagged(parse_array2d_jagged(&data))
+            Value::Jagged(parse_array2d_jagged(data)?)
     })
 }
 
@@ -181,89 +184,160 @@ pub fn parse_release(release: &proto::Release) -> Result<Release> {
 }
 
 pub fn parse_release_node(release_node: &proto::ReleaseNode) -> Result<ReleaseNode> {
+    if release_node.value.is_none() && release_node.encrypted_value.is_some() {
+        return Err("value of the release node is encrypted, and must be decrypted with its release key".into())
+    }
     Ok(ReleaseNode {
         value: parse_value(release_node.value.as_ref()
             .ok_or_else(|| Error::from("value must be defined in a release node"))?)?,
         privacy_usages: release_node.privacy_usages.clone().map(|v| v.values),
-        public: release_node.public
+        public: release_node.public,
+        public_shape: release_node.public_shape,
+        public_metadata: release_node.public_metadata
     })
 }
 
-pub fn parse_value_properties(value: &proto::ValueProperties) -> ValueProperties {
-    match value.variant.clone().unwrap() {
+pub fn parse_value_properties(value: &proto::ValueProperties) -> Result<ValueProperties> {
+    Ok(match value.variant.as_ref().ok_or("value_properties: variant must be defined")? {
         proto::value_properties::Variant::Hashmap(value) =>
-            ValueProperties::Hashmap(parse_hashmap_properties(&value)),
+            ValueProperties::Hashmap(parse_hashmap_properties(value)?),
         proto::value_properties::Variant::Array(value) =>
-            ValueProperties::Array(parse_arraynd_properties(&value)),
+            ValueProperties::Array(parse_arraynd_properties(value)?),
         proto::value_properties::Variant::Jagged(value) =>
-            ValueProperties::Jagged(parse_array2d_jagged_properties(&value)),
-    }
+            ValueProperties::Jagged(parse_array2d_jagged_properties(value)),
+        proto::value_properties::Variant::Scalar(value) =>
+            ValueProperties::Scalar(parse_scalar_properties(value)?),
+    })
 }
 
-pub fn parse_hashmap_properties_str(value: &proto::HashmapValuePropertiesStr) -> Hashmap<ValueProperties> {
-    Hashmap::<ValueProperties>::Str(value.data.iter()
-        .map(|(name, properties)| (name.clone(), parse_value_properties(properties)))
-        .collect())
+pub fn parse_hashmap_properties_str(value: &proto::HashmapValuePropertiesStr) -> Result<Hashmap<ValueProperties>> {
+    Ok(Hashmap::<ValueProperties>::Str(value.data.iter()
+        .map(|(name, properties)| Ok((name.clone(), parse_value_properties(properties)?)))
+        .collect::<Result<_>>()?))
 }
-pub fn parse_hashmap_properties_bool(value: &proto::HashmapValuePropertiesBool) -> Hashmap<ValueProperties> {
-    Hashmap::<ValueProperties>::Bool(value.data.iter()
-        .map(|(name, properties)| (*name, parse_value_properties(properties)))
-        .collect())
+pub fn parse_hashmap_properties_bool(value: &proto::HashmapValuePropertiesBool) -> Result<Hashmap<ValueProperties>> {
+    Ok(Hashmap::<ValueProperties>::Bool(value.data.iter()
+        .map(|(name, properties)| Ok((*name, parse_value_properties(properties)?)))
+        .collect::<Result<_>>()?))
 }
-pub fn parse_hashmap_properties_i64(value: &proto::HashmapValuePropertiesI64) -> Hashmap<ValueProperties> {
-    Hashmap::<ValueProperties>::I64(value.data.iter()
-        .map(|(name, properties)| (*name, parse_value_properties(properties)))
-        .collect())
+pub fn parse_hashmap_properties_i64(value: &proto::HashmapValuePropertiesI64) -> Result<Hashmap<ValueProperties>> {
+    Ok(Hashmap::<ValueProperties>::I64(value.data.iter()
+        .map(|(name, properties)| Ok((*name, parse_value_properties(properties)?)))
+        .collect::<Result<_>>()?))
 }
 
-pub fn parse_hashmap_properties(value: &proto::HashmapProperties) -> HashmapProperties {
-    HashmapProperties {
-        num_records: parse_i64_null(&value.num_records.clone().unwrap()),
+pub fn parse_hashmap_properties(value: &proto::HashmapProperties) -> Result<HashmapProperties> {
+    Ok(HashmapProperties {
+        num_records: parse_i64_null(value.num_records.as_ref().ok_or("hashmap_properties: num_records must be defined")?),
         disjoint: false,
-        properties: match value.value_properties.clone().unwrap().variant.unwrap() {
-            proto::hashmap_value_properties::Variant::String(value) => parse_hashmap_properties_str(&value),
-            proto::hashmap_value_properties::Variant::Bool(value) => parse_hashmap_properties_bool(&value),
-            proto::hashmap_value_properties::Variant::I64(value) => parse_hashmap_properties_i64(&value),
+        properties: match value.value_properties.as_ref().and_then(|properties| properties.variant.as_ref())
+            .ok_or("hashmap_properties: value_properties must be defined")? {
+            proto::hashmap_value_properties::Variant::String(value) => parse_hashmap_properties_str(value)?,
+            proto::hashmap_value_properties::Variant::Bool(value) => parse_hashmap_properties_bool(value)?,
+            proto::hashmap_value_properties::Variant::I64(value) => parse_hashmap_properties_i64(value)?,
         },
-        columnar: value.columnar
-    }
+        columnar: value.columnar,
+        releasable: value.releasable,
+        group_id: value.group_id.clone()
+    })
 }
 
-pub fn parse_arraynd_properties(value: &proto::ArrayNdProperties) -> ArrayProperties {
-    ArrayProperties {
-        num_records: parse_i64_null(&value.num_records.to_owned().unwrap()),
-        num_columns: parse_i64_null(&value.num_columns.to_owned().unwrap()),
+pub fn parse_arraynd_properties(value: &proto::ArrayNdProperties) -> Result<ArrayProperties> {
+    let mut properties = ArrayProperties {
+        num_records: parse_i64_null(value.num_records.as_ref().ok_or("arraynd_properties: num_records must be defined")?),
+        num_columns: parse_i64_null(value.num_columns.as_ref().ok_or("arraynd_properties: num_columns must be defined")?),
         nullity: value.nullity,
         releasable: value.releasable,
-        c_stability: parse_array1d_f64(&value.c_stability.to_owned().unwrap()),
-        aggregator: match value.aggregator.clone() {
-            Some(aggregator) => Some(AggregatorProperties {
-                component: aggregator.component.clone().unwrap().variant.unwrap(),
-                properties: aggregator.properties.iter()
-                    .map(|(name, properties)| (name.clone(), parse_value_properties(&properties)))
-                    .collect::<HashMap<String, ValueProperties>>()
-            }),
-            None => None
-        },
+        public_shape: value.public_shape,
+        public_metadata: value.public_metadata,
+        c_stability: parse_array1d_f64(value.c_stability.as_ref().ok_or("arraynd_properties: c_stability must be defined")?),
+        aggregator: value.aggregator.as_ref().map(parse_aggregator_properties).transpose()?,
         nature: match value.nature.to_owned() {
             Some(nature) => match nature {
                 proto::array_nd_properties::Nature::Continuous(continuous) =>
-                    Some(Nature::Continuous(NatureContinuous {
-                        lower: parse_array1d_null(&continuous.minimum.unwrap()),
-                        upper: parse_array1d_null(&continuous.maximum.unwrap()),
-                    })),
+                    Some(parse_nature_continuous(continuous)?),
                 proto::array_nd_properties::Nature::Categorical(categorical) =>
-                    Some(Nature::Categorical(NatureCategorical {
-                        categories: parse_array2d_jagged(&categorical.categories.unwrap())
-                    }))
+                    Some(parse_nature_categorical(categorical)?)
             },
             None => None,
         },
-        data_type: parse_data_type(proto::DataType::from_i32(value.data_type).unwrap()),
+        data_type: parse_data_type(proto::DataType::from_i32(value.data_type).ok_or("arraynd_properties: unknown data type")?),
         dataset_id: value.dataset_id.as_ref().and_then(parse_i64_null),
         is_not_empty: value.is_not_empty,
+        max_contributions: match value.max_contributions { 0 => None, bound => Some(bound) },
         dimensionality: value.dimensionality
-    }
+    };
+
+    if let Some(shared_columns) = &value.shared_columns {
+        let shared_columns = parse_shared_columns(shared_columns, ColumnProperties {
+            c_stability: properties.c_stability.first().cloned().unwrap_or(1.),
+            nature: properties.nature.take(),
+        })?;
+        properties.set_columns(&shared_columns.expand());
+        properties.num_columns = parse_i64_null(value.num_columns.as_ref().ok_or("arraynd_properties: num_columns must be defined")?);
+    }
+    Ok(properties)
+}
+
+pub fn parse_aggregator_properties(value: &proto::array_nd_properties::AggregatorProperties) -> Result<AggregatorProperties> {
+    Ok(AggregatorProperties {
+        component: value.component.as_ref().and_then(|component| component.variant.clone())
+            .ok_or("aggregator_properties: component must be defined")?,
+        properties: value.properties.iter()
+            .map(|(name, properties)| Ok((name.clone(), parse_value_properties(&properties)?)))
+            .collect::<Result<HashMap<String, ValueProperties>>>()?
+    })
+}
+
+pub fn parse_shared_columns(value: &proto::SharedColumns, template: ColumnProperties) -> Result<SharedColumns> {
+    Ok(SharedColumns {
+        num_columns: value.num_columns as usize,
+        template,
+        exceptions: value.exceptions.iter()
+            .map(|exception| Ok((exception.index as usize, ColumnProperties {
+                c_stability: exception.c_stability,
+                nature: match exception.nature.to_owned() {
+                    Some(proto::shared_columns::exception::Nature::Continuous(continuous)) =>
+                        Some(parse_nature_continuous(continuous)?),
+                    Some(proto::shared_columns::exception::Nature::Categorical(categorical)) =>
+                        Some(parse_nature_categorical(categorical)?),
+                    None => None
+                }
+            })))
+            .collect::<Result<_>>()?
+    })
+}
+
+pub fn parse_nature_continuous(value: proto::NatureContinuous) -> Result<Nature> {
+    Ok(Nature::Continuous(NatureContinuous {
+        lower: parse_array1d_null(value.minimum.as_ref().ok_or("nature_continuous: minimum must be defined")?)?,
+        upper: parse_array1d_null(value.maximum.as_ref().ok_or("nature_continuous: maximum must be defined")?)?,
+    }))
+}
+
+pub fn parse_nature_categorical(value: proto::NatureCategorical) -> Result<Nature> {
+    Ok(Nature::Categorical(NatureCategorical {
+        categories: parse_array2d_jagged(value.categories.as_ref().ok_or("nature_categorical: categories must be defined")?)?
+    }))
+}
+
+pub fn parse_scalar_properties(value: &proto::ScalarProperties) -> Result<ScalarProperties> {
+    Ok(ScalarProperties {
+        nullity: value.nullity,
+        releasable: value.releasable,
+        public_metadata: value.public_metadata,
+        c_stability: value.c_stability,
+        aggregator: value.aggregator.as_ref().map(parse_aggregator_properties).transpose()?,
+        nature: match value.nature.to_owned() {
+            Some(proto::scalar_properties::Nature::Continuous(continuous)) =>
+                Some(parse_nature_continuous(continuous)?),
+            Some(proto::scalar_properties::Nature::Categorical(categorical)) =>
+                Some(parse_nature_categorical(categorical)?),
+            None => None
+        },
+        data_type: parse_data_type(proto::DataType::from_i32(value.data_type).ok_or("scalar_properties: unknown data type")?),
+        dataset_id: value.dataset_id.as_ref().and_then(parse_i64_null),
+    })
 }
 
 pub fn parse_array2d_jagged_properties(value: &proto::Vector2DJaggedProperties) -> JaggedProperties {
@@ -495,7 +569,10 @@ pub fn serialize_release_node(release_node: &ReleaseNode) -> Result<proto::Relea
     Ok(proto::ReleaseNode {
         value: Some(serialize_value(&release_node.value)?),
         privacy_usages: release_node.privacy_usages.as_ref().map(|v| proto::PrivacyUsages {values: v.clone()}),
-        public: release_node.public
+        public: release_node.public,
+        public_shape: release_node.public_shape,
+        public_metadata: release_node.public_metadata,
+        encrypted_value: None
     })
 }
 
@@ -532,45 +609,110 @@ pub fn serialize_hashmap_properties(value: &HashmapProperties) -> proto::Hashmap
                 Hashmap::Bool(value) => proto::hashmap_value_properties::Variant::Bool(serialize_hashmap_properties_bool(&value)),
             })
         }),
-        columnar: value.columnar
+        columnar: value.columnar,
+        releasable: value.releasable,
+        group_id: value.group_id.clone()
     }
 }
 
 pub fn serialize_arraynd_properties(value: &ArrayProperties) -> proto::ArrayNdProperties {
+    // wide data with mostly identical columns is stored as a template column and exceptions
+    let shared_columns = Some(value)
+        .filter(|value| value.c_stability.len() >= SHARED_COLUMNS_THRESHOLD)
+        .and_then(|value| value.shared_columns().ok())
+        .filter(|shared| shared.exceptions.len() * 2 < shared.num_columns);
+
+    let (c_stability, nature) = match &shared_columns {
+        Some(shared) => (vec![shared.template.c_stability], shared.template.nature.clone()),
+        None => (value.c_stability.clone(), value.nature.clone())
+    };
+
     proto::ArrayNdProperties {
         num_records: Some(serialize_i64_null(&value.num_records)),
         num_columns: Some(serialize_i64_null(&value.num_columns)),
         nullity: value.nullity,
         releasable: value.releasable,
-        c_stability: Some(serialize_array1d_f64(&value.c_stability)),
-        nature: match value.clone().nature {
-            Some(nature) => match nature {
-                Nature::Categorical(categorical) => Some(proto::array_nd_properties::Nature::Categorical(proto::NatureCategorical {
-                    categories: Some(serialize_array2d_jagged(&categorical.categories))
-                })),
-                Nature::Continuous(x) => Some(proto::array_nd_properties::Nature::Continuous(proto::NatureContinuous {
-                    minimum: Some(serialize_array1d_null(&x.lower)),
-                    maximum: Some(serialize_array1d_null(&x.upper)),
-                }))
-            },
-            None => None
-        },
-        aggregator: match value.aggregator.clone() {
-            Some(aggregator) => Some(proto::array_nd_properties::AggregatorProperties {
-                component: Some(proto::Component {
-                    variant: Some(aggregator.component),
-                    omit: true, batch: 0, arguments: HashMap::new(),
-                }),
-                properties: aggregator.properties.iter()
-                    .map(|(name, properties)| (name.clone(), serialize_value_properties(&properties)))
-                    .collect::<HashMap<String, proto::ValueProperties>>()
-            }),
+        public_shape: value.public_shape,
+        public_metadata: value.public_metadata,
+        c_stability: Some(serialize_array1d_f64(&c_stability)),
+        nature: match nature {
+            Some(Nature::Categorical(categorical)) =>
+                Some(proto::array_nd_properties::Nature::Categorical(serialize_nature_categorical(&categorical))),
+            Some(Nature::Continuous(continuous)) =>
+                Some(proto::array_nd_properties::Nature::Continuous(serialize_nature_continuous(&continuous))),
             None => None
         },
+        aggregator: value.aggregator.as_ref().map(serialize_aggregator_properties),
         data_type: serialize_data_type(&value.data_type) as i32,
         dataset_id: Some(serialize_i64_null(&value.dataset_id)),
         is_not_empty: value.is_not_empty,
-        dimensionality: value.dimensionality
+        max_contributions: value.max_contributions.unwrap_or(0),
+        dimensionality: value.dimensionality,
+        shared_columns: shared_columns.as_ref().map(serialize_shared_columns)
+    }
+}
+
+pub fn serialize_aggregator_properties(value: &AggregatorProperties) -> proto::array_nd_properties::AggregatorProperties {
+    proto::array_nd_properties::AggregatorProperties {
+        component: Some(proto::Component {
+            variant: Some(value.component.clone()),
+            omit: true, batch: 0, arguments: HashMap::new(),
+        }),
+        properties: value.properties.iter()
+            .map(|(name, properties)| (name.clone(), serialize_value_properties(&properties)))
+            .collect::<HashMap<String, proto::ValueProperties>>()
+    }
+}
+
+pub fn serialize_scalar_properties(value: &ScalarProperties) -> proto::ScalarProperties {
+    proto::ScalarProperties {
+        nullity: value.nullity,
+        releasable: value.releasable,
+        public_metadata: value.public_metadata,
+        c_stability: value.c_stability,
+        aggregator: value.aggregator.as_ref().map(serialize_aggregator_properties),
+        data_type: serialize_data_type(&value.data_type) as i32,
+        nature: match &value.nature {
+            Some(Nature::Categorical(categorical)) =>
+                Some(proto::scalar_properties::Nature::Categorical(serialize_nature_categorical(categorical))),
+            Some(Nature::Continuous(continuous)) =>
+                Some(proto::scalar_properties::Nature::Continuous(serialize_nature_continuous(continuous))),
+            None => None
+        },
+        dataset_id: Some(serialize_i64_null(&value.dataset_id)),
+    }
+}
+
+/// Serialize the exceptions of a SharedColumns. The template is stored on the enclosing ArrayNDProperties.
+pub fn serialize_shared_columns(value: &SharedColumns) -> proto::SharedColumns {
+    proto::SharedColumns {
+        num_columns: value.num_columns as u32,
+        exceptions: value.exceptions.iter()
+            .map(|(index, column)| proto::shared_columns::Exception {
+                index: *index as u32,
+                c_stability: column.c_stability,
+                nature: match &column.nature {
+                    Some(Nature::Categorical(categorical)) =>
+                        Some(proto::shared_columns::exception::Nature::Categorical(serialize_nature_categorical(categorical))),
+                    Some(Nature::Continuous(continuous)) =>
+                        Some(proto::shared_columns::exception::Nature::Continuous(serialize_nature_continuous(continuous))),
+                    None => None
+                }
+            })
+            .collect()
+    }
+}
+
+pub fn serialize_nature_continuous(value: &NatureContinuous) -> proto::NatureContinuous {
+    proto::NatureContinuous {
+        minimum: Some(serialize_array1d_null(&value.lower)),
+        maximum: Some(serialize_array1d_null(&value.upper)),
+    }
+}
+
+pub fn serialize_nature_categorical(value: &NatureCategorical) -> proto::NatureCategorical {
+    proto::NatureCategorical {
+        categories: Some(serialize_array2d_jagged(&value.categories))
     }
 }
 
@@ -588,7 +730,100 @@ pub fn serialize_value_properties(value: &ValueProperties) -> proto::ValueProper
             ValueProperties::Array(value) =>
                 proto::value_properties::Variant::Array(serialize_arraynd_properties(value)),
             ValueProperties::Jagged(value) =>
-                proto::value_properties::Variant::Jagged(serialize_vector2d_jagged_properties(value))
+                proto::value_properties::Variant::Jagged(serialize_vector2d_jagged_properties(value)),
+            ValueProperties::Scalar(value) =>
+                proto::value_properties::Variant::Scalar(serialize_scalar_properties(value))
         })
     }
 }
+
+
+#[cfg(test)]
+mod test_serial {
+    use crate::base::{ArrayProperties, DataType, Nature, NatureContinuous, Vector1DNull, ValueProperties};
+    use crate::utilities::serial::{serialize_arraynd_properties, parse_arraynd_properties, serialize_value_properties, parse_value_properties};
+
+    fn wide_properties(num_columns: usize) -> ArrayProperties {
+        let mut upper = vec![Some(1.); num_columns];
+        if num_columns > 7 { upper[7] = Some(10.); }
+        ArrayProperties {
+            num_records: Some(100),
+            num_columns: Some(num_columns as i64),
+            nullity: false,
+            releasable: false,
+            public_shape: false,
+            public_metadata: false,
+            c_stability: vec![1.; num_columns],
+            aggregator: None,
+            nature: Some(Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::F64(vec![Some(0.); num_columns]),
+                upper: Vector1DNull::F64(upper),
+            })),
+            data_type: DataType::F64,
+            dataset_id: Some(0),
+            is_not_empty: true,
+            max_contributions: None,
+            dimensionality: 2,
+        }
+    }
+
+    #[test]
+    fn test_shared_columns_round_trip() {
+        let properties = wide_properties(1000);
+        let serialized = serialize_arraynd_properties(&properties);
+
+        assert_eq!(serialized.c_stability.as_ref().unwrap().data.len(), 1);
+        assert_eq!(serialized.shared_columns.as_ref().unwrap().exceptions.len(), 1);
+
+        let parsed = parse_arraynd_properties(&serialized).unwrap();
+        assert_eq!(parsed.num_columns, Some(1000));
+        assert_eq!(parsed.c_stability, properties.c_stability);
+        assert_eq!(parsed.nature, properties.nature);
+    }
+
+    #[test]
+    fn test_scalar_form_round_trip() {
+        let mut properties = wide_properties(1);
+        properties.dimensionality = 0;
+        properties.num_records = Some(1);
+
+        let scalar = ValueProperties::Array(properties.clone()).into_scalar_form();
+        let parsed = parse_value_properties(&serialize_value_properties(&scalar)).unwrap();
+        assert!(parsed.scalar().is_ok());
+
+        let array = parsed.into_array_form();
+        assert_eq!(array.array().unwrap().num_records, Some(1));
+        assert_eq!(array.array().unwrap().nature, properties.nature);
+
+        // arrays with any extent along an axis stay arrays
+        assert!(ValueProperties::Array(wide_properties(1)).into_scalar_form().scalar().is_err());
+    }
+
+    #[test]
+    fn test_select_columns() {
+        let selected = wide_properties(1000).select_columns(&[7, 8]).unwrap();
+        assert_eq!(selected.num_columns, Some(2));
+        assert_eq!(selected.nature.unwrap().continuous().unwrap().upper,
+                   Vector1DNull::F64(vec![Some(10.), Some(1.)]));
+    }
+
+    #[test]
+    fn test_malformed_value() {
+        use crate::proto;
+        use crate::utilities::serial::parse_value;
+
+        assert!(parse_value(&proto::Value { data: None }).is_err());
+
+        // three elements may not fill a two by two array
+        let malformed = proto::Value {
+            data: Some(proto::value::Data::Array(proto::ArrayNd {
+                flattened: Some(proto::Array1d {
+                    data: Some(proto::array1d::Data::F64(proto::Array1dF64 { data: vec![1., 2., 3.] }))
+                }),
+                shape: vec![2, 2],
+                order: vec![],
+            }))
+        };
+        assert!(parse_value(&malformed).is_err());
+    }
+}
@@ -0,0 +1,191 @@
+//! Slicing an analysis down to the nodes needed by a subset of its outputs
+//!
+//! A runtime may release urgent statistics from a slice first, and defer the rest of the analysis.
+//! The slice is re-scoped so that accounting stays coherent with the whole analysis:
+//! each mechanism in the slice may spend no more delta than it is allotted in the whole analysis,
+//! and the privacy usage of the deferred mechanisms is reported alongside the slice.
+
+use crate::errors::*;
+
+use std::collections::{HashMap, HashSet};
+
+use crate::proto;
+use crate::utilities::{get_charged_privacy_usage, get_charged_privacy_usages, get_epsilon, get_delta, privacy_usage_reducer};
+
+use itertools::Itertools;
+
+/// Ids of the nodes, and of every node they depend on.
+pub fn get_ancestors(graph: &HashMap<u32, proto::Component>, node_ids: &[u32]) -> HashSet<u32> {
+    let mut ancestors = HashSet::<u32>::new();
+    let mut traversal = node_ids.to_vec();
+    while let Some(node_id) = traversal.pop() {
+        if !ancestors.insert(node_id) {
+            continue
+        }
+        if let Some(component) = graph.get(&node_id) {
+            traversal.extend(component.arguments.values());
+        }
+    }
+    ancestors
+}
+
+/// Minimal sub-analysis that produces the target nodes.
+///
+/// The slice keeps the ancestors of the targets, and the releases of those ancestors.
+/// External usages are kept, as they were spent on the same dataset regardless of which nodes are evaluated.
+/// The slice no longer matches the approval token of the analysis, so it must be approved again if a release gate requires it.
+pub fn slice_analysis(
+    analysis: &proto::Analysis,
+    release: &proto::Release,
+    node_ids: &[u32],
+) -> Result<proto::AnalysisSlice> {
+    let graph = &analysis.computation_graph.as_ref()
+        .ok_or_else(|| Error::from("computation graph must be defined"))?.value;
+    if node_ids.is_empty() {
+        return Err("node_ids: at least one target must be supplied".into())
+    }
+    if let Some(node_id) = node_ids.iter().find(|node_id| !graph.contains_key(node_id)) {
+        bail!("node_ids: node {} is not in the computation graph", node_id)
+    }
+
+    let ancestors = get_ancestors(graph, node_ids);
+    let (sliced_graph, deferred_graph): (HashMap<u32, proto::Component>, HashMap<u32, proto::Component>) = graph.iter()
+        .map(|(node_id, component)| (*node_id, component.clone()))
+        .partition(|(node_id, _)| ancestors.contains(node_id));
+
+    let deferred_privacy_usage = deferred_graph.keys().sorted()
+        .map(|node_id| get_charged_privacy_usage(graph, node_id, release))
+        .collect::<Result<Vec<Option<proto::PrivacyUsage>>>>()?.into_iter().flatten()
+        .fold1(|usage_1, usage_2| privacy_usage_reducer(&usage_1, &usage_2, &|l, r| l + r));
+
+    let privacy_definition = match &analysis.privacy_definition {
+        Some(privacy_definition) => Some(rescope_privacy_definition(privacy_definition, graph, &sliced_graph, release)?),
+        None => None
+    };
+
+    Ok(proto::AnalysisSlice {
+        analysis: Some(proto::Analysis {
+            privacy_definition,
+            computation_graph: Some(proto::ComputationGraph { value: sliced_graph }),
+            approval_token: Vec::new(),
+            external_usages: analysis.external_usages.clone(),
+            optimizations: analysis.optimizations.clone(),
+        }),
+        release: Some(proto::Release {
+            values: release.values.iter()
+                .filter(|(node_id, _)| ancestors.contains(node_id))
+                .map(|(node_id, release_node)| (*node_id, release_node.clone()))
+                .collect()
+        }),
+        deferred_node_ids: deferred_graph.keys().cloned().sorted().collect(),
+        deferred_privacy_usage,
+    })
+}
+
+/// Shrink the delta cap of the privacy definition, so that each mechanism in the slice is allotted the same delta as in the whole analysis.
+///
+/// Under an equal split, the cap shrinks with the number of mechanisms that spend delta,
+/// and under a proportional split, with the epsilon of those mechanisms.
+/// User-specified allotments are fractions of the cap per node, so they are kept as-is.
+fn rescope_privacy_definition(
+    privacy_definition: &proto::PrivacyDefinition,
+    graph: &HashMap<u32, proto::Component>,
+    sliced_graph: &HashMap<u32, proto::Component>,
+    release: &proto::Release,
+) -> Result<proto::PrivacyDefinition> {
+    use proto::privacy_definition::DeltaSplit;
+
+    // epsilon of every node that spends delta
+    let spenders = |graph: &HashMap<u32, proto::Component>| get_charged_privacy_usages(graph, release)?.into_iter()
+        .filter(|(_, usage)| get_delta(usage).unwrap_or(0.) > 0.)
+        .map(|(_, usage)| get_epsilon(&usage))
+        .collect::<Result<Vec<f64>>>();
+    let (spenders, sliced_spenders) = (spenders(graph)?, spenders(sliced_graph)?);
+
+    let share = match DeltaSplit::from_i32(privacy_definition.delta_split)
+        .ok_or_else(|| Error::from("delta_split: must be one of Equal, Proportional or UserSpecified"))? {
+        _ if spenders.is_empty() => 1.,
+        DeltaSplit::Equal => sliced_spenders.len() as f64 / spenders.len() as f64,
+        DeltaSplit::Proportional => sliced_spenders.iter().sum::<f64>() / spenders.iter().sum::<f64>(),
+        DeltaSplit::UserSpecified => 1.
+    };
+
+    Ok(proto::PrivacyDefinition {
+        delta_cap: privacy_definition.delta_cap * share,
+        delta_allotments: privacy_definition.delta_allotments.iter()
+            .filter(|(node_id, _)| sliced_graph.contains_key(node_id))
+            .map(|(node_id, fraction)| (*node_id, *fraction))
+            .collect(),
+        ..privacy_definition.clone()
+    })
+}
+
+
+#[cfg(test)]
+mod test_slice {
+    use crate::proto;
+    use crate::hashmap;
+    use crate::utilities::slice::slice_analysis;
+    use crate::utilities::get_epsilon;
+    use std::collections::HashMap;
+    use itertools::Itertools;
+
+    fn component(arguments: HashMap<String, u32>, variant: proto::component::Variant) -> proto::Component {
+        proto::Component { arguments, variant: Some(variant), omit: false, batch: 0 }
+    }
+
+    fn gaussian(data: u32, epsilon: f64, delta: f64) -> proto::Component {
+        component(hashmap!["data".to_string() => data], proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+            privacy_usage: vec![proto::PrivacyUsage {
+                distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate { epsilon, delta }))
+            }]
+        }))
+    }
+
+    #[test]
+    fn test_slice_analysis() {
+        // two mechanisms over unrelated means
+        let analysis = proto::Analysis {
+            privacy_definition: Some(proto::PrivacyDefinition {
+                group_size: 1,
+                distance: proto::privacy_definition::Distance::Approximate as i32,
+                neighboring: proto::privacy_definition::Neighboring::AddRemove as i32,
+                delta_cap: 1e-6,
+                delta_split: proto::privacy_definition::DeltaSplit::Equal as i32,
+                delta_allotments: HashMap::new(),
+                composition: proto::privacy_definition::Composition::Basic as i32,
+                composition_delta: 0.,
+                epsilon_floor: 0.,
+                epsilon_ceiling: 0.,
+                epsilon_exemptions: Vec::new(),
+                delta_inverse_records: false,
+                epsilon_cap: 0.,
+                enforce_budget: false
+            }),
+            computation_graph: Some(proto::ComputationGraph {
+                value: hashmap![
+                    1 => component(HashMap::new(), proto::component::Variant::Mean(proto::Mean {})),
+                    2 => gaussian(1, 1., 1e-7),
+                    3 => component(HashMap::new(), proto::component::Variant::Mean(proto::Mean {})),
+                    4 => gaussian(3, 0.5, 1e-7)
+                ]
+            }),
+            approval_token: vec![1],
+            external_usages: Vec::new(),
+            optimizations: None
+        };
+        let release = proto::Release { values: HashMap::new() };
+
+        let slice = slice_analysis(&analysis, &release, &[2]).unwrap();
+        let sliced = slice.analysis.unwrap();
+        assert_eq!(sliced.computation_graph.unwrap().value.keys().cloned().sorted().collect::<Vec<u32>>(), vec![1, 2]);
+        assert_eq!(slice.deferred_node_ids, vec![3, 4]);
+        assert_eq!(get_epsilon(&slice.deferred_privacy_usage.unwrap()).unwrap(), 0.5);
+
+        // the remaining mechanism is allotted the same delta as in the whole analysis
+        assert_eq!(sliced.privacy_definition.unwrap().delta_cap, 0.5e-6);
+        assert!(sliced.approval_token.is_empty());
+
+        assert!(slice_analysis(&analysis, &release, &[5]).is_err());
+    }
+}
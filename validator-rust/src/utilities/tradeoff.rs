@@ -0,0 +1,175 @@
+//! Epsilon-delta trade-off curves of the Gaussian mechanism
+//!
+//! A Gaussian mechanism satisfies (epsilon, delta)-DP for a whole curve of pairs, not only the pair it was calibrated with.
+//! The curve is sampled at standard deltas, using the exact privacy profile of the Gaussian mechanism.
+
+use crate::errors::*;
+
+use std::collections::HashMap;
+
+use crate::proto;
+use crate::base::ValueProperties;
+use crate::utilities::{get_charged_privacy_usage, get_requested_privacy_usages, broadcast_privacy_usage};
+use crate::utilities::privacy::get_gaussian_noise_multiplier;
+use statrs::function::erf;
+
+/// Deltas at which the trade-off curves are sampled.
+pub const CURVE_DELTAS: [f64; 6] = [1e-3, 1e-4, 1e-5, 1e-6, 1e-8, 1e-10];
+
+/// Epsilons beyond this are not searched, as the privacy profile is not numerically stable.
+const MAX_EPSILON: f64 = 500.;
+
+/// Ratio of the noise scale to the L2 sensitivity, for a Gaussian mechanism calibrated with the classical bound
+/// `sigma = sqrt(2 ln(1.25 / delta)) sensitivity / epsilon`, as in the runtime.
+pub fn gaussian_noise_multiplier(epsilon: f64, delta: f64) -> Result<f64> {
+    if epsilon.is_nan() || epsilon <= 0. || delta.is_nan() || delta <= 0. || delta >= 1. {
+        return Err("epsilon must be positive, and delta must be within (0, 1)".into())
+    }
+    Ok((2. * (1.25 / delta).ln()).sqrt() / epsilon)
+}
+
+/// Smallest delta for which a Gaussian mechanism with the given noise multiplier is (epsilon, delta)-DP.
+///
+/// `delta(epsilon) = Phi(1 / (2 s) - epsilon s) - e^epsilon Phi(-1 / (2 s) - epsilon s)`, for noise multiplier `s`.
+/// Balle and Wang. Improving the Gaussian Mechanism for Differential Privacy. ICML 2018.
+pub fn gaussian_delta(noise_multiplier: f64, epsilon: f64) -> f64 {
+    let normal_cdf = |x: f64| 0.5 * erf::erfc(-x / 2_f64.sqrt());
+    let (half_inverse, shift) = (1. / (2. * noise_multiplier), epsilon * noise_multiplier);
+    (normal_cdf(half_inverse - shift) - epsilon.exp() * normal_cdf(-half_inverse - shift)).max(0.)
+}
+
+/// Smallest epsilon for which a Gaussian mechanism with the given noise multiplier is (epsilon, delta)-DP.
+///
+/// The privacy profile is decreasing in epsilon, so epsilon is found by bisection.
+pub fn gaussian_epsilon(noise_multiplier: f64, delta: f64) -> f64 {
+    if gaussian_delta(noise_multiplier, 0.) <= delta {
+        return 0.
+    }
+    let mut upper = 1.;
+    while gaussian_delta(noise_multiplier, upper) > delta && upper < MAX_EPSILON {
+        upper *= 2.;
+    }
+    let mut lower = 0.;
+    (0..100).for_each(|_| {
+        let middle = (lower + upper) / 2.;
+        match gaussian_delta(noise_multiplier, middle) > delta {
+            true => lower = middle,
+            false => upper = middle
+        }
+    });
+    upper
+}
+
+/// Points of the trade-off curve at the standard deltas.
+pub fn gaussian_curve(noise_multiplier: f64) -> Vec<proto::EpsilonDeltaPoint> {
+    CURVE_DELTAS.iter()
+        .map(|delta| proto::EpsilonDeltaPoint {
+            epsilon: gaussian_epsilon(noise_multiplier, *delta),
+            delta: *delta,
+        })
+        .collect()
+}
+
+/// Trade-off curve of every Gaussian mechanism in an expanded graph.
+///
+/// A mechanism over several columns releases independent Gaussians, which compose exactly
+/// into a single Gaussian whose inverse squared noise multiplier is the sum over the columns.
+pub fn epsilon_delta_curves(
+    graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, ValueProperties>,
+    release: &proto::Release,
+) -> Result<proto::EpsilonDeltaCurves> {
+    let mut node_ids = graph.iter()
+        .filter(|(_, component)| match component.variant {
+            Some(proto::component::Variant::GaussianMechanism(_)) => true,
+            _ => false
+        })
+        .map(|(node_id, _)| *node_id)
+        .collect::<Vec<u32>>();
+    node_ids.sort();
+
+    Ok(proto::EpsilonDeltaCurves {
+        curves: node_ids.into_iter()
+            .map(|node_id| {
+                let component = graph.get(&node_id).ok_or("node_id: missing from graph")?;
+
+                // the usage actually spent, if released, takes priority over the usage requested
+                let usages = match release.values.get(&node_id).and_then(|release_node| release_node.privacy_usages.as_ref()) {
+                    Some(usages) => usages.values.clone(),
+                    None => {
+                        let num_columns = component.arguments.get("data")
+                            .and_then(|data_id| properties.get(data_id))
+                            .ok_or_else(|| Error::from(format!("node_id {}: data properties are missing", node_id)))?
+                            .array()?.num_columns()?;
+                        broadcast_privacy_usage(
+                            &get_requested_privacy_usages(component)?.unwrap_or_else(Vec::new),
+                            num_columns as usize)?
+                    }
+                };
+
+                let inverse_square = usages.iter()
+                    .map(|usage| get_gaussian_noise_multiplier(usage)
+                        .map(|multiplier| multiplier.powi(-2)))
+                    .collect::<Result<Vec<f64>>>()?.into_iter().sum::<f64>();
+
+                Ok(proto::EpsilonDeltaCurve {
+                    node_id,
+                    mechanism: "Gaussian".to_string(),
+                    privacy_usage: get_charged_privacy_usage(graph, &node_id, release)?,
+                    points: gaussian_curve(inverse_square.powf(-0.5)),
+                })
+            })
+            .collect::<Result<Vec<proto::EpsilonDeltaCurve>>>()?
+    })
+}
+
+/// Trade-off curve of a Gaussian release in a report, from its json privacy loss.
+///
+/// The privacy loss may be a single approximate usage, or a list of usages that compose.
+/// A usage that the release split evenly over several mechanisms is treated as one mechanism,
+/// which is conservative, as splitting adds more noise than the combined usage calibrates.
+pub fn privacy_loss_to_curve(privacy_loss: &serde_json::Value) -> Option<serde_json::Value> {
+    let usages = match privacy_loss {
+        serde_json::Value::Array(usages) => usages.iter().collect(),
+        usage => vec![usage]
+    };
+    let inverse_square = usages.into_iter()
+        .map(|usage| {
+            if usage.get("name")?.as_str()? != "approximate" {
+                return None
+            }
+            gaussian_noise_multiplier(usage.get("epsilon")?.as_f64()?, usage.get("delta")?.as_f64()?).ok()
+                .map(|multiplier| multiplier.powi(-2))
+        })
+        .collect::<Option<Vec<f64>>>()?.into_iter().sum::<f64>();
+    if inverse_square <= 0. {
+        return None
+    }
+
+    Some(serde_json::json!(gaussian_curve(inverse_square.powf(-0.5)).iter()
+        .map(|point| serde_json::json!({"epsilon": point.epsilon, "delta": point.delta}))
+        .collect::<Vec<serde_json::Value>>()))
+}
+
+
+#[cfg(test)]
+mod test_tradeoff {
+    use crate::utilities::tradeoff::{gaussian_noise_multiplier, gaussian_delta, gaussian_epsilon, gaussian_curve, privacy_loss_to_curve};
+
+    #[test]
+    fn test_gaussian_curve() {
+        let noise_multiplier = gaussian_noise_multiplier(0.5, 1e-6).unwrap();
+
+        // the classical calibration is conservative, so the exact profile is tighter at the calibrated delta
+        let epsilon = gaussian_epsilon(noise_multiplier, 1e-6);
+        assert!(epsilon < 0.5 && epsilon > 0.);
+        assert!((gaussian_delta(noise_multiplier, epsilon) - 1e-6).abs() < 1e-9);
+
+        // epsilon increases as delta decreases
+        let curve = gaussian_curve(noise_multiplier);
+        assert!(curve.windows(2).all(|pair| pair[0].epsilon < pair[1].epsilon));
+
+        assert!(gaussian_noise_multiplier(0.5, 0.).is_err());
+        assert!(privacy_loss_to_curve(&serde_json::json!({"name": "pure", "epsilon": 1.})).is_none());
+    }
+}